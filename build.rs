@@ -0,0 +1,61 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+/// Crate solving the kinematics, whose resolved version and features are embedded
+const BACKEND: &str = "rs-opw-kinematics";
+
+/// Version and enabled features of `package` as cargo resolved them for this build, from a
+/// `cargo tree` line such as `rs-opw-kinematics v1.5.0|feature,other`
+fn resolved(manifest_dir: &str, package: &str) -> Option<(String, String)> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let mut command = Command::new(cargo);
+    command
+        .arg("tree")
+        .arg("--manifest-path")
+        .arg(Path::new(manifest_dir).join("Cargo.toml"))
+        .args(["--package", package, "--depth", "0", "--edges", "normal"])
+        .args(["--format", "{p}|{f}", "--color", "never"]);
+    // Cargo has written the lock file and fetched every dependency before running build
+    // scripts, so resolving again needs no network
+    command.arg("--offline");
+    if let Ok(target) = env::var("TARGET") {
+        command.args(["--target", &target]);
+    }
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    let (name, features) = stdout.lines().next()?.split_once('|')?;
+    let version = name.split_whitespace().nth(1)?.strip_prefix('v')?;
+    Some((version.to_string(), features.trim().to_string()))
+}
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    for file in ["build.rs", "Cargo.toml", "Cargo.lock"] {
+        println!("cargo:rerun-if-changed={}", file);
+    }
+    let (version, features) = resolved(&manifest_dir, BACKEND).unwrap_or_else(|| {
+        println!(
+            "cargo:warning=Cannot resolve the version of {}, reporting it as unknown",
+            BACKEND
+        );
+        ("unknown".to_string(), String::new())
+    });
+    println!("cargo:rustc-env=BUILD_BACKEND_VERSION={}", version);
+    println!("cargo:rustc-env=BUILD_BACKEND_FEATURES={}", features);
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase()))
+        .collect();
+    features.sort();
+    println!("cargo:rustc-env=BUILD_FEATURES={}", features.join(","));
+    for variable in ["PROFILE", "TARGET"] {
+        println!(
+            "cargo:rustc-env=BUILD_{}={}",
+            variable,
+            env::var(variable).unwrap_or_default()
+        );
+    }
+}
@@ -1,3 +1,95 @@
-from ._internal import BaseConfig, KinematicModel, Robot, ToolConfig
+from ._internal import (
+    BaseConfig,
+    BatchCancelledError,
+    CancelToken,
+    Cell,
+    DualQuaternion,
+    Frames,
+    KinematicModel,
+    KinematicsServer,
+    Mirror,
+    Positioner,
+    Program,
+    Robot,
+    RobotBuilder,
+    SolverContext,
+    StreamingBridge,
+    ThreadPool,
+    ToolConfig,
+    UnreachablePoseError,
+    add_column_alias,
+    batch_look_at,
+    cli_main,
+    column_aliases,
+    compare_robots,
+    euler_to_quaternions,
+    fit_pose_transform,
+    fit_transform,
+    generate_pallet,
+    interpolate_twist,
+    joint_distance,
+    joint_distances,
+    list_models,
+    look_at,
+    matrices_to_quaternions,
+    pairwise_joint_distances,
+    pose_error,
+    pose_errors,
+    pose_to_twist,
+    quaternions_to_euler,
+    quaternions_to_matrices,
+    seam_poses,
+    set_tool_axis,
+    solver_info,
+    tool_axis,
+    twist_to_pose,
+    version_info,
+)
 
-__all__ = ["BaseConfig", "KinematicModel", "Robot", "ToolConfig"]
+__all__ = [
+    "BaseConfig",
+    "BatchCancelledError",
+    "CancelToken",
+    "Cell",
+    "DualQuaternion",
+    "Frames",
+    "KinematicModel",
+    "KinematicsServer",
+    "Mirror",
+    "Positioner",
+    "Program",
+    "Robot",
+    "RobotBuilder",
+    "SolverContext",
+    "StreamingBridge",
+    "ThreadPool",
+    "ToolConfig",
+    "UnreachablePoseError",
+    "add_column_alias",
+    "batch_look_at",
+    "cli_main",
+    "column_aliases",
+    "compare_robots",
+    "euler_to_quaternions",
+    "fit_pose_transform",
+    "fit_transform",
+    "generate_pallet",
+    "interpolate_twist",
+    "joint_distance",
+    "joint_distances",
+    "list_models",
+    "look_at",
+    "matrices_to_quaternions",
+    "pairwise_joint_distances",
+    "pose_error",
+    "pose_errors",
+    "pose_to_twist",
+    "quaternions_to_euler",
+    "quaternions_to_matrices",
+    "seam_poses",
+    "set_tool_axis",
+    "solver_info",
+    "tool_axis",
+    "twist_to_pose",
+    "version_info",
+]
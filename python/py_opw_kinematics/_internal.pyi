@@ -1,4 +1,4 @@
-from typing import List, Tuple, Optional
+from typing import Any, Callable, Dict, List, Literal, Tuple, Optional, Union
 
 class KinematicModel:
     a1: float
@@ -9,8 +9,9 @@ class KinematicModel:
     c3: float
     c4: float
     offsets: Tuple[float, float, float, float, float, float]
-    flip_axes: Optional[Tuple[bool, bool, bool, bool, bool, bool]]
+    sign_corrections: Tuple[int, int, int, int, int, int]
     has_parallelogram: bool
+    wrist_coupling: Tuple[float, float]
 
     def __init__(
         self,
@@ -29,23 +30,147 @@ class KinematicModel:
             0.0,
             0.0,
         ),
-        flip_axes: Optional[Tuple[bool, bool, bool, bool, bool, bool]] = (
-            False,
-            False,
-            False,
-            False,
-            False,
-            False,
-        ),
+        sign_corrections: Tuple[int, int, int, int, int, int] = (1, 1, 1, 1, 1, 1),
+        offsets_unit: Literal["radians", "degrees"] = "radians",
         has_parallelogram: bool = False,
+        wrist_coupling: Tuple[float, float] = (0.0, 0.0),
     ) -> None:
         """
         Initializes a KinematicModel instance.
 
         :param a1, a2, b, c1, c2, c3, c4: Kinematic parameters.
-        :param offsets: Joint offsets.
-        :param flip_axes: Boolean flags for flipping axes.
-        :param has_parallelogram: Indicates if the model has a parallelogram linkage.
+        :param offsets: Joint offsets, stored in radians.
+        :param sign_corrections: 1 or -1 per joint to flip its direction.
+        :param offsets_unit: Unit of the given offsets, "degrees" allows copying them
+            from vendor documentation directly.
+        :param has_parallelogram: Indicates if the model has a parallelogram linkage, so
+            the controller reports J3 relative to J2 (kinematic J3 = J3 - J2). Applied in
+            forward and inverse kinematics, joint values match the controller display.
+        :param wrist_coupling: Ratios (k4, k5) of mechanically coupled wrist axes, the
+            kinematic J6 is J6 - k4 * J4 - k5 * J5.
+        :raises ValueError: If offsets_unit is unknown.
+        """
+        ...
+
+    @staticmethod
+    def from_model(name: str) -> "KinematicModel":
+        """
+        Creates the model of a robot from the built-in library.
+
+        :param name: Model name, e.g. "ABB IRB 2400/10". Case, spaces and punctuation are
+            ignored and the vendor may be omitted.
+        :raises ValueError: If the name is unknown or ambiguous, listing the closest matches.
+        """
+        ...
+
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        Returns the model as a dictionary with the constructor's keyword names.
+        """
+        ...
+
+    @staticmethod
+    def from_dict(data: Dict[str, Any]) -> "KinematicModel":
+        """
+        Creates a model from a dictionary, missing keys take the constructor defaults.
+
+        :raises ValueError: If the dictionary contains unknown keys.
+        """
+        ...
+
+    def detect_signs_and_offsets(
+        self,
+        samples: List[
+            Tuple[
+                Tuple[float, float, float, float, float, float],
+                Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+            ]
+        ],
+        position_tolerance: float = 1e-3,
+        rotation_tolerance: float = 0.1,
+    ) -> List[Tuple["KinematicModel", float, float]]:
+        """
+        Finds the sign corrections and quarter/half turn offsets that reproduce the
+        poses reported by the controller. The geometric parameters are kept as is.
+
+        :param samples: (joints in degrees, flange pose in the robot base frame) pairs.
+        :param position_tolerance: Maximum position error in meters.
+        :param rotation_tolerance: Maximum rotation error in degrees.
+        :return: (model, max position error, max rotation error in degrees) for every
+            matching combination, best first. Wrist-flipped twins produce identical
+            poses, so matches usually come in pairs.
+        :raises ValueError: If no combination matches.
+        """
+        ...
+
+    def check_against(
+        self,
+        test_cases: List[
+            Tuple[
+                Tuple[float, float, float, float, float, float],
+                Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+            ]
+        ],
+        position_tolerance: float = 1e-3,
+        rotation_tolerance: float = 0.1,
+    ) -> List[Tuple[float, float]]:
+        """
+        Validates the model against golden forward kinematics test cases, e.g. poses
+        read from the controller.
+
+        :param test_cases: (joints in degrees, expected flange pose in the robot base frame) pairs.
+        :param position_tolerance: Maximum position error in meters.
+        :param rotation_tolerance: Maximum rotation error in degrees.
+        :return: (position error in meters, rotation error in degrees) per test case.
+        :raises ValueError: If any test case exceeds the tolerances, listing the failing cases.
+        """
+        ...
+
+    def with_offsets(
+        self,
+        offsets: Tuple[float, float, float, float, float, float],
+        offsets_unit: Literal["radians", "degrees"] = "radians",
+    ) -> "KinematicModel":
+        """
+        Returns a copy of the model with the given joint offsets.
+
+        :param offsets: Joint offsets, in radians unless offsets_unit is "degrees".
+        """
+        ...
+
+    def with_sign_corrections(
+        self, sign_corrections: Tuple[int, int, int, int, int, int]
+    ) -> "KinematicModel":
+        """
+        Returns a copy of the model with the given sign corrections.
+        """
+        ...
+
+    def with_convention(self, vendor: Literal["abb", "fanuc", "kuka"]) -> "KinematicModel":
+        """
+        Returns a copy of the model with the sign corrections and offsets of a vendor's
+        joint convention, so that joints entered exactly as the pendant shows them give
+        the right poses, e.g. KUKA's clockwise A1, A4 and A6 and A2 at -90 degrees with
+        the lower arm upright. The parameters must follow the usual OPW description, a2
+        the elbow offset and c3 the forearm. Case is ignored.
+
+        :raises ValueError: If there is no convention for the vendor.
+        """
+        ...
+
+    def with_param(
+        self,
+        *,
+        a1: Optional[float] = None,
+        a2: Optional[float] = None,
+        b: Optional[float] = None,
+        c1: Optional[float] = None,
+        c2: Optional[float] = None,
+        c3: Optional[float] = None,
+        c4: Optional[float] = None,
+    ) -> "KinematicModel":
+        """
+        Returns a copy of the model with the given kinematic parameters replaced.
         """
         ...
 
@@ -58,6 +183,89 @@ class BaseConfig:
         translation: Tuple[float, float, float],
         rotation: Tuple[float, float, float, float],
     ) -> None: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+    @staticmethod
+    def from_dict(data: Dict[str, Any]) -> "BaseConfig":
+        """
+        Creates the configuration from a dictionary with translation and rotation keys.
+
+        :raises KeyError: If a key is missing.
+        :raises ValueError: If the dictionary contains unknown keys.
+        """
+        ...
+
+    @staticmethod
+    def ceiling_mounted(height: float, x: float = 0.0, y: float = 0.0) -> "BaseConfig":
+        """
+        Base hanging from a ceiling, upside down (turned 180 degrees about x).
+
+        :param height: Height of the base above the world origin in meters.
+        :param x: Position of the base along x in meters.
+        :param y: Position of the base along y in meters.
+        """
+        ...
+
+    @staticmethod
+    def wall_mounted(
+        translation: Tuple[float, float, float],
+        normal: Tuple[float, float, float] = (1.0, 0.0, 0.0),
+    ) -> "BaseConfig":
+        """
+        Base on a wall, with its z axis along the wall normal and its x axis pointing down.
+
+        :param translation: Position of the base in meters.
+        :param normal: Direction pointing away from the wall, must be horizontal.
+        :raises ValueError: If the normal is zero or vertical.
+        """
+        ...
+
+    @staticmethod
+    def mounted(
+        translation: Tuple[float, float, float],
+        z_axis: Tuple[float, float, float],
+        x_axis: Tuple[float, float, float] = (1.0, 0.0, 0.0),
+    ) -> "BaseConfig":
+        """
+        Base in any mounting orientation, given by the world directions of its axes.
+
+        :param translation: Position of the base in meters.
+        :param z_axis: Direction of the base z axis (the axis of joint 1).
+        :param x_axis: Direction the base x axis should point to; only its component
+            perpendicular to z_axis is used.
+        :raises ValueError: If an axis is zero or both are parallel.
+        """
+        ...
+
+    @staticmethod
+    def from_measurements(
+        robot_poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        measured_poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        orientation_weight: float = 1.0,
+    ) -> Dict[str, Any]:
+        """
+        Estimates the base in the world frame by least squares, to register a cell
+        without vendor tooling.
+
+        :param robot_poses: TCP poses reported by the robot, in its base frame, as
+            (position, quaternion) tuples or a table with pose columns.
+        :param measured_poses: The same poses measured in the world frame, e.g. by a
+            laser tracker or camera.
+        :param orientation_weight: Meters of position error that weigh as much as a
+            radian of rotation error. 0 fits the positions only, which then need at
+            least three points off a line.
+        :return: A dict with base_config, the position (m) and rotation (degrees)
+            residual of every pair as position_residuals / rotation_residuals, and
+            rms_position, max_position, rms_rotation and max_rotation.
+        :raises ValueError: If the pose counts differ, a pose is not finite or the poses
+            do not determine the base.
+        """
+        ...
 
 class ToolConfig:
     translation: Tuple[float, float, float]
@@ -68,6 +276,362 @@ class ToolConfig:
         translation: Tuple[float, float, float],
         rotation: Tuple[float, float, float, float],
     ) -> None: ...
+    def to_dict(self) -> Dict[str, Any]: ...
+    @staticmethod
+    def from_dict(data: Dict[str, Any]) -> "ToolConfig":
+        """
+        Creates the configuration from a dictionary with translation and rotation keys.
+
+        :raises KeyError: If a key is missing.
+        :raises ValueError: If the dictionary contains unknown keys.
+        """
+        ...
+
+class DualQuaternion:
+    real: Tuple[float, float, float, float]
+    dual: Tuple[float, float, float, float]
+
+    def __init__(
+        self,
+        real: Tuple[float, float, float, float],
+        dual: Tuple[float, float, float, float],
+    ) -> None:
+        """
+        Initializes a unit dual quaternion, normalizing the given parts.
+
+        :param real: Real part (w, x, y, z), the rotation.
+        :param dual: Dual part (w, x, y, z), encoding the translation.
+        """
+        ...
+
+    @staticmethod
+    def from_pose(
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+    ) -> "DualQuaternion":
+        """
+        Builds a dual quaternion from a pose.
+
+        :param pose: Pose as position and quaternion (w, x, y, z).
+        """
+        ...
+
+    def to_pose(
+        self,
+    ) -> Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]:
+        """
+        Converts the dual quaternion back to a pose.
+
+        :return: Pose as position and quaternion (w, x, y, z).
+        """
+        ...
+
+    def sclerp(self, other: "DualQuaternion", t: float) -> "DualQuaternion":
+        """
+        Screw linear interpolation, coupling translation and rotation.
+
+        :param other: Dual quaternion reached at t = 1.
+        :param t: Interpolation fraction.
+        :return: The interpolated dual quaternion.
+        """
+        ...
+
+    def inverse(self) -> "DualQuaternion": ...
+    def __mul__(self, other: "DualQuaternion") -> "DualQuaternion": ...
+
+class Frames:
+    """
+    Named frames of a cell (world, robot base, workobjects, tool), for re-expressing
+    poses and pose tables between them. Robot.frames() creates one with the robot base.
+    """
+
+    def __init__(self) -> None:
+        """
+        Creates a registry with only the "world" frame.
+        """
+        ...
+
+    def add(
+        self,
+        name: str,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        parent: str = "world",
+    ) -> None:
+        """
+        Adds a frame, or moves it if it exists.
+
+        :param name: Name of the frame, e.g. a workobject.
+        :param pose: Pose of the frame in the parent frame.
+        :param parent: Name of a registered frame the pose is given in.
+        :raises ValueError: If the parent is unknown or name is "world".
+        """
+        ...
+
+    def names(self) -> List[str]:
+        """
+        Returns the names of the frames in the order they were added.
+        """
+        ...
+
+    def pose(
+        self, name: str, as_matrix: bool = False
+    ) -> Union[
+        Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        List[List[float]],
+    ]:
+        """
+        Returns the pose of a frame in the world frame.
+
+        :raises ValueError: If the frame is unknown.
+        """
+        ...
+
+    def transform(
+        self,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        from_frame: str,
+        to_frame: str,
+        as_matrix: bool = False,
+    ) -> Union[
+        Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        List[List[float]],
+    ]:
+        """
+        Re-expresses a pose given in from_frame in to_frame.
+
+        :raises ValueError: If a frame is unknown.
+        """
+        ...
+
+    def transform_df(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        from_frame: str,
+        to_frame: str,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+    ) -> List[Optional[Dict[str, float]]]:
+        """
+        Re-expresses a table of poses given in from_frame in to_frame, in one call.
+
+        :param poses: List of poses or a table with the columns x, y, z, qw, qx, qy, qz
+            under any name in column_aliases().
+        :param from_frame: Frame the poses are given in.
+        :param to_frame: Frame to express them in.
+        :param on_invalid: Poses with NaN or infinite values, see Robot.batch_inverse().
+            With "null" their rows are None.
+        :return: One row per pose with the columns x, y, z, qw, qx, qy, qz.
+        :raises ValueError: If a frame is unknown.
+        """
+        ...
+
+class BatchCancelledError(Exception):
+    """Raised when a batch operation is cancelled through its CancelToken."""
+
+class UnreachablePoseError(ValueError):
+    """Raised by inverse(strict=True) when a pose has no solution."""
+
+    distance: float
+    """How far (m) the wrist center lies outside the workspace, 0 if the joint limits
+    exclude every solution."""
+
+class CancelToken:
+    cancelled: bool
+
+    def __init__(self) -> None: ...
+    def cancel(self) -> None:
+        """
+        Requests cancellation, the running batch stops at its next chunk boundary.
+        Safe to call from any thread.
+        """
+        ...
+
+    def reset(self) -> None:
+        """
+        Clears the flag so the token can be reused.
+        """
+        ...
+
+class KinematicsServer:
+    address: Tuple[str, int]
+    running: bool
+
+    def __init__(
+        self,
+        robot: "Robot",
+        host: str = "127.0.0.1",
+        port: int = 0,
+        max_connections: int = 64,
+    ) -> None:
+        """
+        Serves forward and inverse kinematics of a robot over TCP, so clients in other
+        languages (C#, LabVIEW, PLC gateways) use the exact same kinematics. Each
+        connection runs on its own thread without holding the GIL. Use as a context
+        manager or call stop().
+
+        Requests and answers are single lines of space separated values, joints in the
+        units of the robot:
+
+        - ``PING`` answers ``OK``.
+        - ``FK j1 j2 j3 j4 j5 j6`` answers ``OK x y z qw qx qy qz``.
+        - ``IK x y z qw qx qy qz [j1 .. j6]`` answers ``OK j1 .. j6``, the best
+          solution near the optional seed.
+        - ``IKALL`` with the same arguments answers ``OK n`` followed by the solutions
+          separated by ``;``.
+        - Failures answer ``ERR <message>``.
+
+        A request line longer than 4096 bytes is answered with an error and closes the
+        connection.
+
+        :param robot: The robot to serve.
+        :param host: Interface to listen on.
+        :param port: Port to listen on, 0 picks a free one (see address).
+        :param max_connections: Connections served at a time, further ones are answered
+            ``ERR too many connections`` and closed.
+        :raises ValueError: If max_connections is 0.
+        :raises OSError: If the address cannot be bound.
+        """
+        ...
+
+    def stop(self) -> None:
+        """
+        Stops accepting connections and closes the open ones.
+        """
+        ...
+
+    def __enter__(self) -> "KinematicsServer": ...
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool: ...
+
+class StreamingBridge:
+    address: Tuple[str, int]
+    running: bool
+    joints: Optional[Tuple[float, float, float, float, float, float]]
+
+    def __init__(
+        self,
+        robot: "Robot",
+        host: str = "127.0.0.1",
+        port: int = 0,
+        reply_to: Optional[Tuple[str, int]] = None,
+        current_joints: Optional[Tuple[float, float, float, float, float, float]] = None,
+    ) -> None:
+        """
+        Turns a UDP stream of Cartesian targets into joint targets, e.g. between a vision
+        system and a controller's external guidance interface (ABB EGM and similar).
+
+        Each datagram holds x y z qw qx qy qz, as space separated text or else as seven
+        little-endian doubles; a datagram that reads as seven numbers in text is text. It
+        is solved with the previous joint target as seed, so
+        the stream stays on one continuous branch, and answered with j1..j6 (units of the
+        robot) in the same encoding. Unreachable and malformed targets are counted and
+        get no answer. Use as a context manager or call stop().
+
+        :param robot: The robot to solve for.
+        :param host: Interface to listen on.
+        :param port: Port to listen on, 0 picks a free one (see address).
+        :param reply_to: (host, port) that receives the joint targets, the sender of
+            each target by default.
+        :param current_joints: Joints the stream starts from (optional).
+        :raises OSError: If the address cannot be bound.
+        """
+        ...
+
+    def reset(
+        self,
+        current_joints: Optional[Tuple[float, float, float, float, float, float]] = None,
+    ) -> None:
+        """
+        Restarts the stream from the given joints, keeping the counters.
+        """
+        ...
+
+    def stats(self) -> Dict[str, Any]:
+        """
+        Returns the counts of received, sent, unreachable and invalid targets and
+        latency_mean, latency_max and latency_last in seconds, from receiving a target to
+        sending its joints.
+        """
+        ...
+
+    def stop(self) -> None:
+        """
+        Stops listening for targets.
+        """
+        ...
+
+    def __enter__(self) -> "StreamingBridge": ...
+    def __exit__(self, exc_type: Any, exc_value: Any, traceback: Any) -> bool: ...
+
+class ThreadPool:
+    n_threads: int
+    chunk_size: int
+
+    def __init__(
+        self, n_threads: Optional[int] = None, chunk_size: Optional[int] = None
+    ) -> None:
+        """
+        Worker threads and chunk size of parallel batch methods, reusable across calls.
+        Workers take chunks of chunk_size rows in turn: small chunks balance uneven rows,
+        large chunks cut the overhead on very wide batches. The pool holds settings; its
+        threads are started per call.
+
+        :param n_threads: Number of worker threads, all cores by default.
+        :param chunk_size: Rows per work unit, 1024 by default.
+        :raises ValueError: If either is zero.
+        """
+        ...
+
+class SolverContext:
+    pool: ThreadPool
+
+    def __init__(
+        self,
+        robot: "Robot",
+        n_threads: Optional[int] = None,
+        chunk_size: Optional[int] = None,
+        pool: Optional[ThreadPool] = None,
+        order_by: Literal["distance", "configuration", "comfort", "weighted", "none"] = "distance",
+        dedup_tolerance: Optional[float] = None,
+        dedup_turns: bool = True,
+        radians: Optional[bool] = None,
+    ) -> None:
+        """
+        Robot.batch_inverse with its settings fixed and its worker threads and row buffer
+        kept between calls, for repeated batches of similar size such as sliding windows
+        over a stream of poses. The workers live as long as the context. Batches that fit
+        in one chunk are solved on the calling thread.
+
+        :param robot: The robot to solve for.
+        :param n_threads: Number of worker threads, overriding pool (optional).
+        :param chunk_size: Rows per work unit, overriding pool (optional).
+        :param pool: Thread settings, all cores and chunks of 1024 rows by default.
+        :param order_by: Order of the solutions, see Robot.inverse.
+        :param dedup_tolerance: Merges solutions within this angle, see Robot.inverse.
+        :param dedup_turns: Whether whole turn variants are merged, see Robot.inverse.
+        :param radians: Overrides the joint units of the robot (optional).
+        :raises ValueError: If n_threads or chunk_size is zero.
+        """
+        ...
+
+    def batch_inverse(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+    ) -> List[Optional[List[List[float]]]]:
+        """
+        Solves poses like Robot.batch_inverse with the settings of the context.
+
+        :param poses: Poses as (position, quaternion) tuples or a table with the pose
+            columns.
+        :param on_invalid: What to do with poses containing NaN or infinite values:
+            "null" answers them with None, "raise" raises ValueError and "skip" drops them.
+        :return: The solutions of every pose.
+        """
+        ...
 
 class Robot:
     def __init__(
@@ -85,14 +649,69 @@ class Robot:
         """
         ...
 
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        Returns the complete robot setup (model, base, tool, limits, units and
+        solution policy) as a nested dictionary.
+        """
+        ...
+
+    def fingerprint(self, include_limits: bool = True) -> str:
+        """
+        Returns a stable hash of the kinematic setup: the kinematic model, link
+        corrections, mastering offsets, base, tool and, with include_limits, the joint
+        limits. Equal setups give equal fingerprints on every platform and build, so
+        caches, result files and experiment tracking can key results by it.
+
+        :return: 16 hex digits.
+        """
+        ...
+
+    @staticmethod
+    def from_dict(data: Dict[str, Any]) -> "Robot":
+        """
+        Creates a robot from a dictionary as produced by to_dict.
+
+        :raises ValueError: If the dictionary contains unknown keys or invalid settings.
+        """
+        ...
+
+    @staticmethod
+    def from_model(
+        name: str,
+        base_config: Optional[BaseConfig] = None,
+        tool_config: Optional[ToolConfig] = None,
+    ) -> "Robot":
+        """
+        Creates a robot from the built-in model library, see list_models().
+
+        :param name: Model name, e.g. "KUKA KR 6 R700 sixx".
+        :param base_config: The base configuration, defaults to the world origin.
+        :param tool_config: The tool configuration, defaults to the bare flange.
+        :raises ValueError: If the name is unknown or ambiguous, listing the closest matches.
+        """
+        ...
+
+    @staticmethod
+    def builder() -> "RobotBuilder":
+        """
+        Starts a RobotBuilder for step by step construction.
+        """
+        ...
+
     def forward(
-        self, joints: Tuple[float, float, float, float, float, float]
+        self,
+        joints: Tuple[float, float, float, float, float, float],
+        radians: Optional[bool] = None,
     ) -> Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]:
         """
         Computes the forward kinematics for the given joint angles.
 
-        :param joints: Joint angles of the robot in radians.
+        :param joints: Joint angles of the robot, in degrees unless the robot uses radians.
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
         :return: A tuple containing the position and quaternion of the tool in the world frame.
+        :raises ValueError: If the joints contain NaN or infinite values.
         """
         ...
 
@@ -102,39 +721,2726 @@ class Robot:
         current_joints: Optional[
             Tuple[float, float, float, float, float, float]
         ] = None,
-    ) -> List[Tuple[float, float, float, float, float, float]]:
+        order_by: Literal["distance", "configuration", "comfort", "weighted", "none"] = "distance",
+        dedup_tolerance: Optional[float] = None,
+        dedup_turns: bool = True,
+        radians: Optional[bool] = None,
+        strict: bool = False,
+        seeds: Union[
+            List[Tuple[float, float, float, float, float, float]], Literal["auto"], None
+        ] = None,
+        tool_relative: bool = False,
+        turns: Optional[Tuple[int, int, int]] = None,
+        exclude_singular: bool = False,
+        singularity_tolerance_deg: Optional[float] = None,
+        with_comfort: bool = False,
+    ) -> Union[
+        List[Tuple[float, float, float, float, float, float]], List[Dict[str, Any]]
+    ]:
         """
         Computes the inverse kinematics for a given pose.
 
         :param pose: Desired pose (position and quaternion) of the tool in the world frame.
-        :param current_joints: Current joint configuration (optional).
-        :return: A list of possible joint configurations that achieve the desired pose.
+        :param current_joints: Current joint configuration (optional), the default seed
+            of the robot when omitted.
+        :param order_by: "distance" sorts by summed joint distance to current_joints (or
+            the default seed or the zero position), "configuration" by configuration index
+            cfx (see AXIS_CONFIGURATION.md), "comfort" by the posture cost of
+            RobotBuilder.comfort, "weighted" by the score of the criteria weighted by
+            RobotBuilder.ranking, both closest first among equals, and "none" keeps the
+            solver's order, which may change between releases. Ties are broken by the other
+            key, then by joint values.
+        :param dedup_tolerance: Merges solutions whose joints all lie within this angle
+            of a better ranked solution (optional, no merging by default).
+        :param dedup_turns: Whether solutions differing by whole turns of a joint are
+            merged as well.
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :param strict: Raise UnreachablePoseError instead of returning an empty list.
+        :param seeds: Extra seeds, a list of joint sets or "auto" for a spread over the
+            joint ranges (optional). The union of the distinct solutions of all seeds is
+            returned, which finds turn variants of J4 and J6 that a single seed misses.
+        :param tool_relative: Interpret pose as an offset in the TCP frame at
+            current_joints, or at the default seed without them, e.g. to shift taught
+            positions by sensor corrections.
+        :param turns: Turn numbers of J1, J4 and J6 (optional): 0 for [-180, 180), 1 for
+            [180, 540), -1 for [-540, -180) and so on. Returns the solution of every
+            branch unwound to these turns that lies within the joint limits, e.g. to
+            match ABB cf values of -3, -2, 2 or 3 or KUKA turn bits. Cannot be combined
+            with seeds.
+        :param exclude_singular: Drop solutions at the wrist singularity, J5 within the
+            singularity tolerance of a multiple of 180 degrees, where J4 and J6 are not
+            determined by the pose.
+        :param singularity_tolerance_deg: Tolerance of exclude_singular in degrees for
+            this call, the robot's (see RobotBuilder.singularity_tolerance_deg) by default.
+        :param with_comfort: Return each solution as a dict with "joints" and its posture
+            cost (see RobotBuilder.comfort): "comfort", the weighted total, and its
+            "posture", "limits" and "extension" terms.
+        :return: A list of possible joint configurations that achieve the desired pose,
+            or with with_comfort one dict per solution. Solutions outside the joint limits
+            are dropped, and with the "closest" solution policy only the solution closest
+            to current_joints is returned.
+        :raises ValueError: If the pose or current_joints contain NaN or infinite values,
+            or with tool_relative if there are neither current_joints nor a default seed.
+        :raises UnreachablePoseError: With strict, if the pose has no solution.
         """
         ...
 
-    def batch_inverse(
+    def inverse_branches(
         self,
-        poses: List[
-            Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]
-        ],
-    ) -> List[List[Tuple[float, float, float, float, float, float]]]:
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        current_joints: Optional[
+            Tuple[float, float, float, float, float, float]
+        ] = None,
+        radians: Optional[bool] = None,
+        strict: bool = False,
+        tool_relative: bool = False,
+        with_comfort: bool = False,
+    ) -> List[Dict[str, Any]]:
+        """
+        Computes the inverse kinematics on all eight analytic branches.
+
+        :param pose: Desired pose (position and quaternion) of the tool in the world frame.
+        :param current_joints: Current joint configuration (optional), only used with
+            tool_relative.
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :param strict: Raise UnreachablePoseError if no branch reaches the pose.
+        :param tool_relative: Interpret pose as an offset in the TCP frame at
+            current_joints, or at the default seed without them.
+        :param with_comfort: Add "comfort", the posture cost of the branch (see
+            RobotBuilder.comfort), None for branches that cannot reach the pose.
+        :return: One dict per branch with "branch" (cfx 0..7), "shoulder"
+            ("front"/"back"), "elbow" ("up"/"down"), "wrist" ("noflip"/"flip"),
+            "joints" (None where the branch cannot reach the pose) and "within_limits".
+            Seed continuity, ordering and joint limit filtering do not apply.
+        :raises ValueError: If the pose or current_joints contain NaN or infinite values.
+        :raises UnreachablePoseError: With strict, if no branch reaches the pose.
+        """
+        ...
+
+    def inverse_fast(
+        self,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        current_joints: Optional[
+            Tuple[float, float, float, float, float, float]
+        ] = None,
+        radians: Optional[bool] = None,
+        check_singularity: bool = True,
+    ) -> Optional[List[float]]:
+        """
+        Inverse kinematics for tight loops such as servoing: the solution within the joint
+        limits closest to current_joints, or the default seed. Skips the cache, the
+        solution policy, ordering options and building a list of solutions.
+
+        :param pose: Desired pose (position and quaternion) of the tool in the world frame.
+        :param current_joints: Current joint configuration (optional), the default seed
+            of the robot when omitted.
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :param check_singularity: Probe the wrist singularity so J4 and J6 follow
+            current_joints there. False solves in a single pass, about three times
+            faster, for paths known to stay clear of J5 = 0.
+        :return: The joints, or None if the pose is unreachable within the joint limits.
+        :raises ValueError: If the pose or current_joints contain NaN or infinite values.
+        """
+        ...
+
+    def batch_inverse(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        progress: Optional[Callable[[int, int], Any]] = None,
+        cancel: Optional[CancelToken] = None,
+        order_by: Literal["distance", "configuration", "comfort", "weighted", "none"] = "distance",
+        dedup_tolerance: Optional[float] = None,
+        dedup_turns: bool = True,
+        radians: Optional[bool] = None,
+        with_counts: bool = False,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+        n_threads: Optional[int] = None,
+        chunk_size: Optional[int] = None,
+        pool: Optional[ThreadPool] = None,
+        summary: bool = False,
+        provenance: bool = False,
+    ) -> Union[
+        List[Optional[List[Tuple[float, float, float, float, float, float]]]],
+        List[Optional[Dict[str, Any]]],
+        Tuple[List[Any], Dict[str, Any]],
+    ]:
         """
         Computes the inverse kinematics for multiple poses in batch mode.
 
-        :param poses: List of poses, each containing position and quaternion tuples.
-        :return: List of lists containing all possible joint configurations for each pose.
+        :param poses: List of poses, each containing position and quaternion tuples, or a
+            table (DataFrame, dict of columns or list of row dicts) with the columns x, y,
+            z, qw, qx, qy, qz under any name in column_aliases().
+        :param progress: Called with (done, total) after each chunk of rows.
+        :param cancel: Token whose cancel() stops the batch at the next chunk.
+        :param order_by: Order of the solutions of each pose, see inverse().
+        :param dedup_tolerance: Merging of near-duplicate solutions, see inverse().
+        :param dedup_turns: Merging of solutions a whole turn apart, see inverse().
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :param with_counts: Return one row per pose with "solutions", "n_solutions" (the
+            number of valid solutions) and "solution_index" (the position of the chosen
+            solution among all of them, as returned with the "all" solution policy, or None
+            if the pose is unreachable).
+        :param on_invalid: What to do with poses that contain NaN or infinite values:
+            "null" returns None for them, "raise" raises and "skip" leaves them out.
+        :param n_threads: Worker threads, overrides the pool (optional).
+        :param chunk_size: Rows per work unit, overrides the pool (optional).
+        :param pool: Thread settings shared between calls (optional).
+        :param summary: Also return aggregate statistics, as (rows, summary) with summary
+            a dict of the counts rows, solved, unreachable, limit_violating (poses reachable
+            only beyond the joint limits, or joints beyond them), invalid and singular, the
+            max_joint_jump between consecutive solved rows in the joint units with the
+            max_joint_jump_row it ends at, and the runtime in seconds.
+        :param provenance: Return one row per pose with "solutions" and the kinematic
+            setup that produced them: model_hash (a digest of the kinematic model, link
+            corrections, base, mastering offsets, stationary tool and joint limits),
+            tool_hash (a digest of the tool) and config_policy (the solution policy), as
+            solver_info() reports them.
+        :return: List of lists containing all possible joint configurations for each pose,
+            or the rows described above with with_counts or provenance.
+        :raises ValueError: With on_invalid="raise", naming the first invalid row.
+        :raises BatchCancelledError: If the batch is cancelled through the token.
+        :raises KeyboardInterrupt: If interrupted with Ctrl+C.
+        """
+        ...
+
+    def solve_and_join(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        current_joints: Optional[
+            Tuple[float, float, float, float, float, float]
+        ] = None,
+        order_by: Literal["distance", "configuration", "comfort", "weighted", "none"] = "distance",
+        radians: Optional[bool] = None,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+        limit_margins: bool = False,
+        warning_margin: Optional[float] = None,
+        summary: bool = False,
+        provenance: bool = False,
+    ) -> Union[List[Dict[str, Any]], Tuple[List[Dict[str, Any]], Dict[str, Any]]]:
+        """
+        Solves every pose and joins the inputs with the results, one flat row per pose,
+        ready for pandas.DataFrame or polars.DataFrame.
+
+        :param poses: List of poses, each containing position and quaternion tuples, or a
+            table (DataFrame, dict of columns or list of row dicts) with the columns x, y,
+            z, qw, qx, qy, qz under any name in column_aliases().
+        :param current_joints: Seed for every pose (optional).
+        :param order_by: Which solution is taken as the best, see inverse().
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :param on_invalid: Poses with NaN or infinite values, see batch_inverse(). With
+            "null" their rows have the status "invalid".
+        :param limit_margins: Adds limit_margin_j1..limit_margin_j6, the distance of each
+            joint to its nearest limit in the joint units. Requires joint limits.
+        :param warning_margin: Adds near_limit, whether a joint is closer to a limit than
+            this (joint units), and logs a warning summarizing those rows, so marginal
+            programs are caught offline. Requires joint limits.
+        :param summary: Also return aggregate statistics, as (rows, summary) with summary
+            a dict of the counts rows, solved, unreachable, limit_violating (poses reachable
+            only beyond the joint limits, or joints beyond them), invalid and singular (see
+            RobotBuilder.singularity_tolerance_deg), the max_joint_jump between consecutive
+            solved rows in the joint units with the max_joint_jump_row it ends at, and the
+            runtime in seconds.
+        :param provenance: Stamp every row with the kinematic setup that produced it:
+            model_hash (a digest of the kinematic model, link corrections, base, mastering
+            offsets, stationary tool and joint limits), tool_hash (a digest of the tool)
+            and config_policy (the solution policy), as solver_info() reports them.
+        :return: Rows with the pose columns x, y, z, qw, qx, qy, qz, the best solution
+            j1..j6, status ("ok", "unreachable" or "invalid"), configuration (cfx) and
+            position_residual (m) / rotation_residual (degrees). Solution columns are
+            None for unreachable and invalid poses.
+        :raises ValueError: If limit margins are asked for without joint limits.
+        """
+        ...
+
+    def apply_corrections(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        corrections: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        frame: Literal["world", "workobject", "tool"] = "world",
+        workobject: Optional[
+            Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]
+        ] = None,
+        current_joints: Optional[
+            Tuple[float, float, float, float, float, float]
+        ] = None,
+        order_by: Literal["distance", "configuration", "comfort", "weighted", "none"] = "distance",
+        radians: Optional[bool] = None,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+    ) -> List[Dict[str, Any]]:
+        """
+        Corrects nominal poses by per-row correction transforms, e.g. from a vision
+        system, and solves the corrected poses in one pass.
+
+        :param poses: Nominal poses, as for solve_and_join().
+        :param corrections: One correction transform per pose, in the same formats.
+        :param frame: Frame the corrections are expressed in: "world", "workobject" or
+            "tool", the frame of each nominal pose.
+        :param workobject: Pose of the workobject in the world frame, required with
+            frame="workobject".
+        :param current_joints: Seed for every pose (optional).
+        :param order_by: Which solution is taken as the best, see inverse().
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :param on_invalid: Rows whose pose or correction has NaN or infinite values, see
+            batch_inverse().
+        :return: The rows of solve_and_join() for the corrected poses.
+        :raises ValueError: If the number of corrections differs from the number of poses,
+            or the workobject is missing for frame="workobject".
+        """
+        ...
+
+    def solve_continuous(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        current_joints: Optional[
+            Union[Tuple[float, float, float, float, float, float], str]
+        ] = None,
+        recovery: Optional[List[Literal["relaxed", "seeds", "interpolate"]]] = None,
+        max_step: Optional[float] = None,
+        radians: Optional[bool] = None,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+    ) -> List[Dict[str, Any]]:
+        """
+        Solves the poses of a trajectory in order, each seeded with the solution of the
+        row before and taking the solution closest to it. Rows without such a solution
+        are recovered instead of breaking the trajectory with a gap.
+
+        :param poses: List of poses, each containing position and quaternion tuples, or a
+            table with the columns x, y, z, qw, qx, qy, qz.
+        :param current_joints: Joints the trajectory starts from, or the name of a stored
+            posture (optional).
+        :param recovery: Strategies tried in order for a pose without a solution near the
+            previous joints, all by default: "relaxed" retries without the singularity
+            handling of the continuing solver and without max_step, "seeds" retries from
+            the default seed, the stored postures and the zero position, and
+            "interpolate" interpolates the joints between the solved rows around the gap.
+            An empty list leaves such rows unsolved.
+        :param max_step: Largest change of any joint from the previous row, in the joint
+            units (optional).
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :param on_invalid: What to do with poses containing NaN or infinite values.
+        :return: The rows of solve_and_join with a recovery column naming the strategy
+            that solved each row, None for rows solved directly. Interpolated rows have
+            the status "interpolated", their residuals show how far they are off the pose.
+        """
+        ...
+
+    def track_conveyor(
+        self,
+        poses: Any,
+        direction: Tuple[float, float, float],
+        speed: Optional[float] = None,
+        counts_per_meter: Optional[float] = None,
+        current_joints: Optional[
+            Union[Tuple[float, float, float, float, float, float], str]
+        ] = None,
+        recovery: Optional[List[Literal["relaxed", "seeds", "interpolate"]]] = None,
+        max_step: Optional[float] = None,
+        radians: Optional[bool] = None,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+    ) -> List[Dict[str, Any]]:
+        """
+        Validates a conveyor-tracking application offline. Shifts poses taught on the
+        part at the sync point along the conveyor by how far it has moved, then solves
+        them with continuity as solve_continuous.
+
+        :param poses: Table with the columns x, y, z, qw, qx, qy, qz and a time column
+            (t, time or timestamp, in s) or an encoder column (counts, encoder or
+            encoder_counts).
+        :param direction: Direction the conveyor moves in, in the world frame.
+        :param speed: Conveyor speed (m/s), to shift by the time column.
+        :param counts_per_meter: Encoder resolution, to shift by the encoder column.
+            Give exactly one of speed and counts_per_meter.
+        :param current_joints: Joints the tracking starts from, or the name of a stored
+            posture (optional).
+        :param recovery: Strategies for poses without a solution near the previous joints,
+            as for solve_continuous.
+        :param max_step: Largest change of any joint from the previous row, in the joint
+            units (optional).
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :param on_invalid: What to do with poses or positions that are missing, NaN or
+            infinite.
+        :return: The rows of solve_continuous for the shifted poses with a
+            conveyor_shift column (m).
+        :raises ValueError: If neither or both of speed and counts_per_meter are given, or
+            the table has no matching time or encoder column.
+        """
+        ...
+
+    def solve_path(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        current_joints: Optional[
+            Tuple[float, float, float, float, float, float]
+        ] = None,
+        selection: Literal["greedy", "shortest", "limit_margin"] = "greedy",
+        minimize: Literal["travel", "time", "wrist_motion"] = "travel",
+        config_penalty: Optional[float] = None,
+        max_step: Optional[float] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Tuple[float, float, float, float, float, float]]:
+        """
+        Solves a path of poses with one solution per pose.
+
+        :param poses: List of poses, each containing position and quaternion tuples, or a
+            table with the columns x, y, z, qw, qx, qy, qz.
+        :param current_joints: Joints the path starts from (optional).
+        :param selection: "greedy" takes, pose by pose, the solution cheapest to move to.
+            "shortest" finds the path of least total cost over the graph of per-pose
+            solutions. "limit_margin" maximizes the smallest distance of any joint to its
+            limits along the whole path, then minimizes the cost; it trades some wrist
+            travel for robustness near range ends and requires joint limits.
+        :param minimize: Cost of a move between consecutive poses: "travel" (sum of the
+            joint changes), "time" (largest joint change) or "wrist_motion" (changes of
+            joints 4 to 6).
+        :param config_penalty: Cost added when the configuration changes between poses, in
+            the joint units (default half a turn).
+        :param max_step: Largest change of any joint between consecutive poses (optional).
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One joint set per pose.
+        :raises UnreachablePoseError: If a pose has no solution.
+        :raises ValueError: If no solutions of consecutive poses lie within max_step.
+        """
+        ...
+
+    def order_targets(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        current_joints: Optional[
+            Union[Tuple[float, float, float, float, float, float], str]
+        ] = None,
+        method: Literal["greedy", "two_opt"] = "two_opt",
+        minimize: Literal["travel", "time", "wrist_motion"] = "travel",
+        return_to_start: bool = False,
+        radians: Optional[bool] = None,
+    ) -> Dict[str, Any]:
+        """
+        Orders independent targets, such as drilling holes, for the least joint motion.
+
+        :param poses: List of poses, each containing position and quaternion tuples, or a
+            table with the columns x, y, z, qw, qx, qy, qz.
+        :param current_joints: Joints or the name of a stored posture the robot starts
+            from (default seed or zero position without them).
+        :param method: "greedy" always moves to the nearest target left. "two_opt" then
+            reverses stretches of the order while that shortens it.
+        :param minimize: Cost of a move between targets: "travel" (sum of the joint
+            changes), "time" (largest joint change) or "wrist_motion" (changes of joints
+            4 to 6).
+        :param return_to_start: Whether the robot moves back to the start at the end.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: Dict with the target indices in visiting order ("order"), the joints at
+            each target in that order ("joints"), the estimated total cost in the joint
+            units ("travel") and the indices of targets without a solution, which are left
+            out ("unreachable").
+        """
+        ...
+
+    def optimize_roll(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        current_joints: Optional[
+            Tuple[float, float, float, float, float, float]
+        ] = None,
+        n_samples: int = 36,
+        max_roll: Optional[float] = None,
+        minimize: Literal["travel", "time", "wrist_motion"] = "wrist_motion",
+        max_step: Optional[float] = None,
+        radians: Optional[bool] = None,
+        tool_axis: Optional[Literal["x", "y", "z"]] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Solves a path for a rotationally symmetric process whose tool roll about the tool
+        axis is free, choosing the roll of every pose so the wrist stays as still as
+        possible (no J4/J6 wind-up).
+
+        :param poses: List of poses, each containing position and quaternion tuples, or a
+            table with the columns x, y, z, qw, qx, qy, qz.
+        :param current_joints: Joints the path starts from (optional).
+        :param n_samples: Rolls tried per pose.
+        :param max_roll: Try rolls within this many degrees of the given orientation
+            instead of over a full turn (optional).
+        :param minimize: Cost of a move between consecutive poses, as in solve_path.
+        :param max_step: Largest change of any joint between consecutive poses (optional).
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :param tool_axis: Axis of the TCP frame the tool is symmetric about, the convention
+            of set_tool_axis by default.
+        :return: Rows as solve_and_join returns them for the rolled poses, with the roll
+            (degrees) applied to each.
+        :raises UnreachablePoseError: If a pose has no solution at any roll.
+        :raises ValueError: If no solutions of consecutive poses lie within max_step.
+        """
+        ...
+
+    def filter_reachable(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        n_threads: Optional[int] = None,
+        chunk_size: Optional[int] = None,
+        pool: Optional[ThreadPool] = None,
+        singularity_tolerance_deg: Optional[float] = None,
+    ) -> Tuple[
+        List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]], int
+    ]:
+        """
+        Keeps the poses with at least one solution that respects the joint limits and is
+        not at a singularity, J5 within singularity_tolerance_deg (the robot's by default)
+        of a multiple of 180 degrees. Poses are checked in parallel, poses with NaN or infinite
+        values are removed.
+
+        :param poses: List of poses, each containing position and quaternion tuples, or a
+            table (DataFrame, dict of columns or list of row dicts) with the columns x, y,
+            z, qw, qx, qy, qz under any name in column_aliases().
+        :param n_threads: Worker threads, overrides the pool (optional).
+        :param chunk_size: Rows per work unit, overrides the pool (optional).
+        :param pool: Thread settings shared between calls (optional).
+        :return: The reachable poses in their original order and the number of poses removed.
+        """
+        ...
+
+    def reach_margin(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        margin: Optional[float] = None,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+    ) -> List[Optional[Dict[str, Any]]]:
+        """
+        How much room the wrist center of every pose has before it leaves the shell the arm
+        can reach, to find the points of a path where a small correction would make it
+        unreachable. Joint limits and link corrections are not considered.
+
+        :param poses: Poses as (position, quaternion) tuples or a DataFrame with pose
+            columns.
+        :param margin: Flag poses closer than this (m) to a boundary (optional).
+        :param on_invalid: Poses with NaN or infinite values, see batch_inverse().
+        :return: One row per pose with the wrist center (wrist_x, wrist_y, wrist_z) in the
+            base frame, "outer_margin" (m inside the outer reach sphere), "inner_margin"
+            (m outside the interior void), "margin" (the least of these and of the
+            clearance from the column around J1 that robots with an offset b cannot
+            enter), "boundary" ("outer", "inner" or "offset", the closest) and with margin
+            "near_boundary". Margins are negative outside the shell.
+        :raises ValueError: If margin is not finite.
+        """
+        ...
+
+    def why_unreachable(
+        self,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        max_tilt: Optional[float] = None,
+        n_samples: int = 8,
+        radians: Optional[bool] = None,
+    ) -> Dict[str, Any]:
+        """
+        Explains why a pose cannot be reached.
+
+        The reason is one of "reachable", "outside_reach" (the wrist center lies beyond
+        the reach of the arm), "interior_void" (it lies in the void close to the J2 axis
+        the arm cannot fold into), "joint_limits" (every solution has a joint beyond its
+        limits) or "orientation" (the position is reachable with the tool tilted or rolled
+        as in orientation_reachability).
+
+        :param pose: Target pose as (translation, quaternion).
+        :param max_tilt: Largest tilt of the sampled orientations, in the units of the
+            robot, 45 degrees by default.
+        :param n_samples: Number of tilt and of roll angles sampled.
+        :param radians: Override the joint units of the robot for max_tilt and limit_excess.
+        :return: A dict with reason, distance (m the wrist center lies outside the
+            reachable shell), wrist_center (base frame), wrist_reach, min_reach, max_reach,
+            the numbers of solutions and of those within_limits, limit_excess (how far each
+            joint of the solution closest to the limits lies beyond them, None if reachable
+            or without limits), blocking_joints, reachable_orientations and
+            sampled_orientations.
+        :raises ValueError: If the pose is not finite, n_samples is 0 or max_tilt is
+            negative.
+        """
+        ...
+
+    def project_to_workspace(
+        self,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        current_joints: Optional[Tuple[float, float, float, float, float, float]] = None,
+        relax_orientation: bool = False,
+        max_tilt: Optional[float] = None,
+        n_samples: int = 8,
+        tolerance: float = 1e-4,
+        radians: Optional[bool] = None,
+    ) -> Dict[str, Any]:
+        """
+        Finds the nearest reachable pose to a target, to move a target slightly rather
+        than drop it.
+
+        An unreachable position is moved towards the middle of the shell the arm can
+        reach until it has a solution within the joint limits.
+
+        :param pose: Target pose as (translation, quaternion).
+        :param current_joints: Joints the returned solution is chosen nearest to.
+        :param relax_orientation: Also allow tilting and rolling the tool, choosing the
+            orientation that moves the position least.
+        :param max_tilt: Largest tilt with relax_orientation, in the units of the robot,
+            45 degrees by default.
+        :param n_samples: Number of tilt and of roll angles sampled with relax_orientation.
+        :param tolerance: How close (m) the projection gets to the edge of the workspace.
+        :param radians: Override the joint units of the robot.
+        :return: A dict with the projected pose, its joints, the distance (m) and rotation
+            (joint units) it moved, and whether it moved. pose and joints are None if no
+            reachable pose was found.
+        :raises ValueError: If the pose is not finite, tolerance is not positive,
+            n_samples is 0 or max_tilt is negative.
+        """
+        ...
+
+    def batch_project_to_workspace(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        max_move: Optional[Tuple[float, float, float]] = None,
+        max_rotation: Optional[float] = None,
+        relax_orientation: bool = False,
+        max_tilt: Optional[float] = None,
+        n_samples: int = 8,
+        tolerance: float = 1e-4,
+        radians: Optional[bool] = None,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+        progress: Optional[Callable[[int, int], Any]] = None,
+        cancel: Optional[CancelToken] = None,
+        n_threads: Optional[int] = None,
+        chunk_size: Optional[int] = None,
+        pool: Optional[ThreadPool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Projects a table of poses onto the workspace like project_to_workspace(),
+        fixing rows that are slightly out of reach and flagging the rest.
+
+        :param poses: List of (translation, quaternion) or a DataFrame with x, y, z, qw,
+            qx, qy, qz columns.
+        :param max_move: Largest accepted move in meters along world x, y and z, unbounded
+            if None.
+        :param max_rotation: Largest accepted rotation of the tool, in the units of the
+            robot, unbounded if None.
+        :param relax_orientation: See project_to_workspace().
+        :param max_tilt: See project_to_workspace().
+        :param n_samples: See project_to_workspace().
+        :param tolerance: See project_to_workspace().
+        :param radians: Overrides the joint units of the robot for this call.
+        :param on_invalid: What to do with poses that contain NaN or infinite values:
+            "null" gives them the status "invalid", "raise" raises and "skip" leaves them
+            out.
+        :param progress: Called with (done, total) after each chunk of rows.
+        :param cancel: Token whose cancel() stops the batch at the next chunk.
+        :param n_threads: Worker threads, overrides the pool (optional).
+        :param chunk_size: Rows per work unit, overrides the pool (optional).
+        :param pool: Thread settings shared between calls (optional).
+        :return: One row per pose with x, y, z, qw, qx, qy, qz (the projected pose if it
+            was accepted, else the pose as given), dx, dy, dz, distance, rotation and
+            status: "reachable", "projected", "too_far", "unreachable" or "invalid".
+        :raises ValueError: For invalid settings, or with on_invalid="raise" naming the
+            first invalid row.
+        :raises BatchCancelledError: If the batch is cancelled through the token.
+        """
+        ...
+
+    def configuration_coverage(
+        self,
+        poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+            Any,
+        ],
+        exclude_singular: bool = False,
+    ) -> List[Dict[str, Any]]:
+        """
+        Groups the inverse kinematics solutions of poses sampling a target region into
+        configuration families, the eight analytic branches, and reports which families
+        reach the whole region, to pick one configuration that works everywhere.
+
+        :param poses: Poses sampling the region, as (position, quaternion) tuples or a
+            DataFrame with pose columns.
+        :param exclude_singular: Do not count solutions at the wrist singularity as
+            reaching a pose.
+        :return: One row per branch (cfx 0..7) with "branch", "shoulder", "elbow",
+            "wrist", "reached" (poses reached within the joint limits), "singular" (of
+            those, how many at the wrist singularity), "fraction", "covers_region" and
+            "missing" (indices of the poses not reached).
+        :raises ValueError: If there are no poses or a pose has NaN or infinite values.
+        """
+        ...
+
+    def orientation_reachability(
+        self,
+        point: Tuple[float, float, float],
+        axis: Tuple[float, float, float],
+        n_samples: int = 12,
+        max_tilt: Optional[float] = None,
+        reference: Optional[Tuple[float, float, float]] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Sweeps the tool orientation at a fixed position, e.g. to choose weld gun angles.
+
+        The tool axis (see set_tool_axis) points along `axis`, tilted about the reference
+        x axis and then rolled about the tilted tool axis.
+
+        :param point: TCP position in meters.
+        :param axis: Nominal direction of the tool axis.
+        :param n_samples: Number of tilt angles from -max_tilt to max_tilt, and of roll
+            angles over a full turn.
+        :param max_tilt: Largest tilt either way, in the units of the robot, 90 degrees
+            by default.
+        :param reference: Direction of the reference x axis, made normal to `axis`, world
+            x (or y when x is parallel to the axis) by default.
+        :return: A row per orientation with tilt, roll, qw, qx, qy, qz, reachable, the
+            number of solutions and the manipulability and j1..j6 of the solution with the
+            best manipulability (None when unreachable).
+        :raises ValueError: If n_samples is 0, max_tilt is negative or the axis is zero
+            or parallel to the reference.
+        """
+        ...
+
+    def axis_configuration(
+        self,
+        joints: Tuple[float, float, float, float, float, float],
+        radians: Optional[bool] = None,
+    ) -> Tuple[int, int, int, int]:
+        """
+        Computes the ABB axis configuration of the given joints.
+
+        :param joints: Joint angles of the robot.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: (cf1, cf4, cf6, cfx), the quadrants of axes 1, 4 and 6 and the
+            configuration index cfx described in AXIS_CONFIGURATION.md.
+        :raises ValueError: If the joints contain NaN or infinite values.
+        """
+        ...
+
+    def batch_axis_configuration(
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        vendor: Optional[Literal["kuka", "fanuc"]] = None,
+        radians: Optional[bool] = None,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+    ) -> List[Dict[str, Any]]:
+        """
+        Computes the axis configuration of many joint sets.
+
+        :param joints: List of joint configurations (6 joint angles each).
+            Tables with the columns j1..j6 are accepted as well, see column_aliases().
+        :param vendor: Adds the KUKA Status and Turn (columns S and T) or the Fanuc
+            configuration string such as "N U T, 0, 0, 0" (column FANUC).
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :param on_invalid: Joint sets with NaN or infinite values, see batch_inverse().
+            With "null" their configuration columns are None.
+        :return: One row per joint set with j1..j6 followed by CF1, CF4, CF6 and CFX,
+            ready for pandas.DataFrame or polars.DataFrame.
+        :raises ValueError: If the vendor is unknown.
+        """
+        ...
+
+    def validate_configurations(
+        self,
+        joints: Any,
+        vendor: Optional[Literal["kuka", "fanuc"]] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Checks the axis configuration of exported controller programs against the one
+        computed here, to validate the kinematic setup against real programs at scale.
+
+        :param joints: Table (DataFrame, dict of columns or list of row dicts) with the
+            joint columns j1..j6 and the expected CF1, CF4, CF6 and CFX columns, plus S
+            and T for KUKA or FANUC for Fanuc. Column names are case-insensitive; missing
+            columns and empty cells are not checked. KUKA binary literals such as "B010"
+            are read as numbers, Fanuc strings are compared ignoring case and spaces.
+        :param vendor: Also checks the KUKA Status/Turn or the Fanuc configuration string.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One row per disagreeing joint set with its row index, the joints, the
+            names of the mismatched columns ("mismatches") and each checked column as
+            computed next to the expected value, e.g. CFX and CFX_expected.
+        :raises ValueError: If the table has none of the configuration columns or joints
+            with NaN or infinite values.
+        """
+        ...
+
+    def detect_config_changes(
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        include_quadrants: bool = False,
+        radians: Optional[bool] = None,
+    ) -> Tuple[List[Dict[str, Any]], List[Dict[str, Any]]]:
+        """
+        Finds where the axis configuration flips along a trajectory, e.g. to split a
+        program where the controller refuses configuration changes in linear moves.
+
+        :param joints: Joint sets of the trajectory, in order.
+            Tables with the columns j1..j6 are accepted as well, see column_aliases().
+        :param include_quadrants: Whether changes of CF1, CF4 or CF6 count as well.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: (rows, segments). One row per joint set with CFX, config_change (True
+            where the configuration differs from the previous row) and segment, and one
+            summary per segment with start, end (inclusive), rows and CFX.
+        :raises ValueError: If a joint set contains NaN or infinite values.
+        """
+        ...
+
+    def segment_trajectory(
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        include_quadrants: bool = False,
+        flip_threshold: Optional[float] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Splits a solved trajectory into segments that can each be written as linear
+        moves, breaking wherever the axis configuration changes or a joint flips by a
+        whole turn.
+
+        :param joints: Joint sets of the trajectory, in order.
+            Tables with the columns j1..j6 are accepted as well, see column_aliases().
+        :param include_quadrants: Whether changes of CF1, CF4 or CF6 break as well.
+        :param flip_threshold: Smallest jump of a joint between rows that counts as a
+            flip, in joint units, 180 degrees by default.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One row per segment with start, end (inclusive), rows, reason ("start",
+            "configuration", "flip" or "configuration+flip"), flipped_joints, CFX (and CF1,
+            CF4, CF6 with include_quadrants), travel_j1..travel_j6 and max_step.
+        :raises ValueError: If a joint set contains NaN or infinite values or
+            flip_threshold is not positive.
+        """
+        ...
+
+    def resample_trajectory(
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        times: List[float],
+        period: float = 0.004,
+        interpolation: Literal["linear", "cubic"] = "linear",
+        max_velocity: Optional[Tuple[float, float, float, float, float, float]] = None,
+        strict: bool = False,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Resamples a timestamped joint trajectory at the fixed period of a streaming
+        interface, e.g. 4 ms for EGM or 12 ms for RSI.
+
+        :param joints: Joint sets of the trajectory, in order.
+            Tables with the columns j1..j6 are accepted as well, see column_aliases().
+        :param times: Time of every joint set in seconds, strictly increasing.
+        :param period: Controller period in seconds.
+        :param interpolation: "linear", or "cubic" for a velocity that is continuous
+            through the points.
+        :param max_velocity: Largest speed of every joint in joint units per second
+            (optional).
+        :param strict: Raise at the first sample beyond the joint limits or max_velocity
+            instead of flagging it.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One row per sample with t, j1..j6, within_limits and within_velocity.
+            Samples run from the first time until the first one at or after the last,
+            which holds the last joint set.
+        :raises ValueError: If the times do not match the joints or do not increase, a
+            joint set is not finite, or with strict on a violation.
+        """
+        ...
+
+    def smooth_trajectory(
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        method: Literal["spline", "moving_average"] = "spline",
+        window: int = 5,
+        tolerance: float = 0.0005,
+        angular_tolerance_deg: float = 0.1,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Smooths a solved joint trajectory to remove IK jitter, keeping the TCP within a
+        Cartesian tolerance of its original poses as checked by forward kinematics.
+
+        :param joints: Joint sets of the trajectory, in order.
+            Tables with the columns j1..j6 are accepted as well, see column_aliases().
+        :param method: "spline" fits a discrete smoothing spline, "moving_average" takes
+            centered means. The first and last joint sets stay.
+        :param window: Odd number of points: the moving average window, or the length of
+            a wave the spline halves.
+        :param tolerance: Largest TCP position change in meters.
+        :param angular_tolerance_deg: Largest TCP rotation change in degrees.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One row per joint set with j1..j6, translation_error (m),
+            rotation_error (degrees) and smoothing, the share of the smoothing kept
+            (1 for all of it, 0 where the original joints stay because the tolerance
+            or the joint limits allow no change).
+        :raises ValueError: If a joint set is not finite, the window is not an odd
+            number of at least 3 or a tolerance is not positive.
+        """
+        ...
+
+    def cartesian_deviation(
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        reference_poses: Union[
+            List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]], Any
+        ],
+        tolerance: Optional[float] = None,
+        angular_tolerance_deg: Optional[float] = None,
+        along_path: Optional[bool] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Optional[Dict[str, Any]]]:
+        """
+        Runs forward kinematics on post-processed joints (smoothed, resampled, edited) and
+        measures how far the TCP deviates from the intended path, so the result can be
+        certified against a tolerance.
+
+        :param joints: Joint sets to check.
+            Tables with the columns j1..j6 are accepted as well, see column_aliases().
+        :param reference_poses: Intended TCP poses, as a list or a table with the pose
+            columns.
+        :param tolerance: Largest accepted position deviation in meters (optional).
+        :param angular_tolerance_deg: Largest accepted rotation deviation in degrees
+            (optional).
+        :param along_path: Compare with the closest point of the polyline through the
+            reference positions, the orientation interpolated between them, instead of
+            row by row. By default only when the tables differ in length, e.g. after
+            resampling.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One row per joint set with translation_error (m), rotation_error
+            (degrees), reference (index of the closest reference pose plus the share of
+            the way to the next one) and, with a tolerance, within_tolerance. None for
+            joint sets with NaN or infinite values.
+        :raises ValueError: If a reference pose is not finite, there are none, or
+            along_path is False for tables of different lengths.
+        """
+        ...
+
+    def axis_usage(
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        time_col: Optional[str] = None,
+        near_limit_margin: Optional[float] = None,
+        deadband: Optional[float] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Travel and duty statistics of every joint over a recorded trace, for
+        maintenance-oriented analysis of production data.
+
+        :param joints: Joint sets of the trace, in order, as a table with the columns
+            j1..j6 (see column_aliases()) or a list of joint sets.
+        :param time_col: Column of the table with the time of every row in seconds,
+            strictly increasing (optional).
+        :param near_limit_margin: Distance from a joint limit within which the joint
+            counts as near it, in the joint units, 5 degrees by default.
+        :param deadband: Motion back from the furthest point before a change of direction
+            counts as a reversal, in the joint units, 0.1 degrees by default.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One row per joint with joint ("j1".."j6"), travel, reversals, max_step,
+            max_speed (per second, None without time_col), min and max in the joint units,
+            and near_limit (seconds, or rows without time_col) and near_limit_fraction,
+            both None without joint limits.
+        :raises ValueError: If a row is not finite, the trace is empty, or the time
+            column is missing or does not increase.
+        """
+        ...
+
+    def read_joint_log(
+        self,
+        source: Union[str, Dict[str, List[Any]], Any],
+        joint_names: Optional[List[str]] = None,
+        log_units: Literal["radians", "degrees"] = "radians",
+        relative_time: bool = True,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, float]]:
+        """
+        Reads a joint_states log, e.g. exported from a rosbag, into rows of t and j1..j6
+        that feed batch_forward() and the trajectory tools.
+
+        The log needs a time column (t, time, timestamp, %time, or header stamp sec and
+        nanosec; nanoseconds since the epoch are converted to seconds) and one of:
+
+        - a column per joint, position0.. with optional name0.., as ``rostopic echo -p``
+          writes them,
+        - list cells position and name holding all joints of a message,
+        - one row per joint and message with name and position columns.
+
+        :param source: Path of a CSV file, a dict of columns or a DataFrame.
+        :param joint_names: Names of J1..J6 in the log. By default the six joints of the
+            first message, ordered by the number their names end with.
+        :param log_units: Units of the positions in the log, radians as in ROS.
+        :param relative_time: Whether times start at 0.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One row per message with t in seconds and j1..j6 in the joint units.
+        :raises ValueError: If the log has no time or position columns, more than six
+            joints without joint_names, or a message lacks one of the joints.
+        :raises OSError: If the file cannot be read.
+        """
+        ...
+
+    def optimize_positioner(
+        self,
+        positioner: "Positioner",
+        path: Union[List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]], Any],
+        flat_weight: float = 1.0,
+        limit_weight: float = 1.0,
+        smooth_weight: float = 0.1,
+        n_samples: int = 12,
+        flat_direction: Tuple[float, float, float] = (0.0, 0.0, -1.0),
+        current_joints: Optional[Tuple[float, float, float, float, float, float]] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Optional[float]]]:
+        """
+        Chooses positioner joints along a path on the part that keep the robot in a
+        comfortable posture, e.g. welding in the flat position away from its joint limits,
+        and returns the joints of both chains.
+
+        Every positioner axis is sampled at n_samples values over its limits and every
+        robot branch within the joint limits is considered. The chosen states minimize,
+        summed over the path, flat_weight times the tilt of the tool axis from
+        flat_direction (radians), limit_weight times the limit margin cost (0 in the middle
+        of the joint ranges, 1 at a limit), and smooth_weight times the joint travel of
+        both chains between poses (radians).
+
+        :param positioner: The positioner carrying the part.
+        :param path: TCP poses in the part frame, as a list or a table like in
+            batch_inverse().
+        :param flat_weight: Weight of the tilt from flat_direction.
+        :param limit_weight: Weight of the nearness to the robot joint limits.
+        :param smooth_weight: Weight of the joint travel.
+        :param n_samples: Samples per positioner axis; a full turn does not repeat its end.
+        :param flat_direction: Preferred tool axis in the world frame, straight down for
+            the flat welding position.
+        :param current_joints: Robot joints before the path, whose travel to the first pose
+            is counted (optional).
+        :param radians: Overrides the joint units of the robot and the positioner for this
+            call (optional).
+        :return: One row per pose with the positioner joints p1.., the robot joints j1..j6,
+            the tilt in joint units and limit_margin, the smallest distance of a joint from
+            its nearer limit relative to half its range (None without joint limits).
+        :raises ValueError: If a pose is out of reach at every sampled positioner state or
+            an argument is invalid.
+        """
+        ...
+
+    def export_joint_trajectory(
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        times: List[float],
+        path: Optional[str] = None,
+        joint_names: Optional[List[str]] = None,
+        frame_id: str = "",
+        velocities: bool = True,
+        ros_version: int = 2,
+        radians: Optional[bool] = None,
+    ) -> Dict[str, Any]:
+        """
+        Converts a timed joint trajectory to a trajectory_msgs/JointTrajectory message,
+        to replay it through ROS or MoveIt.
+
+        :param joints: Joint sets of the trajectory, in order.
+            Tables with the columns j1..j6 are accepted as well, see column_aliases().
+        :param times: Time from the start of every joint set in seconds, strictly
+            increasing.
+        :param path: File to write the message to as YAML (optional).
+        :param joint_names: Names of the six joints, joint_1..joint_6 by default.
+        :param frame_id: Frame id of the message header.
+        :param velocities: Whether to fill in velocities from the neighbouring points,
+            at rest at both ends.
+        :param ros_version: 2 for time_from_start as sec and nanosec, 1 for secs and
+            nsecs.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: The message as a dict with header, joint_names and points, positions
+            in radians and velocities in radians per second.
+        :raises ValueError: If the times do not match the joints or do not increase, a
+            joint set is not finite, or there are not six joint names.
+        :raises OSError: If the file cannot be written.
         """
         ...
 
     def batch_forward(
-        self, joints: List[Tuple[float, float, float, float, float, float]]
-    ) -> List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]]:
+        self,
+        joints: Union[List[Tuple[float, float, float, float, float, float]], Any],
+        progress: Optional[Callable[[int, int], Any]] = None,
+        cancel: Optional[CancelToken] = None,
+        radians: Optional[bool] = None,
+        on_invalid: Literal["null", "raise", "skip"] = "null",
+        n_threads: Optional[int] = None,
+        chunk_size: Optional[int] = None,
+        pool: Optional[ThreadPool] = None,
+        configuration: bool = False,
+        singularity: bool = False,
+        singularity_tolerance_deg: Optional[float] = None,
+        summary: bool = False,
+        provenance: bool = False,
+    ) -> Union[
+        List[
+            Union[
+                Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+                Dict[str, Any],
+                None,
+            ]
+        ],
+        Tuple[List[Any], Dict[str, Any]],
+    ]:
         """
         Computes the forward kinematics for multiple sets of joint angles in batch mode.
 
         :param joints: List of joint configurations (6 joint angles each).
-        :return: List of poses, each containing position and quaternion tuples.
+            Tables with the columns j1..j6 are accepted as well, see column_aliases().
+        :param progress: Called with (done, total) after each chunk of rows.
+        :param cancel: Token whose cancel() stops the batch at the next chunk.
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :param on_invalid: Joint sets with NaN or infinite values, see batch_inverse().
+        :param n_threads: Worker threads, overrides the pool (optional).
+        :param chunk_size: Rows per work unit, overrides the pool (optional).
+        :param pool: Thread settings shared between calls (optional).
+        :param configuration: Return rows with the pose columns x, y, z, qw, qx, qy, qz
+            and the axis configuration CF1, CF4, CF6 and CFX.
+        :param singularity: Return rows with the pose columns, near_singularity (the
+            joints are at a kinematic singularity) and manipulability (|det J| of the
+            TCP Jacobian, 0 at singularities).
+        :param singularity_tolerance_deg: How close J5 (degrees) may come to a multiple
+            of 180 to flag near_singularity, the robot's tolerance by default.
+        :param summary: Also return aggregate statistics, as (rows, summary) with summary
+            a dict of the counts rows, solved, unreachable (0), limit_violating (joints
+            beyond the limits), invalid and singular, the max_joint_jump between consecutive
+            solved rows in the joint units with the max_joint_jump_row it ends at, and the
+            runtime in seconds.
+        :param provenance: Return rows with the pose columns and the kinematic setup that
+            produced them: model_hash (a digest of the kinematic model, link corrections,
+            base, mastering offsets, stationary tool and joint limits), tool_hash (a digest
+            of the tool) and config_policy (the solution policy), as solver_info() reports
+            them.
+        :return: List of poses, each containing position and quaternion tuples, or of
+            rows with configuration, singularity or provenance. Invalid rows are None.
+        :raises ValueError: With on_invalid="raise", naming the first invalid row.
+        :raises BatchCancelledError: If the batch is cancelled through the token.
+        :raises KeyboardInterrupt: If interrupted with Ctrl+C.
         """
         ...
 
-__all__: List[str] = ["BaseConfig", "KinematicModel", "Robot", "ToolConfig"]
+    def skeleton(
+        self,
+        joints: Tuple[float, float, float, float, float, float],
+        link_radius: Optional[float] = None,
+        radians: Optional[bool] = None,
+    ) -> Dict[str, Any]:
+        """
+        Returns the geometry of a posture for plotting, in world coordinates.
+
+        :param joints: Joint angles of the robot in degrees.
+        :param link_radius: Radius in meters, adds a cylinder per segment (optional).
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :return: "names" of the points (base, shoulder, elbow, forearm, wrist, flange,
+            tcp), their "points" as [x, y, z], the "segments" [start, end] between
+            consecutive points and, with a link radius, "cylinders" (start, end, radius).
+            Lists convert to arrays with numpy.asarray().
+        :raises ValueError: If the joints contain NaN or infinite values or the radius
+            is not positive.
+        """
+        ...
+
+    def export_posture(
+        self,
+        joints: Tuple[float, float, float, float, float, float],
+        path: str,
+        link_radius: float = 0.05,
+        scale: float = 1.0,
+        radians: Optional[bool] = None,
+    ) -> int:
+        """
+        Writes the posture as cylinders along the skeleton to a mesh file.
+
+        :param joints: Joint angles of the robot in degrees.
+        :param path: File to write, the format follows the extension: .stl (binary),
+            .ply (ASCII), .gltf (embedded buffer) or .glb.
+        :param link_radius: Radius of the cylinders in meters.
+        :param scale: Factor applied to the world coordinates, e.g. 1000 for millimeters.
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :return: The number of triangles written.
+        :raises ValueError: If the joints contain NaN or infinite values, the radius or
+            scale is not positive or the extension is unknown.
+        """
+        ...
+
+    def export_workspace(
+        self,
+        path: str,
+        samples: int = 64,
+        resolution: int = 32,
+        scale: float = 1.0,
+    ) -> int:
+        """
+        Writes the envelope of the wrist center positions within the joint limits (or
+        full turns without limits) to a mesh file in world coordinates.
+
+        The envelope is star-shaped around the shoulder height on the J1 axis, directions
+        nothing reaches collapse to that point.
+
+        :param path: File to write, the format follows the extension: .stl (binary),
+            .ply (ASCII), .gltf (embedded buffer) or .glb.
+        :param samples: Number of values sampled of each of the joints 1 to 3.
+        :param resolution: Rows of the envelope, each with 2 * resolution directions.
+        :param scale: Factor applied to the world coordinates, e.g. 1000 for millimeters.
+        :return: The number of triangles written.
+        :raises ValueError: If samples or resolution is below 2, the scale is not
+            positive or the extension is unknown.
+        """
+        ...
+
+    def forward_twist(
+        self,
+        joints: Tuple[float, float, float, float, float, float],
+        radians: Optional[bool] = None,
+    ) -> Tuple[float, float, float, float, float, float]:
+        """
+        Computes the forward kinematics and returns the pose as a twist.
+
+        :param joints: Joint angles of the robot in degrees.
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :return: Exponential coordinates (vx, vy, vz, wx, wy, wz) of the tool pose.
+        :raises ValueError: If the joints contain NaN or infinite values.
+        """
+        ...
+
+    def inverse_twist(
+        self,
+        twist: Tuple[float, float, float, float, float, float],
+        current_joints: Optional[
+            Tuple[float, float, float, float, float, float]
+        ] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Tuple[float, float, float, float, float, float]]:
+        """
+        Computes the inverse kinematics for a pose given as a twist.
+
+        :param twist: Exponential coordinates (vx, vy, vz, wx, wy, wz) of the desired pose.
+        :param current_joints: Current joint configuration (optional).
+        :param radians: Overrides the joint units of the robot for this call, True for
+            radians and False for degrees (optional).
+        :return: A list of possible joint configurations that achieve the desired pose.
+        :raises ValueError: If the twist or current_joints contain NaN or infinite values.
+        """
+        ...
+
+    def jog(
+        self,
+        current_joints: Tuple[float, float, float, float, float, float],
+        delta: Tuple[float, float, float, float, float, float],
+        frame: Literal["tool", "world", "joint"] = "tool",
+        max_step: Optional[float] = None,
+        radians: Optional[bool] = None,
+    ) -> Tuple[float, float, float, float, float, float]:
+        """
+        Joints after a small increment, as a teach pendant jogs.
+
+        :param current_joints: Joints to jog from.
+        :param delta: Joint increments with frame "joint", else a Cartesian step
+            [dx, dy, dz, rx, ry, rz]: meters and a rotation vector in the joint units.
+        :param frame: "tool" steps along and about the TCP axes, "world" along and about
+            the world axes (rotating about the TCP), "joint" per joint. With a
+            stationary tool the steps move the pose forward() reports, the stationary
+            tool in the part frame.
+        :param max_step: Largest change of any joint (joint units, optional), so a step
+            through a wrist singularity stops instead of flipping the wrist.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: The new joints; Cartesian steps keep the configuration and take the
+            solution closest to current_joints.
+        :raises UnreachablePoseError: If the target has no solution.
+        :raises ValueError: If the step leaves the joint limits, changes the configuration
+            or exceeds max_step.
+        """
+        ...
+
+    def base_transform(
+        self,
+        as_matrix: bool = False,
+    ) -> Union[
+        Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        List[List[float]],
+    ]:
+        """
+        Returns the pose of the robot base in the world frame, as used by the solver.
+
+        :param as_matrix: Return a 4x4 homogeneous matrix (list of rows) instead of
+            position and quaternion.
+        """
+        ...
+
+    def tool_transform(
+        self,
+        as_matrix: bool = False,
+    ) -> Union[
+        Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        List[List[float]],
+    ]:
+        """
+        Returns the pose of the TCP in the flange frame, as used by the solver.
+
+        :param as_matrix: Return a 4x4 homogeneous matrix (list of rows) instead of
+            position and quaternion.
+        """
+        ...
+
+    def frames(
+        self,
+        joints: Optional[Tuple[float, float, float, float, float, float]] = None,
+        radians: Optional[bool] = None,
+    ) -> Frames:
+        """
+        Returns a Frames registry with "world" and the robot base "base", plus the
+        flange "flange" and the TCP "tool" at the given joints. With a stationary tool
+        "tool" is the stationary tool and the part frame on the flange is "part", the
+        frame of the poses forward() reports. Add workobjects with Frames.add().
+
+        :param joints: Posture for the flange and tool frames (optional).
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        """
+        ...
+
+    def world_to_base(
+        self,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        as_matrix: bool = False,
+    ) -> Union[
+        Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        List[List[float]],
+    ]:
+        """
+        Expresses a pose given in the world frame in the robot base frame.
+
+        :param pose: Position and quaternion (w, x, y, z).
+        :param as_matrix: Return a 4x4 homogeneous matrix (list of rows) instead of
+            position and quaternion.
+        :raises ValueError: If the pose contains NaN or infinite values.
+        """
+        ...
+
+    def base_to_world(
+        self,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        as_matrix: bool = False,
+    ) -> Union[
+        Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        List[List[float]],
+    ]:
+        """
+        Expresses a pose given in the robot base frame in the world frame.
+
+        :param pose: Position and quaternion (w, x, y, z).
+        :param as_matrix: Return a 4x4 homogeneous matrix (list of rows) instead of
+            position and quaternion.
+        :raises ValueError: If the pose contains NaN or infinite values.
+        """
+        ...
+
+    def tcp_to_flange(
+        self,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        as_matrix: bool = False,
+    ) -> Union[
+        Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        List[List[float]],
+    ]:
+        """
+        Returns the flange pose that puts the TCP at the given pose.
+
+        :param pose: Position and quaternion (w, x, y, z).
+        :param as_matrix: Return a 4x4 homogeneous matrix (list of rows) instead of
+            position and quaternion.
+        :raises ValueError: If the pose contains NaN or infinite values.
+        """
+        ...
+
+    def flange_to_tcp(
+        self,
+        pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        as_matrix: bool = False,
+    ) -> Union[
+        Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+        List[List[float]],
+    ]:
+        """
+        Returns the TCP pose for the given flange pose.
+
+        :param pose: Position and quaternion (w, x, y, z).
+        :param as_matrix: Return a 4x4 homogeneous matrix (list of rows) instead of
+            position and quaternion.
+        :raises ValueError: If the pose contains NaN or infinite values.
+        """
+        ...
+
+    def calibrate_tool_orientation(
+        self,
+        reference_joints: Tuple[float, float, float, float, float, float],
+        z_joints: List[Tuple[float, float, float, float, float, float]],
+        x_joints: Optional[List[Tuple[float, float, float, float, float, float]]] = None,
+        radians: Optional[bool] = None,
+    ) -> Dict[str, Any]:
+        """
+        Derives the tool rotation from postures aligned with known directions (the
+        "TCP&Z,X" method), keeping the TCP position of the current tool.
+
+        :param reference_joints: Joints with the TCP touching a fixed reference point.
+        :param z_joints: Joints where the reference point lies on the desired positive z
+            axis of the tool, away from the TCP.
+        :param x_joints: Joints where the reference point lies on the desired positive x
+            axis of the tool (optional). Without them the x axis stays as close to the
+            current one as the new z axis allows.
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: A dict with tool_config, z_spread and x_spread (largest angle of one
+            posture from the fitted direction, degrees, x_spread None without x_joints)
+            and axis_angle (degrees between the measured z and x directions, ideally 90).
+        :raises ValueError: If a direction cannot be found or z and x are parallel.
+        """
+        ...
+
+    def posture(self, name: str, radians: Optional[bool] = None) -> List[float]:
+        """
+        Returns the joints of a posture stored with RobotBuilder.posture(), e.g. "home",
+        for use as a seed, program start point or reference.
+
+        :raises ValueError: If the robot has no posture of that name.
+        """
+        ...
+
+    def postures(self, radians: Optional[bool] = None) -> Dict[str, List[float]]:
+        """
+        Returns every stored posture by name.
+        """
+        ...
+
+    def at_posture(
+        self,
+        joints: Tuple[float, float, float, float, float, float],
+        name: str,
+        tolerance: Optional[float] = None,
+        radians: Optional[bool] = None,
+    ) -> bool:
+        """
+        Checks whether joints match a stored posture, e.g. that the cell starts at home or
+        the robot stands in its mastering position.
+
+        :param joints: Joints to check.
+        :param name: Name of the posture.
+        :param tolerance: Largest difference of any joint in the joint units, 0.1 degrees
+            by default.
+        :raises ValueError: If the robot has no posture of that name.
+        """
+        ...
+
+    def enable_stats(self, enabled: bool = True) -> None:
+        """
+        Turns collection of call statistics on or off. Collection is off by default.
+        """
+        ...
+
+    def stats(self) -> Dict[str, Any]:
+        """
+        Returns the collected statistics: enabled, forward_calls, forward_time,
+        inverse_calls, inverse_time, inverse_failures (calls without any solution)
+        and solutions (total returned). Times are cumulative, in seconds.
+        """
+        ...
+
+    def reset_stats(self) -> None:
+        """
+        Clears all collected statistics.
+        """
+        ...
+
+    def enable_cache(
+        self,
+        capacity: int = 4096,
+        position_resolution: float = 1e-6,
+        rotation_resolution: float = 1e-6,
+    ) -> None:
+        """
+        Turns on memoization of inverse kinematics, for workloads with many repeated or
+        nearly identical targets. Enabling again starts an empty cache.
+
+        :param capacity: Number of poses kept, the least recently used is evicted first.
+        :param position_resolution: Position quantization in meters.
+        :param rotation_resolution: Quaternion component quantization. A pose within the
+            resolution of a cached one gets the cached solutions.
+        :raises ValueError: If the capacity or a resolution is not positive.
+        """
+        ...
+
+    def disable_cache(self) -> None:
+        """
+        Turns memoization off and drops the cached solutions.
+        """
+        ...
+
+    def cache_stats(self) -> Dict[str, Any]:
+        """
+        Returns enabled, capacity, size, hits, misses and evictions of the cache.
+        """
+        ...
+
+    def clear_cache(self) -> None:
+        """
+        Drops the cached solutions and resets the cache counters.
+        """
+        ...
+
+    def wrench_flange_to_tcp(
+        self, wrench: Tuple[float, float, float, float, float, float]
+    ) -> Tuple[float, float, float, float, float, float]:
+        """
+        Transforms a wrench measured at the flange into the TCP frame.
+
+        :param wrench: Wrench (fx, fy, fz, tx, ty, tz) in the flange frame, in N and Nm.
+        :return: The same wrench expressed in the TCP frame, torque taken about the TCP.
+        """
+        ...
+
+    def wrench_tcp_to_flange(
+        self, wrench: Tuple[float, float, float, float, float, float]
+    ) -> Tuple[float, float, float, float, float, float]:
+        """
+        Transforms a wrench given in the TCP frame into the flange frame.
+
+        :param wrench: Wrench (fx, fy, fz, tx, ty, tz) in the TCP frame, in N and Nm.
+        :return: The same wrench expressed in the flange frame, torque taken about the flange.
+        """
+        ...
+
+    def batch_wrench_flange_to_tcp(
+        self, wrenches: List[Tuple[float, float, float, float, float, float]]
+    ) -> List[Tuple[float, float, float, float, float, float]]:
+        """
+        Transforms multiple flange wrenches into the TCP frame in batch mode.
+
+        :param wrenches: List of wrenches (fx, fy, fz, tx, ty, tz) in the flange frame.
+        :return: List of wrenches expressed in the TCP frame.
+        """
+        ...
+
+    def batch_wrench_tcp_to_flange(
+        self, wrenches: List[Tuple[float, float, float, float, float, float]]
+    ) -> List[Tuple[float, float, float, float, float, float]]:
+        """
+        Transforms multiple TCP wrenches into the flange frame in batch mode.
+
+        :param wrenches: List of wrenches (fx, fy, fz, tx, ty, tz) in the TCP frame.
+        :return: List of wrenches expressed in the flange frame.
+        """
+        ...
+
+class RobotBuilder:
+    def __init__(self) -> None: ...
+    def model(self, kinematic_model: KinematicModel) -> "RobotBuilder":
+        """
+        Sets the kinematic model (required).
+        """
+        ...
+
+    def base(self, base_config: BaseConfig) -> "RobotBuilder":
+        """
+        Sets the base configuration, defaults to the world origin.
+        """
+        ...
+
+    def tool(self, tool_config: ToolConfig) -> "RobotBuilder":
+        """
+        Sets the tool configuration, defaults to the bare flange.
+        """
+        ...
+
+    def joint_limits(self, joint_limits: List[Tuple[float, float]]) -> "RobotBuilder":
+        """
+        Sets (min, max) limits for each of the six joints, in the units of the robot.
+        """
+        ...
+
+    def units(self, units: Literal["degrees", "radians"]) -> "RobotBuilder":
+        """
+        Sets the units of all joint values, "degrees" by default.
+        """
+        ...
+
+    def solution_policy(self, policy: Literal["all", "closest"]) -> "RobotBuilder":
+        """
+        Sets which inverse kinematics solutions are returned, "all" by default.
+        """
+        ...
+
+    def corrections(
+        self, corrections: List[Tuple[float, float, float, float, float, float]]
+    ) -> "RobotBuilder":
+        """
+        Sets measured per-link corrections layered on the nominal model, one small
+        displacement (vx, vy, vz, wx, wy, wz) in meters and radians per joint, applied
+        after the joint. Forward kinematics then uses the calibrated chain and inverse
+        kinematics refines the analytic solutions onto it numerically.
+        """
+        ...
+
+    def default_seed(
+        self, joints: Tuple[float, float, float, float, float, float]
+    ) -> "RobotBuilder":
+        """
+        Sets the preferred posture, e.g. the typical working posture, that seeds inverse
+        kinematics (single, batch and path) when no current joints are given, in the units
+        of the robot. Without it the solver centers on the zero position.
+        """
+        ...
+
+    def posture(
+        self, name: str, joints: Tuple[float, float, float, float, float, float]
+    ) -> "RobotBuilder":
+        """
+        Stores a named joint posture such as "home", "mastering" or "service", in the
+        units of the robot. Postures are read back with Robot.posture(), can start
+        programs by name and are saved with to_dict() and Cell.save().
+
+        :raises ValueError: At build, if the name is empty or the joints are not finite
+            or lie beyond the joint limits.
+        """
+        ...
+
+    def mastering_offsets(
+        self, offsets: Tuple[float, float, float, float, float, float]
+    ) -> "RobotBuilder":
+        """
+        Sets per-joint mastering offsets in the units of the robot: the joint value the
+        controller reports minus the true joint angle. Joints in and out of every method
+        stay as the controller reports them; the offsets are removed before the kinematic
+        model and added back to its solutions, leaving the nominal model and its
+        `offsets` untouched. Saved with to_dict().
+
+        :raises ValueError: At build, if an offset is not finite.
+        """
+        ...
+
+    def stationary_tool(self, tool_config: ToolConfig) -> "RobotBuilder":
+        """
+        Sets the pose of a tool fixed in the cell, in the world frame, for a robot that
+        holds the workpiece (remote TCP). The tool() is then the part frame on the flange,
+        and the poses in and out of forward, inverse and the solving methods are where the
+        stationary tool meets the part, given in the part frame. Saved with to_dict().
+
+        :raises ValueError: At build, if its rotation is zero or its translation not finite.
+        """
+        ...
+
+    def comfort(
+        self,
+        preferred: Optional[Tuple[float, float, float, float, float, float]] = None,
+        posture_weight: float = 1.0,
+        limit_weight: float = 1.0,
+        extension_weight: float = 1.0,
+        limit_zone: float = 0.1,
+    ) -> "RobotBuilder":
+        """
+        Sets the posture cost of inverse with_comfort and order_by="comfort", 0 at best:
+        the weighted sum of the mean squared distance from the preferred posture per joint
+        relative to half its range (180 degrees without limits), the squared depth of the
+        worst joint in the zone before its limits, and the squared extension of the arm,
+        the distance of the wrist center from J2 over the longest possible.
+
+        :param preferred: Preferred posture in the units of the robot, the default seed
+            or the zero position when omitted.
+        :param posture_weight: Weight of the distance from the preferred posture.
+        :param limit_weight: Weight of the joint limit proximity, 0 without limits.
+        :param extension_weight: Weight of the arm extension.
+        :param limit_zone: Share of each joint range, at either end, over which the limit
+            term rises from 0 to 1 at the limit, in (0, 0.5].
+        :raises ValueError: At build, for negative or non-finite weights or another zone.
+        """
+        ...
+
+    def ranking(
+        self,
+        configuration: Optional[int] = None,
+        configuration_weight: float = 1.0,
+        distance_weight: float = 1.0,
+        manipulability_weight: float = 0.0,
+        limit_margin_weight: float = 0.0,
+    ) -> "RobotBuilder":
+        """
+        Sets the criteria that order_by="weighted" combines into one score per solution,
+        lowest first. Each criterion is about 0 at best and 1 at worst before weighting.
+
+        :param configuration: Wanted configuration index cfx (0 to 7), the configuration
+            of the seed when omitted.
+        :param configuration_weight: Weight of a configuration other than the wanted one.
+        :param distance_weight: Weight of the summed joint distance from the seed, in
+            half turns.
+        :param manipulability_weight: Weight of the shortfall in manipulability against
+            the most dexterous solution of the pose, as a share of it.
+        :param limit_margin_weight: Weight of the shortfall of the smallest joint margin
+            to the limits, relative to half the joint range, 0 without limits.
+        :raises ValueError: At build, for negative or non-finite weights or another cfx.
+        """
+        ...
+
+    def singularity_tolerance_deg(self, tolerance: float) -> "RobotBuilder":
+        """
+        Sets how close J5 (degrees) may come to a multiple of 180 before joints count as
+        at the wrist singularity, for near_singularity flags, filter_reachable and inverse
+        with exclude_singular. Defaults to 0.01, the threshold of the solver.
+        """
+        ...
+
+    def build(self) -> Robot:
+        """
+        Validates the collected settings and creates the Robot.
+
+        :raises ValueError: If the model is missing or a setting is invalid.
+        """
+        ...
+
+class Mirror:
+    """
+    Mirror image of a robot cell about a plane, for building the left-hand variant of a
+    right-hand cell. Mirrored poses keep right-handed frames by flipping their y axis, and
+    the mirrored robot reaches them with joints 1, 4 and 6 negated.
+    """
+
+    def __init__(
+        self,
+        normal: Tuple[float, float, float] = (0.0, 1.0, 0.0),
+        point: Tuple[float, float, float] = (0.0, 0.0, 0.0),
+    ) -> None:
+        """
+        :param normal: Normal of the mirror plane in the world frame.
+        :param point: A point on the mirror plane in meters.
+        :raises ValueError: If the normal is zero.
+        """
+        ...
+
+    def model(self, model: KinematicModel) -> KinematicModel:
+        """
+        Mirrors a kinematic model: b, the offsets of joints 1, 4 and 6 and the J5 -> J6
+        wrist coupling change sign.
+        """
+        ...
+
+    def robot(self, robot: "Robot") -> "Robot":
+        """
+        Mirrors a robot with every setting: the base and the stationary tool are
+        reflected, the tool flipped, and joints 1, 4 and 6 of the joint limits, default
+        seed, comfort posture, postures and mastering offsets negated. The other settings
+        are kept.
+        """
+        ...
+
+    def pose(self, pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]) -> Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]:
+        """
+        Mirrors a pose in the world frame.
+
+        :raises ValueError: If the pose contains NaN or infinite values.
+        """
+        ...
+
+    def poses(self, poses: Union[List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]], Any]) -> List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]]:
+        """
+        Mirrors many poses, given as a list or a table like in Robot.batch_inverse().
+        """
+        ...
+
+    def joints(self, joints: Tuple[float, float, float, float, float, float]) -> Tuple[float, float, float, float, float, float]:
+        """
+        Returns the joints of the mirrored robot that reach the mirrored pose.
+        """
+        ...
+
+    def joint_sets(self, joints: Union[List[Tuple[float, float, float, float, float, float]], Any]) -> List[Tuple[float, float, float, float, float, float]]:
+        """
+        Mirrors many joint sets, given as a list or a table like in Robot.batch_forward().
+        """
+        ...
+
+class Positioner:
+    """
+    A workpiece positioner: a chain of rotary axes carrying the part, e.g. a tilt-rotate
+    table. Each axis turns about its direction through its origin, both in the frame left
+    by the axes before it; the frame after the last axis is the part frame.
+    """
+
+    def __init__(
+        self,
+        axes: List[Tuple[Tuple[float, float, float], Tuple[float, float, float]]],
+        base_config: Optional[BaseConfig] = None,
+        joint_limits: Optional[List[Tuple[float, float]]] = None,
+        radians: bool = False,
+    ) -> None:
+        """
+        :param axes: (origin, direction) of every axis, the origin in meters.
+        :param base_config: Placement of the positioner in the world frame (optional).
+        :param joint_limits: (min, max) per axis in the joint units, ±180 degrees by
+            default.
+        :param radians: Whether joint values are in radians instead of degrees.
+        :raises ValueError: If there are no axes, a direction is zero, or the joint limits
+            do not match the axes.
+        """
+        ...
+
+    @property
+    def n_axes(self) -> int:
+        """Number of axes."""
+        ...
+
+    def forward(
+        self, joints: List[float], radians: Optional[bool] = None
+    ) -> Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]:
+        """
+        Pose of the part frame in the world frame at the given joints.
+
+        :raises ValueError: If the number of joints does not match the axes or a joint
+            is not finite.
+        """
+        ...
+
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        Returns the positioner as a dictionary with the constructor's keyword names.
+        """
+        ...
+
+    @staticmethod
+    def from_dict(data: Dict[str, Any]) -> "Positioner":
+        """
+        Creates a positioner from a dictionary as produced by to_dict.
+
+        :raises ValueError: If the dictionary contains unknown keys or invalid axes.
+        """
+        ...
+
+class Cell:
+    """
+    A whole workcell: the robot with its model, base, tool and limits, the named tools it
+    can carry, the frames of its work objects, an optional positioner as external axes
+    and the collision objects around it, saved together as one JSON file.
+    """
+
+    def __init__(
+        self,
+        robot: Robot,
+        frames: Optional[Frames] = None,
+        tools: Optional[Dict[str, ToolConfig]] = None,
+        positioner: Optional[Positioner] = None,
+        collision_objects: Optional[List[Dict[str, Any]]] = None,
+        name: str = "cell",
+    ) -> None:
+        """
+        :param robot: The robot of the cell.
+        :param frames: Frames of the work objects, a new registry by default.
+        :param tools: Tools the robot can carry by name, as their TCP in the flange frame.
+        :param positioner: Positioner carrying the part, the external axes of the cell.
+        :param collision_objects: Obstacles as dicts with name, shape ("box", "sphere" or
+            "cylinder"), size (edge lengths of a box, radius of a sphere, radius and
+            length along z of a cylinder, in meters), frame (world by default) and pose in
+            that frame (identity by default); shapes are centered on their pose.
+        :raises ValueError: If a collision object has an unknown shape, a wrong size or an
+            unknown frame.
+        """
+        ...
+
+    @property
+    def name(self) -> str: ...
+    @property
+    def robot(self) -> Robot: ...
+    @property
+    def frames(self) -> Frames: ...
+    @property
+    def tools(self) -> Dict[str, ToolConfig]: ...
+    @property
+    def positioner(self) -> Optional[Positioner]: ...
+    @property
+    def collision_objects(self) -> List[Dict[str, Any]]: ...
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        The cell as plain values: format and version of the layout, name, robot as
+        Robot.to_dict() returns it, tools (TCP in the flange frame), frames (the frames
+        other than the world as their poses in the world frame), external_axes as
+        Positioner.to_dict() returns it and collision_objects.
+        """
+        ...
+
+    @staticmethod
+    def from_dict(data: Dict[str, Any]) -> "Cell":
+        """
+        Creates a cell from a dictionary as produced by to_dict.
+
+        :raises ValueError: If the dictionary contains unknown keys, is not a workcell or
+            has a newer version than this release reads.
+        """
+        ...
+
+    @staticmethod
+    def load(path: str) -> "Cell":
+        """Reads a cell from the JSON file at path."""
+        ...
+
+    def save(self, path: str) -> None:
+        """Writes the cell to path as JSON."""
+        ...
+
+class Program:
+    """
+    A robot job: named targets, as TCP poses in named frames or as joints, with the axis
+    configuration they are taught in, their motion, speed and zone, in the order they are
+    visited. Targets can be added while the program is shared between threads.
+    """
+
+    def __init__(
+        self,
+        name: str = "main",
+        frames: Optional[Frames] = None,
+        radians: bool = False,
+    ) -> None:
+        """
+        :param name: Name of the program.
+        :param frames: Registry the target frames are looked up in, a new one with only the
+            world frame by default.
+        :param radians: Whether joint values are in radians instead of degrees.
+        """
+        ...
+
+    @property
+    def name(self) -> str: ...
+    @property
+    def frames(self) -> Frames:
+        """The frames registry targets are given in."""
+        ...
+
+    @property
+    def tools(self) -> Dict[str, ToolConfig]:
+        """The tools of the program by name, as their TCP in the flange frame."""
+        ...
+
+    def add_tool(self, name: str, tool_config: ToolConfig) -> None:
+        """
+        Adds a tool with its TCP in the flange frame, or replaces it if it exists.
+
+        :raises ValueError: If the pose contains NaN or infinite values.
+        """
+        ...
+
+    def add(
+        self,
+        name: str,
+        pose: Optional[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]] = None,
+        joints: Optional[Tuple[float, float, float, float, float, float]] = None,
+        frame: str = "world",
+        tool: Optional[str] = None,
+        configuration: Optional[Tuple[int, int, int, int]] = None,
+        motion: Optional[Literal["joint", "linear"]] = None,
+        speed: Optional[float] = None,
+        zone: Optional[float] = None,
+    ) -> None:
+        """
+        Appends a target. Names need not be unique, a program may visit a target again.
+
+        :param name: Name of the target, e.g. "p10".
+        :param pose: TCP pose in the frame, either this or joints.
+        :param joints: Joint target, either this or a pose.
+        :param frame: Frame the pose is given in, e.g. a workobject.
+        :param tool: Tool of the program the pose is the TCP of, the tool of the robot by
+            default. Solving moves the robot's flange to where it puts this tool's TCP.
+        :param configuration: ABB axis configuration (cf1, cf4, cf6, cfx) the target is
+            taught in (optional).
+        :param motion: Joint (MoveJ, PTP) or linear (MoveL, LIN) motion (optional).
+        :param speed: TCP speed in m/s (optional).
+        :param zone: Zone (blend) radius in meters, 0 for a stop point (optional).
+        :raises ValueError: If neither or both of pose and joints are given, the frame or
+            tool is unknown or a value is invalid.
+        """
+        ...
+
+    def add_rows(
+        self,
+        rows: Any,
+        frame: str = "world",
+        tool: Optional[str] = None,
+        motion: Optional[Literal["joint", "linear"]] = None,
+        speed: Optional[float] = None,
+        zone: Optional[float] = None,
+    ) -> int:
+        """
+        Appends a target per row of a table, e.g. a solved DataFrame with speed and zone
+        columns attached, to export it with per-row speeds and zones.
+
+        Rows are poses if the table has pose columns (x, y, z, qw, qx, qy, qz or their
+        aliases), else joints (j1..j6). The optional columns name (or target), frame, tool,
+        motion, speed (or velocity), zone (or blend, blend_radius) and CF1, CF4, CF6, CFX
+        give these per row; missing values (None or NaN) fall back to the arguments.
+
+        :param rows: A DataFrame, a dict of columns or a list of row dicts.
+        :param frame: Frame of the rows without one.
+        :param tool: Tool of the rows without one, the robot's tool by default.
+        :param motion: Motion of the rows without one.
+        :param speed: TCP speed in m/s of the rows without one.
+        :param zone: Zone radius in meters of the rows without one.
+        :return: The number of targets added. Rows without a name are called p1, p2, ...
+            after their position in the program.
+        :raises ValueError: If the table has neither pose nor joint columns, or a row is
+            invalid or names an unknown frame or tool; then no target is added.
+        """
+        ...
+
+    def names(self) -> List[str]:
+        """Names of the targets in the order they are visited."""
+        ...
+
+    def get(self, name: str) -> Dict[str, Any]:
+        """
+        The first target called name as a dict with name, pose, joints, frame, tool,
+        configuration, motion, speed and zone.
+
+        :raises ValueError: If the program has no such target.
+        """
+        ...
+
+    def rows(self) -> List[Dict[str, Any]]:
+        """
+        The targets as rows for a DataFrame: name, frame, tool, motion, speed, zone, the pose
+        columns x, y, z, qw, qx, qy, qz (None for joint targets), j1..j6 (None for pose
+        targets) and CF1, CF4, CF6, CFX.
+        """
+        ...
+
+    def solve(
+        self,
+        robot: "Robot",
+        current_joints: Union[Tuple[float, float, float, float, float, float], str, None] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Solves the program with a robot, every target from the joints of the one before
+        and in its taught configuration if it has one.
+
+        :param robot: The robot running the program.
+        :param current_joints: Joints the program starts from, or the name of a posture
+            of the robot such as "home" (optional).
+        :param radians: Overrides the joint units of the robot for this call (optional).
+        :return: One row per target with name, tool, motion, speed, zone, the TCP pose in
+            the world frame (x, y, z, qw, qx, qy, qz), j1..j6 (None if not reached), CF1,
+            CF4, CF6, CFX, whether the target is reached as taught (reachable) and the
+            issue if not, as validate() reports it.
+        :raises ValueError: If a target frame is unknown.
+        """
+        ...
+
+    def validate(
+        self,
+        robot: "Robot",
+        current_joints: Union[Tuple[float, float, float, float, float, float], str, None] = None,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Checks that a robot can run the program as taught.
+
+        :return: One dict per problem with the index and name of the target and the
+            issue: "unreachable", "configuration" (reachable only in another configuration
+            than taught) or "joint_limits" (a joint target beyond the limits). Empty if the
+            program is valid.
+        """
+        ...
+
+    def classify_motions(
+        self,
+        robot: "Robot",
+        tolerance: float = 0.001,
+        current_joints: Union[Tuple[float, float, float, float, float, float], str, None] = None,
+        samples: int = 16,
+        radians: Optional[bool] = None,
+    ) -> List[Dict[str, Any]]:
+        """
+        Sets the motion of every target to a joint move where it stays near the straight
+        line from the target before, else to a linear move, ahead of exporting the program.
+
+        A joint move interpolates the joints, so its TCP path bows away from the line
+        between the targets; it is kept where the TCP stays within `tolerance` (meters)
+        of that line at `samples` steps along the move. Joint targets and the first target
+        are joint moves, unless `current_joints` gives where the program starts; targets
+        the robot does not reach keep their motion.
+
+        :return: One dict per target with its index, name, deviation (meters, None where not
+            checked) and the motion set.
+        """
+        ...
+
+    def transform(
+        self,
+        displacement: Optional[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]] = None,
+        to_frame: Optional[str] = None,
+    ) -> "Program":
+        """
+        A copy of the program with every pose target moved by displacement, given in the
+        frame of the target like a program displacement on the controller, and
+        re-expressed in to_frame if given, keeping its place in the world. Joint targets
+        are kept.
+
+        :raises ValueError: If to_frame is unknown.
+        """
+        ...
+
+    def to_dict(self) -> Dict[str, Any]:
+        """
+        The program as plain values: name, radians, frames (the frames other than the
+        world as their poses in the world frame), tools (their TCP in the flange frame) and
+        targets as get() returns them.
+        """
+        ...
+
+    @staticmethod
+    def from_dict(data: Dict[str, Any], frames: Optional[Frames] = None) -> "Program":
+        """
+        Program from a dict as to_dict() returns it. Its frames are added to frames, a new
+        registry by default.
+
+        :raises ValueError: If a key is unknown or a target is invalid.
+        """
+        ...
+
+    @staticmethod
+    def from_rapid(
+        source: str,
+        routine: Optional[str] = None,
+        frames: Optional[Frames] = None,
+        radians: bool = False,
+    ) -> "Program":
+        """
+        Reads an ABB RAPID module into a program, to edit and re-solve an existing
+        controller program.
+
+        The MoveJ, MoveL and MoveAbsJ instructions (and their DO, AO, GO and Sync variants)
+        of the routine become targets, in the order they appear; control flow is not
+        followed and other instructions such as MoveC are skipped with a warning. Targets
+        can be declared robtargets and jointtargets, inline values, or Offs() and RelTool()
+        of them. Positions are converted to meters, speeddata (v100 or declared) to m/s and
+        zonedata (fine, z10 or declared) to a radius in meters, with \\V and \\Z
+        overriding them. The work objects used become frames (user frame times object frame)
+        and the tools used become tools of the program, tool0 being the bare flange.
+
+        :param source: Path of a .mod or .modx file, or the text of the module.
+        :param routine: Routine to read, main by default, or every routine if the module
+            has no main.
+        :param frames: Registry the work objects are added to, a new one by default.
+        :param radians: Whether joint values of the program are in radians.
+        :raises ValueError: If the module cannot be read, e.g. a target is undeclared or
+            given as an expression, or a tool or work object is held stationary.
+        :raises OSError: If the file cannot be read.
+        """
+        ...
+
+    @staticmethod
+    def from_krl(
+        source: str,
+        data: Optional[str] = None,
+        config: Optional[str] = None,
+        routine: Optional[str] = None,
+        frames: Optional[Frames] = None,
+        radians: bool = False,
+    ) -> "Program":
+        """
+        Reads a KUKA KRL program into a program, to edit and re-solve an existing
+        controller program.
+
+        The PTP, LIN, SPTP and SLIN moves of the routine become targets, in the order they
+        appear; control flow is not followed and other moves such as CIRC are skipped with
+        a warning. Targets can be declared E6POS, POS, FRAME, E6AXIS and AXIS points or
+        inline values; positions are converted to meters and A, B, C to a rotation about z,
+        then y, then x. Status and Turn are not kept. The tool and base in effect at each
+        move are selected by ``$TOOL``/``$BASE`` assignments, ``BAS(#TOOL, n)``/
+        ``BAS(#BASE, n)`` or the FDAT of an inline form; numbered ones are looked up as
+        TOOL_DATA[n]/BASE_DATA[n] and named after TOOL_NAME[n]/BASE_NAME[n], or tool{n} and
+        base{n}. The bases used become frames and the tools used become tools of the
+        program, tool 0 being the bare flange (tool0) and base 0 the world. LIN moves take
+        their speed in m/s from the LDAT or ``$VEL.CP``; PTP velocities are percentages and
+        are not kept. Approximated moves (C_DIS, C_PTP, ...) take their zone from APO_DIST
+        or ``$APO.CDIS``, the others have a zone of 0.
+
+        :param source: Path of the .src file, or the text of the program.
+        :param data: Path or text of the .dat file, by default the .dat next to the .src.
+        :param config: Path or text of the robot's $config.dat, needed for numbered tools
+            and bases.
+        :param routine: Routine to read, the first of the file by default.
+        :param frames: Registry the bases are added to, a new one by default.
+        :param radians: Whether joint values of the program are in radians.
+        :raises ValueError: If the program cannot be read, e.g. a point or tool is
+            undeclared, a value is given as an expression, or the tool is stationary.
+        :raises OSError: If a file cannot be read.
+        """
+        ...
+
+    def to_rapid(self, robot: Optional[Robot] = None, path: Optional[str] = None) -> str:
+        """
+        Writes the program as an ABB RAPID module that declares everything it uses.
+
+        Every tool used becomes a tooldata (tool0 is predefined) with the load data of
+        tool0, which has to be set before running the program; every frame other than the
+        world becomes a wobjdata with the frame as its user frame. Pose targets become
+        robtargets in their configuration (0, 0, 0, 0 if not taught) and are moved to with
+        MoveJ or MoveL, joint targets become jointtargets moved to with MoveAbsJ, all in
+        procedure main. The speed and zone of each target become the predefined speeddata
+        and zonedata (v100, z10, fine) where one matches, else declared ones; unset speeds
+        are v1000 and unset zones fine. Names are made valid RAPID identifiers, and targets without a valid
+        name are called p1, p2, ... after their position in the program. The world frame of
+        the program is taken as the world frame of the controller.
+
+        :param robot: Robot whose tool the targets without a tool are on, exported as
+            robot_tool.
+        :param path: File to write the module to.
+        :return: The text of the module.
+        :raises ValueError: If a target has no tool and no robot is given.
+        :raises OSError: If the file cannot be written.
+        """
+        ...
+
+    def to_ls(self, robot: Optional[Robot] = None, path: Optional[str] = None) -> str:
+        """
+        Writes the program as a Fanuc TP program in LS text.
+
+        Frames other than the world become user frames 1..9 and tools other than tool0 user
+        tools 1..10, numbered in the order they are first used, named in remarks and
+        selected with UFRAME_NUM and UTOOL_NUM. A TP program cannot hold their values, so
+        set them on the controller from ``frames`` and ``tools``. Pose targets become
+        positions in their user frame with the Fanuc configuration of their ABB axis
+        configuration (N U T, 0, 0, 0 if not taught), joint targets joint positions.
+        Linear moves take their speed in mm/sec (1000 if unset) and joint moves run at
+        100%. Moves with a zone end in CNT with the zone in millimeters as value, capped
+        at 100, as CNT is not a distance; the others end in FINE.
+
+        :param robot: Robot whose tool the targets without a tool are on, exported as
+            robot_tool.
+        :param path: File to write the program to.
+        :return: The LS text.
+        :raises ValueError: If a target has no tool and no robot is given, or the program
+            uses more frames or tools than the controller numbers.
+        :raises OSError: If the file cannot be written.
+        """
+        ...
+
+    def to_krl(self, robot: Optional[Robot] = None, path: Optional[str] = None) -> tuple[str, str]:
+        """
+        Writes the program as a KUKA KRL .src and .dat that declare everything they use.
+
+        The .dat declares every tool (except tool0, the flange) and every frame other than
+        the world as a FRAME, pose targets as POS and joint targets as AXIS. The .src
+        initializes the motion parameters with ``BAS(#INITMOV, 0)``, selects the tool and
+        base of each move by assigning these FRAMEs (or $NULLFRAME) to ``$TOOL`` and
+        ``$BASE``, and moves with PTP or LIN, so the program does not depend on the
+        robot's TOOL_DATA and BASE_DATA. LIN moves get their speed through ``$VEL.CP``;
+        PTP velocities are percentages of the axis speeds, so the speed of joint moves is
+        not exported. Moves with a zone are approximated with C_DIS at that distance, set
+        through ``$APO.CDIS``, the others stop exactly. Names are made valid KRL identifiers, and targets
+        without a valid name are called P1, P2, ... after their position in the program.
+
+        :param robot: Robot whose tool the targets without a tool are on, exported as
+            robot_tool.
+        :param path: File to write the .src to, the .dat is written next to it.
+        :return: The text of the .src and of the .dat.
+        :raises ValueError: If a target has no tool and no robot is given.
+        :raises OSError: If a file cannot be written.
+        """
+        ...
+
+    def __len__(self) -> int: ...
+
+def add_column_alias(alias: str, column: str) -> None:
+    """
+    Makes batch inputs accept an extra column name, matched case-insensitively.
+
+    :param alias: Column name as found in the user's tables, e.g. "Axis 1".
+    :param column: The column it provides: x, y, z, qw, qx, qy, qz or j1..j6.
+    :raises ValueError: If the column is unknown.
+    """
+    ...
+
+def cli_main(argv: Optional[List[str]] = None) -> int:
+    """
+    Runs the `opw-kin` command, e.g.
+    `opw-kin solve --model abb_irb1660.yaml --in poses.parquet --out joints.parquet --config 0,0,0,5`.
+
+    `solve` writes the rows of Robot.solve_and_join() for a table of poses, `forward` the
+    poses of a table of joints. Models are built-in names or JSON/YAML files of
+    KinematicModel keys, tables CSV or Parquet (with pandas).
+
+    :param argv: Command line arguments without the program name, `sys.argv[1:]` by default.
+    :return: The exit status, 0 on success.
+    """
+    ...
+
+def column_aliases() -> Dict[str, List[str]]:
+    """
+    Returns the accepted names of every pose and joint column, built-in and added.
+    """
+    ...
+
+def compare_robots(
+    poses: Union[
+        List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+        Any,
+    ],
+    robots: List[Union[Robot, KinematicModel, str]],
+    base_config: Optional[BaseConfig] = None,
+    tool_config: Optional[ToolConfig] = None,
+    names: Optional[List[str]] = None,
+    n_threads: Optional[int] = None,
+    chunk_size: Optional[int] = None,
+    pool: Optional[ThreadPool] = None,
+) -> List[Dict[str, Any]]:
+    """
+    Solves the same poses with several candidate robots and summarizes how well each
+    reaches them, for robot selection studies.
+
+    :param poses: Poses in the world frame, as for Robot.solve_and_join().
+    :param robots: Configured Robots, KinematicModels or names of built-in models (see
+        list_models()).
+    :param base_config: Base of the robots given as models or names (optional, identity).
+    :param tool_config: Tool of the robots given as models or names (optional, identity).
+    :param names: Names of the robots in the summary (optional). Built-in models are
+        named after the model, others "robot <i>" or "model <i>".
+    :param n_threads: Worker threads solving the poses, see Robot.batch_inverse().
+    :param chunk_size: Poses per chunk, see Robot.batch_inverse().
+    :param pool: ThreadPool with both settings.
+    :return: One row per robot with name, poses, reachable, reachable_fraction,
+        unreachable_rows (indices of the poses out of reach) and, for robots with joint
+        limits, min_limit_margin and mean_limit_margin: the distance of the joint closest
+        to a limit over the reached poses, in the joint units of the robot. Both are None
+        without joint limits.
+    :raises ValueError: If a pose has NaN or infinite values or a model name is unknown.
+    """
+    ...
+
+def list_models() -> List[str]:
+    """
+    Returns the names of all robot models in the built-in library.
+    """
+    ...
+
+def version_info() -> Dict[str, Any]:
+    """
+    Versions of this package and of the backend it is built on, to include in bug reports
+    and with stored results.
+
+    :return: version, backend ("rs-opw-kinematics"), backend_version and backend_features
+        (as cargo resolved them for the build), features (the Cargo features of this
+        package), profile and target.
+    """
+    ...
+
+def solver_info(robot: Robot) -> Dict[str, Any]:
+    """
+    How a robot solves, to record with its results.
+
+    :return: backend, backend_version and version as in version_info(), kinematic_model as
+        KinematicModel.to_dict() returns it, calibrated (link corrections applied), units,
+        joint_limits (whether limits are set), solution_policy, default_seed, cache and
+        stats (whether they are enabled), and the model_hash and tool_hash batch rows are
+        stamped with.
+    """
+    ...
+
+def fit_transform(
+    points_a: List[Tuple[float, float, float]],
+    points_b: List[Tuple[float, float, float]],
+    as_matrix: bool = False,
+) -> Dict[str, Any]:
+    """
+    Best-fit rigid transform T with T * a ≈ b for corresponding points (Kabsch), e.g. to
+    register measurement data to the robot frame.
+
+    :param points_a: Points (m) in the frame to map from.
+    :param points_b: The same points (m) in the frame to map to.
+    :param as_matrix: Return the transform as a 4x4 matrix instead of a pose.
+    :return: transform, rms_error and max_error (m) over the fitted points, and residuals,
+        the distance of every fitted point from its counterpart.
+    :raises ValueError: If the lists differ in length, contain NaN or infinite values, or
+        do not determine the transform (fewer than three points off a line).
+    """
+    ...
+
+def fit_pose_transform(
+    poses_a: Union[
+        List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+        Any,
+    ],
+    poses_b: Union[
+        List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+        Any,
+    ],
+    orientation_weight: float = 1.0,
+    as_matrix: bool = False,
+) -> Dict[str, Any]:
+    """
+    Best-fit rigid transform T with T * a ≈ b for corresponding poses.
+
+    :param poses_a: Poses in the frame to map from, as tuples or a DataFrame.
+    :param poses_b: The same poses in the frame to map to.
+    :param orientation_weight: How much a radian of rotation error counts against a meter
+        of position error (m); 0 fits the positions only.
+    :param as_matrix: Return the transform as a 4x4 matrix instead of a pose.
+    :return: transform, rms_position and max_position (m), rms_rotation and max_rotation
+        (degrees), and the position_residuals and rotation_residuals of every pair.
+    :raises ValueError: If the tables differ in length, contain NaN or infinite values, or
+        do not determine the transform.
+    """
+    ...
+
+def generate_pallet(
+    origin_pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+    nx: int,
+    ny: int,
+    nz: int,
+    dx: float,
+    dy: float,
+    dz: float,
+    approach: Optional[float] = None,
+    layer_rotations: Optional[List[float]] = None,
+    serpentine: bool = False,
+    tool_axis: Optional[Literal["x", "y", "z"]] = None,
+) -> List[Dict[str, Any]]:
+    """
+    Poses of a palletizing pattern, ready for batch_inverse or solve_and_join.
+
+    :param origin_pose: TCP pose at the first item.
+    :param nx: Items per row, spaced dx (m) along the x axis of origin_pose.
+    :param ny: Rows per layer, spaced dy (m) along its y axis.
+    :param nz: Layers, spaced dz (m) along its z axis; negative to stack up with the tool
+        axis pointing down. Axes are named as for a tool along z: with another tool_axis,
+        dz steps along it and dx and dy along the next two axes.
+    :param approach: Precede every item with its pose backed off this far (m) along the
+        tool axis (optional).
+    :param layer_rotations: Rotation (degrees) of the items of each layer about the tool
+        axis, cycled over the layers, e.g. [0, 90] for an interlocking pattern.
+    :param serpentine: Run every other row backwards.
+    :param tool_axis: Axis of the TCP frame along the approach direction, the convention
+        of set_tool_axis by default.
+    :return: Rows with the pose columns x, y, z, qw, qx, qy, qz, the index, layer, row and
+        column of the item, and approach (whether the row is the approach pose) with
+        approach.
+    :raises ValueError: If a count is 0 or a value is not finite.
+    """
+    ...
+
+def seam_poses(
+    points: List[Tuple[float, float, float]],
+    normal: Tuple[float, float, float] = (0.0, 0.0, 1.0),
+    normals: Optional[List[Tuple[float, float, float]]] = None,
+    work_angle: float = 0.0,
+    travel_angle: float = 0.0,
+    tool_axis: Optional[Literal["x", "y", "z"]] = None,
+) -> List[Dict[str, Any]]:
+    """
+    Tool poses following an ordered polyline, e.g. a scanned weld seam, ready for
+    batch_inverse or solve_and_join.
+
+    :param points: Seam points (m) in travel order.
+    :param normal: Surface normal the tool axis points against (world z by default).
+    :param normals: Surface normal at every point, overriding normal (optional).
+    :param work_angle: Rotation of the tool about the seam (degrees).
+    :param travel_angle: Tilt of the tool forward along the seam (degrees); positive
+        pushes, negative drags.
+    :param tool_axis: Axis of the TCP frame that points into the surface, the convention
+        of set_tool_axis by default.
+    :return: Rows with the pose columns x, y, z, qw, qx, qy, qz, the index of the point and
+        the distance (m) along the seam. The axis after the tool axis (x for a tool along
+        z) follows the seam.
+    :raises ValueError: With fewer than 2 points, coinciding consecutive points, a seam
+        running along its normal, or NaN or infinite values.
+    """
+    ...
+
+def set_tool_axis(axis: Literal["x", "y", "z"]) -> None:
+    """
+    Sets the axis of the TCP frame that points along the approach direction, "z" by
+    default or "x" for tools defined that way. look_at, generate_pallet, seam_poses,
+    Robot.optimize_roll, Robot.orientation_reachability and Robot.optimize_positioner use
+    it unless given another per call.
+    """
+    ...
+
+def tool_axis() -> Literal["x", "y", "z"]:
+    """
+    The axis of the TCP frame that points along the approach direction.
+    """
+    ...
+
+def look_at(
+    position: Tuple[float, float, float],
+    target_point: Tuple[float, float, float],
+    up: Tuple[float, float, float] = (0.0, 0.0, 1.0),
+    axis: Optional[Literal["x", "y", "z"]] = None,
+) -> Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]:
+    """
+    Pose whose axis points at a target, e.g. for camera or inspection targets.
+
+    :param position: Position (m) of the pose.
+    :param target_point: Point (m) the axis points at.
+    :param up: Direction the next axis (x after z, y after x, z after y) points as close
+        to as possible (world z by default).
+    :param axis: Axis of the pose that points at the target, the tool axis of
+        set_tool_axis by default.
+    :return: Pose as position and quaternion (w, x, y, z).
+    :raises ValueError: If the points coincide or the view is parallel to up.
+    """
+    ...
+
+def batch_look_at(
+    positions: List[Tuple[float, float, float]],
+    target_points: List[Tuple[float, float, float]],
+    up: Tuple[float, float, float] = (0.0, 0.0, 1.0),
+    axis: Optional[Literal["x", "y", "z"]] = None,
+) -> List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]]:
+    """
+    look_at for many positions.
+
+    :param positions: Positions (m) of the poses.
+    :param target_points: One point all poses look at, or one per position.
+    :param up: Direction the next axis points as close to as possible.
+    :param axis: Axis of the poses that points at the targets.
+    :return: One pose per position.
+    :raises ValueError: If target_points has neither 1 nor len(positions) entries, or a
+        pose cannot look at its target.
+    """
+    ...
+
+def joint_distance(
+    a: Tuple[float, float, float, float, float, float],
+    b: Tuple[float, float, float, float, float, float],
+    weights: Optional[Tuple[float, float, float, float, float, float]] = None,
+    wrap: bool = True,
+    radians: bool = False,
+) -> float:
+    """
+    Distance between two joint sets by the metric inverse kinematics ranks solutions with:
+    the sum of the absolute joint differences.
+
+    :param a: Joints, in degrees unless radians.
+    :param b: Joints to compare.
+    :param weights: Factor of each joint's difference, 1 by default.
+    :param wrap: Ignore whole turns of a joint, so 179 and -179 degrees lie 2 apart.
+    :param radians: Whether the joints are in radians; the distance is in the same unit.
+    :return: The weighted distance.
+    :raises ValueError: If the joints contain NaN or infinite values or a weight is
+        negative.
+    """
+    ...
+
+def joint_distances(
+    a: Union[List[Tuple[float, float, float, float, float, float]], Any],
+    b: Union[List[Tuple[float, float, float, float, float, float]], Any],
+    weights: Optional[Tuple[float, float, float, float, float, float]] = None,
+    wrap: bool = True,
+    radians: bool = False,
+) -> List[Optional[float]]:
+    """
+    joint_distance row by row.
+
+    :param a: Joint sets as sequences or a DataFrame with joint columns.
+    :param b: As many joint sets as a, or a single one to measure every row of a from.
+    :param weights: Factor of each joint's difference, 1 by default.
+    :param wrap: Ignore whole turns of a joint.
+    :param radians: Whether the joints are in radians.
+    :return: The distance of every row, None where a row has NaN or infinite values.
+    :raises ValueError: If b has neither one row nor as many as a.
+    """
+    ...
+
+def pairwise_joint_distances(
+    a: Union[List[Tuple[float, float, float, float, float, float]], Any],
+    b: Optional[Union[List[Tuple[float, float, float, float, float, float]], Any]] = None,
+    weights: Optional[Tuple[float, float, float, float, float, float]] = None,
+    wrap: bool = True,
+    radians: bool = False,
+) -> List[List[float]]:
+    """
+    joint_distance from every row of a to every row of b, computed in parallel, e.g. to
+    cluster joint solutions.
+
+    :param a: Joint sets as sequences or a DataFrame with joint columns.
+    :param b: Joint sets to measure to, a itself by default.
+    :param weights: Factor of each joint's difference, 1 by default.
+    :param wrap: Ignore whole turns of a joint.
+    :param radians: Whether the joints are in radians.
+    :return: A len(a) by len(b) matrix as a list of rows.
+    :raises ValueError: If a row has NaN or infinite values.
+    """
+    ...
+
+def pose_error(
+    p1: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+    p2: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+    radians: bool = False,
+) -> Tuple[float, float]:
+    """
+    Error between two poses.
+
+    :param p1: Pose as position and quaternion (w, x, y, z).
+    :param p2: Pose as position and quaternion (w, x, y, z).
+    :param radians: Return the rotational error in radians instead of degrees.
+    :return: Translational error (m) and rotational error (angle-axis magnitude).
+    :raises ValueError: If a pose contains NaN or infinite values.
+    """
+    ...
+
+def pose_errors(
+    poses1: Union[
+        List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+        Any,
+    ],
+    poses2: Union[
+        List[Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]],
+        Any,
+    ],
+    radians: bool = False,
+) -> List[Optional[Dict[str, float]]]:
+    """
+    Compares two pose tables row by row, e.g. forward kinematics against a controller log
+    or calibration results against measurements.
+
+    :param poses1: Poses as (position, quaternion) tuples or a DataFrame with pose columns.
+    :param poses2: Poses to compare, with as many rows as poses1.
+    :param radians: Return rotational errors in radians instead of degrees.
+    :return: One row per pose with dx, dy, dz and translation_error (m), and rx, ry, rz and
+        rotation_error, the rotation vector from the first pose to the second and its
+        magnitude, both in the common frame. Rows with NaN or infinite values are None.
+    :raises ValueError: If the tables differ in length.
+    """
+    ...
+
+def quaternions_to_euler(
+    quaternions: Union[List[Tuple[float, float, float, float]], Any],
+    sequence: str = "xyz",
+    radians: bool = False,
+) -> List[Optional[Tuple[float, float, float]]]:
+    """
+    Converts quaternions to Euler angles, in parallel.
+
+    :param quaternions: Quaternions (w, x, y, z) as sequences, an (n, 4) array or a
+        DataFrame with the columns qw, qx, qy, qz (or their aliases).
+    :param sequence: Axes of the rotations, lowercase for extrinsic ("xyz", roll, pitch,
+        yaw about the fixed axes) and uppercase for intrinsic ("ZYX", about the rotated
+        axes), any Tait-Bryan or proper Euler sequence. "kuka" is "ZYX" (A, B, C) and
+        "fanuc" is "xyz" (W, P, R).
+    :param radians: Return radians instead of degrees.
+    :return: The angles of each quaternion in the order of the sequence. The middle angle
+        lies in [-90, 90] degrees for Tait-Bryan and [0, 180] for proper Euler sequences;
+        in gimbal lock the third angle is 0. Rows that are not finite or zero give None.
+    :raises ValueError: If the sequence is unknown.
+    """
+    ...
+
+def euler_to_quaternions(
+    angles: Union[List[Tuple[float, float, float]], Any],
+    sequence: str = "xyz",
+    radians: bool = False,
+) -> List[Optional[Tuple[float, float, float, float]]]:
+    """
+    Converts Euler angles to quaternions (w, x, y, z), in parallel.
+
+    :param angles: Angle triples as sequences or an (n, 3) array, in degrees unless radians.
+    :param sequence: Axes of the rotations, see quaternions_to_euler.
+    :param radians: Whether the angles are in radians.
+    :return: The quaternion of each triple. Rows that are not finite give None.
+    :raises ValueError: If the sequence is unknown.
+    """
+    ...
+
+def quaternions_to_matrices(
+    quaternions: Union[List[Tuple[float, float, float, float]], Any],
+) -> List[Optional[List[List[float]]]]:
+    """
+    Converts quaternions (w, x, y, z), given as for quaternions_to_euler, to 3x3 rotation
+    matrices as lists of rows, in parallel. Rows that are not finite or zero give None.
+    """
+    ...
+
+def matrices_to_quaternions(
+    matrices: Union[List[List[List[float]]], Any],
+) -> List[Optional[Tuple[float, float, float, float]]]:
+    """
+    Converts 3x3 rotation matrices, as lists of rows or an (n, 3, 3) array, to quaternions
+    (w, x, y, z), in parallel. Matrices that are not quite orthonormal give the nearest
+    rotation; those that are not finite give None.
+    """
+    ...
+
+def pose_to_twist(
+    pose: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+) -> Tuple[float, float, float, float, float, float]:
+    """
+    Converts a pose to its se(3) twist (exponential coordinates).
+
+    :param pose: Pose as position and quaternion (w, x, y, z).
+    :return: Twist (vx, vy, vz, wx, wy, wz).
+    """
+    ...
+
+def twist_to_pose(
+    twist: Tuple[float, float, float, float, float, float],
+) -> Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]:
+    """
+    Converts an se(3) twist (exponential coordinates) to a pose.
+
+    :param twist: Twist (vx, vy, vz, wx, wy, wz).
+    :return: Pose as position and quaternion (w, x, y, z).
+    """
+    ...
+
+def interpolate_twist(
+    start: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+    end: Tuple[Tuple[float, float, float], Tuple[float, float, float, float]],
+    t: float,
+) -> Tuple[Tuple[float, float, float], Tuple[float, float, float, float]]:
+    """
+    Interpolates along the constant screw motion from start to end.
+
+    :param start: Pose at t = 0.
+    :param end: Pose at t = 1.
+    :param t: Interpolation fraction.
+    :return: The interpolated pose.
+    """
+    ...
+
+__all__: List[str] = [
+    "BaseConfig",
+    "BatchCancelledError",
+    "CancelToken",
+    "DualQuaternion",
+    "KinematicModel",
+    "Robot",
+    "RobotBuilder",
+    "ToolConfig",
+    "interpolate_twist",
+    "list_models",
+    "pose_to_twist",
+    "twist_to_pose",
+]
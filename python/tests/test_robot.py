@@ -1,3 +1,5 @@
+from concurrent.futures import ThreadPoolExecutor
+
 from py_opw_kinematics import Robot, KinematicModel, BaseConfig, ToolConfig
 import numpy as np
 import pytest
@@ -142,3 +144,16 @@ def test_batch_inverse_random(example_robot):
             assert np.allclose(computed_pose[0], original_pose[0], atol=1e-6), (
                 f"Position mismatch for pose {i}"
             )
+
+
+def test_shared_robot_across_threads(example_robot):
+    robot = example_robot
+    poses = [robot.forward([float(i), -85.0, 19.0, -70.0, -35.0, 185.0]) for i in range(100)]
+    expected = robot.batch_inverse(poses)
+
+    # One instance is shared by all workers, batch calls release the GIL
+    with ThreadPoolExecutor(max_workers=8) as executor:
+        results = list(executor.map(lambda _: robot.batch_inverse(poses), range(16)))
+
+    for result in results:
+        assert result == expected
@@ -0,0 +1,111 @@
+use nalgebra::{Rotation3, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::sync::RwLock;
+
+/// A coordinate axis of a frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+/// Axis of the TCP frame along the approach direction, set with `set_tool_axis`
+static TOOL_AXIS: RwLock<Axis> = RwLock::new(Axis::Z);
+
+impl Axis {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "x" => Ok(Axis::X),
+            "y" => Ok(Axis::Y),
+            "z" => Ok(Axis::Z),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown axis '{}', expected 'x', 'y' or 'z'",
+                name
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Axis::X => "x",
+            Axis::Y => "y",
+            Axis::Z => "z",
+        }
+    }
+
+    /// Column of the axis in a rotation matrix
+    pub fn index(&self) -> usize {
+        match self {
+            Axis::X => 0,
+            Axis::Y => 1,
+            Axis::Z => 2,
+        }
+    }
+
+    pub fn unit(&self) -> Vector3<f64> {
+        Vector3::ith(self.index(), 1.0)
+    }
+
+    /// The axis after this one in the right-handed cycle x, y, z
+    pub fn next(&self) -> Axis {
+        match self {
+            Axis::X => Axis::Y,
+            Axis::Y => Axis::Z,
+            Axis::Z => Axis::X,
+        }
+    }
+
+    /// Rotation taking this axis to z and the next one to x. A TCP orientation built with
+    /// the tool along z, multiplied by it on the right, becomes the orientation with the
+    /// tool along this axis and the z-tool x axis along the next.
+    pub fn tool_rotation(&self) -> UnitQuaternion<f64> {
+        let mut basis = [Vector3::zeros(); 3];
+        basis[self.index()] = Vector3::z();
+        basis[self.next().index()] = Vector3::x();
+        basis[self.next().next().index()] = Vector3::y();
+        UnitQuaternion::from_rotation_matrix(&Rotation3::from_basis_unchecked(&basis))
+    }
+
+    /// The tool axis of a call, the convention set with `set_tool_axis` unless given
+    pub fn tool(axis: Option<Axis>) -> Axis {
+        axis.unwrap_or_else(|| *TOOL_AXIS.read().unwrap_or_else(|e| e.into_inner()))
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for Axis {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        Axis::parse(&obj.extract::<String>()?)
+    }
+}
+
+/// Sets the axis of the TCP frame that points along the approach direction, "z" by
+/// default or "x" for tools defined that way. The pose generators and the methods for
+/// rotationally symmetric tools use it unless given another per call.
+#[pyfunction]
+pub fn set_tool_axis(axis: Axis) {
+    *TOOL_AXIS.write().unwrap_or_else(|e| e.into_inner()) = axis;
+}
+
+/// The axis of the TCP frame that points along the approach direction
+#[pyfunction]
+pub fn tool_axis() -> &'static str {
+    Axis::tool(None).name()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tool_rotation() {
+        for axis in [Axis::X, Axis::Y, Axis::Z] {
+            let rotation = axis.tool_rotation();
+            assert!((rotation * axis.unit() - Vector3::z()).norm() < 1e-12);
+            assert!((rotation * axis.next().unit() - Vector3::x()).norm() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+create_exception!(
+    _internal,
+    BatchCancelledError,
+    PyException,
+    "Raised when a batch operation is cancelled through its CancelToken."
+);
+
+/// Rows per work unit of a worker thread
+const CHUNK_SIZE: usize = 1024;
+
+/// Flag for cooperative cancellation of batch operations, settable from any Python thread.
+#[pyclass(frozen)]
+#[derive(Default)]
+pub struct CancelToken {
+    cancelled: AtomicBool,
+}
+
+#[pymethods]
+impl CancelToken {
+    #[new]
+    fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// Requests cancellation, the running batch stops at its next chunk boundary
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Clears the flag so the token can be reused
+    fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    #[getter]
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Worker threads and chunk size of parallel batch methods. Workers take chunks of
+/// `chunk_size` rows in turn, so small chunks balance uneven rows and large chunks cut the
+/// overhead on very wide batches. A pool holds settings, not threads: pass the same pool to
+/// every call to reuse its configuration, the workers are started per call.
+#[pyclass(frozen, from_py_object)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThreadPool {
+    n_threads: usize,
+    chunk_size: usize,
+}
+
+impl Default for ThreadPool {
+    fn default() -> Self {
+        ThreadPool {
+            n_threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            chunk_size: CHUNK_SIZE,
+        }
+    }
+}
+
+impl ThreadPool {
+    /// The settings of `pool` (or the defaults) with `n_threads` and `chunk_size` overriding them
+    pub fn resolve(
+        pool: Option<&ThreadPool>,
+        n_threads: Option<usize>,
+        chunk_size: Option<usize>,
+    ) -> PyResult<Self> {
+        let pool = pool.copied().unwrap_or_default();
+        ThreadPool::new(
+            Some(n_threads.unwrap_or(pool.n_threads)),
+            Some(chunk_size.unwrap_or(pool.chunk_size)),
+        )
+    }
+
+    /// Rows per round of one chunk per thread, saturating for very large settings
+    fn round(&self) -> usize {
+        self.chunk_size.saturating_mul(self.n_threads)
+    }
+
+    /// Maps `f` over `items` on the pool's threads, keeping the order of the items
+    pub fn map<T, R, F>(&self, items: &[T], f: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&T) -> R + Sync,
+    {
+        let chunks: Vec<&[T]> = items.chunks(self.chunk_size).collect();
+        let workers = self.n_threads.min(chunks.len());
+        if workers <= 1 {
+            return items.iter().map(f).collect();
+        }
+        let next = AtomicUsize::new(0);
+        let mut done: Vec<(usize, Vec<R>)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(chunk) = chunks.get(index) else {
+                                return done;
+                            };
+                            done.push((index, chunk.iter().map(&f).collect::<Vec<_>>()));
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("Batch worker thread panicked"))
+                .collect()
+        });
+        done.sort_by_key(|(index, _)| *index);
+        done.into_iter().flat_map(|(_, results)| results).collect()
+    }
+}
+
+#[pymethods]
+impl ThreadPool {
+    /// `n_threads` defaults to the number of cores, `chunk_size` to 1024 rows
+    #[new]
+    #[pyo3(signature = (n_threads=None, chunk_size=None))]
+    fn new(n_threads: Option<usize>, chunk_size: Option<usize>) -> PyResult<Self> {
+        let pool = ThreadPool::default();
+        let pool = ThreadPool {
+            n_threads: n_threads.unwrap_or(pool.n_threads),
+            chunk_size: chunk_size.unwrap_or(pool.chunk_size),
+        };
+        if pool.n_threads == 0 || pool.chunk_size == 0 {
+            return Err(PyValueError::new_err(format!(
+                "n_threads and chunk_size must be positive, got {} and {}",
+                pool.n_threads, pool.chunk_size
+            )));
+        }
+        Ok(pool)
+    }
+
+    #[getter]
+    pub fn n_threads(&self) -> usize {
+        self.n_threads
+    }
+
+    #[getter]
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ThreadPool(n_threads={}, chunk_size={})",
+            self.n_threads, self.chunk_size
+        )
+    }
+}
+
+/// Maps `f` over `items` on the threads of `pool` with the GIL released. After every round
+/// of one chunk per thread `progress` is called with (done, total), and Ctrl+C or a
+/// cancelled token abort the batch.
+pub fn run<T, R, F>(
+    py: Python<'_>,
+    items: &[T],
+    progress: Option<&Bound<'_, PyAny>>,
+    cancel: Option<&CancelToken>,
+    pool: &ThreadPool,
+    f: F,
+) -> PyResult<Vec<R>>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let total = items.len();
+    let mut results = Vec::with_capacity(total);
+    for round in items.chunks(pool.round()) {
+        py.check_signals()?;
+        if cancel.is_some_and(CancelToken::cancelled) {
+            return Err(BatchCancelledError::new_err(format!(
+                "Batch cancelled after {} of {} rows",
+                results.len(),
+                total
+            )));
+        }
+        results.extend(py.detach(|| pool.map(round, &f)));
+        if let Some(progress) = progress {
+            progress.call1((results.len(), total))?;
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_map_keeps_order() {
+        let items: Vec<usize> = (0..10_000).collect();
+        for pool in [
+            ThreadPool::default(),
+            ThreadPool::new(Some(3), Some(7)).unwrap(),
+        ] {
+            let squares = pool.map(&items, |&x| x * x);
+            assert!(squares.iter().enumerate().all(|(i, &x)| x == i * i));
+            assert!(pool.map(&[] as &[usize], |&x| x).is_empty());
+        }
+        assert!(ThreadPool::new(Some(0), None).is_err());
+        let huge = ThreadPool::new(Some(usize::MAX), Some(usize::MAX)).unwrap();
+        assert_eq!(huge.round(), usize::MAX);
+        assert_eq!(huge.map(&items[..3], |&x| x + 1), [1, 2, 3]);
+        let resolved = ThreadPool::resolve(None, Some(2), None).unwrap();
+        assert_eq!((resolved.n_threads, resolved.chunk_size), (2, CHUNK_SIZE));
+    }
+}
@@ -0,0 +1,94 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+use crate::configuration;
+use crate::kinematic_model::KinematicModel;
+
+/// Number of analytic branches of an OPW robot: two shoulder, two elbow and two wrist
+/// solutions
+pub const BRANCHES: usize = 8;
+
+/// Shoulder, elbow and wrist labels of the branch with configuration index `cfx`, see
+/// `configuration::configuration` for its bits
+pub fn labels(cfx: u8) -> (&'static str, &'static str, &'static str) {
+    (
+        if cfx & 4 == 0 { "front" } else { "back" },
+        if cfx & 2 == 0 { "up" } else { "down" },
+        if cfx & 1 == 0 { "noflip" } else { "flip" },
+    )
+}
+
+/// The solutions (radians, as the controller reports them, coupled) placed at the index of
+/// their branch. Branches without a solution stay None; of solutions on the same branch,
+/// which only happens at singularities, the first is kept.
+pub fn by_branch(model: &KinematicModel, solutions: &[Joints]) -> [Option<Joints>; BRANCHES] {
+    let mut branches = [None; BRANCHES];
+    for solution in solutions {
+        let branch = &mut branches[configuration::configuration(model, solution) as usize];
+        if branch.is_none() {
+            *branch = Some(*solution);
+        } else {
+            log::debug!("Solutions share branch {:?}, keeping the first", branch);
+        }
+    }
+    branches
+}
+
+/// One row of the complete inverse: branch index, labels, the joints or None if the
+/// branch cannot reach the pose, and whether they lie within the joint limits
+pub fn to_dict<'py>(
+    py: Python<'py>,
+    cfx: u8,
+    joints: Option<[f64; 6]>,
+    within_limits: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    let (shoulder, elbow, wrist) = labels(cfx);
+    let row = PyDict::new(py);
+    row.set_item("branch", cfx)?;
+    row.set_item("shoulder", shoulder)?;
+    row.set_item("elbow", elbow)?;
+    row.set_item("wrist", wrist)?;
+    row.set_item("joints", joints)?;
+    row.set_item("within_limits", within_limits)?;
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+    use rs_opw_kinematics::kinematic_traits::Kinematics;
+
+    #[test]
+    fn test_regular_pose_fills_every_branch() {
+        let robot = ABB_1660.to_opw_kinematics();
+        let pose = robot.forward(&[0.2, 0.3, 0.1, 0.4, 0.5, 0.6]);
+        let branches = by_branch(&ABB_1660, &robot.inverse(&pose));
+        for (cfx, joints) in branches.iter().enumerate() {
+            let joints = joints.expect("every branch reaches a regular pose");
+            let reached = robot.forward(&joints);
+            assert!((reached.translation.vector - pose.translation.vector).norm() < 1e-9);
+            assert_eq!(configuration::configuration(&ABB_1660, &joints), cfx as u8);
+        }
+        assert_eq!(labels(0), ("front", "up", "noflip"));
+        assert_eq!(labels(7), ("back", "down", "flip"));
+    }
+
+    #[test]
+    fn test_coupled_joints_fill_every_branch() {
+        let parallelogram = KinematicModel {
+            has_parallelogram: true,
+            ..ABB_1660
+        };
+        let robot = ABB_1660.to_opw_kinematics();
+        let pose = robot.forward(&[0.2, 0.3, 0.1, 0.4, 0.5, 0.6]);
+        let coupled: Vec<Joints> = robot
+            .inverse(&pose)
+            .iter()
+            .map(|solution| parallelogram.couple(solution))
+            .collect();
+        let branches = by_branch(&parallelogram, &coupled);
+        assert!(branches.iter().all(Option::is_some));
+    }
+}
@@ -0,0 +1,255 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use crate::ordering::{InverseOptions, SolutionOrder};
+use crate::pose::PyPose;
+
+/// Solutions of one pose with the number of valid solutions and the index of the chosen one
+pub type Solved = (Vec<Joints>, usize, Option<usize>);
+
+/// A pose quantized to the cache resolution, with the exact seed and options it was solved with
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct Key {
+    pose: [i64; 7],
+    seed: Option<[u64; 6]>,
+    order_by: SolutionOrder,
+    dedup_tolerance: Option<u64>,
+    dedup_turns: bool,
+}
+
+struct Lru {
+    capacity: usize,
+    position_resolution: f64,
+    rotation_resolution: f64,
+    entries: HashMap<Key, (Solved, u64)>,
+    /// Keys by the tick of their last use, the first is evicted next
+    order: BTreeMap<u64, Key>,
+    tick: u64,
+}
+
+impl Lru {
+    fn key(&self, pose: &PyPose, seed: Option<Joints>, options: &InverseOptions) -> Option<Key> {
+        let ([x, y, z], rotation) = *pose;
+        let norm = rotation.iter().map(|q| q * q).sum::<f64>().sqrt();
+        // q and -q are the same rotation
+        let sign = if rotation[0] < 0.0 { -norm } else { norm };
+        let values = [x, y, z]
+            .map(|p| p / self.position_resolution)
+            .into_iter()
+            .chain(rotation.iter().map(|q| q / sign / self.rotation_resolution));
+        let mut quantized = [0i64; 7];
+        for (slot, value) in quantized.iter_mut().zip(values) {
+            if !value.is_finite() {
+                return None;
+            }
+            *slot = value.round() as i64;
+        }
+        Some(Key {
+            pose: quantized,
+            seed: seed.map(|seed| seed.map(f64::to_bits)),
+            order_by: options.order_by,
+            dedup_tolerance: options.dedup_tolerance.map(f64::to_bits),
+            dedup_turns: options.dedup_turns,
+        })
+    }
+
+    fn get(&mut self, key: &Key) -> Option<Solved> {
+        self.tick += 1;
+        let tick = self.tick;
+        let (solved, used) = self.entries.get_mut(key)?;
+        self.order.remove(used);
+        *used = tick;
+        self.order.insert(tick, key.clone());
+        Some(solved.clone())
+    }
+
+    /// Stores solutions, returns whether the least recently used entry was evicted
+    fn insert(&mut self, key: Key, solved: Solved) -> bool {
+        self.tick += 1;
+        let mut evicted = false;
+        if let Some((_, used)) = self.entries.get(&key) {
+            self.order.remove(used);
+        } else if self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.order.pop_first() {
+                self.entries.remove(&oldest);
+                evicted = true;
+            }
+        }
+        self.order.insert(self.tick, key.clone());
+        self.entries.insert(key, (solved, self.tick));
+        evicted
+    }
+}
+
+/// Opt-in memoization of inverse kinematics with least recently used eviction, for
+/// workloads that solve the same targets again and again (palletizing patterns). Poses are
+/// quantized, so poses within the resolution of a cached one get its solutions.
+#[derive(Default)]
+pub struct IkCache {
+    lru: Mutex<Option<Lru>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl IkCache {
+    /// Enables an empty cache, dropping any cached solutions
+    pub fn enable(
+        &self,
+        capacity: usize,
+        position_resolution: f64,
+        rotation_resolution: f64,
+    ) -> PyResult<()> {
+        if capacity == 0 {
+            return Err(PyValueError::new_err("The cache capacity must be positive"));
+        }
+        for (name, resolution) in [
+            ("position_resolution", position_resolution),
+            ("rotation_resolution", rotation_resolution),
+        ] {
+            if !(resolution.is_finite() && resolution > 0.0) {
+                return Err(PyValueError::new_err(format!(
+                    "{} must be positive and finite, got {}",
+                    name, resolution
+                )));
+            }
+        }
+        *self.lock() = Some(Lru {
+            capacity,
+            position_resolution,
+            rotation_resolution,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+            tick: 0,
+        });
+        self.reset_counters();
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    pub fn disable(&self) {
+        *self.lock() = None;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Lru>> {
+        self.lru.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn reset_counters(&self) {
+        for counter in [&self.hits, &self.misses, &self.evictions] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Returns cached solutions, or solves with `solve` and caches the result. Without a
+    /// cache this just calls `solve`.
+    pub fn get_or_solve(
+        &self,
+        pose: &PyPose,
+        seed: Option<Joints>,
+        options: &InverseOptions,
+        solve: impl FnOnce() -> Solved,
+    ) -> Solved {
+        let lookup = self.lock().as_mut().and_then(|lru| {
+            let key = lru.key(pose, seed, options)?;
+            Some(lru.get(&key).ok_or(key))
+        });
+        let key = match lookup {
+            None => return solve(),
+            Some(Ok(solved)) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return solved;
+            }
+            Some(Err(key)) => key,
+        };
+        // Solved without the lock so other threads keep using the cache meanwhile
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let solved = solve();
+        if let Some(lru) = self.lock().as_mut() {
+            if lru.insert(key, solved.clone()) {
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        solved
+    }
+
+    /// Drops the cached solutions and counters, keeping the settings
+    pub fn clear(&self) {
+        if let Some(lru) = self.lock().as_mut() {
+            lru.entries.clear();
+            lru.order.clear();
+        }
+        self.reset_counters();
+    }
+
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        let lru = self.lock();
+        dict.set_item("enabled", lru.is_some())?;
+        dict.set_item("capacity", lru.as_ref().map_or(0, |lru| lru.capacity))?;
+        dict.set_item("size", lru.as_ref().map_or(0, |lru| lru.entries.len()))?;
+        dict.set_item("hits", self.hits.load(Ordering::Relaxed))?;
+        dict.set_item("misses", self.misses.load(Ordering::Relaxed))?;
+        dict.set_item("evictions", self.evictions.load(Ordering::Relaxed))?;
+        Ok(dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_quantizes_and_evicts() {
+        let cache = IkCache::default();
+        cache.enable(2, 1e-3, 1e-3).unwrap();
+        let options = InverseOptions::default();
+        let solved = |value: f64| (vec![[value; 6]], 1, Some(0));
+        let pose = |x: f64| ([x, 0.0, 1.0], [1.0, 0.0, 0.0, 0.0]);
+
+        assert_eq!(
+            cache
+                .get_or_solve(&pose(0.5), None, &options, || solved(1.0))
+                .0[0][0],
+            1.0
+        );
+        // Within the resolution, and the same rotation as -q
+        let nearby = ([0.5002, 0.0, 1.0], [-1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(
+            cache
+                .get_or_solve(&nearby, None, &options, || solved(2.0))
+                .0[0][0],
+            1.0
+        );
+        // Another seed is another entry
+        assert_eq!(
+            cache
+                .get_or_solve(&pose(0.5), Some([0.0; 6]), &options, || solved(3.0))
+                .0[0][0],
+            3.0
+        );
+        // The third entry evicts the least recently used one
+        cache.get_or_solve(&pose(0.7), None, &options, || solved(4.0));
+        assert_eq!(
+            cache
+                .get_or_solve(&pose(0.5), None, &options, || solved(5.0))
+                .0[0][0],
+            5.0
+        );
+
+        let lru = cache.lock();
+        assert_eq!(lru.as_ref().unwrap().entries.len(), 2);
+        assert_eq!(cache.hits.load(Ordering::Relaxed), 1);
+        assert_eq!(cache.evictions.load(Ordering::Relaxed), 2);
+        drop(lru);
+        assert!(cache.enable(0, 1e-3, 1e-3).is_err());
+    }
+}
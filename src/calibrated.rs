@@ -0,0 +1,152 @@
+use nalgebra::{Isometry3, Matrix6, Translation3, Vector3, Vector6};
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+use crate::kinematic_model::KinematicModel;
+use crate::twist::{self, Twist};
+
+/// Per-link corrections [vx, vy, vz, wx, wy, wz] (m, rad), identified from measurement
+pub type Corrections = [Twist; 6];
+
+/// Newton iterations that refine the analytic solution against the calibrated chain
+const MAX_ITERATIONS: usize = 20;
+/// Joint step of the numerical Jacobian, in radians
+const JACOBIAN_STEP: f64 = 1e-7;
+/// Residual (m and rad) below which a refined solution is accepted
+const TOLERANCE: f64 = 1e-10;
+/// Residual above which a refined solution is dropped
+const MAX_RESIDUAL: f64 = 1e-6;
+
+/// The OPW chain as a product of exponentials, with a small constant displacement of each
+/// link after its joint. With zero corrections it matches the nominal model exactly.
+#[derive(Clone)]
+pub struct CalibratedChain {
+    model: KinematicModel,
+    corrections: Corrections,
+    /// Joint screw axes in the robot base frame at the zero position
+    axes: [Twist; 6],
+    /// Flange pose at the zero position
+    home: Isometry3<f64>,
+    base: Isometry3<f64>,
+    tool: Isometry3<f64>,
+}
+
+/// Screw axis of a revolute joint through `point` along `direction`
+fn revolute(direction: Vector3<f64>, point: Vector3<f64>) -> Twist {
+    let v = -direction.cross(&point);
+    [v.x, v.y, v.z, direction.x, direction.y, direction.z]
+}
+
+impl CalibratedChain {
+    pub fn new(
+        model: &KinematicModel,
+        corrections: Corrections,
+        base: Isometry3<f64>,
+        tool: Isometry3<f64>,
+    ) -> Self {
+        let (y, z) = (Vector3::y(), Vector3::z());
+        let wrist = Vector3::new(model.a1 + model.a2, model.b, model.c1 + model.c2 + model.c3);
+        let axes = [
+            revolute(z, Vector3::zeros()),
+            revolute(y, Vector3::new(model.a1, 0.0, model.c1)),
+            revolute(y, Vector3::new(model.a1, 0.0, model.c1 + model.c2)),
+            revolute(z, wrist),
+            revolute(y, wrist),
+            revolute(z, wrist),
+        ];
+        let home =
+            Isometry3::from_parts(Translation3::from(wrist + z * model.c4), Default::default());
+        CalibratedChain {
+            model: model.clone(),
+            corrections,
+            axes,
+            home,
+            base,
+            tool,
+        }
+    }
+
+    pub fn corrections(&self) -> &Corrections {
+        &self.corrections
+    }
+
+    /// TCP pose in the world frame for controller joints in radians
+    pub fn forward(&self, joints: &Joints) -> Isometry3<f64> {
+        let decoupled = self.model.decouple(joints);
+        let mut pose = self.base;
+        for (i, joint) in decoupled.iter().enumerate() {
+            let q = joint * self.model.sign_corrections[i] as f64 - self.model.offsets[i];
+            pose =
+                pose * twist::exp(&self.axes[i].map(|x| x * q)) * twist::exp(&self.corrections[i]);
+        }
+        pose * self.home * self.tool
+    }
+
+    /// Refines an approximate solution (controller joints, radians) until the calibrated
+    /// chain reaches `target`. Returns None if it does not converge.
+    pub fn refine(&self, target: &Isometry3<f64>, joints: &Joints) -> Option<Joints> {
+        let mut joints = *joints;
+        for _ in 0..MAX_ITERATIONS {
+            let current = self.forward(&joints);
+            let error = Vector6::from(twist::log(&(current.inverse() * target)));
+            if error.norm() < TOLERANCE {
+                return Some(joints);
+            }
+            let mut jacobian = Matrix6::zeros();
+            for k in 0..6 {
+                let mut stepped = joints;
+                stepped[k] += JACOBIAN_STEP;
+                let column = twist::log(&(current.inverse() * self.forward(&stepped)));
+                jacobian.set_column(k, &(Vector6::from(column) / JACOBIAN_STEP));
+            }
+            let step = jacobian.lu().solve(&error)?;
+            for (joint, delta) in joints.iter_mut().zip(step.iter()) {
+                *joint += delta;
+            }
+        }
+        let residual = Vector6::from(twist::log(&(self.forward(&joints).inverse() * target)));
+        (residual.norm() < MAX_RESIDUAL).then_some(joints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pose::pose_errors;
+    use crate::tests::ABB_1660;
+    use nalgebra::UnitQuaternion;
+    use rs_opw_kinematics::kinematic_traits::Kinematics;
+
+    const JOINTS: Joints = [0.3, -0.2, 0.4, 1.1, -0.7, 2.0];
+
+    #[test]
+    fn test_zero_corrections_match_nominal() {
+        let chain = CalibratedChain::new(
+            &ABB_1660,
+            [[0.0; 6]; 6],
+            Isometry3::identity(),
+            Isometry3::identity(),
+        );
+        let nominal = ABB_1660.to_opw_kinematics().forward(&JOINTS);
+        let (position_error, rotation_error) = pose_errors(&chain.forward(&JOINTS), &nominal);
+        assert!(position_error < 1e-12 && rotation_error < 1e-12);
+    }
+
+    #[test]
+    fn test_refine_reaches_calibrated_pose() {
+        let mut corrections = [[0.0; 6]; 6];
+        corrections[1] = [0.001, 0.0, -0.0005, 0.0, 0.002, 0.0];
+        corrections[4] = [0.0, 0.0003, 0.0, 0.001, 0.0, -0.001];
+        let chain = CalibratedChain::new(
+            &ABB_1660,
+            corrections,
+            Isometry3::identity(),
+            Isometry3::from_parts(Translation3::new(0.0, 0.0, 0.1), UnitQuaternion::identity()),
+        );
+        let target = chain.forward(&JOINTS);
+        let start = JOINTS.map(|j| j + 0.01);
+        let refined = chain.refine(&target, &start).unwrap();
+        for (a, b) in refined.iter().zip(JOINTS.iter()) {
+            assert!((a - b).abs() < 1e-8);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use nalgebra::Isometry3;
+use rs_opw_kinematics::kinematic_traits::{Joints, Kinematics};
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use crate::kinematic_model::KinematicModel;
+use crate::pose::pose_errors;
+
+/// Candidate joint offsets: the zero position of a joint is usually off by a quarter or half turn
+const OFFSET_CANDIDATES: [f64; 4] = [0.0, FRAC_PI_2, -FRAC_PI_2, PI];
+
+/// A sign/offset combination that reproduces the reference poses
+pub struct Match {
+    pub model: KinematicModel,
+    pub position_error: f64,
+    pub rotation_error: f64,
+}
+
+/// Worst position (m) and rotation (rad) error of `model` over the samples, giving up
+/// as soon as either exceeds its limit.
+fn max_errors(
+    model: &KinematicModel,
+    samples: &[(Joints, Isometry3<f64>)],
+    position_limit: f64,
+    rotation_limit: f64,
+) -> Option<(f64, f64)> {
+    let robot = model.to_opw_kinematics();
+    let mut worst = (0.0_f64, 0.0_f64);
+    for (joints, expected) in samples {
+        let pose = robot.forward(&model.decouple(joints));
+        let (position_error, rotation_error) = pose_errors(&pose, expected);
+        if position_error > position_limit || rotation_error > rotation_limit {
+            return None;
+        }
+        worst = (worst.0.max(position_error), worst.1.max(rotation_error));
+    }
+    Some(worst)
+}
+
+/// Searches all sign corrections and quarter/half turn offsets of `model` for the
+/// combinations whose forward kinematics reproduce the samples within tolerance,
+/// best first. Joints are in radians, poses of the flange in the robot base frame.
+///
+/// A combination and its wrist-flipped twin (J4 and J6 turned by half a turn, J5 mirrored)
+/// produce identical poses, so matches usually come in pairs.
+pub fn detect_signs_and_offsets(
+    model: &KinematicModel,
+    samples: &[(Joints, Isometry3<f64>)],
+    position_tolerance: f64,
+    rotation_tolerance: f64,
+) -> Vec<Match> {
+    let mut matches = Vec::new();
+    let offset_combinations = OFFSET_CANDIDATES.len().pow(6);
+    for signs in 0..64_u32 {
+        let sign_corrections: [i8; 6] =
+            std::array::from_fn(|i| if signs >> i & 1 == 1 { -1 } else { 1 });
+        for combination in 0..offset_combinations {
+            let offsets: [f64; 6] = std::array::from_fn(|i| {
+                OFFSET_CANDIDATES
+                    [combination / OFFSET_CANDIDATES.len().pow(i as u32) % OFFSET_CANDIDATES.len()]
+            });
+            let candidate = KinematicModel {
+                offsets,
+                sign_corrections,
+                ..model.clone()
+            };
+            if let Some((position_error, rotation_error)) =
+                max_errors(&candidate, samples, position_tolerance, rotation_tolerance)
+            {
+                matches.push(Match {
+                    model: candidate,
+                    position_error,
+                    rotation_error,
+                });
+            }
+        }
+    }
+    // Errors relative to their tolerance, so neither unit dominates the ranking
+    let score =
+        |m: &Match| m.position_error / position_tolerance + m.rotation_error / rotation_tolerance;
+    matches.sort_by(|a, b| score(a).total_cmp(&score(b)));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+
+    #[test]
+    fn test_recovers_signs_and_offsets() {
+        let truth = KinematicModel {
+            offsets: [0.0, FRAC_PI_2, -FRAC_PI_2, 0.0, PI, 0.0],
+            sign_corrections: [-1, 1, 1, -1, 1, -1],
+            ..ABB_1660
+        };
+        let robot = truth.to_opw_kinematics();
+        let samples: Vec<_> = [
+            [0.1, 0.2, -0.3, 0.4, 0.5, 0.6],
+            [-0.7, 0.3, 0.2, -1.1, 0.9, 2.0],
+            [1.2, -0.4, 0.6, 0.3, -0.8, -1.5],
+            [0.5, 0.1, -0.2, 2.1, 1.3, 0.7],
+        ]
+        .iter()
+        .map(|joints| (*joints, robot.forward(joints)))
+        .collect();
+
+        let matches = detect_signs_and_offsets(&ABB_1660, &samples, 1e-6, 1e-6);
+        // The truth and its wrist-flipped twin (J4 and J6 half a turn, J5 mirrored)
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().any(|m| {
+            m.model.sign_corrections == truth.sign_corrections
+                && m.model
+                    .offsets
+                    .iter()
+                    .zip(truth.offsets.iter())
+                    .all(|(found, expected)| (found - expected).abs() < 1e-12)
+        }));
+    }
+}
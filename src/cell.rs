@@ -0,0 +1,399 @@
+use nalgebra::Isometry3;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::dict;
+use crate::frames::{Frames, WORLD};
+use crate::pose::{from_isometry, to_isometry, PyPose};
+use crate::positioner::Positioner;
+use crate::validation;
+use crate::{Robot, ToolConfig};
+
+/// Value of the "format" key of a workcell file
+const FORMAT: &str = "py-opw-kinematics cell";
+/// Version of the workcell file layout written by `Cell.save`
+const VERSION: u32 = 1;
+
+/// A finite pose given as a pair of sequences, lists as read back from JSON or tuples
+fn extract_pose(value: &Bound<'_, PyAny>) -> PyResult<PyPose> {
+    let [translation, rotation]: [Vec<f64>; 2] = value.extract()?;
+    match (translation.try_into(), rotation.try_into()) {
+        (Ok(translation), Ok(rotation)) => {
+            let pose = (translation, rotation);
+            validation::check_pose(&pose)?;
+            Ok(pose)
+        }
+        _ => Err(PyValueError::new_err(format!(
+            "Expected a pose as ([x, y, z], [w, x, y, z]), got {}",
+            value
+        ))),
+    }
+}
+
+/// Shape of a collision object, in meters about the origin of its pose
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// Box with its edge lengths along x, y and z, centered on the origin
+    Box([f64; 3]),
+    Sphere(f64),
+    /// Cylinder along z, centered on the origin
+    Cylinder {
+        radius: f64,
+        length: f64,
+    },
+}
+
+impl Shape {
+    /// Shape of the kind `name` with `size`: the edge lengths of a box, the radius of a
+    /// sphere, the radius and length of a cylinder
+    pub fn new(name: &str, size: &[f64]) -> Result<Self, String> {
+        if !size.iter().all(|value| value.is_finite() && *value > 0.0) {
+            return Err(format!(
+                "The size of a {} must be positive, got {:?}",
+                name, size
+            ));
+        }
+        match (name, size) {
+            ("box", &[x, y, z]) => Ok(Shape::Box([x, y, z])),
+            ("sphere", &[radius]) => Ok(Shape::Sphere(radius)),
+            ("cylinder", &[radius, length]) => Ok(Shape::Cylinder { radius, length }),
+            ("box" | "sphere" | "cylinder", _) => Err(format!(
+                "A {} takes {} sizes, got {:?}",
+                name,
+                match name {
+                    "box" => "3 (x, y, z)",
+                    "sphere" => "1 (radius)",
+                    _ => "2 (radius, length)",
+                },
+                size
+            )),
+            _ => Err(format!(
+                "Unknown shape '{}', expected 'box', 'sphere' or 'cylinder'",
+                name
+            )),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Box(_) => "box",
+            Shape::Sphere(_) => "sphere",
+            Shape::Cylinder { .. } => "cylinder",
+        }
+    }
+
+    pub fn size(&self) -> Vec<f64> {
+        match self {
+            Shape::Box(edges) => edges.to_vec(),
+            Shape::Sphere(radius) => vec![*radius],
+            Shape::Cylinder { radius, length } => vec![*radius, *length],
+        }
+    }
+}
+
+/// A fixture, fence or other obstacle of the cell, placed at `pose` in `frame`
+#[derive(Clone, Debug)]
+pub struct CollisionObject {
+    pub name: String,
+    pub shape: Shape,
+    pub frame: String,
+    pub pose: Isometry3<f64>,
+}
+
+impl CollisionObject {
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(
+            data,
+            &["name", "shape", "size", "frame", "pose"],
+            "collision object",
+        )?;
+        let shape: String = dict::required(data, "shape", "A collision object")?;
+        let size: Vec<f64> = dict::required(data, "size", "A collision object")?;
+        let pose = match data.get_item("pose")? {
+            Some(pose) if !pose.is_none() => Some(extract_pose(&pose)?),
+            _ => None,
+        };
+        Ok(CollisionObject {
+            name: dict::required(data, "name", "A collision object")?,
+            shape: Shape::new(&shape, &size).map_err(PyValueError::new_err)?,
+            frame: dict::optional(data, "frame")?.unwrap_or_else(|| WORLD.to_string()),
+            pose: pose.map_or(Isometry3::identity(), |pose| to_isometry(pose.0, pose.1)),
+        })
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("name", &self.name)?;
+        dict.set_item("shape", self.shape.name())?;
+        dict.set_item("size", self.shape.size())?;
+        dict.set_item("frame", &self.frame)?;
+        dict.set_item("pose", from_isometry(&self.pose))?;
+        Ok(dict)
+    }
+}
+
+/// A whole workcell: the robot with its model, base, tool and limits, the named tools it
+/// can carry, the frames of its work objects, an optional positioner as external axes and
+/// the collision objects around it. Saved as one JSON file, so a setup can be passed
+/// between scripts and colleagues.
+#[pyclass(frozen)]
+pub struct Cell {
+    name: String,
+    robot: Py<Robot>,
+    frames: Py<Frames>,
+    tools: Vec<(String, Isometry3<f64>)>,
+    positioner: Option<Py<Positioner>>,
+    collision_objects: Vec<CollisionObject>,
+}
+
+#[pymethods]
+impl Cell {
+    /// Cell of `robot` with `frames` (a new registry by default), `tools` by name as their
+    /// TCP in the flange frame, an optional `positioner` and `collision_objects` as dicts
+    /// with name, shape ("box", "sphere" or "cylinder"), size (meters), frame (world by
+    /// default) and pose in that frame (identity by default).
+    #[new]
+    #[pyo3(signature = (
+        robot, frames=None, tools=None, positioner=None, collision_objects=None, name="cell"
+    ))]
+    fn new(
+        py: Python<'_>,
+        robot: Py<Robot>,
+        frames: Option<Py<Frames>>,
+        tools: Option<Bound<'_, PyDict>>,
+        positioner: Option<Py<Positioner>>,
+        collision_objects: Option<Vec<Bound<'_, PyDict>>>,
+        name: &str,
+    ) -> PyResult<Self> {
+        let frames = match frames {
+            Some(frames) => frames,
+            None => Py::new(py, Frames::with([]))?,
+        };
+        let tools = match tools {
+            Some(tools) => tools
+                .iter()
+                .map(|(name, tool)| {
+                    let tool: ToolConfig = tool.extract()?;
+                    let tcp = (tool.translation, tool.rotation);
+                    validation::check_pose(&tcp)?;
+                    Ok((name.extract()?, to_isometry(tcp.0, tcp.1)))
+                })
+                .collect::<PyResult<_>>()?,
+            None => Vec::new(),
+        };
+        let collision_objects = collision_objects
+            .unwrap_or_default()
+            .iter()
+            .map(|object| {
+                let object = CollisionObject::from_dict(object)?;
+                frames.get().between(&object.frame, WORLD)?;
+                Ok(object)
+            })
+            .collect::<PyResult<_>>()?;
+        Ok(Cell {
+            name: name.to_string(),
+            robot,
+            frames,
+            tools,
+            positioner,
+            collision_objects,
+        })
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[getter]
+    fn robot(&self, py: Python<'_>) -> Py<Robot> {
+        self.robot.clone_ref(py)
+    }
+
+    /// The frames registry of the work objects
+    #[getter]
+    fn frames(&self, py: Python<'_>) -> Py<Frames> {
+        self.frames.clone_ref(py)
+    }
+
+    /// The tools by name
+    #[getter]
+    fn tools<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (name, tcp) in &self.tools {
+            let (translation, rotation) = from_isometry(tcp);
+            dict.set_item(name, ToolConfig::new(translation, rotation))?;
+        }
+        Ok(dict)
+    }
+
+    #[getter]
+    fn positioner(&self, py: Python<'_>) -> Option<Py<Positioner>> {
+        self.positioner
+            .as_ref()
+            .map(|positioner| positioner.clone_ref(py))
+    }
+
+    #[getter]
+    fn collision_objects<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.collision_objects
+            .iter()
+            .map(|object| object.to_dict(py))
+            .collect()
+    }
+
+    /// The cell as a dict of plain values: format and version of the layout, name, robot
+    /// as `Robot.to_dict` returns it, tools as their TCP in the flange frame, frames other
+    /// than the world as their poses in the world frame, external_axes as
+    /// `Positioner.to_dict` returns it and the collision objects
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let frames = self.frames.get();
+        let frame_poses = PyDict::new(py);
+        for name in frames.names().iter().filter(|name| *name != WORLD) {
+            frame_poses.set_item(name, from_isometry(&frames.between(name, WORLD)?))?;
+        }
+        let tools = PyDict::new(py);
+        for (name, tcp) in &self.tools {
+            tools.set_item(name, from_isometry(tcp))?;
+        }
+        let dict = PyDict::new(py);
+        dict.set_item("format", FORMAT)?;
+        dict.set_item("version", VERSION)?;
+        dict.set_item("name", &self.name)?;
+        dict.set_item("robot", self.robot.get().to_dict(py)?)?;
+        dict.set_item("tools", tools)?;
+        dict.set_item("frames", frame_poses)?;
+        dict.set_item(
+            "external_axes",
+            self.positioner
+                .as_ref()
+                .map(|positioner| positioner.get().to_dict(py))
+                .transpose()?,
+        )?;
+        dict.set_item("collision_objects", self.collision_objects(py)?)?;
+        Ok(dict)
+    }
+
+    /// Cell from a dict as `to_dict` returns it
+    #[staticmethod]
+    fn from_dict(py: Python<'_>, data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(
+            data,
+            &[
+                "format",
+                "version",
+                "name",
+                "robot",
+                "tools",
+                "frames",
+                "external_axes",
+                "collision_objects",
+            ],
+            "Cell",
+        )?;
+        if let Some(format) = dict::optional::<String>(data, "format")? {
+            if format != FORMAT {
+                return Err(PyValueError::new_err(format!(
+                    "Not a workcell: the format is '{}', expected '{}'",
+                    format, FORMAT
+                )));
+            }
+        }
+        let version: u32 = dict::optional(data, "version")?.unwrap_or(VERSION);
+        if version > VERSION {
+            return Err(PyValueError::new_err(format!(
+                "The workcell has version {}, this release reads up to version {}",
+                version, VERSION
+            )));
+        }
+        let robot: Bound<'_, PyDict> = dict::required(data, "robot", "Cell")?;
+        let poses = |key: &str| -> PyResult<Vec<(String, PyPose)>> {
+            match dict::optional::<Bound<'_, PyDict>>(data, key)? {
+                Some(poses) => poses
+                    .iter()
+                    .map(|(name, pose)| Ok((name.extract()?, extract_pose(&pose)?)))
+                    .collect(),
+                None => Ok(Vec::new()),
+            }
+        };
+        let frames = Frames::with([]);
+        for (name, pose) in poses("frames")? {
+            frames.add(&name, pose, WORLD)?;
+        }
+        let tools = PyDict::new(py);
+        for (name, (translation, rotation)) in poses("tools")? {
+            tools.set_item(name, ToolConfig::new(translation, rotation))?;
+        }
+        let positioner = dict::optional::<Bound<'_, PyDict>>(data, "external_axes")?
+            .map(|positioner| Py::new(py, Positioner::from_dict(&positioner)?))
+            .transpose()?;
+        Cell::new(
+            py,
+            Py::new(py, Robot::from_dict(&robot)?)?,
+            Some(Py::new(py, frames)?),
+            Some(tools),
+            positioner,
+            dict::optional(data, "collision_objects")?,
+            &dict::optional::<String>(data, "name")?.unwrap_or_else(|| "cell".to_string()),
+        )
+    }
+
+    /// Reads a cell from the JSON file at `path`
+    #[staticmethod]
+    fn load(py: Python<'_>, path: &str) -> PyResult<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let data = py.import("json")?.call_method1("loads", (text,))?;
+        let data = data
+            .cast::<PyDict>()
+            .map_err(|_| PyValueError::new_err(format!("{} does not hold a workcell", path)))?;
+        Cell::from_dict(py, data)
+    }
+
+    /// Writes the cell to `path` as JSON
+    fn save(&self, py: Python<'_>, path: &str) -> PyResult<()> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("indent", 2)?;
+        let text: String = py
+            .import("json")?
+            .call_method("dumps", (self.to_dict(py)?,), Some(&kwargs))?
+            .extract()?;
+        std::fs::write(path, text + "\n")?;
+        Ok(())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Cell('{}', {} tools, {} frames, {} collision objects)",
+            self.name,
+            self.tools.len(),
+            self.frames.get().names().len() - 1,
+            self.collision_objects.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collision_shapes() {
+        let cylinder = Shape::new("cylinder", &[0.1, 0.5]).unwrap();
+        assert_eq!(
+            cylinder,
+            Shape::Cylinder {
+                radius: 0.1,
+                length: 0.5
+            }
+        );
+        assert_eq!(Shape::new(cylinder.name(), &cylinder.size()), Ok(cylinder));
+        assert_eq!(
+            Shape::new("box", &[1.0, 2.0, 0.5]).unwrap().size(),
+            [1.0, 2.0, 0.5]
+        );
+        assert!(Shape::new("box", &[1.0, 2.0]).is_err());
+        assert!(Shape::new("sphere", &[-0.1]).is_err());
+        assert!(Shape::new("cone", &[0.1]).is_err());
+    }
+}
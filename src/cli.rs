@@ -0,0 +1,428 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::path::Path;
+
+use crate::batch::ThreadPool;
+use crate::columns::{JointTable, PoseTable};
+use crate::kinematic_model::KinematicModel;
+use crate::models;
+use crate::ordering::InverseOptions;
+use crate::rows::{Rows, JOINT_COLUMNS, POSE_COLUMNS};
+use crate::validation;
+use crate::{BaseConfig, Robot, ToolConfig};
+
+const USAGE: &str = "\
+usage: opw-kin solve --model MODEL --in POSES --out JOINTS [options]
+       opw-kin forward --model MODEL --in JOINTS --out POSES [options]
+
+MODEL is a name from list_models() or a JSON or YAML file with the keys of
+KinematicModel. Tables are CSV files, or Parquet files when pandas is installed.
+Pose columns are x, y, z, qw, qx, qy, qz and joint columns j1..j6, under any
+name in column_aliases().
+
+options:
+  --config CF1,CF4,CF6,CFX    keep only solutions with this ABB axis configuration
+  --base X,Y,Z,QW,QX,QY,QZ    pose of the robot base in the world frame
+  --tool X,Y,Z,QW,QX,QY,QZ    pose of the TCP in the flange frame
+  --units degrees|radians     joint units, degrees by default";
+
+const IDENTITY: [f64; 7] = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    command: String,
+    model: String,
+    input: String,
+    output: String,
+    config: Option<(i32, i32, i32, u8)>,
+    base: [f64; 7],
+    tool: [f64; 7],
+    radians: bool,
+}
+
+fn numbers<const N: usize>(option: &str, text: &str) -> Result<[f64; N], String> {
+    let values: Vec<f64> = text
+        .split(',')
+        .map(|value| value.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("{} expects {} comma separated numbers", option, N))?;
+    values
+        .try_into()
+        .map_err(|_| format!("{} expects {} comma separated numbers", option, N))
+}
+
+fn parse_args(argv: &[String]) -> Result<Args, String> {
+    let (command, options) = argv.split_first().ok_or("missing command")?;
+    if command != "solve" && command != "forward" {
+        return Err(format!("unknown command '{}'", command));
+    }
+    let mut model = None;
+    let mut input = None;
+    let mut output = None;
+    let mut args = Args {
+        command: command.clone(),
+        model: String::new(),
+        input: String::new(),
+        output: String::new(),
+        config: None,
+        base: IDENTITY,
+        tool: IDENTITY,
+        radians: false,
+    };
+    let mut options = options.iter();
+    while let Some(option) = options.next() {
+        let value = options
+            .next()
+            .ok_or_else(|| format!("{} needs a value", option))?;
+        match option.as_str() {
+            "--model" => model = Some(value.clone()),
+            "--in" => input = Some(value.clone()),
+            "--out" => output = Some(value.clone()),
+            "--config" => {
+                let [cf1, cf4, cf6, cfx] = numbers::<4>(option, value)?;
+                if [cf1, cf4, cf6, cfx].iter().any(|cf| cf.fract() != 0.0)
+                    || !(0.0..8.0).contains(&cfx)
+                {
+                    return Err("--config expects integers with CFX from 0 to 7".to_string());
+                }
+                args.config = Some((cf1 as i32, cf4 as i32, cf6 as i32, cfx as u8));
+            }
+            "--base" => args.base = numbers(option, value)?,
+            "--tool" => args.tool = numbers(option, value)?,
+            "--units" => {
+                args.radians = match value.as_str() {
+                    "degrees" => false,
+                    "radians" => true,
+                    _ => return Err("--units expects degrees or radians".to_string()),
+                }
+            }
+            _ => return Err(format!("unknown option '{}'", option)),
+        }
+    }
+    args.model = model.ok_or("--model is required")?;
+    args.input = input.ok_or("--in is required")?;
+    args.output = output.ok_or("--out is required")?;
+    Ok(args)
+}
+
+/// A column of a CSV table, numbers unless a cell is not
+#[derive(Debug, PartialEq)]
+pub enum Column {
+    Numbers(Vec<f64>),
+    Text(Vec<String>),
+}
+
+/// Cells of a CSV line, commas within double quotes belong to the cell and "" is a quote
+fn split_row(line: &str) -> Vec<String> {
+    let mut cells = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cells.last_mut().expect("a cell").push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(String::new()),
+            c => cells.last_mut().expect("a cell").push(c),
+        }
+    }
+    cells
+}
+
+/// Header and columns of a CSV table, empty cells of number columns are NaN
+pub fn parse_csv(text: &str) -> Result<(Vec<String>, Vec<Column>), String> {
+    let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+    let header: Vec<String> = split_row(lines.next().ok_or("the table is empty")?)
+        .iter()
+        .map(|name| name.trim().to_string())
+        .collect();
+    let mut cells = vec![Vec::new(); header.len()];
+    for (i, line) in lines.enumerate() {
+        let row = split_row(line);
+        if row.len() != header.len() {
+            return Err(format!(
+                "row {} has {} cells, the header has {}",
+                i + 1,
+                row.len(),
+                header.len()
+            ));
+        }
+        for (column, cell) in cells.iter_mut().zip(row) {
+            column.push(cell.trim().to_string());
+        }
+    }
+    let columns = cells
+        .into_iter()
+        .map(|column| {
+            column
+                .iter()
+                .map(|cell| {
+                    if cell.is_empty() {
+                        Ok(f64::NAN)
+                    } else {
+                        cell.parse()
+                    }
+                })
+                .collect::<Result<_, _>>()
+                .map_or(Column::Text(column), Column::Numbers)
+        })
+        .collect();
+    Ok((header, columns))
+}
+
+/// Columns of a dict of columns or a DataFrame, numbers unless a cell is not, in which case
+/// every cell becomes its text
+pub fn columns_of(table: &Bound<'_, PyAny>) -> PyResult<(Vec<String>, Vec<Column>)> {
+    let (header, cells): (Vec<String>, Vec<Bound<'_, PyAny>>) =
+        if let Ok(dict) = table.cast::<PyDict>() {
+            dict.iter()
+                .map(|(name, values)| Ok((name.str()?.to_string(), values)))
+                .collect::<PyResult<Vec<_>>>()?
+                .into_iter()
+                .unzip()
+        } else if table.hasattr("columns")? {
+            table
+                .getattr("columns")?
+                .try_iter()?
+                .map(|name| {
+                    let name = name?;
+                    let values = table.get_item(&name)?.call_method0("to_list")?;
+                    Ok((name.str()?.to_string(), values))
+                })
+                .collect::<PyResult<Vec<_>>>()?
+                .into_iter()
+                .unzip()
+        } else {
+            return Err(PyValueError::new_err(
+                "Expected a CSV path, a dict of columns or a DataFrame",
+            ));
+        };
+    let columns = cells
+        .iter()
+        .map(|values| match values.extract::<Vec<Option<f64>>>() {
+            Ok(numbers) => Ok(Column::Numbers(
+                numbers.into_iter().map(|x| x.unwrap_or(f64::NAN)).collect(),
+            )),
+            Err(_) => Ok(Column::Text(
+                values
+                    .try_iter()?
+                    .map(|value| Ok(value?.str()?.to_string()))
+                    .collect::<PyResult<_>>()?,
+            )),
+        })
+        .collect::<PyResult<_>>()?;
+    Ok((header, columns))
+}
+
+fn is_parquet(path: &str) -> bool {
+    path.to_lowercase().ends_with(".parquet")
+}
+
+fn read_table<'py>(py: Python<'py>, path: &str) -> PyResult<Bound<'py, PyAny>> {
+    if is_parquet(path) {
+        return py.import("pandas")?.call_method1("read_parquet", (path,));
+    }
+    let text = std::fs::read_to_string(path)?;
+    let (header, columns) =
+        parse_csv(&text).map_err(|e| PyValueError::new_err(format!("{}: {}", path, e)))?;
+    let table = PyDict::new(py);
+    for (name, column) in header.iter().zip(columns) {
+        match column {
+            Column::Numbers(values) => table.set_item(name, values)?,
+            Column::Text(values) => table.set_item(name, values)?,
+        }
+    }
+    Ok(table.into_any())
+}
+
+fn write_table(py: Python<'_>, path: &str, rows: &Rows<'_>) -> PyResult<()> {
+    if is_parquet(path) {
+        let frame = py
+            .import("pandas")?
+            .call_method1("DataFrame", (rows.clone(),))?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("index", false)?;
+        frame.call_method("to_parquet", (path,), Some(&kwargs))?;
+        return Ok(());
+    }
+    let mut text = String::new();
+    if let Some(first) = rows.first() {
+        let header: Vec<String> = first
+            .keys()
+            .iter()
+            .map(|key| key.str().map(|key| key.to_string()))
+            .collect::<PyResult<_>>()?;
+        text.push_str(&header.join(","));
+        text.push('\n');
+    }
+    for row in rows {
+        let cells: Vec<String> = row
+            .values()
+            .iter()
+            .map(|value| {
+                if value.is_none() {
+                    Ok(String::new())
+                } else {
+                    value.str().map(|value| value.to_string())
+                }
+            })
+            .collect::<PyResult<_>>()?;
+        text.push_str(&cells.join(","));
+        text.push('\n');
+    }
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+/// A model of the built-in library, or a JSON/YAML file with the keys of KinematicModel
+fn load_model(py: Python<'_>, spec: &str) -> PyResult<KinematicModel> {
+    if !Path::new(spec).is_file() {
+        return models::model_by_name(spec);
+    }
+    let text = std::fs::read_to_string(spec)?;
+    let lower = spec.to_lowercase();
+    let data = if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+        py.import("yaml")?.call_method1("safe_load", (text,))?
+    } else {
+        py.import("json")?.call_method1("loads", (text,))?
+    };
+    KinematicModel::from_dict(data.cast::<PyDict>()?)
+}
+
+fn run(py: Python<'_>, args: &Args) -> PyResult<()> {
+    let pose = |values: [f64; 7]| {
+        (
+            [values[0], values[1], values[2]],
+            [values[3], values[4], values[5], values[6]],
+        )
+    };
+    let (base_translation, base_rotation) = pose(args.base);
+    let (tool_translation, tool_rotation) = pose(args.tool);
+    let mut robot = Robot::new(
+        load_model(py, &args.model)?,
+        BaseConfig::new(base_translation, base_rotation),
+        ToolConfig::new(tool_translation, tool_rotation),
+    )?;
+    robot.radians = args.radians;
+    let table = read_table(py, &args.input)?;
+    let pool = ThreadPool::default();
+
+    let rows: Rows = if args.command == "solve" {
+        let PoseTable(poses) = table.extract()?;
+        let options = InverseOptions::default();
+        let solved = py.detach(|| {
+            pool.map(&poses, |&pose| {
+                robot.solve_row_in(pose, None, &options, args.config)
+            })
+        });
+        let unreachable = solved.iter().filter(|row| row.status() != "ok").count();
+        eprintln!(
+            "Solved {} poses, {} without a solution",
+            solved.len(),
+            unreachable
+        );
+        solved
+            .iter()
+            .map(|row| row.to_dict(py))
+            .collect::<PyResult<_>>()?
+    } else {
+        let JointTable(joints) = table.extract()?;
+        let poses =
+            py.detach(|| pool.map(&joints, |&joint_set| robot.forward(joint_set, None).ok()));
+        joints
+            .iter()
+            .zip(poses)
+            .map(|(joint_set, pose)| {
+                let row = PyDict::new(py);
+                for (name, value) in JOINT_COLUMNS.iter().zip(joint_set) {
+                    row.set_item(name, value)?;
+                }
+                let values = pose
+                    .map(|(translation, rotation)| [translation.as_slice(), &rotation].concat());
+                for (i, name) in POSE_COLUMNS.iter().enumerate() {
+                    row.set_item(name, values.as_ref().map(|values| values[i]))?;
+                }
+                row.set_item(
+                    "status",
+                    if validation::is_finite_joints(joint_set) {
+                        "ok"
+                    } else {
+                        "invalid"
+                    },
+                )?;
+                Ok(row)
+            })
+            .collect::<PyResult<_>>()?
+    };
+    write_table(py, &args.output, &rows)
+}
+
+/// Entry point of the `opw-kin` command, solving tables of poses or joints from files.
+/// Reads the arguments from `sys.argv` unless given, returns the exit status.
+#[pyfunction]
+#[pyo3(signature = (argv=None))]
+pub fn cli_main(py: Python<'_>, argv: Option<Vec<String>>) -> PyResult<i32> {
+    let argv = match argv {
+        Some(argv) => argv,
+        None => {
+            let argv: Vec<String> = py.import("sys")?.getattr("argv")?.extract()?;
+            argv.into_iter().skip(1).collect()
+        }
+    };
+    if argv.is_empty() || argv.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return Ok(if argv.is_empty() { 2 } else { 0 });
+    }
+    let args = match parse_args(&argv) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{}\nopw-kin: error: {}", USAGE, message);
+            return Ok(2);
+        }
+    };
+    match run(py, &args) {
+        Ok(()) => Ok(0),
+        Err(error) => {
+            eprintln!("opw-kin: error: {}", error);
+            Ok(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(line: &str) -> Vec<String> {
+        line.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = parse_args(&argv(
+            "solve --model abb_irb1660.yaml --in poses.parquet --out joints.parquet --config 0,0,0,5",
+        ))
+        .unwrap();
+        assert_eq!(args.config, Some((0, 0, 0, 5)));
+        assert_eq!(args.base, IDENTITY);
+        assert!(parse_args(&argv("solve --model x --in a.csv")).is_err());
+        assert!(parse_args(&argv("solve --model x --in a --out b --config 0,0,0,9")).is_err());
+        assert!(parse_args(&argv("jog --model x")).is_err());
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let (header, columns) = parse_csv("x,y\n1,2\n3,\n").unwrap();
+        assert_eq!(header, vec!["x", "y"]);
+        assert_eq!(columns[0], Column::Numbers(vec![1.0, 3.0]));
+        assert!(matches!(&columns[1], Column::Numbers(y) if y[1].is_nan()));
+        assert!(parse_csv("x,y\n1\n").is_err());
+        let (_, columns) = parse_csv("t,name\n0.5,\"[a, \"\"b\"\"]\"\n").unwrap();
+        assert_eq!(columns[1], Column::Text(vec!["[a, \"b\"]".to_string()]));
+        let (_, columns) = parse_csv("x,status\n1,ok\n").unwrap();
+        assert_eq!(columns[1], Column::Text(vec!["ok".to_string()]));
+    }
+}
@@ -0,0 +1,322 @@
+use pyo3::conversion::FromPyObjectOwned;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::pose::PyPose;
+use crate::rows::{JOINT_COLUMNS, POSE_COLUMNS};
+
+/// Names under which each pose column is found in tables, compared case-insensitively
+const POSE_ALIASES: [&[&str]; 7] = [
+    &["x", "pos_x", "position_x", "px", "tx"],
+    &["y", "pos_y", "position_y", "py", "ty"],
+    &["z", "pos_z", "position_z", "pz", "tz"],
+    &["qw", "w", "quat_w", "orientation_w", "q0"],
+    &["qx", "quat_x", "orientation_x", "q1"],
+    &["qy", "quat_y", "orientation_y", "q2"],
+    &["qz", "quat_z", "orientation_z", "q3"],
+];
+
+/// Names under which each joint column is found in tables, compared case-insensitively
+const JOINT_ALIASES: [&[&str]; 6] = [
+    &["j1", "joint1", "joint_1", "a1", "axis1", "axis_1", "rax_1"],
+    &["j2", "joint2", "joint_2", "a2", "axis2", "axis_2", "rax_2"],
+    &["j3", "joint3", "joint_3", "a3", "axis3", "axis_3", "rax_3"],
+    &["j4", "joint4", "joint_4", "a4", "axis4", "axis_4", "rax_4"],
+    &["j5", "joint5", "joint_5", "a5", "axis5", "axis_5", "rax_5"],
+    &["j6", "joint6", "joint_6", "a6", "axis6", "axis_6", "rax_6"],
+];
+
+/// Aliases added with `add_column_alias`, as (alias, column), the alias in lowercase
+static CUSTOM_ALIASES: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Built-in and custom aliases of a column
+fn aliases(column: &str, builtin: &[&str]) -> Vec<String> {
+    let custom = CUSTOM_ALIASES.read().unwrap_or_else(|e| e.into_inner());
+    builtin
+        .iter()
+        .map(|alias| alias.to_string())
+        .chain(
+            custom
+                .iter()
+                .filter(|(_, target)| target == column)
+                .map(|(alias, _)| alias.clone()),
+        )
+        .collect()
+}
+
+/// Finds the column of a table that provides each of `columns`
+fn resolve<const N: usize>(
+    found: &[String],
+    columns: &[&str; N],
+    builtin: &[&[&str]; N],
+) -> PyResult<[String; N]> {
+    let mut resolved: [String; N] = std::array::from_fn(|_| String::new());
+    for (i, column) in columns.iter().enumerate() {
+        let aliases = aliases(column, builtin[i]);
+        let matches: Vec<&String> = found
+            .iter()
+            .filter(|name| aliases.contains(&normalize(name)))
+            .collect();
+        match matches.as_slice() {
+            [name] => resolved[i] = name.to_string(),
+            [] => {
+                return Err(PyValueError::new_err(format!(
+                    "No column for '{}' (accepted: {}). Expected columns {}, found {}",
+                    column,
+                    aliases.join(", "),
+                    columns.join(", "),
+                    found.join(", ")
+                )))
+            }
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Columns {:?} all match '{}', keep only one of them",
+                    matches, column
+                )))
+            }
+        }
+    }
+    Ok(resolved)
+}
+
+/// Values of a column, None (missing) becomes NaN
+fn values(column: &Bound<'_, PyAny>) -> PyResult<Vec<f64>> {
+    Ok(column
+        .extract::<Vec<Option<f64>>>()?
+        .into_iter()
+        .map(|value| value.unwrap_or(f64::NAN))
+        .collect())
+}
+
+fn names(keys: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
+    keys.try_iter()?
+        .map(|key| Ok(key?.str()?.to_string()))
+        .collect()
+}
+
+/// Reads a table given as a DataFrame (anything with `columns` whose columns have
+/// `to_list`), a dict of columns or a list of row dicts. Returns None for other inputs.
+fn table<const N: usize>(
+    obj: &Bound<'_, PyAny>,
+    columns: &[&str; N],
+    builtin: &[&[&str]; N],
+) -> PyResult<Option<Vec<[f64; N]>>> {
+    let data: Vec<Vec<f64>> = if let Ok(dict) = obj.cast::<PyDict>() {
+        let found = names(dict.keys().as_any())?;
+        resolve(&found, columns, builtin)?
+            .iter()
+            .map(|name| values(&dict.get_item(name)?.expect("resolved key exists")))
+            .collect::<PyResult<_>>()?
+    } else if obj.hasattr("columns")? {
+        let found = names(&obj.getattr("columns")?)?;
+        resolve(&found, columns, builtin)?
+            .iter()
+            .map(|name| values(&obj.get_item(name)?.call_method0("to_list")?))
+            .collect::<PyResult<_>>()?
+    } else {
+        let Ok(rows) = obj.extract::<Vec<Bound<'_, PyDict>>>() else {
+            return Ok(None);
+        };
+        let Some(first) = rows.first() else {
+            return Ok(None);
+        };
+        let resolved = resolve(&names(first.keys().as_any())?, columns, builtin)?;
+        let mut data = vec![Vec::with_capacity(rows.len()); N];
+        for (i, row) in rows.iter().enumerate() {
+            for (column, name) in data.iter_mut().zip(resolved.iter()) {
+                let value = row.get_item(name)?.ok_or_else(|| {
+                    PyValueError::new_err(format!("Row {} has no column '{}'", i, name))
+                })?;
+                column.push(value.extract::<Option<f64>>()?.unwrap_or(f64::NAN));
+            }
+        }
+        data
+    };
+    let rows = data.first().map_or(0, Vec::len);
+    if data.iter().any(|column| column.len() != rows) {
+        return Err(PyValueError::new_err("Columns have different lengths"));
+    }
+    Ok(Some(
+        (0..rows)
+            .map(|row| std::array::from_fn(|i| data[i][row]))
+            .collect(),
+    ))
+}
+
+/// A cell of a table, None where missing or NaN, as pandas fills missing values
+fn cell<'py, T: FromPyObjectOwned<'py>>(value: &Bound<'py, PyAny>) -> PyResult<Option<T>> {
+    if value.is_none() || value.extract::<f64>().is_ok_and(f64::is_nan) {
+        return Ok(None);
+    }
+    value.extract().map(Some).map_err(Into::into)
+}
+
+/// Values of the first of `names` (compared case-insensitively) that a table, given as for
+/// the pose and joint columns, has. None if it has none of them.
+pub fn optional_column<'py, T: FromPyObjectOwned<'py>>(
+    obj: &Bound<'py, PyAny>,
+    names: &[&str],
+) -> PyResult<Option<Vec<Option<T>>>> {
+    let pick = |found: Vec<String>| {
+        names.iter().find_map(|name| {
+            found
+                .iter()
+                .find(|column| normalize(column) == *name)
+                .cloned()
+        })
+    };
+    let column = if let Ok(dict) = obj.cast::<PyDict>() {
+        match pick(self::names(dict.keys().as_any())?) {
+            Some(name) => dict.get_item(name)?.expect("picked key exists"),
+            None => return Ok(None),
+        }
+    } else if obj.hasattr("columns")? {
+        match pick(self::names(&obj.getattr("columns")?)?) {
+            Some(name) => obj.get_item(name)?.call_method0("to_list")?,
+            None => return Ok(None),
+        }
+    } else {
+        let rows: Vec<Bound<'py, PyDict>> = obj.extract()?;
+        let Some(first) = rows.first() else {
+            return Ok(None);
+        };
+        let Some(name) = pick(self::names(first.keys().as_any())?) else {
+            return Ok(None);
+        };
+        return rows
+            .iter()
+            .map(|row| match row.get_item(&name)? {
+                Some(value) => cell(&value),
+                None => Ok(None),
+            })
+            .collect::<PyResult<_>>()
+            .map(Some);
+    };
+    column
+        .try_iter()?
+        .map(|value| cell(&value?))
+        .collect::<PyResult<_>>()
+        .map(Some)
+}
+
+/// Poses given as (translation, rotation) tuples or as a table with pose columns
+pub struct PoseTable(pub Vec<PyPose>);
+
+impl<'a, 'py> FromPyObject<'a, 'py> for PoseTable {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        match table(&obj, &POSE_COLUMNS, &POSE_ALIASES)? {
+            Some(rows) => Ok(PoseTable(
+                rows.iter()
+                    .map(|[x, y, z, qw, qx, qy, qz]| ([*x, *y, *z], [*qw, *qx, *qy, *qz]))
+                    .collect(),
+            )),
+            None => Ok(PoseTable(obj.extract()?)),
+        }
+    }
+}
+
+/// Quaternions given as (w, x, y, z) sequences or as a table with the columns qw, qx, qy, qz
+pub struct QuaternionTable(pub Vec<[f64; 4]>);
+
+impl<'a, 'py> FromPyObject<'a, 'py> for QuaternionTable {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        let columns = ["qw", "qx", "qy", "qz"];
+        let aliases = [
+            POSE_ALIASES[3],
+            POSE_ALIASES[4],
+            POSE_ALIASES[5],
+            POSE_ALIASES[6],
+        ];
+        match table(&obj, &columns, &aliases)? {
+            Some(rows) => Ok(QuaternionTable(rows)),
+            None => Ok(QuaternionTable(obj.extract()?)),
+        }
+    }
+}
+
+/// Joint sets given as sequences of six values or as a table with joint columns
+pub struct JointTable(pub Vec<[f64; 6]>);
+
+impl<'a, 'py> FromPyObject<'a, 'py> for JointTable {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        match table(&obj, &JOINT_COLUMNS, &JOINT_ALIASES)? {
+            Some(rows) => Ok(JointTable(rows)),
+            None => Ok(JointTable(obj.extract()?)),
+        }
+    }
+}
+
+/// Makes batch inputs accept `alias` (case-insensitive) as the name of `column`, one of
+/// x, y, z, qw, qx, qy, qz or j1..j6
+#[pyfunction]
+pub fn add_column_alias(alias: &str, column: &str) -> PyResult<()> {
+    if !POSE_COLUMNS.contains(&column) && !JOINT_COLUMNS.contains(&column) {
+        return Err(PyValueError::new_err(format!(
+            "Unknown column '{}', expected one of {} or {}",
+            column,
+            POSE_COLUMNS.join(", "),
+            JOINT_COLUMNS.join(", ")
+        )));
+    }
+    let mut custom = CUSTOM_ALIASES.write().unwrap_or_else(|e| e.into_inner());
+    let alias = normalize(alias);
+    custom.retain(|(existing, _)| *existing != alias);
+    custom.push((alias, column.to_string()));
+    Ok(())
+}
+
+/// The accepted names of every pose and joint column
+#[pyfunction]
+pub fn column_aliases() -> HashMap<String, Vec<String>> {
+    POSE_COLUMNS
+        .iter()
+        .zip(POSE_ALIASES.iter())
+        .chain(JOINT_COLUMNS.iter().zip(JOINT_ALIASES.iter()))
+        .map(|(column, builtin)| (column.to_string(), aliases(column, builtin)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resolve_aliases_and_case() {
+        let names = found(&["Pos_X", "Y", "z", "QW", "quat_x", "qy", "qz", "extra"]);
+        let resolved = resolve(&names, &POSE_COLUMNS, &POSE_ALIASES).unwrap();
+        assert_eq!(resolved[0], "Pos_X");
+        assert_eq!(resolved[3], "QW");
+
+        let missing = found(&["x", "y", "z", "qw", "qx", "qy"]);
+        assert!(resolve(&missing, &POSE_COLUMNS, &POSE_ALIASES).is_err());
+        let ambiguous = found(&["x", "X", "y", "z", "qw", "qx", "qy", "qz"]);
+        assert!(resolve(&ambiguous, &POSE_COLUMNS, &POSE_ALIASES).is_err());
+    }
+
+    #[test]
+    fn test_custom_alias() {
+        let names = found(&["Axis One", "j2", "j3", "j4", "j5", "j6"]);
+        assert!(resolve(&names, &JOINT_COLUMNS, &JOINT_ALIASES).is_err());
+        add_column_alias("axis one", "j1").unwrap();
+        let resolved = resolve(&names, &JOINT_COLUMNS, &JOINT_ALIASES).unwrap();
+        assert_eq!(resolved[0], "Axis One");
+        assert!(add_column_alias("speed", "v").is_err());
+    }
+}
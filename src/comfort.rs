@@ -0,0 +1,202 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::f64::consts::PI;
+
+use crate::dict;
+
+/// Weights of the posture cost that scores how comfortable a set of joints is, 0 at best
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Comfort {
+    /// Preferred posture, the default seed or the zero position without it
+    pub preferred: Option<Joints>,
+    /// Weight of the mean squared distance from the preferred posture, per joint relative to
+    /// half its range (180 degrees without limits)
+    pub posture_weight: f64,
+    /// Weight of the squared depth of the worst joint in the zone before its limits
+    pub limit_weight: f64,
+    /// Weight of the squared extension of the arm, wrist center reach over maximum reach
+    pub extension_weight: f64,
+    /// Share of each joint range at either end in which the limit term rises from 0 to 1
+    pub limit_zone: f64,
+}
+
+impl Default for Comfort {
+    fn default() -> Self {
+        Comfort {
+            preferred: None,
+            posture_weight: 1.0,
+            limit_weight: 1.0,
+            extension_weight: 1.0,
+            limit_zone: 0.1,
+        }
+    }
+}
+
+/// Terms of the posture cost of one set of joints, each weighted into `total`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Score {
+    pub posture: f64,
+    pub limits: f64,
+    pub extension: f64,
+    pub total: f64,
+}
+
+impl Comfort {
+    pub fn check(&self) -> PyResult<()> {
+        let weights = [
+            self.posture_weight,
+            self.limit_weight,
+            self.extension_weight,
+        ];
+        if !weights
+            .iter()
+            .all(|weight| weight.is_finite() && *weight >= 0.0)
+        {
+            return Err(PyValueError::new_err(format!(
+                "Comfort weights must be finite and not negative, got {:?}",
+                weights
+            )));
+        }
+        if !(self.limit_zone > 0.0 && self.limit_zone <= 0.5) {
+            return Err(PyValueError::new_err(format!(
+                "limit_zone must lie in (0, 0.5], got {}",
+                self.limit_zone
+            )));
+        }
+        if let Some(preferred) = &self.preferred {
+            crate::validation::check_joints("preferred", preferred)?;
+        }
+        Ok(())
+    }
+
+    /// Scores controller joints (radians) against `preferred`, with the joint `limits` of
+    /// the robot if it has them. `extension` is the arm extension of the true joint angles,
+    /// see `KinematicModel::extension`.
+    pub fn score(
+        &self,
+        joints: &Joints,
+        preferred: &Joints,
+        limits: Option<&[(f64, f64); 6]>,
+        extension: f64,
+    ) -> Score {
+        let half_range = |k: usize| limits.map_or(PI, |limits| (limits[k].1 - limits[k].0) / 2.0);
+        let posture = (0..6)
+            .map(|k| ((joints[k] - preferred[k]) / half_range(k).max(1e-9)).powi(2))
+            .sum::<f64>()
+            / 6.0;
+        let limits = limits.map_or(0.0, |limits| {
+            joints
+                .iter()
+                .zip(limits)
+                .map(|(joint, (min, max))| {
+                    let zone = self.limit_zone * (max - min);
+                    let margin = (joint - min).min(max - joint);
+                    match zone > 0.0 {
+                        true => (1.0 - margin / zone).max(0.0).powi(2),
+                        false => 0.0,
+                    }
+                })
+                .fold(0.0, f64::max)
+        });
+        let extension = extension.powi(2);
+        Score {
+            posture,
+            limits,
+            extension,
+            total: self.posture_weight * posture
+                + self.limit_weight * limits
+                + self.extension_weight * extension,
+        }
+    }
+
+    /// The settings as a dict, the preferred posture converted by `joints_out`
+    pub fn to_dict<'py>(
+        self,
+        py: Python<'py>,
+        joints_out: impl Fn(Joints) -> Joints,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("preferred", self.preferred.map(joints_out))?;
+        dict.set_item("posture_weight", self.posture_weight)?;
+        dict.set_item("limit_weight", self.limit_weight)?;
+        dict.set_item("extension_weight", self.extension_weight)?;
+        dict.set_item("limit_zone", self.limit_zone)?;
+        Ok(dict)
+    }
+
+    /// Settings from a dict as produced by `to_dict`, the preferred posture as given
+    pub fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(
+            data,
+            &[
+                "preferred",
+                "posture_weight",
+                "limit_weight",
+                "extension_weight",
+                "limit_zone",
+            ],
+            "comfort",
+        )?;
+        let default = Comfort::default();
+        Ok(Comfort {
+            preferred: dict::optional(data, "preferred")?,
+            posture_weight: dict::optional(data, "posture_weight")?
+                .unwrap_or(default.posture_weight),
+            limit_weight: dict::optional(data, "limit_weight")?.unwrap_or(default.limit_weight),
+            extension_weight: dict::optional(data, "extension_weight")?
+                .unwrap_or(default.extension_weight),
+            limit_zone: dict::optional(data, "limit_zone")?.unwrap_or(default.limit_zone),
+        })
+    }
+}
+
+impl Score {
+    pub fn to_dict<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("posture", self.posture)?;
+        dict.set_item("limits", self.limits)?;
+        dict.set_item("extension", self.extension)?;
+        dict.set_item("comfort", self.total)?;
+        Ok(dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+
+    #[test]
+    fn test_comfort_score() {
+        let comfort = Comfort::default();
+        let preferred = [0.0, 0.3, 0.2, 0.0, 0.8, 0.0];
+        let limits = [(-PI, PI); 6];
+        let extension = |joints: &Joints| ABB_1660.extension(joints);
+        let at_rest = comfort.score(&preferred, &preferred, Some(&limits), extension(&preferred));
+        assert_eq!((at_rest.posture, at_rest.limits), (0.0, 0.0));
+        assert!(at_rest.extension > 0.0 && at_rest.extension < 1.0);
+
+        // J1 half way into the zone before its limit
+        let mut near_limit = preferred;
+        near_limit[0] = PI - 0.05 * 2.0 * PI;
+        let score = comfort.score(
+            &near_limit,
+            &preferred,
+            Some(&limits),
+            extension(&near_limit),
+        );
+        assert!((score.limits - 0.25).abs() < 1e-12);
+        assert!(score.total > at_rest.total);
+        let unlimited = comfort.score(&near_limit, &preferred, None, extension(&near_limit));
+        assert_eq!(unlimited.limits, 0.0);
+
+        assert!(Comfort {
+            limit_zone: 0.0,
+            ..comfort
+        }
+        .check()
+        .is_err());
+    }
+}
@@ -0,0 +1,201 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+use crate::batch::ThreadPool;
+use crate::columns::PoseTable;
+use crate::kinematic_model::KinematicModel;
+use crate::ordering::SolutionOrder;
+use crate::pose::PyPose;
+use crate::validation::{self, InvalidPolicy};
+use crate::{BaseConfig, Robot, ToolConfig};
+
+/// A robot to compare: a configured Robot, a kinematic model or the name of a built-in model
+pub enum Candidate {
+    Robot(Py<Robot>),
+    Model(KinematicModel),
+    Preset(String),
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for Candidate {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if let Ok(robot) = obj.cast::<Robot>() {
+            return Ok(Candidate::Robot(robot.to_owned().unbind()));
+        }
+        if let Ok(model) = obj.extract::<KinematicModel>() {
+            return Ok(Candidate::Model(model));
+        }
+        match obj.extract::<String>() {
+            Ok(name) => Ok(Candidate::Preset(name)),
+            Err(_) => Err(PyValueError::new_err(format!(
+                "Expected a Robot, KinematicModel or model name to compare, got {}",
+                obj.get_type().name()?
+            ))),
+        }
+    }
+}
+
+/// Reachability of the poses by one robot: per pose the smallest distance (radians) of any
+/// joint of the best solution to its limits, infinite without joint limits, or None where
+/// the pose is out of reach
+pub fn margins(robot: &Robot, poses: &[PyPose], pool: &ThreadPool) -> Vec<Option<f64>> {
+    let options = robot.inverse_options(SolutionOrder::Distance, None, true, None);
+    pool.map(poses, |&pose| {
+        let solutions = robot.solve_radians(pose, None, &options);
+        solutions.first().map(|joints| limit_margin(robot, joints))
+    })
+}
+
+fn limit_margin(robot: &Robot, joints: &Joints) -> f64 {
+    robot.joint_limits.map_or(f64::INFINITY, |limits| {
+        joints
+            .iter()
+            .zip(limits)
+            .map(|(joint, (min, max))| (joint - min).min(max - joint))
+            .fold(f64::INFINITY, f64::min)
+    })
+}
+
+/// Solves the same poses with several candidate robots and summarizes how well each
+/// reaches them, for robot selection studies. Candidates are Robots, KinematicModels or
+/// names of built-in models (see list_models()); models are placed with `base_config` and
+/// `tool_config`. Returns one row per robot with its name, the number of poses, how many it
+/// reaches and their fraction, the indices of the unreachable poses and, for robots with
+/// joint limits, the smallest and mean distance of any joint to its limits over the
+/// reached poses, in the joint units of the robot. Poses are solved in parallel as set by
+/// `n_threads`, `chunk_size` and `pool`.
+#[pyfunction]
+#[pyo3(signature = (
+    poses,
+    robots,
+    base_config=None,
+    tool_config=None,
+    names=None,
+    n_threads=None,
+    chunk_size=None,
+    pool=None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn compare_robots<'py>(
+    py: Python<'py>,
+    poses: PoseTable,
+    robots: Vec<Candidate>,
+    base_config: Option<BaseConfig>,
+    tool_config: Option<ToolConfig>,
+    names: Option<Vec<String>>,
+    n_threads: Option<usize>,
+    chunk_size: Option<usize>,
+    pool: Option<ThreadPool>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    if let Some(names) = &names {
+        if names.len() != robots.len() {
+            return Err(PyValueError::new_err(format!(
+                "Got {} names for {} robots",
+                names.len(),
+                robots.len()
+            )));
+        }
+    }
+    let pool = ThreadPool::resolve(pool.as_ref(), n_threads, chunk_size)?;
+    let poses = validation::screen(poses.0, validation::is_finite_pose, InvalidPolicy::Raise)?;
+    let base_config = base_config.unwrap_or(BaseConfig::new([0.0; 3], [1.0, 0.0, 0.0, 0.0]));
+    let tool_config = tool_config.unwrap_or(ToolConfig::new([0.0; 3], [1.0, 0.0, 0.0, 0.0]));
+    let place = |model| Robot::new(model, base_config.clone(), tool_config.clone());
+
+    let mut rows = Vec::with_capacity(robots.len());
+    for (i, candidate) in robots.into_iter().enumerate() {
+        let (name, robot) = match candidate {
+            Candidate::Robot(robot) => (format!("robot {}", i), robot),
+            Candidate::Model(model) => (format!("model {}", i), Py::new(py, place(model)?)?),
+            Candidate::Preset(name) => {
+                let robot = Py::new(py, place(KinematicModel::from_model(&name)?)?)?;
+                (name, robot)
+            }
+        };
+        let robot = robot.get();
+        let margins = py.detach(|| margins(robot, &poses, &pool));
+
+        let reached: Vec<f64> = margins.iter().flatten().copied().collect();
+        let unreachable: Vec<usize> = (0..margins.len())
+            .filter(|&k| margins[k].is_none())
+            .collect();
+        let limited = robot.joint_limits.is_some() && !reached.is_empty();
+        let row = PyDict::new(py);
+        row.set_item(
+            "name",
+            names.as_ref().map_or(name, |names| names[i].clone()),
+        )?;
+        row.set_item("poses", poses.len())?;
+        row.set_item("reachable", reached.len())?;
+        row.set_item(
+            "reachable_fraction",
+            reached.len() as f64 / poses.len().max(1) as f64,
+        )?;
+        row.set_item("unreachable_rows", unreachable)?;
+        row.set_item(
+            "min_limit_margin",
+            limited.then(|| {
+                robot.joint_out(reached.iter().copied().fold(f64::INFINITY, f64::min), None)
+            }),
+        )?;
+        row.set_item(
+            "mean_limit_margin",
+            limited
+                .then(|| robot.joint_out(reached.iter().sum::<f64>() / reached.len() as f64, None)),
+        )?;
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::abb_1660;
+
+    #[test]
+    fn test_compare_robots() {
+        Python::initialize();
+        Python::attach(|py| {
+            let near = ([1.0, 0.2, 0.8], [0.0, 1.0, 0.0, 0.0]);
+            let far = ([1.8, 0.0, 0.8], [0.0, 1.0, 0.0, 0.0]);
+            let mut limited = abb_1660();
+            limited.joint_limits = Some([(-3.0, 3.0); 6]);
+            let robots = vec![
+                Candidate::Robot(Py::new(py, limited).unwrap()),
+                Candidate::Preset("IRB 4600-60/2.05".to_string()),
+            ];
+            let rows = compare_robots(
+                py,
+                PoseTable(vec![near, far]),
+                robots,
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )
+            .unwrap();
+            let get = |row: usize, key: &str| rows[row].get_item(key).unwrap().unwrap();
+
+            assert_eq!(get(0, "reachable").extract::<usize>().unwrap(), 1);
+            assert_eq!(
+                get(0, "unreachable_rows").extract::<Vec<usize>>().unwrap(),
+                [1]
+            );
+            let margin: f64 = get(0, "min_limit_margin").extract().unwrap();
+            assert!(margin > 0.0 && margin < 3.0_f64.to_degrees());
+            // The larger robot reaches both poses and has no joint limits to report
+            assert_eq!(
+                get(1, "name").extract::<String>().unwrap(),
+                "IRB 4600-60/2.05"
+            );
+            assert_eq!(get(1, "reachable").extract::<usize>().unwrap(), 2);
+            assert!(get(1, "min_limit_margin").is_none());
+        });
+    }
+}
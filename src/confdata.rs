@@ -0,0 +1,108 @@
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+use crate::configuration::{self, Vendor};
+use crate::kinematic_model::KinematicModel;
+
+/// A configuration value of an exported program: a flag or turn number, or the Fanuc
+/// configuration string
+#[derive(Clone, Debug, PartialEq)]
+pub enum Flag {
+    Number(i64),
+    Text(String),
+}
+
+impl Flag {
+    /// Whether an expected flag agrees with a computed one. Numbers are compared as
+    /// numbers, strings ignoring case and spaces.
+    pub fn matches(&self, computed: &Flag) -> bool {
+        let squeeze = |text: &str| -> String {
+            text.chars()
+                .filter(|c| !c.is_whitespace())
+                .map(|c| c.to_ascii_uppercase())
+                .collect()
+        };
+        match (self, computed) {
+            (Flag::Text(a), Flag::Text(b)) => squeeze(a) == squeeze(b),
+            _ => self == computed,
+        }
+    }
+
+    pub fn to_object<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(match self {
+            Flag::Number(number) => number.into_pyobject(py)?.into_any(),
+            Flag::Text(text) => text.into_pyobject(py)?.into_any(),
+        })
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for Flag {
+    type Error = PyErr;
+
+    /// Integers, whole floats as pandas stores integer columns with gaps, and strings. A
+    /// string of a number or a KUKA binary literal such as "B010" is that number.
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if let Ok(number) = obj.extract::<i64>() {
+            return Ok(Flag::Number(number));
+        }
+        if let Ok(number) = obj.extract::<f64>() {
+            if number.fract() == 0.0 {
+                return Ok(Flag::Number(number as i64));
+            }
+        }
+        let text: String = obj.extract()?;
+        let trimmed = text.trim();
+        let number = match trimmed.strip_prefix(['B', 'b']) {
+            Some(binary) => i64::from_str_radix(binary, 2).ok(),
+            None => trimmed.parse().ok(),
+        };
+        Ok(number.map_or(Flag::Text(text), Flag::Number))
+    }
+}
+
+/// Names of the configuration columns checked for a vendor, as computed by `flags`
+pub fn columns(vendor: Option<Vendor>) -> Vec<&'static str> {
+    let mut columns = vec!["CF1", "CF4", "CF6", "CFX"];
+    match vendor {
+        Some(Vendor::Kuka) => columns.extend(["S", "T"]),
+        Some(Vendor::Fanuc) => columns.push("FANUC"),
+        None => {}
+    }
+    columns
+}
+
+/// The configuration of joints (radians) in the order of `columns`
+pub fn flags(model: &KinematicModel, joints: &Joints, vendor: Option<Vendor>) -> Vec<Flag> {
+    let mut flags = vec![
+        Flag::Number(configuration::quadrant(joints[0]).into()),
+        Flag::Number(configuration::quadrant(joints[3]).into()),
+        Flag::Number(configuration::quadrant(joints[5]).into()),
+        Flag::Number(configuration::configuration(model, joints).into()),
+    ];
+    match vendor {
+        Some(Vendor::Kuka) => flags.extend([
+            Flag::Number(configuration::kuka_status(model, joints).into()),
+            Flag::Number(configuration::kuka_turn(joints).into()),
+        ]),
+        Some(Vendor::Fanuc) => {
+            flags.push(Flag::Text(configuration::fanuc_config(model, joints)));
+        }
+        None => {}
+    }
+    flags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flags_match() {
+        let text = |text: &str| Flag::Text(text.to_string());
+        assert!(text("n u t, 0, 0, 0").matches(&text("N U T, 0, 0, 0")));
+        assert!(!text("F U T, 0, 0, 0").matches(&text("N U T, 0, 0, 0")));
+        assert!(Flag::Number(2).matches(&Flag::Number(2)));
+        assert!(!Flag::Number(2).matches(&text("2")));
+        assert_eq!(columns(Some(Vendor::Kuka)).len(), 6);
+    }
+}
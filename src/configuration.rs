@@ -0,0 +1,227 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::f64::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::kinematic_model::KinematicModel;
+
+/// ABB style configuration index cfx (0..=7) of a solution, see AXIS_CONFIGURATION.md.
+///
+/// Bit 2 is set when the wrist center is behind axis 1, bit 1 when it is behind the lower
+/// arm and bit 0 when axis 5 is negative. Joints are in radians, as the controller reports
+/// them (coupled, before sign corrections and offsets).
+pub fn configuration(model: &KinematicModel, joints: &Joints) -> u8 {
+    let joints = &model.decouple(joints);
+    let q: [f64; 6] =
+        std::array::from_fn(|i| joints[i] * model.sign_corrections[i] as f64 - model.offsets[i]);
+    let psi3 = model.a2.atan2(model.c3);
+    let k = (model.a2 * model.a2 + model.c3 * model.c3).sqrt();
+
+    // Wrist center in the arm plane, along the direction axis 1 points the arm to
+    let reach = model.c2 * q[1].sin() + k * (q[1] + q[2] + psi3).sin() + model.a1;
+    // Side of the lower arm the forearm folds to, k * sin(q3 + psi3) is the cross product
+    // of the lower arm direction and the J2 -> wrist center vector
+    let fold = (q[2] + psi3).sin();
+
+    let behind_axis_1 = reach < 0.0;
+    let behind_lower_arm = fold < 0.0;
+    let wrist_negative = joints[4] < 0.0;
+    (u8::from(behind_axis_1) << 2) | (u8::from(behind_lower_arm) << 1) | u8::from(wrist_negative)
+}
+
+/// Controller whose configuration flags are reported next to the ABB ones
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Vendor {
+    Kuka,
+    Fanuc,
+}
+
+impl Vendor {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "kuka" => Ok(Vendor::Kuka),
+            "fanuc" => Ok(Vendor::Fanuc),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown vendor '{}', expected 'kuka' or 'fanuc'",
+                name
+            ))),
+        }
+    }
+}
+
+/// ABB quadrant number of a joint angle (radians): 0 for [0, 90), 1 for [90, 180),
+/// -1 for [-90, 0) and so on
+pub fn quadrant(angle: f64) -> i32 {
+    (angle / FRAC_PI_2).floor() as i32
+}
+
+/// KUKA Status: bit 0 wrist center behind axis 1, bit 1 wrist center behind the lower arm
+/// (A3 past its stretched position), bit 2 A5 <= 0
+pub fn kuka_status(model: &KinematicModel, joints: &Joints) -> u8 {
+    let cfx = configuration(model, joints);
+    let wrist = u8::from(joints[4] <= 0.0);
+    (cfx >> 2 & 1) | (cfx >> 1 & 1) << 1 | wrist << 2
+}
+
+/// KUKA Turn: bit i is set when axis i + 1 is negative
+pub fn kuka_turn(joints: &Joints) -> u8 {
+    joints
+        .iter()
+        .enumerate()
+        .fold(0, |turn, (i, joint)| turn | u8::from(*joint < 0.0) << i)
+}
+
+/// Turn number of a joint as Fanuc counts it: 0 for [-180, 180), 1 for [180, 540) and so on
+pub fn turn(angle: f64) -> i32 {
+    ((angle + PI) / TAU).floor() as i32
+}
+
+/// Joints (radians, as the controller reports them) with J1, J4 and J6 moved to the given
+/// turn numbers. The whole turns are taken by the joints of the kinematic model, so with
+/// wrist coupling J6 follows a turn of J4 and the joints still reach the same pose.
+pub fn with_turns(model: &KinematicModel, joints: &Joints, turns: [i32; 3]) -> Joints {
+    let mut decoupled = model.decouple(joints);
+    decoupled[0] += TAU * (turns[0] - turn(joints[0])) as f64;
+    decoupled[3] += TAU * (turns[1] - turn(joints[3])) as f64;
+    let j6 = model.couple(&decoupled)[5];
+    decoupled[5] += TAU * (turns[2] - turn(j6)) as f64;
+    model.couple(&decoupled)
+}
+
+/// Fanuc configuration string such as "N U T, 0, 0, 0": Flip (J5 < 0) or No flip, Up or
+/// Down elbow, Toward or Back of axis 1, then the turn numbers of J1, J4 and J6
+pub fn fanuc_config(model: &KinematicModel, joints: &Joints) -> String {
+    let cfx = configuration(model, joints);
+    format!(
+        "{} {} {}, {}, {}, {}",
+        if joints[4] < 0.0 { "F" } else { "N" },
+        if cfx & 2 == 0 { "U" } else { "D" },
+        if cfx & 4 == 0 { "T" } else { "B" },
+        turn(joints[0]),
+        turn(joints[3]),
+        turn(joints[5]),
+    )
+}
+
+/// Runs of equal consecutive values as (first row, last row, value)
+pub fn segments<T: PartialEq + Copy>(values: &[T]) -> Vec<(usize, usize, T)> {
+    let mut segments: Vec<(usize, usize, T)> = Vec::new();
+    for (row, value) in values.iter().enumerate() {
+        match segments.last_mut() {
+            Some((_, last, current)) if current == value => *last = row,
+            _ => segments.push((row, row, *value)),
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_home_is_configuration_zero() {
+        assert_eq!(configuration(&ABB_1660, &[0.0, 0.0, 0.0, 0.0, 0.3, 0.0]), 0);
+        assert_eq!(
+            configuration(&ABB_1660, &[0.0, 0.0, 0.0, 0.0, -0.3, 0.0]),
+            1
+        );
+    }
+
+    #[test]
+    fn test_inverse_solutions_have_distinct_configurations() {
+        let robot = ABB_1660.to_opw_kinematics();
+        let pose = rs_opw_kinematics::kinematic_traits::Kinematics::forward(
+            &robot,
+            &[0.3, 0.2, -0.1, 0.4, 0.5, 0.6],
+        );
+        let solutions = rs_opw_kinematics::kinematic_traits::Kinematics::inverse(&robot, &pose);
+        let configurations: HashSet<u8> = solutions
+            .iter()
+            .map(|joints| configuration(&ABB_1660, joints))
+            .collect();
+        assert_eq!(configurations.len(), solutions.len());
+    }
+
+    #[test]
+    fn test_coupled_joints_keep_their_configuration() {
+        let robot = ABB_1660.to_opw_kinematics();
+        let pose = rs_opw_kinematics::kinematic_traits::Kinematics::forward(
+            &robot,
+            &[0.3, 0.2, -0.1, 0.4, 0.5, 0.6],
+        );
+        let solutions = rs_opw_kinematics::kinematic_traits::Kinematics::inverse(&robot, &pose);
+        assert_eq!(solutions.len(), 8);
+        let coupled = [
+            KinematicModel {
+                has_parallelogram: true,
+                ..ABB_1660
+            },
+            KinematicModel {
+                wrist_coupling: [0.5, -0.2],
+                ..ABB_1660
+            },
+        ];
+        for model in &coupled {
+            // The controller reports the coupled joints of the same arm posture
+            for solution in &solutions {
+                assert_eq!(
+                    configuration(model, &model.couple(solution)),
+                    configuration(&ABB_1660, solution)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_vendor_flags() {
+        assert_eq!(quadrant(0.0), 0);
+        assert_eq!(quadrant(-0.1), -1);
+        assert_eq!(quadrant(3.0), 1);
+        let joints = [-0.2, 0.0, 0.0, 3.5, -0.3, 0.0];
+        assert_eq!(kuka_status(&ABB_1660, &joints), 0b100);
+        assert_eq!(kuka_turn(&joints), 0b10001);
+        assert_eq!(fanuc_config(&ABB_1660, &joints), "F U T, 0, 1, 0");
+
+        let turned = with_turns(&ABB_1660, &joints, [1, -1, 0]);
+        assert!((turned[0] - (-0.2 + TAU)).abs() < 1e-12);
+        assert!((turned[3] - (3.5 - 2.0 * TAU)).abs() < 1e-12);
+        assert_eq!(&turned[4..], &joints[4..]);
+        assert_eq!([turn(turned[0]), turn(turned[3])], [1, -1]);
+    }
+
+    #[test]
+    fn test_turns_of_a_coupled_wrist_reach_the_pose() {
+        let model = KinematicModel {
+            wrist_coupling: [0.5, 0.0],
+            ..ABB_1660
+        };
+        let robot = model.to_opw_kinematics();
+        let forward = |joints: &Joints| {
+            rs_opw_kinematics::kinematic_traits::Kinematics::forward(
+                &robot,
+                &model.decouple(joints),
+            )
+        };
+        let joints = [0.3, 0.2, -0.1, 0.4, 0.5, 0.6];
+        let turned = with_turns(&model, &joints, [0, 1, -1]);
+        assert_eq!(
+            [turn(turned[0]), turn(turned[3]), turn(turned[5])],
+            [0, 1, -1]
+        );
+        let (pose, reached) = (forward(&joints), forward(&turned));
+        assert!((pose.translation.vector - reached.translation.vector).norm() < 1e-9);
+        assert!(pose.rotation.angle_to(&reached.rotation) < 1e-9);
+    }
+
+    #[test]
+    fn test_segments() {
+        assert_eq!(
+            segments(&[0, 0, 1, 1, 1, 0]),
+            vec![(0, 1, 0), (2, 4, 1), (5, 5, 0)]
+        );
+        assert!(segments::<u8>(&[]).is_empty());
+    }
+}
@@ -0,0 +1,225 @@
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+
+use crate::batch::ThreadPool;
+use crate::columns::PoseTable;
+use crate::ordering::{InverseOptions, SolutionOrder};
+use crate::pose::PyPose;
+use crate::validation::{self, InvalidPolicy};
+use crate::Robot;
+
+/// Solutions of one row in the units of the context, None for invalid rows
+type Solutions = Option<Vec<[f64; 6]>>;
+
+/// Work handed to a worker thread, run with the robot the worker holds
+type Job = Box<dyn FnOnce(&Robot) + Send>;
+
+/// Solutions of a row as batch_inverse returns them
+fn solve(robot: &Robot, pose: &PyPose, options: &InverseOptions) -> Solutions {
+    if !validation::is_finite_pose(pose) {
+        return None;
+    }
+    let solutions = robot.solve_radians(*pose, None, options);
+    Some(
+        solutions
+            .iter()
+            .map(|joints| robot.joints_out(*joints, options.radians))
+            .collect(),
+    )
+}
+
+/// batch_inverse with its settings fixed and its worker threads and row buffer kept
+/// between calls, for repeated batches of similar size such as sliding windows over a
+/// stream of poses. The workers hold the robot and live as long as the context.
+#[pyclass(frozen)]
+pub struct SolverContext {
+    robot: Py<Robot>,
+    pool: ThreadPool,
+    options: InverseOptions,
+    workers: Vec<Sender<Job>>,
+    /// Row buffer, kept with its capacity between batches
+    rows: Mutex<Vec<Solutions>>,
+}
+
+impl SolverContext {
+    /// Solves `poses` on the workers into `rows`, or on the calling thread when the batch
+    /// fits in a single chunk
+    fn run(&self, poses: Vec<PyPose>, mut rows: Vec<Solutions>) -> Vec<Solutions> {
+        let robot = self.robot.get();
+        let chunk_size = self.pool.chunk_size();
+        let options = self.options;
+        rows.clear();
+        let jobs = self.workers.len().min(poses.len().div_ceil(chunk_size));
+        if jobs <= 1 {
+            rows.extend(poses.iter().map(|pose| solve(robot, pose, &options)));
+            return rows;
+        }
+        rows.resize(poses.len(), None);
+        let poses = Arc::new(poses);
+        let rows = Arc::new(Mutex::new(rows));
+        let next = Arc::new(AtomicUsize::new(0));
+        let (done, finished) = mpsc::channel();
+        for worker in &self.workers[..jobs] {
+            let (poses, rows, next, done) =
+                (poses.clone(), rows.clone(), next.clone(), done.clone());
+            let job: Job = Box::new(move |robot| {
+                {
+                    let (poses, rows, next) = (poses, rows, next);
+                    loop {
+                        let start = next.fetch_add(1, Ordering::Relaxed) * chunk_size;
+                        let Some(chunk) = poses.get(start..(start + chunk_size).min(poses.len()))
+                        else {
+                            break;
+                        };
+                        let solved: Vec<Solutions> = chunk
+                            .iter()
+                            .map(|pose| solve(robot, pose, &options))
+                            .collect();
+                        let mut rows = rows.lock().unwrap_or_else(|e| e.into_inner());
+                        for (row, solutions) in rows[start..].iter_mut().zip(solved) {
+                            *row = solutions;
+                        }
+                    }
+                }
+                // The shared buffers are released before the batch counts as done
+                let _ = done.send(());
+            });
+            worker.send(job).expect("Batch worker thread panicked");
+        }
+        drop(done);
+        for _ in 0..jobs {
+            finished.recv().expect("Batch worker thread panicked");
+        }
+        Arc::try_unwrap(rows)
+            .expect("Batch workers release the rows when done")
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[pymethods]
+impl SolverContext {
+    /// Starts the workers of `pool` (or `n_threads` and `chunk_size`) for `robot`, with the
+    /// inverse settings of batch_inverse
+    #[new]
+    #[pyo3(signature = (
+        robot,
+        n_threads=None,
+        chunk_size=None,
+        pool=None,
+        order_by=SolutionOrder::Distance,
+        dedup_tolerance=None,
+        dedup_turns=true,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        py: Python<'_>,
+        robot: Py<Robot>,
+        n_threads: Option<usize>,
+        chunk_size: Option<usize>,
+        pool: Option<ThreadPool>,
+        order_by: SolutionOrder,
+        dedup_tolerance: Option<f64>,
+        dedup_turns: bool,
+        radians: Option<bool>,
+    ) -> PyResult<Self> {
+        let pool = ThreadPool::resolve(pool.as_ref(), n_threads, chunk_size)?;
+        let options = robot
+            .get()
+            .inverse_options(order_by, dedup_tolerance, dedup_turns, radians);
+        let workers = (0..pool.n_threads())
+            .map(|_| {
+                let robot = robot.clone_ref(py);
+                let (sender, jobs) = mpsc::channel::<Job>();
+                std::thread::spawn(move || {
+                    for job in jobs {
+                        job(robot.get());
+                    }
+                });
+                sender
+            })
+            .collect();
+        Ok(SolverContext {
+            robot,
+            pool,
+            options,
+            workers,
+            rows: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Worker threads and chunk size of the context
+    #[getter]
+    fn pool(&self) -> ThreadPool {
+        self.pool
+    }
+
+    /// Solves `poses` like batch_inverse with the settings of the context: a list of
+    /// solutions per pose, None for poses with NaN or infinite values unless `on_invalid`
+    /// skips them or raises
+    #[pyo3(signature = (poses, on_invalid=InvalidPolicy::Null))]
+    fn batch_inverse<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        on_invalid: InvalidPolicy,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        let poses = validation::screen(poses.0, validation::is_finite_pose, on_invalid)?;
+        let buffer = std::mem::take(&mut *self.rows.lock().unwrap_or_else(|e| e.into_inner()));
+        let mut rows = py.detach(|| self.run(poses, buffer));
+        let objects = rows
+            .iter()
+            .map(|row| row.into_pyobject(py))
+            .collect::<PyResult<Vec<_>>>()?;
+        rows.clear();
+        *self.rows.lock().unwrap_or_else(|e| e.into_inner()) = rows;
+        Ok(objects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::abb_1660;
+
+    #[test]
+    fn test_context_matches_serial() {
+        let robot = abb_1660();
+        let mut poses: Vec<PyPose> = (0..21)
+            .map(|i| {
+                robot
+                    .forward([i as f64, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+                    .unwrap()
+            })
+            .collect();
+        poses[5].0[0] = f64::NAN;
+        let options = robot.inverse_options(SolutionOrder::Distance, None, true, None);
+        let serial: Vec<Solutions> = poses
+            .iter()
+            .map(|pose| solve(&robot, pose, &options))
+            .collect();
+        Python::initialize();
+        Python::attach(|py| {
+            let robot = Py::new(py, robot).unwrap();
+            let context = SolverContext::new(
+                py,
+                robot,
+                Some(3),
+                Some(4),
+                None,
+                SolutionOrder::Distance,
+                None,
+                true,
+                None,
+            )
+            .unwrap();
+            let rows = context.run(poses.clone(), Vec::new());
+            assert_eq!(rows, serial);
+            // Again on the buffer of the first batch
+            assert_eq!(context.run(poses, rows), serial);
+        });
+    }
+}
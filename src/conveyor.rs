@@ -0,0 +1,41 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::pose::PyPose;
+
+/// Unit vector of a conveyor direction given in the world frame
+pub fn unit_direction(direction: [f64; 3]) -> PyResult<[f64; 3]> {
+    let norm = direction.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if !(norm.is_finite() && norm > 0.0) {
+        return Err(PyValueError::new_err(format!(
+            "The conveyor direction must be a non-zero, finite vector, got {:?}",
+            direction
+        )));
+    }
+    Ok(direction.map(|x| x / norm))
+}
+
+/// A pose taught on the conveyor carried `distance` (m) along the unit `direction`
+pub fn shift(pose: &PyPose, direction: &[f64; 3], distance: f64) -> PyPose {
+    let (translation, rotation) = *pose;
+    (
+        std::array::from_fn(|k| translation[k] + distance * direction[k]),
+        rotation,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shift_along_the_conveyor() {
+        let direction = unit_direction([0.0, 3.0, 4.0]).unwrap();
+        assert_eq!(direction, [0.0, 0.6, 0.8]);
+        let pose = ([1.0, 0.0, 0.5], [1.0, 0.0, 0.0, 0.0]);
+        let shifted = shift(&pose, &direction, 0.5);
+        assert_eq!(shifted.0, [1.0, 0.3, 0.9]);
+        assert_eq!(shifted.1, pose.1);
+        assert!(unit_direction([0.0; 3]).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use nalgebra::Isometry3;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::pose::{from_isometry, to_isometry, PyPose};
+
+/// Frame in which correction transforms are expressed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CorrectionFrame {
+    /// Applied in the world frame, before the nominal target
+    World,
+    /// Applied in the frame of a workobject given in the world frame
+    Workobject,
+    /// Applied in the frame of the nominal target, after it
+    Tool,
+}
+
+impl CorrectionFrame {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "world" => Ok(CorrectionFrame::World),
+            "workobject" => Ok(CorrectionFrame::Workobject),
+            "tool" => Ok(CorrectionFrame::Tool),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown correction frame '{}', expected 'world', 'workobject' or 'tool'",
+                name
+            ))),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for CorrectionFrame {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        CorrectionFrame::parse(&obj.extract::<String>()?)
+    }
+}
+
+/// Corrects every nominal pose by the correction of the same row, expressed in `frame`.
+/// `workobject` is the workobject frame in the world, required for the workobject frame.
+pub fn apply(
+    nominal: &[PyPose],
+    corrections: &[PyPose],
+    frame: CorrectionFrame,
+    workobject: Option<&Isometry3<f64>>,
+) -> PyResult<Vec<PyPose>> {
+    if nominal.len() != corrections.len() {
+        return Err(PyValueError::new_err(format!(
+            "Got {} poses but {} corrections, expected one correction per pose",
+            nominal.len(),
+            corrections.len()
+        )));
+    }
+    let workobject = match (frame, workobject) {
+        (CorrectionFrame::Workobject, None) => {
+            return Err(PyValueError::new_err(
+                "Corrections in the workobject frame need the workobject pose",
+            ))
+        }
+        (_, workobject) => workobject.copied().unwrap_or_else(Isometry3::identity),
+    };
+    Ok(nominal
+        .iter()
+        .zip(corrections)
+        .map(|(pose, correction)| {
+            let pose = to_isometry(pose.0, pose.1);
+            let correction = to_isometry(correction.0, correction.1);
+            from_isometry(&match frame {
+                CorrectionFrame::World => correction * pose,
+                CorrectionFrame::Workobject => {
+                    workobject * correction * workobject.inverse() * pose
+                }
+                CorrectionFrame::Tool => pose * correction,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Translation3, UnitQuaternion};
+
+    #[test]
+    fn test_corrections_act_in_their_frame() {
+        let turn = UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2);
+        let pose = from_isometry(&Isometry3::from_parts(
+            Translation3::new(1.0, 0.0, 0.0),
+            turn,
+        ));
+        let shift = ([0.1, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]);
+        let workobject = Isometry3::from_parts(Translation3::new(0.0, 2.0, 0.0), turn);
+        let corrected = |frame| apply(&[pose], &[shift], frame, Some(&workobject)).unwrap()[0].0;
+        let close = |a: [f64; 3], b: [f64; 3]| a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-12);
+
+        assert!(close(corrected(CorrectionFrame::World), [1.1, 0.0, 0.0]));
+        // x of the workobject and of the target both point along world y
+        assert!(close(
+            corrected(CorrectionFrame::Workobject),
+            [1.0, 0.1, 0.0]
+        ));
+        assert!(close(corrected(CorrectionFrame::Tool), [1.0, 0.1, 0.0]));
+
+        assert!(apply(&[pose], &[], CorrectionFrame::World, None).is_err());
+        assert!(apply(&[pose], &[shift], CorrectionFrame::Workobject, None).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+use nalgebra::Isometry3;
+
+/// How far a TCP pose lies from a reference path
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Deviation {
+    /// Distance (m) from the closest point of the path
+    pub translation: f64,
+    /// Angle (radians) from the orientation of the path at that point
+    pub rotation: f64,
+    /// Position of that point along the path, the index of a reference pose plus the
+    /// share of the way to the next one
+    pub reference: f64,
+}
+
+/// Deviation of `pose` from the single reference pose with index `reference`
+pub fn from_pose(pose: &Isometry3<f64>, reference: &Isometry3<f64>, index: usize) -> Deviation {
+    let (translation, rotation) = crate::pose::pose_errors(pose, reference);
+    Deviation {
+        translation,
+        rotation,
+        reference: index as f64,
+    }
+}
+
+/// Deviation of `pose` from the polyline through the positions of `path`, its
+/// orientation interpolated (slerp) between the reference poses. The closest segment
+/// wins, the first of equally close ones.
+pub fn from_path(pose: &Isometry3<f64>, path: &[Isometry3<f64>]) -> Deviation {
+    let point = pose.translation.vector;
+    let mut best = from_pose(pose, &path[0], 0);
+    for (i, segment) in path.windows(2).enumerate() {
+        let (a, b) = (segment[0].translation.vector, segment[1].translation.vector);
+        let line = b - a;
+        let length = line.norm_squared();
+        let t = match length > 1e-24 {
+            true => ((point - a).dot(&line) / length).clamp(0.0, 1.0),
+            false => 0.0,
+        };
+        let translation = (point - (a + t * line)).norm();
+        if translation < best.translation {
+            let rotation = segment[0]
+                .rotation
+                .try_slerp(&segment[1].rotation, t, 1e-12)
+                .unwrap_or(segment[0].rotation);
+            best = Deviation {
+                translation,
+                rotation: pose.rotation.angle_to(&rotation),
+                reference: i as f64 + t,
+            };
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Translation3, UnitQuaternion, Vector3};
+
+    #[test]
+    fn test_deviation_from_path() {
+        let pose = |x: f64, y: f64, angle: f64| {
+            Isometry3::from_parts(
+                Translation3::new(x, y, 0.0),
+                UnitQuaternion::from_axis_angle(&Vector3::z_axis(), angle),
+            )
+        };
+        let path = [
+            pose(0.0, 0.0, 0.0),
+            pose(1.0, 0.0, 0.4),
+            pose(1.0, 1.0, 0.4),
+        ];
+        // A quarter of the way along the first segment, 2 mm off it and rotated as planned
+        let deviation = from_path(&pose(0.25, 0.002, 0.1), &path);
+        assert!((deviation.translation - 0.002).abs() < 1e-12);
+        assert!(deviation.rotation < 1e-9);
+        assert!((deviation.reference - 0.25).abs() < 1e-12);
+        // Halfway up the second segment
+        let deviation = from_path(&pose(1.001, 0.5, 0.4), &path);
+        assert!((deviation.reference - 1.5).abs() < 1e-12);
+        assert!((deviation.translation - 0.001).abs() < 1e-12);
+
+        let single = from_path(&pose(0.0, 0.003, 0.0), &path[..1]);
+        assert_eq!(single, from_pose(&pose(0.0, 0.003, 0.0), &path[0], 0));
+    }
+}
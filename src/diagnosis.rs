@@ -0,0 +1,62 @@
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+/// Why a pose cannot be reached, from the most to the least fundamental cause
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reason {
+    Reachable,
+    /// The wrist center lies beyond the outer radius of the arm
+    OutsideReach,
+    /// The wrist center lies in the void the arm cannot fold into
+    InteriorVoid,
+    /// Every analytic solution has a joint beyond its limits
+    JointLimits,
+    /// Other orientations of the tool at the same position are reachable
+    Orientation,
+}
+
+impl Reason {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reason::Reachable => "reachable",
+            Reason::OutsideReach => "outside_reach",
+            Reason::InteriorVoid => "interior_void",
+            Reason::JointLimits => "joint_limits",
+            Reason::Orientation => "orientation",
+        }
+    }
+}
+
+/// How far (radians) each joint lies beyond its limits, 0 within them
+pub fn limit_excess(joints: &Joints, limits: &[(f64, f64); 6]) -> [f64; 6] {
+    std::array::from_fn(|k| {
+        let (min, max) = limits[k];
+        (min - joints[k]).max(joints[k] - max).max(0.0)
+    })
+}
+
+/// The solution that misses the limits by the least summed excess, with its excess
+pub fn least_excess(solutions: &[Joints], limits: &[(f64, f64); 6]) -> Option<[f64; 6]> {
+    solutions
+        .iter()
+        .map(|joints| limit_excess(joints, limits))
+        .min_by(|a, b| a.iter().sum::<f64>().total_cmp(&b.iter().sum::<f64>()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_least_excess() {
+        let limits = [(-1.0, 1.0); 6];
+        let inside = [0.5, -0.5, 0.0, 1.0, -1.0, 0.0];
+        assert_eq!(limit_excess(&inside, &limits), [0.0; 6]);
+        let far = [1.5, 0.0, 0.0, 0.0, 0.0, -3.0];
+        let near = [0.0, 0.0, 1.2, 0.0, 0.0, 0.0];
+        let excess = least_excess(&[far, near], &limits).unwrap();
+        assert!((excess[2] - 0.2).abs() < 1e-12);
+        assert_eq!(excess.iter().filter(|x| **x > 0.0).count(), 1);
+        assert!(least_excess(&[], &limits).is_none());
+        assert_eq!(Reason::InteriorVoid.name(), "interior_void");
+    }
+}
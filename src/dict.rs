@@ -0,0 +1,37 @@
+use pyo3::conversion::FromPyObjectOwned;
+use pyo3::exceptions::{PyKeyError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Rejects keys that `class` does not know, so typos in configs don't pass silently.
+pub fn check_keys(dict: &Bound<'_, PyDict>, allowed: &[&str], class: &str) -> PyResult<()> {
+    for key in dict.keys() {
+        let key: String = key.extract()?;
+        if !allowed.contains(&key.as_str()) {
+            return Err(PyValueError::new_err(format!(
+                "Unknown key '{}' for {}, expected one of {:?}",
+                key, class, allowed
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn optional<'py, T: FromPyObjectOwned<'py>>(
+    dict: &Bound<'py, PyDict>,
+    key: &str,
+) -> PyResult<Option<T>> {
+    match dict.get_item(key)? {
+        Some(value) if !value.is_none() => Ok(Some(value.extract().map_err(Into::into)?)),
+        _ => Ok(None),
+    }
+}
+
+pub fn required<'py, T: FromPyObjectOwned<'py>>(
+    dict: &Bound<'py, PyDict>,
+    key: &str,
+    class: &str,
+) -> PyResult<T> {
+    optional(dict, key)?
+        .ok_or_else(|| PyKeyError::new_err(format!("{} requires the key '{}'", class, key)))
+}
@@ -0,0 +1,129 @@
+use nalgebra::{DualQuaternion as NaDualQuaternion, Quaternion, UnitDualQuaternion};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::pose::{from_isometry, to_isometry, PyPose};
+use crate::twist;
+
+/// Rigid transform as a unit dual quaternion, supporting ScLERP interpolation.
+#[pyclass(frozen)]
+#[pyo3(from_py_object)]
+#[derive(Clone)]
+pub struct DualQuaternion {
+    pub inner: UnitDualQuaternion<f64>,
+}
+
+fn wxyz(q: &Quaternion<f64>) -> [f64; 4] {
+    [q.w, q.i, q.j, q.k]
+}
+
+#[pymethods]
+impl DualQuaternion {
+    /// Builds a dual quaternion from its real and dual parts, both as (w, x, y, z).
+    /// The result is normalized.
+    #[new]
+    fn new(real: [f64; 4], dual: [f64; 4]) -> PyResult<Self> {
+        let real = Quaternion::new(real[0], real[1], real[2], real[3]);
+        if real.norm() == 0.0 {
+            return Err(PyValueError::new_err(
+                "The real part of a dual quaternion must not be zero",
+            ));
+        }
+        let dual = Quaternion::new(dual[0], dual[1], dual[2], dual[3]);
+        Ok(DualQuaternion {
+            inner: UnitDualQuaternion::new_normalize(NaDualQuaternion::from_real_and_dual(
+                real, dual,
+            )),
+        })
+    }
+
+    /// Builds a dual quaternion from a pose ([x, y, z], [w, x, y, z])
+    #[staticmethod]
+    fn from_pose(pose: PyPose) -> Self {
+        DualQuaternion {
+            inner: UnitDualQuaternion::from_isometry(&to_isometry(pose.0, pose.1)),
+        }
+    }
+
+    /// Converts back to a pose ([x, y, z], [w, x, y, z])
+    fn to_pose(&self) -> PyPose {
+        from_isometry(&self.inner.to_isometry())
+    }
+
+    #[getter]
+    fn real(&self) -> [f64; 4] {
+        wxyz(&self.inner.as_ref().real)
+    }
+
+    #[getter]
+    fn dual(&self) -> [f64; 4] {
+        wxyz(&self.inner.as_ref().dual)
+    }
+
+    /// Screw linear interpolation towards `other`, `t` = 0 gives self and 1 gives `other`
+    fn sclerp(&self, other: &DualQuaternion, t: f64) -> Self {
+        // ScLERP follows the same constant screw motion as the se(3) geodesic, which,
+        // unlike nalgebra's sclerp, stays defined for rotations 180 degrees apart.
+        let start = self.inner.to_isometry();
+        let end = other.inner.to_isometry();
+        DualQuaternion {
+            inner: UnitDualQuaternion::from_isometry(&twist::interpolate(&start, &end, t)),
+        }
+    }
+
+    fn inverse(&self) -> Self {
+        DualQuaternion {
+            inner: self.inner.inverse(),
+        }
+    }
+
+    fn __mul__(&self, other: &DualQuaternion) -> Self {
+        DualQuaternion {
+            inner: self.inner * other.inner,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DualQuaternion(real={:?}, dual={:?})",
+            self.real(),
+            self.dual()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pose_round_trip() {
+        let pose = ([0.3, -0.1, 0.8], [0.5, 0.5, 0.5, 0.5]);
+        let (translation, rotation) = DualQuaternion::from_pose(pose).to_pose();
+        for (a, b) in translation.iter().zip(pose.0.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        for (a, b) in rotation.iter().zip(pose.1.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_sclerp_midpoint_of_translation() {
+        let start = DualQuaternion::from_pose(([0.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]));
+        let end = DualQuaternion::from_pose(([1.0, 2.0, 0.0], [1.0, 0.0, 0.0, 0.0]));
+        let (translation, _) = start.sclerp(&end, 0.5).to_pose();
+        assert!((translation[0] - 0.5).abs() < 1e-12);
+        assert!((translation[1] - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_sclerp_half_turn() {
+        let start = DualQuaternion::from_pose(([0.0, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]));
+        let end = DualQuaternion::from_pose(([0.0, 0.0, 0.0], [0.0, 0.0, 0.0, 1.0]));
+        let (_, rotation) = start.sclerp(&end, 0.5).to_pose();
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((rotation[0].abs() - half).abs() < 1e-12);
+        assert!((rotation[3].abs() - half).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,190 @@
+use nalgebra::Isometry3;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::sync::RwLock;
+
+use crate::columns::PoseTable;
+use crate::pose::{from_isometry, to_isometry, PyPose};
+use crate::rows::POSE_COLUMNS;
+use crate::transform_out;
+use crate::validation::{self, InvalidPolicy};
+
+/// Name of the root frame, which every other frame is placed in
+pub const WORLD: &str = "world";
+
+/// Named frames of a cell (world, robot base, workobjects, tool), each stored as its pose
+/// in the world frame, for re-expressing poses and whole pose tables between them. Frames
+/// can be added while the registry is shared between threads.
+#[pyclass(frozen)]
+pub struct Frames {
+    frames: RwLock<Vec<(String, Isometry3<f64>)>>,
+}
+
+impl Frames {
+    /// Registry with the world frame and the given frames, in the world frame
+    pub fn with(frames: impl IntoIterator<Item = (String, Isometry3<f64>)>) -> Self {
+        let world = (WORLD.to_string(), Isometry3::identity());
+        Frames {
+            frames: RwLock::new(std::iter::once(world).chain(frames).collect()),
+        }
+    }
+
+    /// Pose of the frame `name` in the world frame
+    fn get(&self, name: &str) -> PyResult<Isometry3<f64>> {
+        let frames = self.frames.read().unwrap_or_else(|e| e.into_inner());
+        frames
+            .iter()
+            .find(|(frame, _)| frame == name)
+            .map(|(_, pose)| *pose)
+            .ok_or_else(|| {
+                let names: Vec<&str> = frames.iter().map(|(frame, _)| frame.as_str()).collect();
+                PyValueError::new_err(format!(
+                    "Unknown frame '{}', the frames are {}",
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+
+    /// Transform taking poses expressed in `from_frame` to poses expressed in `to_frame`
+    pub fn between(&self, from_frame: &str, to_frame: &str) -> PyResult<Isometry3<f64>> {
+        Ok(self.get(to_frame)?.inverse() * self.get(from_frame)?)
+    }
+}
+
+#[pymethods]
+impl Frames {
+    /// Registry with only the world frame
+    #[new]
+    fn new() -> Self {
+        Frames::with([])
+    }
+
+    /// Adds the frame `name` at `pose` in the `parent` frame, or moves it if it exists.
+    /// The world frame cannot be moved.
+    #[pyo3(signature = (name, pose, parent=WORLD))]
+    pub fn add(&self, name: &str, pose: PyPose, parent: &str) -> PyResult<()> {
+        validation::check_pose(&pose)?;
+        if name == WORLD {
+            return Err(PyValueError::new_err("The world frame cannot be moved"));
+        }
+        let pose = self.get(parent)? * to_isometry(pose.0, pose.1);
+        let mut frames = self.frames.write().unwrap_or_else(|e| e.into_inner());
+        match frames.iter_mut().find(|(frame, _)| frame == name) {
+            Some((_, existing)) => *existing = pose,
+            None => frames.push((name.to_string(), pose)),
+        }
+        Ok(())
+    }
+
+    /// Names of the frames, in the order they were added
+    pub fn names(&self) -> Vec<String> {
+        let frames = self.frames.read().unwrap_or_else(|e| e.into_inner());
+        frames.iter().map(|(name, _)| name.clone()).collect()
+    }
+
+    /// Pose of a frame in the world frame, as (translation, quaternion) or as a 4x4 matrix
+    /// with `as_matrix`
+    #[pyo3(signature = (name, as_matrix=false))]
+    fn pose<'py>(
+        &self,
+        py: Python<'py>,
+        name: &str,
+        as_matrix: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        transform_out(py, &self.get(name)?, as_matrix)
+    }
+
+    /// Re-expresses a pose given in `from_frame` in `to_frame`
+    #[pyo3(signature = (pose, from_frame, to_frame, as_matrix=false))]
+    fn transform<'py>(
+        &self,
+        py: Python<'py>,
+        pose: PyPose,
+        from_frame: &str,
+        to_frame: &str,
+        as_matrix: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        validation::check_pose(&pose)?;
+        let transform = self.between(from_frame, to_frame)?;
+        transform_out(py, &(transform * to_isometry(pose.0, pose.1)), as_matrix)
+    }
+
+    /// Re-expresses a table of poses given in `from_frame` in `to_frame`. Returns one row
+    /// per pose with the pose columns (x, y, z, qw, qx, qy, qz). Poses with NaN or infinite
+    /// values are handled as set by `on_invalid`, "null" gives them a None row.
+    #[pyo3(signature = (poses, from_frame, to_frame, on_invalid=InvalidPolicy::Null))]
+    fn transform_df<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        from_frame: &str,
+        to_frame: &str,
+        on_invalid: InvalidPolicy,
+    ) -> PyResult<Vec<Option<Bound<'py, PyDict>>>> {
+        let transform = self.between(from_frame, to_frame)?;
+        let poses = validation::screen(poses.0, validation::is_finite_pose, on_invalid)?;
+        let transformed: Vec<Option<PyPose>> = py.detach(|| {
+            poses
+                .iter()
+                .map(|pose| {
+                    validation::is_finite_pose(pose)
+                        .then(|| from_isometry(&(transform * to_isometry(pose.0, pose.1))))
+                })
+                .collect()
+        });
+        transformed
+            .into_iter()
+            .map(|pose| {
+                pose.map(|(translation, rotation)| {
+                    let row = PyDict::new(py);
+                    for (column, value) in
+                        POSE_COLUMNS.iter().zip(translation.iter().chain(&rotation))
+                    {
+                        row.set_item(column, value)?;
+                    }
+                    Ok(row)
+                })
+                .transpose()
+            })
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Frames({})", self.names().join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Translation3, UnitQuaternion};
+
+    #[test]
+    fn test_frames_chain_through_parents() {
+        let base = Isometry3::from_parts(
+            Translation3::new(1.0, 0.0, 0.5),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, std::f64::consts::FRAC_PI_2),
+        );
+        let frames = Frames::with([("base".to_string(), base)]);
+        frames
+            .add("table", ([0.2, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]), "base")
+            .unwrap();
+        let table = frames.get("table").unwrap();
+        // x of the base points along world y
+        assert!((table.translation.vector - nalgebra::Vector3::new(1.0, 0.2, 0.5)).norm() < 1e-12);
+
+        let point = Isometry3::translation(0.1, 0.0, 0.0);
+        let in_base = frames.between("table", "base").unwrap() * point;
+        assert!((in_base.translation.vector.x - 0.3).abs() < 1e-12);
+        let round_trip = frames.between("base", "table").unwrap() * in_base;
+        assert!((round_trip.translation.vector - point.translation.vector).norm() < 1e-12);
+
+        assert!(frames.get("conveyor").is_err());
+        assert!(frames
+            .add("world", ([0.0; 3], [1.0, 0.0, 0.0, 0.0]), "base")
+            .is_err());
+        assert_eq!(frames.names(), ["world", "base", "table"]);
+    }
+}
@@ -0,0 +1,76 @@
+use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Frame in which a jog increment is given
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JogFrame {
+    /// Along and about the axes of the TCP
+    Tool,
+    /// Along and about the world axes, rotating about the TCP
+    World,
+    /// Per joint
+    Joint,
+}
+
+impl JogFrame {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "tool" => Ok(JogFrame::Tool),
+            "world" => Ok(JogFrame::World),
+            "joint" => Ok(JogFrame::Joint),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown jog frame '{}', expected 'tool', 'world' or 'joint'",
+                name
+            ))),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for JogFrame {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        JogFrame::parse(&obj.extract::<String>()?)
+    }
+}
+
+/// TCP pose after a Cartesian increment [dx, dy, dz, rx, ry, rz] (m and a rotation vector
+/// in radians) of `pose` in a Cartesian `frame`. World rotations turn about the TCP, so the
+/// TCP stays in place while reorienting.
+pub fn target(pose: &Isometry3<f64>, delta: &[f64; 6], frame: JogFrame) -> Isometry3<f64> {
+    let translation = Translation3::new(delta[0], delta[1], delta[2]);
+    let rotation = UnitQuaternion::from_scaled_axis(Vector3::new(delta[3], delta[4], delta[5]));
+    match frame {
+        JogFrame::World => {
+            Isometry3::from_parts(translation * pose.translation, rotation * pose.rotation)
+        }
+        JogFrame::Tool | JogFrame::Joint => pose * Isometry3::from_parts(translation, rotation),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_jog_target() {
+        // TCP pointing down, turned 90 degrees about x
+        let pose = Isometry3::from_parts(
+            Translation3::new(1.0, 0.0, 0.5),
+            UnitQuaternion::from_euler_angles(FRAC_PI_2, 0.0, 0.0),
+        );
+        let up = [0.0, 0.0, 0.01, 0.0, 0.0, 0.0];
+        let world = target(&pose, &up, JogFrame::World);
+        assert!((world.translation.vector - Vector3::new(1.0, 0.0, 0.51)).norm() < 1e-12);
+        // Along the tool z axis, which points along -y
+        let tool = target(&pose, &up, JogFrame::Tool);
+        assert!((tool.translation.vector - Vector3::new(1.0, -0.01, 0.5)).norm() < 1e-12);
+
+        let turn = [0.0, 0.0, 0.0, 0.0, 0.0, 0.1];
+        let turned = target(&pose, &turn, JogFrame::World);
+        assert_eq!(turned.translation, pose.translation);
+        assert!((turned.rotation.angle_to(&pose.rotation) - 0.1).abs() < 1e-12);
+    }
+}
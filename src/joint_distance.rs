@@ -0,0 +1,150 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::batch::ThreadPool;
+use crate::columns::JointTable;
+use crate::ordering;
+use crate::validation;
+
+/// Settings shared by the joint distance functions
+struct Metric {
+    weights: [f64; 6],
+    wrap: bool,
+    radians: bool,
+}
+
+impl Metric {
+    fn new(weights: Option<[f64; 6]>, wrap: bool, radians: bool) -> PyResult<Self> {
+        let weights = weights.unwrap_or([1.0; 6]);
+        if !weights
+            .iter()
+            .all(|weight| weight.is_finite() && *weight >= 0.0)
+        {
+            return Err(PyValueError::new_err(format!(
+                "weights must be finite and not negative, got {:?}",
+                weights
+            )));
+        }
+        Ok(Metric {
+            weights,
+            wrap,
+            radians,
+        })
+    }
+
+    /// Distance in the units of the joints, None if either has NaN or infinite values
+    fn distance(&self, a: &[f64; 6], b: &[f64; 6]) -> Option<f64> {
+        if !(validation::is_finite_joints(a) && validation::is_finite_joints(b)) {
+            return None;
+        }
+        let radians = |joints: &[f64; 6]| match self.radians {
+            true => *joints,
+            false => joints.map(f64::to_radians),
+        };
+        let distance = ordering::joint_distance(&radians(a), &radians(b), &self.weights, self.wrap);
+        Some(match self.radians {
+            true => distance,
+            false => distance.to_degrees(),
+        })
+    }
+}
+
+/// Distance between two joint sets as inverse kinematics ranks solutions by closeness to
+/// the seed: the sum of the absolute joint differences, each scaled by its weight (1 by
+/// default), in degrees unless `radians`. With `wrap` whole turns of a joint do not count,
+/// so 179 and -179 degrees lie 2 degrees apart.
+#[pyfunction]
+#[pyo3(signature = (a, b, weights=None, wrap=true, radians=false))]
+pub fn joint_distance(
+    a: [f64; 6],
+    b: [f64; 6],
+    weights: Option<[f64; 6]>,
+    wrap: bool,
+    radians: bool,
+) -> PyResult<f64> {
+    validation::check_joints("a", &a)?;
+    validation::check_joints("b", &b)?;
+    let metric = Metric::new(weights, wrap, radians)?;
+    Ok(metric.distance(&a, &b).expect("joints are finite"))
+}
+
+/// joint_distance row by row between two joint tables of the same length, or from every
+/// row of `a` to a single row of `b`. Rows with NaN or infinite values give None.
+#[pyfunction]
+#[pyo3(signature = (a, b, weights=None, wrap=true, radians=false))]
+pub fn joint_distances(
+    a: JointTable,
+    b: JointTable,
+    weights: Option<[f64; 6]>,
+    wrap: bool,
+    radians: bool,
+) -> PyResult<Vec<Option<f64>>> {
+    let metric = Metric::new(weights, wrap, radians)?;
+    match b.0.as_slice() {
+        [b] => Ok(a.0.iter().map(|a| metric.distance(a, b)).collect()),
+        b if b.len() == a.0.len() => Ok(a
+            .0
+            .iter()
+            .zip(b)
+            .map(|(a, b)| metric.distance(a, b))
+            .collect()),
+        b => Err(PyValueError::new_err(format!(
+            "Expected a single row or {} rows in b, got {}",
+            a.0.len(),
+            b.len()
+        ))),
+    }
+}
+
+/// Matrix of joint_distance from every row of `a` to every row of `b` (`a` itself by
+/// default), computed in parallel, e.g. for clustering joint solutions
+#[pyfunction]
+#[pyo3(signature = (a, b=None, weights=None, wrap=true, radians=false))]
+pub fn pairwise_joint_distances(
+    py: Python<'_>,
+    a: JointTable,
+    b: Option<JointTable>,
+    weights: Option<[f64; 6]>,
+    wrap: bool,
+    radians: bool,
+) -> PyResult<Vec<Vec<f64>>> {
+    let metric = Metric::new(weights, wrap, radians)?;
+    let a = validation::screen(
+        a.0,
+        validation::is_finite_joints,
+        validation::InvalidPolicy::Raise,
+    )?;
+    let b = match b {
+        Some(b) => validation::screen(
+            b.0,
+            validation::is_finite_joints,
+            validation::InvalidPolicy::Raise,
+        )?,
+        None => a.clone(),
+    };
+    Ok(py.detach(|| {
+        ThreadPool::default().map(&a, |a| {
+            b.iter()
+                .map(|b| metric.distance(a, b).expect("joints are finite"))
+                .collect()
+        })
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_joint_distance() {
+        let a = [179.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let b = [-179.0, 0.0, 0.0, 0.0, 0.0, 10.0];
+        let wrapped = joint_distance(a, b, None, true, false).unwrap();
+        assert!((wrapped - 12.0).abs() < 1e-9);
+        let unwrapped = joint_distance(a, b, None, false, false).unwrap();
+        assert!((unwrapped - 368.0).abs() < 1e-9);
+        let weighted = joint_distance(a, b, Some([1.0, 1.0, 1.0, 1.0, 1.0, 0.5]), true, false);
+        assert!((weighted.unwrap() - 7.0).abs() < 1e-9);
+        assert!(joint_distance(a, b, Some([-1.0; 6]), true, false).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+use crate::cli::Column;
+
+/// Names of a time column, compared case-insensitively
+const TIME_COLUMNS: [&str; 7] = [
+    "t",
+    "time",
+    "timestamp",
+    "%time",
+    "stamp",
+    "header.stamp",
+    "field.header.stamp",
+];
+
+/// Times beyond this are nanoseconds since the epoch, as rosbag exports write them
+const NANOSECONDS: f64 = 1e14;
+
+/// One joint_states message: its time and the position of every joint, with their names
+/// unless the log has none
+#[derive(Debug, PartialEq)]
+pub struct Sample {
+    pub t: f64,
+    pub names: Option<Vec<String>>,
+    pub positions: Vec<f64>,
+}
+
+fn normalize(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    name.strip_prefix("field.")
+        .map_or(name.clone(), str::to_string)
+}
+
+fn find(header: &[String], names: &[&str]) -> Option<usize> {
+    names.iter().find_map(|name| {
+        header
+            .iter()
+            .position(|column| normalize(column) == normalize(name))
+    })
+}
+
+fn numbers<'a>(header: &[String], columns: &'a [Column], i: usize) -> Result<&'a [f64], String> {
+    match &columns[i] {
+        Column::Numbers(values) => Ok(values),
+        Column::Text(_) => Err(format!("Column '{}' is not numeric", header[i])),
+    }
+}
+
+fn text<'a>(header: &[String], columns: &'a [Column], i: usize) -> Result<&'a [String], String> {
+    match &columns[i] {
+        Column::Text(values) => Ok(values),
+        Column::Numbers(_) => Err(format!("Column '{}' holds numbers, not names", header[i])),
+    }
+}
+
+/// Times in seconds, from a time column or from sec and nanosec stamp columns. `relative`
+/// times start at 0, subtracted before scaling nanoseconds to keep their precision.
+fn times(header: &[String], columns: &[Column], relative: bool) -> Result<Vec<f64>, String> {
+    let origin = |values: &[f64]| match relative {
+        true => values.first().copied().unwrap_or(0.0),
+        false => 0.0,
+    };
+    if let Some(i) = find(header, &TIME_COLUMNS) {
+        let values = numbers(header, columns, i)?;
+        let scale = match values.iter().any(|t| t.abs() > NANOSECONDS) {
+            true => 1e-9,
+            false => 1.0,
+        };
+        let start = origin(values);
+        return Ok(values.iter().map(|t| (t - start) * scale).collect());
+    }
+    let ends = |suffixes: &[&str]| {
+        header.iter().position(|column| {
+            let column = normalize(column);
+            suffixes.iter().any(|suffix| column.ends_with(suffix))
+        })
+    };
+    let (Some(sec), Some(nanosec)) = (
+        ends(&["stamp.sec", "stamp.secs"]),
+        ends(&["stamp.nanosec", "stamp.nsecs"]),
+    ) else {
+        return Err(format!(
+            "No time column, expected one of {} or header stamp sec and nanosec",
+            TIME_COLUMNS.join(", ")
+        ));
+    };
+    let (sec, nanosec) = (
+        numbers(header, columns, sec)?,
+        numbers(header, columns, nanosec)?,
+    );
+    let (sec_start, nanosec_start) = (origin(sec), origin(nanosec));
+    Ok(sec
+        .iter()
+        .zip(nanosec)
+        .map(|(s, ns)| (s - sec_start) + (ns - nanosec_start) * 1e-9)
+        .collect())
+}
+
+/// Columns `stem` followed by an index, as position0 or field.name_5, ordered by index
+fn indexed(header: &[String], stem: &str) -> Vec<usize> {
+    let mut found: Vec<(usize, usize)> = header
+        .iter()
+        .enumerate()
+        .filter_map(|(i, column)| {
+            let rest = normalize(column).strip_prefix(stem)?.to_string();
+            let digits = rest.trim_start_matches(['_', '.']);
+            digits.parse::<usize>().ok().map(|index| (index, i))
+        })
+        .collect();
+    found.sort();
+    found.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Items of a list written as text, e.g. [0.1, 0.2], (0.1 0.2) or ['joint_1', 'joint_2']
+fn list(cell: &str) -> Vec<String> {
+    cell.trim()
+        .trim_start_matches(['[', '('])
+        .trim_end_matches([']', ')'])
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .map(|item| item.trim_matches(['\'', '"']).to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// The messages of a joint_states log. Accepts one column per joint (position0.. with
+/// optional name0.., as `rostopic echo -p` writes), list cells (position and name holding
+/// all joints of a message) or one row per joint and message (name and position columns,
+/// consecutive rows of equal time forming a message). With `relative_time` times start at 0.
+pub fn samples(
+    header: &[String],
+    columns: &[Column],
+    relative_time: bool,
+) -> Result<Vec<Sample>, String> {
+    let times = times(header, columns, relative_time)?;
+    let position = find(header, &["position", "positions"]);
+    let name = find(header, &["name", "names"]);
+    let wide = indexed(header, "position");
+    if !wide.is_empty() {
+        let names = indexed(header, "name");
+        let positions: Vec<&[f64]> = wide
+            .iter()
+            .map(|&i| numbers(header, columns, i))
+            .collect::<Result<_, _>>()?;
+        let names: Vec<&[String]> = names
+            .iter()
+            .map(|&i| text(header, columns, i))
+            .collect::<Result<_, _>>()?;
+        return Ok((0..times.len())
+            .map(|row| Sample {
+                t: times[row],
+                names: (!names.is_empty()).then(|| names.iter().map(|n| n[row].clone()).collect()),
+                positions: positions.iter().map(|p| p[row]).collect(),
+            })
+            .collect());
+    }
+    let Some(position) = position else {
+        return Err("No position columns, expected position0.., or position".to_string());
+    };
+    let names = name.map(|i| text(header, columns, i)).transpose()?;
+    match &columns[position] {
+        Column::Text(cells) => (0..times.len())
+            .map(|row| {
+                let positions = list(&cells[row])
+                    .iter()
+                    .map(|value| value.parse::<f64>())
+                    .collect::<Result<Vec<f64>, _>>()
+                    .map_err(|_| format!("Row {} has positions that are not numbers", row))?;
+                Ok(Sample {
+                    t: times[row],
+                    names: names.map(|names| list(&names[row])),
+                    positions,
+                })
+            })
+            .collect(),
+        Column::Numbers(values) => {
+            let Some(names) = names else {
+                return Err("One position per row needs a name column".to_string());
+            };
+            let mut samples: Vec<Sample> = Vec::new();
+            for (row, (t, value)) in times.iter().zip(values).enumerate() {
+                match samples.last_mut() {
+                    Some(sample) if sample.t == *t => {
+                        sample
+                            .names
+                            .get_or_insert_with(Vec::new)
+                            .push(names[row].clone());
+                        sample.positions.push(*value);
+                    }
+                    _ => samples.push(Sample {
+                        t: *t,
+                        names: Some(vec![names[row].clone()]),
+                        positions: vec![*value],
+                    }),
+                }
+            }
+            Ok(samples)
+        }
+    }
+}
+
+/// Number a joint name ends with, as 1 in joint_1 or a1
+fn trailing_number(name: &str) -> Option<usize> {
+    let digits = name.len() - name.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    name[name.len() - digits..].parse().ok()
+}
+
+/// The six arm joints of a log in J1..J6 order: `joint_names` if given, else the six
+/// joints of the first message ordered by the number their names end with
+fn arm_joints(samples: &[Sample], joint_names: Option<&[String]>) -> Result<Vec<String>, String> {
+    if let Some(joint_names) = joint_names {
+        return Ok(joint_names.to_vec());
+    }
+    let Some(names) = samples.first().and_then(|sample| sample.names.as_ref()) else {
+        return Ok(vec![]);
+    };
+    if names.len() != 6 {
+        return Err(format!(
+            "The log has {} joints ({}), give the joint_names of J1..J6",
+            names.len(),
+            names.join(", ")
+        ));
+    }
+    let mut names = names.clone();
+    let mut numbers: Vec<Option<usize>> = names.iter().map(|name| trailing_number(name)).collect();
+    numbers.sort();
+    numbers.dedup();
+    if numbers.len() == 6 && numbers.iter().all(Option::is_some) {
+        names.sort_by_key(|name| trailing_number(name));
+    }
+    Ok(names)
+}
+
+/// Joints of every message in J1..J6 order, looked up by name where the log has names
+pub fn joints(
+    samples: &[Sample],
+    joint_names: Option<&[String]>,
+) -> Result<Vec<(f64, Joints)>, String> {
+    let arm = arm_joints(samples, joint_names)?;
+    samples
+        .iter()
+        .enumerate()
+        .map(|(row, sample)| {
+            let positions: Vec<f64> = match &sample.names {
+                Some(names) => arm
+                    .iter()
+                    .map(|joint| {
+                        names
+                            .iter()
+                            .position(|name| name == joint)
+                            .and_then(|k| sample.positions.get(k).copied())
+                            .ok_or_else(|| format!("Message {} has no joint '{}'", row, joint))
+                    })
+                    .collect::<Result<_, _>>()?,
+                None if joint_names.is_some() => {
+                    return Err("The log has no joint names to look joint_names up in".to_string())
+                }
+                None => sample.positions.clone(),
+            };
+            let joints: Joints = positions.try_into().map_err(|positions: Vec<f64>| {
+                format!("Message {} has {} joints, expected 6", row, positions.len())
+            })?;
+            Ok((sample.t, joints))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::parse_csv;
+
+    fn read(csv: &str, joint_names: Option<&[String]>) -> Result<Vec<(f64, Joints)>, String> {
+        let (header, columns) = parse_csv(csv)?;
+        joints(&samples(&header, &columns, false)?, joint_names)
+    }
+
+    #[test]
+    fn test_joint_log_formats() {
+        // rostopic echo -p, names out of order and times in nanoseconds
+        let wide = "%time,field.name0,field.name1,field.name2,field.name3,field.name4,field.name5,\
+                    field.position0,field.position1,field.position2,field.position3,field.position4,field.position5\n\
+                    1700000000500000000,joint_2,joint_1,joint_3,joint_4,joint_5,joint_6,0.2,0.1,0.3,0.4,0.5,0.6\n";
+        let [(t, joints)] = read(wide, None).unwrap().try_into().unwrap();
+        assert!((t - 1_700_000_000.5).abs() < 1e-3);
+        let (header, columns) = parse_csv(
+            "header.stamp.sec,header.stamp.nanosec,position0\n\
+                                           1700000000,999000000,1\n1700000001,3000000,2\n",
+        )
+        .unwrap();
+        let relative = samples(&header, &columns, true).unwrap();
+        assert!((relative[1].t - 0.004).abs() < 1e-12);
+        assert_eq!(joints, [0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+
+        // List cells with a gripper joint, which needs the arm joints named
+        let cells = "t,name,position\n\
+                     0.0,\"['a1', 'a2', 'a3', 'a4', 'a5', 'a6', 'finger']\",\"[1, 2, 3, 4, 5, 6, 0.01]\"\n";
+        assert!(read(cells, None).is_err());
+        let names: Vec<String> = (1..=6).map(|k| format!("a{}", k)).collect();
+        assert_eq!(
+            read(cells, Some(&names)).unwrap()[0].1,
+            [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]
+        );
+
+        // One row per joint and message
+        let long: String = std::iter::once("t,name,position\n".to_string())
+            .chain((0..2).flat_map(|t| {
+                (1..=6)
+                    .rev()
+                    .map(move |k| format!("{},joint_{},{}\n", t, k, k * 10 + t))
+            }))
+            .collect();
+        let rows = read(&long, None).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1], (1.0, [11.0, 21.0, 31.0, 41.0, 51.0, 61.0]));
+
+        assert!(read("t,x\n0,1\n", None).is_err());
+        assert!(read("position0\n1\n", None).is_err());
+    }
+}
@@ -1,7 +1,80 @@
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
+use nalgebra::{Isometry3, Vector3};
+
+use rs_opw_kinematics::kinematic_traits::{Joints, Kinematics};
 use rs_opw_kinematics::kinematics_impl::OPWKinematics;
 use rs_opw_kinematics::parameters::opw_kinematics::Parameters;
+use std::f64::consts::PI;
+
+use crate::calibration;
+use crate::dict;
+use crate::models;
+use crate::pose::{pose_errors, to_isometry, PyPose};
+
+/// Converts offsets given in `unit` ("radians" or "degrees") to radians
+fn offsets_to_radians(offsets: [f64; 6], unit: &str) -> PyResult<[f64; 6]> {
+    match unit {
+        "radians" => Ok(offsets),
+        "degrees" => Ok(offsets.map(f64::to_radians)),
+        _ => Err(PyValueError::new_err(format!(
+            "Unknown offsets unit '{}', expected 'radians' or 'degrees'",
+            unit
+        ))),
+    }
+}
+
+const KEYS: [&str; 12] = [
+    "a1",
+    "a2",
+    "b",
+    "c1",
+    "c2",
+    "c3",
+    "c4",
+    "offsets",
+    "offsets_unit",
+    "sign_corrections",
+    "has_parallelogram",
+    "wrist_coupling",
+];
+
+/// The wrist center of a pose and its distance from the J2 axis, for the shoulder side that
+/// brings it closest to the shell between `min_reach` and `max_reach` the arm can reach
+pub struct WristReach {
+    /// Wrist center in the robot base frame
+    pub center: Vector3<f64>,
+    pub reach: f64,
+    pub min_reach: f64,
+    pub max_reach: f64,
+    /// How far the wrist center lies inside the cylinder of radius b around J1 it cannot enter
+    pub offset_deficit: f64,
+}
+
+impl WristReach {
+    /// How far (m) the wrist center lies outside the reachable shell, 0 if inside
+    pub fn deficit(&self) -> f64 {
+        let shell = (self.reach - self.max_reach).max(self.min_reach - self.reach);
+        shell.max(self.offset_deficit).max(0.0)
+    }
+
+    /// Whether the wrist center lies beyond the outer radius of the shell
+    pub fn beyond(&self) -> bool {
+        self.reach > self.max_reach
+    }
+
+    /// Distances (m) of the wrist center inside the outer sphere, outside the interior void
+    /// and outside the cylinder around J1, each negative on the wrong side
+    pub fn margins(&self) -> [f64; 3] {
+        [
+            self.max_reach - self.reach,
+            self.reach - self.min_reach,
+            -self.offset_deficit,
+        ]
+    }
+}
 
 #[pyclass(frozen)] // Declare the class as frozen to provide immutability.
 #[pyo3(from_py_object)]
@@ -16,9 +89,97 @@ pub struct KinematicModel {
     pub c4: f64,
     pub offsets: [f64; 6],
     pub sign_corrections: [i8; 6],
+    /// J3 is reported relative to J2, as by robots with a parallelogram linkage
+    pub has_parallelogram: bool,
+    /// Ratios (J4 -> J6, J5 -> J6) by which mechanically coupled wrist axes move J6
+    pub wrist_coupling: [f64; 2],
 }
 
 impl KinematicModel {
+    pub fn from_parameters(parameters: &Parameters) -> Self {
+        KinematicModel {
+            a1: parameters.a1,
+            a2: parameters.a2,
+            b: parameters.b,
+            c1: parameters.c1,
+            c2: parameters.c2,
+            c3: parameters.c3,
+            c4: parameters.c4,
+            offsets: parameters.offsets,
+            sign_corrections: parameters.sign_corrections,
+            has_parallelogram: false,
+            wrist_coupling: [0.0; 2],
+        }
+    }
+
+    /// Converts joints as the controller reports them to the joints of the kinematic
+    /// model (radians). With a parallelogram the kinematic J3 is J3 - J2, with wrist
+    /// coupling the kinematic J6 is J6 - k4 * J4 - k5 * J5.
+    pub fn decouple(&self, joints: &Joints) -> Joints {
+        let mut joints = *joints;
+        if self.has_parallelogram {
+            joints[2] -= joints[1];
+        }
+        joints[5] -= self.wrist_coupling[0] * joints[3] + self.wrist_coupling[1] * joints[4];
+        joints
+    }
+
+    /// Inverse of `decouple`, kinematic model joints to controller joints (radians)
+    pub fn couple(&self, joints: &Joints) -> Joints {
+        let mut joints = *joints;
+        if self.has_parallelogram {
+            joints[2] += joints[1];
+        }
+        joints[5] += self.wrist_coupling[0] * joints[3] + self.wrist_coupling[1] * joints[4];
+        joints
+    }
+
+    /// Distance of the wrist center from the J2 axis for controller joints (radians), as a
+    /// share of the longest the arm can reach: 1 stretched out, the smallest when folded
+    pub fn extension(&self, joints: &Joints) -> f64 {
+        let joints = self.decouple(joints);
+        let q3 = joints[2] * self.sign_corrections[2] as f64 - self.offsets[2];
+        let forearm = self.a2.hypot(self.c3);
+        let psi3 = self.a2.atan2(self.c3);
+        let reach =
+            (self.c2 * self.c2 + forearm * forearm + 2.0 * self.c2 * forearm * (q3 + psi3).cos())
+                .max(0.0)
+                .sqrt();
+        reach / (self.c2 + forearm)
+    }
+
+    /// How far (m) the wrist center of a flange pose in the robot base frame lies outside
+    /// the shell the arm can reach, 0 if it is inside. Joint limits are not considered.
+    pub fn reach_deficit(&self, flange: &Isometry3<f64>) -> f64 {
+        self.wrist_reach(flange).deficit()
+    }
+
+    /// Where the wrist center of a flange pose in the robot base frame lies relative to the
+    /// shell the arm can reach
+    pub fn wrist_reach(&self, flange: &Isometry3<f64>) -> WristReach {
+        let center = flange.translation.vector - self.c4 * (flange.rotation * Vector3::z());
+        let rho = center.x.hypot(center.y);
+        let radial = (rho * rho - self.b * self.b).max(0.0).sqrt();
+        let height = center.z - self.c1;
+        let forearm = self.a2.hypot(self.c3);
+        let (min_reach, max_reach) = ((self.c2 - forearm).abs(), self.c2 + forearm);
+        let shell = |reach: f64| (reach - max_reach).max(min_reach - reach);
+        // The shoulder can point at the wrist center or away from it, whichever leaves it
+        // deepest inside the shell
+        let reach = [radial - self.a1, radial + self.a1]
+            .map(|r| r.hypot(height))
+            .into_iter()
+            .min_by(|a, b| shell(*a).total_cmp(&shell(*b)))
+            .unwrap_or(f64::NAN);
+        WristReach {
+            center,
+            reach,
+            min_reach,
+            max_reach,
+            offset_deficit: self.b.abs() - rho,
+        }
+    }
+
     pub fn to_opw_kinematics(&self) -> OPWKinematics {
         OPWKinematics::new(Parameters {
             a1: self.a1,
@@ -33,6 +194,39 @@ impl KinematicModel {
             dof: 6,
         })
     }
+
+    /// Logs parameters that are valid but most likely a mistake
+    fn warn_if_suspicious(&self) {
+        let lengths = [self.a1, self.a2, self.b, self.c1, self.c2, self.c3, self.c4];
+        if lengths.iter().any(|x| !x.is_finite()) {
+            log::warn!("Kinematic model has non-finite link lengths: {:?}", lengths);
+        }
+        if self.c2 <= 0.0 || self.c3 <= 0.0 {
+            log::warn!(
+                "Kinematic model has a non-positive arm length (c2 = {}, c3 = {})",
+                self.c2,
+                self.c3
+            );
+        }
+        if lengths.iter().any(|x| x.abs() > 10.0) {
+            log::warn!("Kinematic model has link lengths over 10, lengths are expected in meters");
+        }
+        if self.offsets.iter().any(|x| x.abs() > 2.0 * PI) {
+            log::warn!(
+                "Joint offsets {:?} exceed a full turn, offsets are expected in radians",
+                self.offsets
+            );
+        }
+        if self.wrist_coupling.iter().any(|k| !k.is_finite()) {
+            log::warn!("Wrist coupling {:?} is not finite", self.wrist_coupling);
+        }
+        if self.sign_corrections.iter().any(|s| s.abs() != 1) {
+            log::warn!(
+                "Sign corrections {:?} should all be 1 or -1",
+                self.sign_corrections
+            );
+        }
+    }
 }
 
 #[pymethods]
@@ -48,6 +242,9 @@ impl KinematicModel {
         c4 = 0.0,
         offsets = (0.0, 0.0, 0.0, 0.0, 0.0, 0.0),
         sign_corrections = (1, 1, 1, 1, 1, 1),
+        offsets_unit = "radians",
+        has_parallelogram = false,
+        wrist_coupling = (0.0, 0.0),
     ))]
     #[allow(clippy::too_many_arguments)]
     pub fn new(
@@ -60,8 +257,11 @@ impl KinematicModel {
         c4: f64,
         offsets: (f64, f64, f64, f64, f64, f64),
         sign_corrections: (i8, i8, i8, i8, i8, i8),
+        offsets_unit: &str,
+        has_parallelogram: bool,
+        wrist_coupling: (f64, f64),
     ) -> PyResult<Self> {
-        Ok(KinematicModel {
+        let model = KinematicModel {
             a1,
             a2,
             b,
@@ -69,9 +269,13 @@ impl KinematicModel {
             c2,
             c3,
             c4,
-            offsets: offsets.into(),
+            offsets: offsets_to_radians(offsets.into(), offsets_unit)?,
             sign_corrections: sign_corrections.into(),
-        })
+            has_parallelogram,
+            wrist_coupling: wrist_coupling.into(),
+        };
+        model.warn_if_suspicious();
+        Ok(model)
     }
 
     // Getter methods to provide access to attributes since the class is frozen.
@@ -120,11 +324,274 @@ impl KinematicModel {
         self.sign_corrections.to_vec() // Convert the array to a Vec for easier handling in Python.
     }
 
+    #[getter]
+    pub fn has_parallelogram(&self) -> bool {
+        self.has_parallelogram
+    }
+
+    #[getter]
+    pub fn wrist_coupling(&self) -> (f64, f64) {
+        self.wrist_coupling.into()
+    }
+
+    /// Creates the model of a robot from the built-in library, e.g. "ABB IRB 2400/10"
+    #[staticmethod]
+    pub fn from_model(name: &str) -> PyResult<Self> {
+        models::model_by_name(name)
+    }
+
+    /// Finds the sign corrections and quarter/half turn offsets that reproduce the poses
+    /// reported by the controller for the given joints (degrees). Poses are of the flange
+    /// in the robot base frame. Returns (model, max position error, max rotation error in
+    /// degrees) for every matching combination, best first.
+    #[pyo3(signature = (samples, position_tolerance=1e-3, rotation_tolerance=0.1))]
+    pub fn detect_signs_and_offsets(
+        &self,
+        samples: Vec<([f64; 6], PyPose)>,
+        position_tolerance: f64,
+        rotation_tolerance: f64,
+    ) -> PyResult<Vec<(KinematicModel, f64, f64)>> {
+        if samples.is_empty() {
+            return Err(PyValueError::new_err("At least one sample is required"));
+        }
+        let samples: Vec<_> = samples
+            .iter()
+            .map(|(joints, pose)| (joints.map(f64::to_radians), to_isometry(pose.0, pose.1)))
+            .collect();
+        let matches = calibration::detect_signs_and_offsets(
+            self,
+            &samples,
+            position_tolerance,
+            rotation_tolerance.to_radians(),
+        );
+        if matches.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "No sign/offset combination reproduces the samples within {} m and {} degrees, \
+                 check the kinematic parameters",
+                position_tolerance, rotation_tolerance
+            )));
+        }
+        Ok(matches
+            .into_iter()
+            .map(|m| (m.model, m.position_error, m.rotation_error.to_degrees()))
+            .collect())
+    }
+
+    /// Runs forward kinematics for golden (joints in degrees, expected flange pose) test
+    /// cases and returns the (position error, rotation error in degrees) of each case.
+    /// Raises if any case exceeds the tolerances.
+    #[pyo3(signature = (test_cases, position_tolerance=1e-3, rotation_tolerance=0.1))]
+    pub fn check_against(
+        &self,
+        test_cases: Vec<([f64; 6], PyPose)>,
+        position_tolerance: f64,
+        rotation_tolerance: f64,
+    ) -> PyResult<Vec<(f64, f64)>> {
+        let robot = self.to_opw_kinematics();
+        let errors: Vec<(f64, f64)> = test_cases
+            .iter()
+            .map(|(joints, (translation, rotation))| {
+                let pose = robot.forward(&self.decouple(&joints.map(f64::to_radians)));
+                let (position_error, rotation_error) =
+                    pose_errors(&pose, &to_isometry(*translation, *rotation));
+                (position_error, rotation_error.to_degrees())
+            })
+            .collect();
+
+        let failures: Vec<String> = errors
+            .iter()
+            .enumerate()
+            .filter(|(_, (position_error, rotation_error))| {
+                !(*position_error <= position_tolerance && *rotation_error <= rotation_tolerance)
+            })
+            .map(|(i, (position_error, rotation_error))| {
+                format!(
+                    "case {}: {:.6} m, {:.4} deg",
+                    i, position_error, rotation_error
+                )
+            })
+            .collect();
+        if !failures.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "{} of {} test cases exceed {} m / {} deg: {}",
+                failures.len(),
+                errors.len(),
+                position_tolerance,
+                rotation_tolerance,
+                failures.join("; ")
+            )));
+        }
+        Ok(errors)
+    }
+
+    /// Returns a copy of the model with the given joint offsets, in radians unless
+    /// `offsets_unit` is "degrees"
+    #[pyo3(signature = (offsets, offsets_unit="radians"))]
+    pub fn with_offsets(
+        &self,
+        offsets: (f64, f64, f64, f64, f64, f64),
+        offsets_unit: &str,
+    ) -> PyResult<Self> {
+        Ok(KinematicModel {
+            offsets: offsets_to_radians(offsets.into(), offsets_unit)?,
+            ..self.clone()
+        })
+    }
+
+    /// Returns a copy of the model with the given sign corrections
+    pub fn with_sign_corrections(&self, sign_corrections: (i8, i8, i8, i8, i8, i8)) -> Self {
+        KinematicModel {
+            sign_corrections: sign_corrections.into(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns a copy of the model with the sign corrections and offsets of a vendor's
+    /// joint convention ("abb", "fanuc" or "kuka"), so that joints entered as the pendant
+    /// shows them give the right poses. The parameters must follow the usual OPW
+    /// description, a2 the elbow offset and c3 the forearm.
+    pub fn with_convention(&self, vendor: &str) -> PyResult<Self> {
+        let convention = models::convention(vendor)?;
+        Ok(KinematicModel {
+            sign_corrections: convention.sign_corrections,
+            offsets: convention.offsets,
+            ..self.clone()
+        })
+    }
+
+    /// Returns a copy of the model with the given kinematic parameters replaced
+    #[pyo3(signature = (*, a1=None, a2=None, b=None, c1=None, c2=None, c3=None, c4=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_param(
+        &self,
+        a1: Option<f64>,
+        a2: Option<f64>,
+        b: Option<f64>,
+        c1: Option<f64>,
+        c2: Option<f64>,
+        c3: Option<f64>,
+        c4: Option<f64>,
+    ) -> Self {
+        KinematicModel {
+            a1: a1.unwrap_or(self.a1),
+            a2: a2.unwrap_or(self.a2),
+            b: b.unwrap_or(self.b),
+            c1: c1.unwrap_or(self.c1),
+            c2: c2.unwrap_or(self.c2),
+            c3: c3.unwrap_or(self.c3),
+            c4: c4.unwrap_or(self.c4),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the model as a dictionary with the constructor's keyword names
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("a1", self.a1)?;
+        dict.set_item("a2", self.a2)?;
+        dict.set_item("b", self.b)?;
+        dict.set_item("c1", self.c1)?;
+        dict.set_item("c2", self.c2)?;
+        dict.set_item("c3", self.c3)?;
+        dict.set_item("c4", self.c4)?;
+        dict.set_item("offsets", self.offsets)?;
+        dict.set_item("sign_corrections", self.sign_corrections)?;
+        dict.set_item("has_parallelogram", self.has_parallelogram)?;
+        dict.set_item("wrist_coupling", self.wrist_coupling)?;
+        Ok(dict)
+    }
+
+    /// Creates a model from a dictionary, missing keys take the constructor defaults
+    #[staticmethod]
+    pub fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(data, &KEYS, "KinematicModel")?;
+        let offsets_unit: Option<String> = dict::optional(data, "offsets_unit")?;
+        Ok(KinematicModel {
+            a1: dict::optional(data, "a1")?.unwrap_or(0.0),
+            a2: dict::optional(data, "a2")?.unwrap_or(0.0),
+            b: dict::optional(data, "b")?.unwrap_or(0.0),
+            c1: dict::optional(data, "c1")?.unwrap_or(0.0),
+            c2: dict::optional(data, "c2")?.unwrap_or(0.0),
+            c3: dict::optional(data, "c3")?.unwrap_or(0.0),
+            c4: dict::optional(data, "c4")?.unwrap_or(0.0),
+            offsets: offsets_to_radians(
+                dict::optional(data, "offsets")?.unwrap_or([0.0; 6]),
+                offsets_unit.as_deref().unwrap_or("radians"),
+            )?,
+            sign_corrections: dict::optional(data, "sign_corrections")?.unwrap_or([1; 6]),
+            has_parallelogram: dict::optional(data, "has_parallelogram")?.unwrap_or(false),
+            wrist_coupling: dict::optional(data, "wrist_coupling")?.unwrap_or([0.0; 2]),
+        })
+    }
+
     pub fn __repr__(&self) -> String {
         format!(
-            "KinematicModel(\n    a1={},\n    a2={},\n    b={},\n    c1={},\n    c2={},\n    c3={},\n    c4={},\n    offsets={:?},\n    sign_corrections={:?}\n)",
+            "KinematicModel(\n    a1={},\n    a2={},\n    b={},\n    c1={},\n    c2={},\n    c3={},\n    c4={},\n    offsets={:?},\n    sign_corrections={:?},\n    has_parallelogram={},\n    wrist_coupling={:?}\n)",
             self.a1, self.a2, self.b, self.c1, self.c2, self.c3, self.c4,
-            self.offsets, self.sign_corrections
+            self.offsets, self.sign_corrections, self.has_parallelogram, self.wrist_coupling
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::pose::from_isometry;
+    use crate::tests::ABB_1660;
+    use rs_opw_kinematics::kinematic_traits::Kinematics;
+
+    #[test]
+    fn test_check_against() {
+        let joints = [10.0, -20.0, 30.0, 40.0, 50.0, 60.0];
+        let pose = from_isometry(
+            &ABB_1660
+                .to_opw_kinematics()
+                .forward(&joints.map(f64::to_radians)),
+        );
+        let errors = ABB_1660
+            .check_against(vec![(joints, pose)], 1e-9, 1e-6)
+            .unwrap();
+        assert!(errors[0].0 < 1e-12);
+
+        let shifted = ([pose.0[0] + 0.01, pose.0[1], pose.0[2]], pose.1);
+        assert!(ABB_1660
+            .check_against(vec![(joints, pose), (joints, shifted)], 1e-3, 0.1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_reach_deficit() {
+        let robot = ABB_1660.to_opw_kinematics();
+        let flange = robot.forward(&[0.1, -0.4, 0.3, 1.0, 0.8, -0.5]);
+        assert!(ABB_1660.reach_deficit(&flange) == 0.0);
+
+        let mut far = flange;
+        far.translation.vector.x += 5.0;
+        let deficit = ABB_1660.reach_deficit(&far);
+        assert!(deficit > 4.0 && deficit < 5.0, "{}", deficit);
+    }
+
+    #[test]
+    fn test_with_copies_leave_original_untouched() {
+        let model = ABB_1660.with_param(Some(0.2), None, None, None, None, Some(0.7), None);
+        assert_eq!((model.a1, model.a2, model.c3), (0.2, -0.110, 0.7));
+        assert_eq!(ABB_1660.a1, 0.150);
+
+        let model = model.with_sign_corrections((1, -1, -1, 1, 1, 1));
+        assert_eq!(model.sign_corrections, [1, -1, -1, 1, 1, 1]);
+        assert_eq!(model.offsets, ABB_1660.offsets);
+
+        let model = model
+            .with_offsets((0.0, 0.0, 0.0, 0.0, 0.0, 0.1), "radians")
+            .unwrap();
+        assert_eq!(model.offsets, [0.0, 0.0, 0.0, 0.0, 0.0, 0.1]);
+        assert_eq!(model.a1, 0.2);
+
+        let model = model
+            .with_offsets((0.0, 0.0, -90.0, 0.0, 0.0, 0.0), "degrees")
+            .unwrap();
+        assert_eq!(model.offsets, ABB_1660.offsets);
+        assert!(model
+            .with_offsets((0.0, 0.0, 0.0, 0.0, 0.0, 0.0), "turns")
+            .is_err());
+    }
+}
@@ -0,0 +1,710 @@
+use nalgebra::{Isometry3, Translation3, UnitQuaternion};
+use std::collections::HashMap;
+
+use crate::frames::WORLD;
+use crate::program::{Motion, Target, Waypoint};
+use crate::rapid::{decimal, split_top, target_names, Identifiers, Module};
+
+/// KRL positions are in millimeters
+const MM: f64 = 1000.0;
+
+/// Data types read from declarations: Cartesian points and frames, axis points and the
+/// motion parameters of inline forms
+const TYPES: [&str; 8] = [
+    "E6POS", "POS", "FRAME", "E6AXIS", "AXIS", "FDAT", "PDAT", "LDAT",
+];
+
+/// A data value: a number, bool, string, name (including #enums and $variables) or
+/// structure {KEY value, ..}
+#[derive(Debug, PartialEq)]
+enum Value {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+    Name(String),
+    Structure(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Structure(items) => items
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(key))
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn number(&self, key: &str) -> Result<f64, String> {
+        match self.get(key) {
+            Some(Value::Number(number)) => Ok(*number),
+            Some(value) => Err(format!("Expected a number for {}, got {:?}", key, value)),
+            None => Err(format!("No {} in {:?}", key, self)),
+        }
+    }
+
+    fn has(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// A frame {X, Y, Z, A, B, C}: position in millimeters, then rotations in degrees
+    /// about z, the new y and the newer x
+    fn frame(&self) -> Result<Isometry3<f64>, String> {
+        let [x, y, z, a, b, c] = ["X", "Y", "Z", "A", "B", "C"].map(|key| self.number(key));
+        let rotation =
+            UnitQuaternion::from_euler_angles(c?.to_radians(), b?.to_radians(), a?.to_radians());
+        Ok(Isometry3::from_parts(
+            Translation3::new(x? / MM, y? / MM, z? / MM),
+            rotation,
+        ))
+    }
+
+    /// Axis values A1..A6 in radians
+    fn axes(&self) -> Result<[f64; 6], String> {
+        let mut joints = [0.0; 6];
+        for (i, joint) in joints.iter_mut().enumerate() {
+            *joint = self.number(&format!("A{}", i + 1))?.to_radians();
+        }
+        Ok(joints)
+    }
+
+    /// Target of a move: a Cartesian point as a TCP pose in the base, or axis values
+    fn target(&self) -> Result<Target, String> {
+        match (self.has("A1"), self.has("X")) {
+            (true, _) => Ok(Target::Joints(self.axes()?)),
+            (false, true) => Ok(Target::Pose(self.frame()?)),
+            _ => Err(format!("Cannot read the target {:?}", self)),
+        }
+    }
+}
+
+/// Text up to a `;` comment outside strings
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn is_name(text: &str) -> bool {
+    let text = text.strip_prefix(['#', '$']).unwrap_or(text);
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    if let Some(inner) = text.strip_prefix('{').and_then(|t| t.strip_suffix('}')) {
+        // A structure may start with its type, as in {E6POS: X 10, ..}
+        let inner = match inner.split_once(':') {
+            Some((head, rest)) if is_name(head.trim()) => rest,
+            _ => inner,
+        };
+        return split_top(inner, ',')
+            .into_iter()
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                let (key, value) = item
+                    .split_once(char::is_whitespace)
+                    .ok_or_else(|| format!("Cannot read '{}' in a structure", item))?;
+                Ok((key.to_string(), parse_value(value)?))
+            })
+            .collect::<Result<_, String>>()
+            .map(Value::Structure);
+    }
+    if let Some(inner) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        return Ok(Value::Text(inner.to_string()));
+    }
+    match text.to_ascii_uppercase().as_str() {
+        "TRUE" => return Ok(Value::Bool(true)),
+        "FALSE" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+    if let Ok(number) = text.replace(' ', "").parse::<f64>() {
+        return Ok(Value::Number(number));
+    }
+    match is_name(text) {
+        true => Ok(Value::Name(text.to_string())),
+        false => Err(format!(
+            "Cannot read '{}', expressions are not supported",
+            text
+        )),
+    }
+}
+
+/// Statements of a file with the routine they are in, and the module name
+fn statements(text: &str) -> (Option<String>, Vec<(Option<String>, String)>) {
+    let (mut module, mut routine) = (None, None);
+    let mut statements = Vec::new();
+    for line in text.lines() {
+        let line = strip_comment(line).trim();
+        // &ACCESS and &REL are editor attributes
+        if line.is_empty() || line.starts_with('&') {
+            continue;
+        }
+        let mut words = line
+            .split(|c: char| c.is_whitespace() || c == '(')
+            .filter(|word| !word.is_empty());
+        let mut first = words.next().unwrap_or("").to_ascii_uppercase();
+        if first == "GLOBAL" {
+            first = words.next().unwrap_or("").to_ascii_uppercase();
+        }
+        match first.as_str() {
+            "DEF" | "DEFDAT" => {
+                let name = words.next().map(String::from);
+                if module.is_none() {
+                    module = name.clone();
+                }
+                if first == "DEF" {
+                    routine = name;
+                }
+            }
+            // DEFFCT names the return type first
+            "DEFFCT" => routine = words.nth(1).map(String::from),
+            "END" | "ENDFCT" => routine = None,
+            "ENDDAT" => {}
+            _ => statements.push((routine.clone(), line.to_string())),
+        }
+    }
+    (module, statements)
+}
+
+/// Declarations and structure assignments by lowercase name, as TOOL_DATA[1]: type (empty
+/// for assignments) and value
+fn declarations(
+    statements: &[(Option<String>, String)],
+    data: &mut HashMap<String, (String, Value)>,
+) -> Result<(), String> {
+    for (routine, statement) in statements {
+        let mut words: Vec<&str> = statement.split_whitespace().collect();
+        let upper = |word: &&str| word.to_ascii_uppercase();
+        while words
+            .first()
+            .is_some_and(|w| ["DECL", "GLOBAL", "CONST"].contains(&upper(w).as_str()))
+        {
+            words.remove(0);
+        }
+        let data_type = match words.first().map(upper) {
+            Some(first) if TYPES.contains(&first.as_str()) => {
+                words.remove(0);
+                first
+            }
+            // Assignments outside routines fill arrays, as in $config.dat
+            _ if routine.is_none() => String::new(),
+            _ => continue,
+        };
+        let Some((name, value)) = words.join(" ").split_once('=').map(|(name, value)| {
+            (
+                name.split_whitespace().collect::<String>(),
+                value.to_string(),
+            )
+        }) else {
+            continue;
+        };
+        let value = parse_value(&value).map_err(|e| format!("{}: {}", name, e))?;
+        data.insert(name.to_ascii_lowercase(), (data_type, value));
+    }
+    Ok(())
+}
+
+struct Reader {
+    data: HashMap<String, (String, Value)>,
+}
+
+impl Reader {
+    fn lookup(&self, name: &str) -> Option<&(String, Value)> {
+        // KRL names are case-insensitive
+        self.data.get(&name.to_ascii_lowercase())
+    }
+
+    /// Tool (`array` "tool") or base ("base") number `index` of the controller: its name,
+    /// TOOL_NAME[n] or tool{n} unless unnamed, and frame. 0 is the flange or the world.
+    fn numbered(&self, array: &str, index: f64) -> Result<(String, Isometry3<f64>), String> {
+        if index == 0.0 {
+            let name = if array == "tool" { "tool0" } else { WORLD };
+            return Ok((name.to_string(), Isometry3::identity()));
+        }
+        let key = format!("{}_DATA[{}]", array.to_ascii_uppercase(), index);
+        let (_, value) = self
+            .lookup(&key)
+            .ok_or_else(|| format!("Unknown {}, pass the $config.dat that defines it", key))?;
+        let name = match self.lookup(&format!("{}_name[{},]", array, index)) {
+            Some((_, Value::Text(name))) if !name.trim().is_empty() => name.trim().to_string(),
+            _ => format!("{}{}", array, index),
+        };
+        Ok((name, value.frame()?))
+    }
+
+    /// Tool or base assigned to $TOOL or $BASE: a numbered frame, the null frame or a
+    /// FRAME variable
+    fn assigned(&self, array: &str, text: &str) -> Result<(String, Isometry3<f64>), String> {
+        let text: String = text.split_whitespace().collect();
+        let upper = text.to_ascii_uppercase();
+        if ["$NULLFRAME", "$WORLD", "$ROBROOT"].contains(&upper.as_str()) {
+            return self.numbered(array, 0.0);
+        }
+        let prefix = format!("{}_DATA[", array.to_ascii_uppercase());
+        if let Some(index) = upper
+            .strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            let index = index
+                .parse::<f64>()
+                .map_err(|_| format!("Cannot read the index of {}", text))?;
+            return self.numbered(array, index);
+        }
+        match self.lookup(&text) {
+            Some((_, value)) => Ok((text, value.frame()?)),
+            None => Err(format!("Unknown frame {}", text)),
+        }
+    }
+
+    fn structure(&self, name: &str, data_type: &str) -> Result<&Value, String> {
+        match self.lookup(name.trim()) {
+            Some((declared, value)) if declared == data_type => Ok(value),
+            Some((declared, _)) => Err(format!(
+                "{} is a {}, expected {}",
+                name.trim(),
+                declared,
+                data_type
+            )),
+            None => Err(format!("Unknown {} {}", data_type, name.trim())),
+        }
+    }
+}
+
+/// Tool, base and motion parameters in effect at a move
+#[derive(Default)]
+struct State {
+    tool: Option<(String, Isometry3<f64>)>,
+    base: Option<(String, Isometry3<f64>)>,
+    /// Path speed (m/s) of LIN
+    cp_speed: Option<f64>,
+    /// Approximation distances (m) of PTP and LIN
+    ptp_distance: Option<f64>,
+    cp_distance: Option<f64>,
+}
+
+/// Applies a statement selecting a tool or base or setting a motion parameter, ignoring
+/// any other
+fn read_setting(reader: &Reader, state: &mut State, statement: &str) -> Result<(), String> {
+    let number = |value: &str| {
+        value
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| format!("Cannot read the number {}", value.trim()))
+    };
+    let call: String = statement.split_whitespace().collect();
+    if let Some(arguments) = call
+        .to_ascii_uppercase()
+        .strip_prefix("BAS(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let (function, value) = arguments.split_once(',').unwrap_or((arguments, ""));
+        match function {
+            "#TOOL" => state.tool = Some(reader.numbered("tool", number(value)?)?),
+            "#BASE" => state.base = Some(reader.numbered("base", number(value)?)?),
+            "#CP_PARAMS" | "#VEL_CP" => state.cp_speed = Some(number(value)?),
+            _ => {}
+        }
+        return Ok(());
+    }
+    let Some((target, value)) = statement.split_once('=') else {
+        return Ok(());
+    };
+    let value = value.trim();
+    match target.trim().to_ascii_uppercase().as_str() {
+        "$TOOL" => state.tool = Some(reader.assigned("tool", value)?),
+        "$BASE" => state.base = Some(reader.assigned("base", value)?),
+        "$VEL.CP" => state.cp_speed = Some(number(value)?),
+        "$APO.CDIS" => {
+            state.ptp_distance = Some(number(value)? / MM);
+            state.cp_distance = state.ptp_distance;
+        }
+        "FDAT_ACT" => {
+            let fdat = reader.structure(value, "FDAT")?;
+            if matches!(fdat.get("IPO_FRAME"), Some(Value::Name(frame)) if frame.eq_ignore_ascii_case("#TCP"))
+            {
+                return Err("A stationary tool (IPO_FRAME #TCP) is not supported".to_string());
+            }
+            state.tool = Some(reader.numbered("tool", fdat.number("TOOL_NO")?)?);
+            state.base = Some(reader.numbered("base", fdat.number("BASE_NO")?)?);
+        }
+        "PDAT_ACT" => {
+            let pdat = reader.structure(value, "PDAT")?;
+            state.ptp_distance = Some(pdat.number("APO_DIST")? / MM);
+        }
+        "LDAT_ACT" => {
+            let ldat = reader.structure(value, "LDAT")?;
+            state.cp_speed = Some(ldat.number("VEL")?);
+            state.cp_distance = Some(ldat.number("APO_DIST")? / MM);
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Reads the moves of a KRL program: PTP, LIN, SPTP and SLIN to named or inline points, in
+/// the order they appear in `routine`, by default the first routine of `source`. Points,
+/// frames and the FDAT, PDAT and LDAT of inline forms are declared in `source` or `data`
+/// (the .dat file, then $config.dat for TOOL_DATA and BASE_DATA; earlier files take
+/// precedence). Tools and bases are selected by $TOOL, $BASE, BAS(#TOOL, n), BAS(#BASE, n)
+/// or FDAT_ACT. Control flow is not followed. Positions are converted to meters; Status
+/// and Turn are not kept, as they do not map onto the ABB axis configuration, and PTP
+/// velocities are percentages of the axis speeds, so joint moves have no speed.
+pub fn parse(source: &str, data: &[&str], routine: Option<&str>) -> Result<Module, String> {
+    let (module, statements) = statements(source);
+    let mut declared = HashMap::new();
+    for text in data.iter().rev() {
+        declarations(&self::statements(text).1, &mut declared)?;
+    }
+    declarations(&statements, &mut declared)?;
+    let reader = Reader { data: declared };
+    let routine = match routine {
+        Some(wanted) => statements
+            .iter()
+            .find_map(|(routine, _)| routine.as_ref().filter(|r| r.eq_ignore_ascii_case(wanted)))
+            .ok_or_else(|| format!("No routine {} with statements in the program", wanted))?,
+        None => statements
+            .iter()
+            .find_map(|(routine, _)| routine.as_ref())
+            .ok_or("The program has no routine")?,
+    };
+    let mut state = State::default();
+    let mut tools: Vec<(String, Isometry3<f64>)> = Vec::new();
+    let mut work_objects: Vec<(String, Isometry3<f64>)> = Vec::new();
+    let mut waypoints = Vec::new();
+    for (_, statement) in statements
+        .iter()
+        .filter(|(r, _)| r.as_ref().is_some_and(|r| r.eq_ignore_ascii_case(routine)))
+    {
+        let error = |e: String| format!("{}: {}", statement, e);
+        let (instruction, rest) = statement
+            .split_once(char::is_whitespace)
+            .unwrap_or((statement.as_str(), ""));
+        let upper = instruction.to_ascii_uppercase();
+        let motion = match upper.as_str() {
+            "PTP" | "SPTP" => Motion::Joint,
+            "LIN" | "SLIN" => Motion::Linear,
+            _ if ["PTP", "LIN", "CIRC", "SPTP", "SLIN", "SCIRC"]
+                .iter()
+                .any(|motion| upper.starts_with(motion)) =>
+            {
+                log::warn!("{} is not imported", instruction);
+                continue;
+            }
+            _ => {
+                read_setting(&reader, &mut state, statement).map_err(error)?;
+                continue;
+            }
+        };
+        // An inline point is a structure, possibly with spaces, before the approximation
+        let rest = rest.trim();
+        let (target_text, modifiers) = match rest.starts_with('{') {
+            true => rest.split_at(rest.find('}').map_or(rest.len(), |end| end + 1)),
+            false => rest.split_at(rest.find(char::is_whitespace).unwrap_or(rest.len())),
+        };
+        let (name, target) = match parse_value(target_text).map_err(error)? {
+            Value::Name(name) => {
+                let (_, value) = reader
+                    .lookup(&name)
+                    .ok_or_else(|| error(format!("Unknown point {}", name)))?;
+                (name, value.target().map_err(error)?)
+            }
+            value @ Value::Structure(_) => ("*".to_string(), value.target().map_err(error)?),
+            value => return Err(error(format!("Cannot read the target {:?}", value))),
+        };
+        let approximated = modifiers
+            .split_whitespace()
+            .any(|word| word.to_ascii_uppercase().starts_with("C_"));
+        let (speed, distance) = match motion {
+            Motion::Joint => (None, state.ptp_distance),
+            Motion::Linear => (state.cp_speed, state.cp_distance),
+        };
+        let zone = match approximated {
+            true => distance,
+            false => Some(0.0),
+        };
+        let (tool, tcp) = state
+            .tool
+            .get_or_insert_with(|| {
+                log::warn!("No tool is selected before the first move, the flange is assumed");
+                ("tool0".to_string(), Isometry3::identity())
+            })
+            .clone();
+        let (base, frame) = state
+            .base
+            .get_or_insert_with(|| (WORLD.to_string(), Isometry3::identity()))
+            .clone();
+        if !tools.iter().any(|(name, _)| *name == tool) {
+            tools.push((tool.clone(), tcp));
+        }
+        if base != WORLD && !work_objects.iter().any(|(name, _)| *name == base) {
+            work_objects.push((base.clone(), frame));
+        }
+        waypoints.push(Waypoint {
+            name,
+            target,
+            frame: base,
+            tool: Some(tool),
+            configuration: None,
+            motion: Some(motion),
+            speed,
+            zone,
+        });
+    }
+    Ok(Module {
+        name: module.unwrap_or_else(|| routine.clone()),
+        tools,
+        work_objects,
+        waypoints,
+    })
+}
+
+/// A frame as {X, Y, Z, A, B, C}, millimeters and degrees
+fn krl_frame(pose: &Isometry3<f64>) -> String {
+    let [x, y, z] = [0, 1, 2].map(|i| decimal(pose.translation.vector[i] * MM, 3));
+    let (c, b, a) = pose.rotation.euler_angles();
+    let [a, b, c] = [a, b, c].map(|angle| decimal(angle.to_degrees(), 4));
+    format!("{{X {},Y {},Z {},A {},B {},C {}}}", x, y, z, a, b, c)
+}
+
+/// Writes a KRL program as its .src and .dat. The .dat declares the tools (TCP in the
+/// flange frame) and bases (in the world frame) of `module` as FRAMEs, and every target as
+/// a POS or AXIS; the .src initializes the motion parameters and selects the tool and
+/// base of each move with $TOOL and $BASE, so that the program does not depend on the
+/// robot's TOOL_DATA and BASE_DATA. The speed of LIN moves is set with $VEL.CP, PTP
+/// velocities being axis speed percentages it is not given for them; moves with a zone
+/// are approximated (C_DIS) at that distance, set with $APO.CDIS.
+pub fn write(module: &Module) -> (String, String) {
+    let mut identifiers = Identifiers::new(24, &[]);
+    let name = identifiers.add(&module.name, "program");
+    let mut dat = vec![
+        "&ACCESS RVP".to_string(),
+        "&REL 1".to_string(),
+        format!("DEFDAT {}", name),
+    ];
+    let mut frames = HashMap::new();
+    let tools = module
+        .tools
+        .iter()
+        .filter(|(name, _)| !name.eq_ignore_ascii_case("tool0"));
+    for ((name, frame), fallback) in tools
+        .map(|tool| (tool, "tool"))
+        .chain(module.work_objects.iter().map(|base| (base, "base")))
+    {
+        let identifier = identifiers.add(name, fallback);
+        dat.push(format!("  DECL FRAME {}={}", identifier, krl_frame(frame)));
+        frames.insert((fallback, name.clone()), identifier);
+    }
+    let names = target_names(&module.waypoints, &mut identifiers, |i| {
+        format!("P{}", i + 1)
+    });
+    for (waypoint, (identifier, first)) in module.waypoints.iter().zip(&names) {
+        if !first {
+            continue;
+        }
+        dat.push(match &waypoint.target {
+            Target::Pose(pose) => format!("  DECL POS {}={}", identifier, krl_frame(pose)),
+            Target::Joints(joints) => {
+                let axes: Vec<String> = joints
+                    .iter()
+                    .enumerate()
+                    .map(|(i, joint)| format!("A{} {}", i + 1, decimal(joint.to_degrees(), 4)))
+                    .collect();
+                format!("  DECL AXIS {}={{{}}}", identifier, axes.join(","))
+            }
+        });
+    }
+    dat.push("ENDDAT".to_string());
+    dat.push(String::new());
+
+    let mut src = vec![
+        "&ACCESS RVP".to_string(),
+        "&REL 1".to_string(),
+        format!("DEF {}( )", name),
+        "  BAS(#INITMOV, 0)".to_string(),
+    ];
+    let (mut tool, mut base) = (None, None);
+    let (mut cp_speed, mut apo) = (None, None);
+    for (waypoint, (identifier, _)) in module.waypoints.iter().zip(&names) {
+        let frame = |kind: &'static str, name: &str| {
+            frames
+                .get(&(kind, name.to_string()))
+                .map_or("$NULLFRAME".to_string(), String::clone)
+        };
+        let wanted_tool = frame("tool", waypoint.tool.as_deref().unwrap_or("tool0"));
+        if tool.as_ref() != Some(&wanted_tool) {
+            src.push(format!("  $TOOL = {}", wanted_tool));
+            tool = Some(wanted_tool);
+        }
+        let wanted_base = frame("base", &waypoint.frame);
+        if base.as_ref() != Some(&wanted_base) {
+            src.push(format!("  $BASE = {}", wanted_base));
+            base = Some(wanted_base);
+        }
+        let linear = matches!(
+            (&waypoint.target, waypoint.motion),
+            (Target::Pose(_), Some(Motion::Linear))
+        );
+        if let Some(speed) = waypoint.speed.filter(|_| linear) {
+            let speed = decimal(speed, 4);
+            if cp_speed.as_ref() != Some(&speed) {
+                src.push(format!("  $VEL.CP = {}", speed));
+                cp_speed = Some(speed);
+            }
+        }
+        let approximation = match waypoint.zone.map(|zone| decimal(zone * MM, 1)) {
+            Some(distance) if distance != "0" => {
+                if apo.as_ref() != Some(&distance) {
+                    src.push(format!("  $APO.CDIS = {}", distance));
+                    apo = Some(distance);
+                }
+                " C_DIS"
+            }
+            _ => "",
+        };
+        let instruction = if linear { "LIN" } else { "PTP" };
+        src.push(format!("  {} {}{}", instruction, identifier, approximation));
+    }
+    src.push("END".to_string());
+    src.push(String::new());
+    (src.join("\n"), dat.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::Vector3;
+
+    const SRC: &str = r#"
+&ACCESS RVP
+DEF weld( )
+;FOLD INI
+  BAS (#INITMOV,0 )
+;ENDFOLD (INI)
+  PTP {A1 0, A2 -90, A3 90, A4 0, A5 0, A6 0}
+  $TOOL = TOOL_DATA[1]
+  $BASE = BASE_DATA[2]
+  BAS(#CP_PARAMS, 0.2)
+  $APO.CDIS = 5
+  LIN XP1 C_DIS
+;FOLD PTP P2 Vel=100 % PDAT1 Tool[0] Base[0];%{PE}
+  FDAT_ACT=FP2
+  PDAT_ACT=PPDAT1
+  PTP XP2 C_PTP
+;ENDFOLD
+  CIRC XP1, XP2
+END
+"#;
+
+    const DAT: &str = r#"
+DEFDAT weld
+DECL E6POS XP1={X 100.0,Y 0.0,Z 50.0,A 90.0,B 0.0,C 180.0,S 6,T 50,E1 0.0}
+DECL E6POS XP2={X 500.0,Y 0.0,Z 800.0,A 0.0,B 90.0,C 0.0,S 2,T 2}
+DECL FDAT FP2={TOOL_NO 0,BASE_NO 0,IPO_FRAME #BASE,POINT2[] " "}
+DECL PDAT PPDAT1={VEL 100.0,ACC 100.0,APO_DIST 20.0}
+ENDDAT
+"#;
+
+    const CONFIG: &str = r#"
+DEFDAT $CONFIG
+TOOL_DATA[1]={X 0.0,Y 0.0,Z 400.0,A 0.0,B 0.0,C 0.0}
+TOOL_NAME[1,]="torch"
+BASE_DATA[2]={X 1000.0,Y 200.0,Z 0.0,A 90.0,B 0.0,C 0.0}
+ENDDAT
+"#;
+
+    #[test]
+    fn test_parse_krl_program() {
+        let module = parse(SRC, &[DAT, CONFIG], None).unwrap();
+        assert_eq!(module.name, "weld");
+        let names: Vec<&str> = module.waypoints.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["*", "XP1", "XP2"]);
+        let [home, p1, p2] = &module.waypoints[..] else {
+            panic!("three moves");
+        };
+        assert_eq!(
+            home.target,
+            Target::Joints([0.0, -90f64.to_radians(), 90f64.to_radians(), 0.0, 0.0, 0.0])
+        );
+        assert_eq!(
+            (home.tool.as_deref(), home.frame.as_str()),
+            (Some("tool0"), WORLD)
+        );
+        assert_eq!(home.zone, Some(0.0));
+
+        assert_eq!(
+            (p1.tool.as_deref(), p1.frame.as_str()),
+            (Some("torch"), "base2")
+        );
+        assert_eq!(
+            (p1.motion, p1.speed, p1.zone),
+            (Some(Motion::Linear), Some(0.2), Some(0.005))
+        );
+        let Target::Pose(pose) = &p1.target else {
+            panic!("a pose")
+        };
+        assert!((pose.translation.vector - Vector3::new(0.1, 0.0, 0.05)).norm() < 1e-12);
+        // A 90 turns x onto y, C 180 then flips z down
+        assert!((pose.rotation * Vector3::x() - Vector3::y()).norm() < 1e-12);
+        assert!((pose.rotation * Vector3::z() + Vector3::z()).norm() < 1e-12);
+
+        // The inline form's FDAT selects the flange and the world again
+        assert_eq!((p2.frame.as_str(), p2.zone), (WORLD, Some(0.02)));
+        assert_eq!((p2.motion, p2.speed), (Some(Motion::Joint), None));
+
+        assert_eq!(module.tools.len(), 2);
+        assert!((module.tools[1].1.translation.vector.z - 0.4).abs() < 1e-12);
+        let (name, base) = &module.work_objects[0];
+        assert_eq!(name, "base2");
+        assert!((base.translation.vector - Vector3::new(1.0, 0.2, 0.0)).norm() < 1e-12);
+
+        // Without $config.dat the tool is unknown
+        assert!(parse(SRC, &[DAT], None).is_err());
+        assert!(parse(SRC, &[DAT, CONFIG], Some("missing")).is_err());
+    }
+
+    #[test]
+    fn test_write_krl_reads_back() {
+        let module = parse(SRC, &[DAT, CONFIG], None).unwrap();
+        let (src, dat) = write(&module);
+        assert!(dat.contains("  DECL FRAME torch={X 0,Y 0,Z 400,A 0,B 0,C 0}\n"));
+        assert!(dat.contains("  DECL FRAME base2={X 1000,Y 200,Z 0,A 90,B 0,C 0}\n"));
+        assert!(dat.contains("  DECL AXIS P1={A1 0,A2 -90,A3 90,A4 0,A5 0,A6 0}\n"));
+        assert!(src.contains(
+            "  $TOOL = torch\n  $BASE = base2\n  $VEL.CP = 0.2\n  $APO.CDIS = 5\n  LIN XP1 C_DIS\n"
+        ));
+        assert!(src.contains(
+            "  $TOOL = $NULLFRAME\n  $BASE = $NULLFRAME\n  $APO.CDIS = 20\n  PTP XP2 C_DIS\n"
+        ));
+        let read = parse(&src, &[&dat], None).unwrap();
+        assert_eq!(read.name, "weld");
+        assert_eq!(read.tools.len(), module.tools.len());
+        let (name, base) = &read.work_objects[0];
+        assert_eq!(name, "base2");
+        assert!((base.to_homogeneous() - module.work_objects[0].1.to_homogeneous()).norm() < 1e-9);
+        for (read, written) in read.waypoints.iter().zip(&module.waypoints) {
+            assert_eq!((&read.frame, &read.tool), (&written.frame, &written.tool));
+            assert_eq!((read.zone, read.motion), (written.zone, written.motion));
+            match (&read.target, &written.target) {
+                (Target::Pose(read), Target::Pose(written)) => {
+                    assert!((read.to_homogeneous() - written.to_homogeneous()).norm() < 1e-6)
+                }
+                (Target::Joints(read), Target::Joints(written)) => {
+                    assert!(read.iter().zip(written).all(|(a, b)| (a - b).abs() < 1e-9))
+                }
+                _ => panic!("the target kind changed"),
+            }
+        }
+    }
+}
@@ -1,19 +1,198 @@
+mod axis;
+mod batch;
+mod branches;
+mod cache;
+mod calibrated;
+mod calibration;
+mod cell;
+mod cli;
+mod columns;
+mod comfort;
+mod comparison;
+mod confdata;
+mod configuration;
+mod context;
+mod conveyor;
+mod corrections;
+mod deviation;
+mod diagnosis;
+mod dict;
+mod dual_quaternion;
+mod frames;
+mod jog;
+mod joint_distance;
+mod joint_log;
 mod kinematic_model;
+mod krl;
+mod logging;
+mod look_at;
+mod ls;
+mod mesh;
+mod metadata;
+mod mirror;
+mod models;
+mod ordering;
+mod pallet;
+mod path;
+mod pose;
+mod pose_error;
+mod positioner;
+mod postures;
+mod program;
+mod projection;
+mod provenance;
+mod ranking;
+mod rapid;
+mod reachability;
+mod recovery;
+mod registration;
+mod resampling;
+mod robot_builder;
+mod ros;
+mod rotations;
+mod rows;
+mod seam;
+mod seeds;
+mod segmentation;
+mod server;
+mod skeleton;
+mod smoothing;
+mod stats;
+mod streaming;
+mod summary;
+mod tool_calibration;
+mod tour;
+mod twist;
+mod usage;
+mod validation;
+mod wrench;
+use crate::axis::Axis;
+use crate::batch::{CancelToken, ThreadPool};
+use crate::cache::IkCache;
+use crate::calibrated::CalibratedChain;
+use crate::columns::{JointTable, PoseTable};
+use crate::comfort::Comfort;
+use crate::configuration::Vendor;
+use crate::context::SolverContext;
+use crate::corrections::CorrectionFrame;
+use crate::diagnosis::Reason;
+use crate::dual_quaternion::DualQuaternion;
+use crate::frames::Frames;
+use crate::jog::JogFrame;
 use crate::kinematic_model::KinematicModel;
+use crate::mesh::Mesh;
+use crate::ordering::{InverseOptions, SolutionOrder};
+use crate::path::{PathCost, PathSelection};
+use crate::pose::{from_isometry, to_isometry, PyPose};
+use crate::positioner::Positioner;
+use crate::postures::{Postures, Start};
+use crate::program::Program;
+use crate::projection::Outcome;
+use crate::provenance::Provenance;
+use crate::ranking::Ranking;
+use crate::recovery::Recovery;
+use crate::resampling::Interpolation;
+use crate::robot_builder::RobotBuilder;
+use crate::rows::{Rows, SolvedRow, JOINT_COLUMNS, POSE_COLUMNS};
+use crate::seeds::Seeds;
+use crate::server::KinematicsServer;
+use crate::skeleton::Skeleton;
+use crate::smoothing::Smoothing;
+use crate::stats::Stats;
+use crate::streaming::StreamingBridge;
+use crate::summary::Summary;
+use crate::tour::TourMethod;
+use crate::twist::Twist;
+use crate::validation::InvalidPolicy;
+use crate::wrench::Wrench;
 
-use nalgebra::{Isometry3, Quaternion, Translation3, UnitQuaternion};
+use pyo3::create_exception;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::types::PyDict;
 
-use rs_opw_kinematics::kinematic_traits::{Kinematics, Pose, CONSTRAINT_CENTERED};
+use nalgebra::{Isometry3, Vector3};
+use rs_opw_kinematics::kinematic_traits::{Joints, Kinematics, Pose, CONSTRAINT_CENTERED};
 use rs_opw_kinematics::tool::{Base, Tool};
+use std::collections::BTreeMap;
+use std::f64::consts::{PI, TAU};
 use std::sync::Arc;
+use std::time::Instant;
 
-#[pyclass]
+/// Default singularity tolerance in radians, the 0.01 degrees the solver uses
+const SINGULARITY_TOLERANCE: f64 = 0.01 * PI / 180.0;
+
+create_exception!(
+    _internal,
+    UnreachablePoseError,
+    PyValueError,
+    "Raised by strict inverse kinematics when a pose has no solution. `distance` is how far (m) the wrist center lies outside the workspace, 0 if the joint limits exclude every solution."
+);
+
+/// A robot with base and tool. All methods take `&self` and the class is frozen, so one
+/// instance can be shared between Python threads; batch methods release the GIL while solving.
+#[pyclass(frozen)]
 struct Robot {
     base_config: BaseConfig,
     tool_config: ToolConfig,
     _tool: Tool,
     _kinematic_model: KinematicModel,
+    /// Joint limits as (min, max) per joint, in radians
+    joint_limits: Option<[(f64, f64); 6]>,
+    /// Whether joint values are exchanged in radians instead of degrees
+    radians: bool,
+    solution_policy: SolutionPolicy,
+    /// Measured link corrections, FK uses the calibrated chain and IK refines onto it
+    calibration: Option<CalibratedChain>,
+    stats: Stats,
+    /// Opt-in memoization of inverse kinematics
+    cache: IkCache,
+    /// Seed of inverse kinematics without current joints, in radians
+    default_seed: Option<Joints>,
+    /// Distance of J5 from a multiple of 180 degrees within which joints are singular, in
+    /// radians
+    singularity_tolerance: f64,
+    /// Posture cost of inverse with_comfort and order_by="comfort", preferred in radians
+    comfort: Comfort,
+    /// Criteria and weights of order_by="weighted"
+    ranking: Ranking,
+    /// Named postures such as home and mastering, in radians
+    postures: Postures,
+    /// Reported minus true joint angle per joint (radians), removed before the kinematic
+    /// model and added back to its solutions
+    mastering: Joints,
+    /// Pose of a tool fixed in the cell (world frame) when the robot holds the part, whose
+    /// frame is then the tool_config. Targets are poses in the part frame.
+    stationary_tool: Option<ToolConfig>,
+}
+
+/// Which inverse kinematics solutions are returned
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SolutionPolicy {
+    /// Every solution, ordered by proximity to the seed
+    All,
+    /// Only the solution closest to the seed
+    Closest,
+}
+
+impl SolutionPolicy {
+    fn name(&self) -> &'static str {
+        match self {
+            SolutionPolicy::All => "all",
+            SolutionPolicy::Closest => "closest",
+        }
+    }
+
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "all" => Ok(SolutionPolicy::All),
+            "closest" => Ok(SolutionPolicy::Closest),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown solution policy '{}', expected 'all' or 'closest'",
+                name
+            ))),
+        }
+    }
 }
 
 #[pyclass]
@@ -35,6 +214,119 @@ impl BaseConfig {
             rotation,
         }
     }
+
+    /// Base hanging from a ceiling at `height`, upside down (turned 180 degrees about x)
+    #[staticmethod]
+    #[pyo3(signature = (height, x=0.0, y=0.0))]
+    fn ceiling_mounted(height: f64, x: f64, y: f64) -> Self {
+        BaseConfig::new([x, y, height], [0.0, 1.0, 0.0, 0.0])
+    }
+
+    /// Base on a wall, its z axis along the wall `normal` (pointing away from the wall) and
+    /// its x axis pointing down
+    #[staticmethod]
+    #[pyo3(signature = (translation, normal=[1.0, 0.0, 0.0]))]
+    fn wall_mounted(translation: [f64; 3], normal: [f64; 3]) -> PyResult<Self> {
+        BaseConfig::mounted(translation, normal, [0.0, 0.0, -1.0])
+    }
+
+    /// Base at `translation` with its z axis along `z_axis` and its x axis as close to
+    /// `x_axis` as the z axis allows
+    #[staticmethod]
+    #[pyo3(signature = (translation, z_axis, x_axis=[1.0, 0.0, 0.0]))]
+    fn mounted(translation: [f64; 3], z_axis: [f64; 3], x_axis: [f64; 3]) -> PyResult<Self> {
+        let rotation = pose::rotation_from_axes(z_axis, x_axis).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Cannot orient the base from z axis {:?} and x axis {:?}, they must be \
+                 non-zero and not parallel",
+                z_axis, x_axis
+            ))
+        })?;
+        Ok(BaseConfig::new(translation, rotation))
+    }
+
+    /// Estimates the base in the world frame from pairs of TCP poses reported by the robot
+    /// (in its base frame) and the same poses measured in the world frame, e.g. by a laser
+    /// tracker or camera, by least squares. `orientation_weight` (m) sets how much a radian
+    /// of rotation error counts against a meter of position error; 0 fits the positions
+    /// only, which then need three points off a line. Returns the base with the position
+    /// (m) and rotation (degrees) residual of every pair and their RMS and maximum.
+    #[staticmethod]
+    #[pyo3(signature = (robot_poses, measured_poses, orientation_weight=1.0))]
+    fn from_measurements<'py>(
+        py: Python<'py>,
+        robot_poses: PoseTable,
+        measured_poses: PoseTable,
+        orientation_weight: f64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        if robot_poses.0.len() != measured_poses.0.len() {
+            return Err(PyValueError::new_err(format!(
+                "Got {} robot poses but {} measured poses, expected pairs",
+                robot_poses.0.len(),
+                measured_poses.0.len()
+            )));
+        }
+        if !(orientation_weight.is_finite() && orientation_weight >= 0.0) {
+            return Err(PyValueError::new_err(
+                "orientation_weight must be finite and not negative",
+            ));
+        }
+        let pairs = robot_poses
+            .0
+            .iter()
+            .zip(&measured_poses.0)
+            .map(|(robot, measured)| {
+                validation::check_pose(robot)?;
+                validation::check_pose(measured)?;
+                Ok((
+                    to_isometry(robot.0, robot.1),
+                    to_isometry(measured.0, measured.1),
+                ))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let base = registration::fit_base(&pairs, orientation_weight).ok_or_else(|| {
+            PyValueError::new_err(
+                "The poses do not determine the base, measure at least three points off a \
+                 line or give a positive orientation_weight",
+            )
+        })?;
+        let residuals = registration::residuals(&base, &pairs);
+        let positions: Vec<f64> = residuals.iter().map(|(position, _)| *position).collect();
+        let rotations: Vec<f64> = residuals
+            .iter()
+            .map(|(_, rotation)| rotation.to_degrees())
+            .collect();
+        let rms = |values: &[f64]| {
+            (values.iter().map(|value| value * value).sum::<f64>() / values.len() as f64).sqrt()
+        };
+        let max = |values: &[f64]| values.iter().copied().fold(0.0, f64::max);
+        let (translation, rotation) = from_isometry(&base);
+        let dict = PyDict::new(py);
+        dict.set_item("base_config", BaseConfig::new(translation, rotation))?;
+        dict.set_item("rms_position", rms(&positions))?;
+        dict.set_item("max_position", max(&positions))?;
+        dict.set_item("rms_rotation", rms(&rotations))?;
+        dict.set_item("max_rotation", max(&rotations))?;
+        dict.set_item("position_residuals", positions)?;
+        dict.set_item("rotation_residuals", rotations)?;
+        Ok(dict)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("translation", self.translation)?;
+        dict.set_item("rotation", self.rotation)?;
+        Ok(dict)
+    }
+
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(data, &["translation", "rotation"], "BaseConfig")?;
+        Ok(BaseConfig::new(
+            dict::required(data, "translation", "BaseConfig")?,
+            dict::required(data, "rotation", "BaseConfig")?,
+        ))
+    }
 }
 
 #[pyclass]
@@ -56,6 +348,22 @@ impl ToolConfig {
             rotation,
         }
     }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("translation", self.translation)?;
+        dict.set_item("rotation", self.rotation)?;
+        Ok(dict)
+    }
+
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(data, &["translation", "rotation"], "ToolConfig")?;
+        Ok(ToolConfig::new(
+            dict::required(data, "translation", "ToolConfig")?,
+            dict::required(data, "rotation", "ToolConfig")?,
+        ))
+    }
 }
 
 #[pymethods]
@@ -69,25 +377,8 @@ impl Robot {
     ) -> PyResult<Self> {
         let robot = kinematic_model.to_opw_kinematics();
 
-        let base = Isometry3::from_parts(
-            Translation3::from(base_config.translation),
-            UnitQuaternion::from_quaternion(Quaternion::new(
-                base_config.rotation[0],
-                base_config.rotation[1],
-                base_config.rotation[2],
-                base_config.rotation[3],
-            )),
-        );
-
-        let tool = Isometry3::from_parts(
-            Translation3::from(tool_config.translation),
-            UnitQuaternion::from_quaternion(Quaternion::new(
-                tool_config.rotation[0],
-                tool_config.rotation[1],
-                tool_config.rotation[2],
-                tool_config.rotation[3],
-            )),
-        );
+        let base = to_isometry(base_config.translation, base_config.rotation);
+        let tool = to_isometry(tool_config.translation, tool_config.rotation);
 
         let robot_with_base = Base {
             robot: Arc::new(robot),
@@ -105,11 +396,155 @@ impl Robot {
             tool_config,
             _tool: robot_on_base_with_tool,
             _kinematic_model: kinematic_model,
+            joint_limits: None,
+            radians: false,
+            solution_policy: SolutionPolicy::All,
+            calibration: None,
+            stats: Stats::default(),
+            cache: IkCache::default(),
+            default_seed: None,
+            singularity_tolerance: SINGULARITY_TOLERANCE,
+            comfort: Comfort::default(),
+            ranking: Ranking::default(),
+            postures: Postures::new(),
+            mastering: [0.0; 6],
+            stationary_tool: None,
         };
 
         Ok(robot_instance)
     }
 
+    /// Returns the complete robot setup as a nested dictionary
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("kinematic_model", self._kinematic_model.to_dict(py)?)?;
+        dict.set_item("base_config", self.base_config.to_dict(py)?)?;
+        dict.set_item("tool_config", self.tool_config.to_dict(py)?)?;
+        let joint_limits = self.joint_limits.map(|limits| {
+            limits.map(|(min, max)| [self.joint_out(min, None), self.joint_out(max, None)])
+        });
+        dict.set_item("joint_limits", joint_limits)?;
+        dict.set_item("units", if self.radians { "radians" } else { "degrees" })?;
+        dict.set_item("solution_policy", self.solution_policy.name())?;
+        dict.set_item(
+            "corrections",
+            self.calibration.as_ref().map(CalibratedChain::corrections),
+        )?;
+        dict.set_item(
+            "default_seed",
+            self.default_seed.map(|seed| self.joints_out(seed, None)),
+        )?;
+        dict.set_item(
+            "singularity_tolerance_deg",
+            self.singularity_tolerance.to_degrees(),
+        )?;
+        dict.set_item(
+            "comfort",
+            self.comfort
+                .to_dict(py, |joints| self.joints_out(joints, None))?,
+        )?;
+        dict.set_item("ranking", self.ranking.to_dict(py)?)?;
+        let postures = PyDict::new(py);
+        for (name, joints) in &self.postures {
+            postures.set_item(name, self.joints_out(*joints, None))?;
+        }
+        dict.set_item("postures", postures)?;
+        dict.set_item("mastering_offsets", self.joints_out(self.mastering, None))?;
+        let stationary_tool = self
+            .stationary_tool
+            .as_ref()
+            .map(|tool| tool.to_dict(py))
+            .transpose()?;
+        dict.set_item("stationary_tool", stationary_tool)?;
+        Ok(dict)
+    }
+
+    /// Creates a robot from a dictionary as produced by to_dict
+    #[staticmethod]
+    fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(
+            data,
+            &[
+                "kinematic_model",
+                "base_config",
+                "tool_config",
+                "joint_limits",
+                "units",
+                "solution_policy",
+                "corrections",
+                "default_seed",
+                "singularity_tolerance_deg",
+                "comfort",
+                "ranking",
+                "postures",
+                "mastering_offsets",
+                "stationary_tool",
+            ],
+            "Robot",
+        )?;
+        let kinematic_model: Bound<'_, PyDict> = dict::required(data, "kinematic_model", "Robot")?;
+        let base_config: Option<Bound<'_, PyDict>> = dict::optional(data, "base_config")?;
+        let tool_config: Option<Bound<'_, PyDict>> = dict::optional(data, "tool_config")?;
+        let comfort: Option<Bound<'_, PyDict>> = dict::optional(data, "comfort")?;
+        let ranking: Option<Bound<'_, PyDict>> = dict::optional(data, "ranking")?;
+        let stationary_tool: Option<Bound<'_, PyDict>> = dict::optional(data, "stationary_tool")?;
+        RobotBuilder {
+            kinematic_model: Some(KinematicModel::from_dict(&kinematic_model)?),
+            base_config: base_config.map(|d| BaseConfig::from_dict(&d)).transpose()?,
+            tool_config: tool_config.map(|d| ToolConfig::from_dict(&d)).transpose()?,
+            joint_limits: dict::optional::<[[f64; 2]; 6]>(data, "joint_limits")?
+                .map(|limits| limits.map(|[min, max]| (min, max))),
+            units: dict::optional(data, "units")?,
+            solution_policy: dict::optional(data, "solution_policy")?,
+            corrections: dict::optional(data, "corrections")?,
+            default_seed: dict::optional(data, "default_seed")?,
+            singularity_tolerance_deg: dict::optional(data, "singularity_tolerance_deg")?,
+            comfort: comfort.map(|d| Comfort::from_dict(&d)).transpose()?,
+            ranking: ranking.map(|d| Ranking::from_dict(&d)).transpose()?,
+            postures: dict::optional(data, "postures")?.unwrap_or_default(),
+            mastering_offsets: dict::optional(data, "mastering_offsets")?,
+            stationary_tool: stationary_tool
+                .map(|d| ToolConfig::from_dict(&d))
+                .transpose()?,
+        }
+        .build()
+    }
+
+    /// Stable hash (16 hex digits) of the kinematic setup: the kinematic model, link
+    /// corrections, mastering offsets, base, stationary tool, tool and, with
+    /// `include_limits`, the joint limits. Equal setups give equal fingerprints on every
+    /// platform and build, to key caches, result files and experiments by.
+    #[pyo3(signature = (include_limits=true))]
+    fn fingerprint(&self, include_limits: bool) -> String {
+        let mut values = self.setup_values(include_limits);
+        values.extend(self.tool_config.translation);
+        values.extend(self.tool_config.rotation);
+        provenance::digest(values)
+    }
+
+    /// Creates a robot from the built-in model library, see list_models()
+    #[staticmethod]
+    #[pyo3(signature = (name, base_config=None, tool_config=None))]
+    fn from_model(
+        name: &str,
+        base_config: Option<BaseConfig>,
+        tool_config: Option<ToolConfig>,
+    ) -> PyResult<Self> {
+        RobotBuilder {
+            kinematic_model: Some(KinematicModel::from_model(name)?),
+            base_config,
+            tool_config,
+            ..RobotBuilder::default()
+        }
+        .build()
+    }
+
+    /// Starts a RobotBuilder for step by step construction
+    #[staticmethod]
+    fn builder() -> RobotBuilder {
+        RobotBuilder::default()
+    }
+
     fn __repr__(&self) -> String {
         let km_repr = self
             ._kinematic_model
@@ -125,18 +560,16 @@ impl Robot {
         )
     }
 
-    /// Forward kinematics: calculates the pose for given joints in degrees
-    fn forward(&self, joints: [f64; 6]) -> ([f64; 3], [f64; 4]) {
-        let joints = joints.map(|x| x.to_radians());
-        let pose: Pose = self._tool.forward(&joints);
-        // Storage order is (x, y, z, w)
-        let quat = [
-            pose.rotation.coords[3],
-            pose.rotation.coords[0],
-            pose.rotation.coords[1],
-            pose.rotation.coords[2],
-        ];
-        (pose.translation.vector.into(), quat)
+    /// Forward kinematics: calculates the pose for given joints in degrees. `radians`
+    /// overrides the units of the robot for this call.
+    #[pyo3(signature = (joints, radians=None))]
+    fn forward(&self, joints: [f64; 6], radians: Option<bool>) -> PyResult<PyPose> {
+        validation::check_joints("joints", &joints)?;
+        let start = self.stats.start();
+        let joints = self.joints_in(joints, radians);
+        let pose: Pose = self.reported(&self.forward_radians(&joints));
+        self.stats.record_forward(start);
+        Ok(from_isometry(&pose))
     }
 
     fn convert_to_degrees(&self, joints: [f64; 6]) -> [f64; 6] {
@@ -152,62 +585,3824 @@ impl Robot {
     ///
     /// # Arguments
     /// * `pose` - The target pose as a tuple: ([x, y, z], [w, x, y, z]), where the translation is in meters and the rotation is a quaternion.
-    /// * `current_joints` - (Optional) The current joint angles as an array of 6 elements (in degrees). Used as a seed for solution selection. If not provided, a default centered configuration is used.
+    /// * `current_joints` - (Optional) The current joint angles as an array of 6 elements (in degrees). Used as a seed for solution selection. If not provided, the default seed of the robot is used, or a centered configuration without one.
+    /// * `order_by` - (Optional) "distance" (default) sorts by the summed joint distance to the seed, "configuration" by the configuration index cfx, "comfort" by the posture cost set by RobotBuilder.comfort(), "weighted" by the score of the criteria weighted by RobotBuilder.ranking() and "none" keeps the order of the solver. Ties are broken by the other key and then by the joint values, so the order is deterministic.
+    /// * `dedup_tolerance` - (Optional) Merges solutions whose joints all lie within this angle (in degrees) of a better ranked one.
+    /// * `dedup_turns` - (Optional) Whether solutions differing by whole turns of a joint are merged as well, true by default.
+    /// * `strict` - (Optional) Raise UnreachablePoseError instead of returning no solutions.
+    /// * `seeds` - (Optional) Extra seeds, a list of joint sets or "auto" for a spread over the joint ranges. The union of the distinct solutions of all seeds is returned, which finds turn variants of the joints that a single seed misses.
+    /// * `tool_relative` - (Optional) Interpret the pose as an offset in the TCP frame at `current_joints` (or the default seed), for shifts of taught positions by tool-relative corrections.
+    /// * `turns` - (Optional) Turn numbers of J1, J4 and J6, 0 for [-180, 180), 1 for [180, 540), -1 for [-540, -180) and so on. Returns the solutions of every branch unwound to these turns that lie within the joint limits, e.g. to match ABB cf1/cf4/cf6 of -3, -2, 2 or 3 or KUKA turn bits.
+    /// * `exclude_singular` - (Optional) Drop solutions at the wrist singularity, J5 within `singularity_tolerance_deg` (the robot's by default) of a multiple of 180 degrees, where J4 and J6 are not determined by the pose.
+    /// * `singularity_tolerance_deg` - (Optional) Tolerance of `exclude_singular` in degrees for this call.
+    /// * `with_comfort` - (Optional) Return each solution as a dict of its joints with the posture cost set by RobotBuilder.comfort(), the total as comfort and its posture, limits and extension terms.
     ///
     /// # Returns
-    /// * `Vec<[f64; 6]>` - A vector of all possible joint solutions (in degrees).
+    /// * `Vec<[f64; 6]>` - A vector of all possible joint solutions (in degrees), or with `with_comfort` one dict per solution.
     ///
     /// # Notes
-    /// All solutions are returned without filtering or sorting.
-    #[pyo3(signature = (pose, current_joints=None))]
-    fn inverse(
+    /// Solutions outside the joint limits are dropped. With the "closest" solution policy only
+    /// the solution closest to the seed is returned. NaN or infinite inputs raise ValueError.
+    #[pyo3(signature = (
+        pose,
+        current_joints=None,
+        order_by=SolutionOrder::Distance,
+        dedup_tolerance=None,
+        dedup_turns=true,
+        radians=None,
+        strict=false,
+        seeds=None,
+        tool_relative=false,
+        turns=None,
+        exclude_singular=false,
+        singularity_tolerance_deg=None,
+        with_comfort=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn inverse<'py>(
         &self,
-        pose: ([f64; 3], [f64; 4]),
+        py: Python<'py>,
+        pose: PyPose,
         current_joints: Option<[f64; 6]>,
-    ) -> Vec<[f64; 6]> {
-        let quat = UnitQuaternion::from_quaternion(Quaternion::new(
-            pose.1[0], pose.1[1], pose.1[2], pose.1[3],
-        ));
-        let iso_pose = Isometry3::from_parts(Translation3::from(pose.0), quat);
+        order_by: SolutionOrder,
+        dedup_tolerance: Option<f64>,
+        dedup_turns: bool,
+        radians: Option<bool>,
+        strict: bool,
+        seeds: Option<Seeds>,
+        tool_relative: bool,
+        turns: Option<[i32; 3]>,
+        exclude_singular: bool,
+        singularity_tolerance_deg: Option<f64>,
+        with_comfort: bool,
+    ) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        validation::check_pose(&pose)?;
+        let tolerance = self.singularity_tolerance(singularity_tolerance_deg)?;
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let pose = if tool_relative {
+            self.tool_relative(pose, current_joints, radians)?
+        } else {
+            pose
+        };
+        let options = self.inverse_options(order_by, dedup_tolerance, dedup_turns, radians);
+        let mut solutions: Vec<[f64; 6]> = match (seeds, turns) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err("seeds and turns cannot be combined"));
+            }
+            (None, None) => self.solve(pose, current_joints, &options),
+            (None, Some(turns)) => {
+                let seed = current_joints.map(|joints| self.joints_in(joints, radians));
+                self.solve_turns(pose, seed, turns, &options)
+                    .into_iter()
+                    .map(|solution| self.joints_out(solution, radians))
+                    .collect()
+            }
+            (Some(seeds), None) => {
+                let seeds = match seeds {
+                    Seeds::Given(seeds) => {
+                        for seed in &seeds {
+                            validation::check_joints("seeds", seed)?;
+                        }
+                        seeds
+                            .iter()
+                            .map(|seed| self.joints_in(*seed, radians))
+                            .collect()
+                    }
+                    Seeds::Auto => seeds::spread(&self.joint_limits.unwrap_or(seeds::FULL_TURNS)),
+                };
+                let seed = current_joints.map(|joints| self.joints_in(joints, radians));
+                self.solve_seeded(pose, seed, &seeds, &options)
+                    .into_iter()
+                    .map(|solution| self.joints_out(solution, radians))
+                    .collect()
+            }
+        };
+        if exclude_singular {
+            solutions
+                .retain(|solution| self.is_regular(&self.joints_in(*solution, radians), tolerance));
+        }
+        if strict && solutions.is_empty() {
+            return Err(self.unreachable_error(py, pose));
+        }
+        solutions
+            .into_iter()
+            .map(|solution| {
+                if !with_comfort {
+                    return Ok(solution.into_pyobject(py)?.into_any());
+                }
+                let row = self
+                    .comfort_score(&self.joints_in(solution, radians))
+                    .to_dict(py)?;
+                row.set_item("joints", solution)?;
+                Ok(row.into_any())
+            })
+            .collect()
+    }
+
+    /// Inverse kinematics on all eight analytic branches: one row per branch with its index
+    /// (cfx), its shoulder, elbow and wrist labels, the joints (None where the branch cannot
+    /// reach the pose) and whether they lie within the joint limits. No seed continuity,
+    /// ordering or joint limit filtering applies. `strict` raises UnreachablePoseError if no
+    /// branch reaches the pose, `tool_relative` is as for `inverse` and `with_comfort` adds
+    /// the total posture cost set by RobotBuilder.comfort(), None for unreachable branches.
+    #[pyo3(signature = (
+        pose,
+        current_joints=None,
+        radians=None,
+        strict=false,
+        tool_relative=false,
+        with_comfort=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn inverse_branches<'py>(
+        &self,
+        py: Python<'py>,
+        pose: PyPose,
+        current_joints: Option<[f64; 6]>,
+        radians: Option<bool>,
+        strict: bool,
+        tool_relative: bool,
+        with_comfort: bool,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        validation::check_pose(&pose)?;
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let pose = if tool_relative {
+            self.tool_relative(pose, current_joints, radians)?
+        } else {
+            pose
+        };
+        let branches = self.branches(pose);
+        if strict && branches.iter().all(Option::is_none) {
+            return Err(self.unreachable_error(py, pose));
+        }
+        (0u8..)
+            .zip(branches)
+            .map(|(cfx, joints)| {
+                let within_limits = joints.is_some_and(|joints| self.within_limits(&joints));
+                let comfort = joints.map(|joints| self.comfort_score(&joints).total);
+                let joints = joints.map(|joints| self.joints_out(joints, radians));
+                let row = branches::to_dict(py, cfx, joints, within_limits)?;
+                if with_comfort {
+                    row.set_item("comfort", comfort)?;
+                }
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Inverse kinematics for tight loops such as servoing: the solution within the joint
+    /// limits closest to `current_joints` (or the default seed), None if the pose is
+    /// unreachable. Skips the cache, the solution policy, ordering options and list
+    /// building. Without `check_singularity` the solver takes a single pass instead of
+    /// probing the wrist singularity, so J4 and J6 may not follow the seed there.
+    #[pyo3(signature = (pose, current_joints=None, radians=None, check_singularity=true))]
+    fn inverse_fast(
+        &self,
+        pose: PyPose,
+        current_joints: Option<[f64; 6]>,
+        radians: Option<bool>,
+        check_singularity: bool,
+    ) -> PyResult<Option<[f64; 6]>> {
+        validation::check_pose(&pose)?;
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let start = self.stats.start();
+        let seed = current_joints.map(|joints| self.joints_in(joints, radians));
+        let solution = self.closest_solution(&self.target(&pose), seed, check_singularity);
+        self.stats
+            .record_inverse(start, solution.is_some() as usize);
+        Ok(solution.map(|joints| self.joints_out(joints, radians)))
+    }
+
+    /// Inverse kinematics for many poses. `progress` is called with (done, total) as rows are
+    /// solved; Ctrl+C or cancelling `cancel` raises instead of finishing the batch. With
+    /// `with_counts` every pose gives a row with its solutions, the number of valid solutions
+    /// and the index of the chosen one among all solutions. Poses with NaN or infinite values
+    /// are handled as set by `on_invalid`. Rows are solved in parallel on `n_threads`
+    /// threads in chunks of `chunk_size` rows, overriding the settings of `pool`.
+    /// With `summary` the result is (rows, summary), the summary dict described on
+    /// `Summary`. With `provenance` every pose gives a row with its solutions and the
+    /// kinematic setup that produced them: model_hash (kinematic model, link corrections,
+    /// base, mastering offsets, stationary tool and joint limits), tool_hash and
+    /// config_policy (the solution policy).
+    #[pyo3(signature = (
+        poses,
+        progress=None,
+        cancel=None,
+        order_by=SolutionOrder::Distance,
+        dedup_tolerance=None,
+        dedup_turns=true,
+        radians=None,
+        with_counts=false,
+        on_invalid=InvalidPolicy::Null,
+        n_threads=None,
+        chunk_size=None,
+        pool=None,
+        summary=false,
+        provenance=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn batch_inverse<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        progress: Option<&Bound<'_, PyAny>>,
+        cancel: Option<PyRef<'_, CancelToken>>,
+        order_by: SolutionOrder,
+        dedup_tolerance: Option<f64>,
+        dedup_turns: bool,
+        radians: Option<bool>,
+        with_counts: bool,
+        on_invalid: InvalidPolicy,
+        n_threads: Option<usize>,
+        chunk_size: Option<usize>,
+        pool: Option<ThreadPool>,
+        summary: bool,
+        provenance: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let start = Instant::now();
+        let provenance = provenance.then(|| self.provenance());
+        let pool = ThreadPool::resolve(pool.as_ref(), n_threads, chunk_size)?;
+        let total = poses.0.len();
+        let poses = validation::screen(poses.0, validation::is_finite_pose, on_invalid)?;
+        let options = self.inverse_options(order_by, dedup_tolerance, dedup_turns, radians);
+        let results = batch::run(py, &poses, progress, cancel.as_deref(), &pool, |pose| {
+            if !validation::is_finite_pose(pose) {
+                return None;
+            }
+            let (solutions, n_solutions, solution_index) =
+                self.solve_counted(*pose, None, &options);
+            let row = summary.then(|| self.summary_row(*pose, solutions.first()));
+            let solutions: Vec<[f64; 6]> = solutions
+                .iter()
+                .map(|x| self.joints_out(*x, options.radians))
+                .collect();
+            Some((solutions, n_solutions, solution_index, row))
+        })?;
+        let mut totals = Summary::new(start, total - poses.len());
+        let rows = results
+            .into_iter()
+            .map(|result| {
+                let Some((solutions, n_solutions, solution_index, row)) = result else {
+                    totals.record(summary::Row::Invalid);
+                    return Ok(py.None().into_bound(py));
+                };
+                if let Some(row) = row {
+                    totals.record(row);
+                }
+                if !(with_counts || provenance.is_some()) {
+                    return Ok(solutions.into_pyobject(py)?.into_any());
+                }
+                let row = PyDict::new(py);
+                row.set_item("solutions", solutions)?;
+                if with_counts {
+                    row.set_item("n_solutions", n_solutions)?;
+                    row.set_item("solution_index", solution_index)?;
+                }
+                if let Some(provenance) = &provenance {
+                    provenance.stamp(&row)?;
+                }
+                Ok(row.into_any())
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let totals = summary
+            .then(|| totals.to_dict(py, |joint| self.joint_out(joint, radians)))
+            .transpose()?;
+        summary::with_summary(py, rows, totals)
+    }
+
+    /// Solves every pose and returns one flat row per pose with the pose columns (x, y, z,
+    /// qw, qx, qy, qz), the best solution (j1..j6), status, configuration and the position
+    /// (m) and rotation (degrees) residuals. The rows load directly into a DataFrame. Poses
+    /// with NaN or infinite values are handled as set by `on_invalid`, "null" gives them the
+    /// status "invalid".
+    ///
+    /// With joint limits set, `limit_margins` adds the distance of every joint to its
+    /// nearest limit as limit_margin_j1..limit_margin_j6, and `warning_margin` adds a
+    /// near_limit column and logs a warning summarizing the rows with a joint closer to a
+    /// limit than that. Both are in the joint units.
+    ///
+    /// With `summary` the result is (rows, summary), the summary dict described on
+    /// `Summary`.
+    ///
+    /// `provenance` adds the model_hash, tool_hash and config_policy columns of
+    /// batch_inverse, so archived joint tables stay traceable to the kinematic setup.
+    #[pyo3(signature = (
+        poses,
+        current_joints=None,
+        order_by=SolutionOrder::Distance,
+        radians=None,
+        on_invalid=InvalidPolicy::Null,
+        limit_margins=false,
+        warning_margin=None,
+        summary=false,
+        provenance=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn solve_and_join<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        current_joints: Option<[f64; 6]>,
+        order_by: SolutionOrder,
+        radians: Option<bool>,
+        on_invalid: InvalidPolicy,
+        limit_margins: bool,
+        warning_margin: Option<f64>,
+        summary: bool,
+        provenance: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let start = Instant::now();
+        let provenance = provenance.then(|| self.provenance());
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let limits = match (limit_margins || warning_margin.is_some(), self.joint_limits) {
+            (false, _) => None,
+            (true, Some(limits)) => Some(limits),
+            (true, None) => {
+                return Err(PyValueError::new_err(
+                    "limit_margins and warning_margin require joint limits",
+                ))
+            }
+        };
+        if let Some(margin) = warning_margin {
+            if !(margin.is_finite() && margin >= 0.0) {
+                return Err(PyValueError::new_err(
+                    "warning_margin must be finite and not negative",
+                ));
+            }
+        }
+        let total = poses.0.len();
+        let poses = validation::screen(poses.0, validation::is_finite_pose, on_invalid)?;
+        let options = self.inverse_options(order_by, None, true, radians);
+        let (rows, summary_rows): (Vec<SolvedRow>, Vec<Option<summary::Row>>) = py.detach(|| {
+            poses
+                .iter()
+                .map(|&pose| {
+                    let row = self.solve_row(pose, current_joints, &options);
+                    let summary_row = summary.then(|| match row.status() {
+                        "invalid" => summary::Row::Invalid,
+                        _ => self.summary_row(
+                            pose,
+                            row.joints
+                                .map(|joints| self.joints_in(joints, radians))
+                                .as_ref(),
+                        ),
+                    });
+                    (row, summary_row)
+                })
+                .unzip()
+        });
+        let totals = summary
+            .then(|| {
+                let mut totals = Summary::new(start, total - poses.len());
+                summary_rows
+                    .into_iter()
+                    .flatten()
+                    .for_each(|row| totals.record(row));
+                totals.to_dict(py, |joint| self.joint_out(joint, radians))
+            })
+            .transpose()?;
+        let Some(limits) = limits else {
+            let dicts = rows
+                .iter()
+                .map(|row| {
+                    let dict = row.to_dict(py)?;
+                    if let Some(provenance) = &provenance {
+                        provenance.stamp(&dict)?;
+                    }
+                    Ok(dict.into_any())
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            return summary::with_summary(py, dicts, totals);
+        };
+
+        let mut near_rows = Vec::new();
+        let mut near_joints = [0usize; 6];
+        let dicts = rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let dict = row.to_dict(py)?;
+                let margins = row.joints.map(|joints| {
+                    let joints = self.joints_in(joints, radians);
+                    std::array::from_fn::<f64, 6, _>(|k| {
+                        let (min, max) = limits[k];
+                        self.joint_out((joints[k] - min).min(max - joints[k]), radians)
+                    })
+                });
+                if limit_margins {
+                    for (k, column) in JOINT_COLUMNS.iter().enumerate() {
+                        let name = format!("limit_margin_{}", column);
+                        dict.set_item(name, margins.map(|margins| margins[k]))?;
+                    }
+                }
+                if let Some(warning) = warning_margin {
+                    let near = margins.map(|margins| margins.map(|margin| margin < warning));
+                    if let Some(near) = near.filter(|near| near.contains(&true)) {
+                        near_rows.push(i);
+                        for (count, near) in near_joints.iter_mut().zip(near) {
+                            *count += near as usize;
+                        }
+                    }
+                    dict.set_item("near_limit", near.map(|near| near.contains(&true)))?;
+                }
+                if let Some(provenance) = &provenance {
+                    provenance.stamp(&dict)?;
+                }
+                Ok(dict.into_any())
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        if let (Some(warning), false) = (warning_margin, near_rows.is_empty()) {
+            let shown: Vec<String> = near_rows.iter().take(10).map(|i| i.to_string()).collect();
+            let joints: Vec<String> = near_joints
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| **count > 0)
+                .map(|(k, count)| format!("J{}: {}", k + 1, count))
+                .collect();
+            log::warn!(
+                "{} of {} rows come within {} of a joint limit (rows {}{}; {})",
+                near_rows.len(),
+                rows.len(),
+                warning,
+                shown.join(", "),
+                if near_rows.len() > shown.len() {
+                    ", ..."
+                } else {
+                    ""
+                },
+                joints.join(", ")
+            );
+        }
+        summary::with_summary(py, dicts, totals)
+    }
+
+    /// Corrects nominal poses by per-row correction transforms, e.g. from a vision system,
+    /// and solves the corrected poses. `frame` is the frame the corrections are expressed
+    /// in: "world", "workobject" (the `workobject` pose in the world frame) or "tool" (the
+    /// frame of each nominal pose). Returns the rows of `solve_and_join` for the corrected
+    /// poses. Rows whose pose or correction has NaN or infinite values are handled as set
+    /// by `on_invalid`.
+    #[pyo3(signature = (
+        poses,
+        corrections,
+        frame=CorrectionFrame::World,
+        workobject=None,
+        current_joints=None,
+        order_by=SolutionOrder::Distance,
+        radians=None,
+        on_invalid=InvalidPolicy::Null,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn apply_corrections<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        corrections: PoseTable,
+        frame: CorrectionFrame,
+        workobject: Option<PyPose>,
+        current_joints: Option<[f64; 6]>,
+        order_by: SolutionOrder,
+        radians: Option<bool>,
+        on_invalid: InvalidPolicy,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        if let Some(workobject) = &workobject {
+            validation::check_pose(workobject)?;
+        }
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let workobject = workobject.map(|pose| to_isometry(pose.0, pose.1));
+        let poses = corrections::apply(&poses.0, &corrections.0, frame, workobject.as_ref())?;
+        let poses = validation::screen(poses, validation::is_finite_pose, on_invalid)?;
+        let options = self.inverse_options(order_by, None, true, radians);
+        let rows: Vec<SolvedRow> = py.detach(|| {
+            poses
+                .iter()
+                .map(|&pose| self.solve_row(pose, current_joints, &options))
+                .collect()
+        });
+        rows.iter().map(|row| row.to_dict(py)).collect()
+    }
+
+    /// Solves the poses of a trajectory in order, each seeded with the solution of the row
+    /// before (the first with `current_joints`, joints or the name of a stored posture) and
+    /// taking the solution closest to it, within `max_step` (joint units) of it if given.
+    /// A pose without such a solution is retried with the `recovery` strategies in the
+    /// given order, all by default: "relaxed" solves it without the singularity handling of
+    /// the continuing solver and without `max_step`, "seeds" from the default seed, the
+    /// stored postures and the zero position, and "interpolate" interpolates the joints
+    /// between the solved rows around the gap. Returns the rows of `solve_and_join` with
+    /// the recovery column naming the strategy that solved a row, None for rows solved
+    /// directly; interpolated rows have the status "interpolated" and residuals showing how
+    /// far they are off the pose. Poses with NaN or infinite values are handled as set by
+    /// `on_invalid`.
+    #[pyo3(signature = (
+        poses,
+        current_joints=None,
+        recovery=None,
+        max_step=None,
+        radians=None,
+        on_invalid=InvalidPolicy::Null,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn solve_continuous<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        current_joints: Option<Start>,
+        recovery: Option<Vec<Recovery>>,
+        max_step: Option<f64>,
+        radians: Option<bool>,
+        on_invalid: InvalidPolicy,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        if let Some(step) = max_step {
+            validation::check_positive("max_step", step)?;
+        }
+        let recovery = recovery.unwrap_or(Recovery::ALL.to_vec());
+        let seed = self.start_joints(current_joints.as_ref(), radians)?;
+        let max_step = max_step.map_or(f64::INFINITY, |step| self.joint_in(step, radians));
+        let poses = validation::screen(poses.0, validation::is_finite_pose, on_invalid)?;
+        self.continuous_rows(py, &poses, seed, &recovery, max_step, radians)
+    }
+
+    /// Validates a conveyor-tracking application offline: shifts the poses, taught on the
+    /// part when it passed the sync point, along the conveyor `direction` (world frame) by
+    /// how far the conveyor has moved since, and solves them as `solve_continuous`. The
+    /// distance is `speed` (m/s) times the time column (t, time or timestamp, in s) or the
+    /// encoder column (counts, encoder or encoder_counts) divided by `counts_per_meter`;
+    /// give exactly one of the two. Returns the rows of `solve_continuous` for the shifted
+    /// poses with the conveyor_shift column (m).
+    #[pyo3(signature = (
+        poses,
+        direction,
+        speed=None,
+        counts_per_meter=None,
+        current_joints=None,
+        recovery=None,
+        max_step=None,
+        radians=None,
+        on_invalid=InvalidPolicy::Null,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn track_conveyor<'py>(
+        &self,
+        py: Python<'py>,
+        poses: &Bound<'py, PyAny>,
+        direction: [f64; 3],
+        speed: Option<f64>,
+        counts_per_meter: Option<f64>,
+        current_joints: Option<Start>,
+        recovery: Option<Vec<Recovery>>,
+        max_step: Option<f64>,
+        radians: Option<bool>,
+        on_invalid: InvalidPolicy,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let direction = conveyor::unit_direction(direction)?;
+        let (positions, scale) = match (speed, counts_per_meter) {
+            (Some(speed), None) => {
+                validation::check_finite("speed", &speed, speed.is_finite())?;
+                (
+                    columns::optional_column::<f64>(poses, &["t", "time", "timestamp"])?,
+                    speed,
+                )
+            }
+            (None, Some(counts)) => {
+                validation::check_positive("counts_per_meter", counts)?;
+                (
+                    columns::optional_column::<f64>(
+                        poses,
+                        &["counts", "encoder", "encoder_counts"],
+                    )?,
+                    1.0 / counts,
+                )
+            }
+            _ => return Err(PyValueError::new_err(
+                "Give either speed with a time column or counts_per_meter with an encoder column",
+            )),
+        };
+        let positions = positions.ok_or_else(|| {
+            PyValueError::new_err(match speed {
+                Some(_) => "The table has no time column (t, time or timestamp)",
+                None => "The table has no encoder column (counts, encoder or encoder_counts)",
+            })
+        })?;
+        let PoseTable(taught) = poses.extract()?;
+        if positions.len() != taught.len() {
+            return Err(PyValueError::new_err(format!(
+                "Got {} conveyor positions for {} poses",
+                positions.len(),
+                taught.len()
+            )));
+        }
+        if let Some(step) = max_step {
+            validation::check_positive("max_step", step)?;
+        }
+        let recovery = recovery.unwrap_or(Recovery::ALL.to_vec());
+        let seed = self.start_joints(current_joints.as_ref(), radians)?;
+        let max_step = max_step.map_or(f64::INFINITY, |step| self.joint_in(step, radians));
+        // A missing position leaves the pose invalid, handled as set by on_invalid
+        let shifted: Vec<(PyPose, f64)> = taught
+            .iter()
+            .zip(positions)
+            .map(|(pose, position)| {
+                let distance = position.unwrap_or(f64::NAN) * scale;
+                (conveyor::shift(pose, &direction, distance), distance)
+            })
+            .collect();
+        let shifted = validation::screen(
+            shifted,
+            |(pose, _)| validation::is_finite_pose(pose),
+            on_invalid,
+        )?;
+        let poses: Vec<PyPose> = shifted.iter().map(|(pose, _)| *pose).collect();
+        let rows = self.continuous_rows(py, &poses, seed, &recovery, max_step, radians)?;
+        for (row, (_, distance)) in rows.iter().zip(&shifted) {
+            row.set_item("conveyor_shift", distance.is_finite().then_some(*distance))?;
+        }
+        Ok(rows)
+    }
+
+    /// Solves a path of poses with one solution per pose. Moving between the solutions of
+    /// consecutive poses costs what `minimize` measures ("travel", "time" or
+    /// "wrist_motion"), plus `config_penalty` (joint units, default half a turn) when the
+    /// configuration changes. The "greedy" selection takes, pose by pose, the cheapest
+    /// solution to move to; "shortest" finds the path of least total cost. "limit_margin"
+    /// makes the smallest distance of any joint to its limits along the path as large as
+    /// possible, then takes the least cost, which keeps the path clear of range ends at the
+    /// cost of some extra wrist travel; it requires joint limits. With joint limits set,
+    /// whole-turn variants of the joints within them are considered. `max_step` bounds the
+    /// change of every joint between consecutive poses, in the joint units.
+    ///
+    /// Raises UnreachablePoseError for a pose without solutions, and ValueError for NaN or
+    /// infinite inputs or when no solutions of consecutive poses lie within `max_step`.
+    #[pyo3(signature = (
+        poses,
+        current_joints=None,
+        selection=PathSelection::Greedy,
+        minimize=PathCost::Travel,
+        config_penalty=None,
+        max_step=None,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn solve_path(
+        &self,
+        py: Python<'_>,
+        poses: PoseTable,
+        current_joints: Option<[f64; 6]>,
+        selection: PathSelection,
+        minimize: PathCost,
+        config_penalty: Option<f64>,
+        max_step: Option<f64>,
+        radians: Option<bool>,
+    ) -> PyResult<Vec<[f64; 6]>> {
+        let PoseTable(poses) = poses;
+        for pose in &poses {
+            validation::check_pose(pose)?;
+        }
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let limits = match (selection, self.joint_limits) {
+            (PathSelection::LimitMargin, None) => {
+                return Err(PyValueError::new_err(
+                    "The limit_margin selection needs joint limits on the robot",
+                ))
+            }
+            (_, limits) => limits,
+        };
+        let seed = current_joints
+            .map(|joints| self.joints_in(joints, radians))
+            .or(self.default_seed);
+        let start = seed.unwrap_or([0.0; 6]);
+        let max_step = max_step.map_or(f64::INFINITY, |step| self.joint_in(step, radians));
+        let config_penalty = config_penalty.map_or(PI, |penalty| self.joint_in(penalty, radians));
+        let options = self.inverse_options(SolutionOrder::Distance, None, true, radians);
+        let cost = |a: &Joints, b: &Joints| {
+            let switches = self.configuration(a) != self.configuration(b);
+            minimize.motion(a, b) + if switches { config_penalty } else { 0.0 }
+        };
+        let (layers, chosen) = py.detach(|| {
+            // Each pose is seeded with the cheapest solution of the previous one so the
+            // candidates keep the turns of the joints along the path
+            let mut layers: Vec<Vec<Joints>> = Vec::with_capacity(poses.len());
+            let mut previous = seed;
+            for pose in &poses {
+                let mut layer = self.candidates(*pose, previous, &options);
+                if let Some(limits) = &limits {
+                    layer = layer
+                        .iter()
+                        .flat_map(|solution| path::turn_variants(solution, limits))
+                        .collect();
+                }
+                let from = previous.unwrap_or(start);
+                previous = path::closest(&layer, &from, f64::INFINITY, cost)
+                    .map(|index| layer[index])
+                    .or(previous);
+                layers.push(layer);
+            }
+            let chosen = match (selection, &limits) {
+                (PathSelection::Greedy, _) => path::greedy(&layers, &start, max_step, cost),
+                (PathSelection::Shortest, _) => {
+                    path::shortest(&layers, &start, max_step, cost, |_, _| true)
+                }
+                (PathSelection::LimitMargin, Some(limits)) => {
+                    path::max_limit_margin(&layers, &start, limits, max_step, cost)
+                }
+                (PathSelection::LimitMargin, None) => unreachable!("checked above"),
+            };
+            (layers, chosen)
+        });
+        match chosen {
+            Ok(chosen) => Ok(layers
+                .iter()
+                .zip(chosen)
+                .map(|(layer, index)| self.joints_out(layer[index], radians))
+                .collect()),
+            Err(i) if layers[i].is_empty() => Err(self.unreachable_error(py, poses[i])),
+            Err(i) => Err(PyValueError::new_err(format!(
+                "No solution of pose {} lies within max_step of a solution of pose {}",
+                i,
+                i - 1
+            ))),
+        }
+    }
+
+    /// Orders independent targets such as drilling holes so that visiting them all from
+    /// `current_joints` (joints or the name of a stored posture, the default seed or the
+    /// zero position without them) costs least by `minimize` ("travel", "time" or
+    /// "wrist_motion"), returning to the start with `return_to_start`. "greedy" always
+    /// moves to the nearest target left, "two_opt" then reverses stretches of that order
+    /// while it gets shorter. Targets are compared by their closest solutions, and the
+    /// solutions along the final order are chosen for the least total cost. Returns a dict
+    /// with the target indices in visiting order (order), the joints at each of them in
+    /// that order (joints), the total cost in the joint units (travel) and the indices of
+    /// the targets without a solution within the joint limits, which are left out
+    /// (unreachable).
+    #[pyo3(signature = (
+        poses,
+        current_joints=None,
+        method=TourMethod::TwoOpt,
+        minimize=PathCost::Travel,
+        return_to_start=false,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn order_targets<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        current_joints: Option<Start>,
+        method: TourMethod,
+        minimize: PathCost,
+        return_to_start: bool,
+        radians: Option<bool>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let PoseTable(poses) = poses;
+        for pose in &poses {
+            validation::check_pose(pose)?;
+        }
+        let start = self
+            .start_joints(current_joints.as_ref(), radians)?
+            .or(self.default_seed)
+            .unwrap_or([0.0; 6]);
+        let options = self.inverse_options(SolutionOrder::Distance, None, true, radians);
+        let cost = |a: &Joints, b: &Joints| minimize.motion(a, b);
+        let (order, joints, unreachable) = py.detach(|| {
+            let mut reachable = Vec::new();
+            let mut unreachable = Vec::new();
+            let mut layers: Vec<Vec<Joints>> = Vec::new();
+            for (i, pose) in poses.iter().enumerate() {
+                let mut layer = self.candidates(*pose, Some(start), &options);
+                if let Some(limits) = &self.joint_limits {
+                    layer = layer
+                        .iter()
+                        .flat_map(|solution| path::turn_variants(solution, limits))
+                        .collect();
+                }
+                match layer.is_empty() {
+                    true => unreachable.push(i),
+                    false => {
+                        reachable.push(i);
+                        layers.push(layer);
+                    }
+                }
+            }
+            // Node 0 is the start, node k + 1 the k-th reachable target
+            let nodes: Vec<&[Joints]> = std::iter::once(std::slice::from_ref(&start))
+                .chain(layers.iter().map(Vec::as_slice))
+                .collect();
+            let distances: Vec<Vec<f64>> = nodes
+                .iter()
+                .map(|a| {
+                    nodes
+                        .iter()
+                        .map(|b| {
+                            a.iter()
+                                .flat_map(|x| b.iter().map(move |y| cost(x, y)))
+                                .fold(f64::INFINITY, f64::min)
+                        })
+                        .collect()
+                })
+                .collect();
+            let tour = tour::order(&distances, method, return_to_start);
+            let mut ordered: Vec<Vec<Joints>> = tour.iter().map(|&k| layers[k].clone()).collect();
+            if return_to_start {
+                ordered.push(vec![start]);
+            }
+            let chosen = path::shortest(&ordered, &start, f64::INFINITY, cost, |_, _| true)
+                .expect("every layer has solutions and the steps are unbounded");
+            let joints: Vec<Joints> = ordered
+                .iter()
+                .zip(chosen)
+                .map(|(layer, index)| layer[index])
+                .collect();
+            let order: Vec<usize> = tour.iter().map(|&k| reachable[k]).collect();
+            (order, joints, unreachable)
+        });
+        let travel: f64 = std::iter::once(&start)
+            .chain(&joints)
+            .zip(&joints)
+            .map(|(a, b)| cost(a, b))
+            .sum();
+        let visited = &joints[..order.len()];
+        let result = PyDict::new(py);
+        result.set_item("order", order)?;
+        result.set_item(
+            "joints",
+            visited
+                .iter()
+                .map(|joints| self.joints_out(*joints, radians))
+                .collect::<Vec<_>>(),
+        )?;
+        result.set_item("travel", self.joint_out(travel, radians))?;
+        result.set_item("unreachable", unreachable)?;
+        Ok(result)
+    }
+
+    /// Solves a path for a rotationally symmetric process (milling, gluing, round welding
+    /// torches) whose tool roll about the tool axis (`tool_axis`, the convention of
+    /// set_tool_axis by default) is free. Every pose is tried at
+    /// `n_samples` rolls, spread over a full turn or, with `max_roll` (degrees), over
+    /// -max_roll..max_roll from the given orientation, and the rolls and solutions are
+    /// chosen over the whole path so that moving between consecutive poses costs least by
+    /// `minimize`, the wrist motion by default, which keeps J4 and J6 from winding up.
+    /// `max_step` bounds the change of every joint between consecutive poses, in the joint
+    /// units. Returns the rows of `solve_and_join` for the rolled poses, with the roll
+    /// (degrees) applied to each.
+    ///
+    /// Raises UnreachablePoseError for a pose without solutions at any roll, and ValueError
+    /// for NaN or infinite inputs or when no solutions of consecutive poses lie within
+    /// `max_step`.
+    #[pyo3(signature = (
+        poses,
+        current_joints=None,
+        n_samples=36,
+        max_roll=None,
+        minimize=PathCost::WristMotion,
+        max_step=None,
+        radians=None,
+        tool_axis=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn optimize_roll<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        current_joints: Option<[f64; 6]>,
+        n_samples: usize,
+        max_roll: Option<f64>,
+        minimize: PathCost,
+        max_step: Option<f64>,
+        radians: Option<bool>,
+        tool_axis: Option<Axis>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let PoseTable(poses) = poses;
+        let tool_axis = Axis::tool(tool_axis).unit();
+        for pose in &poses {
+            validation::check_pose(pose)?;
+        }
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        if n_samples == 0 {
+            return Err(PyValueError::new_err("n_samples must be at least 1"));
+        }
+        let rolls: Vec<f64> = match max_roll {
+            None => (0..n_samples)
+                .map(|k| 2.0 * PI * k as f64 / n_samples as f64)
+                .collect(),
+            Some(max_roll) => {
+                validation::check_positive("max_roll", max_roll)?;
+                let max_roll = max_roll.to_radians();
+                match n_samples {
+                    1 => vec![0.0],
+                    _ => (0..n_samples)
+                        .map(|k| -max_roll + 2.0 * max_roll * k as f64 / (n_samples - 1) as f64)
+                        .collect(),
+                }
+            }
+        };
+        let seed = current_joints
+            .map(|joints| self.joints_in(joints, radians))
+            .or(self.default_seed);
+        let start = seed.unwrap_or([0.0; 6]);
+        let max_step = max_step.map_or(f64::INFINITY, |step| self.joint_in(step, radians));
+        let options = self.inverse_options(SolutionOrder::Distance, None, true, radians);
+        let cost = |a: &Joints, b: &Joints| minimize.motion(a, b);
+        let rolled = |pose: &PyPose, roll: f64| {
+            let spin = Isometry3::rotation(tool_axis * roll);
+            from_isometry(&(to_isometry(pose.0, pose.1) * spin))
+        };
+        let (layers, chosen) = py.detach(|| {
+            // Candidates are (roll, joints), each pose seeded with the cheapest candidate of
+            // the previous one so the joints keep their turns along the path
+            let mut layers: Vec<Vec<(f64, Joints)>> = Vec::with_capacity(poses.len());
+            let mut previous = seed;
+            for pose in &poses {
+                let layer: Vec<(f64, Joints)> = rolls
+                    .iter()
+                    .flat_map(|&roll| {
+                        self.candidates(rolled(pose, roll), previous, &options)
+                            .into_iter()
+                            .map(move |joints| (roll, joints))
+                    })
+                    .collect();
+                let joints: Vec<Joints> = layer.iter().map(|(_, joints)| *joints).collect();
+                let from = previous.unwrap_or(start);
+                previous = path::closest(&joints, &from, f64::INFINITY, cost)
+                    .map(|index| joints[index])
+                    .or(previous);
+                layers.push(layer);
+            }
+            let joints: Vec<Vec<Joints>> = layers
+                .iter()
+                .map(|layer| layer.iter().map(|(_, joints)| *joints).collect())
+                .collect();
+            let chosen = path::shortest(&joints, &start, max_step, cost, |_, _| true);
+            (layers, chosen)
+        });
+        let chosen = match chosen {
+            Ok(chosen) => chosen,
+            Err(i) if layers[i].is_empty() => return Err(self.unreachable_error(py, poses[i])),
+            Err(i) => {
+                return Err(PyValueError::new_err(format!(
+                    "No solution of pose {} lies within max_step of a solution of pose {}",
+                    i,
+                    i - 1
+                )))
+            }
+        };
+        poses
+            .iter()
+            .zip(layers.iter().zip(chosen))
+            .map(|(pose, (layer, index))| {
+                let (roll, joints) = layer[index];
+                let pose = rolled(pose, roll);
+                let (position, rotation) = pose::pose_errors(
+                    &self.reported(&self.forward_radians(&joints)),
+                    &to_isometry(pose.0, pose.1),
+                );
+                let row = SolvedRow {
+                    pose,
+                    joints: Some(self.joints_out(joints, radians)),
+                    configuration: Some(self.configuration(&joints)),
+                    residual: Some((position, rotation.to_degrees())),
+                }
+                .to_dict(py)?;
+                // Rolls past half a turn read as the shorter turn the other way
+                let roll = (roll + PI).rem_euclid(2.0 * PI) - PI;
+                row.set_item("roll", roll.to_degrees())?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Keeps the poses that have at least one solution within the joint limits that is not
+    /// at a singularity, J5 within `singularity_tolerance_deg` (the robot's by default) of a
+    /// multiple of 180 degrees. Poses are checked in parallel, `n_threads`, `chunk_size` and
+    /// `pool` as in `batch_inverse`, poses with NaN or infinite values are removed. Returns
+    /// (reachable poses, number of poses removed).
+    #[pyo3(signature = (
+        poses, n_threads=None, chunk_size=None, pool=None, singularity_tolerance_deg=None
+    ))]
+    fn filter_reachable(
+        &self,
+        py: Python<'_>,
+        poses: PoseTable,
+        n_threads: Option<usize>,
+        chunk_size: Option<usize>,
+        pool: Option<ThreadPool>,
+        singularity_tolerance_deg: Option<f64>,
+    ) -> PyResult<(Vec<PyPose>, usize)> {
+        let tolerance = self.singularity_tolerance(singularity_tolerance_deg)?;
+        let pool = ThreadPool::resolve(pool.as_ref(), n_threads, chunk_size)?;
+        let PoseTable(poses) = poses;
+        let options = InverseOptions::default();
+        let reachable = py.detach(|| {
+            pool.map(&poses, |&pose| {
+                validation::is_finite_pose(&pose)
+                    && self
+                        .solve_radians(pose, None, &options)
+                        .iter()
+                        .any(|solution| self.is_regular(solution, tolerance))
+            })
+        });
+        let total = poses.len();
+        let kept: Vec<PyPose> = poses
+            .into_iter()
+            .zip(reachable)
+            .filter_map(|(pose, reachable)| reachable.then_some(pose))
+            .collect();
+        let removed = total - kept.len();
+        Ok((kept, removed))
+    }
+
+    /// How much room the wrist center of every pose has before it leaves the shell the arm
+    /// can reach, e.g. to find the points of a path where a small correction would make it
+    /// unreachable. Rows have the wrist center in the base frame, outer_margin (m, inside
+    /// the outer sphere), inner_margin (outside the interior void the arm cannot fold
+    /// into), margin (the least of these and of the clearance from the column around J1
+    /// that robots with an offset b cannot enter), the boundary it is closest to ("outer",
+    /// "inner" or "offset"), and with `margin` (m) whether it lies closer than that to it,
+    /// near_boundary. Margins are negative outside the shell; joint limits and link
+    /// corrections are not considered. Poses with NaN or infinite values are handled as
+    /// set by `on_invalid`.
+    #[pyo3(signature = (poses, margin=None, on_invalid=InvalidPolicy::Null))]
+    fn reach_margin<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        margin: Option<f64>,
+        on_invalid: InvalidPolicy,
+    ) -> PyResult<Vec<Option<Bound<'py, PyDict>>>> {
+        if let Some(margin) = margin {
+            validation::check_finite("margin", &margin, margin.is_finite())?;
+        }
+        let poses = validation::screen(poses.0, validation::is_finite_pose, on_invalid)?;
+        let (base, tool) = (self.base().inverse(), self.tool().inverse());
+        poses
+            .iter()
+            .map(|pose| {
+                if !validation::is_finite_pose(pose) {
+                    return Ok(None);
+                }
+                let flange = base * self.target(pose) * tool;
+                let reach = self._kinematic_model.wrist_reach(&flange);
+                let margins = reach.margins();
+                // Without an offset b the column around J1 shrinks to its axis, which the
+                // wrist can reach
+                let boundaries = if self._kinematic_model.b == 0.0 { 2 } else { 3 };
+                let (closest, least) = ["outer", "inner", "offset"]
+                    .into_iter()
+                    .zip(margins)
+                    .take(boundaries)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .expect("three margins");
+                let row = PyDict::new(py);
+                for (column, value) in ["wrist_x", "wrist_y", "wrist_z"]
+                    .iter()
+                    .zip(reach.center.iter())
+                {
+                    row.set_item(column, value)?;
+                }
+                row.set_item("outer_margin", margins[0])?;
+                row.set_item("inner_margin", margins[1])?;
+                row.set_item("margin", least)?;
+                row.set_item("boundary", closest)?;
+                if let Some(margin) = margin {
+                    row.set_item("near_boundary", least < margin)?;
+                }
+                Ok(Some(row))
+            })
+            .collect()
+    }
+
+    /// Explains why a pose cannot be reached. The reason is "reachable", "outside_reach"
+    /// (the wrist center lies beyond the reach of the arm), "interior_void" (it lies in the
+    /// void the arm cannot fold into), "joint_limits" (every solution has a joint beyond its
+    /// limits) or "orientation" (the position is reachable with the tool tilted up to
+    /// `max_tilt`, 45 degrees by default, or rolled, sampled at `n_samples` angles each as in
+    /// orientation_reachability). Also returns the wrist center in the base frame, its
+    /// distance from the J2 axis, the inner and outer radius of the reachable shell and how
+    /// far outside it lies (m), the number of analytic solutions and of those within the
+    /// limits, and for the solution missing the limits by least how far each joint lies
+    /// beyond them (limit_excess, joint units) and which joints block.
+    #[pyo3(signature = (pose, max_tilt=None, n_samples=8, radians=None))]
+    fn why_unreachable<'py>(
+        &self,
+        py: Python<'py>,
+        pose: PyPose,
+        max_tilt: Option<f64>,
+        n_samples: usize,
+        radians: Option<bool>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        validation::check_pose(&pose)?;
+        if n_samples == 0 {
+            return Err(PyValueError::new_err("n_samples must be positive"));
+        }
+        let max_tilt = max_tilt.map_or(PI / 4.0, |tilt| self.joint_in(tilt, radians));
+        if !(max_tilt.is_finite() && max_tilt >= 0.0) {
+            return Err(PyValueError::new_err(
+                "max_tilt must be finite and not negative",
+            ));
+        }
+        let target = to_isometry(pose.0, pose.1);
+        let flange = self.base().inverse() * self.target(&pose) * self.tool().inverse();
+        let reach = self._kinematic_model.wrist_reach(&flange);
+        let solutions: Vec<Joints> = self.branches(pose).into_iter().flatten().collect();
+        let valid = solutions
+            .iter()
+            .filter(|joints| self.within_limits(joints))
+            .count();
+        let excess = self
+            .joint_limits
+            .and_then(|limits| diagnosis::least_excess(&solutions, &limits))
+            .filter(|_| valid == 0);
+
+        let orientations = reachability::fan(&target.rotation, max_tilt, n_samples);
+        let options = InverseOptions::default();
+        let reachable_orientations = if valid > 0 {
+            orientations.len()
+        } else {
+            py.detach(|| {
+                orientations
+                    .iter()
+                    .filter(|(_, _, rotation)| {
+                        let pose =
+                            from_isometry(&Isometry3::from_parts(target.translation, *rotation));
+                        !self.candidates(pose, None, &options).is_empty()
+                    })
+                    .count()
+            })
+        };
+        let reason = if valid > 0 {
+            Reason::Reachable
+        } else if reachable_orientations > 0 {
+            Reason::Orientation
+        } else if reach.deficit() > 0.0 && reach.beyond() {
+            Reason::OutsideReach
+        } else if reach.deficit() > 0.0 {
+            Reason::InteriorVoid
+        } else {
+            Reason::JointLimits
+        };
+
+        let dict = PyDict::new(py);
+        dict.set_item("reason", reason.name())?;
+        dict.set_item("distance", reach.deficit())?;
+        dict.set_item("wrist_center", <[f64; 3]>::from(reach.center))?;
+        dict.set_item("wrist_reach", reach.reach)?;
+        dict.set_item("min_reach", reach.min_reach)?;
+        dict.set_item("max_reach", reach.max_reach)?;
+        dict.set_item("solutions", solutions.len())?;
+        dict.set_item("within_limits", valid)?;
+        dict.set_item(
+            "limit_excess",
+            excess.map(|excess| self.joints_out(excess, radians)),
+        )?;
+        let blocking: Option<Vec<&str>> = excess.map(|excess| {
+            JOINT_COLUMNS
+                .iter()
+                .zip(excess)
+                .filter(|(_, excess)| *excess > 0.0)
+                .map(|(name, _)| *name)
+                .collect()
+        });
+        dict.set_item("blocking_joints", blocking)?;
+        dict.set_item("reachable_orientations", reachable_orientations)?;
+        dict.set_item("sampled_orientations", orientations.len())?;
+        Ok(dict)
+    }
+
+    /// Nearest reachable pose to `pose`, for planners that would rather move a target a
+    /// little than drop it. Unreachable positions are moved towards the middle of the
+    /// reachable shell until a solution within the joint limits exists, refined to
+    /// `tolerance` (m). With `relax_orientation` the tool may also tilt up to `max_tilt`, 45
+    /// degrees by default, or roll, sampled at `n_samples` angles each as in
+    /// orientation_reachability, and the orientation that moves the position least wins.
+    /// Returns the projected pose, the solution nearest to `current_joints`, the distance
+    /// (m) and rotation (joint units) it moved and whether it moved at all, with pose and
+    /// joints None if no pose was found.
+    #[pyo3(signature = (
+        pose,
+        current_joints=None,
+        relax_orientation=false,
+        max_tilt=None,
+        n_samples=8,
+        tolerance=1e-4,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn project_to_workspace<'py>(
+        &self,
+        py: Python<'py>,
+        pose: PyPose,
+        current_joints: Option<[f64; 6]>,
+        relax_orientation: bool,
+        max_tilt: Option<f64>,
+        n_samples: usize,
+        tolerance: f64,
+        radians: Option<bool>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        validation::check_pose(&pose)?;
+        let relax =
+            self.projection_settings(relax_orientation, max_tilt, n_samples, tolerance, radians)?;
+        let target = to_isometry(pose.0, pose.1);
+        let projected = py.detach(|| self.project(&target, relax, tolerance));
+
+        let dict = PyDict::new(py);
+        let moved = projected.map(|projected| projection::Move::between(&target, &projected));
+        let joints = projected.and_then(|projected| {
+            let seed = current_joints.map(|joints| self.joints_in(joints, radians));
+            let options = self.inverse_options(SolutionOrder::Distance, None, true, radians);
+            self.solve_radians(from_isometry(&projected), seed, &options)
+                .first()
+                .map(|joints| self.joints_out(*joints, radians))
+        });
+        dict.set_item("pose", projected.map(|projected| from_isometry(&projected)))?;
+        dict.set_item("joints", joints)?;
+        dict.set_item("distance", moved.as_ref().map(|moved| moved.shift.norm()))?;
+        dict.set_item(
+            "rotation",
+            moved
+                .as_ref()
+                .map(|moved| self.joint_out(moved.rotation, radians)),
+        )?;
+        dict.set_item("moved", projected.map(|projected| projected != target))?;
+        Ok(dict)
+    }
+
+    /// Projects every pose of a table onto the workspace as `project_to_workspace` does,
+    /// fixing rows that are slightly out of reach and flagging the rest. A projection is
+    /// accepted if it moves the position by at most `max_move` (m, per world axis x, y, z)
+    /// and turns the tool by at most `max_rotation` (joint units), either unbounded if None.
+    /// Returns one row per pose with the pose columns (x, y, z, qw, qx, qy, qz) of the
+    /// projected pose, or of the pose as given if it was not accepted, the move dx, dy, dz,
+    /// distance (m) and rotation, and the status "reachable", "projected", "too_far" (the
+    /// projection moves beyond the tolerance), "unreachable" or "invalid" (NaN or infinite
+    /// values, handled as set by `on_invalid`). `progress`, `cancel`, `n_threads`,
+    /// `chunk_size` and `pool` as in `batch_inverse`.
+    #[pyo3(signature = (
+        poses,
+        max_move=None,
+        max_rotation=None,
+        relax_orientation=false,
+        max_tilt=None,
+        n_samples=8,
+        tolerance=1e-4,
+        radians=None,
+        on_invalid=InvalidPolicy::Null,
+        progress=None,
+        cancel=None,
+        n_threads=None,
+        chunk_size=None,
+        pool=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn batch_project_to_workspace<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        max_move: Option<[f64; 3]>,
+        max_rotation: Option<f64>,
+        relax_orientation: bool,
+        max_tilt: Option<f64>,
+        n_samples: usize,
+        tolerance: f64,
+        radians: Option<bool>,
+        on_invalid: InvalidPolicy,
+        progress: Option<&Bound<'_, PyAny>>,
+        cancel: Option<PyRef<'_, CancelToken>>,
+        n_threads: Option<usize>,
+        chunk_size: Option<usize>,
+        pool: Option<ThreadPool>,
+    ) -> PyResult<Rows<'py>> {
+        let relax =
+            self.projection_settings(relax_orientation, max_tilt, n_samples, tolerance, radians)?;
+        if let Some(max_move) = &max_move {
+            let valid = max_move.iter().all(|x| x.is_finite() && *x >= 0.0);
+            validation::check_finite("max_move", max_move, valid)?;
+        }
+        let max_rotation = max_rotation.map(|rotation| self.joint_in(rotation, radians));
+        if max_rotation.is_some_and(|rotation| !(rotation.is_finite() && rotation >= 0.0)) {
+            return Err(PyValueError::new_err(
+                "max_rotation must be finite and not negative",
+            ));
+        }
+        let pool = ThreadPool::resolve(pool.as_ref(), n_threads, chunk_size)?;
+        let poses = validation::screen(poses.0, validation::is_finite_pose, on_invalid)?;
+        let results = batch::run(py, &poses, progress, cancel.as_deref(), &pool, |pose| {
+            validation::is_finite_pose(pose).then(|| {
+                let target = to_isometry(pose.0, pose.1);
+                self.project(&target, relax, tolerance)
+                    .map(|projected| projection::Move::between(&target, &projected))
+            })
+        })?;
+        poses
+            .iter()
+            .zip(results)
+            .map(|(pose, result)| {
+                let outcome = match &result {
+                    None => Outcome::Invalid,
+                    Some(None) => Outcome::Unreachable,
+                    Some(Some(moved)) => moved.outcome(max_move, max_rotation),
+                };
+                let moved = result.flatten();
+                let pose = match (outcome, &moved) {
+                    (Outcome::Projected, Some(moved)) => from_isometry(&moved.to),
+                    _ => *pose,
+                };
+                let row = PyDict::new(py);
+                for (column, value) in POSE_COLUMNS.iter().zip(pose.0.iter().chain(&pose.1)) {
+                    row.set_item(column, value)?;
+                }
+                for (k, column) in ["dx", "dy", "dz"].iter().enumerate() {
+                    row.set_item(column, moved.as_ref().map(|moved| moved.shift[k]))?;
+                }
+                row.set_item("distance", moved.as_ref().map(|moved| moved.shift.norm()))?;
+                row.set_item(
+                    "rotation",
+                    moved
+                        .as_ref()
+                        .map(|moved| self.joint_out(moved.rotation, radians)),
+                )?;
+                row.set_item("status", outcome.name())?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Groups the solutions of poses sampling a target region into configuration families,
+    /// the eight analytic branches, and reports which families reach every pose. Returns a
+    /// row per branch with its shoulder, elbow and wrist labels, the number of poses it
+    /// reaches within the joint limits, how many of those are at the wrist singularity,
+    /// the fraction reached, whether it covers the whole region and the indices of the
+    /// poses it misses. With `exclude_singular` singular solutions do not count as reached,
+    /// so a covering family can be used everywhere without wrist flips.
+    #[pyo3(signature = (poses, exclude_singular=false))]
+    fn configuration_coverage<'py>(
+        &self,
+        py: Python<'py>,
+        poses: PoseTable,
+        exclude_singular: bool,
+    ) -> PyResult<Rows<'py>> {
+        let poses = validation::screen(poses.0, validation::is_finite_pose, InvalidPolicy::Raise)?;
+        if poses.is_empty() {
+            return Err(PyValueError::new_err(
+                "Expected at least one pose of the region",
+            ));
+        }
+        // Per pose and branch: reached within limits, and regular there
+        let reached: Vec<[Option<bool>; branches::BRANCHES]> = py.detach(|| {
+            ThreadPool::default().map(&poses, |pose| {
+                self.branches(*pose).map(|joints| {
+                    joints
+                        .filter(|joints| self.within_limits(joints))
+                        .map(|joints| self.is_regular(&joints, self.singularity_tolerance))
+                })
+            })
+        });
+        (0..branches::BRANCHES)
+            .map(|branch| {
+                let mut count = 0;
+                let mut singular = 0;
+                let mut missing = Vec::new();
+                for (i, pose) in reached.iter().enumerate() {
+                    match pose[branch] {
+                        Some(true) => count += 1,
+                        Some(false) if !exclude_singular => {
+                            count += 1;
+                            singular += 1;
+                        }
+                        _ => missing.push(i),
+                    }
+                }
+                let (shoulder, elbow, wrist) = branches::labels(branch as u8);
+                let row = PyDict::new(py);
+                row.set_item("branch", branch)?;
+                row.set_item("shoulder", shoulder)?;
+                row.set_item("elbow", elbow)?;
+                row.set_item("wrist", wrist)?;
+                row.set_item("reached", count)?;
+                row.set_item("singular", singular)?;
+                row.set_item("fraction", count as f64 / poses.len() as f64)?;
+                row.set_item("covers_region", missing.is_empty())?;
+                row.set_item("missing", missing)?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Sweeps the tool orientation at `point`: the tool axis (see set_tool_axis) along
+    /// `axis`, tilted by
+    /// `n_samples` angles up to `max_tilt` either way about the reference x axis (the
+    /// `reference` direction made normal to `axis`, world x by default), each rolled by
+    /// `n_samples` angles over a full turn about the tool axis. Returns a row per
+    /// orientation with tilt, roll, the quaternion, whether it is reachable, the number of
+    /// solutions and the joints of the solution with the best manipulability. Angles are in
+    /// the units of the robot, `max_tilt` defaults to 90 degrees.
+    #[pyo3(signature = (point, axis, n_samples=12, max_tilt=None, reference=None))]
+    fn orientation_reachability<'py>(
+        &self,
+        py: Python<'py>,
+        point: [f64; 3],
+        axis: [f64; 3],
+        n_samples: usize,
+        max_tilt: Option<f64>,
+        reference: Option<[f64; 3]>,
+    ) -> PyResult<Rows<'py>> {
+        validation::check_finite("point", &point, point.iter().all(|x| x.is_finite()))?;
+        if n_samples == 0 {
+            return Err(PyValueError::new_err("n_samples must be positive"));
+        }
+        let max_tilt = max_tilt.map_or(PI / 2.0, |tilt| self.joint_in(tilt, None));
+        if !(max_tilt.is_finite() && max_tilt >= 0.0) {
+            return Err(PyValueError::new_err(
+                "max_tilt must be finite and not negative",
+            ));
+        }
+        let frame = match reference {
+            Some(reference) => pose::rotation_from_axes(axis, reference),
+            None => pose::rotation_from_axes(axis, [1.0, 0.0, 0.0])
+                .or_else(|| pose::rotation_from_axes(axis, [0.0, 1.0, 0.0])),
+        }
+        .ok_or_else(|| {
+            PyValueError::new_err("axis must be non-zero and not parallel to the reference")
+        })?;
+        let frame = to_isometry([0.0; 3], frame).rotation;
+
+        let tool_rotation = Axis::tool(None).tool_rotation();
+        let orientations: Vec<_> = reachability::fan(&frame, max_tilt, n_samples)
+            .into_iter()
+            .map(|(tilt, roll, rotation)| (tilt, roll, rotation * tool_rotation))
+            .collect();
+        let options = InverseOptions::default();
+        let forward = |joints: &Joints| self.forward_radians(joints);
+        let results: Vec<(usize, Option<(Joints, f64)>)> = py.detach(|| {
+            orientations
+                .iter()
+                .map(|(_, _, rotation)| {
+                    let pose = from_isometry(&Isometry3::from_parts(point.into(), *rotation));
+                    let solutions = self.candidates(pose, None, &options);
+                    let best = solutions
+                        .iter()
+                        .map(|joints| (*joints, reachability::manipulability(forward, joints)))
+                        .max_by(|a, b| a.1.total_cmp(&b.1));
+                    (solutions.len(), best)
+                })
+                .collect()
+        });
+        orientations
+            .iter()
+            .zip(results)
+            .map(|((tilt, roll, rotation), (count, best))| {
+                let row = PyDict::new(py);
+                row.set_item("tilt", self.joint_out(*tilt, None))?;
+                row.set_item("roll", self.joint_out(*roll, None))?;
+                let (_, quaternion) =
+                    from_isometry(&Isometry3::from_parts(point.into(), *rotation));
+                for (name, value) in ["qw", "qx", "qy", "qz"].iter().zip(quaternion) {
+                    row.set_item(name, value)?;
+                }
+                row.set_item("reachable", count > 0)?;
+                row.set_item("solutions", count)?;
+                row.set_item("manipulability", best.map(|(_, value)| value))?;
+                let joints = best.map(|(joints, _)| self.joints_out(joints, None));
+                for (i, name) in JOINT_COLUMNS.iter().enumerate() {
+                    row.set_item(name, joints.map(|joints| joints[i]))?;
+                }
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// ABB axis configuration (cf1, cf4, cf6, cfx) of the given joints
+    #[pyo3(signature = (joints, radians=None))]
+    fn axis_configuration(
+        &self,
+        joints: [f64; 6],
+        radians: Option<bool>,
+    ) -> PyResult<(i32, i32, i32, u8)> {
+        validation::check_joints("joints", &joints)?;
+        Ok(self.axis_configuration_radians(&self.joints_in(joints, radians)))
+    }
+
+    /// Axis configuration of many joint sets, one row per set with the joints (j1..j6)
+    /// followed by CF1, CF4, CF6 and CFX, plus the KUKA Status/Turn (S, T) or the Fanuc
+    /// configuration string (FANUC) when `vendor` is given. Joint sets with NaN or infinite
+    /// values are handled as set by `on_invalid`, "null" leaves their flags None.
+    #[pyo3(signature = (joints, vendor=None, radians=None, on_invalid=InvalidPolicy::Null))]
+    fn batch_axis_configuration<'py>(
+        &self,
+        py: Python<'py>,
+        joints: JointTable,
+        vendor: Option<&str>,
+        radians: Option<bool>,
+        on_invalid: InvalidPolicy,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let vendor = vendor.map(Vendor::parse).transpose()?;
+        let joints = validation::screen(joints.0, validation::is_finite_joints, on_invalid)?;
+        joints
+            .iter()
+            .map(|joint_set| {
+                let radians = self.joints_in(*joint_set, radians);
+                let unmastered = self.unmastered(&radians);
+                rows::configuration_row(py, &self._kinematic_model, joint_set, &unmastered, vendor)
+            })
+            .collect()
+    }
+
+    /// Checks the axis configuration exported controller programs give for joint sets
+    /// against the one computed here. `joints` is a table with the joint columns and the
+    /// expected CF1, CF4, CF6 and CFX columns, plus the KUKA Status/Turn (S, T) or the
+    /// Fanuc configuration string (FANUC) when `vendor` is given; missing columns and empty
+    /// cells are not checked. Returns one row per joint set that disagrees, with its row
+    /// index, the joints as given, the names of the mismatched columns and each checked
+    /// column as computed next to the expected value (e.g. CFX and CFX_expected).
+    #[pyo3(signature = (joints, vendor=None, radians=None))]
+    fn validate_configurations<'py>(
+        &self,
+        py: Python<'py>,
+        joints: &Bound<'py, PyAny>,
+        vendor: Option<&str>,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        let vendor = vendor.map(Vendor::parse).transpose()?;
+        let JointTable(table) = joints.extract()?;
+        let table = validation::screen(table, validation::is_finite_joints, InvalidPolicy::Raise)?;
+        let mut expected = Vec::new();
+        for (k, column) in confdata::columns(vendor).into_iter().enumerate() {
+            let name = column.to_lowercase();
+            if let Some(values) = columns::optional_column::<confdata::Flag>(joints, &[&name])? {
+                if values.len() != table.len() {
+                    return Err(PyValueError::new_err(format!(
+                        "The {} column has {} rows, the joints {}",
+                        column,
+                        values.len(),
+                        table.len()
+                    )));
+                }
+                expected.push((k, column, values));
+            }
+        }
+        if expected.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "The table has none of the configuration columns {}",
+                confdata::columns(vendor).join(", ")
+            )));
+        }
+        let mut rows = Vec::new();
+        for (i, joint_set) in table.iter().enumerate() {
+            let joints = self.unmastered(&self.joints_in(*joint_set, radians));
+            let flags = confdata::flags(&self._kinematic_model, &joints, vendor);
+            let mismatches: Vec<&str> = expected
+                .iter()
+                .filter(|(k, _, values)| {
+                    values[i]
+                        .as_ref()
+                        .is_some_and(|value| !value.matches(&flags[*k]))
+                })
+                .map(|(_, column, _)| *column)
+                .collect();
+            if mismatches.is_empty() {
+                continue;
+            }
+            let row = PyDict::new(py);
+            row.set_item("row", i)?;
+            for (column, value) in JOINT_COLUMNS.iter().zip(joint_set) {
+                row.set_item(column, value)?;
+            }
+            row.set_item("mismatches", mismatches)?;
+            for (k, column, values) in &expected {
+                row.set_item(*column, flags[*k].to_object(py)?)?;
+                let value = values[i]
+                    .as_ref()
+                    .map(|value| value.to_object(py))
+                    .transpose()?;
+                row.set_item(format!("{}_expected", column), value)?;
+            }
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Finds where the axis configuration changes along a trajectory of joint sets. Returns
+    /// one row per joint set with its CFX and whether it differs from the previous row
+    /// (`config_change`) and its segment number, plus one summary per segment of equal
+    /// configuration (start and end rows, inclusive). With `include_quadrants` changes of
+    /// CF1, CF4 or CF6 also start a new segment.
+    #[pyo3(signature = (joints, include_quadrants=false, radians=None))]
+    fn detect_config_changes<'py>(
+        &self,
+        py: Python<'py>,
+        joints: JointTable,
+        include_quadrants: bool,
+        radians: Option<bool>,
+    ) -> PyResult<(Rows<'py>, Rows<'py>)> {
+        let joints =
+            validation::screen(joints.0, validation::is_finite_joints, InvalidPolicy::Raise)?;
+        let configurations: Vec<(i32, i32, i32, u8)> = joints
+            .iter()
+            .map(|joint_set| {
+                let (cf1, cf4, cf6, cfx) = self.axis_configuration(*joint_set, radians)?;
+                Ok(if include_quadrants {
+                    (cf1, cf4, cf6, cfx)
+                } else {
+                    (0, 0, 0, cfx)
+                })
+            })
+            .collect::<PyResult<_>>()?;
+        let segments = configuration::segments(&configurations);
+
+        let mut rows = Vec::with_capacity(joints.len());
+        for (segment, (start, end, _)) in segments.iter().enumerate() {
+            for (offset, (_, _, _, cfx)) in configurations[*start..=*end].iter().enumerate() {
+                let dict = PyDict::new(py);
+                dict.set_item("CFX", cfx)?;
+                dict.set_item("config_change", offset == 0 && segment > 0)?;
+                dict.set_item("segment", segment)?;
+                rows.push(dict);
+            }
+        }
+        let summaries = segments
+            .iter()
+            .map(|(start, end, (cf1, cf4, cf6, cfx))| {
+                let dict = PyDict::new(py);
+                dict.set_item("start", start)?;
+                dict.set_item("end", end)?;
+                dict.set_item("rows", end - start + 1)?;
+                dict.set_item("CFX", cfx)?;
+                if include_quadrants {
+                    dict.set_item("CF1", cf1)?;
+                    dict.set_item("CF4", cf4)?;
+                    dict.set_item("CF6", cf6)?;
+                }
+                Ok(dict)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok((rows, summaries))
+    }
+
+    /// Splits a solved trajectory into the segments a program can run as linear moves: a
+    /// new segment starts wherever the axis configuration changes (CF1, CF4 and CF6 as
+    /// well with `include_quadrants`) or a joint jumps by `flip_threshold` or more between
+    /// rows, 180 degrees by default, as a ±360° flip of a wrapped joint does. Returns one
+    /// row per segment with start and end (inclusive) rows, rows, the reason it starts
+    /// ("start", "configuration", "flip" or "configuration+flip"), the flipped joints,
+    /// CFX (and CF1, CF4, CF6 with `include_quadrants`), the travel of every joint
+    /// (travel_j1..travel_j6) and the largest step of any joint between rows (max_step), in
+    /// joint units.
+    #[pyo3(signature = (joints, include_quadrants=false, flip_threshold=None, radians=None))]
+    fn segment_trajectory<'py>(
+        &self,
+        py: Python<'py>,
+        joints: JointTable,
+        include_quadrants: bool,
+        flip_threshold: Option<f64>,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        let flip_threshold =
+            flip_threshold.map_or(PI, |threshold| self.joint_in(threshold, radians));
+        validation::check_positive("flip_threshold", flip_threshold)?;
+        let joints =
+            validation::screen(joints.0, validation::is_finite_joints, InvalidPolicy::Raise)?;
+        let joints: Vec<Joints> = joints
+            .iter()
+            .map(|joint_set| self.joints_in(*joint_set, radians))
+            .collect();
+        let configurations: Vec<(i32, i32, i32, u8)> = joints
+            .iter()
+            .map(|joint_set| {
+                let (cf1, cf4, cf6, cfx) = self.axis_configuration_radians(joint_set);
+                if include_quadrants {
+                    (cf1, cf4, cf6, cfx)
+                } else {
+                    (0, 0, 0, cfx)
+                }
+            })
+            .collect();
+        segmentation::split(&joints, &configurations, flip_threshold)
+            .iter()
+            .map(|segment| {
+                let reason = match (segment.configuration_change, !segment.flipped.is_empty()) {
+                    (true, true) => "configuration+flip",
+                    (true, false) => "configuration",
+                    (false, true) => "flip",
+                    (false, false) => "start",
+                };
+                let (cf1, cf4, cf6, cfx) = segment.configuration;
+                let row = PyDict::new(py);
+                row.set_item("start", segment.start)?;
+                row.set_item("end", segment.end)?;
+                row.set_item("rows", segment.end - segment.start + 1)?;
+                row.set_item("reason", reason)?;
+                let flipped: Vec<&str> =
+                    segment.flipped.iter().map(|&k| JOINT_COLUMNS[k]).collect();
+                row.set_item("flipped_joints", flipped)?;
+                row.set_item("CFX", cfx)?;
+                if include_quadrants {
+                    row.set_item("CF1", cf1)?;
+                    row.set_item("CF4", cf4)?;
+                    row.set_item("CF6", cf6)?;
+                }
+                let travel = self.joints_out(segment.travel, radians);
+                for (column, travel) in JOINT_COLUMNS.iter().zip(travel) {
+                    row.set_item(format!("travel_{}", column), travel)?;
+                }
+                row.set_item("max_step", self.joint_out(segment.max_step, radians))?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Resamples a timestamped joint trajectory at the fixed `period` (s) of a controller
+    /// interface, e.g. 0.004 or 0.012, interpolating "linear" or "cubic" (velocity
+    /// continuous through the points). Samples run from the first time until the first one
+    /// at or after the last, which holds the last point. Returns one row per sample with t,
+    /// j1..j6 and whether it is within the joint limits and moved from the previous sample
+    /// within `max_velocity` (joint units per second, per joint). With `strict` the first
+    /// violation raises instead.
+    #[pyo3(signature = (
+        joints,
+        times,
+        period=0.004,
+        interpolation=Interpolation::Linear,
+        max_velocity=None,
+        strict=false,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn resample_trajectory<'py>(
+        &self,
+        py: Python<'py>,
+        joints: JointTable,
+        times: Vec<f64>,
+        period: f64,
+        interpolation: Interpolation,
+        max_velocity: Option<[f64; 6]>,
+        strict: bool,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        validation::check_positive("period", period)?;
+        if let Some(max_velocity) = &max_velocity {
+            let valid = max_velocity.iter().all(|v| *v > 0.0);
+            validation::check_finite("max_velocity", max_velocity, valid)?;
+        }
+        let joints =
+            validation::screen(joints.0, validation::is_finite_joints, InvalidPolicy::Raise)?;
+        resampling::check_times(&times, joints.len())?;
+        let samples = resampling::resample(&times, &joints, period, interpolation);
+
+        let mut rows = Vec::with_capacity(samples.len());
+        for (k, (t, sample)) in samples.iter().enumerate() {
+            let within_limits = self.within_limits(&self.joints_in(*sample, radians));
+            let too_fast = k.checked_sub(1).and_then(|previous| {
+                let max_velocity = max_velocity?;
+                (0..6).find(|&j| {
+                    (sample[j] - samples[previous].1[j]).abs() / period > max_velocity[j]
+                })
+            });
+            if strict && !within_limits {
+                return Err(PyValueError::new_err(format!(
+                    "Sample at t={} is beyond the joint limits: {:?}",
+                    t, sample
+                )));
+            }
+            if let (true, Some(j)) = (strict, too_fast) {
+                return Err(PyValueError::new_err(format!(
+                    "J{} moves faster than {} per second at t={}",
+                    j + 1,
+                    max_velocity.map_or(0.0, |max_velocity| max_velocity[j]),
+                    t
+                )));
+            }
+            let row = PyDict::new(py);
+            row.set_item("t", t)?;
+            for (column, value) in JOINT_COLUMNS.iter().zip(sample) {
+                row.set_item(column, value)?;
+            }
+            row.set_item("within_limits", within_limits)?;
+            row.set_item("within_velocity", too_fast.is_none())?;
+            rows.push(row);
+        }
+        Ok(rows)
+    }
+
+    /// Smooths a solved joint trajectory to remove IK jitter while the TCP stays within
+    /// `tolerance` (m) and `angular_tolerance_deg` of its original poses, checked by forward
+    /// kinematics. "spline" (default) fits a discrete smoothing spline that halves waves of
+    /// `window` points, "moving_average" takes centered means of `window` points; the first
+    /// and last point stay. Where a smoothed point strays too far or leaves the joint limits
+    /// it is moved back toward the original until it complies. Returns one row per point
+    /// with j1..j6, the translation_error (m) and rotation_error (degrees) of its TCP pose
+    /// and the share of the smoothing kept, 0 where the original joints stay.
+    #[pyo3(signature = (
+        joints,
+        method=Smoothing::Spline,
+        window=5,
+        tolerance=0.0005,
+        angular_tolerance_deg=0.1,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn smooth_trajectory<'py>(
+        &self,
+        py: Python<'py>,
+        joints: JointTable,
+        method: Smoothing,
+        window: usize,
+        tolerance: f64,
+        angular_tolerance_deg: f64,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        smoothing::check_window(window)?;
+        validation::check_positive("tolerance", tolerance)?;
+        validation::check_positive("angular_tolerance_deg", angular_tolerance_deg)?;
+        let joints =
+            validation::screen(joints.0, validation::is_finite_joints, InvalidPolicy::Raise)?;
+        let joints: Vec<Joints> = joints
+            .iter()
+            .map(|joint_set| self.joints_in(*joint_set, radians))
+            .collect();
+        let smoothed = method.apply(&joints, window);
+        let errors = |original: &Joints, candidate: &Joints| {
+            pose::pose_errors(
+                &self.reported(&self.forward_radians(original)),
+                &self.reported(&self.forward_radians(candidate)),
+            )
+        };
+        let angular_tolerance = angular_tolerance_deg.to_radians();
+        let constrained = smoothing::constrain(&joints, &smoothed, |original, candidate| {
+            let (translation, rotation) = errors(original, candidate);
+            translation <= tolerance
+                && rotation <= angular_tolerance
+                && self.within_limits(candidate)
+        });
+        joints
+            .iter()
+            .zip(constrained)
+            .map(|(original, (point, share))| {
+                let (translation, rotation) = errors(original, &point);
+                let row = PyDict::new(py);
+                for (column, value) in JOINT_COLUMNS.iter().zip(self.joints_out(point, radians)) {
+                    row.set_item(column, value)?;
+                }
+                row.set_item("translation_error", translation)?;
+                row.set_item("rotation_error", rotation.to_degrees())?;
+                row.set_item("smoothing", share)?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Certifies post-processed joints (smoothed, resampled, edited) against the intended
+    /// path: runs forward kinematics on every joint set and measures the TCP against
+    /// `reference_poses`. With as many references as joint sets each row is compared with
+    /// its own reference, otherwise (or with `along_path`) with the closest point of the
+    /// polyline through the reference positions, the orientation interpolated between them.
+    /// Returns one row per joint set with the translation_error (m), rotation_error
+    /// (degrees) and reference, the index of the closest reference plus the share of the
+    /// way to the next one, and within_tolerance with `tolerance` (m) or
+    /// `angular_tolerance_deg`. Joint sets with NaN or infinite values give None.
+    #[pyo3(signature = (
+        joints,
+        reference_poses,
+        tolerance=None,
+        angular_tolerance_deg=None,
+        along_path=None,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn cartesian_deviation<'py>(
+        &self,
+        py: Python<'py>,
+        joints: JointTable,
+        reference_poses: PoseTable,
+        tolerance: Option<f64>,
+        angular_tolerance_deg: Option<f64>,
+        along_path: Option<bool>,
+        radians: Option<bool>,
+    ) -> PyResult<Vec<Option<Bound<'py, PyDict>>>> {
+        for (name, value) in [
+            ("tolerance", tolerance),
+            ("angular_tolerance_deg", angular_tolerance_deg),
+        ] {
+            if let Some(value) = value {
+                validation::check_positive(name, value)?;
+            }
+        }
+        let references = validation::screen(
+            reference_poses.0,
+            validation::is_finite_pose,
+            InvalidPolicy::Raise,
+        )?;
+        if references.is_empty() {
+            return Err(PyValueError::new_err(
+                "Expected at least one reference pose",
+            ));
+        }
+        let joints = joints.0;
+        let along_path = match along_path {
+            Some(false) if references.len() != joints.len() => {
+                return Err(PyValueError::new_err(format!(
+                    "Got {} reference poses for {} joint rows, compare along_path instead",
+                    references.len(),
+                    joints.len()
+                )));
+            }
+            Some(along_path) => along_path,
+            None => references.len() != joints.len(),
+        };
+        let references: Vec<Isometry3<f64>> = references
+            .iter()
+            .map(|pose| to_isometry(pose.0, pose.1))
+            .collect();
+        let indexed: Vec<(usize, [f64; 6])> = joints.into_iter().enumerate().collect();
+        let deviations = py.detach(|| {
+            ThreadPool::default().map(&indexed, |(i, joints)| {
+                if !validation::is_finite_joints(joints) {
+                    return None;
+                }
+                let pose = self.reported(&self.forward_radians(&self.joints_in(*joints, radians)));
+                Some(match along_path {
+                    true => deviation::from_path(&pose, &references),
+                    false => deviation::from_pose(&pose, &references[*i], *i),
+                })
+            })
+        });
+        deviations
+            .into_iter()
+            .map(|deviation| {
+                let Some(deviation) = deviation else {
+                    return Ok(None);
+                };
+                let row = PyDict::new(py);
+                row.set_item("translation_error", deviation.translation)?;
+                row.set_item("rotation_error", deviation.rotation.to_degrees())?;
+                row.set_item("reference", deviation.reference)?;
+                if tolerance.is_some() || angular_tolerance_deg.is_some() {
+                    let within = tolerance
+                        .is_none_or(|tolerance| deviation.translation <= tolerance)
+                        && angular_tolerance_deg
+                            .is_none_or(|tolerance| deviation.rotation.to_degrees() <= tolerance);
+                    row.set_item("within_tolerance", within)?;
+                }
+                Ok(Some(row))
+            })
+            .collect()
+    }
+
+    /// Travel and duty of every joint over a recorded trace, for maintenance: one row per
+    /// joint with its total travel, reversals (changes of direction after moving at least
+    /// `deadband`, so jitter does not count), the largest step between rows and the min and
+    /// max positions, in the joint units. With the times (s) of column `time_col` of the
+    /// table also max_speed per second. With joint limits, near_limit is the time (s, or
+    /// the number of rows without times) the joint spent within `near_limit_margin` of a
+    /// limit and near_limit_fraction its share of the trace, otherwise both are None.
+    /// `near_limit_margin` and `deadband` are in the joint units, 5 and 0.1 degrees by
+    /// default.
+    #[pyo3(signature = (joints, time_col=None, near_limit_margin=None, deadband=None, radians=None))]
+    fn axis_usage<'py>(
+        &self,
+        py: Python<'py>,
+        joints: &Bound<'py, PyAny>,
+        time_col: Option<&str>,
+        near_limit_margin: Option<f64>,
+        deadband: Option<f64>,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        let margin = match near_limit_margin {
+            Some(margin) => {
+                validation::check_finite(
+                    "near_limit_margin",
+                    &margin,
+                    margin >= 0.0 && margin.is_finite(),
+                )?;
+                self.joint_in(margin, radians)
+            }
+            None => 5f64.to_radians(),
+        };
+        let deadband = match deadband {
+            Some(deadband) => {
+                validation::check_positive("deadband", deadband)?;
+                self.joint_in(deadband, radians)
+            }
+            None => 0.1f64.to_radians(),
+        };
+        let JointTable(table) = joints.extract()?;
+        let table = validation::screen(table, validation::is_finite_joints, InvalidPolicy::Raise)?;
+        if table.is_empty() {
+            return Err(PyValueError::new_err("The trace has no rows"));
+        }
+        let times = match time_col {
+            Some(name) => {
+                let times = columns::optional_column::<f64>(
+                    joints,
+                    &[name.trim().to_lowercase().as_str()],
+                )?
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!("The table has no time column '{}'", name))
+                })?;
+                let times: Vec<f64> = times.into_iter().map(|t| t.unwrap_or(f64::NAN)).collect();
+                resampling::check_times(&times, table.len())?;
+                Some(times)
+            }
+            None => None,
+        };
+        let table: Vec<Joints> = table
+            .iter()
+            .map(|joint_set| self.joints_in(*joint_set, radians))
+            .collect();
+        (0..6)
+            .map(|k| {
+                let values: Vec<f64> = table.iter().map(|joint_set| joint_set[k]).collect();
+                let limits = self.joint_limits.map(|limits| limits[k]);
+                let usage = usage::axis_usage(&values, times.as_deref(), limits, margin, deadband);
+                let out = |value: f64| self.joint_out(value, radians);
+                let row = PyDict::new(py);
+                row.set_item("joint", JOINT_COLUMNS[k])?;
+                row.set_item("travel", out(usage.travel))?;
+                row.set_item("reversals", usage.reversals)?;
+                row.set_item("max_step", out(usage.max_step))?;
+                row.set_item("max_speed", usage.max_speed.map(out))?;
+                row.set_item("min", out(usage.min))?;
+                row.set_item("max", out(usage.max))?;
+                row.set_item("near_limit", usage.near_limit)?;
+                row.set_item("near_limit_fraction", usage.near_limit_fraction)?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Reads a joint_states log into rows of t and j1..j6 in the joint units, ready for
+    /// batch_forward and the trajectory tools. `source` is a CSV file or a table (dict of
+    /// columns or DataFrame) with a time column (t, time, timestamp, %time or header stamp
+    /// sec and nanosec; nanoseconds since the epoch are converted to seconds) and either a
+    /// column per joint (position0.. and name0.., as `rostopic echo -p` writes them), list
+    /// cells holding all joints of a message (position and name), or one row per joint and
+    /// message (name and position). Joints are looked up by name in the order of
+    /// `joint_names`, by default the six joints of the first message ordered by the number
+    /// their names end with. Positions are in `log_units`, radians as in ROS by default.
+    /// With `relative_time` times start at 0.
+    #[pyo3(signature = (source, joint_names=None, log_units="radians", relative_time=true, radians=None))]
+    fn read_joint_log<'py>(
+        &self,
+        py: Python<'py>,
+        source: &Bound<'py, PyAny>,
+        joint_names: Option<Vec<String>>,
+        log_units: &str,
+        relative_time: bool,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        let log_radians = match log_units {
+            "radians" => true,
+            "degrees" => false,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown log_units '{}', expected 'radians' or 'degrees'",
+                    log_units
+                )))
+            }
+        };
+        if let Some(names) = &joint_names {
+            if names.len() != 6 {
+                return Err(PyValueError::new_err(format!(
+                    "Expected 6 joint names, got {}",
+                    names.len()
+                )));
+            }
+        }
+        let (header, columns) = match source.extract::<String>() {
+            Ok(path) => {
+                let text = std::fs::read_to_string(&path)?;
+                cli::parse_csv(&text)
+                    .map_err(|e| PyValueError::new_err(format!("{}: {}", path, e)))?
+            }
+            Err(_) => cli::columns_of(source)?,
+        };
+        let samples = joint_log::samples(&header, &columns, relative_time)
+            .and_then(|samples| joint_log::joints(&samples, joint_names.as_deref()))
+            .map_err(PyValueError::new_err)?;
+        samples
+            .iter()
+            .map(|(t, joints)| {
+                let joints = joints.map(|joint| {
+                    if log_radians {
+                        joint
+                    } else {
+                        joint.to_radians()
+                    }
+                });
+                let row = PyDict::new(py);
+                row.set_item("t", t)?;
+                for (column, value) in JOINT_COLUMNS.iter().zip(self.joints_out(joints, radians)) {
+                    row.set_item(column, value)?;
+                }
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Chooses positioner joints along a path given in the part frame, carried by
+    /// `positioner`, that keep the robot comfortable: the tool axis (see set_tool_axis) near
+    /// `flat_direction` in the world frame (straight down by default, the flat welding
+    /// position) and the joints away from their limits, at the least joint travel of both
+    /// chains. Every positioner axis is sampled at `n_samples` values over its limits and
+    /// every robot branch within the joint limits is considered; the weights set how tilt
+    /// (radians), the limit margin (0 in the middle of the range, 1 at a limit) and the
+    /// travel (radians, summed over both chains, also from `current_joints` if given)
+    /// trade off. Returns one row per pose with the positioner joints p1.., the robot
+    /// joints j1..j6, the tilt and the smallest joint limit margin (1 in the middle of the
+    /// range, 0 at a limit, None without joint limits). Raises if a pose is out of reach
+    /// at every sampled positioner state.
+    #[pyo3(signature = (
+        positioner,
+        path,
+        flat_weight=1.0,
+        limit_weight=1.0,
+        smooth_weight=0.1,
+        n_samples=12,
+        flat_direction=[0.0, 0.0, -1.0],
+        current_joints=None,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn optimize_positioner<'py>(
+        &self,
+        py: Python<'py>,
+        positioner: PyRef<'_, Positioner>,
+        path: PoseTable,
+        flat_weight: f64,
+        limit_weight: f64,
+        smooth_weight: f64,
+        n_samples: usize,
+        flat_direction: [f64; 3],
+        current_joints: Option<[f64; 6]>,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        for (name, weight) in [
+            ("flat_weight", flat_weight),
+            ("limit_weight", limit_weight),
+            ("smooth_weight", smooth_weight),
+        ] {
+            validation::check_finite(name, &weight, weight.is_finite() && weight >= 0.0)?;
+        }
+        if n_samples == 0 {
+            return Err(PyValueError::new_err("n_samples must be at least 1"));
+        }
+        let flat_direction = Vector3::from(flat_direction);
+        let valid = flat_direction.iter().all(|x| x.is_finite()) && flat_direction.norm() > 0.0;
+        validation::check_finite("flat_direction", &flat_direction, valid)?;
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let path = validation::screen(path.0, validation::is_finite_pose, InvalidPolicy::Raise)?;
+        let current = current_joints.map(|joints| self.joints_in(joints, radians));
+        let positioner: &Positioner = &positioner;
+        let grid = positioner::grid(&positioner.limits, n_samples);
+        let tool_axis = Axis::tool(None).unit();
+        let travel =
+            |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| (a - b).abs()).sum::<f64>();
+
+        // Every state per pose: (positioner joints, robot joints, tilt, limit margin)
+        type State = (usize, Joints, f64, Option<f64>);
+        let states: Vec<Vec<State>> = py.detach(|| {
+            path.iter()
+                .map(|pose| {
+                    let part = to_isometry(pose.0, pose.1);
+                    grid.iter()
+                        .enumerate()
+                        .flat_map(|(g, positioner_joints)| {
+                            let world = positioner.transform(positioner_joints) * part;
+                            let tilt = (world.rotation * tool_axis).angle(&flat_direction);
+                            self.branches(from_isometry(&world))
+                                .into_iter()
+                                .flatten()
+                                .filter(|joints| self.within_limits(joints))
+                                .map(move |joints| (g, joints, tilt, self.limit_margin(&joints)))
+                                .collect::<Vec<_>>()
+                        })
+                        .collect()
+                })
+                .collect()
+        });
+        let nodes: Vec<Vec<Option<f64>>> = states
+            .iter()
+            .enumerate()
+            .map(|(i, states)| {
+                states
+                    .iter()
+                    .map(|(_, joints, tilt, margin)| {
+                        let start = match (i, current) {
+                            (0, Some(current)) => smooth_weight * travel(joints, &current),
+                            _ => 0.0,
+                        };
+                        let limits = margin.map_or(0.0, |margin| limit_weight * (1.0 - margin));
+                        Some(flat_weight * tilt + limits + start)
+                    })
+                    .collect()
+            })
+            .collect();
+        let chosen = py
+            .detach(|| {
+                positioner::optimal(&nodes, |i, from, to| {
+                    let ((g_from, joints_from, ..), (g_to, joints_to, ..)) =
+                        (&states[i - 1][from], &states[i][to]);
+                    smooth_weight
+                        * (travel(&grid[*g_from], &grid[*g_to]) + travel(joints_from, joints_to))
+                })
+            })
+            .map_err(|i| {
+                PyValueError::new_err(format!(
+                    "Pose {} of the path is out of reach at every sampled positioner state",
+                    i
+                ))
+            })?;
+        chosen
+            .iter()
+            .enumerate()
+            .map(|(i, &s)| {
+                let (g, joints, tilt, margin) = &states[i][s];
+                let row = PyDict::new(py);
+                for (k, value) in positioner.joints_out(&grid[*g], radians).iter().enumerate() {
+                    row.set_item(format!("p{}", k + 1), value)?;
+                }
+                for (column, value) in JOINT_COLUMNS.iter().zip(self.joints_out(*joints, radians)) {
+                    row.set_item(column, value)?;
+                }
+                row.set_item("tilt", self.joint_out(*tilt, radians))?;
+                row.set_item("limit_margin", margin)?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Converts a timed joint trajectory to a trajectory_msgs/JointTrajectory message, to
+    /// replay it through ROS or MoveIt. Returns the message as a dict with header,
+    /// joint_names (joint_1..joint_6 by default), and points with positions in radians,
+    /// velocities from the neighbouring points (at rest at both ends) unless `velocities`
+    /// is false, and time_from_start as sec and nanosec, or secs and nsecs with
+    /// `ros_version` 1. With `path` the message is also written there as YAML, as
+    /// `ros2 topic pub` or `rostopic pub` read it.
+    #[pyo3(signature = (
+        joints,
+        times,
+        path=None,
+        joint_names=None,
+        frame_id="",
+        velocities=true,
+        ros_version=2,
+        radians=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn export_joint_trajectory<'py>(
+        &self,
+        py: Python<'py>,
+        joints: JointTable,
+        times: Vec<f64>,
+        path: Option<&str>,
+        joint_names: Option<Vec<String>>,
+        frame_id: &str,
+        velocities: bool,
+        ros_version: u8,
+        radians: Option<bool>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let version = ros::RosVersion::parse(ros_version)?;
+        let joint_names =
+            joint_names.unwrap_or_else(|| ros::DEFAULT_JOINT_NAMES.map(String::from).to_vec());
+        if joint_names.len() != 6 {
+            return Err(PyValueError::new_err(format!(
+                "Expected 6 joint names, got {}",
+                joint_names.len()
+            )));
+        }
+        let joints =
+            validation::screen(joints.0, validation::is_finite_joints, InvalidPolicy::Raise)?;
+        resampling::check_times(&times, joints.len())?;
+        let positions: Vec<Joints> = joints
+            .iter()
+            .map(|joint_set| self.joints_in(*joint_set, radians))
+            .collect();
+        let trajectory = ros::JointTrajectory {
+            joint_names,
+            frame_id: frame_id.to_string(),
+            velocities: velocities.then(|| ros::velocities(&times, &positions)),
+            times,
+            positions,
+            version,
+        };
+        if let Some(path) = path {
+            std::fs::write(path, trajectory.to_yaml())?;
+        }
+        trajectory.to_dict(py)
+    }
+
+    /// Forward kinematics for many joint sets, with the same progress, cancellation,
+    /// threading and invalid input handling as batch_inverse. With `configuration` or
+    /// `singularity` every joint set gives a row with the pose columns (x, y, z, qw, qx, qy,
+    /// qz), plus the axis configuration (CF1, CF4, CF6, CFX) with `configuration`, and
+    /// whether the joints are at a kinematic singularity (near_singularity, J5 within
+    /// `singularity_tolerance_deg` of a multiple of 180 degrees, the robot's tolerance by
+    /// default) and the manipulability |det J| of the TCP Jacobian with `singularity`, and
+    /// the model_hash, tool_hash and config_policy columns of batch_inverse with
+    /// `provenance`. With `summary` the result is (rows, summary), the summary dict
+    /// described on `Summary`.
+    #[pyo3(signature = (
+        joints,
+        progress=None,
+        cancel=None,
+        radians=None,
+        on_invalid=InvalidPolicy::Null,
+        n_threads=None,
+        chunk_size=None,
+        pool=None,
+        configuration=false,
+        singularity=false,
+        singularity_tolerance_deg=None,
+        summary=false,
+        provenance=false,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn batch_forward<'py>(
+        &self,
+        py: Python<'py>,
+        joints: JointTable,
+        progress: Option<&Bound<'_, PyAny>>,
+        cancel: Option<PyRef<'_, CancelToken>>,
+        radians: Option<bool>,
+        on_invalid: InvalidPolicy,
+        n_threads: Option<usize>,
+        chunk_size: Option<usize>,
+        pool: Option<ThreadPool>,
+        configuration: bool,
+        singularity: bool,
+        singularity_tolerance_deg: Option<f64>,
+        summary: bool,
+        provenance: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let start = Instant::now();
+        let provenance = provenance.then(|| self.provenance());
+        let tolerance = self.singularity_tolerance(singularity_tolerance_deg)?;
+        let pool = ThreadPool::resolve(pool.as_ref(), n_threads, chunk_size)?;
+        let total = joints.0.len();
+        let joints = validation::screen(joints.0, validation::is_finite_joints, on_invalid)?;
+        let forward = |joints: &Joints| self.forward_radians(joints);
+        let results = batch::run(
+            py,
+            &joints,
+            progress,
+            cancel.as_deref(),
+            &pool,
+            |&joint_set| {
+                let pose = self.forward(joint_set, radians).ok()?;
+                let joints = self.joints_in(joint_set, radians);
+                let manipulability =
+                    singularity.then(|| reachability::manipulability(forward, &joints));
+                Some((pose, joints, manipulability))
+            },
+        )?;
+        let mut totals = Summary::new(start, total - joints.len());
+        let rows = results
+            .into_iter()
+            .map(|result| {
+                let Some((pose, joints, manipulability)) = result else {
+                    totals.record(summary::Row::Invalid);
+                    return Ok(py.None().into_bound(py));
+                };
+                totals.record(summary::Row::Solved {
+                    joints,
+                    singular: !self.is_regular(&joints, tolerance),
+                    within_limits: self.within_limits(&joints),
+                });
+                if !(configuration || singularity || provenance.is_some()) {
+                    return Ok(pose.into_pyobject(py)?.into_any());
+                }
+                let row = PyDict::new(py);
+                for (column, value) in POSE_COLUMNS.iter().zip(pose.0.iter().chain(&pose.1)) {
+                    row.set_item(column, value)?;
+                }
+                if configuration {
+                    let (cf1, cf4, cf6, cfx) = self.axis_configuration_radians(&joints);
+                    row.set_item("CF1", cf1)?;
+                    row.set_item("CF4", cf4)?;
+                    row.set_item("CF6", cf6)?;
+                    row.set_item("CFX", cfx)?;
+                }
+                if let Some(manipulability) = manipulability {
+                    row.set_item("near_singularity", !self.is_regular(&joints, tolerance))?;
+                    row.set_item("manipulability", manipulability)?;
+                }
+                if let Some(provenance) = &provenance {
+                    provenance.stamp(&row)?;
+                }
+                Ok(row.into_any())
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let totals = summary
+            .then(|| totals.to_dict(py, |joint| self.joint_out(joint, radians)))
+            .transpose()?;
+        summary::with_summary(py, rows, totals)
+    }
+
+    /// Forward kinematics returning the pose as a twist [vx, vy, vz, wx, wy, wz]
+    #[pyo3(signature = (joints, radians=None))]
+    fn forward_twist(&self, joints: [f64; 6], radians: Option<bool>) -> PyResult<Twist> {
+        validation::check_joints("joints", &joints)?;
+        let start = self.stats.start();
+        let joints = self.joints_in(joints, radians);
+        let pose = self.reported(&self.forward_radians(&joints));
+        self.stats.record_forward(start);
+        Ok(twist::log(&pose))
+    }
+
+    /// Points of the posture from the base over shoulder, elbow, forearm, wrist center and
+    /// flange to the TCP in world coordinates, with the line segments between them, for
+    /// plotting. A `link_radius` (m) adds a cylinder per segment.
+    #[pyo3(signature = (joints, link_radius=None, radians=None))]
+    fn skeleton<'py>(
+        &self,
+        py: Python<'py>,
+        joints: [f64; 6],
+        link_radius: Option<f64>,
+        radians: Option<bool>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        validation::check_joints("joints", &joints)?;
+        if let Some(radius) = link_radius {
+            validation::check_positive("link_radius", radius)?;
+        }
+        let joints = self.joints_in(joints, radians);
+        let skeleton = Skeleton::new(
+            &self._kinematic_model,
+            &self._kinematic_model.decouple(&self.unmastered(&joints)),
+            &self.base(),
+            &self.forward_radians(&joints),
+            &self.tool(),
+        );
+        skeleton.to_dict(py, link_radius)
+    }
+
+    /// Writes the posture as cylinders of `link_radius` (m) along the skeleton to an STL,
+    /// PLY, glTF or GLB file, in world coordinates times `scale` (e.g. 1000 for mm).
+    /// Returns the number of triangles.
+    #[pyo3(signature = (joints, path, link_radius=0.05, scale=1.0, radians=None))]
+    fn export_posture(
+        &self,
+        joints: [f64; 6],
+        path: &str,
+        link_radius: f64,
+        scale: f64,
+        radians: Option<bool>,
+    ) -> PyResult<usize> {
+        validation::check_joints("joints", &joints)?;
+        validation::check_positive("link_radius", link_radius)?;
+        validation::check_positive("scale", scale)?;
+        let joints = self.joints_in(joints, radians);
+        let skeleton = Skeleton::new(
+            &self._kinematic_model,
+            &self._kinematic_model.decouple(&self.unmastered(&joints)),
+            &self.base(),
+            &self.forward_radians(&joints),
+            &self.tool(),
+        );
+        let mut mesh = Mesh::default();
+        for segment in skeleton.points.windows(2) {
+            mesh.append(mesh::cylinder(segment[0], segment[1], link_radius));
+        }
+        let mesh = mesh.transformed(&Isometry3::identity(), scale);
+        mesh.write(path)?;
+        Ok(mesh.triangles.len())
+    }
+
+    /// Writes the envelope of wrist center positions within the joint limits to an STL,
+    /// PLY, glTF or GLB file, in world coordinates times `scale` (e.g. 1000 for mm). Joints
+    /// 1 to 3 are sampled `samples` times each, the envelope has `resolution` rows of
+    /// 2 * `resolution` directions around the shoulder height on the J1 axis. Returns the
+    /// number of triangles.
+    #[pyo3(signature = (path, samples=64, resolution=32, scale=1.0))]
+    fn export_workspace(
+        &self,
+        py: Python<'_>,
+        path: &str,
+        samples: usize,
+        resolution: usize,
+        scale: f64,
+    ) -> PyResult<usize> {
+        if samples < 2 || resolution < 2 {
+            return Err(PyValueError::new_err(
+                "samples and resolution must be at least 2",
+            ));
+        }
+        validation::check_positive("scale", scale)?;
+        let limits = self.joint_limits.unwrap_or([(-PI, PI); 6]);
+        // The envelope samples the true joint angles
+        let limits: [(f64, f64); 3] = std::array::from_fn(|k| {
+            (
+                limits[k].0 - self.mastering[k],
+                limits[k].1 - self.mastering[k],
+            )
+        });
+        let mesh = py.detach(|| {
+            mesh::workspace_envelope(&self._kinematic_model, &limits, samples, resolution)
+                .transformed(&self.base(), scale)
+        });
+        mesh.write(path)?;
+        Ok(mesh.triangles.len())
+    }
+
+    /// Inverse kinematics for a pose given as a twist [vx, vy, vz, wx, wy, wz]
+    #[pyo3(signature = (twist, current_joints=None, radians=None))]
+    fn inverse_twist(
+        &self,
+        twist: Twist,
+        current_joints: Option<[f64; 6]>,
+        radians: Option<bool>,
+    ) -> PyResult<Vec<[f64; 6]>> {
+        validation::check_finite("twist", &twist, twist.iter().all(|x| x.is_finite()))?;
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let options = InverseOptions {
+            radians,
+            ..InverseOptions::default()
+        };
+        Ok(self.solve(from_isometry(&twist::exp(&twist)), current_joints, &options))
+    }
+
+    /// Joints after a small increment from `current_joints`, as a teach pendant jogs. With
+    /// `frame` "joint", `delta` adds to every joint; with "tool" or "world" it is a Cartesian
+    /// step [dx, dy, dz, rx, ry, rz] in meters and a rotation vector in the joint units,
+    /// along and about the TCP axes or the world axes (rotating about the TCP). Cartesian
+    /// steps keep the configuration of the current joints and take its solution closest to
+    /// them. `max_step` bounds the change of every joint, in the joint units, so a step
+    /// through a wrist singularity stops instead of flipping the wrist. With a stationary
+    /// tool the Cartesian steps move the pose `forward` reports, the stationary tool in the
+    /// part frame.
+    ///
+    /// Raises UnreachablePoseError if the target has no solution, and ValueError if the
+    /// step leaves the joint limits, changes the configuration or exceeds `max_step`.
+    #[pyo3(signature = (current_joints, delta, frame=JogFrame::Tool, max_step=None, radians=None))]
+    fn jog(
+        &self,
+        py: Python<'_>,
+        current_joints: [f64; 6],
+        delta: [f64; 6],
+        frame: JogFrame,
+        max_step: Option<f64>,
+        radians: Option<bool>,
+    ) -> PyResult<[f64; 6]> {
+        validation::check_joints("current_joints", &current_joints)?;
+        validation::check_finite("delta", &delta, delta.iter().all(|x| x.is_finite()))?;
+        let current = self.joints_in(current_joints, radians);
+        let joints = match frame {
+            JogFrame::Joint => {
+                let jogged: [f64; 6] = std::array::from_fn(|i| current_joints[i] + delta[i]);
+                let joints = self.joints_in(jogged, radians);
+                if !self.within_limits(&joints) {
+                    return Err(PyValueError::new_err(format!(
+                        "Jogging to {:?} leaves the joint limits",
+                        jogged
+                    )));
+                }
+                joints
+            }
+            JogFrame::Tool | JogFrame::World => {
+                let [dx, dy, dz, rx, ry, rz] = delta;
+                let rotation = [rx, ry, rz].map(|angle| self.joint_in(angle, radians));
+                let step = [dx, dy, dz, rotation[0], rotation[1], rotation[2]];
+                let tcp = self.reported(&self.forward_radians(&current));
+                let pose = from_isometry(&jog::target(&tcp, &step, frame));
+                let options = self.inverse_options(SolutionOrder::Distance, None, true, radians);
+                let solutions = self.candidates(pose, Some(current), &options);
+                if solutions.is_empty() {
+                    return Err(self.unreachable_error(py, pose));
+                }
+                let configuration = self.configuration(&current);
+                *solutions
+                    .iter()
+                    .find(|joints| self.configuration(joints) == configuration)
+                    .ok_or_else(|| {
+                        PyValueError::new_err(
+                            "The step cannot be made without changing the configuration",
+                        )
+                    })?
+            }
+        };
+        if let Some(max_step) = max_step {
+            let max_step = self.joint_in(max_step, radians);
+            if let Some(i) = (0..6).find(|&i| (joints[i] - current[i]).abs() > max_step) {
+                return Err(PyValueError::new_err(format!(
+                    "The step moves J{} by {}, more than max_step {}",
+                    i + 1,
+                    self.joint_out(joints[i] - current[i], radians),
+                    self.joint_out(max_step, radians)
+                )));
+            }
+        }
+        match frame {
+            // Joint steps add in the joint units, without a round trip through radians
+            JogFrame::Joint => Ok(std::array::from_fn(|i| current_joints[i] + delta[i])),
+            JogFrame::Tool | JogFrame::World => Ok(self.joints_out(joints, radians)),
+        }
+    }
+
+    /// Pose of the robot base in the world frame, as (translation, quaternion) or as a 4x4
+    /// matrix with `as_matrix`
+    #[pyo3(signature = (as_matrix=false))]
+    fn base_transform<'py>(&self, py: Python<'py>, as_matrix: bool) -> PyResult<Bound<'py, PyAny>> {
+        transform_out(py, &self.base(), as_matrix)
+    }
+
+    /// Pose of the TCP in the flange frame, as (translation, quaternion) or as a 4x4 matrix
+    /// with `as_matrix`
+    #[pyo3(signature = (as_matrix=false))]
+    fn tool_transform<'py>(&self, py: Python<'py>, as_matrix: bool) -> PyResult<Bound<'py, PyAny>> {
+        transform_out(py, &self.tool(), as_matrix)
+    }
+
+    /// Frames registry of the cell with the world and the robot base ("base"), plus the
+    /// flange ("flange") and the TCP ("tool") at `joints` if given. With a stationary tool
+    /// "tool" is the stationary tool and the part frame on the flange at `joints` is "part",
+    /// so poses `forward` reports are in "part". Workobjects are added to it with
+    /// Frames.add.
+    #[pyo3(signature = (joints=None, radians=None))]
+    fn frames(&self, joints: Option<[f64; 6]>, radians: Option<bool>) -> PyResult<Frames> {
+        let mut frames = vec![("base".to_string(), self.base())];
+        if let Some(tool) = &self.stationary_tool {
+            frames.push((
+                "tool".to_string(),
+                to_isometry(tool.translation, tool.rotation),
+            ));
+        }
+        if let Some(joints) = joints {
+            validation::check_joints("joints", &joints)?;
+            let tcp = self.forward_radians(&self.joints_in(joints, radians));
+            frames.push(("flange".to_string(), tcp * self.tool().inverse()));
+            let name = match self.stationary_tool {
+                Some(_) => "part",
+                None => "tool",
+            };
+            frames.push((name.to_string(), tcp));
+        }
+        Ok(Frames::with(frames))
+    }
+
+    /// Expresses a pose given in the world frame in the robot base frame
+    #[pyo3(signature = (pose, as_matrix=false))]
+    fn world_to_base<'py>(
+        &self,
+        py: Python<'py>,
+        pose: PyPose,
+        as_matrix: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        validation::check_pose(&pose)?;
+        transform_out(
+            py,
+            &(self.base().inverse() * to_isometry(pose.0, pose.1)),
+            as_matrix,
+        )
+    }
+
+    /// Expresses a pose given in the robot base frame in the world frame
+    #[pyo3(signature = (pose, as_matrix=false))]
+    fn base_to_world<'py>(
+        &self,
+        py: Python<'py>,
+        pose: PyPose,
+        as_matrix: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        validation::check_pose(&pose)?;
+        transform_out(py, &(self.base() * to_isometry(pose.0, pose.1)), as_matrix)
+    }
+
+    /// Flange pose that puts the TCP at the given pose
+    #[pyo3(signature = (pose, as_matrix=false))]
+    fn tcp_to_flange<'py>(
+        &self,
+        py: Python<'py>,
+        pose: PyPose,
+        as_matrix: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        validation::check_pose(&pose)?;
+        transform_out(
+            py,
+            &(to_isometry(pose.0, pose.1) * self.tool().inverse()),
+            as_matrix,
+        )
+    }
+
+    /// TCP pose for the given flange pose
+    #[pyo3(signature = (pose, as_matrix=false))]
+    fn flange_to_tcp<'py>(
+        &self,
+        py: Python<'py>,
+        pose: PyPose,
+        as_matrix: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        validation::check_pose(&pose)?;
+        transform_out(py, &(to_isometry(pose.0, pose.1) * self.tool()), as_matrix)
+    }
+
+    /// Derives the tool rotation with the "TCP&Z,X" method, keeping the TCP position of the
+    /// current tool. At `reference_joints` the TCP touches a fixed point; at each of
+    /// `z_joints` the point lies on the desired positive z axis of the tool, and at each of
+    /// `x_joints` on the desired positive x axis. Without `x_joints` the x axis stays as
+    /// close to the current one as the new z axis allows. Returns the new tool_config with
+    /// the largest angle of a single posture from the fitted z and x directions (z_spread,
+    /// x_spread) and the angle between the measured directions (axis_angle), all in degrees.
+    #[pyo3(signature = (reference_joints, z_joints, x_joints=None, radians=None))]
+    fn calibrate_tool_orientation<'py>(
+        &self,
+        py: Python<'py>,
+        reference_joints: [f64; 6],
+        z_joints: Vec<[f64; 6]>,
+        x_joints: Option<Vec<[f64; 6]>>,
+        radians: Option<bool>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        validation::check_joints("reference_joints", &reference_joints)?;
+        for joints in z_joints.iter().chain(x_joints.iter().flatten()) {
+            validation::check_joints("joints", joints)?;
+        }
+        let tool = self.tool();
+        let point = self
+            .forward_radians(&self.joints_in(reference_joints, radians))
+            .translation
+            .vector;
+        let direction = |joints: &[[f64; 6]], axis: &str| {
+            let flanges: Vec<Isometry3<f64>> = joints
+                .iter()
+                .map(|joints| {
+                    self.forward_radians(&self.joints_in(*joints, radians)) * tool.inverse()
+                })
+                .collect();
+            tool_calibration::axis_direction(&flanges, &point.into(), &tool.translation.vector)
+                .ok_or_else(|| {
+                    PyValueError::new_err(format!(
+                        "Cannot find the {} axis, the postures must move the reference point \
+                         away from the TCP along it",
+                        axis
+                    ))
+                })
+        };
+        let (z, z_spread) = direction(&z_joints, "z")?;
+        let (x, x_spread) = match &x_joints {
+            Some(x_joints) => {
+                let (x, spread) = direction(x_joints, "x")?;
+                (x, Some(spread))
+            }
+            None => (tool.rotation * nalgebra::Vector3::x(), None),
+        };
+        let rotation = pose::rotation_from_axes(z.into(), x.into())
+            .ok_or_else(|| PyValueError::new_err("The measured z and x directions are parallel"))?;
+        let dict = PyDict::new(py);
+        dict.set_item(
+            "tool_config",
+            ToolConfig::new(self.tool_config.translation, rotation),
+        )?;
+        dict.set_item("z_spread", z_spread.to_degrees())?;
+        dict.set_item("x_spread", x_spread.map(f64::to_degrees))?;
+        dict.set_item(
+            "axis_angle",
+            x_joints.is_some().then(|| z.angle(&x).to_degrees()),
+        )?;
+        Ok(dict)
+    }
+
+    /// The joints of the posture `name` stored with RobotBuilder.posture(), e.g. "home", as
+    /// a seed, program start or reference
+    #[pyo3(signature = (name, radians=None))]
+    fn posture(&self, name: &str, radians: Option<bool>) -> PyResult<[f64; 6]> {
+        Ok(self.joints_out(postures::get(&self.postures, name)?, radians))
+    }
+
+    /// Every stored posture, by name
+    #[pyo3(signature = (radians=None))]
+    fn postures(&self, radians: Option<bool>) -> BTreeMap<String, [f64; 6]> {
+        self.postures
+            .iter()
+            .map(|(name, joints)| (name.clone(), self.joints_out(*joints, radians)))
+            .collect()
+    }
+
+    /// Whether every joint lies within `tolerance` (joint units, 0.1 degrees by default) of
+    /// the posture `name`, e.g. to check that a cell starts at home or a robot stands in its
+    /// mastering position
+    #[pyo3(signature = (joints, name, tolerance=None, radians=None))]
+    fn at_posture(
+        &self,
+        joints: [f64; 6],
+        name: &str,
+        tolerance: Option<f64>,
+        radians: Option<bool>,
+    ) -> PyResult<bool> {
+        validation::check_joints("joints", &joints)?;
+        let posture = postures::get(&self.postures, name)?;
+        let tolerance = match tolerance {
+            Some(tolerance) => {
+                validation::check_positive("tolerance", tolerance)?;
+                self.joint_in(tolerance, radians)
+            }
+            None => 0.1f64.to_radians(),
+        };
+        let joints = self.joints_in(joints, radians);
+        Ok(joints
+            .iter()
+            .zip(posture)
+            .all(|(joint, target)| (joint - target).abs() <= tolerance))
+    }
+
+    /// Turns collection of call statistics on or off, it is off by default
+    #[pyo3(signature = (enabled=true))]
+    fn enable_stats(&self, enabled: bool) {
+        self.stats.set_enabled(enabled);
+    }
+
+    /// Returns the FK/IK call counts, solution counts, failures and cumulative solver time
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.stats.to_dict(py)
+    }
+
+    /// Clears all collected statistics
+    fn reset_stats(&self) {
+        self.stats.reset();
+    }
+
+    /// Turns on memoization of inverse kinematics for up to `capacity` poses, evicting the
+    /// least recently used. Poses are quantized to `position_resolution` (m) and
+    /// `rotation_resolution` (quaternion components), so a pose within the resolution of a
+    /// cached one gets its solutions; solutions also depend on the exact seed and options.
+    /// Enabling again starts an empty cache.
+    #[pyo3(signature = (capacity=4096, position_resolution=1e-6, rotation_resolution=1e-6))]
+    fn enable_cache(
+        &self,
+        capacity: usize,
+        position_resolution: f64,
+        rotation_resolution: f64,
+    ) -> PyResult<()> {
+        self.cache
+            .enable(capacity, position_resolution, rotation_resolution)
+    }
+
+    /// Turns memoization of inverse kinematics off and drops the cached solutions
+    fn disable_cache(&self) {
+        self.cache.disable();
+    }
+
+    /// Returns whether the cache is enabled, its capacity, size, hits, misses and evictions
+    fn cache_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.cache.to_dict(py)
+    }
+
+    /// Drops the cached solutions and resets the cache counters
+    fn clear_cache(&self) {
+        self.cache.clear();
+    }
+
+    /// Transforms a wrench [fx, fy, fz, tx, ty, tz] measured at the flange into the TCP frame
+    fn wrench_flange_to_tcp(&self, wrench: Wrench) -> Wrench {
+        wrench::flange_to_tcp(&self._tool.tool, &wrench)
+    }
+
+    /// Transforms a wrench [fx, fy, fz, tx, ty, tz] given in the TCP frame into the flange frame
+    fn wrench_tcp_to_flange(&self, wrench: Wrench) -> Wrench {
+        wrench::tcp_to_flange(&self._tool.tool, &wrench)
+    }
+
+    #[pyo3(signature = (wrenches))]
+    fn batch_wrench_flange_to_tcp(&self, wrenches: Vec<Wrench>) -> Vec<Wrench> {
+        wrenches
+            .iter()
+            .map(|&wrench| self.wrench_flange_to_tcp(wrench))
+            .collect()
+    }
+
+    #[pyo3(signature = (wrenches))]
+    fn batch_wrench_tcp_to_flange(&self, wrenches: Vec<Wrench>) -> Vec<Wrench> {
+        wrenches
+            .iter()
+            .map(|&wrench| self.wrench_tcp_to_flange(wrench))
+            .collect()
+    }
+}
+
+impl Robot {
+    /// A builder holding every setting of the robot, in the units of the robot, to build
+    /// variants of it
+    pub(crate) fn to_builder(&self) -> RobotBuilder {
+        let joints_out = |joints: Joints| self.joints_out(joints, None);
+        RobotBuilder {
+            kinematic_model: Some(self._kinematic_model.clone()),
+            base_config: Some(self.base_config.clone()),
+            tool_config: Some(self.tool_config.clone()),
+            joint_limits: self.joint_limits.map(|limits| {
+                limits.map(|(min, max)| (self.joint_out(min, None), self.joint_out(max, None)))
+            }),
+            units: Some(if self.radians { "radians" } else { "degrees" }.to_string()),
+            solution_policy: Some(self.solution_policy.name().to_string()),
+            corrections: self.calibration.as_ref().map(|chain| *chain.corrections()),
+            default_seed: self.default_seed.map(joints_out),
+            singularity_tolerance_deg: Some(self.singularity_tolerance.to_degrees()),
+            comfort: Some(Comfort {
+                preferred: self.comfort.preferred.map(joints_out),
+                ..self.comfort
+            }),
+            ranking: Some(self.ranking),
+            postures: self
+                .postures
+                .iter()
+                .map(|(name, joints)| (name.clone(), joints_out(*joints)))
+                .collect(),
+            mastering_offsets: Some(joints_out(self.mastering)),
+            stationary_tool: self.stationary_tool.clone(),
+        }
+    }
+
+    /// Forward kinematics for controller joints in radians
+    fn forward_radians(&self, joints: &Joints) -> Pose {
+        let joints = &self.unmastered(joints);
+        match &self.calibration {
+            Some(chain) => chain.forward(joints),
+            None => self._tool.forward(&self._kinematic_model.decouple(joints)),
+        }
+    }
+
+    /// Pose of the TCP (world frame) that reaches a target. With a stationary tool the target
+    /// is where the tool meets the part in the part frame, and the TCP is the part frame.
+    fn target(&self, pose: &PyPose) -> Isometry3<f64> {
+        let target = to_isometry(pose.0, pose.1);
+        match &self.stationary_tool {
+            Some(tool) => to_isometry(tool.translation, tool.rotation) * target.inverse(),
+            None => target,
+        }
+    }
+
+    /// The pose a TCP pose (world frame) is reported as, the inverse of `target`
+    fn reported(&self, tcp: &Pose) -> Pose {
+        match &self.stationary_tool {
+            Some(tool) => tcp.inverse() * to_isometry(tool.translation, tool.rotation),
+            None => *tcp,
+        }
+    }
+
+    /// The true joint angles of reported joints (radians), without the mastering offsets
+    fn unmastered(&self, joints: &Joints) -> Joints {
+        std::array::from_fn(|k| joints[k] - self.mastering[k])
+    }
+
+    /// Reported joints (radians) of true joint angles such as the solutions of the model
+    fn mastered(&self, joints: &Joints) -> Joints {
+        std::array::from_fn(|k| joints[k] + self.mastering[k])
+    }
+
+    /// Configuration index cfx of reported joints (radians), from their true joint angles
+    fn configuration(&self, joints: &Joints) -> u8 {
+        configuration::configuration(&self._kinematic_model, &self.unmastered(joints))
+    }
+
+    /// Start joints (radians) given as joints in the units of the robot (or the call) or as
+    /// the name of a stored posture
+    fn start_joints(
+        &self,
+        start: Option<&Start>,
+        radians: Option<bool>,
+    ) -> PyResult<Option<Joints>> {
+        match start {
+            Some(Start::Joints(joints)) => {
+                validation::check_joints("current_joints", joints)?;
+                Ok(Some(self.joints_in(*joints, radians)))
+            }
+            Some(Start::Posture(name)) => postures::get(&self.postures, name).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Whether joints are exchanged in radians, `radians` overrides the robot's units per call
+    fn uses_radians(&self, radians: Option<bool>) -> bool {
+        radians.unwrap_or(self.radians)
+    }
+
+    /// Converts joints from the units used by this robot (or the call) to radians
+    fn joints_in(&self, joints: [f64; 6], radians: Option<bool>) -> [f64; 6] {
+        joints.map(|x| self.joint_in(x, radians))
+    }
+
+    fn joint_in(&self, joint: f64, radians: Option<bool>) -> f64 {
+        if self.uses_radians(radians) {
+            joint
+        } else {
+            joint.to_radians()
+        }
+    }
+
+    /// Inverse kinematics behind `inverse`, with joints in the units of this robot or those
+    /// given in the options
+    fn solve(
+        &self,
+        pose: PyPose,
+        current_joints: Option<[f64; 6]>,
+        options: &InverseOptions,
+    ) -> Vec<[f64; 6]> {
+        let seed = current_joints.map(|joints| self.joints_in(joints, options.radians));
+        self.solve_radians(pose, seed, options)
+            .iter()
+            .map(|x| self.joints_out(*x, options.radians))
+            .collect::<Vec<_>>()
+    }
+
+    /// Inverse kinematics with the seed and solutions as controller joints in radians
+    fn solve_radians(
+        &self,
+        pose: PyPose,
+        seed: Option<Joints>,
+        options: &InverseOptions,
+    ) -> Vec<Joints> {
+        self.solve_counted(pose, seed, options).0
+    }
+
+    /// Like `solve_radians`, also returning the number of valid solutions and the index of
+    /// the chosen one among them, as returned with the "all" solution policy
+    fn solve_counted(
+        &self,
+        pose: PyPose,
+        seed: Option<Joints>,
+        options: &InverseOptions,
+    ) -> (Vec<Joints>, usize, Option<usize>) {
+        let start = self.stats.start();
+        let seed = seed.or(self.default_seed);
+        let solved = self.cache.get_or_solve(&pose, seed, options, || {
+            let mut solutions = self.candidates(pose, seed, options);
+            let seed = seed.unwrap_or([0.0; 6]);
+            let n_solutions = solutions.len();
+            let mut solution_index = (n_solutions > 0).then_some(0);
+            if self.solution_policy == SolutionPolicy::Closest {
+                let all = solutions.clone();
+                self.apply_policy(&mut solutions, &seed);
+                solution_index = solutions
+                    .first()
+                    .and_then(|chosen| all.iter().position(|solution| solution == chosen));
+            }
+            if let Some(solution) = solutions
+                .first()
+                .filter(|solution| !self.is_regular(solution, self.singularity_tolerance))
+            {
+                log::debug!(
+                    "Solution {:?} for pose {:?} is at the wrist singularity, the redundant joints follow the seed",
+                    solution,
+                    pose
+                );
+            }
+            (solutions, n_solutions, solution_index)
+        });
+        self.stats.record_inverse(start, solved.0.len());
+        solved
+    }
+
+    /// Inverse kinematics with J1, J4 and J6 unwound to the given turn numbers: the solution
+    /// of every branch within the joint limits, ordered by the seed (radians) and merged as
+    /// set in the options, then reduced by the solution policy
+    fn solve_turns(
+        &self,
+        pose: PyPose,
+        seed: Option<Joints>,
+        turns: [i32; 3],
+        options: &InverseOptions,
+    ) -> Vec<Joints> {
+        let start = self.stats.start();
+        let seed = seed.or(self.default_seed).unwrap_or([0.0; 6]);
+        let model = &self._kinematic_model;
+        let mut solutions: Vec<Joints> = self
+            .branches(pose)
+            .iter()
+            .flatten()
+            .map(|solution| configuration::with_turns(model, solution, turns))
+            .filter(|solution| self.within_limits(solution))
+            .collect();
+        self.arrange(&mut solutions, &seed, options);
+        self.apply_policy(&mut solutions, &seed);
+        self.stats.record_inverse(start, solutions.len());
+        solutions
+    }
+
+    /// Inverse kinematics from the seed and every extra seed (radians), the union of their
+    /// distinct solutions ordered by the seed and merged as set in the options, then
+    /// reduced by the solution policy
+    fn solve_seeded(
+        &self,
+        pose: PyPose,
+        seed: Option<Joints>,
+        seeds: &[Joints],
+        options: &InverseOptions,
+    ) -> Vec<Joints> {
+        let start = self.stats.start();
+        let seed = seed.or(self.default_seed);
+        let unmerged = InverseOptions {
+            order_by: SolutionOrder::Unordered,
+            dedup_tolerance: None,
+            ..*options
+        };
+        let mut solutions = seeds::merge(
+            std::iter::once(seed)
+                .chain(seeds.iter().copied().map(Some))
+                .flat_map(|seed| self.candidates(pose, seed, &unmerged)),
+        );
+        let seed = seed.unwrap_or([0.0; 6]);
+        self.arrange(&mut solutions, &seed, options);
+        self.apply_policy(&mut solutions, &seed);
+        self.stats.record_inverse(start, solutions.len());
+        solutions
+    }
+
+    /// Orders solutions (radians) as set in the options, by the seed, and merges those
+    /// within the dedup tolerance of a better ranked one
+    fn arrange(&self, solutions: &mut Vec<Joints>, seed: &Joints, options: &InverseOptions) {
+        self.sort_solutions(solutions, options.order_by, seed);
+        if let Some(tolerance) = options.dedup_tolerance {
+            ordering::dedup(
+                solutions,
+                tolerance,
+                options.dedup_turns.then_some(&self._kinematic_model),
+            );
+        }
+    }
+
+    /// Reduces solutions (radians) by the solution policy, to the one closest to the seed
+    /// with the "closest" policy
+    fn apply_policy(&self, solutions: &mut Vec<Joints>, seed: &Joints) {
+        if self.solution_policy == SolutionPolicy::Closest {
+            let cfx = |joints: &Joints| self.configuration(joints);
+            ordering::sort(solutions, SolutionOrder::Distance, seed, cfx);
+            solutions.truncate(1);
+        }
+    }
+
+    /// Sorts solutions (radians) in the given order, by the posture cost of the robot for
+    /// the comfort order and by the score of its ranking for the weighted order
+    fn sort_solutions(&self, solutions: &mut [Joints], order: SolutionOrder, seed: &Joints) {
+        let cfx = |joints: &Joints| self.configuration(joints);
+        ordering::sort(solutions, order, seed, cfx);
+        let scores: Vec<f64> = match order {
+            SolutionOrder::Comfort => solutions
+                .iter()
+                .map(|joints| self.comfort_score(joints).total)
+                .collect(),
+            SolutionOrder::Weighted => {
+                self.ranking
+                    .scores(solutions, seed, self.joint_limits.as_ref(), cfx, |joints| {
+                        reachability::manipulability(|q| self.forward_radians(q), joints)
+                    })
+            }
+            _ => return,
+        };
+        let mut scored: Vec<(f64, Joints)> =
+            scores.into_iter().zip(solutions.iter().copied()).collect();
+        scored.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (solution, (_, joints)) in solutions.iter_mut().zip(scored) {
+            *solution = joints;
+        }
+    }
+
+    /// Posture cost of joints (radians) with the comfort settings of the robot
+    fn comfort_score(&self, joints: &Joints) -> comfort::Score {
+        let preferred = self
+            .comfort
+            .preferred
+            .or(self.default_seed)
+            .unwrap_or([0.0; 6]);
+        self.comfort.score(
+            joints,
+            &preferred,
+            self.joint_limits.as_ref(),
+            self._kinematic_model.extension(&self.unmastered(joints)),
+        )
+    }
+
+    /// Every valid solution of a pose in radians, ordered and merged as set in the options,
+    /// before the solution policy applies
+    fn candidates(
+        &self,
+        pose: PyPose,
+        seed: Option<Joints>,
+        options: &InverseOptions,
+    ) -> Vec<Joints> {
+        let seed = seed.or(self.default_seed);
+        let iso_pose = self.target(&pose);
+
+        let model = &self._kinematic_model;
+        let mut solutions: Vec<Joints> = self
+            ._tool
+            .inverse_continuing(
+                &iso_pose,
+                &seed.map_or(CONSTRAINT_CENTERED, |seed| {
+                    model.decouple(&self.unmastered(&seed))
+                }),
+            )
+            .iter()
+            .map(|solution| model.couple(solution))
+            .collect();
+        if let Some(chain) = &self.calibration {
+            // The analytic solutions of the nominal model seed the calibrated chain
+            let found = solutions.len();
+            solutions.retain_mut(|solution| match chain.refine(&iso_pose, solution) {
+                Some(refined) => {
+                    *solution = refined;
+                    true
+                }
+                None => false,
+            });
+            if solutions.len() < found {
+                log::debug!(
+                    "Dropped {} of {} solutions that did not converge on the calibrated chain",
+                    found - solutions.len(),
+                    found
+                );
+            }
+        }
+        for solution in solutions.iter_mut() {
+            *solution = self.mastered(solution);
+        }
+        // Without joints the solver centers on the zero position
+        let seed = seed.unwrap_or([0.0; 6]);
+        if solutions.is_empty() {
+            log::debug!("Pose {:?} is unreachable", pose);
+        }
+        let found = solutions.len();
+        solutions.retain(|solution| self.within_limits(solution));
+        if solutions.len() < found {
+            log::debug!(
+                "Dropped {} of {} solutions outside the joint limits",
+                found - solutions.len(),
+                found
+            );
+        }
+        self.arrange(&mut solutions, &seed, options);
+        solutions
+    }
+
+    /// The solution (radians) within the joint limits closest to the seed, or the default
+    /// seed, without allocating beyond the solver's own result. Without `check_singularity`
+    /// the solutions are unwound to the seed here instead of by the continuing solver.
+    fn closest_solution(
+        &self,
+        pose: &Isometry3<f64>,
+        seed: Option<Joints>,
+        check_singularity: bool,
+    ) -> Option<Joints> {
+        let seed = seed.or(self.default_seed);
+        let model = &self._kinematic_model;
+        let solutions = match (check_singularity, seed) {
+            (true, _) | (false, None) => self._tool.inverse_continuing(
+                pose,
+                &seed.map_or(CONSTRAINT_CENTERED, |seed| {
+                    model.decouple(&self.unmastered(&seed))
+                }),
+            ),
+            (false, Some(_)) => self._tool.inverse(pose),
+        };
+        let seed = seed.unwrap_or([0.0; 6]);
+        let unwound = solutions.iter().map(|solution| {
+            let mut joints = self.mastered(&model.couple(solution));
+            for (joint, seed) in joints.iter_mut().zip(&seed) {
+                *joint -= TAU * ((*joint - seed) / TAU).round();
+            }
+            joints
+        });
+        let closest = ordering::closest(
+            unwound.filter(|solution| self.within_limits(solution)),
+            &seed,
+            |joints| self.configuration(joints),
+        );
+        match &self.calibration {
+            // Refining every branch onto the calibrated chain is not cheap, only the
+            // closest nominal one is
+            Some(chain) => closest
+                .and_then(|solution| chain.refine(pose, &self.unmastered(&solution)))
+                .map(|solution| self.mastered(&solution))
+                .filter(|solution| self.within_limits(solution)),
+            None => closest,
+        }
+    }
+
+    /// World pose of an `offset` given in the TCP frame at `joints`, or at the default seed
+    /// without them
+    fn tool_relative(
+        &self,
+        offset: PyPose,
+        joints: Option<[f64; 6]>,
+        radians: Option<bool>,
+    ) -> PyResult<PyPose> {
+        let joints = joints
+            .map(|joints| self.joints_in(joints, radians))
+            .or(self.default_seed)
+            .ok_or_else(|| {
+                PyValueError::new_err(
+                    "A tool relative pose needs current_joints or a default seed of the robot",
+                )
+            })?;
+        let tcp = self.reported(&self.forward_radians(&joints));
+        Ok(from_isometry(&(tcp * to_isometry(offset.0, offset.1))))
+    }
+
+    /// The solution of every analytic branch of a pose (radians) by branch index, without
+    /// the singularity handling of the continuing solver and before the joint limits
+    fn branches(&self, pose: PyPose) -> [Option<Joints>; branches::BRANCHES] {
+        let iso_pose = self.target(&pose);
+        let model = &self._kinematic_model;
+        let solutions: Vec<Joints> = self
+            ._tool
+            .inverse(&iso_pose)
+            .iter()
+            .map(|solution| model.couple(solution))
+            .filter_map(|solution| match &self.calibration {
+                Some(chain) => chain.refine(&iso_pose, &solution),
+                None => Some(solution),
+            })
+            .collect();
+        branches::by_branch(model, &solutions).map(|solution| solution.map(|s| self.mastered(&s)))
+    }
+
+    /// Checks the settings of a workspace projection and returns the fan to relax the
+    /// orientation over as (max_tilt in radians, samples), None without `relax_orientation`
+    fn projection_settings(
+        &self,
+        relax_orientation: bool,
+        max_tilt: Option<f64>,
+        n_samples: usize,
+        tolerance: f64,
+        radians: Option<bool>,
+    ) -> PyResult<Option<(f64, usize)>> {
+        validation::check_positive("tolerance", tolerance)?;
+        if n_samples == 0 {
+            return Err(PyValueError::new_err("n_samples must be positive"));
+        }
+        let max_tilt = max_tilt.map_or(PI / 4.0, |tilt| self.joint_in(tilt, radians));
+        if !(max_tilt.is_finite() && max_tilt >= 0.0) {
+            return Err(PyValueError::new_err(
+                "max_tilt must be finite and not negative",
+            ));
+        }
+        Ok(relax_orientation.then_some((max_tilt, n_samples)))
+    }
+
+    /// Whether a pose as given to `inverse` has an analytic solution within the joint limits
+    fn reaches(&self, pose: &Isometry3<f64>) -> bool {
+        self.branches(from_isometry(pose))
+            .iter()
+            .flatten()
+            .any(|joints| self.within_limits(joints))
+    }
+
+    /// Nearest reachable pose to `target`: its TCP position moved towards the middle of the
+    /// reachable shell, at the target orientation or, with `relax` as (max_tilt, samples),
+    /// at the orientation of the fan around it that needs the least move, preferring the
+    /// smaller rotation among moves equal within `tolerance`. The target and the projected
+    /// pose are as given to `inverse`, with a stationary tool in the part frame.
+    fn project(
+        &self,
+        target: &Isometry3<f64>,
+        relax: Option<(f64, usize)>,
+        tolerance: f64,
+    ) -> Option<Isometry3<f64>> {
+        if self.reaches(target) {
+            return Some(*target);
+        }
+        let tcp = self.target(&from_isometry(target));
+        let mut rotations = vec![tcp.rotation];
+        if let Some((max_tilt, samples)) = relax {
+            let fan = reachability::fan(&tcp.rotation, max_tilt, samples);
+            rotations.extend(fan.into_iter().map(|(_, _, rotation)| rotation));
+        }
+        let (base, tool) = (self.base(), self.tool());
+        let from = tcp.translation.vector;
+        let mut best: Option<(f64, f64, Isometry3<f64>)> = None;
+        for rotation in rotations {
+            let pose = Isometry3::from_parts(tcp.translation, rotation);
+            let flange = base.inverse() * pose * tool.inverse();
+            let to =
+                from + base.rotation * projection::shell_shift(&self._kinematic_model, &flange);
+            let reachable = |point: &Vector3<f64>| {
+                self.reaches(&self.reported(&Isometry3::from_parts((*point).into(), rotation)))
+            };
+            let Some(point) = projection::line_search(reachable, &from, &to, tolerance) else {
+                continue;
+            };
+            let (distance, angle) = ((point - from).norm(), rotation.angle_to(&tcp.rotation));
+            let better = best.is_none_or(|(best_distance, best_angle, _)| {
+                distance < best_distance - tolerance
+                    || (distance <= best_distance + tolerance && angle < best_angle)
+            });
+            if better {
+                best = Some((
+                    distance,
+                    angle,
+                    Isometry3::from_parts(point.into(), rotation),
+                ));
+            }
+        }
+        best.map(|(_, _, pose)| self.reported(&pose))
+    }
+
+    /// Solves one pose and checks the best solution against it
+    fn solve_row(
+        &self,
+        pose: PyPose,
+        current_joints: Option<[f64; 6]>,
+        options: &InverseOptions,
+    ) -> SolvedRow {
+        self.solve_row_in(pose, current_joints, options, None)
+    }
+
+    /// Like `solve_row`, taking the best solution with the given ABB axis configuration
+    /// (cf1, cf4, cf6, cfx) if one is given
+    fn solve_row_in(
+        &self,
+        pose: PyPose,
+        current_joints: Option<[f64; 6]>,
+        options: &InverseOptions,
+        axis_configuration: Option<(i32, i32, i32, u8)>,
+    ) -> SolvedRow {
+        let seed = current_joints.map(|joints| self.joints_in(joints, options.radians));
+        let radians = if validation::is_finite_pose(&pose) {
+            self.solve_radians(pose, seed, options)
+                .into_iter()
+                .find(|joints| {
+                    axis_configuration
+                        .is_none_or(|wanted| self.axis_configuration_radians(joints) == wanted)
+                })
+        } else {
+            None
+        };
+        self.solved_row(pose, radians, options.radians)
+    }
+
+    /// The row of a pose and its solution (radians), checked against the pose
+    fn solved_row(&self, pose: PyPose, joints: Option<Joints>, radians: Option<bool>) -> SolvedRow {
+        SolvedRow {
+            pose,
+            joints: joints.map(|joints| self.joints_out(joints, radians)),
+            configuration: joints.map(|joints| self.configuration(&joints)),
+            residual: joints.map(|joints| {
+                let (position, rotation) = pose::pose_errors(
+                    &self.reported(&self.forward_radians(&joints)),
+                    &to_isometry(pose.0, pose.1),
+                );
+                (position, rotation.to_degrees())
+            }),
+        }
+    }
+
+    /// Rows of `solve_continuous` for screened poses, seeded with `seed` (radians) and
+    /// within `max_step` (radians) from row to row
+    fn continuous_rows<'py>(
+        &self,
+        py: Python<'py>,
+        poses: &[PyPose],
+        seed: Option<Joints>,
+        recovery: &[Recovery],
+        max_step: f64,
+        radians: Option<bool>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let options = self.inverse_options(SolutionOrder::Distance, None, true, radians);
+        let solved = py.detach(|| {
+            let mut previous = seed;
+            let mut solved: Vec<Option<(Joints, Option<Recovery>)>> = poses
+                .iter()
+                .map(|pose| {
+                    if !validation::is_finite_pose(pose) {
+                        return None;
+                    }
+                    let found =
+                        self.solve_continuing(*pose, previous, max_step, recovery, &options);
+                    previous = found.map(|(joints, _)| joints).or(previous);
+                    found
+                })
+                .collect();
+            if recovery.contains(&Recovery::Interpolate) {
+                recovery::interpolate(&mut solved, |i| validation::is_finite_pose(&poses[i]));
+            }
+            solved
+        });
+        poses
+            .iter()
+            .zip(solved)
+            .map(|(&pose, solved)| {
+                let dict = self
+                    .solved_row(pose, solved.map(|(joints, _)| joints), radians)
+                    .to_dict(py)?;
+                let strategy = solved.and_then(|(_, strategy)| strategy);
+                if strategy == Some(Recovery::Interpolate) {
+                    dict.set_item("status", "interpolated")?;
+                }
+                dict.set_item("recovery", strategy.map(|strategy| strategy.name()))?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// The solution (radians) of a pose closest to the `previous` row's within `max_step`
+    /// (radians), or the first the `recovery` strategies find with the one that found it
+    fn solve_continuing(
+        &self,
+        pose: PyPose,
+        previous: Option<Joints>,
+        max_step: f64,
+        recovery: &[Recovery],
+        options: &InverseOptions,
+    ) -> Option<(Joints, Option<Recovery>)> {
+        let from = previous.or(self.default_seed).unwrap_or([0.0; 6]);
+        let max_step = if previous.is_some() {
+            max_step
+        } else {
+            f64::INFINITY
+        };
+        let nearest = |solutions: &[Joints], max_step: f64| {
+            path::closest(solutions, &from, max_step, |a, b| {
+                PathCost::Travel.motion(a, b)
+            })
+            .map(|index| solutions[index])
+        };
+        let solutions = self.solve_radians(pose, previous, options);
+        if let Some(joints) = nearest(&solutions, max_step) {
+            return Some((joints, None));
+        }
+        recovery.iter().find_map(|&strategy| {
+            let joints = match strategy {
+                Recovery::Relaxed => self.closest_solution(&self.target(&pose), Some(from), false),
+                Recovery::Seeds => {
+                    let seeds = self
+                        .default_seed
+                        .into_iter()
+                        .chain(self.postures.values().copied())
+                        .chain([[0.0; 6]]);
+                    let solutions: Vec<Joints> = seeds
+                        .flat_map(|seed| self.solve_radians(pose, Some(seed), options))
+                        .collect();
+                    nearest(&solutions, max_step)
+                }
+                // Needs the rows after the gap, filled in once all are solved
+                Recovery::Interpolate => None,
+            };
+            joints.map(|joints| (joints, Some(strategy)))
+        })
+    }
+
+    /// ABB axis configuration (cf1, cf4, cf6, cfx) of joints in radians, from their true
+    /// joint angles
+    fn axis_configuration_radians(&self, joints: &Joints) -> (i32, i32, i32, u8) {
+        let joints = &self.unmastered(joints);
+        (
+            configuration::quadrant(joints[0]),
+            configuration::quadrant(joints[3]),
+            configuration::quadrant(joints[5]),
+            configuration::configuration(&self._kinematic_model, joints),
+        )
+    }
+
+    /// Pose of the robot base in the world frame
+    fn base(&self) -> Isometry3<f64> {
+        to_isometry(self.base_config.translation, self.base_config.rotation)
+    }
+
+    /// Pose of the TCP in the flange frame
+    fn tool(&self) -> Isometry3<f64> {
+        to_isometry(self.tool_config.translation, self.tool_config.rotation)
+    }
+
+    /// UnreachablePoseError for a pose without solutions, explaining why
+    fn unreachable_error(&self, py: Python<'_>, pose: PyPose) -> PyErr {
+        let target = self.target(&pose);
+        let flange = self.base().inverse() * target * self.tool().inverse();
+        let distance = self._kinematic_model.reach_deficit(&flange);
+        let reason = if distance > 0.0 {
+            format!(
+                "the wrist center is {:.4} m outside the workspace",
+                distance
+            )
+        } else if self.joint_limits.is_some() {
+            "no solution is within the joint limits".to_string()
+        } else {
+            "no solution was found".to_string()
+        };
+        let error =
+            UnreachablePoseError::new_err(format!("Pose {:?} is unreachable: {}", pose, reason));
+        match error.value(py).setattr("distance", distance) {
+            Ok(()) => error,
+            Err(setattr_error) => setattr_error,
+        }
+    }
+
+    /// Collects the per-call inverse settings, converting angles to radians
+    fn inverse_options(
+        &self,
+        order_by: SolutionOrder,
+        dedup_tolerance: Option<f64>,
+        dedup_turns: bool,
+        radians: Option<bool>,
+    ) -> InverseOptions {
+        InverseOptions {
+            order_by,
+            dedup_tolerance: dedup_tolerance.map(|tolerance| self.joint_in(tolerance, radians)),
+            dedup_turns,
+            radians,
+        }
+    }
+
+    /// Converts joints in radians to the units used by this robot (or the call)
+    fn joints_out(&self, joints: [f64; 6], radians: Option<bool>) -> [f64; 6] {
+        joints.map(|x| self.joint_out(x, radians))
+    }
+
+    fn joint_out(&self, joint: f64, radians: Option<bool>) -> f64 {
+        if self.uses_radians(radians) {
+            joint
+        } else {
+            joint.to_degrees()
+        }
+    }
 
-        let joints = if let Some(joints) = current_joints {
-            joints.map(|x| x.to_radians())
-        } else {
-            CONSTRAINT_CENTERED
-        };
-        let solutions = self._tool.inverse_continuing(&iso_pose, &joints);
+    /// Checks that controller joints in radians are not at the wrist singularity, J5 within
+    /// `tolerance` (radians) of a multiple of 180 degrees
+    fn is_regular(&self, joints: &Joints, tolerance: f64) -> bool {
+        let j5 = self._kinematic_model.decouple(&self.unmastered(joints))[4].rem_euclid(PI);
+        j5 >= tolerance && PI - j5 >= tolerance
+    }
 
-        // Convert all solutions to degrees without filtering
-        solutions
-            .iter()
-            .map(|x| self.convert_to_degrees(*x))
-            .collect::<Vec<_>>()
+    /// What a batch summary records of a pose solved to `joints` (radians), None if it has
+    /// no solution
+    fn summary_row(&self, pose: PyPose, joints: Option<&Joints>) -> summary::Row {
+        match joints {
+            Some(joints) => summary::Row::Solved {
+                joints: *joints,
+                singular: !self.is_regular(joints, self.singularity_tolerance),
+                within_limits: self.within_limits(joints),
+            },
+            None => summary::Row::Unreachable {
+                outside_limits: self.joint_limits.is_some()
+                    && self.branches(pose).iter().any(Option::is_some),
+            },
+        }
     }
 
-    #[pyo3(signature = (poses))]
-    fn batch_inverse(&self, poses: Vec<([f64; 3], [f64; 4])>) -> Vec<Vec<[f64; 6]>> {
-        poses
+    /// Digests of the kinematic setup and the solution policy, to stamp on batch rows
+    fn provenance(&self) -> Provenance {
+        let tool = self
+            .tool_config
+            .translation
             .iter()
-            .map(|&pose| self.inverse(pose, None))
-            .collect()
+            .chain(&self.tool_config.rotation);
+        Provenance {
+            model_hash: provenance::digest(self.setup_values(true)),
+            tool_hash: provenance::digest(tool.copied()),
+            config_policy: self.solution_policy.name(),
+        }
     }
 
-    #[pyo3(signature = (joints))]
-    fn batch_forward(&self, joints: Vec<[f64; 6]>) -> Vec<([f64; 3], [f64; 4])> {
-        joints
-            .iter()
-            .map(|&joint_set| self.forward(joint_set))
-            .collect()
+    /// The numbers that define the kinematic model, link corrections, mastering offsets,
+    /// base, stationary tool and, with `limits`, the joint limits, in a fixed order for
+    /// digests
+    fn setup_values(&self, limits: bool) -> Vec<f64> {
+        let model = &self._kinematic_model;
+        let parameters = [
+            model.a1, model.a2, model.b, model.c1, model.c2, model.c3, model.c4,
+        ];
+        let mut values: Vec<f64> = parameters.to_vec();
+        values.extend(model.offsets);
+        values.extend(model.sign_corrections.map(f64::from));
+        values.push(model.has_parallelogram as u8 as f64);
+        values.extend(model.wrist_coupling);
+        values.extend(self.base_config.translation);
+        values.extend(self.base_config.rotation);
+        if let Some(calibration) = &self.calibration {
+            values.extend(calibration.corrections().iter().flatten());
+        }
+        // Only with offsets, so digests of robots without them stay as they were
+        if self.mastering != [0.0; 6] {
+            values.extend(self.mastering);
+        }
+        if let Some(tool) = &self.stationary_tool {
+            values.extend(tool.translation);
+            values.extend(tool.rotation);
+        }
+        if let Some(joint_limits) = self.joint_limits.filter(|_| limits) {
+            values.extend(joint_limits.iter().flat_map(|(min, max)| [*min, *max]));
+        }
+        values
+    }
+
+    /// Singularity tolerance of a call in radians, the robot's unless given in degrees
+    fn singularity_tolerance(&self, tolerance_deg: Option<f64>) -> PyResult<f64> {
+        match tolerance_deg {
+            Some(tolerance) => {
+                validation::check_positive("singularity_tolerance_deg", tolerance)?;
+                Ok(tolerance.to_radians())
+            }
+            None => Ok(self.singularity_tolerance),
+        }
+    }
+
+    /// Smallest distance of any joint (radians) from its nearer limit, relative to half its
+    /// range: 1 in the middle, 0 at a limit. None without joint limits.
+    fn limit_margin(&self, joints: &Joints) -> Option<f64> {
+        self.joint_limits.map(|limits| {
+            joints
+                .iter()
+                .zip(limits.iter())
+                .map(|(joint, (min, max))| {
+                    ((joint - min).min(max - joint) / (0.5 * (max - min))).max(0.0)
+                })
+                .fold(1.0, f64::min)
+        })
+    }
+
+    /// Checks joints in radians against the joint limits, if any are set
+    fn within_limits(&self, joints: &[f64; 6]) -> bool {
+        self.joint_limits.is_none_or(|limits| {
+            joints
+                .iter()
+                .zip(limits.iter())
+                .all(|(joint, (min, max))| min <= joint && joint <= max)
+        })
+    }
+}
+
+/// A pose for Python, as (translation, quaternion) or as a 4x4 matrix
+pub(crate) fn transform_out<'py>(
+    py: Python<'py>,
+    pose: &Isometry3<f64>,
+    as_matrix: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    if as_matrix {
+        Ok(pose::to_matrix(pose).into_pyobject(py)?.into_any())
+    } else {
+        Ok(from_isometry(pose).into_pyobject(py)?.into_any())
     }
 }
 
 /// Module initialization for Python
-#[pymodule(name = "_internal")]
+#[pymodule(name = "_internal", gil_used = false)]
 fn py_opw_kinematics(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    logging::init();
     m.add_class::<KinematicModel>()?;
     m.add_class::<Robot>()?;
+    m.add_class::<RobotBuilder>()?;
     m.add_class::<BaseConfig>()?;
     m.add_class::<ToolConfig>()?;
+    m.add_class::<DualQuaternion>()?;
+    m.add_class::<CancelToken>()?;
+    m.add_class::<ThreadPool>()?;
+    m.add_class::<SolverContext>()?;
+    m.add_class::<KinematicsServer>()?;
+    m.add_class::<StreamingBridge>()?;
+    m.add_class::<mirror::Mirror>()?;
+    m.add_class::<Frames>()?;
+    m.add_class::<Positioner>()?;
+    m.add_class::<cell::Cell>()?;
+    m.add_class::<Program>()?;
+    m.add(
+        "BatchCancelledError",
+        m.py().get_type::<batch::BatchCancelledError>(),
+    )?;
+    m.add(
+        "UnreachablePoseError",
+        m.py().get_type::<UnreachablePoseError>(),
+    )?;
+    m.add_function(wrap_pyfunction!(models::list_models, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata::version_info, m)?)?;
+    m.add_function(wrap_pyfunction!(metadata::solver_info, m)?)?;
+    m.add_function(wrap_pyfunction!(cli::cli_main, m)?)?;
+    m.add_function(wrap_pyfunction!(comparison::compare_robots, m)?)?;
+    m.add_function(wrap_pyfunction!(columns::add_column_alias, m)?)?;
+    m.add_function(wrap_pyfunction!(columns::column_aliases, m)?)?;
+    m.add_function(wrap_pyfunction!(twist::pose_to_twist, m)?)?;
+    m.add_function(wrap_pyfunction!(twist::twist_to_pose, m)?)?;
+    m.add_function(wrap_pyfunction!(twist::interpolate_twist, m)?)?;
+    m.add_function(wrap_pyfunction!(joint_distance::joint_distance, m)?)?;
+    m.add_function(wrap_pyfunction!(joint_distance::joint_distances, m)?)?;
+    m.add_function(wrap_pyfunction!(
+        joint_distance::pairwise_joint_distances,
+        m
+    )?)?;
+    m.add_function(wrap_pyfunction!(pose_error::pose_error, m)?)?;
+    m.add_function(wrap_pyfunction!(rotations::quaternions_to_euler, m)?)?;
+    m.add_function(wrap_pyfunction!(rotations::euler_to_quaternions, m)?)?;
+    m.add_function(wrap_pyfunction!(rotations::quaternions_to_matrices, m)?)?;
+    m.add_function(wrap_pyfunction!(rotations::matrices_to_quaternions, m)?)?;
+    m.add_function(wrap_pyfunction!(pose_error::pose_errors, m)?)?;
+    m.add_function(wrap_pyfunction!(registration::fit_transform, m)?)?;
+    m.add_function(wrap_pyfunction!(registration::fit_pose_transform, m)?)?;
+    m.add_function(wrap_pyfunction!(pallet::generate_pallet, m)?)?;
+    m.add_function(wrap_pyfunction!(seam::seam_poses, m)?)?;
+    m.add_function(wrap_pyfunction!(look_at::look_at, m)?)?;
+    m.add_function(wrap_pyfunction!(look_at::batch_look_at, m)?)?;
+    m.add_function(wrap_pyfunction!(axis::set_tool_axis, m)?)?;
+    m.add_function(wrap_pyfunction!(axis::tool_axis, m)?)?;
     Ok(())
 }
 
@@ -215,7 +4410,7 @@ fn py_opw_kinematics(m: &Bound<'_, PyModule>) -> PyResult<()> {
 mod tests {
     use super::*;
 
-    const ABB_1660: KinematicModel = KinematicModel {
+    pub(crate) const ABB_1660: KinematicModel = KinematicModel {
         a1: 0.150,  // Distance from base to J1 axis
         a2: -0.110, // Distance from J1 to J2 axis (parallel offset)
         b: 0.0,     // Distance from J2 to J3 axis (perpendicular offset)
@@ -225,8 +4420,21 @@ mod tests {
         c4: 0.135,  // Distance from J4 to J6 axis (wrist length)
         offsets: [0.0, 0.0, -std::f64::consts::FRAC_PI_2, 0.0, 0.0, 0.0],
         sign_corrections: [1, 1, 1, 1, 1, 1],
+        has_parallelogram: false,
+        wrist_coupling: [0.0, 0.0],
     };
 
+    /// ABB_1660 at the world origin, without a tool
+    pub(crate) fn abb_1660() -> Robot {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.0; 3], identity),
+            ToolConfig::new([0.0; 3], identity),
+        )
+        .unwrap()
+    }
+
     #[test]
     fn test_simple_forward() {
         let kinematic_model = ABB_1660;
@@ -245,7 +4453,7 @@ mod tests {
         };
         let robot = Robot::new(kinematic_model, base_config, tool_config).unwrap();
         let joints = [-103.1, -85.03, 19.06, -70.19, -35.87, 185.01];
-        let (translation, rotation) = robot.forward(joints);
+        let (translation, rotation) = robot.forward(joints, None).unwrap();
         assert_eq!(
             translation,
             [0.2000017014027134, -0.30003856402112994, 0.8999972858765594]
@@ -287,7 +4495,7 @@ mod tests {
                 -0.19848490647852607,
             ],
         );
-        let solutions = robot.inverse(pose, None);
+        let solutions = robot.solve(pose, None, &InverseOptions::default());
         // Check that we get solutions (exact number may vary)
         assert!(!solutions.is_empty());
         // Check that the first solution is reasonable
@@ -323,32 +4531,58 @@ mod tests {
                     -0.19848490647852607,
                 ],
             ),
-            (
-                [0.5, 0.0, 1.2],
-                [1.0, 0.0, 0.0, 0.0],
-            ),
+            ([0.5, 0.0, 1.2], [1.0, 0.0, 0.0, 0.0]),
         ];
-        
-        let batch_solutions = robot.batch_inverse(poses.clone());
-        
+
+        Python::initialize();
+        let batch_solutions = Python::attach(|py| {
+            robot
+                .batch_inverse(
+                    py,
+                    PoseTable(poses.clone()),
+                    None,
+                    None,
+                    SolutionOrder::Distance,
+                    None,
+                    true,
+                    None,
+                    false,
+                    InvalidPolicy::Null,
+                    None,
+                    None,
+                    None,
+                    false,
+                    false,
+                )
+                .and_then(|rows| rows.extract::<Vec<Vec<[f64; 6]>>>())
+        })
+        .unwrap();
+
         // Check that we get solutions for each pose
         assert_eq!(batch_solutions.len(), 2);
-        
+
         // Check that each pose has multiple solutions
         for (i, solutions) in batch_solutions.iter().enumerate() {
             assert!(!solutions.is_empty(), "Pose {} should have solutions", i);
-            
+
             // Verify that solutions are valid by doing forward kinematics
             for solution in solutions {
-                let (computed_translation, computed_rotation) = robot.forward(*solution);
+                let (computed_translation, _) = robot.forward(*solution, None).unwrap();
                 let original_pose = &poses[i];
-                
+
                 // Check translation (with some tolerance for numerical precision)
-                for j in 0..3 {
+                for (j, (computed, expected)) in computed_translation
+                    .iter()
+                    .zip(original_pose.0.iter())
+                    .enumerate()
+                {
                     assert!(
-                        (computed_translation[j] - original_pose.0[j]).abs() < 1e-10,
+                        (computed - expected).abs() < 1e-10,
                         "Translation mismatch at pose {}, axis {}: expected {}, got {}",
-                        i, j, original_pose.0[j], computed_translation[j]
+                        i,
+                        j,
+                        expected,
+                        computed
                     );
                 }
             }
@@ -356,7 +4590,7 @@ mod tests {
         
         // Test that batch_inverse gives same results as individual inverse calls
         for (i, pose) in poses.iter().enumerate() {
-            let individual_solutions = robot.inverse(*pose, None);
+            let individual_solutions = robot.solve(*pose, None, &InverseOptions::default());
             let batch_solution = &batch_solutions[i];
             assert_eq!(
                 individual_solutions.len(),
@@ -376,4 +4610,1643 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_dict_round_trip() {
+        Python::initialize();
+        Python::attach(|py| {
+            let robot = RobotBuilder {
+                kinematic_model: Some(ABB_1660),
+                joint_limits: Some([(-170.0, 170.0); 6]),
+                solution_policy: Some("closest".to_string()),
+                ..RobotBuilder::default()
+            }
+            .build()
+            .unwrap();
+            let dict = robot.to_dict(py).unwrap();
+            let restored = Robot::from_dict(&dict).unwrap();
+            assert!(restored.to_dict(py).unwrap().eq(&dict).unwrap());
+            assert_eq!(restored.solution_policy, SolutionPolicy::Closest);
+
+            dict.set_item("unknown", 1).unwrap();
+            assert!(Robot::from_dict(&dict).is_err());
+        });
+    }
+
+    #[test]
+    fn test_robot_shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Robot>();
+
+        let robot = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.0; 3], [1.0, 0.0, 0.0, 0.0]),
+            ToolConfig::new([0.0, 0.0, 0.1], [1.0, 0.0, 0.0, 0.0]),
+        )
+        .unwrap();
+        robot.enable_stats(true);
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let expected = robot.forward(joints, None).unwrap();
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        assert_eq!(robot.forward(joints, None).unwrap(), expected);
+                        assert!(!robot
+                            .solve(expected, Some(joints), &InverseOptions::default())
+                            .is_empty());
+                    }
+                });
+            }
+        });
+        Python::initialize();
+        Python::attach(|py| {
+            let stats = robot.stats(py).unwrap();
+            let calls: u64 = stats
+                .get_item("inverse_calls")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(calls, 400);
+        });
+    }
+
+    #[test]
+    fn test_inverse_cache() {
+        let robot = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.0; 3], [1.0, 0.0, 0.0, 0.0]),
+            ToolConfig::new([0.0, 0.0, 0.1], [1.0, 0.0, 0.0, 0.0]),
+        )
+        .unwrap();
+        robot.enable_cache(16, 1e-6, 1e-6).unwrap();
+        let pose = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        let options = InverseOptions::default();
+        let solved = robot.solve(pose, None, &options);
+        assert_eq!(robot.solve(pose, None, &options), solved);
+        Python::initialize();
+        Python::attach(|py| {
+            let stats = robot.cache_stats(py).unwrap();
+            let hits: u64 = stats.get_item("hits").unwrap().unwrap().extract().unwrap();
+            let misses: u64 = stats
+                .get_item("misses")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!((hits, misses), (1, 1));
+        });
+    }
+
+    #[test]
+    fn test_batch_cancel() {
+        let robot = abb_1660();
+        let token = CancelToken::default();
+        Python::initialize();
+        Python::attach(|py| {
+            let token = Bound::new(py, token).unwrap();
+            let joints = vec![[0.0; 6]; 3000];
+            assert_eq!(
+                robot
+                    .batch_forward(
+                        py,
+                        JointTable(joints.clone()),
+                        None,
+                        Some(token.borrow()),
+                        None,
+                        InvalidPolicy::Null,
+                        None,
+                        None,
+                        None,
+                        false,
+                        false,
+                        None,
+                        false,
+                        false
+                    )
+                    .unwrap()
+                    .len()
+                    .unwrap(),
+                3000
+            );
+            token.get().cancel();
+            let result = robot.batch_forward(
+                py,
+                JointTable(joints),
+                None,
+                Some(token.borrow()),
+                None,
+                InvalidPolicy::Null,
+                None,
+                None,
+                None,
+                false,
+                false,
+                None,
+                false,
+                false,
+            );
+            assert!(result
+                .unwrap_err()
+                .is_instance_of::<batch::BatchCancelledError>(py));
+        });
+    }
+
+    #[test]
+    fn test_annotated_batch_forward() {
+        let robot = abb_1660();
+        Python::initialize();
+        Python::attach(|py| {
+            let joints = vec![
+                [10.0, 20.0, 30.0, 40.0, 50.0, 60.0],
+                [10.0, 20.0, 30.0, 40.0, 0.0, 60.0],
+            ];
+            let rows: Vec<Bound<'_, PyAny>> = robot
+                .batch_forward(
+                    py,
+                    JointTable(joints.clone()),
+                    None,
+                    None,
+                    None,
+                    InvalidPolicy::Null,
+                    None,
+                    None,
+                    None,
+                    true,
+                    true,
+                    None,
+                    false,
+                    true,
+                )
+                .unwrap()
+                .extract()
+                .unwrap();
+            let get = |row: usize, key: &str| rows[row].get_item(key).unwrap();
+            let (translation, _) = robot.forward(joints[0], None).unwrap();
+            assert_eq!(get(0, "x").extract::<f64>().unwrap(), translation[0]);
+            assert_eq!(get(0, "CFX").extract::<u8>().unwrap(), 0);
+            assert!(!get(0, "near_singularity").extract::<bool>().unwrap());
+            // J5 = 0 aligns the axes of J4 and J6
+            assert!(get(1, "near_singularity").extract::<bool>().unwrap());
+            let regular: f64 = get(0, "manipulability").extract().unwrap();
+            let singular: f64 = get(1, "manipulability").extract().unwrap();
+            assert!(singular < 1e-6 * regular);
+            let model_hash: String = get(0, "model_hash").extract().unwrap();
+            assert_eq!(model_hash, robot.provenance().model_hash);
+            assert_eq!(get(1, "config_policy").extract::<String>().unwrap(), "all");
+
+            let (_, summary): (Bound<'_, PyAny>, Bound<'_, PyDict>) = robot
+                .batch_forward(
+                    py,
+                    JointTable(joints),
+                    None,
+                    None,
+                    None,
+                    InvalidPolicy::Null,
+                    None,
+                    None,
+                    None,
+                    false,
+                    false,
+                    None,
+                    true,
+                    false,
+                )
+                .unwrap()
+                .extract()
+                .unwrap();
+            let count = |key: &str| {
+                summary
+                    .get_item(key)
+                    .unwrap()
+                    .unwrap()
+                    .extract::<usize>()
+                    .unwrap()
+            };
+            assert_eq!(
+                (count("rows"), count("solved"), count("singular")),
+                (2, 2, 1)
+            );
+            let jump: f64 = summary
+                .get_item("max_joint_jump")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!((jump - 50.0).abs() < 1e-9);
+        });
+    }
+
+    #[test]
+    fn test_per_call_radians() {
+        let robot = abb_1660();
+        let degrees = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let radians = degrees.map(f64::to_radians);
+        let pose = robot.forward(radians, Some(true)).unwrap();
+        assert_eq!(pose, robot.forward(degrees, None).unwrap());
+
+        let options = InverseOptions {
+            radians: Some(true),
+            ..InverseOptions::default()
+        };
+        let solution = robot.solve(pose, Some(radians), &options)[0];
+        for (a, b) in solution.iter().zip(radians.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_parallelogram_round_trip() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let coupled = KinematicModel {
+            has_parallelogram: true,
+            ..ABB_1660
+        };
+        let robot = Robot::new(
+            coupled,
+            BaseConfig::new([0.0; 3], identity),
+            ToolConfig::new([0.0; 3], identity),
+        )
+        .unwrap();
+        let plain = abb_1660();
+
+        // The controller shows J3 = 30 + 20 for a kinematic J3 of 30
+        let joints = [10.0, 20.0, 50.0, 40.0, 50.0, 60.0];
+        let pose = robot.forward(joints, None).unwrap();
+        let expected = plain
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        let (position_error, rotation_error) = pose::pose_errors(
+            &to_isometry(pose.0, pose.1),
+            &to_isometry(expected.0, expected.1),
+        );
+        assert!(position_error < 1e-12 && rotation_error < 1e-12);
+        let solution = robot.solve(pose, Some(joints), &InverseOptions::default())[0];
+        for (a, b) in solution.iter().zip(joints.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_wrist_coupling_round_trip() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let robot = Robot::new(
+            KinematicModel {
+                wrist_coupling: [0.1, -0.25],
+                ..ABB_1660
+            },
+            BaseConfig::new([0.0; 3], identity),
+            ToolConfig::new([0.0; 3], identity),
+        )
+        .unwrap();
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let pose = robot.forward(joints, None).unwrap();
+        let solution = robot.solve(pose, Some(joints), &InverseOptions::default())[0];
+        for (a, b) in solution.iter().zip(joints.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_solve_row() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let robot = abb_1660();
+        let options = InverseOptions::default();
+        let pose = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        let row = robot.solve_row(pose, None, &options);
+        assert_eq!(row.status(), "ok");
+        assert_eq!(row.configuration, Some(0));
+        assert!(row.residual.unwrap().0 < 1e-9);
+
+        let row = robot.solve_row(([9.0, 9.0, 9.0], identity), None, &options);
+        assert_eq!(row.status(), "unreachable");
+        assert!(row.joints.is_none() && row.residual.is_none());
+    }
+
+    #[test]
+    fn test_optimize_roll() {
+        let robot = abb_1660();
+        // The tool spins about its axis in steps of a sampled roll, which it takes back out
+        let poses: Vec<PyPose> = (0..5)
+            .map(|i| {
+                robot
+                    .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0 * i as f64], None)
+                    .unwrap()
+            })
+            .collect();
+        Python::initialize();
+        Python::attach(|py| {
+            let rows = robot
+                .optimize_roll(
+                    py,
+                    PoseTable(poses),
+                    None,
+                    36,
+                    None,
+                    PathCost::WristMotion,
+                    None,
+                    None,
+                    Some(Axis::Z),
+                )
+                .unwrap();
+            let joint = |row: &Bound<'_, PyDict>, key: &str| {
+                row.get_item(key)
+                    .unwrap()
+                    .unwrap()
+                    .extract::<f64>()
+                    .unwrap()
+            };
+            for pair in rows.windows(2) {
+                for key in ["j4", "j5", "j6"] {
+                    assert!((joint(&pair[0], key) - joint(&pair[1], key)).abs() < 1e-6);
+                }
+                assert!(joint(&pair[1], "position_residual") < 1e-9);
+            }
+        });
+    }
+
+    #[test]
+    fn test_configuration_coverage() {
+        let robot = abb_1660();
+        // A small region in front of the robot, reached by the branch of its joints
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let poses: Vec<PyPose> = (0..5)
+            .map(|i| {
+                robot
+                    .forward(
+                        [joints[0] + 2.0 * i as f64, 20.0, 30.0, 40.0, 50.0, 60.0],
+                        None,
+                    )
+                    .unwrap()
+            })
+            .collect();
+        let model = &robot._kinematic_model;
+        let cfx = configuration::configuration(model, &robot.joints_in(joints, None)) as usize;
+        Python::initialize();
+        Python::attach(|py| {
+            let rows = robot
+                .configuration_coverage(py, PoseTable(poses), false)
+                .unwrap();
+            assert_eq!(rows.len(), branches::BRANCHES);
+            let covers = |row: &Bound<'_, PyDict>| {
+                row.get_item("covers_region")
+                    .unwrap()
+                    .unwrap()
+                    .extract::<bool>()
+                    .unwrap()
+            };
+            assert!(covers(&rows[cfx]));
+        });
+    }
+
+    #[test]
+    fn test_reach_margin() {
+        let robot = abb_1660();
+        // Stretched out along x, the arm reaches 3 cm further
+        let stretched = robot
+            .forward([0.0, 90.0, -90.0 + 4.6, 0.0, 0.0, 0.0], None)
+            .unwrap();
+        let inside = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        let mut beyond = inside;
+        beyond.0[0] += 5.0;
+        Python::initialize();
+        Python::attach(|py| {
+            let rows = robot
+                .reach_margin(
+                    py,
+                    PoseTable(vec![stretched, inside, beyond]),
+                    Some(0.05),
+                    InvalidPolicy::Null,
+                )
+                .unwrap();
+            let get =
+                |i: usize, key: &str| rows[i].as_ref().unwrap().get_item(key).unwrap().unwrap();
+            assert_eq!(get(0, "boundary").extract::<String>().unwrap(), "outer");
+            assert!(get(0, "near_boundary").extract::<bool>().unwrap());
+            assert!(!get(1, "near_boundary").extract::<bool>().unwrap());
+            assert!(get(2, "margin").extract::<f64>().unwrap() < -4.0);
+
+            // With a stationary tool the margins are those of the world TCP the part-frame
+            // poses put the robot at
+            let mut remote = abb_1660();
+            remote.stationary_tool = Some(ToolConfig::new([1.2, 0.3, 0.9], [0.0, 0.0, 1.0, 0.0]));
+            let held: Vec<PyPose> = [
+                [0.0, 90.0, -90.0 + 4.6, 0.0, 0.0, 0.0],
+                [10.0, 20.0, 30.0, 40.0, 50.0, 60.0],
+            ]
+            .into_iter()
+            .map(|joints| remote.forward(joints, None).unwrap())
+            .collect();
+            let remote_rows = remote
+                .reach_margin(py, PoseTable(held), Some(0.05), InvalidPolicy::Null)
+                .unwrap();
+            for (i, row) in remote_rows.iter().enumerate() {
+                let row = row.as_ref().unwrap();
+                for key in ["wrist_x", "wrist_y", "wrist_z", "margin"] {
+                    let value: f64 = row.get_item(key).unwrap().unwrap().extract().unwrap();
+                    assert!((value - get(i, key).extract::<f64>().unwrap()).abs() < 1e-9);
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_inverse_fast() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let robot = abb_1660();
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 400.0];
+        let pose = robot.forward(joints, None).unwrap();
+        let options = robot.inverse_options(SolutionOrder::Distance, None, true, None);
+        let closest = robot.solve(pose, Some(joints), &options)[0];
+        for check_singularity in [true, false] {
+            let solution = robot
+                .inverse_fast(pose, Some(joints), None, check_singularity)
+                .unwrap()
+                .unwrap();
+            for (a, b) in solution.iter().zip(&closest) {
+                assert!((a - b).abs() < 1e-6);
+            }
+        }
+        let far = ([5.0, 0.0, 0.0], identity);
+        assert_eq!(robot.inverse_fast(far, None, None, true).unwrap(), None);
+    }
+
+    #[test]
+    fn test_filter_reachable() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let mut robot = abb_1660();
+        // Limits that leave only the branch around the joints below
+        let center: [f64; 6] = [10.0, 20.0, 30.0, 40.0, 25.0, 60.0];
+        robot.joint_limits =
+            Some(center.map(|x| ((x - 45.0).to_radians(), (x + 45.0).to_radians())));
+        let reachable = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        // J5 at zero aligns J4 and J6
+        let singular = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 0.0, 60.0], None)
+            .unwrap();
+        let unreachable = ([9.0, 9.0, 9.0], identity);
+        Python::initialize();
+        Python::attach(|py| {
+            let (kept, removed) = robot
+                .filter_reachable(
+                    py,
+                    PoseTable(vec![unreachable, reachable, singular, reachable]),
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(kept, vec![reachable, reachable]);
+            assert_eq!(removed, 2);
+        });
+    }
+
+    #[test]
+    fn test_limit_margin_columns() {
+        let mut robot = abb_1660();
+        let pose = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        Python::initialize();
+        Python::attach(|py| {
+            let poses = || PoseTable(vec![pose]);
+            let solve = |robot: &Robot, margins: bool, warning: Option<f64>| {
+                robot
+                    .solve_and_join(
+                        py,
+                        poses(),
+                        Some([10.0, 20.0, 30.0, 40.0, 50.0, 60.0]),
+                        SolutionOrder::Distance,
+                        None,
+                        InvalidPolicy::Null,
+                        margins,
+                        warning,
+                        false,
+                        false,
+                    )
+                    .and_then(|rows| rows.extract::<Vec<Bound<'_, PyDict>>>())
+            };
+            assert!(solve(&robot, true, None).is_err());
+
+            robot.joint_limits = Some(
+                [(-170.0f64, 170.0f64); 6].map(|(min, max)| (min.to_radians(), max.to_radians())),
+            );
+            let rows = solve(&robot, true, Some(30.0)).unwrap();
+            let margin: f64 = rows[0]
+                .get_item("limit_margin_j3")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!((margin - 140.0).abs() < 1e-6);
+            let near: bool = rows[0]
+                .get_item("near_limit")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(!near);
+            let rows = solve(&robot, false, Some(120.0)).unwrap();
+            assert!(rows[0].get_item("limit_margin_j1").unwrap().is_none());
+            let near: bool = rows[0]
+                .get_item("near_limit")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(near);
+        });
+    }
+
+    #[test]
+    fn test_multi_seed_inverse() {
+        let mut robot = abb_1660();
+        let limits = [180.0, 150.0, 80.0, 400.0, 120.0, 400.0];
+        robot.joint_limits =
+            Some(limits.map(|limit: f64| (-limit.to_radians(), limit.to_radians())));
+        let pose = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        Python::initialize();
+        Python::attach(|py| {
+            let inverse = |seeds| -> Vec<[f64; 6]> {
+                let solutions = robot.inverse(
+                    py,
+                    pose,
+                    None,
+                    SolutionOrder::Distance,
+                    None,
+                    true,
+                    None,
+                    false,
+                    seeds,
+                    false,
+                    None,
+                    false,
+                    None,
+                    false,
+                );
+                solutions
+                    .unwrap()
+                    .iter()
+                    .map(|joints| joints.extract().unwrap())
+                    .collect()
+            };
+            let single = inverse(None);
+            let spread = inverse(Some(Seeds::Auto));
+            // J4 and J6 reach a turn further either way, seeds across their range find those postures
+            assert!(spread.len() > single.len());
+            assert_eq!(spread[0], single[0]);
+            let given = inverse(Some(Seeds::Given(vec![[
+                10.0, 20.0, 30.0, 400.0, 50.0, 60.0,
+            ]])));
+            assert!(given.iter().any(|joints| (joints[3] - 400.0).abs() < 1e-6));
+            for joints in &spread {
+                let (translation, _) = robot.forward(*joints, None).unwrap();
+                assert!(translation
+                    .iter()
+                    .zip(pose.0)
+                    .all(|(a, b)| (a - b).abs() < 1e-9));
+            }
+        });
+    }
+
+    #[test]
+    fn test_inverse_with_turns() {
+        let mut robot = abb_1660();
+        let limits = [180.0, 150.0, 80.0, 400.0, 120.0, 400.0];
+        robot.joint_limits =
+            Some(limits.map(|limit: f64| (-limit.to_radians(), limit.to_radians())));
+        let pose = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        Python::initialize();
+        Python::attach(|py| {
+            let inverse = |turns| -> Vec<[f64; 6]> {
+                let solutions = robot.inverse(
+                    py,
+                    pose,
+                    None,
+                    SolutionOrder::Distance,
+                    None,
+                    true,
+                    None,
+                    false,
+                    None,
+                    false,
+                    Some(turns),
+                    false,
+                    None,
+                    false,
+                );
+                solutions
+                    .unwrap()
+                    .iter()
+                    .map(|joints| joints.extract().unwrap())
+                    .collect()
+            };
+            let turned = inverse([0, 1, -1]);
+            assert!(!turned.is_empty());
+            assert!(
+                (turned[0][3] - 400.0).abs() < 1e-6 && (turned[0][5] - (60.0 - 360.0)).abs() < 1e-6
+            );
+            for joints in &turned {
+                assert!(
+                    (180.0..540.0).contains(&joints[3]) && (-540.0..-180.0).contains(&joints[5])
+                );
+                let (translation, _) = robot.forward(*joints, None).unwrap();
+                assert!(translation
+                    .iter()
+                    .zip(pose.0)
+                    .all(|(a, b)| (a - b).abs() < 1e-9));
+            }
+            // Two turns of J4 lie beyond its limits
+            assert!(inverse([0, 2, 0]).is_empty());
+        });
+    }
+
+    #[test]
+    fn test_comfort_order() {
+        let mut robot = abb_1660();
+        let limits = [180.0, 150.0, 80.0, 400.0, 120.0, 400.0];
+        robot.joint_limits =
+            Some(limits.map(|limit: f64| (-limit.to_radians(), limit.to_radians())));
+        let pose = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        let seed = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let solve = |order_by| {
+            robot.solve(
+                pose,
+                Some(seed),
+                &robot.inverse_options(order_by, None, true, None),
+            )
+        };
+        let by_distance = solve(SolutionOrder::Distance);
+        let by_comfort = solve(SolutionOrder::Comfort);
+        assert_eq!(by_comfort.len(), by_distance.len());
+        let totals: Vec<f64> = by_comfort
+            .iter()
+            .map(|joints| robot.comfort_score(&robot.joints_in(*joints, None)).total)
+            .collect();
+        assert!(totals.windows(2).all(|pair| pair[0] <= pair[1]));
+        Python::initialize();
+        Python::attach(|py| {
+            let rows = robot
+                .inverse(
+                    py,
+                    pose,
+                    Some(seed),
+                    SolutionOrder::Comfort,
+                    None,
+                    true,
+                    None,
+                    false,
+                    None,
+                    false,
+                    None,
+                    false,
+                    None,
+                    true,
+                )
+                .unwrap();
+            let first = rows[0].cast::<PyDict>().unwrap();
+            let joints: [f64; 6] = first
+                .get_item("joints")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            let comfort: f64 = first
+                .get_item("comfort")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(joints, by_comfort[0]);
+            assert_eq!(comfort, totals[0]);
+        });
+    }
+
+    #[test]
+    fn test_why_unreachable() {
+        let mut robot = abb_1660();
+        Python::initialize();
+        Python::attach(|py| {
+            let reason = |robot: &Robot, pose: PyPose| -> Bound<'_, PyDict> {
+                robot.why_unreachable(py, pose, None, 4, None).unwrap()
+            };
+            fn get<'py>(dict: &Bound<'py, PyDict>, key: &str) -> Bound<'py, PyAny> {
+                dict.get_item(key).unwrap().unwrap()
+            }
+
+            let pose = robot
+                .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+                .unwrap();
+            let reachable = reason(&robot, pose);
+            assert_eq!(
+                get(&reachable, "reason").extract::<String>().unwrap(),
+                "reachable"
+            );
+            assert_eq!(get(&reachable, "distance").extract::<f64>().unwrap(), 0.0);
+            assert!(get(&reachable, "limit_excess").is_none());
+
+            let far = reason(&robot, ([3.0, 0.0, 0.8], [0.0, 1.0, 0.0, 0.0]));
+            assert_eq!(
+                get(&far, "reason").extract::<String>().unwrap(),
+                "outside_reach"
+            );
+            let distance: f64 = get(&far, "distance").extract().unwrap();
+            let max_reach: f64 = get(&far, "max_reach").extract().unwrap();
+            let wrist_reach: f64 = get(&far, "wrist_reach").extract().unwrap();
+            assert!(distance > 0.5 && (wrist_reach - max_reach - distance).abs() < 1e-9);
+            assert_eq!(
+                get(&far, "reachable_orientations")
+                    .extract::<usize>()
+                    .unwrap(),
+                0
+            );
+
+            // A wall on J1 blocks the pose at every orientation of the tool
+            robot.joint_limits = Some([
+                (-0.1, 0.1),
+                (-PI, PI),
+                (-PI, PI),
+                (-PI, PI),
+                (-PI, PI),
+                (-PI, PI),
+            ]);
+            let blocked = reason(&robot, pose);
+            assert_eq!(
+                get(&blocked, "reason").extract::<String>().unwrap(),
+                "joint_limits"
+            );
+            assert_eq!(
+                get(&blocked, "within_limits").extract::<usize>().unwrap(),
+                0
+            );
+            assert_eq!(
+                get(&blocked, "blocking_joints")
+                    .extract::<Vec<String>>()
+                    .unwrap(),
+                ["j1"]
+            );
+            let excess: [f64; 6] = get(&blocked, "limit_excess").extract().unwrap();
+            assert!((excess[0] - (10.0 - 0.1_f64.to_degrees())).abs() < 1e-6);
+        });
+    }
+
+    #[test]
+    fn test_project_to_workspace() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let robot = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.0, 0.0, 1.0], identity),
+            ToolConfig::new([0.0, 0.0, 0.1], identity),
+        )
+        .unwrap();
+        Python::initialize();
+        Python::attach(|py| {
+            fn get<'py, T: for<'a> FromPyObject<'a, 'py>>(
+                dict: &Bound<'py, PyDict>,
+                key: &str,
+            ) -> T {
+                dict.get_item(key).unwrap().unwrap().extract().ok().unwrap()
+            }
+            let project = |pose: PyPose, relax: bool| {
+                robot
+                    .project_to_workspace(py, pose, None, relax, None, 8, 1e-5, None)
+                    .unwrap()
+            };
+
+            let pose = robot
+                .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+                .unwrap();
+            let inside = project(pose, false);
+            assert!(!get::<bool>(&inside, "moved"));
+            assert_eq!(get::<PyPose>(&inside, "pose"), pose);
+
+            let far = ([3.0, 0.5, 1.8], [0.0, 1.0, 0.0, 0.0]);
+            let projected = project(far, false);
+            assert!(get::<bool>(&projected, "moved"));
+            let (translation, rotation) = get::<PyPose>(&projected, "pose");
+            assert_eq!(rotation, far.1);
+            let distance: f64 = get(&projected, "distance");
+            let moved = translation
+                .iter()
+                .zip(far.0)
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f64>();
+            assert!(distance > 1.0 && (moved.sqrt() - distance).abs() < 1e-9);
+            let joints: [f64; 6] = get(&projected, "joints");
+            let (reached, _) = robot.forward(joints, None).unwrap();
+            assert!(reached
+                .iter()
+                .zip(translation)
+                .all(|(a, b)| (a - b).abs() < 1e-9));
+            // Just short of the projection the pose is still out of reach
+            let short: Vec<f64> = translation
+                .iter()
+                .zip(far.0)
+                .map(|(a, b)| a + (b - a) * 1e-3 / distance)
+                .collect();
+            let short = to_isometry([short[0], short[1], short[2]], rotation);
+            assert!(!robot.reaches(&short));
+
+            // Tilting the tool lets the position move less
+            let relaxed = project(far, true);
+            assert!(get::<f64>(&relaxed, "distance") <= distance);
+            assert!(get::<f64>(&relaxed, "rotation") > 0.0);
+
+            // 5 mm beyond the edge is fixed, the far pose only flagged
+            let slightly: Vec<f64> = translation
+                .iter()
+                .zip(far.0)
+                .map(|(a, b)| a + (b - a) * 5e-3 / distance)
+                .collect();
+            let slightly = ([slightly[0], slightly[1], slightly[2]], rotation);
+            let invalid = ([f64::NAN, 0.0, 0.0], rotation);
+            let poses = PoseTable(vec![pose, slightly, far, invalid]);
+            let rows = robot
+                .batch_project_to_workspace(
+                    py,
+                    poses,
+                    Some([0.01; 3]),
+                    None,
+                    false,
+                    None,
+                    8,
+                    1e-5,
+                    None,
+                    InvalidPolicy::Null,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            let status: Vec<String> = rows.iter().map(|row| get(row, "status")).collect();
+            assert_eq!(status, ["reachable", "projected", "too_far", "invalid"]);
+            assert!((get::<f64>(&rows[1], "distance") - 5e-3).abs() < 1e-4);
+            assert_eq!(get::<f64>(&rows[2], "x"), far.0[0]);
+            assert!(rows[3].get_item("dx").unwrap().unwrap().is_none());
+        });
+    }
+
+    #[test]
+    fn test_stationary_tool_poses() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let mut remote = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.0; 3], identity),
+            ToolConfig::new([0.0, 0.0, 0.1], identity),
+        )
+        .unwrap();
+        let tool = ToolConfig::new([1.2, 0.3, 0.9], [0.0, 0.0, 1.0, 0.0]);
+        remote.stationary_tool = Some(tool.clone());
+        let nominal = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.0; 3], identity),
+            ToolConfig::new([0.0, 0.0, 0.1], identity),
+        )
+        .unwrap();
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let pose = remote.forward(joints, None).unwrap();
+        let close = |a: &Isometry3<f64>, b: &Isometry3<f64>| {
+            let (position, rotation) = pose::pose_errors(a, b);
+            position < 1e-6 && rotation < 1e-6
+        };
+        Python::initialize();
+        Python::attach(|py| {
+            fn get<'py, T: for<'a> FromPyObject<'a, 'py>>(
+                dict: &Bound<'py, PyDict>,
+                key: &str,
+            ) -> T {
+                dict.get_item(key).unwrap().unwrap().extract().ok().unwrap()
+            }
+
+            // Jogging moves the reported pose, here 1 mm along its own z axis
+            let step = [0.0, 0.0, 0.001, 0.0, 0.0, 0.0];
+            let jogged = remote
+                .jog(py, joints, step, JogFrame::Tool, None, None)
+                .unwrap();
+            let reached = remote.forward(jogged, None).unwrap();
+            let expected = to_isometry(pose.0, pose.1) * Isometry3::translation(0.0, 0.0, 0.001);
+            assert!(close(&to_isometry(reached.0, reached.1), &expected));
+
+            // The wrist reach is that of the world TCP the pose puts the robot at
+            let world_tcp = nominal.forward(joints, None).unwrap();
+            let reason = remote.why_unreachable(py, pose, None, 4, None).unwrap();
+            let expected = nominal
+                .why_unreachable(py, world_tcp, None, 4, None)
+                .unwrap();
+            assert_eq!(get::<String>(&reason, "reason"), "reachable");
+            let (center, expected): ([f64; 3], [f64; 3]) =
+                (get(&reason, "wrist_center"), get(&expected, "wrist_center"));
+            assert!(center
+                .iter()
+                .zip(expected)
+                .all(|(a, b)| (a - b).abs() < 1e-9));
+
+            // Projections are poses of the part frame that the robot reaches
+            let project = |pose: PyPose| {
+                remote
+                    .project_to_workspace(py, pose, None, false, None, 8, 1e-5, None)
+                    .unwrap()
+            };
+            assert!(!get::<bool>(&project(pose), "moved"));
+            let far = ([pose.0[0] + 3.0, pose.0[1], pose.0[2]], pose.1);
+            let projected = project(far);
+            assert!(get::<bool>(&projected, "moved"));
+            let target: PyPose = get(&projected, "pose");
+            let reached = remote.forward(get(&projected, "joints"), None).unwrap();
+            assert!(close(
+                &to_isometry(reached.0, reached.1),
+                &to_isometry(target.0, target.1)
+            ));
+            let rows = remote
+                .batch_project_to_workspace(
+                    py,
+                    PoseTable(vec![pose, far]),
+                    None,
+                    None,
+                    false,
+                    None,
+                    8,
+                    1e-5,
+                    None,
+                    InvalidPolicy::Null,
+                    None,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            let status: Vec<String> = rows.iter().map(|row| get(row, "status")).collect();
+            assert_eq!(status, ["reachable", "projected"]);
+
+            // Joints that reach the reference deviate by nothing
+            let deviations = remote
+                .cartesian_deviation(
+                    py,
+                    JointTable(vec![joints]),
+                    PoseTable(vec![pose]),
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            let deviation = deviations[0].as_ref().unwrap();
+            assert!(get::<f64>(deviation, "translation_error") < 1e-9);
+            assert!(get::<f64>(deviation, "rotation_error") < 1e-6);
+
+            // The reported pose is the stationary tool in the part frame
+            let frames = remote.frames(Some(joints), None).unwrap();
+            let between = frames.between("tool", "part").unwrap();
+            assert!(close(&between, &to_isometry(pose.0, pose.1)));
+            assert_eq!(
+                frames.between("tool", "world").unwrap(),
+                to_isometry(tool.translation, tool.rotation)
+            );
+
+            let twist = remote.forward_twist(joints, None).unwrap();
+            assert!(close(&twist::exp(&twist), &to_isometry(pose.0, pose.1)));
+        });
+    }
+
+    #[test]
+    fn test_optimize_positioner() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let mut robot = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.0, 0.0, 0.0], identity),
+            ToolConfig::new([0.0, 0.0, 0.1], identity),
+        )
+        .unwrap();
+        robot.joint_limits = Some([
+            (-PI, PI),
+            (-1.5, 2.5),
+            (-PI, 1.3),
+            (-PI, PI),
+            (-2.0, 2.0),
+            (-PI, PI),
+        ]);
+        // A tilting table 1.2 m in front of the robot
+        let positioner = Positioner {
+            base: Isometry3::translation(1.2, 0.0, 0.6),
+            axes: vec![(Vector3::zeros(), Vector3::x_axis())],
+            limits: vec![(-PI / 2.0, PI / 2.0)],
+            radians: false,
+        };
+        Python::initialize();
+        Python::attach(|py| {
+            fn get<'py, T: for<'a> FromPyObject<'a, 'py>>(
+                dict: &Bound<'py, PyDict>,
+                key: &str,
+            ) -> T {
+                dict.get_item(key).unwrap().unwrap().extract().ok().unwrap()
+            }
+            let positioner = Bound::new(py, positioner).unwrap();
+            // A seam on a side face of the part, welded along part -y: flat only with the
+            // table tilted by 90 degrees
+            let down_y = [0.5f64.sqrt(), 0.5f64.sqrt(), 0.0, 0.0];
+            let path: Vec<PyPose> = (0..5)
+                .map(|i| ([0.0, 0.1, 0.02 * i as f64], down_y))
+                .collect();
+            let rows = robot
+                .optimize_positioner(
+                    py,
+                    positioner.borrow(),
+                    PoseTable(path.clone()),
+                    1.0,
+                    1.0,
+                    0.1,
+                    7,
+                    [0.0, 0.0, -1.0],
+                    None,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(rows.len(), 5);
+            for (row, pose) in rows.iter().zip(&path) {
+                let tilted: f64 = get(row, "p1");
+                assert!((tilted.abs() - 90.0).abs() < 1e-9);
+                assert!(get::<f64>(row, "tilt") < 1e-6);
+                assert!(get::<f64>(row, "limit_margin") > 0.0);
+                let joints: [f64; 6] = JOINT_COLUMNS.map(|column| get(row, column));
+                let world = positioner.get().transform(&[tilted.to_radians()])
+                    * to_isometry(pose.0, pose.1);
+                let (reached, _) = robot.forward(joints, None).unwrap();
+                assert!((Vector3::from(reached) - world.translation.vector).norm() < 1e-9);
+            }
+            let p1: Vec<f64> = rows.iter().map(|row| get(row, "p1")).collect();
+            assert!(p1.iter().all(|p| *p == p1[0]));
+
+            let far = PoseTable(vec![([5.0, 0.0, 0.0], identity)]);
+            assert!(robot
+                .optimize_positioner(
+                    py,
+                    positioner.borrow(),
+                    far,
+                    1.0,
+                    1.0,
+                    0.1,
+                    7,
+                    [0.0, 0.0, -1.0],
+                    None,
+                    None
+                )
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_batch_inverse_counts() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let mut robot = abb_1660();
+        let pose = robot
+            .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+            .unwrap();
+        let seed = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0].map(f64::to_radians);
+        let options = InverseOptions {
+            order_by: SolutionOrder::Configuration,
+            ..InverseOptions::default()
+        };
+        let (all, n_solutions, index) = robot.solve_counted(pose, Some(seed), &options);
+        assert_eq!((all.len(), index), (n_solutions, Some(0)));
+
+        robot.solution_policy = SolutionPolicy::Closest;
+        let (closest, count, index) = robot.solve_counted(pose, Some(seed), &options);
+        assert_eq!(count, n_solutions);
+        assert_eq!(closest, vec![all[index.unwrap()]]);
+
+        let unreachable = ([9.0, 9.0, 9.0], identity);
+        assert_eq!(robot.solve_counted(unreachable, None, &options).1, 0);
+        assert_eq!(robot.solve_counted(unreachable, None, &options).2, None);
+    }
+
+    #[test]
+    fn test_non_finite_inputs() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let robot = abb_1660();
+        let nan_joints = [0.0, f64::NAN, 0.0, 0.0, 0.0, 0.0];
+        let nan_pose = ([f64::INFINITY, 0.0, 1.0], identity);
+        Python::initialize();
+        assert!(robot.forward(nan_joints, None).is_err());
+        assert!(robot.axis_configuration(nan_joints, None).is_err());
+
+        let row = robot.solve_row(nan_pose, None, &InverseOptions::default());
+        assert_eq!(row.status(), "invalid");
+        Python::attach(|py| {
+            assert!(robot
+                .inverse(
+                    py,
+                    nan_pose,
+                    None,
+                    SolutionOrder::Distance,
+                    None,
+                    true,
+                    None,
+                    false,
+                    None,
+                    false,
+                    None,
+                    false,
+                    None,
+                    false
+                )
+                .is_err());
+            let joints = vec![[0.0; 6], nan_joints];
+            let poses = |policy| {
+                robot
+                    .batch_forward(
+                        py,
+                        JointTable(joints.clone()),
+                        None,
+                        None,
+                        None,
+                        policy,
+                        None,
+                        None,
+                        None,
+                        false,
+                        false,
+                        None,
+                        false,
+                        false,
+                    )
+                    .and_then(|rows| rows.extract::<Vec<Bound<'_, PyAny>>>())
+            };
+            assert!(poses(InvalidPolicy::Null).unwrap()[1].is_none());
+            assert_eq!(poses(InvalidPolicy::Skip).unwrap().len(), 1);
+            assert!(poses(InvalidPolicy::Raise).is_err());
+        });
+    }
+
+    #[test]
+    fn test_strict_inverse() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let robot = abb_1660();
+        let unreachable = ([9.0, 9.0, 9.0], identity);
+        Python::initialize();
+        Python::attach(|py| {
+            let inverse = |pose, strict| {
+                robot.inverse(
+                    py,
+                    pose,
+                    None,
+                    SolutionOrder::Distance,
+                    None,
+                    true,
+                    None,
+                    strict,
+                    None,
+                    false,
+                    None,
+                    false,
+                    None,
+                    false,
+                )
+            };
+            let complete = |pose| robot.inverse_branches(py, pose, None, None, true, false, false);
+            assert!(inverse(unreachable, false).unwrap().is_empty());
+            let error = inverse(unreachable, true).unwrap_err();
+            assert!(error.is_instance_of::<UnreachablePoseError>(py));
+            let distance: f64 = error
+                .value(py)
+                .getattr("distance")
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(distance > 10.0);
+
+            let pose = robot
+                .forward([10.0, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+                .unwrap();
+            assert!(!inverse(pose, true).unwrap().is_empty());
+            assert!(complete(unreachable).is_err());
+            assert_eq!(complete(pose).unwrap().len(), 8);
+        });
+    }
+
+    #[test]
+    fn test_tool_relative_inverse() {
+        let robot = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.5, -0.2, 0.1], [0.9238795, 0.0, 0.0, 0.3826834]),
+            ToolConfig::new([0.0, 0.05, 0.2], [0.6, 0.8, 0.0, 0.0]),
+        )
+        .unwrap();
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        // 2 cm along the tool axis, turned 10 degrees about it
+        let half = 5.0_f64.to_radians();
+        let offset = ([0.0, 0.0, 0.02], [half.cos(), 0.0, 0.0, half.sin()]);
+        Python::initialize();
+        Python::attach(|py| {
+            let inverse = |current_joints| {
+                robot.inverse(
+                    py,
+                    offset,
+                    current_joints,
+                    SolutionOrder::Distance,
+                    None,
+                    true,
+                    None,
+                    false,
+                    None,
+                    true,
+                    None,
+                    false,
+                    None,
+                    false,
+                )
+            };
+            assert!(inverse(None).is_err());
+            let solution: [f64; 6] = inverse(Some(joints)).unwrap()[0].extract().unwrap();
+            let (translation, rotation) = robot.forward(joints, None).unwrap();
+            let tcp = to_isometry(translation, rotation);
+            let (translation, rotation) = robot.forward(solution, None).unwrap();
+            let shifted = tcp.inverse() * to_isometry(translation, rotation);
+            assert!(
+                (shifted.translation.vector - nalgebra::Vector3::new(0.0, 0.0, 0.02)).norm() < 1e-9
+            );
+            assert!((shifted.rotation.angle() - 10.0_f64.to_radians()).abs() < 1e-9);
+            assert!(shifted.rotation.axis().unwrap().z > 0.999_999);
+        });
+    }
+
+    #[test]
+    fn test_frame_transforms() {
+        let robot = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.5, -0.2, 0.1], [0.9238795, 0.0, 0.0, 0.3826834]),
+            ToolConfig::new([0.0, 0.05, 0.2], [0.6, 0.8, 0.0, 0.0]),
+        )
+        .unwrap();
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let tcp = robot.forward(joints, None).unwrap();
+        let flange = robot
+            ._kinematic_model
+            .to_opw_kinematics()
+            .forward(&joints.map(f64::to_radians));
+        Python::initialize();
+        Python::attach(|py| {
+            let to_base: PyPose = robot
+                .world_to_base(py, tcp, false)
+                .unwrap()
+                .extract()
+                .unwrap();
+            let in_base: PyPose = robot
+                .tcp_to_flange(py, to_base, false)
+                .unwrap()
+                .extract()
+                .unwrap();
+            let (position_error, rotation_error) =
+                pose::pose_errors(&to_isometry(in_base.0, in_base.1), &flange);
+            assert!(position_error < 1e-9 && rotation_error < 1e-9);
+
+            let back: PyPose = robot
+                .flange_to_tcp(py, in_base, false)
+                .unwrap()
+                .extract()
+                .unwrap();
+            let back: PyPose = robot
+                .base_to_world(py, back, false)
+                .unwrap()
+                .extract()
+                .unwrap();
+            for (a, b) in back.0.iter().zip(tcp.0.iter()) {
+                assert!((a - b).abs() < 1e-9);
+            }
+
+            let matrix: [[f64; 4]; 4] = robot.base_transform(py, true).unwrap().extract().unwrap();
+            assert_eq!([matrix[0][3], matrix[1][3], matrix[2][3]], [0.5, -0.2, 0.1]);
+            assert_eq!(matrix[3], [0.0, 0.0, 0.0, 1.0]);
+        });
+    }
+
+    #[test]
+    fn test_mounting_presets() {
+        let ceiling = BaseConfig::ceiling_mounted(2.3, 0.0, 0.0);
+        let mounted = BaseConfig::mounted([0.0, 0.0, 2.3], [0.0, 0.0, -1.0], [1.0, 0.0, 0.0]);
+        let mounted = mounted.unwrap();
+        let (_, rotation_error) = pose::pose_errors(
+            &to_isometry(ceiling.translation, ceiling.rotation),
+            &to_isometry(mounted.translation, mounted.rotation),
+        );
+        assert!(rotation_error < 1e-12);
+
+        let wall = BaseConfig::wall_mounted([0.0, 1.0, 1.5], [0.0, -1.0, 0.0]).unwrap();
+        let base = to_isometry(wall.translation, wall.rotation);
+        let z = base.rotation * nalgebra::Vector3::z();
+        let x = base.rotation * nalgebra::Vector3::x();
+        assert!((z - nalgebra::Vector3::new(0.0, -1.0, 0.0)).norm() < 1e-12);
+        assert!((x - nalgebra::Vector3::new(0.0, 0.0, -1.0)).norm() < 1e-12);
+        assert!(BaseConfig::wall_mounted([0.0; 3], [0.0, 0.0, 1.0]).is_err());
+    }
+
+    #[test]
+    fn test_track_conveyor() {
+        let robot = abb_1660();
+        let taught: Vec<PyPose> = (0..3)
+            .map(|i| {
+                robot
+                    .forward([2.0 * i as f64, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+                    .unwrap()
+            })
+            .collect();
+        Python::initialize();
+        Python::attach(|py| {
+            fn get<'py, T: for<'a> FromPyObject<'a, 'py>>(
+                dict: &Bound<'py, PyDict>,
+                key: &str,
+            ) -> T {
+                dict.get_item(key).unwrap().unwrap().extract().ok().unwrap()
+            }
+            let columns = |time_column: &str, times: &[f64]| {
+                let table = PyDict::new(py);
+                for (k, column) in ["x", "y", "z"].into_iter().enumerate() {
+                    let values: Vec<f64> = taught.iter().map(|pose| pose.0[k]).collect();
+                    table.set_item(column, values).unwrap();
+                }
+                for (k, column) in ["qw", "qx", "qy", "qz"].into_iter().enumerate() {
+                    let values: Vec<f64> = taught.iter().map(|pose| pose.1[k]).collect();
+                    table.set_item(column, values).unwrap();
+                }
+                table.set_item(time_column, times.to_vec()).unwrap();
+                table
+            };
+            let track = |poses, speed, counts_per_meter| {
+                robot.track_conveyor(
+                    py,
+                    poses,
+                    [2.0, 0.0, 0.0],
+                    speed,
+                    counts_per_meter,
+                    None,
+                    None,
+                    None,
+                    None,
+                    InvalidPolicy::Null,
+                )
+            };
+
+            // 0.1 m/s along x, the direction normalized
+            let timed = columns("timestamp", &[0.0, 0.1, 0.2]);
+            let rows = track(timed.as_any(), Some(0.1), None).unwrap();
+            for (i, (row, pose)) in rows.iter().zip(&taught).enumerate() {
+                let shift: f64 = get(row, "conveyor_shift");
+                assert!((shift - 0.01 * i as f64).abs() < 1e-12);
+                assert!((get::<f64>(row, "x") - pose.0[0] - shift).abs() < 1e-12);
+                assert_eq!(get::<f64>(row, "y"), pose.0[1]);
+                assert!(get::<f64>(row, "position_residual") < 1e-9);
+            }
+
+            // Encoder counts as rows, a missing count leaves its row invalid
+            let counted: Vec<Bound<'_, PyDict>> = taught
+                .iter()
+                .zip([Some(0.0), Some(20.0), None])
+                .map(|(pose, counts)| {
+                    let row = PyDict::new(py);
+                    for (column, value) in ["x", "y", "z"].into_iter().zip(pose.0) {
+                        row.set_item(column, value).unwrap();
+                    }
+                    for (column, value) in ["qw", "qx", "qy", "qz"].into_iter().zip(pose.1) {
+                        row.set_item(column, value).unwrap();
+                    }
+                    row.set_item("encoder_counts", counts).unwrap();
+                    row
+                })
+                .collect();
+            let counted = counted.into_pyobject(py).unwrap();
+            let rows = track(counted.as_any(), None, Some(1000.0)).unwrap();
+            assert!((get::<f64>(&rows[1], "conveyor_shift") - 0.02).abs() < 1e-12);
+            assert!(rows[2]
+                .get_item("conveyor_shift")
+                .unwrap()
+                .unwrap()
+                .is_none());
+            assert_eq!(get::<String>(&rows[2], "status"), "invalid");
+
+            let short = columns("t", &[0.0, 0.1]);
+            let error = track(short.as_any(), Some(0.1), None).unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("Got 2 conveyor positions for 3 poses"));
+            assert!(track(short.as_any(), None, Some(1000.0)).is_err());
+            assert!(track(timed.as_any(), Some(0.1), Some(1000.0)).is_err());
+        });
+    }
+
+    #[test]
+    fn test_order_targets() {
+        let robot = abb_1660();
+        let start = [0.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let mut poses: Vec<PyPose> = [40.0, 0.0, 20.0, 60.0, 10.0]
+            .into_iter()
+            .map(|j1| {
+                robot
+                    .forward([j1, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+                    .unwrap()
+            })
+            .collect();
+        poses.push(([3.0, 0.0, 0.8], [0.0, 1.0, 0.0, 0.0]));
+        Python::initialize();
+        Python::attach(|py| {
+            fn get<'py, T: for<'a> FromPyObject<'a, 'py>>(
+                dict: &Bound<'py, PyDict>,
+                key: &str,
+            ) -> T {
+                dict.get_item(key).unwrap().unwrap().extract().ok().unwrap()
+            }
+            let order = |method, return_to_start| {
+                robot
+                    .order_targets(
+                        py,
+                        PoseTable(poses.clone()),
+                        Some(Start::Joints(start)),
+                        method,
+                        PathCost::Travel,
+                        return_to_start,
+                        None,
+                    )
+                    .unwrap()
+            };
+
+            // Sweeping J1 once, the unreachable target left out
+            for method in [TourMethod::Greedy, TourMethod::TwoOpt] {
+                let tour = order(method, false);
+                assert_eq!(get::<Vec<usize>>(&tour, "order"), [1, 4, 2, 0, 3]);
+                assert_eq!(get::<Vec<usize>>(&tour, "unreachable"), [5]);
+                assert!((get::<f64>(&tour, "travel") - 60.0).abs() < 1e-6);
+                let joints: Vec<[f64; 6]> = get(&tour, "joints");
+                assert_eq!(joints.len(), 5);
+                assert!((joints[4][0] - 60.0).abs() < 1e-6);
+            }
+            let round_trip = order(TourMethod::TwoOpt, true);
+            assert!((get::<f64>(&round_trip, "travel") - 120.0).abs() < 1e-6);
+            assert_eq!(get::<Vec<[f64; 6]>>(&round_trip, "joints").len(), 5);
+        });
+    }
+
+    #[test]
+    fn test_solve_continuous() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let robot = abb_1660();
+        let mut poses: Vec<PyPose> = (0..5)
+            .map(|i| {
+                robot
+                    .forward([10.0 + 2.0 * i as f64, 20.0, 30.0, 40.0, 50.0, 60.0], None)
+                    .unwrap()
+            })
+            .collect();
+        // A pose out of reach in the middle of the trajectory and an invalid one at its end
+        poses[2] = ([3.0, 0.0, 0.8], [0.0, 1.0, 0.0, 0.0]);
+        poses.push(([f64::NAN, 0.0, 0.0], identity));
+        Python::initialize();
+        Python::attach(|py| {
+            fn get<'py, T: for<'a> FromPyObject<'a, 'py>>(
+                dict: &Bound<'py, PyDict>,
+                key: &str,
+            ) -> T {
+                dict.get_item(key).unwrap().unwrap().extract().ok().unwrap()
+            }
+            let solve = |recovery, max_step| {
+                robot
+                    .solve_continuous(
+                        py,
+                        PoseTable(poses.clone()),
+                        Some(Start::Joints([10.0, 20.0, 30.0, 40.0, 50.0, 60.0])),
+                        recovery,
+                        max_step,
+                        None,
+                        InvalidPolicy::Null,
+                    )
+                    .unwrap()
+            };
+
+            // The gap is interpolated between the rows around it
+            let rows = solve(None, None);
+            let recovered: Vec<Option<String>> =
+                rows.iter().map(|row| get(row, "recovery")).collect();
+            assert_eq!(
+                recovered,
+                [
+                    None,
+                    None,
+                    Some("interpolate".to_string()),
+                    None,
+                    None,
+                    None
+                ]
+            );
+            assert_eq!(get::<String>(&rows[2], "status"), "interpolated");
+            assert!((get::<f64>(&rows[2], "j1") - 14.0).abs() < 1e-6);
+            assert!(get::<f64>(&rows[2], "position_residual") > 1.0);
+            for row in [&rows[0], &rows[1], &rows[3], &rows[4]] {
+                assert!(get::<f64>(row, "position_residual") < 1e-9);
+            }
+            assert_eq!(get::<String>(&rows[5], "status"), "invalid");
+
+            // Without interpolation the gap stays unsolved, and steps beyond max_step are
+            // taken by the relaxed solver
+            let rows = solve(Some(vec![Recovery::Relaxed]), Some(1.0));
+            assert!(rows[2].get_item("j1").unwrap().unwrap().is_none());
+            let recovered: Vec<Option<String>> =
+                rows.iter().map(|row| get(row, "recovery")).collect();
+            assert_eq!(recovered[1].as_deref(), Some("relaxed"));
+            assert!((get::<f64>(&rows[1], "j1") - 12.0).abs() < 1e-6);
+        });
+    }
+
+    #[test]
+    fn test_validate_configurations() {
+        let robot = abb_1660();
+        let joints = [
+            [10.0, 20.0, 30.0, 40.0, 50.0, 60.0],
+            [-100.0, 10.0, -20.0, 120.0, -40.0, -170.0],
+            [170.0, -30.0, 40.0, -95.0, 20.0, 100.0],
+        ];
+        let flags: Vec<Vec<confdata::Flag>> = joints
+            .iter()
+            .map(|joint_set| {
+                let radians = joint_set.map(f64::to_radians);
+                confdata::flags(&robot._kinematic_model, &radians, None)
+            })
+            .collect();
+        Python::initialize();
+        Python::attach(|py| {
+            let table = PyDict::new(py);
+            for (k, column) in JOINT_COLUMNS.iter().enumerate() {
+                let values: Vec<f64> = joints.iter().map(|joint_set| joint_set[k]).collect();
+                table.set_item(column, values).unwrap();
+            }
+            for (k, column) in ["cf1", "cf4", "cf6", "cfx"].iter().enumerate() {
+                let values: Vec<Bound<PyAny>> = flags
+                    .iter()
+                    .map(|row| row[k].to_object(py).unwrap())
+                    .collect();
+                table.set_item(column, values).unwrap();
+            }
+            let rows = robot
+                .validate_configurations(py, &table, None, None)
+                .unwrap();
+            assert!(rows.is_empty());
+
+            // Only the row with the wrong configuration is reported
+            let confdata::Flag::Number(cfx) = flags[1][3] else {
+                panic!("cfx is a number");
+            };
+            let wrong = vec![
+                flags[0][3].to_object(py).unwrap(),
+                ((cfx + 1) % 8).into_pyobject(py).unwrap().into_any(),
+                py.None().into_bound(py),
+            ];
+            table.set_item("cfx", wrong).unwrap();
+            let rows = robot
+                .validate_configurations(py, &table, None, None)
+                .unwrap();
+            assert_eq!(rows.len(), 1);
+            let row: usize = rows[0].get_item("row").unwrap().unwrap().extract().unwrap();
+            assert_eq!(row, 1);
+            let mismatches: Vec<String> = rows[0]
+                .get_item("mismatches")
+                .unwrap()
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert_eq!(mismatches, ["CFX"]);
+
+            table.set_item("cf1", vec![0, 0]).unwrap();
+            let error = robot
+                .validate_configurations(py, &table, None, None)
+                .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("The CF1 column has 2 rows, the joints 3"));
+
+            for column in ["cf1", "cf4", "cf6", "cfx"] {
+                table.del_item(column).unwrap();
+            }
+            let error = robot
+                .validate_configurations(py, &table, None, None)
+                .unwrap_err();
+            assert!(error
+                .to_string()
+                .contains("The table has none of the configuration columns"));
+        });
+    }
 }
@@ -0,0 +1,157 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use pyo3::prelude::*;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+/// How long (ms) the effective level of the Python logger is cached before it is queried
+/// again
+const LEVEL_CACHE_MS: u64 = 1000;
+
+/// Marks a level that has not been queried yet
+const UNKNOWN: usize = usize::MAX;
+
+/// Forwards `log` records to Python's `logging`, under the "py_opw_kinematics" logger
+/// and its children (one per Rust module). The cached level is kept in atomics, so
+/// disabled records on worker threads neither lock nor take the GIL.
+struct PythonLogger {
+    /// The cached level as an index of `LevelFilter::iter()`
+    level: AtomicUsize,
+    /// When the level was queried, in ms since the logger was installed
+    queried: AtomicU64,
+}
+
+fn python_level(level: Level) -> u32 {
+    match level {
+        Level::Error => 40,
+        Level::Warn => 30,
+        Level::Info => 20,
+        Level::Debug => 10,
+        Level::Trace => 5,
+    }
+}
+
+fn level_filter(python_level: u32) -> LevelFilter {
+    match python_level {
+        0..=5 => LevelFilter::Trace,
+        6..=10 => LevelFilter::Debug,
+        11..=20 => LevelFilter::Info,
+        21..=30 => LevelFilter::Warn,
+        31..=40 => LevelFilter::Error,
+        _ => LevelFilter::Off,
+    }
+}
+
+/// "py_opw_kinematics::pose" -> "py_opw_kinematics.pose"
+fn logger_name(target: &str) -> String {
+    target.replace("::", ".")
+}
+
+impl PythonLogger {
+    /// The effective level of the Python logger, refreshed at most every LEVEL_CACHE_MS so
+    /// that disabled records do not take the GIL
+    fn level(&self) -> LevelFilter {
+        let now = START.get_or_init(Instant::now).elapsed().as_millis() as u64;
+        let cached = self.level.load(Ordering::Relaxed);
+        if cached != UNKNOWN
+            && now.saturating_sub(self.queried.load(Ordering::Relaxed)) < LEVEL_CACHE_MS
+        {
+            return LevelFilter::iter().nth(cached).unwrap_or(LevelFilter::Off);
+        }
+        let level = Python::attach(|py| -> PyResult<u32> {
+            py.import("logging")?
+                .call_method1("getLogger", (env!("CARGO_CRATE_NAME"),))?
+                .call_method0("getEffectiveLevel")?
+                .extract()
+        })
+        .map(level_filter)
+        .unwrap_or(LevelFilter::Off);
+        self.queried.store(now, Ordering::Relaxed);
+        self.level.store(level as usize, Ordering::Relaxed);
+        level
+    }
+}
+
+impl Log for PythonLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        Python::attach(|py| {
+            let logged = py
+                .import("logging")
+                .and_then(|logging| {
+                    logging.call_method1("getLogger", (logger_name(record.target()),))
+                })
+                .and_then(|logger| {
+                    logger.call_method1(
+                        "log",
+                        (python_level(record.level()), record.args().to_string()),
+                    )
+                });
+            // A failing handler must not turn a warning into an error of the caller
+            if let Err(err) = logged {
+                err.print(py);
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: PythonLogger = PythonLogger {
+    level: AtomicUsize::new(UNKNOWN),
+    queried: AtomicU64::new(0),
+};
+
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Installs the bridge, once per process. The level is controlled from Python with
+/// `logging.getLogger("py_opw_kinematics").setLevel(...)`.
+pub fn init() {
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Trace);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_mapping_round_trips() {
+        for level in [
+            Level::Error,
+            Level::Warn,
+            Level::Info,
+            Level::Debug,
+            Level::Trace,
+        ] {
+            assert_eq!(level_filter(python_level(level)), level.to_level_filter());
+            let cached = level.to_level_filter() as usize;
+            assert_eq!(
+                LevelFilter::iter().nth(cached),
+                Some(level.to_level_filter())
+            );
+        }
+        assert_eq!(level_filter(50), LevelFilter::Off);
+        assert_eq!(
+            logger_name("py_opw_kinematics::pose"),
+            "py_opw_kinematics.pose"
+        );
+    }
+
+    #[test]
+    fn test_level_queried_by_another_thread_is_cached() {
+        // Another thread may store a later query time than this thread's now
+        let logger = PythonLogger {
+            level: AtomicUsize::new(LevelFilter::Debug as usize),
+            queried: AtomicU64::new(u64::MAX),
+        };
+        assert_eq!(logger.level(), LevelFilter::Debug);
+    }
+}
@@ -0,0 +1,104 @@
+use nalgebra::{Rotation3, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::axis::Axis;
+use crate::pose::PyPose;
+use crate::validation;
+
+/// Rotation whose `axis` points along `direction` and whose next axis (x after z, y after
+/// x, z after y) points as close as possible to `up`. None if `direction` is zero or
+/// parallel to `up`.
+pub fn look_at_rotation(
+    direction: &Vector3<f64>,
+    up: &Vector3<f64>,
+    axis: Axis,
+) -> Option<UnitQuaternion<f64>> {
+    let pointing = direction.try_normalize(1e-12)?;
+    let upward = (up - pointing * up.dot(&pointing)).try_normalize(1e-9)?;
+    let mut basis = [Vector3::zeros(); 3];
+    basis[axis.index()] = pointing;
+    basis[axis.next().index()] = upward;
+    basis[axis.next().next().index()] = pointing.cross(&upward);
+    Some(UnitQuaternion::from_rotation_matrix(
+        &Rotation3::from_basis_unchecked(&basis),
+    ))
+}
+
+/// Pose at `position` looking at `target_point`, or an error naming the row
+fn pose(
+    position: [f64; 3],
+    target_point: [f64; 3],
+    up: &Vector3<f64>,
+    axis: Axis,
+) -> PyResult<PyPose> {
+    let finite = position.iter().chain(&target_point).all(|x| x.is_finite());
+    validation::check_finite("position, target_point", &(position, target_point), finite)?;
+    let direction = Vector3::from(target_point) - Vector3::from(position);
+    let rotation = look_at_rotation(&direction, up, axis).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "Cannot look from {:?} at {:?}: the points coincide or the view is parallel to up",
+            position, target_point
+        ))
+    })?;
+    Ok((position, [rotation.w, rotation.i, rotation.j, rotation.k]))
+}
+
+/// Pose at `position` whose `axis` (the tool axis by default) points at `target_point`,
+/// turned about it so that the next axis (x after z, y after x, z after y) points as close
+/// as possible to `up`, e.g. for camera or inspection targets
+#[pyfunction]
+#[pyo3(signature = (position, target_point, up=[0.0, 0.0, 1.0], axis=None))]
+pub fn look_at(
+    position: [f64; 3],
+    target_point: [f64; 3],
+    up: [f64; 3],
+    axis: Option<Axis>,
+) -> PyResult<PyPose> {
+    pose(position, target_point, &Vector3::from(up), Axis::tool(axis))
+}
+
+/// `look_at` for many positions, looking at one target point or at one per position
+#[pyfunction]
+#[pyo3(signature = (positions, target_points, up=[0.0, 0.0, 1.0], axis=None))]
+pub fn batch_look_at(
+    positions: Vec<[f64; 3]>,
+    target_points: Vec<[f64; 3]>,
+    up: [f64; 3],
+    axis: Option<Axis>,
+) -> PyResult<Vec<PyPose>> {
+    if target_points.len() != 1 && target_points.len() != positions.len() {
+        return Err(PyValueError::new_err(format!(
+            "Expected 1 or {} target points, got {}",
+            positions.len(),
+            target_points.len()
+        )));
+    }
+    let (up, axis) = (Vector3::from(up), Axis::tool(axis));
+    positions
+        .iter()
+        .zip(target_points.iter().cycle())
+        .map(|(position, target_point)| pose(*position, *target_point, &up, axis))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_look_at_rotation() {
+        let direction = Vector3::new(1.0, 1.0, 0.0);
+        let up = Vector3::z();
+        for axis in [Axis::X, Axis::Y, Axis::Z] {
+            let rotation = look_at_rotation(&direction, &up, axis)
+                .unwrap()
+                .to_rotation_matrix();
+            let column = |axis: Axis| rotation.matrix().column(axis.index()).into_owned();
+            assert!((column(axis) - direction.normalize()).norm() < 1e-12);
+            assert!((column(axis.next()) - up).norm() < 1e-12);
+        }
+        assert!(look_at_rotation(&up, &up, Axis::Z).is_none());
+        assert!(look_at_rotation(&Vector3::zeros(), &up, Axis::Z).is_none());
+    }
+}
@@ -0,0 +1,289 @@
+use nalgebra::Isometry3;
+use std::f64::consts::FRAC_PI_2;
+
+use crate::configuration::turn;
+use crate::frames::WORLD;
+use crate::program::{Motion, Target};
+use crate::rapid::{Identifiers, Module};
+
+/// User frames and tools a Fanuc controller numbers, besides the world and the faceplate (0)
+const USER_FRAMES: usize = 9;
+const USER_TOOLS: usize = 10;
+
+/// Fanuc configuration string of an ABB axis configuration: Flip or No flip from cfx bit 0,
+/// Up or Down from bit 1, Toward or Back from bit 2, and the turn numbers of the middle of
+/// the quadrants of J1, J4 and J6
+fn config((cf1, cf4, cf6, cfx): (i32, i32, i32, u8)) -> String {
+    let turn = |quadrant: i32| turn((quadrant as f64 + 0.5) * FRAC_PI_2);
+    format!(
+        "{} {} {}, {}, {}, {}",
+        if cfx & 1 == 0 { "N" } else { "F" },
+        if cfx & 2 == 0 { "U" } else { "D" },
+        if cfx & 4 == 0 { "T" } else { "B" },
+        turn(cf1),
+        turn(cf4),
+        turn(cf6),
+    )
+}
+
+/// The position of a pose: X, Y, Z in millimeters and W, P, R in degrees about the fixed x,
+/// y and z axes
+fn position(pose: &Isometry3<f64>) -> [f64; 6] {
+    let v = pose.translation.vector * 1000.0;
+    let (w, p, r) = pose.rotation.euler_angles();
+    [
+        v.x,
+        v.y,
+        v.z,
+        w.to_degrees(),
+        p.to_degrees(),
+        r.to_degrees(),
+    ]
+    .map(rounded)
+}
+
+/// `value` rounded to the three decimals written, without a negative zero
+fn rounded(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0 + 0.0
+}
+
+/// Number of each name in `names` from 1, `zero` being 0, or an error if there are more
+/// than `limit`
+fn numbers(names: &[&String], zero: &str, limit: usize, kind: &str) -> Result<Vec<String>, String> {
+    let numbered: Vec<String> = names
+        .iter()
+        .filter(|name| name.as_str() != zero)
+        .map(|name| name.to_string())
+        .collect();
+    match numbered.len() > limit {
+        true => Err(format!(
+            "The program uses {} {}s, a Fanuc controller has {}",
+            numbered.len(),
+            kind,
+            limit
+        )),
+        false => Ok(numbered),
+    }
+}
+
+/// Writes a Fanuc TP program as LS text. Frames other than the world become user frames 1..9
+/// and tools other than tool0 (the faceplate) user tools 1..10, numbered in the order of
+/// `module` and named in remarks, selected with UFRAME_NUM and UTOOL_NUM; their values
+/// are not part of a TP program and have to be set on the controller. Pose targets are
+/// positions in their user frame with the configuration of their ABB axis configuration
+/// (N U T, 0, 0, 0 if not taught), joint targets positions in joints. Linear moves take
+/// their speed in mm/sec (1000 if unset), joint moves run at 100%; moves with a zone end
+/// in CNT with the zone in millimeters as value, up to 100, the others in FINE.
+pub fn write(module: &Module) -> Result<String, String> {
+    let tools = numbers(
+        &module
+            .tools
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>(),
+        "tool0",
+        USER_TOOLS,
+        "tool",
+    )?;
+    let frames = numbers(
+        &module
+            .work_objects
+            .iter()
+            .map(|(name, _)| name)
+            .collect::<Vec<_>>(),
+        WORLD,
+        USER_FRAMES,
+        "frame",
+    )?;
+    let number = |numbered: &[String], name: &str| {
+        numbered
+            .iter()
+            .position(|numbered| numbered == name)
+            .map_or(0, |i| i + 1)
+    };
+    let mut lines: Vec<String> = Vec::new();
+    for (i, frame) in frames.iter().enumerate() {
+        lines.push(format!("  ! UFRAME {}: {:.20} ;", i + 1, frame));
+    }
+    for (i, tool) in tools.iter().enumerate() {
+        lines.push(format!("  ! UTOOL {}: {:.20} ;", i + 1, tool));
+    }
+    // A position is recorded once per target, frame and tool however often it is visited
+    let mut positions: Vec<(usize, usize, usize)> = Vec::new();
+    let (mut user_frame, mut user_tool) = (None, None);
+    for (i, waypoint) in module.waypoints.iter().enumerate() {
+        let frame = number(&frames, &waypoint.frame);
+        let tool = number(&tools, waypoint.tool.as_deref().unwrap_or("tool0"));
+        if user_frame != Some(frame) {
+            lines.push(format!("  UFRAME_NUM={} ;", frame));
+            user_frame = Some(frame);
+        }
+        if user_tool != Some(tool) {
+            lines.push(format!("  UTOOL_NUM={} ;", tool));
+            user_tool = Some(tool);
+        }
+        let same = |(k, f, t): &&(usize, usize, usize)| {
+            let other = &module.waypoints[*k];
+            (*f, *t) == (frame, tool)
+                && other.name == waypoint.name
+                && other.target == waypoint.target
+                && other.configuration == waypoint.configuration
+        };
+        let p = match positions.iter().position(|entry| same(&entry)) {
+            Some(p) => p + 1,
+            None => {
+                positions.push((i, frame, tool));
+                positions.len()
+            }
+        };
+        let linear = matches!(
+            (&waypoint.target, waypoint.motion),
+            (Target::Pose(_), Some(Motion::Linear))
+        );
+        let speed = match linear {
+            true => format!(
+                "{}mm/sec",
+                waypoint
+                    .speed
+                    .map_or(1000.0, |speed| (speed * 1000.0).round().max(1.0))
+            ),
+            false => "100%".to_string(),
+        };
+        let termination = match waypoint.zone.map(|zone| (zone * 1000.0).round()) {
+            Some(mm) if mm >= 1.0 => format!("CNT{}", mm.min(100.0)),
+            _ => "FINE".to_string(),
+        };
+        lines.push(format!(
+            "{}P[{}] {} {}    ;",
+            if linear { "L " } else { "J " },
+            p,
+            speed,
+            termination
+        ));
+    }
+
+    let mut identifiers = Identifiers::new(36, &[]);
+    let name = identifiers.add(&module.name, "main").to_ascii_uppercase();
+    let mut text = format!(
+        "/PROG  {}\n/ATTR\nOWNER\t\t= MNEDITOR;\nCOMMENT\t\t= \"\";\nPROG_SIZE\t= 0;\n\
+         FILE_NAME\t= ;\nVERSION\t\t= 0;\nLINE_COUNT\t= {};\nMEMORY_SIZE\t= 0;\n\
+         PROTECT\t\t= READ_WRITE;\nDEFAULT_GROUP\t= 1,*,*,*,*;\n\
+         CONTROL_CODE\t= 00000000 00000000;\n/MN\n",
+        name,
+        lines.len()
+    );
+    for (i, line) in lines.iter().enumerate() {
+        text.push_str(&format!("{:4}:{}\n", i + 1, line));
+    }
+    text.push_str("/POS\n");
+    for (p, (i, frame, tool)) in positions.iter().enumerate() {
+        let waypoint = &module.waypoints[*i];
+        let comment: String = waypoint
+            .name
+            .chars()
+            .filter(|c| *c != '"')
+            .take(16)
+            .collect();
+        text.push_str(&format!(
+            "P[{}:\"{}\"]{{\n   GP1:\n\tUF : {}, UT : {},",
+            p + 1,
+            comment,
+            frame,
+            tool
+        ));
+        match &waypoint.target {
+            Target::Pose(pose) => {
+                let [x, y, z, w, pitch, r] = position(pose);
+                let configuration = waypoint.configuration.unwrap_or((0, 0, 0, 0));
+                text.push_str(&format!(
+                    "\t\tCONFIG : '{}',\n\
+                     \tX = {:9.3}  mm,\tY = {:9.3}  mm,\tZ = {:9.3}  mm,\n\
+                     \tW = {:9.3} deg,\tP = {:9.3} deg,\tR = {:9.3} deg\n",
+                    config(configuration),
+                    x,
+                    y,
+                    z,
+                    w,
+                    pitch,
+                    r
+                ));
+            }
+            Target::Joints(joints) => {
+                let [j1, j2, j3, j4, j5, j6] = joints.map(|joint| rounded(joint.to_degrees()));
+                text.push_str(&format!(
+                    "\n\tJ1= {:9.3} deg,\tJ2= {:9.3} deg,\tJ3= {:9.3} deg,\n\
+                     \tJ4= {:9.3} deg,\tJ5= {:9.3} deg,\tJ6= {:9.3} deg\n",
+                    j1, j2, j3, j4, j5, j6
+                ));
+            }
+        }
+        text.push_str("};\n");
+    }
+    text.push_str("/END\n");
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::program::Waypoint;
+
+    #[test]
+    fn test_write_ls_program() {
+        let waypoint = |name: &str, target, frame: &str, motion, speed, zone| Waypoint {
+            name: name.to_string(),
+            target,
+            frame: frame.to_string(),
+            tool: Some("gun".to_string()),
+            configuration: Some((-1, 0, 2, 5)),
+            motion: Some(motion),
+            speed,
+            zone,
+        };
+        let pose = Target::Pose(Isometry3::translation(0.5, 0.0, 0.25));
+        let module = Module {
+            name: "weld seam".to_string(),
+            tools: vec![("gun".to_string(), Isometry3::identity())],
+            work_objects: vec![("table".to_string(), Isometry3::identity())],
+            waypoints: vec![
+                waypoint(
+                    "home",
+                    Target::Joints([0.0; 6]),
+                    WORLD,
+                    Motion::Joint,
+                    None,
+                    None,
+                ),
+                waypoint(
+                    "a",
+                    pose.clone(),
+                    "table",
+                    Motion::Linear,
+                    Some(0.25),
+                    Some(0.01),
+                ),
+                waypoint("a", pose, "table", Motion::Linear, None, Some(0.0)),
+            ],
+        };
+        let text = write(&module).unwrap();
+        assert!(text.starts_with("/PROG  WELD_SEAM\n"));
+        assert!(text.contains("LINE_COUNT\t= 8;\n"));
+        assert!(text.contains("   1:  ! UFRAME 1: table ;\n   2:  ! UTOOL 1: gun ;\n"));
+        assert!(text.contains("   5:J P[1] 100% FINE    ;\n"));
+        assert!(text.contains("   6:  UFRAME_NUM=1 ;\n   7:L P[2] 250mm/sec CNT10    ;\n"));
+        // The second visit of a reuses its position
+        assert!(text.contains("   8:L P[2] 1000mm/sec FINE    ;\n"));
+        assert!(!text.contains("P[3"));
+        assert!(text.contains("\tUF : 1, UT : 1,\t\tCONFIG : 'F U B, 0, 0, 1',\n"));
+        assert!(text.contains("\tX =   500.000  mm,"));
+        assert!(text.ends_with("};\n/END\n"));
+
+        let many = Module {
+            work_objects: (0..10)
+                .map(|i| (format!("f{}", i), Isometry3::identity()))
+                .collect(),
+            ..module
+        };
+        assert!(write(&many).is_err());
+    }
+}
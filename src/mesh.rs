@@ -0,0 +1,374 @@
+use nalgebra::{Isometry3, Point3, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::f64::consts::{FRAC_1_SQRT_2, PI, TAU};
+use std::path::Path;
+
+use crate::kinematic_model::KinematicModel;
+use crate::skeleton;
+
+/// Sides of the cylinders of a posed robot
+const CYLINDER_SIDES: usize = 16;
+
+/// A triangle mesh, triangles wind counterclockwise seen from outside
+#[derive(Clone, Debug, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Point3<f64>>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Mesh {
+    pub fn append(&mut self, other: Mesh) {
+        let offset = self.vertices.len() as u32;
+        self.vertices.extend(other.vertices);
+        self.triangles.extend(
+            other
+                .triangles
+                .iter()
+                .map(|triangle| triangle.map(|i| i + offset)),
+        );
+    }
+
+    /// The mesh moved by `transform`, then scaled about the origin (e.g. 1000 for mm)
+    pub fn transformed(mut self, transform: &Isometry3<f64>, scale: f64) -> Mesh {
+        for vertex in &mut self.vertices {
+            *vertex = Point3::from((transform * *vertex).coords * scale);
+        }
+        self
+    }
+
+    fn corners(&self, triangle: &[u32; 3]) -> [Point3<f64>; 3] {
+        triangle.map(|i| self.vertices[i as usize])
+    }
+
+    /// Writes the mesh as binary STL, ASCII PLY, glTF with an embedded buffer or binary
+    /// glTF, by the extension of `path`
+    pub fn write(&self, path: &str) -> PyResult<()> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        let bytes = match extension.as_deref() {
+            Some("stl") => self.stl(),
+            Some("ply") => self.ply().into_bytes(),
+            Some("gltf") => {
+                let buffer = self.gltf_buffer();
+                let uri = format!("data:application/octet-stream;base64,{}", base64(&buffer));
+                self.gltf_json(buffer.len(), Some(&uri)).into_bytes()
+            }
+            Some("glb") => self.glb(),
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown mesh format of '{}', expected .stl, .ply, .gltf or .glb",
+                    path
+                )))
+            }
+        };
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    fn stl(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; 80];
+        bytes.extend((self.triangles.len() as u32).to_le_bytes());
+        for triangle in &self.triangles {
+            let [a, b, c] = self.corners(triangle);
+            let normal = (b - a)
+                .cross(&(c - a))
+                .try_normalize(0.0)
+                .unwrap_or_default();
+            for value in normal
+                .iter()
+                .chain(a.iter())
+                .chain(b.iter())
+                .chain(c.iter())
+            {
+                bytes.extend((*value as f32).to_le_bytes());
+            }
+            bytes.extend([0, 0]);
+        }
+        bytes
+    }
+
+    fn ply(&self) -> String {
+        let mut text = format!(
+            "ply\nformat ascii 1.0\nelement vertex {}\nproperty float x\nproperty float y\n\
+             property float z\nelement face {}\nproperty list uchar int vertex_indices\nend_header\n",
+            self.vertices.len(),
+            self.triangles.len()
+        );
+        for vertex in &self.vertices {
+            text.push_str(&format!("{} {} {}\n", vertex.x, vertex.y, vertex.z));
+        }
+        for [a, b, c] in &self.triangles {
+            text.push_str(&format!("3 {} {} {}\n", a, b, c));
+        }
+        text
+    }
+
+    /// Positions as f32 followed by the u32 indices
+    fn gltf_buffer(&self) -> Vec<u8> {
+        let positions = self
+            .vertices
+            .iter()
+            .flat_map(|vertex| vertex.iter().map(|value| *value as f32).collect::<Vec<_>>());
+        let indices = self.triangles.iter().flatten();
+        positions
+            .flat_map(f32::to_le_bytes)
+            .chain(indices.flat_map(|index| index.to_le_bytes()))
+            .collect()
+    }
+
+    /// glTF document of the mesh, turned from z up to the y up of glTF
+    fn gltf_json(&self, buffer_length: usize, uri: Option<&str>) -> String {
+        let positions_length = self.vertices.len() * 12;
+        let (mut min, mut max) = ([f32::MAX; 3], [f32::MIN; 3]);
+        for vertex in &self.vertices {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(vertex[axis] as f32);
+                max[axis] = max[axis].max(vertex[axis] as f32);
+            }
+        }
+        let uri = uri.map_or(String::new(), |uri| format!(r#","uri":"{}""#, uri));
+        format!(
+            concat!(
+                r#"{{"asset":{{"version":"2.0","generator":"py-opw-kinematics"}},"scene":0,"#,
+                r#""scenes":[{{"nodes":[0]}}],"#,
+                r#""nodes":[{{"mesh":0,"rotation":[{r},0,0,{w}]}}],"#,
+                r#""meshes":[{{"primitives":[{{"attributes":{{"POSITION":0}},"indices":1,"mode":4}}]}}],"#,
+                r#""accessors":[{{"bufferView":0,"componentType":5126,"count":{vertices},"type":"VEC3","min":{min:?},"max":{max:?}}},"#,
+                r#"{{"bufferView":1,"componentType":5125,"count":{indices},"type":"SCALAR"}}],"#,
+                r#""bufferViews":[{{"buffer":0,"byteOffset":0,"byteLength":{positions},"target":34962}},"#,
+                r#"{{"buffer":0,"byteOffset":{positions},"byteLength":{index_bytes},"target":34963}}],"#,
+                r#""buffers":[{{"byteLength":{length}{uri}}}]}}"#
+            ),
+            r = -FRAC_1_SQRT_2,
+            w = FRAC_1_SQRT_2,
+            vertices = self.vertices.len(),
+            min = min,
+            max = max,
+            indices = self.triangles.len() * 3,
+            positions = positions_length,
+            index_bytes = self.triangles.len() * 12,
+            length = buffer_length,
+            uri = uri,
+        )
+    }
+
+    fn glb(&self) -> Vec<u8> {
+        let mut buffer = self.gltf_buffer();
+        let mut json = self.gltf_json(buffer.len(), None).into_bytes();
+        // Chunks are aligned to 4 bytes, JSON padded with spaces and binary with zeros
+        json.resize(json.len().next_multiple_of(4), b' ');
+        buffer.resize(buffer.len().next_multiple_of(4), 0);
+        let length = 12 + 8 + json.len() + 8 + buffer.len();
+        let mut bytes = Vec::with_capacity(length);
+        bytes.extend(b"glTF");
+        bytes.extend(2u32.to_le_bytes());
+        bytes.extend((length as u32).to_le_bytes());
+        bytes.extend((json.len() as u32).to_le_bytes());
+        bytes.extend(b"JSON");
+        bytes.extend(json);
+        bytes.extend((buffer.len() as u32).to_le_bytes());
+        bytes.extend(b"BIN\0");
+        bytes.extend(buffer);
+        bytes
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (i, byte)| {
+            value | (*byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            text.push(if i <= chunk.len() {
+                ALPHABET[(value >> (18 - 6 * i) & 63) as usize] as char
+            } else {
+                '='
+            });
+        }
+    }
+    text
+}
+
+/// Closed cylinder around the segment from `start` to `end`, empty for a zero length
+pub fn cylinder(start: Point3<f64>, end: Point3<f64>, radius: f64) -> Mesh {
+    let Some(axis) = (end - start).try_normalize(1e-12) else {
+        return Mesh::default();
+    };
+    let helper = if axis.x.abs() < 0.9 {
+        Vector3::x()
+    } else {
+        Vector3::y()
+    };
+    let u = axis.cross(&helper).normalize();
+    let v = axis.cross(&u);
+    let mut mesh = Mesh::default();
+    for center in [start, end] {
+        for k in 0..CYLINDER_SIDES {
+            let angle = TAU * k as f64 / CYLINDER_SIDES as f64;
+            mesh.vertices
+                .push(center + radius * (angle.cos() * u + angle.sin() * v));
+        }
+    }
+    mesh.vertices.extend([start, end]);
+    let n = CYLINDER_SIDES as u32;
+    let (start_center, end_center) = (2 * n, 2 * n + 1);
+    for k in 0..n {
+        let next = (k + 1) % n;
+        mesh.triangles.extend([
+            [k, next, n + next],
+            [k, n + next, n + k],
+            [start_center, next, k],
+            [end_center, n + k, n + next],
+        ]);
+    }
+    mesh
+}
+
+/// Outer envelope of the wrist center over `samples` values of each of the joints 1 to 3
+/// within `limits` (radians), in the robot base frame. The envelope is star-shaped around
+/// the shoulder height on the J1 axis: every direction of a `resolution` by
+/// 2 * `resolution` latitude/longitude grid takes the farthest reachable wrist center, and
+/// directions that nothing reaches collapse to the center.
+pub fn workspace_envelope(
+    model: &KinematicModel,
+    limits: &[(f64, f64); 3],
+    samples: usize,
+    resolution: usize,
+) -> Mesh {
+    let (rows, columns) = (resolution, 2 * resolution);
+    let center = Point3::new(0.0, 0.0, model.c1);
+    let mut radii = vec![0.0f64; rows * columns];
+    let steps = |(min, max): (f64, f64)| {
+        (0..samples).map(move |i| min + (max - min) * i as f64 / (samples - 1).max(1) as f64)
+    };
+    for j1 in steps(limits[0]) {
+        for j2 in steps(limits[1]) {
+            for j3 in steps(limits[2]) {
+                let joints = model.decouple(&[j1, j2, j3, 0.0, 0.0, 0.0]);
+                let offset = skeleton::arm_points(model, &joints)[3] - center;
+                let radius = offset.norm();
+                if radius < 1e-12 {
+                    continue;
+                }
+                let polar = (offset.z / radius).clamp(-1.0, 1.0).acos();
+                let azimuth = offset.y.atan2(offset.x) + PI;
+                let row = ((polar / PI * rows as f64) as usize).min(rows - 1);
+                let column = (azimuth / TAU * columns as f64) as usize % columns;
+                let bin = &mut radii[row * columns + column];
+                *bin = bin.max(radius);
+            }
+        }
+    }
+
+    let mut mesh = Mesh::default();
+    for row in 0..rows {
+        let polar = PI * (row as f64 + 0.5) / rows as f64;
+        for column in 0..columns {
+            let azimuth = TAU * (column as f64 + 0.5) / columns as f64 - PI;
+            let direction = Vector3::new(
+                polar.sin() * azimuth.cos(),
+                polar.sin() * azimuth.sin(),
+                polar.cos(),
+            );
+            mesh.vertices
+                .push(center + radii[row * columns + column] * direction);
+        }
+    }
+    // The poles take the mean radius of the nearest row
+    for (row, sign) in [(0, 1.0), (rows - 1, -1.0)] {
+        let mean = radii[row * columns..(row + 1) * columns]
+            .iter()
+            .sum::<f64>()
+            / columns as f64;
+        mesh.vertices.push(center + sign * mean * Vector3::z());
+    }
+    let index = |row: usize, column: usize| (row * columns + column % columns) as u32;
+    let (north, south) = ((rows * columns) as u32, (rows * columns + 1) as u32);
+    for column in 0..columns {
+        mesh.triangles
+            .push([north, index(0, column), index(0, column + 1)]);
+        mesh.triangles
+            .push([south, index(rows - 1, column + 1), index(rows - 1, column)]);
+        for row in 0..rows - 1 {
+            let (a, b) = (index(row, column), index(row + 1, column));
+            let (c, d) = (index(row + 1, column + 1), index(row, column + 1));
+            mesh.triangles.extend([[a, b, c], [a, c, d]]);
+        }
+    }
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+    use std::collections::HashMap;
+
+    /// Whether every edge is shared by exactly two triangles, in opposite directions
+    fn is_closed(mesh: &Mesh) -> bool {
+        let mut edges = HashMap::new();
+        for [a, b, c] in &mesh.triangles {
+            for edge in [(*a, *b), (*b, *c), (*c, *a)] {
+                *edges.entry(edge).or_insert(0) += 1;
+            }
+        }
+        edges
+            .iter()
+            .all(|(&(a, b), count)| *count == 1 && edges.get(&(b, a)) == Some(&1))
+    }
+
+    /// Signed volume, positive when the triangles face outwards
+    fn volume(mesh: &Mesh) -> f64 {
+        mesh.triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = mesh.corners(triangle);
+                a.coords.dot(&b.coords.cross(&c.coords)) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_meshes_are_closed() {
+        let radius = 0.1;
+        let cylinder = cylinder(Point3::origin(), Point3::new(0.0, 0.0, 2.0), radius);
+        assert!(is_closed(&cylinder));
+        let exact = PI * radius * radius * 2.0;
+        assert!(volume(&cylinder) > 0.9 * exact && volume(&cylinder) < exact);
+
+        let limits = [(-PI, PI), (-1.5, 1.5), (-3.0, 1.0)];
+        let envelope = workspace_envelope(&ABB_1660, &limits, 24, 12);
+        assert!(is_closed(&envelope));
+        assert!(volume(&envelope) > 0.0);
+        let reach = envelope
+            .vertices
+            .iter()
+            .map(|vertex| (vertex - Point3::new(0.0, 0.0, ABB_1660.c1)).norm())
+            .fold(0.0, f64::max);
+        let arm = ABB_1660.a1 + ABB_1660.c2 + ABB_1660.a2.hypot(ABB_1660.c3);
+        assert!(reach <= arm + 1e-9);
+
+        let mut mesh = envelope;
+        mesh.append(cylinder);
+        assert!(is_closed(&mesh));
+        assert_eq!(mesh.stl().len(), 84 + 50 * mesh.triangles.len());
+        let glb = mesh.glb();
+        assert_eq!(&glb[..4], b"glTF");
+        assert_eq!(
+            u32::from_le_bytes(glb[8..12].try_into().unwrap()) as usize,
+            glb.len()
+        );
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b"Man"), "TWFu");
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+    }
+}
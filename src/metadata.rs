@@ -0,0 +1,81 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::Robot;
+
+/// Name of the crate solving the kinematics
+const BACKEND: &str = "rs-opw-kinematics";
+
+/// The entries of a comma separated list of features
+fn features(list: &'static str) -> Vec<&'static str> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|feature| !feature.is_empty())
+        .collect()
+}
+
+/// Versions of this package and of the backend it is built on, the Cargo features of both,
+/// and the profile and target of the build, to include in bug reports and with stored results
+#[pyfunction]
+pub fn version_info(py: Python<'_>) -> PyResult<Bound<'_, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("version", env!("CARGO_PKG_VERSION"))?;
+    dict.set_item("backend", BACKEND)?;
+    dict.set_item("backend_version", env!("BUILD_BACKEND_VERSION"))?;
+    dict.set_item("backend_features", features(env!("BUILD_BACKEND_FEATURES")))?;
+    dict.set_item("features", features(env!("BUILD_FEATURES")))?;
+    dict.set_item("profile", env!("BUILD_PROFILE"))?;
+    dict.set_item("target", env!("BUILD_TARGET"))?;
+    Ok(dict)
+}
+
+/// How `robot` solves: the backend and its version, the package version, the kinematic model
+/// with its couplings, whether link corrections are applied, the joint units and limits, the
+/// solution policy and seed, the singularity tolerance, whether the cache and call statistics
+/// are on, and the model_hash and tool_hash that batch rows are stamped with
+#[pyfunction]
+pub fn solver_info<'py>(py: Python<'py>, robot: &Robot) -> PyResult<Bound<'py, PyDict>> {
+    let provenance = robot.provenance();
+    let dict = PyDict::new(py);
+    dict.set_item("backend", BACKEND)?;
+    dict.set_item("backend_version", env!("BUILD_BACKEND_VERSION"))?;
+    dict.set_item("version", env!("CARGO_PKG_VERSION"))?;
+    dict.set_item("kinematic_model", robot._kinematic_model.to_dict(py)?)?;
+    dict.set_item("calibrated", robot.calibration.is_some())?;
+    dict.set_item("units", if robot.radians { "radians" } else { "degrees" })?;
+    dict.set_item("joint_limits", robot.joint_limits.is_some())?;
+    dict.set_item("solution_policy", robot.solution_policy.name())?;
+    dict.set_item(
+        "default_seed",
+        robot.default_seed.map(|seed| robot.joints_out(seed, None)),
+    )?;
+    dict.set_item("cache", robot.cache.is_enabled())?;
+    dict.set_item(
+        "singularity_tolerance_deg",
+        robot.singularity_tolerance.to_degrees(),
+    )?;
+    dict.set_item("stats", robot.stats.is_enabled())?;
+    dict.set_item("model_hash", provenance.model_hash)?;
+    dict.set_item("tool_hash", provenance.tool_hash)?;
+    Ok(dict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_metadata_is_embedded() {
+        assert!(!env!("BUILD_PROFILE").is_empty());
+        let version = env!("BUILD_BACKEND_VERSION");
+        assert!(
+            version.split('.').all(|part| part.parse::<u32>().is_ok()),
+            "{}",
+            version
+        );
+        assert!(features(env!("BUILD_FEATURES"))
+            .iter()
+            .all(|feature| !feature.is_empty()));
+        assert_eq!(features(" a, b,,"), ["a", "b"]);
+    }
+}
@@ -0,0 +1,296 @@
+use nalgebra::{Isometry3, Matrix3, Rotation3, Translation3, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::calibrated::Corrections;
+use crate::columns::{JointTable, PoseTable};
+use crate::comfort::Comfort;
+use crate::kinematic_model::KinematicModel;
+use crate::pose::{from_isometry, to_isometry, PyPose};
+use crate::robot_builder::RobotBuilder;
+use crate::validation;
+use crate::{BaseConfig, Robot, ToolConfig};
+
+/// Joints whose axes lie in the mirror plane of the robot (J1, J4, J6) and change sign
+const MIRRORED_JOINTS: [bool; 6] = [true, false, false, true, false, true];
+
+/// Reflection about the local xz plane, which turns a reflected frame right-handed again
+fn flip_y() -> Matrix3<f64> {
+    Matrix3::from_diagonal(&Vector3::new(1.0, -1.0, 1.0))
+}
+
+fn isometry(translation: Vector3<f64>, rotation: Matrix3<f64>) -> Isometry3<f64> {
+    Isometry3::from_parts(
+        Translation3::from(translation),
+        UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(rotation)),
+    )
+}
+
+/// Frame conjugated with the xz reflection, as tool and link frames of a mirrored robot are
+fn flip_frame(frame: &Isometry3<f64>) -> Isometry3<f64> {
+    let s = flip_y();
+    isometry(
+        s * frame.translation.vector,
+        s * frame.rotation.to_rotation_matrix().matrix() * s,
+    )
+}
+
+/// Builds the mirror image of a robot cell about a plane: the left-hand variant of a
+/// right-hand cell. Mirrored poses keep right-handed frames by flipping their y axis, and
+/// the mirrored robot reaches them with joints 1, 4 and 6 negated.
+#[pyclass(frozen)]
+pub struct Mirror {
+    normal: Vector3<f64>,
+    point: Vector3<f64>,
+}
+
+impl Mirror {
+    fn reflect(&self, frame: &Isometry3<f64>) -> Isometry3<f64> {
+        let reflection = Matrix3::identity() - 2.0 * self.normal * self.normal.transpose();
+        let translation = frame.translation.vector;
+        isometry(
+            translation - 2.0 * self.normal * self.normal.dot(&(translation - self.point)),
+            reflection * frame.rotation.to_rotation_matrix().matrix() * flip_y(),
+        )
+    }
+
+    fn mirror_joints(joints: [f64; 6]) -> [f64; 6] {
+        std::array::from_fn(|i| {
+            if MIRRORED_JOINTS[i] {
+                -joints[i]
+            } else {
+                joints[i]
+            }
+        })
+    }
+
+    /// Link corrections as twists conjugated with the xz reflection (angular parts are
+    /// pseudovectors)
+    fn mirror_corrections(corrections: &Corrections) -> Corrections {
+        corrections.map(|[vx, vy, vz, wx, wy, wz]| [vx, -vy, vz, -wx, wy, -wz])
+    }
+}
+
+#[pymethods]
+impl Mirror {
+    /// Mirror plane through `point` with the given `normal`, by default the world xz plane
+    #[new]
+    #[pyo3(signature = (normal=[0.0, 1.0, 0.0], point=[0.0, 0.0, 0.0]))]
+    fn new(normal: [f64; 3], point: [f64; 3]) -> PyResult<Self> {
+        let normal = Vector3::from(normal)
+            .try_normalize(1e-12)
+            .ok_or_else(|| PyValueError::new_err("The mirror plane normal must be non-zero"))?;
+        Ok(Mirror {
+            normal,
+            point: Vector3::from(point),
+        })
+    }
+
+    /// The mirror image of a kinematic model: the lateral offset b and the offsets of
+    /// joints 1, 4 and 6 change sign, as does the J5 -> J6 coupling
+    fn model(&self, model: KinematicModel) -> KinematicModel {
+        KinematicModel {
+            b: -model.b,
+            offsets: Mirror::mirror_joints(model.offsets),
+            wrist_coupling: [model.wrist_coupling[0], -model.wrist_coupling[1]],
+            ..model
+        }
+    }
+
+    /// The mirror image of a robot with every setting: the base and the stationary tool
+    /// reflected, the tool flipped, and the joint limits, postures, seeds and mastering
+    /// offsets of joints 1, 4 and 6 negated
+    fn robot(&self, robot: &Robot) -> PyResult<Robot> {
+        let builder = robot.to_builder();
+        let reflect = |translation, rotation| {
+            from_isometry(&self.reflect(&to_isometry(translation, rotation)))
+        };
+        let (base_translation, base_rotation) =
+            reflect(robot.base_config.translation, robot.base_config.rotation);
+        let (tool_translation, tool_rotation) = from_isometry(&flip_frame(&to_isometry(
+            robot.tool_config.translation,
+            robot.tool_config.rotation,
+        )));
+        let stationary_tool = robot.stationary_tool.as_ref().map(|tool| {
+            let (translation, rotation) = reflect(tool.translation, tool.rotation);
+            ToolConfig::new(translation, rotation)
+        });
+        let joint_limits = builder.joint_limits.map(|limits| {
+            std::array::from_fn(|i| {
+                let (min, max) = limits[i];
+                if MIRRORED_JOINTS[i] {
+                    (-max, -min)
+                } else {
+                    (min, max)
+                }
+            })
+        });
+        RobotBuilder {
+            kinematic_model: Some(self.model(robot._kinematic_model.clone())),
+            base_config: Some(BaseConfig::new(base_translation, base_rotation)),
+            tool_config: Some(ToolConfig::new(tool_translation, tool_rotation)),
+            joint_limits,
+            corrections: builder.corrections.as_ref().map(Mirror::mirror_corrections),
+            default_seed: builder.default_seed.map(Mirror::mirror_joints),
+            comfort: builder.comfort.map(|comfort| Comfort {
+                preferred: comfort.preferred.map(Mirror::mirror_joints),
+                ..comfort
+            }),
+            postures: builder
+                .postures
+                .iter()
+                .map(|(name, joints)| (name.clone(), Mirror::mirror_joints(*joints)))
+                .collect(),
+            mastering_offsets: builder.mastering_offsets.map(Mirror::mirror_joints),
+            stationary_tool,
+            ..builder
+        }
+        .build()
+    }
+
+    /// The mirror image of a pose in the world frame
+    fn pose(&self, pose: PyPose) -> PyResult<PyPose> {
+        validation::check_pose(&pose)?;
+        Ok(from_isometry(&self.reflect(&to_isometry(pose.0, pose.1))))
+    }
+
+    /// The mirror images of many poses
+    fn poses(&self, poses: PoseTable) -> PyResult<Vec<PyPose>> {
+        poses.0.into_iter().map(|pose| self.pose(pose)).collect()
+    }
+
+    /// Joints of the mirrored robot that mirror the given joints, in the same units
+    fn joints(&self, joints: [f64; 6]) -> [f64; 6] {
+        Mirror::mirror_joints(joints)
+    }
+
+    /// Mirrored joints of many joint sets
+    fn joint_sets(&self, joints: JointTable) -> Vec<[f64; 6]> {
+        joints.0.into_iter().map(Mirror::mirror_joints).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::calibrated::CalibratedChain;
+    use crate::pose::pose_errors;
+    use crate::ranking::Ranking;
+    use crate::tests::ABB_1660;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn test_mirrored_robot_reaches_mirrored_poses() {
+        let model = KinematicModel {
+            b: 0.05,
+            offsets: [0.1, 0.0, -1.5, 0.2, 0.0, -0.3],
+            wrist_coupling: [0.01, 0.02],
+            ..ABB_1660
+        };
+        let mut robot = Robot::new(
+            model,
+            BaseConfig::new([0.4, -0.3, 0.2], [0.8, 0.0, 0.0, 0.6]),
+            ToolConfig::new([0.02, 0.05, 0.2], [0.6, 0.8, 0.0, 0.0]),
+        )
+        .unwrap();
+        let mut corrections = [[0.0; 6]; 6];
+        corrections[2] = [0.001, -0.0005, 0.0, 0.002, 0.0, -0.001];
+        robot.calibration = Some(CalibratedChain::new(
+            &robot._kinematic_model,
+            corrections,
+            robot.base(),
+            robot.tool(),
+        ));
+        let mirror = Mirror::new([0.3, 1.0, 0.1], [0.0, 1.5, 0.0]).unwrap();
+        let mirrored = mirror.robot(&robot).unwrap();
+
+        let joints = [10.0, -20.0, 30.0, 40.0, 50.0, 60.0];
+        let expected = mirror.pose(robot.forward(joints, None).unwrap()).unwrap();
+        let actual = mirrored.forward(mirror.joints(joints), None).unwrap();
+        let (position_error, rotation_error) = pose_errors(
+            &to_isometry(actual.0, actual.1),
+            &to_isometry(expected.0, expected.1),
+        );
+        assert!(position_error < 1e-9 && rotation_error < 1e-9);
+    }
+
+    #[test]
+    fn test_mirrored_robot_keeps_every_setting() {
+        fn assert_close(a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) {
+            if let (Ok(a), Ok(b)) = (a.cast::<PyDict>(), b.cast::<PyDict>()) {
+                assert_eq!(a.len(), b.len());
+                for (key, value) in a.iter() {
+                    assert_close(&value, &b.get_item(&key).unwrap().unwrap());
+                }
+            } else if let (Ok(a), Ok(b)) = (a.extract::<f64>(), b.extract::<f64>()) {
+                assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+            } else if let (Ok(a), Ok(b)) = (
+                a.extract::<Vec<Bound<'_, PyAny>>>(),
+                b.extract::<Vec<Bound<'_, PyAny>>>(),
+            ) {
+                assert_eq!(a.len(), b.len());
+                a.iter().zip(&b).for_each(|(a, b)| assert_close(a, b));
+            } else {
+                assert!(a.eq(b).unwrap(), "{} != {}", a, b);
+            }
+        }
+
+        let mut corrections = [[0.0; 6]; 6];
+        corrections[1] = [0.001, 0.0, -0.0005, 0.0, 0.001, 0.0];
+        let robot = RobotBuilder {
+            kinematic_model: Some(ABB_1660),
+            base_config: Some(BaseConfig::new([0.4, -0.3, 0.2], [0.8, 0.0, 0.0, 0.6])),
+            tool_config: Some(ToolConfig::new([0.02, 0.05, 0.2], [1.0, 0.0, 0.0, 0.0])),
+            joint_limits: Some([(-170.0, 160.0); 6]),
+            solution_policy: Some("closest".to_string()),
+            corrections: Some(corrections),
+            default_seed: Some([10.0, 20.0, 30.0, 40.0, 50.0, 60.0]),
+            singularity_tolerance_deg: Some(0.5),
+            comfort: Some(Comfort {
+                preferred: Some([5.0, 10.0, 15.0, 20.0, 25.0, 30.0]),
+                ..Comfort::default()
+            }),
+            ranking: Some(Ranking {
+                manipulability_weight: 0.5,
+                ..Ranking::default()
+            }),
+            postures: [("home".to_string(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0])].into(),
+            mastering_offsets: Some([0.1, 0.2, 0.3, 0.4, 0.5, 0.6]),
+            stationary_tool: Some(ToolConfig::new([1.2, 0.3, 0.9], [0.0, 0.0, 1.0, 0.0])),
+            ..RobotBuilder::default()
+        }
+        .build()
+        .unwrap();
+        let mirror = Mirror::new([0.0, 1.0, 0.0], [0.0, 0.5, 0.0]).unwrap();
+        let mirrored = mirror.robot(&robot).unwrap();
+        assert_eq!(
+            mirrored.default_seed.unwrap()[0],
+            -robot.default_seed.unwrap()[0]
+        );
+        assert_eq!(mirrored.mastering[3], -robot.mastering[3]);
+        assert_eq!(mirrored.mastering[4], robot.mastering[4]);
+        let tool = mirrored.stationary_tool.as_ref().unwrap();
+        assert_eq!(tool.translation, [1.2, 0.7, 0.9]);
+
+        Python::initialize();
+        Python::attach(|py| {
+            let twice = mirror.robot(&mirrored).unwrap();
+            assert_close(
+                twice.to_dict(py).unwrap().as_any(),
+                robot.to_dict(py).unwrap().as_any(),
+            );
+        });
+    }
+
+    #[test]
+    fn test_mirror_twice_is_identity() {
+        let mirror = Mirror::new([0.0, 2.0, 0.0], [0.0, 0.5, 0.0]).unwrap();
+        let pose = ([0.3, 0.2, 1.0], [0.5, 0.5, 0.5, 0.5]);
+        let back = mirror.pose(mirror.pose(pose).unwrap()).unwrap();
+        let (position_error, rotation_error) =
+            pose_errors(&to_isometry(back.0, back.1), &to_isometry(pose.0, pose.1));
+        assert!(position_error < 1e-12 && rotation_error < 1e-12);
+        assert_eq!(mirror.pose(pose).unwrap().0, [0.3, 0.8, 1.0]);
+        assert!(Mirror::new([0.0; 3], [0.0; 3]).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use rs_opw_kinematics::parameters::opw_kinematics::Parameters;
+use std::f64::consts::FRAC_PI_2;
+
+use crate::kinematic_model::KinematicModel;
+
+/// How a vendor's pendant counts the joints of an OPW robot described by the usual
+/// parameters (a2 the elbow offset, c3 the forearm): the direction of every axis and the
+/// offset of its zero from the OPW zero, in radians
+pub struct Convention {
+    pub vendor: &'static str,
+    pub sign_corrections: [i8; 6],
+    pub offsets: [f64; 6],
+}
+
+/// ABB: every axis positive by the right hand rule, J3 zero with the forearm horizontal
+const ABB: Convention = Convention {
+    vendor: "ABB",
+    sign_corrections: [1; 6],
+    offsets: [0.0, 0.0, -FRAC_PI_2, 0.0, 0.0, 0.0],
+};
+
+/// Fanuc counts the directions and zeros as ABB does
+const FANUC: Convention = Convention {
+    vendor: "Fanuc",
+    sign_corrections: [1; 6],
+    offsets: [0.0, 0.0, -FRAC_PI_2, 0.0, 0.0, 0.0],
+};
+
+/// KUKA: A1, A4 and A6 turn clockwise seen from the motor, A2 reads -90 with the lower arm
+/// upright and A3 +90 with the forearm horizontal
+const KUKA: Convention = Convention {
+    vendor: "KUKA",
+    sign_corrections: [-1, 1, 1, -1, 1, -1],
+    offsets: [0.0, -FRAC_PI_2, 0.0, 0.0, 0.0, 0.0],
+};
+
+const CONVENTIONS: [&Convention; 3] = [&ABB, &FANUC, &KUKA];
+
+/// The joint convention of a vendor, e.g. "kuka"
+pub fn convention(vendor: &str) -> PyResult<&'static Convention> {
+    CONVENTIONS
+        .into_iter()
+        .find(|convention| normalize(convention.vendor) == normalize(vendor))
+        .ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "No joint convention for vendor '{}', expected 'abb', 'fanuc' or 'kuka'",
+                vendor
+            ))
+        })
+}
+
+/// A named entry of the built-in model library
+struct ModelPreset {
+    vendor: &'static str,
+    name: &'static str,
+    parameters: fn() -> Parameters,
+}
+
+fn irb1660() -> Parameters {
+    Parameters {
+        a1: 0.150,
+        a2: -0.110,
+        b: 0.0,
+        c1: 0.4865,
+        c2: 0.700,
+        c3: 0.678,
+        c4: 0.135,
+        offsets: [0.0, 0.0, -FRAC_PI_2, 0.0, 0.0, 0.0],
+        ..Parameters::new()
+    }
+}
+
+fn kr6_r900_sixx() -> Parameters {
+    Parameters {
+        a1: 0.025,
+        a2: -0.035,
+        b: 0.0,
+        c1: 0.400,
+        c2: 0.455,
+        c3: 0.420,
+        c4: 0.080,
+        offsets: KUKA.offsets,
+        sign_corrections: KUKA.sign_corrections,
+        ..Parameters::new()
+    }
+}
+
+const PRESETS: &[ModelPreset] = &[
+    ModelPreset {
+        vendor: "ABB",
+        name: "IRB 1660",
+        parameters: irb1660,
+    },
+    ModelPreset {
+        vendor: "ABB",
+        name: "IRB 2400/10",
+        parameters: Parameters::irb2400_10,
+    },
+    ModelPreset {
+        vendor: "ABB",
+        name: "IRB 2600-12/1.65",
+        parameters: Parameters::irb2600_12_165,
+    },
+    ModelPreset {
+        vendor: "ABB",
+        name: "IRB 4600-60/2.05",
+        parameters: Parameters::irb4600_60_205,
+    },
+    ModelPreset {
+        vendor: "Fanuc",
+        name: "R-2000iB/200R",
+        parameters: Parameters::fanuc_r2000ib_200r,
+    },
+    ModelPreset {
+        vendor: "KUKA",
+        name: "KR 6 R700 sixx",
+        parameters: Parameters::kuka_kr6_r700_sixx,
+    },
+    ModelPreset {
+        vendor: "KUKA",
+        name: "KR 6 R900 sixx",
+        parameters: kr6_r900_sixx,
+    },
+    ModelPreset {
+        vendor: "Staubli",
+        name: "TX2-140",
+        parameters: Parameters::staubli_tx2_140,
+    },
+    ModelPreset {
+        vendor: "Staubli",
+        name: "TX2-160",
+        parameters: Parameters::staubli_tx2_160,
+    },
+    ModelPreset {
+        vendor: "Staubli",
+        name: "TX2-160L",
+        parameters: Parameters::staubli_tx2_160l,
+    },
+    ModelPreset {
+        vendor: "Staubli",
+        name: "TX40",
+        parameters: Parameters::staubli_tx40,
+    },
+];
+
+impl ModelPreset {
+    fn full_name(&self) -> String {
+        format!("{} {}", self.vendor, self.name)
+    }
+}
+
+/// Lowercases and drops everything but letters and digits, so "KR6-R700 Sixx" == "kr6r700sixx"
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+fn find(name: &str) -> PyResult<&'static ModelPreset> {
+    let query = normalize(name);
+    let keys = |preset: &ModelPreset| [normalize(&preset.full_name()), normalize(preset.name)];
+
+    if let Some(preset) = PRESETS.iter().find(|p| keys(p).contains(&query)) {
+        return Ok(preset);
+    }
+    // A partial name such as "1660" is accepted as long as it is unambiguous
+    let partial: Vec<_> = PRESETS
+        .iter()
+        .filter(|p| !query.is_empty() && keys(p)[0].contains(&query))
+        .collect();
+    if partial.len() == 1 {
+        return Ok(partial[0]);
+    }
+
+    let mut candidates: Vec<_> = if partial.is_empty() {
+        PRESETS.iter().collect()
+    } else {
+        partial
+    };
+    // Names starting like the query (same vendor or series prefix) are listed first
+    let prefix: String = query.chars().take(2).collect();
+    candidates.sort_by_key(|p| {
+        let keys = keys(p);
+        let shares_prefix = keys.iter().any(|k| k.starts_with(&prefix));
+        let distance = keys.iter().map(|k| levenshtein(&query, k)).min();
+        (!shares_prefix, distance)
+    });
+    let suggestions: Vec<_> = candidates.iter().take(3).map(|p| p.full_name()).collect();
+    Err(PyValueError::new_err(format!(
+        "Unknown robot model '{}', closest matches: {} (see list_models() for all)",
+        name,
+        suggestions.join(", ")
+    )))
+}
+
+/// Looks up a model of the built-in library by (partial) name
+pub fn model_by_name(name: &str) -> PyResult<KinematicModel> {
+    Ok(KinematicModel::from_parameters(&(find(name)?.parameters)()))
+}
+
+/// Names of all models in the built-in library
+#[pyfunction]
+pub fn list_models() -> Vec<String> {
+    PRESETS.iter().map(ModelPreset::full_name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rs_opw_kinematics::kinematic_traits::Kinematics;
+
+    #[test]
+    fn test_lookup_is_normalized() {
+        assert_eq!(find("kuka kr6-r700 SIXX").unwrap().name, "KR 6 R700 sixx");
+        assert_eq!(find("IRB 2400/10").unwrap().name, "IRB 2400/10");
+        assert_eq!(find("1660").unwrap().name, "IRB 1660");
+    }
+
+    #[test]
+    fn test_ambiguous_and_unknown_names_fail() {
+        assert!(find("TX2-1").is_err());
+        assert!(find("KR 210 R2700").is_err());
+    }
+
+    #[test]
+    fn test_presets_follow_the_vendor_convention() {
+        for preset in PRESETS.iter().filter(|p| p.vendor != "Staubli") {
+            let parameters = (preset.parameters)();
+            let convention = convention(preset.vendor).unwrap();
+            assert_eq!(parameters.sign_corrections, convention.sign_corrections);
+            assert_eq!(parameters.offsets, convention.offsets);
+        }
+        assert!(convention("Kuka").is_ok() && convention("Yaskawa").is_err());
+    }
+
+    #[test]
+    fn test_kuka_pendant_directions() {
+        let robot = model_by_name("KR 6 R900").unwrap().to_opw_kinematics();
+        let forward = |joints: [f64; 6]| robot.forward(&joints.map(f64::to_radians)).translation;
+        // Home: lower arm upright, forearm and flange pointing forward
+        let home = forward([0.0, -90.0, 90.0, 0.0, 0.0, 0.0]);
+        assert!((home.x - (0.025 + 0.420 + 0.080)).abs() < 1e-9);
+        assert!((home.z - (0.400 + 0.455 + 0.035)).abs() < 1e-9);
+        // Positive A1 turns clockwise seen from above, to the right of the robot
+        assert!(forward([30.0, -90.0, 90.0, 0.0, 0.0, 0.0]).y < 0.0);
+        // Positive A2 lowers the arm forward
+        assert!(forward([0.0, -80.0, 90.0, 0.0, 0.0, 0.0]).x > home.x);
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+    }
+}
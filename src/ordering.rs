@@ -0,0 +1,236 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::cmp::Ordering;
+use std::f64::consts::TAU;
+
+use crate::kinematic_model::KinematicModel;
+
+/// Order in which inverse kinematics solutions are returned
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SolutionOrder {
+    /// Closest to the seed first (sum of absolute joint differences)
+    Distance,
+    /// By configuration index cfx, 0 first
+    Configuration,
+    /// As produced by the solver, which may change between releases
+    Unordered,
+    /// Lowest posture cost of the robot first, then closest to the seed
+    Comfort,
+    /// Lowest weighted score of the robot's ranking criteria first, then closest to the seed
+    Weighted,
+}
+
+impl SolutionOrder {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "distance" => Ok(SolutionOrder::Distance),
+            "configuration" => Ok(SolutionOrder::Configuration),
+            "none" => Ok(SolutionOrder::Unordered),
+            "comfort" => Ok(SolutionOrder::Comfort),
+            "weighted" => Ok(SolutionOrder::Weighted),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown order '{}', expected 'distance', 'configuration', 'comfort', 'weighted' \
+                 or 'none'",
+                name
+            ))),
+        }
+    }
+}
+
+/// Per-call settings of inverse kinematics, tolerances in radians
+#[derive(Clone, Copy, Debug)]
+pub struct InverseOptions {
+    pub order_by: SolutionOrder,
+    /// Merge solutions within this angle of a better ranked one
+    pub dedup_tolerance: Option<f64>,
+    /// Whether whole turns are ignored when merging
+    pub dedup_turns: bool,
+    /// Overrides the joint units of the robot
+    pub radians: Option<bool>,
+}
+
+impl Default for InverseOptions {
+    fn default() -> Self {
+        InverseOptions {
+            order_by: SolutionOrder::Distance,
+            dedup_tolerance: None,
+            dedup_turns: true,
+            radians: None,
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for SolutionOrder {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        SolutionOrder::parse(&obj.extract::<String>()?)
+    }
+}
+
+fn distance(a: &Joints, b: &Joints) -> f64 {
+    joint_distance(a, b, &[1.0; 6], false)
+}
+
+/// Weighted sum of the absolute joint differences (radians), ignoring whole turns when
+/// `turn_aware`. With unit weights and without turns it is the distance solutions are
+/// ordered by.
+pub fn joint_distance(a: &Joints, b: &Joints, weights: &[f64; 6], turn_aware: bool) -> f64 {
+    a.iter()
+        .zip(b)
+        .zip(weights)
+        .map(|((a, b), weight)| weight * joint_difference(*a, *b, turn_aware))
+        .sum()
+}
+
+/// Joint values compared one by one, the final tie breaker
+fn lexicographic(a: &Joints, b: &Joints) -> Ordering {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| a.total_cmp(b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// The solution (radians) that `sort` puts first by distance, without collecting them
+pub fn closest(
+    solutions: impl IntoIterator<Item = Joints>,
+    seed: &Joints,
+    configuration: impl Fn(&Joints) -> u8,
+) -> Option<Joints> {
+    let key = |joints: &Joints| (distance(joints, seed), configuration(joints));
+    solutions.into_iter().min_by(|a, b| {
+        let (a_distance, a_configuration) = key(a);
+        let (b_distance, b_configuration) = key(b);
+        a_distance
+            .total_cmp(&b_distance)
+            .then(a_configuration.cmp(&b_configuration))
+            .then_with(|| lexicographic(a, b))
+    })
+}
+
+/// Sorts solutions (radians) in the given order. Ties on the primary key are broken by
+/// the other key and then by the joint values, so the result does not depend on the
+/// order the solver produced them in. `configuration` gives the configuration index cfx
+/// of a solution.
+pub fn sort(
+    solutions: &mut [Joints],
+    order: SolutionOrder,
+    seed: &Joints,
+    configuration: impl Fn(&Joints) -> u8,
+) {
+    let key = |joints: &Joints| (distance(joints, seed), configuration(joints));
+    match order {
+        // The robot ranks by comfort or score on top of the distance order, which breaks the
+        // ties
+        SolutionOrder::Distance | SolutionOrder::Comfort | SolutionOrder::Weighted => solutions
+            .sort_by(|a, b| {
+                let (a_distance, a_configuration) = key(a);
+                let (b_distance, b_configuration) = key(b);
+                a_distance
+                    .total_cmp(&b_distance)
+                    .then(a_configuration.cmp(&b_configuration))
+                    .then_with(|| lexicographic(a, b))
+            }),
+        SolutionOrder::Configuration => solutions.sort_by(|a, b| {
+            let (a_distance, a_configuration) = key(a);
+            let (b_distance, b_configuration) = key(b);
+            a_configuration
+                .cmp(&b_configuration)
+                .then(a_distance.total_cmp(&b_distance))
+                .then_with(|| lexicographic(a, b))
+        }),
+        SolutionOrder::Unordered => {}
+    }
+}
+
+/// Difference between two joint values, ignoring whole turns when `turn_aware`
+fn joint_difference(a: f64, b: f64, turn_aware: bool) -> f64 {
+    let difference = a - b;
+    if turn_aware {
+        (difference - TAU * (difference / TAU).round()).abs()
+    } else {
+        difference.abs()
+    }
+}
+
+/// Drops solutions whose joints all lie within `tolerance` (radians) of an earlier one,
+/// so the first, best ranked, of each group is kept. With the kinematic model for `turns`,
+/// solutions whose joints of the model differ by whole turns count as duplicates too;
+/// they are compared decoupled, as with wrist coupling a turn of J4 turns J6 along.
+pub fn dedup(solutions: &mut Vec<Joints>, tolerance: f64, turns: Option<&KinematicModel>) {
+    let compared = |joints: &Joints| turns.map_or(*joints, |model| model.decouple(joints));
+    let mut kept: Vec<(Joints, Joints)> = Vec::with_capacity(solutions.len());
+    for solution in solutions.drain(..) {
+        let joints = compared(&solution);
+        let duplicate = kept.iter().any(|(other, _)| {
+            joints
+                .iter()
+                .zip(other.iter())
+                .all(|(a, b)| joint_difference(*a, *b, turns.is_some()) <= tolerance)
+        });
+        if !duplicate {
+            kept.push((joints, solution));
+        }
+    }
+    *solutions = kept.into_iter().map(|(_, solution)| solution).collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::configuration;
+    use crate::tests::ABB_1660;
+
+    #[test]
+    fn test_order_does_not_depend_on_input_order() {
+        let seed = [0.0; 6];
+        let solutions = [
+            [0.1, 0.2, 0.3, 0.0, 0.5, 0.0],
+            [-0.1, 0.2, 0.3, 0.0, 0.5, 0.0],
+            [0.1, 0.2, 0.3, 0.0, -0.5, 0.0],
+            [2.0, 0.2, 0.3, 0.0, 0.5, 0.0],
+        ];
+        for order in [SolutionOrder::Distance, SolutionOrder::Configuration] {
+            let mut forward = solutions;
+            let mut backward = solutions;
+            backward.reverse();
+            let cfx = |joints: &Joints| configuration(&ABB_1660, joints);
+            sort(&mut forward, order, &seed, cfx);
+            sort(&mut backward, order, &seed, cfx);
+            assert_eq!(forward, backward);
+        }
+    }
+
+    #[test]
+    fn test_dedup_merges_noise_and_turns() {
+        let mut solutions = vec![
+            [0.1, 0.2, 0.3, 0.0, 0.5, 0.0],
+            [0.1 + 1e-9, 0.2, 0.3, 0.0, 0.5, 0.0],
+            [0.1, 0.2, 0.3, TAU, 0.5, 0.0],
+            [0.1, 0.2, 0.3, 0.0, -0.5, 0.0],
+        ];
+        let mut without_turns = solutions.clone();
+        dedup(&mut solutions, 1e-6, Some(&ABB_1660));
+        assert_eq!(solutions.len(), 2);
+        dedup(&mut without_turns, 1e-6, None);
+        assert_eq!(without_turns.len(), 3);
+    }
+
+    #[test]
+    fn test_dedup_turns_of_a_coupled_wrist() {
+        let coupled = KinematicModel {
+            wrist_coupling: [0.5, 0.0],
+            ..ABB_1660
+        };
+        let joints = [0.1, 0.2, 0.3, 0.0, 0.5, 0.0];
+        // A turn of J4 alone moves the decoupled J6, the same posture turns J6 along
+        let mut solutions = vec![joints, [0.1, 0.2, 0.3, TAU, 0.5, 0.0]];
+        dedup(&mut solutions, 1e-6, Some(&coupled));
+        assert_eq!(solutions.len(), 2);
+        let mut solutions = vec![joints, coupled.couple(&[0.1, 0.2, 0.3, TAU, 0.5, 0.0])];
+        dedup(&mut solutions, 1e-6, Some(&coupled));
+        assert_eq!(solutions, vec![joints]);
+    }
+}
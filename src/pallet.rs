@@ -0,0 +1,187 @@
+use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::axis::Axis;
+use crate::pose::{from_isometry, to_isometry, PyPose};
+use crate::rows::POSE_COLUMNS;
+use crate::validation;
+
+/// One place position of a pallet pattern
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Place {
+    layer: usize,
+    row: usize,
+    column: usize,
+    pose: Isometry3<f64>,
+}
+
+/// Place poses of an `nx` by `ny` by `nz` grid spaced `step` (m) along the axes of
+/// `origin`, layer by layer and row by row, each turned about its z axis by the rotation
+/// (radians) of its layer from `layer_rotations`, cycled. With `serpentine` every other row
+/// runs backwards.
+fn places(
+    origin: &Isometry3<f64>,
+    counts: [usize; 3],
+    step: [f64; 3],
+    layer_rotations: &[f64],
+    serpentine: bool,
+) -> Vec<Place> {
+    let [nx, ny, nz] = counts;
+    let mut places = Vec::with_capacity(nx * ny * nz);
+    for layer in 0..nz {
+        let angle = match layer_rotations.is_empty() {
+            true => 0.0,
+            false => layer_rotations[layer % layer_rotations.len()],
+        };
+        let rotation = UnitQuaternion::from_axis_angle(&Vector3::z_axis(), angle);
+        for row in 0..ny {
+            for i in 0..nx {
+                let column = if serpentine && row % 2 == 1 {
+                    nx - 1 - i
+                } else {
+                    i
+                };
+                let offset = Translation3::new(
+                    column as f64 * step[0],
+                    row as f64 * step[1],
+                    layer as f64 * step[2],
+                );
+                places.push(Place {
+                    layer,
+                    row,
+                    column,
+                    pose: origin * Isometry3::from_parts(offset, rotation),
+                });
+            }
+        }
+    }
+    places
+}
+
+/// Poses of a palletizing pattern of `nx` by `ny` items in `nz` layers, ready for
+/// batch_inverse or solve_and_join. `origin_pose` is the TCP pose at the first item; items
+/// are spaced `dx`, `dy` and `dz` (m) along its x, y and z axes as named for a tool along
+/// z, so with the tool axis pointing down, layers stack with a negative `dz`; with another
+/// `tool_axis` (the convention of set_tool_axis by default) `dz` steps along it and `dx`
+/// and `dy` along the next two axes. `layer_rotations` (degrees, cycled over the layers)
+/// turns the items of each layer about the tool axis, e.g. [0, 90] for an interlocking
+/// pattern, and `serpentine` runs every other row backwards. Rows have the pose columns
+/// with the index, layer, row and column of the item; with `approach` (m) every item is
+/// preceded by its pose backed off along the tool axis, with approach True.
+#[pyfunction]
+#[pyo3(signature = (
+    origin_pose,
+    nx,
+    ny,
+    nz,
+    dx,
+    dy,
+    dz,
+    approach=None,
+    layer_rotations=None,
+    serpentine=false,
+    tool_axis=None,
+))]
+#[allow(clippy::too_many_arguments)]
+pub fn generate_pallet<'py>(
+    py: Python<'py>,
+    origin_pose: PyPose,
+    nx: usize,
+    ny: usize,
+    nz: usize,
+    dx: f64,
+    dy: f64,
+    dz: f64,
+    approach: Option<f64>,
+    layer_rotations: Option<Vec<f64>>,
+    serpentine: bool,
+    tool_axis: Option<Axis>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    validation::check_finite(
+        "origin_pose",
+        &origin_pose,
+        validation::is_finite_pose(&origin_pose),
+    )?;
+    if nx == 0 || ny == 0 || nz == 0 {
+        return Err(PyValueError::new_err("nx, ny and nz must be at least 1"));
+    }
+    validation::check_finite(
+        "dx, dy, dz",
+        &[dx, dy, dz],
+        [dx, dy, dz].iter().all(|x| x.is_finite()),
+    )?;
+    if let Some(approach) = approach {
+        validation::check_positive("approach", approach)?;
+    }
+    let layer_rotations: Vec<f64> = layer_rotations
+        .unwrap_or_default()
+        .iter()
+        .map(|angle| angle.to_radians())
+        .collect();
+    validation::check_finite(
+        "layer_rotations",
+        &layer_rotations,
+        layer_rotations.iter().all(|x| x.is_finite()),
+    )?;
+    // The pattern is laid out for a tool along z and turned onto the tool axis
+    let tool_rotation = Axis::tool(tool_axis).tool_rotation();
+    let origin = to_isometry(origin_pose.0, origin_pose.1) * tool_rotation.inverse();
+    let places = places(
+        &origin,
+        [nx, ny, nz],
+        [dx, dy, dz],
+        &layer_rotations,
+        serpentine,
+    );
+    let row =
+        |index: usize, place: &Place, pose: &Isometry3<f64>, is_approach: bool| -> PyResult<_> {
+            let (translation, rotation) = from_isometry(&(pose * tool_rotation));
+            let dict = PyDict::new(py);
+            for (column, value) in POSE_COLUMNS.iter().zip(translation.iter().chain(&rotation)) {
+                dict.set_item(column, value)?;
+            }
+            dict.set_item("index", index)?;
+            dict.set_item("layer", place.layer)?;
+            dict.set_item("row", place.row)?;
+            dict.set_item("column", place.column)?;
+            if approach.is_some() {
+                dict.set_item("approach", is_approach)?;
+            }
+            Ok(dict)
+        };
+    let mut rows = Vec::new();
+    for (index, place) in places.iter().enumerate() {
+        if let Some(approach) = approach {
+            let backed_off = place.pose * Translation3::new(0.0, 0.0, -approach);
+            rows.push(row(index, place, &backed_off, true)?);
+        }
+        rows.push(row(index, place, &place.pose, false)?);
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pallet_places() {
+        // Tool z pointing down, layers stacking up
+        let origin = Isometry3::from_parts(
+            Translation3::new(1.0, 0.0, 0.2),
+            UnitQuaternion::from_euler_angles(std::f64::consts::PI, 0.0, 0.0),
+        );
+        let rotations = [0.0, std::f64::consts::FRAC_PI_2];
+        let places = places(&origin, [3, 2, 2], [0.3, 0.2, -0.15], &rotations, true);
+        assert_eq!(places.len(), 12);
+        // The second row runs backwards
+        assert_eq!((places[3].row, places[3].column), (1, 2));
+        let last = places.last().unwrap();
+        assert_eq!((last.layer, last.row, last.column), (1, 1, 0));
+        let position = last.pose.translation.vector;
+        assert!((position - Vector3::new(1.0, -0.2, 0.35)).norm() < 1e-12);
+        assert!((last.pose.rotation.angle_to(&origin.rotation) - rotations[1]).abs() < 1e-12);
+    }
+}
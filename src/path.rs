@@ -0,0 +1,419 @@
+use nalgebra::Vector3;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::f64::consts::TAU;
+
+/// Whole turns added to or removed from a joint when listing turn variants
+const MAX_TURNS: i32 = 2;
+
+/// How one solution per pose is chosen along a path
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathSelection {
+    /// Pose by pose, the solution closest to the previous one
+    Greedy,
+    /// Over the whole path, the least total cost
+    Shortest,
+    /// Over the whole path, the largest smallest distance to the joint limits, then the
+    /// least total cost
+    LimitMargin,
+}
+
+impl PathSelection {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "greedy" => Ok(PathSelection::Greedy),
+            "shortest" => Ok(PathSelection::Shortest),
+            "limit_margin" => Ok(PathSelection::LimitMargin),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown path selection '{}', expected 'greedy', 'shortest' or 'limit_margin'",
+                name
+            ))),
+        }
+    }
+}
+
+/// What moving between the solutions of consecutive poses costs, in radians
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCost {
+    /// Sum of the absolute changes of all joints
+    Travel,
+    /// Largest change of any joint, the duration of a synchronized move at equal axis speeds
+    Time,
+    /// Sum of the absolute changes of joints 4 to 6, with the travel of the main axes as a
+    /// small tie breaker
+    WristMotion,
+}
+
+/// Weight of the main axes in the wrist motion cost
+const MAIN_AXES_WEIGHT: f64 = 1e-3;
+
+impl PathCost {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "travel" => Ok(PathCost::Travel),
+            "time" => Ok(PathCost::Time),
+            "wrist_motion" => Ok(PathCost::WristMotion),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown path cost '{}', expected 'travel', 'time' or 'wrist_motion'",
+                name
+            ))),
+        }
+    }
+
+    /// Cost of moving from joints `a` to `b`
+    pub fn motion(&self, a: &Joints, b: &Joints) -> f64 {
+        let changes = a.iter().zip(b.iter()).map(|(a, b)| (a - b).abs());
+        match self {
+            PathCost::Travel => changes.sum(),
+            PathCost::Time => changes.fold(0.0, f64::max),
+            PathCost::WristMotion => changes
+                .enumerate()
+                .map(|(i, change)| {
+                    if i < 3 {
+                        MAIN_AXES_WEIGHT * change
+                    } else {
+                        change
+                    }
+                })
+                .sum(),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for PathCost {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        PathCost::parse(&obj.extract::<String>()?)
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for PathSelection {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        PathSelection::parse(&obj.extract::<String>()?)
+    }
+}
+
+fn within_step(a: &Joints, b: &Joints, max_step: f64) -> bool {
+    a.iter()
+        .zip(b.iter())
+        .all(|(a, b)| (a - b).abs() <= max_step)
+}
+
+/// Smallest distance (radians) of any joint to either of its limits
+pub fn limit_margin(joints: &Joints, limits: &[(f64, f64); 6]) -> f64 {
+    joints
+        .iter()
+        .zip(limits.iter())
+        .map(|(joint, (min, max))| (joint - min).min(max - joint))
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Largest distance of the TCP from the straight line between its end positions while the
+/// joints move linearly from `start` to `end`, as a joint move (MoveJ, PTP) does, checked
+/// at `samples` steps. `tcp` gives the TCP position at joints.
+pub fn joint_move_deviation(
+    start: &Joints,
+    end: &Joints,
+    samples: usize,
+    tcp: impl Fn(&Joints) -> Vector3<f64>,
+) -> f64 {
+    let (from, to) = (tcp(start), tcp(end));
+    let line = to - from;
+    let length = line.norm();
+    (1..samples)
+        .map(|step| {
+            let t = step as f64 / samples as f64;
+            let joints: Joints = std::array::from_fn(|i| start[i] + t * (end[i] - start[i]));
+            let offset = tcp(&joints) - from;
+            match length > 1e-12 {
+                true => {
+                    (offset - line * (offset.dot(&line) / (length * length)).clamp(0.0, 1.0)).norm()
+                }
+                false => offset.norm(),
+            }
+        })
+        .fold(0.0, f64::max)
+}
+
+/// The solution and its copies with whole turns of the joints added or removed (up to
+/// `MAX_TURNS`) that stay within the limits
+pub fn turn_variants(solution: &Joints, limits: &[(f64, f64); 6]) -> Vec<Joints> {
+    let mut variants = vec![*solution];
+    for (i, (min, max)) in limits.iter().enumerate() {
+        let turns: Vec<f64> = (-MAX_TURNS..=MAX_TURNS)
+            .filter(|&k| k != 0)
+            .map(|k| solution[i] + TAU * k as f64)
+            .filter(|joint| min <= joint && joint <= max)
+            .collect();
+        let count = variants.len();
+        for variant in 0..count {
+            for &joint in &turns {
+                let mut turned = variants[variant];
+                turned[i] = joint;
+                variants.push(turned);
+            }
+        }
+    }
+    variants
+}
+
+/// Index of the candidate cheapest to reach from `previous` among those within `max_step`
+/// (radians, per joint) of it, the greedy choice for the next pose of a path
+pub fn closest(
+    candidates: &[Joints],
+    previous: &Joints,
+    max_step: f64,
+    cost: impl Fn(&Joints, &Joints) -> f64,
+) -> Option<usize> {
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, joints)| within_step(previous, joints, max_step))
+        .min_by(|(_, a), (_, b)| cost(previous, a).total_cmp(&cost(previous, b)))
+        .map(|(index, _)| index)
+}
+
+/// Chooses pose by pose the candidate cheapest to move to, the first pose from `seed`.
+/// Returns the chosen index per pose or the index of the first pose that cannot be reached
+/// within `max_step`.
+pub fn greedy(
+    layers: &[Vec<Joints>],
+    seed: &Joints,
+    max_step: f64,
+    cost: impl Fn(&Joints, &Joints) -> f64,
+) -> Result<Vec<usize>, usize> {
+    let mut previous = *seed;
+    let mut chosen = Vec::with_capacity(layers.len());
+    for (i, layer) in layers.iter().enumerate() {
+        let step = if i == 0 { f64::INFINITY } else { max_step };
+        let index = closest(layer, &previous, step, &cost).ok_or(i)?;
+        previous = layer[index];
+        chosen.push(index);
+    }
+    Ok(chosen)
+}
+
+/// Least `cost` path through the candidates of every pose, starting at `seed` and using
+/// only the candidates for which `allowed` holds. Consecutive candidates must lie within
+/// `max_step` of each other; the first pose is not bound to the seed. Returns the chosen
+/// index per pose or the index of the first pose that cannot be reached.
+pub fn shortest(
+    layers: &[Vec<Joints>],
+    seed: &Joints,
+    max_step: f64,
+    cost: impl Fn(&Joints, &Joints) -> f64,
+    allowed: impl Fn(usize, usize) -> bool,
+) -> Result<Vec<usize>, usize> {
+    let mut costs: Vec<Vec<f64>> = Vec::with_capacity(layers.len());
+    let mut parents: Vec<Vec<usize>> = Vec::with_capacity(layers.len());
+    for (i, layer) in layers.iter().enumerate() {
+        let mut layer_costs = vec![f64::INFINITY; layer.len()];
+        let mut layer_parents = vec![0; layer.len()];
+        for (s, joints) in layer.iter().enumerate() {
+            if !allowed(i, s) {
+                continue;
+            }
+            if i == 0 {
+                layer_costs[s] = cost(seed, joints);
+                continue;
+            }
+            for (p, previous) in layers[i - 1].iter().enumerate() {
+                let total = costs[i - 1][p] + cost(previous, joints);
+                if total < layer_costs[s] && within_step(previous, joints, max_step) {
+                    layer_costs[s] = total;
+                    layer_parents[s] = p;
+                }
+            }
+        }
+        if layer_costs.iter().all(|cost| cost.is_infinite()) {
+            return Err(i);
+        }
+        costs.push(layer_costs);
+        parents.push(layer_parents);
+    }
+    let Some(last) = costs.last() else {
+        return Ok(Vec::new());
+    };
+    let mut index = (0..last.len())
+        .min_by(|&a, &b| last[a].total_cmp(&last[b]))
+        .expect("the last layer has a finite cost");
+    let mut chosen = vec![0; layers.len()];
+    for i in (0..layers.len()).rev() {
+        chosen[i] = index;
+        index = parents[i][index];
+    }
+    Ok(chosen)
+}
+
+/// Chooses the path whose smallest limit margin is largest and, among those, the one with
+/// the least `cost`. Returns the chosen index per pose or the index of the first pose that
+/// cannot be reached within `max_step`.
+pub fn max_limit_margin(
+    layers: &[Vec<Joints>],
+    seed: &Joints,
+    limits: &[(f64, f64); 6],
+    max_step: f64,
+    cost: impl Fn(&Joints, &Joints) -> f64,
+) -> Result<Vec<usize>, usize> {
+    let margins: Vec<Vec<f64>> = layers
+        .iter()
+        .map(|layer| {
+            layer
+                .iter()
+                .map(|joints| limit_margin(joints, limits))
+                .collect()
+        })
+        .collect();
+    // Best smallest margin of any path ending at each candidate
+    let mut best: Vec<f64> = Vec::new();
+    for (i, layer) in layers.iter().enumerate() {
+        let next: Vec<f64> = layer
+            .iter()
+            .enumerate()
+            .map(|(s, joints)| {
+                let reached = if i == 0 {
+                    f64::INFINITY
+                } else {
+                    layers[i - 1]
+                        .iter()
+                        .zip(best.iter())
+                        .filter(|(previous, _)| within_step(previous, joints, max_step))
+                        .map(|(_, margin)| *margin)
+                        .fold(f64::NEG_INFINITY, f64::max)
+                };
+                reached.min(margins[i][s])
+            })
+            .collect();
+        if next.iter().all(|margin| *margin == f64::NEG_INFINITY) {
+            return Err(i);
+        }
+        best = next;
+    }
+    let bound = best.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    shortest(layers, seed, max_step, cost, |i, s| margins[i][s] >= bound)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: [(f64, f64); 6] = [(-3.0, 3.0); 6];
+
+    #[test]
+    fn test_turn_variants_stay_within_limits() {
+        let limits = [
+            (-3.0, 3.0),
+            (-3.0, 3.0),
+            (-3.0, 3.0),
+            (-7.0, 7.0),
+            (-3.0, 3.0),
+            (-7.0, 7.0),
+        ];
+        let variants = turn_variants(&[0.0, 0.0, 0.0, 1.0, 0.0, -1.0], &limits);
+        assert_eq!(variants.len(), 4);
+        assert!(variants
+            .iter()
+            .all(|joints| limit_margin(joints, &limits) >= 0.0));
+    }
+
+    #[test]
+    fn test_limit_margin_path_avoids_range_ends() {
+        // Greedy stays on the short branch that passes 0.1 rad from a limit
+        let near = |x: f64| [x, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let layers = vec![
+            vec![near(2.0), near(-1.0)],
+            vec![near(2.9), near(-0.5)],
+            vec![near(2.5), near(0.0)],
+        ];
+        let seed = near(2.0);
+        let travel = |a: &Joints, b: &Joints| PathCost::Travel.motion(a, b);
+        assert_eq!(
+            closest(&layers[1], &near(2.0), f64::INFINITY, travel),
+            Some(0)
+        );
+        assert_eq!(
+            closest(&layers[2], &near(2.9), f64::INFINITY, travel),
+            Some(0)
+        );
+        assert_eq!(
+            max_limit_margin(&layers, &seed, &LIMITS, f64::INFINITY, travel),
+            Ok(vec![1, 1, 1])
+        );
+        // Greedy commits to the first pose and gets stuck where the margin path does not
+        assert_eq!(closest(&layers[1], &near(2.0), 0.6, travel), None);
+        assert_eq!(
+            max_limit_margin(&layers, &seed, &LIMITS, 0.6, travel),
+            Ok(vec![1, 1, 1])
+        );
+        assert_eq!(
+            max_limit_margin(&layers, &seed, &LIMITS, 0.3, travel),
+            Err(1)
+        );
+    }
+
+    #[test]
+    fn test_shortest_path_costs() {
+        let seed = [0.0; 6];
+        let all = |_: usize, _: usize| true;
+        let shortest_by = |layers: &[Vec<Joints>], minimize: PathCost| {
+            shortest(
+                layers,
+                &seed,
+                f64::INFINITY,
+                |a, b| minimize.motion(a, b),
+                all,
+            )
+        };
+        // Greedy takes the nearer first solution and pays for it at the second pose
+        let layers = vec![
+            vec![
+                [0.1, 0.0, 0.0, 0.0, 0.0, 0.0],
+                [-0.2, 0.0, 0.0, 0.0, 0.0, 0.0],
+            ],
+            vec![[-1.0, 0.0, 0.0, 0.0, 0.0, 0.0]],
+        ];
+        let travel = |a: &Joints, b: &Joints| PathCost::Travel.motion(a, b);
+        assert_eq!(
+            greedy(&layers, &seed, f64::INFINITY, travel),
+            Ok(vec![0, 0])
+        );
+        assert_eq!(shortest_by(&layers, PathCost::Travel), Ok(vec![1, 0]));
+
+        let main_or_wrist = vec![vec![
+            [1.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.5, 0.0, 0.0],
+        ]];
+        assert_eq!(shortest_by(&main_or_wrist, PathCost::Travel), Ok(vec![1]));
+        assert_eq!(
+            shortest_by(&main_or_wrist, PathCost::WristMotion),
+            Ok(vec![0])
+        );
+        let spread_or_single = vec![vec![
+            [0.3, 0.3, 0.3, 0.0, 0.0, 0.0],
+            [0.5, 0.0, 0.0, 0.0, 0.0, 0.0],
+        ]];
+        assert_eq!(
+            shortest_by(&spread_or_single, PathCost::Travel),
+            Ok(vec![1])
+        );
+        assert_eq!(shortest_by(&spread_or_single, PathCost::Time), Ok(vec![0]));
+    }
+
+    #[test]
+    fn test_joint_move_deviation_of_an_arc() {
+        let arm = |joints: &Joints| Vector3::new(joints[0].cos(), joints[0].sin(), joints[1]);
+        let (start, end) = (
+            [0.0; 6],
+            [std::f64::consts::FRAC_PI_2, 0.0, 0.0, 0.0, 0.0, 0.0],
+        );
+        // The middle of the quarter circle is 1 - cos 45 from its chord
+        let deviation = joint_move_deviation(&start, &end, 8, arm);
+        assert!((deviation - (1.0 - std::f64::consts::FRAC_1_SQRT_2)).abs() < 1e-12);
+        let lift = [0.0, 0.5, 0.0, 0.0, 0.0, 0.0];
+        assert!(joint_move_deviation(&start, &lift, 8, arm) < 1e-12);
+    }
+}
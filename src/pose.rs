@@ -0,0 +1,59 @@
+use nalgebra::{Isometry3, Quaternion, Rotation3, Translation3, UnitQuaternion, Vector3};
+
+/// Pose as exchanged with Python: ([x, y, z], [w, x, y, z])
+pub type PyPose = ([f64; 3], [f64; 4]);
+
+/// Quaternions further than this from unit norm are reported when renormalized
+const NORM_TOLERANCE: f64 = 1e-4;
+
+pub fn to_isometry(translation: [f64; 3], rotation: [f64; 4]) -> Isometry3<f64> {
+    let quaternion = Quaternion::new(rotation[0], rotation[1], rotation[2], rotation[3]);
+    let norm = quaternion.norm();
+    if norm.is_nan() || (norm - 1.0).abs() > NORM_TOLERANCE {
+        log::warn!(
+            "Quaternion {:?} has norm {}, it was renormalized",
+            rotation,
+            norm
+        );
+    }
+    Isometry3::from_parts(
+        Translation3::from(translation),
+        UnitQuaternion::from_quaternion(quaternion),
+    )
+}
+
+pub fn from_isometry(pose: &Isometry3<f64>) -> PyPose {
+    // Storage order is (x, y, z, w)
+    let quat = [
+        pose.rotation.coords[3],
+        pose.rotation.coords[0],
+        pose.rotation.coords[1],
+        pose.rotation.coords[2],
+    ];
+    (pose.translation.vector.into(), quat)
+}
+
+/// Quaternion (w, x, y, z) of the frame whose z axis points along `z_axis` and whose x axis
+/// points as close as possible to `x_axis`. None if an axis is zero or both are parallel.
+pub fn rotation_from_axes(z_axis: [f64; 3], x_axis: [f64; 3]) -> Option<[f64; 4]> {
+    let z = Vector3::from(z_axis).try_normalize(1e-12)?;
+    let x = Vector3::from(x_axis);
+    let x = (x - z * x.dot(&z)).try_normalize(1e-9)?;
+    let rotation = Rotation3::from_basis_unchecked(&[x, z.cross(&x), z]);
+    let quaternion = UnitQuaternion::from_rotation_matrix(&rotation);
+    Some([quaternion.w, quaternion.i, quaternion.j, quaternion.k])
+}
+
+/// Homogeneous 4x4 matrix of a pose, as a list of rows
+pub fn to_matrix(pose: &Isometry3<f64>) -> [[f64; 4]; 4] {
+    let matrix = pose.to_homogeneous();
+    std::array::from_fn(|row| std::array::from_fn(|column| matrix[(row, column)]))
+}
+
+/// Position (m) and rotation (rad) distance between two poses
+pub fn pose_errors(a: &Isometry3<f64>, b: &Isometry3<f64>) -> (f64, f64) {
+    (
+        (a.translation.vector - b.translation.vector).norm(),
+        a.rotation.angle_to(&b.rotation),
+    )
+}
@@ -0,0 +1,98 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::columns::PoseTable;
+use crate::pose::{to_isometry, PyPose};
+use crate::validation;
+
+/// Difference of `b` from `a`: the translation [dx, dy, dz] (m, in the common frame) and the
+/// rotation vector (angle-axis, rad) of `b` relative to `a`, expressed in the common frame
+fn difference(a: &PyPose, b: &PyPose) -> ([f64; 3], [f64; 3]) {
+    let (a, b) = (to_isometry(a.0, a.1), to_isometry(b.0, b.1));
+    let translation = b.translation.vector - a.translation.vector;
+    let rotation = (b.rotation * a.rotation.inverse()).scaled_axis();
+    (translation.into(), rotation.into())
+}
+
+/// Translational (m) and rotational (angle-axis magnitude, degrees unless `radians`) error
+/// between two poses
+#[pyfunction]
+#[pyo3(signature = (p1, p2, radians=false))]
+pub fn pose_error(p1: PyPose, p2: PyPose, radians: bool) -> PyResult<(f64, f64)> {
+    validation::check_finite("p1", &p1, validation::is_finite_pose(&p1))?;
+    validation::check_finite("p2", &p2, validation::is_finite_pose(&p2))?;
+    let (translation, rotation) = difference(&p1, &p2);
+    let angle = norm(rotation);
+    Ok((
+        norm(translation),
+        if radians { angle } else { angle.to_degrees() },
+    ))
+}
+
+/// Compares two pose tables row by row, e.g. forward kinematics against a controller log or
+/// calibration results against measurements. Every row has dx, dy, dz and the
+/// translation_error (m), and rx, ry, rz and the rotation_error (degrees unless `radians`)
+/// of the rotation vector from the first pose to the second, both in the common frame.
+/// Rows with NaN or infinite values are None.
+#[pyfunction]
+#[pyo3(signature = (poses1, poses2, radians=false))]
+pub fn pose_errors<'py>(
+    py: Python<'py>,
+    poses1: PoseTable,
+    poses2: PoseTable,
+    radians: bool,
+) -> PyResult<Vec<Option<Bound<'py, PyDict>>>> {
+    if poses1.0.len() != poses2.0.len() {
+        return Err(PyValueError::new_err(format!(
+            "Expected tables of the same length, got {} and {} rows",
+            poses1.0.len(),
+            poses2.0.len()
+        )));
+    }
+    let angle = |angle: f64| if radians { angle } else { angle.to_degrees() };
+    poses1
+        .0
+        .iter()
+        .zip(&poses2.0)
+        .map(|(a, b)| {
+            if !(validation::is_finite_pose(a) && validation::is_finite_pose(b)) {
+                return Ok(None);
+            }
+            let (translation, rotation) = difference(a, b);
+            let row = PyDict::new(py);
+            for (column, value) in ["dx", "dy", "dz"].iter().zip(translation) {
+                row.set_item(column, value)?;
+            }
+            row.set_item("translation_error", norm(translation))?;
+            for (column, value) in ["rx", "ry", "rz"].iter().zip(rotation) {
+                row.set_item(column, angle(value))?;
+            }
+            row.set_item("rotation_error", angle(norm(rotation)))?;
+            Ok(Some(row))
+        })
+        .collect()
+}
+
+fn norm(vector: [f64; 3]) -> f64 {
+    vector.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pose_error() {
+        let a = ([0.1, 0.2, 0.3], [1.0, 0.0, 0.0, 0.0]);
+        // 90 degrees about z
+        let half = std::f64::consts::FRAC_1_SQRT_2;
+        let b = ([0.1, 0.2, 0.35], [half, 0.0, 0.0, half]);
+        let (translation, rotation) = pose_error(a, b, false).unwrap();
+        assert!((translation - 0.05).abs() < 1e-12);
+        assert!((rotation - 90.0).abs() < 1e-9);
+        let (_, rotation) = difference(&a, &b);
+        assert!((rotation[2] - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+        assert!(pose_error(a, ([f64::NAN, 0.0, 0.0], a.1), false).is_err());
+    }
+}
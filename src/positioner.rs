@@ -0,0 +1,321 @@
+use nalgebra::{Isometry3, Translation3, Unit, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::f64::consts::{PI, TAU};
+
+use crate::dict;
+use crate::pose::{from_isometry, to_isometry, PyPose};
+use crate::BaseConfig;
+
+/// A workpiece positioner: a chain of rotary axes carrying the part. Each axis turns about
+/// `direction` through `origin`, both in the frame left by the axes before it, starting
+/// from the base; the frame after the last axis is the part frame.
+#[pyclass(frozen)]
+pub struct Positioner {
+    pub base: Isometry3<f64>,
+    pub axes: Vec<(Vector3<f64>, Unit<Vector3<f64>>)>,
+    /// Joint limits as (min, max) per axis, in radians
+    pub limits: Vec<(f64, f64)>,
+    /// Whether joint values are exchanged in radians instead of degrees
+    pub radians: bool,
+}
+
+impl Positioner {
+    /// Pose of the part frame in the world frame at `joints` (radians)
+    pub fn transform(&self, joints: &[f64]) -> Isometry3<f64> {
+        self.axes
+            .iter()
+            .zip(joints)
+            .fold(self.base, |frame, ((origin, direction), joint)| {
+                frame
+                    * Translation3::from(*origin)
+                    * UnitQuaternion::from_axis_angle(direction, *joint)
+            })
+    }
+
+    fn joints_in(&self, joints: &[f64], radians: Option<bool>) -> Vec<f64> {
+        match radians.unwrap_or(self.radians) {
+            true => joints.to_vec(),
+            false => joints.iter().map(|joint| joint.to_radians()).collect(),
+        }
+    }
+
+    pub fn joints_out(&self, joints: &[f64], radians: Option<bool>) -> Vec<f64> {
+        match radians.unwrap_or(self.radians) {
+            true => joints.to_vec(),
+            false => joints.iter().map(|joint| joint.to_degrees()).collect(),
+        }
+    }
+}
+
+#[pymethods]
+impl Positioner {
+    /// Positioner with `axes` as (origin, direction) pairs, placed at `base_config` in the
+    /// world frame. `joint_limits` are (min, max) per axis in the joint units, ±180 degrees
+    /// by default.
+    #[new]
+    #[pyo3(signature = (axes, base_config=None, joint_limits=None, radians=false))]
+    fn new(
+        axes: Vec<([f64; 3], [f64; 3])>,
+        base_config: Option<BaseConfig>,
+        joint_limits: Option<Vec<(f64, f64)>>,
+        radians: bool,
+    ) -> PyResult<Self> {
+        if axes.is_empty() {
+            return Err(PyValueError::new_err(
+                "A positioner needs at least one axis",
+            ));
+        }
+        let axes = axes
+            .iter()
+            .enumerate()
+            .map(|(i, (origin, direction))| {
+                let direction = Vector3::from(*direction);
+                let finite = origin.iter().chain(direction.iter()).all(|x| x.is_finite());
+                match (finite, Unit::try_new(direction, 1e-9)) {
+                    (true, Some(direction)) => Ok((Vector3::from(*origin), direction)),
+                    _ => Err(PyValueError::new_err(format!(
+                        "Axis {} needs a finite origin and a non-zero direction",
+                        i + 1
+                    ))),
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let limits = match joint_limits {
+            None => vec![(-PI, PI); axes.len()],
+            Some(limits) if limits.len() == axes.len() => {
+                let scale = if radians { 1.0 } else { PI / 180.0 };
+                limits
+                    .iter()
+                    .map(
+                        |(min, max)| match min.is_finite() && max.is_finite() && min < max {
+                            true => Ok((min * scale, max * scale)),
+                            false => Err(PyValueError::new_err(format!(
+                                "Invalid positioner joint limits ({}, {})",
+                                min, max
+                            ))),
+                        },
+                    )
+                    .collect::<PyResult<_>>()?
+            }
+            Some(limits) => {
+                return Err(PyValueError::new_err(format!(
+                    "Got {} joint limits for {} axes",
+                    limits.len(),
+                    axes.len()
+                )))
+            }
+        };
+        let base = base_config.map_or(Isometry3::identity(), |base| {
+            to_isometry(base.translation, base.rotation)
+        });
+        Ok(Positioner {
+            base,
+            axes,
+            limits,
+            radians,
+        })
+    }
+
+    /// Number of axes
+    #[getter]
+    fn n_axes(&self) -> usize {
+        self.axes.len()
+    }
+
+    /// Pose of the part frame in the world frame at `joints`
+    #[pyo3(signature = (joints, radians=None))]
+    fn forward(&self, joints: Vec<f64>, radians: Option<bool>) -> PyResult<PyPose> {
+        if joints.len() != self.axes.len() || !joints.iter().all(|x| x.is_finite()) {
+            return Err(PyValueError::new_err(format!(
+                "Expected {} finite positioner joints, got {:?}",
+                self.axes.len(),
+                joints
+            )));
+        }
+        Ok(from_isometry(
+            &self.transform(&self.joints_in(&joints, radians)),
+        ))
+    }
+
+    /// The positioner as a dict of plain values with the constructor's keyword names
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let axes: Vec<([f64; 3], [f64; 3])> = self
+            .axes
+            .iter()
+            .map(|(origin, direction)| ((*origin).into(), direction.into_inner().into()))
+            .collect();
+        let limits: Vec<(f64, f64)> = self
+            .limits
+            .iter()
+            .map(|&(min, max)| {
+                let limits = self.joints_out(&[min, max], None);
+                (limits[0], limits[1])
+            })
+            .collect();
+        let (translation, rotation) = from_isometry(&self.base);
+        let dict = PyDict::new(py);
+        dict.set_item("axes", axes)?;
+        dict.set_item(
+            "base_config",
+            BaseConfig::new(translation, rotation).to_dict(py)?,
+        )?;
+        dict.set_item("joint_limits", limits)?;
+        dict.set_item("radians", self.radians)?;
+        Ok(dict)
+    }
+
+    /// Positioner from a dict as `to_dict` returns it
+    #[staticmethod]
+    pub fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(
+            data,
+            &["axes", "base_config", "joint_limits", "radians"],
+            "Positioner",
+        )?;
+        let base_config: Option<Bound<'_, PyDict>> = dict::optional(data, "base_config")?;
+        // Lists as well as tuples, as read back from JSON
+        let axes: Vec<[[f64; 3]; 2]> = dict::required(data, "axes", "Positioner")?;
+        let limits: Option<Vec<[f64; 2]>> = dict::optional(data, "joint_limits")?;
+        Positioner::new(
+            axes.iter()
+                .map(|[origin, direction]| (*origin, *direction))
+                .collect(),
+            base_config.map(|d| BaseConfig::from_dict(&d)).transpose()?,
+            limits.map(|limits| limits.iter().map(|[min, max]| (*min, *max)).collect()),
+            dict::optional(data, "radians")?.unwrap_or(false),
+        )
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Positioner({} axes)", self.axes.len())
+    }
+}
+
+/// `samples` joint values per axis spread over the limits, a full turn without repeating
+/// its end, and every combination of them
+pub fn grid(limits: &[(f64, f64)], samples: usize) -> Vec<Vec<f64>> {
+    let values: Vec<Vec<f64>> = limits
+        .iter()
+        .map(|(min, max)| {
+            let range = max - min;
+            let steps = if range >= TAU - 1e-9 {
+                samples
+            } else {
+                samples.max(2) - 1
+            };
+            (0..samples)
+                .map(|i| match samples {
+                    1 => 0.5 * (min + max),
+                    _ => min + range * i as f64 / steps as f64,
+                })
+                .collect()
+        })
+        .collect();
+    values.iter().fold(vec![vec![]], |combinations, axis| {
+        combinations
+            .iter()
+            .flat_map(|combination| {
+                axis.iter().map(move |value| {
+                    let mut next = combination.clone();
+                    next.push(*value);
+                    next
+                })
+            })
+            .collect()
+    })
+}
+
+/// Least total cost choice of one state per pose, the cost of a state given per pose by
+/// `nodes` (None where the state is not allowed) and moving between states of consecutive
+/// poses costing `edge(pose, from, to)`. Returns the chosen state per pose or the index of
+/// the first pose without an allowed state.
+pub fn optimal(
+    nodes: &[Vec<Option<f64>>],
+    edge: impl Fn(usize, usize, usize) -> f64,
+) -> Result<Vec<usize>, usize> {
+    let mut costs: Vec<Vec<f64>> = Vec::with_capacity(nodes.len());
+    let mut parents: Vec<Vec<usize>> = Vec::with_capacity(nodes.len());
+    for (i, layer) in nodes.iter().enumerate() {
+        let mut layer_costs = vec![f64::INFINITY; layer.len()];
+        let mut layer_parents = vec![0; layer.len()];
+        for (s, node) in layer.iter().enumerate() {
+            let Some(node) = node else {
+                continue;
+            };
+            if i == 0 {
+                layer_costs[s] = *node;
+                continue;
+            }
+            for (p, previous) in costs[i - 1].iter().enumerate() {
+                let total = previous + edge(i, p, s) + node;
+                if total < layer_costs[s] {
+                    layer_costs[s] = total;
+                    layer_parents[s] = p;
+                }
+            }
+        }
+        if layer_costs.iter().all(|cost| cost.is_infinite()) {
+            return Err(i);
+        }
+        costs.push(layer_costs);
+        parents.push(layer_parents);
+    }
+    let Some(last) = costs.last() else {
+        return Ok(Vec::new());
+    };
+    let mut index = (0..last.len())
+        .min_by(|&a, &b| last[a].total_cmp(&last[b]))
+        .expect("the last layer has a finite cost");
+    let mut chosen = vec![0; nodes.len()];
+    for i in (0..nodes.len()).rev() {
+        chosen[i] = index;
+        index = parents[i][index];
+    }
+    Ok(chosen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tilt_rotate_positioner() {
+        let positioner = Positioner {
+            base: Isometry3::translation(1.0, 0.0, 0.5),
+            axes: vec![
+                (Vector3::zeros(), Vector3::x_axis()),
+                (Vector3::new(0.0, 0.0, 0.2), Vector3::z_axis()),
+            ],
+            limits: vec![(-PI / 2.0, PI / 2.0), (-PI, PI)],
+            radians: true,
+        };
+        // Tilted by 90 degrees the table top faces -y, 0.2 m off the tilt axis
+        let part = positioner.transform(&[PI / 2.0, 0.0]);
+        assert!((part.translation.vector - Vector3::new(1.0, -0.2, 0.5)).norm() < 1e-12);
+        assert!((part.rotation * Vector3::z() - Vector3::new(0.0, -1.0, 0.0)).norm() < 1e-12);
+
+        let grid = grid(&positioner.limits, 4);
+        assert_eq!(grid.len(), 16);
+        assert_eq!(grid[0], [-PI / 2.0, -PI]);
+        // The full turn of the second axis does not repeat -180 as +180
+        assert!((grid[3][1] - PI / 2.0).abs() < 1e-12);
+        assert!((grid[15][0] - PI / 2.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_optimal_trades_node_and_edge_costs() {
+        // Two states per pose; switching costs 1
+        let nodes = vec![
+            vec![Some(0.0), Some(0.5)],
+            vec![Some(0.6), Some(0.0)],
+            vec![Some(0.0), None],
+        ];
+        let switch = |_: usize, a: usize, b: usize| if a == b { 0.0 } else { 1.0 };
+        assert_eq!(optimal(&nodes, switch), Ok(vec![0, 0, 0]));
+        let free = |_: usize, _: usize, _: usize| 0.0;
+        assert_eq!(optimal(&nodes, free), Ok(vec![0, 1, 0]));
+        assert_eq!(optimal(&[vec![None]], free), Err(0));
+    }
+}
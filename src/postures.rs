@@ -0,0 +1,76 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::collections::BTreeMap;
+
+use crate::validation;
+
+/// Named joint postures of a robot such as home, mastering and service, in radians
+pub type Postures = BTreeMap<String, Joints>;
+
+/// Where a motion starts: joints, or the name of a posture of the robot
+#[derive(Clone, Debug, PartialEq)]
+pub enum Start {
+    Joints([f64; 6]),
+    Posture(String),
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for Start {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        match obj.extract::<String>() {
+            Ok(name) => Ok(Start::Posture(name)),
+            Err(_) => Ok(Start::Joints(obj.extract()?)),
+        }
+    }
+}
+
+/// Checks a posture before it is stored: a name, finite joints (radians) within the joint
+/// limits if the robot has them
+pub fn check(name: &str, joints: &Joints, limits: Option<&[(f64, f64); 6]>) -> PyResult<()> {
+    if name.trim().is_empty() {
+        return Err(PyValueError::new_err("A posture needs a name"));
+    }
+    validation::check_joints(name, joints)?;
+    let beyond = limits
+        .and_then(|limits| (0..6).find(|&k| !(limits[k].0..=limits[k].1).contains(&joints[k])));
+    match beyond {
+        Some(k) => Err(PyValueError::new_err(format!(
+            "J{} of posture '{}' is beyond the joint limits",
+            k + 1,
+            name
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The posture called `name`, or an error listing the stored ones
+pub fn get(postures: &Postures, name: &str) -> PyResult<Joints> {
+    postures.get(name).copied().ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "Unknown posture '{}', the robot has {:?}",
+            name,
+            postures.keys().collect::<Vec<_>>()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_posture_checks() {
+        let home = [0.0, -0.5, 0.5, 0.0, 0.9, 0.0];
+        let limits = [(-1.0, 1.0); 6];
+        assert!(check("home", &home, Some(&limits)).is_ok());
+        assert!(check(" ", &home, None).is_err());
+        assert!(check("service", &[0.0, 0.0, 0.0, 0.0, 2.0, 0.0], Some(&limits)).is_err());
+        assert!(check("service", &[0.0, 0.0, 0.0, 0.0, 2.0, 0.0], None).is_ok());
+
+        let postures = Postures::from([("home".to_string(), home)]);
+        assert_eq!(get(&postures, "home").unwrap(), home);
+        assert!(get(&postures, "mastering").is_err());
+    }
+}
@@ -0,0 +1,1125 @@
+use nalgebra::Isometry3;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::sync::RwLock;
+
+use crate::columns::{self, JointTable, PoseTable};
+use crate::dict;
+use crate::frames::{Frames, WORLD};
+use crate::krl;
+use crate::ls;
+use crate::ordering::SolutionOrder;
+use crate::path;
+use crate::pose::{from_isometry, to_isometry, PyPose};
+use crate::postures::Start;
+use crate::rapid;
+use crate::rows::{Rows, JOINT_COLUMNS, POSE_COLUMNS};
+use crate::validation;
+use crate::{Robot, ToolConfig};
+
+/// Name of the robot's tool in exported programs, for targets without a tool
+const ROBOT_TOOL: &str = "robot_tool";
+
+/// Keys of a target dict, as `Program.get` returns and `Program.from_dict` reads them
+const TARGET_KEYS: [&str; 9] = [
+    "name",
+    "pose",
+    "joints",
+    "frame",
+    "tool",
+    "configuration",
+    "motion",
+    "speed",
+    "zone",
+];
+
+/// How the robot moves to a target
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Motion {
+    /// Interpolated in joint space (MoveJ, PTP)
+    Joint,
+    /// Along a straight line of the TCP (MoveL, LIN)
+    Linear,
+}
+
+impl Motion {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "joint" => Ok(Motion::Joint),
+            "linear" => Ok(Motion::Linear),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown motion '{}', expected 'joint' or 'linear'",
+                name
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Motion::Joint => "joint",
+            Motion::Linear => "linear",
+        }
+    }
+}
+
+/// Where a target is: a TCP pose in the frame of the target, or joints in radians
+#[derive(Clone, Debug, PartialEq)]
+pub enum Target {
+    Pose(Isometry3<f64>),
+    Joints(Joints),
+}
+
+/// A named target of a program with its motion metadata
+#[derive(Clone, Debug, PartialEq)]
+pub struct Waypoint {
+    pub name: String,
+    pub target: Target,
+    /// Frame the pose is given in, e.g. a workobject
+    pub frame: String,
+    /// Tool of the program the pose is the TCP of, the tool of the robot if None
+    pub tool: Option<String>,
+    /// ABB axis configuration (cf1, cf4, cf6, cfx) the target is taught in
+    pub configuration: Option<(i32, i32, i32, u8)>,
+    pub motion: Option<Motion>,
+    /// TCP speed (m/s)
+    pub speed: Option<f64>,
+    /// Zone (blend) radius (m), 0 for a stop point
+    pub zone: Option<f64>,
+}
+
+impl Waypoint {
+    /// Checks and builds a waypoint; `joints` are in radians if `radians`, else degrees
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        name: &str,
+        pose: Option<PyPose>,
+        joints: Option<[f64; 6]>,
+        frame: &str,
+        configuration: Option<(i32, i32, i32, u8)>,
+        motion: Option<Motion>,
+        speed: Option<f64>,
+        zone: Option<f64>,
+        radians: bool,
+    ) -> PyResult<Self> {
+        let target = match (pose, joints) {
+            (Some(pose), None) => {
+                validation::check_pose(&pose)?;
+                Target::Pose(to_isometry(pose.0, pose.1))
+            }
+            (None, Some(joints)) => {
+                validation::check_joints("joints", &joints)?;
+                Target::Joints(match radians {
+                    true => joints,
+                    false => joints.map(f64::to_radians),
+                })
+            }
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "Target '{}' needs either a pose or joints",
+                    name
+                )))
+            }
+        };
+        if let Some(speed) = speed {
+            validation::check_positive("speed", speed)?;
+        }
+        if let Some(zone) = zone {
+            validation::check_finite("zone", &zone, zone.is_finite() && zone >= 0.0)?;
+        }
+        if configuration.is_some_and(|(_, _, _, cfx)| cfx > 7) {
+            return Err(PyValueError::new_err(format!(
+                "Invalid configuration {:?} of target '{}', CFX goes from 0 to 7",
+                configuration, name
+            )));
+        }
+        Ok(Waypoint {
+            name: name.to_string(),
+            target,
+            frame: frame.to_string(),
+            tool: None,
+            configuration,
+            motion,
+            speed,
+            zone,
+        })
+    }
+
+    fn from_dict(data: &Bound<'_, PyDict>, radians: bool) -> PyResult<Self> {
+        dict::check_keys(data, &TARGET_KEYS, "a program target")?;
+        let motion: Option<String> = dict::optional(data, "motion")?;
+        let waypoint = Waypoint::new(
+            &dict::required::<String>(data, "name", "A program target")?,
+            dict::optional(data, "pose")?,
+            dict::optional(data, "joints")?,
+            &dict::optional(data, "frame")?.unwrap_or_else(|| WORLD.to_string()),
+            dict::optional(data, "configuration")?,
+            motion.as_deref().map(Motion::parse).transpose()?,
+            dict::optional(data, "speed")?,
+            dict::optional(data, "zone")?,
+            radians,
+        )?;
+        Ok(Waypoint {
+            tool: dict::optional(data, "tool")?,
+            ..waypoint
+        })
+    }
+
+    /// Joints of a joint target in radians or degrees
+    fn joints_out(&self, radians: bool) -> Option<[f64; 6]> {
+        match &self.target {
+            Target::Joints(joints) if radians => Some(*joints),
+            Target::Joints(joints) => Some(joints.map(f64::to_degrees)),
+            Target::Pose(_) => None,
+        }
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>, radians: bool) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("name", &self.name)?;
+        let pose = match &self.target {
+            Target::Pose(pose) => Some(from_isometry(pose)),
+            Target::Joints(_) => None,
+        };
+        dict.set_item("pose", pose)?;
+        dict.set_item("joints", self.joints_out(radians))?;
+        dict.set_item("frame", &self.frame)?;
+        dict.set_item("tool", &self.tool)?;
+        dict.set_item("configuration", self.configuration)?;
+        dict.set_item("motion", self.motion.map(|motion| motion.name()))?;
+        dict.set_item("speed", self.speed)?;
+        dict.set_item("zone", self.zone)?;
+        Ok(dict)
+    }
+}
+
+/// A waypoint resolved by a robot: its TCP pose in the world frame, the joints reaching it
+/// (radians) and what keeps it from being reached as taught
+pub struct Solved {
+    pub pose: Option<Isometry3<f64>>,
+    pub joints: Option<Joints>,
+    pub issue: Option<&'static str>,
+}
+
+/// A robot job: named targets, as poses in named frames or as joints, with their tool, axis
+/// configuration, motion, speed and zone, in the order they are visited. Targets can be
+/// added while the program is shared between threads.
+#[pyclass(frozen)]
+pub struct Program {
+    pub name: String,
+    pub frames: Py<Frames>,
+    /// Named tools as their TCP in the flange frame
+    pub tools: RwLock<Vec<(String, Isometry3<f64>)>>,
+    pub waypoints: RwLock<Vec<Waypoint>>,
+    /// Whether joint values are exchanged in radians instead of degrees
+    pub radians: bool,
+}
+
+impl Program {
+    pub fn with(
+        name: &str,
+        frames: Py<Frames>,
+        tools: Vec<(String, Isometry3<f64>)>,
+        waypoints: Vec<Waypoint>,
+        radians: bool,
+    ) -> Self {
+        Program {
+            name: name.to_string(),
+            frames,
+            tools: RwLock::new(tools),
+            waypoints: RwLock::new(waypoints),
+            radians,
+        }
+    }
+
+    /// A copy of the waypoints
+    pub fn waypoints(&self) -> Vec<Waypoint> {
+        self.waypoints
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// A copy of the tools
+    pub fn tools(&self) -> Vec<(String, Isometry3<f64>)> {
+        self.tools.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// TCP of the tool `name` in the flange frame
+    pub fn tool(&self, name: &str) -> PyResult<Isometry3<f64>> {
+        let tools = self.tools.read().unwrap_or_else(|e| e.into_inner());
+        tools
+            .iter()
+            .find(|(tool, _)| tool == name)
+            .map(|(_, tcp)| *tcp)
+            .ok_or_else(|| {
+                let names: Vec<&str> = tools.iter().map(|(tool, _)| tool.as_str()).collect();
+                PyValueError::new_err(format!(
+                    "Unknown tool '{}', the tools are {}",
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+
+    /// Adds the tool `name`, or replaces it if it exists
+    pub fn set_tool(&self, name: &str, tcp: Isometry3<f64>) {
+        let mut tools = self.tools.write().unwrap_or_else(|e| e.into_inner());
+        match tools.iter_mut().find(|(tool, _)| tool == name) {
+            Some((_, existing)) => *existing = tcp,
+            None => tools.push((name.to_string(), tcp)),
+        }
+    }
+
+    /// Appends a waypoint, checking that its frame and tool exist
+    pub fn push(&self, waypoint: Waypoint) -> PyResult<()> {
+        self.frames.get().between(&waypoint.frame, WORLD)?;
+        if let Some(tool) = &waypoint.tool {
+            self.tool(tool)?;
+        }
+        self.waypoints
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(waypoint);
+        Ok(())
+    }
+
+    /// Program of an imported module: its frames added to `frames`, its tools and moves
+    fn from_module(
+        py: Python<'_>,
+        module: rapid::Module,
+        frames: Option<Py<Frames>>,
+        radians: bool,
+    ) -> PyResult<Program> {
+        let program = Program::new(py, &module.name, frames, radians)?;
+        for (name, pose) in &module.work_objects {
+            program.frames.get().add(name, from_isometry(pose), WORLD)?;
+        }
+        for (name, tcp) in module.tools {
+            program.set_tool(&name, tcp);
+        }
+        for waypoint in module.waypoints {
+            program.push(waypoint)?;
+        }
+        Ok(program)
+    }
+
+    /// The targets with the tools and frames they use, frames as their pose in the world
+    /// frame, for writing a controller program. Targets without a tool are on the tool of
+    /// `robot`, exported as robot_tool.
+    fn export_module(&self, robot: Option<&Robot>) -> PyResult<rapid::Module> {
+        let mut waypoints = self.waypoints();
+        let mut tools: Vec<(String, Isometry3<f64>)> = Vec::new();
+        let mut work_objects: Vec<(String, Isometry3<f64>)> = Vec::new();
+        for waypoint in &mut waypoints {
+            let tool = match (&waypoint.tool, robot) {
+                (Some(tool), _) => tool.clone(),
+                (None, Some(robot)) => {
+                    if !tools.iter().any(|(name, _)| name == ROBOT_TOOL) {
+                        tools.push((ROBOT_TOOL.to_string(), robot.tool()));
+                    }
+                    ROBOT_TOOL.to_string()
+                }
+                (None, None) => {
+                    return Err(PyValueError::new_err(format!(
+                        "Target '{}' has no tool, give the robot whose tool it is on",
+                        waypoint.name
+                    )))
+                }
+            };
+            if !tools.iter().any(|(name, _)| *name == tool) {
+                tools.push((tool.clone(), self.tool(&tool)?));
+            }
+            waypoint.tool = Some(tool);
+            let frame = &waypoint.frame;
+            if frame != WORLD && !work_objects.iter().any(|(name, _)| name == frame) {
+                let pose = self.frames.get().between(frame, WORLD)?;
+                work_objects.push((frame.clone(), pose));
+            }
+        }
+        Ok(rapid::Module {
+            name: self.name.clone(),
+            tools,
+            work_objects,
+            waypoints,
+        })
+    }
+
+    /// Solves every waypoint with `robot`, each from the joints of the one before, the
+    /// first from `current` (radians) if given. Joint targets beyond the joint limits,
+    /// poses out of reach and poses only reachable in another configuration than taught
+    /// are reported as issues. Poses taught with another tool than the robot's are solved
+    /// for the same flange pose.
+    pub(crate) fn solve_with(
+        &self,
+        robot: &Robot,
+        current: Option<Joints>,
+    ) -> PyResult<Vec<Solved>> {
+        let frames = self.frames.get();
+        let options = robot.inverse_options(SolutionOrder::Distance, None, true, Some(true));
+        let mut previous = current;
+        self.waypoints()
+            .iter()
+            .map(|waypoint| {
+                let taught = |joints: &Joints| {
+                    waypoint
+                        .configuration
+                        .is_none_or(|wanted| robot.axis_configuration_radians(joints) == wanted)
+                };
+                // From the TCP of the waypoint's tool to the TCP of the robot's
+                let retool = match &waypoint.tool {
+                    Some(tool) => self.tool(tool)?.inverse() * robot.tool(),
+                    None => Isometry3::identity(),
+                };
+                let solved = match &waypoint.target {
+                    Target::Joints(joints) => Solved {
+                        pose: Some(robot.forward_radians(joints) * retool.inverse()),
+                        joints: Some(*joints),
+                        issue: match (robot.within_limits(joints), taught(joints)) {
+                            (false, _) => Some("joint_limits"),
+                            (true, false) => Some("configuration"),
+                            (true, true) => None,
+                        },
+                    },
+                    Target::Pose(pose) => {
+                        let world = frames.between(&waypoint.frame, WORLD)? * pose;
+                        let solutions = robot.solve_radians(
+                            from_isometry(&(world * retool)),
+                            previous,
+                            &options,
+                        );
+                        let joints = solutions.iter().find(|joints| taught(joints)).copied();
+                        Solved {
+                            pose: Some(world),
+                            joints,
+                            issue: match (joints, solutions.is_empty()) {
+                                (Some(_), _) => None,
+                                (None, true) => Some("unreachable"),
+                                (None, false) => Some("configuration"),
+                            },
+                        }
+                    }
+                };
+                previous = solved.joints.or(previous);
+                Ok(solved)
+            })
+            .collect()
+    }
+}
+
+#[pymethods]
+impl Program {
+    /// Empty program. Target frames are looked up in `frames`, a new registry with only
+    /// the world frame by default.
+    #[new]
+    #[pyo3(signature = (name="main", frames=None, radians=false))]
+    fn new(
+        py: Python<'_>,
+        name: &str,
+        frames: Option<Py<Frames>>,
+        radians: bool,
+    ) -> PyResult<Self> {
+        let frames = match frames {
+            Some(frames) => frames,
+            None => Py::new(py, Frames::with([]))?,
+        };
+        Ok(Program::with(name, frames, Vec::new(), Vec::new(), radians))
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The frames registry targets are given in
+    #[getter]
+    fn frames(&self, py: Python<'_>) -> Py<Frames> {
+        self.frames.clone_ref(py)
+    }
+
+    /// Adds the tool `name` with its TCP in the flange frame, or replaces it if it exists
+    fn add_tool(&self, name: &str, tool_config: ToolConfig) -> PyResult<()> {
+        let tcp = (tool_config.translation, tool_config.rotation);
+        validation::check_pose(&tcp)?;
+        self.set_tool(name, to_isometry(tcp.0, tcp.1));
+        Ok(())
+    }
+
+    /// The tools by name
+    #[getter(tools)]
+    fn tools_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        for (name, tcp) in self.tools() {
+            let (translation, rotation) = from_isometry(&tcp);
+            dict.set_item(name, ToolConfig::new(translation, rotation))?;
+        }
+        Ok(dict)
+    }
+
+    /// Appends a target, given as a TCP `pose` of `tool` (one of the program's, the
+    /// robot's tool by default) in `frame` or as `joints`, with the axis `configuration`
+    /// (cf1, cf4, cf6, cfx) it is taught in, its `motion` ("joint" or "linear"), the TCP
+    /// `speed` (m/s) and the `zone` radius (m, 0 for a stop point). Names need not be
+    /// unique, a program may visit a target again.
+    #[pyo3(signature = (
+        name,
+        pose=None,
+        joints=None,
+        frame=WORLD,
+        tool=None,
+        configuration=None,
+        motion=None,
+        speed=None,
+        zone=None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn add(
+        &self,
+        name: &str,
+        pose: Option<PyPose>,
+        joints: Option<[f64; 6]>,
+        frame: &str,
+        tool: Option<String>,
+        configuration: Option<(i32, i32, i32, u8)>,
+        motion: Option<&str>,
+        speed: Option<f64>,
+        zone: Option<f64>,
+    ) -> PyResult<()> {
+        let motion = motion.map(Motion::parse).transpose()?;
+        let waypoint = Waypoint::new(
+            name,
+            pose,
+            joints,
+            frame,
+            configuration,
+            motion,
+            speed,
+            zone,
+            self.radians,
+        )?;
+        self.push(Waypoint { tool, ..waypoint })
+    }
+
+    /// Appends a target per row of a table (a DataFrame, dict of columns or list of row
+    /// dicts), such as a solved table with speed and zone columns attached. Rows are poses
+    /// if the table has pose columns, else joints; optional columns name, frame, tool,
+    /// motion, speed, zone and CF1, CF4, CF6, CFX give them per row, `frame`, `tool`,
+    /// `motion`, `speed` and `zone` where a row has none. Unnamed rows are called p1, p2, ...
+    /// after their position in the program. Returns the number of targets added.
+    #[pyo3(signature = (rows, frame=WORLD, tool=None, motion=None, speed=None, zone=None))]
+    fn add_rows(
+        &self,
+        rows: &Bound<'_, PyAny>,
+        frame: &str,
+        tool: Option<String>,
+        motion: Option<&str>,
+        speed: Option<f64>,
+        zone: Option<f64>,
+    ) -> PyResult<usize> {
+        let targets: Vec<(Option<PyPose>, Option<[f64; 6]>)> = match rows.extract::<PoseTable>() {
+            Ok(PoseTable(poses)) => poses.into_iter().map(|pose| (Some(pose), None)).collect(),
+            Err(error) => match rows.extract::<JointTable>() {
+                Ok(JointTable(joints)) => joints
+                    .into_iter()
+                    .map(|joints| (None, Some(joints)))
+                    .collect(),
+                Err(_) => {
+                    return Err(PyValueError::new_err(format!(
+                        "Rows need pose or joint columns: {}",
+                        error
+                    )))
+                }
+            },
+        };
+        let names = columns::optional_column::<String>(rows, &["name", "target"])?;
+        let frames = columns::optional_column::<String>(rows, &["frame"])?;
+        let tools = columns::optional_column::<String>(rows, &["tool"])?;
+        let motions = columns::optional_column::<String>(rows, &["motion"])?;
+        let speeds = columns::optional_column::<f64>(rows, &["speed", "velocity"])?;
+        let zones = columns::optional_column::<f64>(rows, &["zone", "blend", "blend_radius"])?;
+        let [cf1, cf4, cf6, cfx] =
+            ["cf1", "cf4", "cf6", "cfx"].map(|name| columns::optional_column::<i32>(rows, &[name]));
+        let (cf1, cf4, cf6, cfx) = (cf1?, cf4?, cf6?, cfx?);
+        fn at<T: Clone>(column: &Option<Vec<Option<T>>>, row: usize) -> Option<T> {
+            column
+                .as_ref()
+                .and_then(|values| values.get(row).cloned().flatten())
+        }
+        let start = self.__len__();
+        let waypoints = targets
+            .into_iter()
+            .enumerate()
+            .map(|(row, (pose, joints))| {
+                let name = at(&names, row).unwrap_or_else(|| format!("p{}", start + row + 1));
+                let configuration =
+                    match (at(&cf1, row), at(&cf4, row), at(&cf6, row), at(&cfx, row)) {
+                        (Some(cf1), Some(cf4), Some(cf6), Some(cfx)) => {
+                            let cfx = u8::try_from(cfx).map_err(|_| {
+                                PyValueError::new_err(format!("Invalid CFX {} in row {}", cfx, row))
+                            })?;
+                            Some((cf1, cf4, cf6, cfx))
+                        }
+                        _ => None,
+                    };
+                let motion = at(&motions, row).or(motion.map(String::from));
+                let waypoint = Waypoint::new(
+                    &name,
+                    pose,
+                    joints,
+                    &at(&frames, row).unwrap_or(frame.to_string()),
+                    configuration,
+                    motion.as_deref().map(Motion::parse).transpose()?,
+                    at(&speeds, row).or(speed),
+                    at(&zones, row).or(zone),
+                    self.radians,
+                )?;
+                let tool = at(&tools, row).or(tool.clone());
+                if let Some(tool) = &tool {
+                    self.tool(tool)?;
+                }
+                self.frames.get().between(&waypoint.frame, WORLD)?;
+                Ok(Waypoint { tool, ..waypoint })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let added = waypoints.len();
+        for waypoint in waypoints {
+            self.push(waypoint)?;
+        }
+        Ok(added)
+    }
+
+    /// Names of the targets in the order they are visited
+    fn names(&self) -> Vec<String> {
+        self.waypoints()
+            .into_iter()
+            .map(|waypoint| waypoint.name)
+            .collect()
+    }
+
+    /// The first target called `name` as a dict with name, pose, joints, frame, tool,
+    /// configuration, motion, speed and zone
+    fn get<'py>(&self, py: Python<'py>, name: &str) -> PyResult<Bound<'py, PyDict>> {
+        self.waypoints()
+            .iter()
+            .find(|waypoint| waypoint.name == name)
+            .ok_or_else(|| PyValueError::new_err(format!("No target '{}' in the program", name)))?
+            .to_dict(py, self.radians)
+    }
+
+    /// The targets as rows for a DataFrame: name, frame, tool, motion, speed, zone, the pose
+    /// columns (x, y, z, qw, qx, qy, qz, None for joint targets), the joints (j1..j6, None
+    /// for pose targets) and the configuration (CF1, CF4, CF6, CFX)
+    fn rows<'py>(&self, py: Python<'py>) -> PyResult<Rows<'py>> {
+        self.waypoints()
+            .iter()
+            .map(|waypoint| {
+                let row = PyDict::new(py);
+                row.set_item("name", &waypoint.name)?;
+                row.set_item("frame", &waypoint.frame)?;
+                row.set_item("tool", &waypoint.tool)?;
+                row.set_item("motion", waypoint.motion.map(|motion| motion.name()))?;
+                row.set_item("speed", waypoint.speed)?;
+                row.set_item("zone", waypoint.zone)?;
+                let pose = match &waypoint.target {
+                    Target::Pose(pose) => {
+                        let (translation, rotation) = from_isometry(pose);
+                        translation
+                            .iter()
+                            .chain(&rotation)
+                            .map(|x| Some(*x))
+                            .collect()
+                    }
+                    Target::Joints(_) => vec![None; 7],
+                };
+                for (column, value) in POSE_COLUMNS.iter().zip(pose) {
+                    row.set_item(column, value)?;
+                }
+                let joints = waypoint.joints_out(self.radians);
+                for (i, column) in JOINT_COLUMNS.iter().enumerate() {
+                    row.set_item(column, joints.map(|joints| joints[i]))?;
+                }
+                let configuration = waypoint.configuration;
+                row.set_item("CF1", configuration.map(|c| c.0))?;
+                row.set_item("CF4", configuration.map(|c| c.1))?;
+                row.set_item("CF6", configuration.map(|c| c.2))?;
+                row.set_item("CFX", configuration.map(|c| c.3))?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Solves the program with `robot`, every target from the joints of the one before
+    /// (the first from `current_joints`, joints or the name of a robot posture such as
+    /// "home", if given) and in its taught configuration if it
+    /// has one. Returns one row per target with its name, tool, motion, speed and zone, the
+    /// TCP pose in the world frame (x, y, z, qw, qx, qy, qz), the joints (j1..j6, None if
+    /// not reached), their
+    /// configuration (CF1, CF4, CF6, CFX), whether the target is reached as taught and
+    /// the issue if not, as `validate` reports it. Joints are in the units of the robot.
+    #[pyo3(signature = (robot, current_joints=None, radians=None))]
+    fn solve<'py>(
+        &self,
+        py: Python<'py>,
+        robot: &Robot,
+        current_joints: Option<Start>,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        let current = robot.start_joints(current_joints.as_ref(), radians)?;
+        let solved = py.detach(|| self.solve_with(robot, current))?;
+        self.waypoints()
+            .iter()
+            .zip(solved)
+            .map(|(waypoint, solved)| {
+                let row = PyDict::new(py);
+                row.set_item("name", &waypoint.name)?;
+                // Carried along, for add_rows to take the solved table back
+                row.set_item("tool", &waypoint.tool)?;
+                row.set_item("motion", waypoint.motion.map(|motion| motion.name()))?;
+                row.set_item("speed", waypoint.speed)?;
+                row.set_item("zone", waypoint.zone)?;
+                let pose = solved.pose.map(|pose| from_isometry(&pose));
+                for (i, column) in POSE_COLUMNS.iter().enumerate() {
+                    let value = pose.map(|(translation, rotation)| match i {
+                        0..=2 => translation[i],
+                        _ => rotation[i - 3],
+                    });
+                    row.set_item(column, value)?;
+                }
+                let joints = solved
+                    .joints
+                    .map(|joints| robot.joints_out(joints, radians));
+                for (i, column) in JOINT_COLUMNS.iter().enumerate() {
+                    row.set_item(column, joints.map(|joints| joints[i]))?;
+                }
+                let configuration = solved
+                    .joints
+                    .map(|joints| robot.axis_configuration_radians(&joints));
+                row.set_item("CF1", configuration.map(|c| c.0))?;
+                row.set_item("CF4", configuration.map(|c| c.1))?;
+                row.set_item("CF6", configuration.map(|c| c.2))?;
+                row.set_item("CFX", configuration.map(|c| c.3))?;
+                row.set_item("reachable", solved.issue.is_none())?;
+                row.set_item("issue", solved.issue)?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// Checks that `robot` can run the program as taught. Returns one dict per problem
+    /// with the index and name of the target and the issue: "unreachable", "configuration"
+    /// (reachable only in another configuration than taught) or "joint_limits" (a joint
+    /// target beyond the limits). An empty list means the program is valid.
+    #[pyo3(signature = (robot, current_joints=None, radians=None))]
+    fn validate<'py>(
+        &self,
+        py: Python<'py>,
+        robot: &Robot,
+        current_joints: Option<Start>,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        let current = robot.start_joints(current_joints.as_ref(), radians)?;
+        let solved = py.detach(|| self.solve_with(robot, current))?;
+        self.waypoints()
+            .iter()
+            .zip(solved)
+            .enumerate()
+            .filter_map(|(index, (waypoint, solved))| {
+                solved.issue.map(|issue| {
+                    let row = PyDict::new(py);
+                    row.set_item("index", index)?;
+                    row.set_item("name", &waypoint.name)?;
+                    row.set_item("issue", issue)?;
+                    Ok(row)
+                })
+            })
+            .collect()
+    }
+
+    /// Sets the motion of every target the robot reaches: joint where moving the joints
+    /// linearly from the target before keeps the TCP within `tolerance` (m) of the straight
+    /// line between them, checked at `samples` steps, else linear. Joint targets and the
+    /// first target (unless `current_joints` gives where the program starts) are joint
+    /// moves; unreached targets keep their motion. Returns one row per target with index,
+    /// name, the deviation of a joint move (None where not checked) and the motion.
+    #[pyo3(signature = (robot, tolerance=0.001, current_joints=None, samples=16, radians=None))]
+    fn classify_motions<'py>(
+        &self,
+        py: Python<'py>,
+        robot: &Robot,
+        tolerance: f64,
+        current_joints: Option<Start>,
+        samples: usize,
+        radians: Option<bool>,
+    ) -> PyResult<Rows<'py>> {
+        validation::check_positive("tolerance", tolerance)?;
+        if samples < 2 {
+            return Err(PyValueError::new_err("samples must be at least 2"));
+        }
+        let current = robot.start_joints(current_joints.as_ref(), radians)?;
+        let waypoints = self.waypoints();
+        let classified = py.detach(|| -> PyResult<Vec<(Option<f64>, Option<Motion>)>> {
+            let solved = self.solve_with(robot, current)?;
+            let mut previous = current;
+            waypoints
+                .iter()
+                .zip(&solved)
+                .map(|(waypoint, solved)| {
+                    let classified = match (&waypoint.target, solved.joints, previous) {
+                        (Target::Joints(_), _, _) | (_, Some(_), None) => {
+                            (None, Some(Motion::Joint))
+                        }
+                        (_, None, _) => (None, waypoint.motion),
+                        (_, Some(joints), Some(start)) => {
+                            // From the robot's TCP to the TCP of the waypoint's tool
+                            let tcp = match &waypoint.tool {
+                                Some(tool) => robot.tool().inverse() * self.tool(tool)?,
+                                None => Isometry3::identity(),
+                            };
+                            let deviation =
+                                path::joint_move_deviation(&start, &joints, samples, |joints| {
+                                    (robot.forward_radians(joints) * tcp).translation.vector
+                                });
+                            let motion = match deviation <= tolerance {
+                                true => Motion::Joint,
+                                false => Motion::Linear,
+                            };
+                            (Some(deviation), Some(motion))
+                        }
+                    };
+                    previous = solved.joints.or(previous);
+                    Ok(classified)
+                })
+                .collect()
+        })?;
+        let mut stored = self.waypoints.write().unwrap_or_else(|e| e.into_inner());
+        for (waypoint, (_, motion)) in stored.iter_mut().zip(&classified) {
+            waypoint.motion = *motion;
+        }
+        drop(stored);
+        waypoints
+            .iter()
+            .zip(classified)
+            .enumerate()
+            .map(|(index, (waypoint, (deviation, motion)))| {
+                let row = PyDict::new(py);
+                row.set_item("index", index)?;
+                row.set_item("name", &waypoint.name)?;
+                row.set_item("deviation", deviation)?;
+                row.set_item("motion", motion.map(|motion| motion.name()))?;
+                Ok(row)
+            })
+            .collect()
+    }
+
+    /// A copy of the program with every pose target moved by `displacement`, given in the
+    /// frame of the target (as a program displacement on the controller), and re-expressed
+    /// in `to_frame` if given, keeping its place in the world. Joint targets are kept.
+    #[pyo3(signature = (displacement=None, to_frame=None))]
+    fn transform(
+        &self,
+        py: Python<'_>,
+        displacement: Option<PyPose>,
+        to_frame: Option<&str>,
+    ) -> PyResult<Program> {
+        if let Some(displacement) = &displacement {
+            validation::check_pose(displacement)?;
+        }
+        let displacement =
+            displacement.map_or(Isometry3::identity(), |pose| to_isometry(pose.0, pose.1));
+        let frames = self.frames.get();
+        let waypoints = self
+            .waypoints()
+            .into_iter()
+            .map(|waypoint| {
+                let Target::Pose(pose) = waypoint.target else {
+                    return Ok(waypoint);
+                };
+                let pose = displacement * pose;
+                Ok(match to_frame {
+                    Some(frame) => Waypoint {
+                        target: Target::Pose(frames.between(&waypoint.frame, frame)? * pose),
+                        frame: frame.to_string(),
+                        ..waypoint
+                    },
+                    None => Waypoint {
+                        target: Target::Pose(pose),
+                        ..waypoint
+                    },
+                })
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(Program::with(
+            &self.name,
+            self.frames.clone_ref(py),
+            self.tools(),
+            waypoints,
+            self.radians,
+        ))
+    }
+
+    /// The program as a dict of plain values: name, radians, the frames other than the
+    /// world as their poses in the world frame, the tools as their TCP in the flange frame
+    /// and the targets as `get` returns them
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let frames = self.frames.get();
+        let frame_poses = PyDict::new(py);
+        for name in frames.names().iter().filter(|name| *name != WORLD) {
+            frame_poses.set_item(name, from_isometry(&frames.between(name, WORLD)?))?;
+        }
+        let tools = PyDict::new(py);
+        for (name, tcp) in self.tools() {
+            tools.set_item(name, from_isometry(&tcp))?;
+        }
+        let targets = self
+            .waypoints()
+            .iter()
+            .map(|waypoint| waypoint.to_dict(py, self.radians))
+            .collect::<PyResult<Vec<_>>>()?;
+        let dict = PyDict::new(py);
+        dict.set_item("name", &self.name)?;
+        dict.set_item("radians", self.radians)?;
+        dict.set_item("frames", frame_poses)?;
+        dict.set_item("tools", tools)?;
+        dict.set_item("targets", targets)?;
+        Ok(dict)
+    }
+
+    /// Program from a dict as `to_dict` returns it. Its frames are added to `frames`, a new
+    /// registry by default.
+    #[staticmethod]
+    #[pyo3(signature = (data, frames=None))]
+    fn from_dict(
+        py: Python<'_>,
+        data: &Bound<'_, PyDict>,
+        frames: Option<Py<Frames>>,
+    ) -> PyResult<Program> {
+        dict::check_keys(
+            data,
+            &["name", "radians", "frames", "tools", "targets"],
+            "Program",
+        )?;
+        let name: String = dict::optional(data, "name")?.unwrap_or_else(|| "main".to_string());
+        let radians: bool = dict::optional(data, "radians")?.unwrap_or(false);
+        let program = Program::new(py, &name, frames, radians)?;
+        let poses = |key: &str| -> PyResult<Vec<(String, PyPose)>> {
+            match data.get_item(key)? {
+                Some(poses) if !poses.is_none() => poses
+                    .cast::<PyDict>()?
+                    .iter()
+                    .map(|(name, pose)| Ok((name.extract()?, pose.extract()?)))
+                    .collect(),
+                _ => Ok(Vec::new()),
+            }
+        };
+        for (name, pose) in poses("frames")? {
+            program.frames.get().add(&name, pose, WORLD)?;
+        }
+        for (name, tcp) in poses("tools")? {
+            validation::check_pose(&tcp)?;
+            program.set_tool(&name, to_isometry(tcp.0, tcp.1));
+        }
+        let targets: Vec<Bound<'_, PyDict>> = dict::optional(data, "targets")?.unwrap_or_default();
+        for target in &targets {
+            program.push(Waypoint::from_dict(target, radians)?)?;
+        }
+        Ok(program)
+    }
+
+    /// Program from a RAPID module, given as the path of a .mod or .modx file or as its
+    /// text. The moves (MoveJ, MoveL, MoveAbsJ) of `routine`, main by default, become the
+    /// targets with their configuration, speed and zone, positions in meters; the work
+    /// objects they use are added to `frames` (a new registry by default) and their tools to
+    /// the program.
+    #[staticmethod]
+    #[pyo3(signature = (source, routine=None, frames=None, radians=false))]
+    fn from_rapid(
+        py: Python<'_>,
+        source: &str,
+        routine: Option<&str>,
+        frames: Option<Py<Frames>>,
+        radians: bool,
+    ) -> PyResult<Program> {
+        let text = read_source(source, "RAPID module")?;
+        let module = rapid::parse(&text, routine).map_err(PyValueError::new_err)?;
+        Program::from_module(py, module, frames, radians)
+    }
+
+    /// Program from a KUKA KRL program, given as the path of its .src file or as its text.
+    /// Points and inline form data are read from `data`, the .dat file (next to the .src by
+    /// default), and tools and bases from `config`, the robot's $config.dat; both are paths
+    /// or text. The moves (PTP, LIN, SPTP, SLIN) of `routine`, the first of the file by
+    /// default, become the targets with their speed and zone, positions in meters; the bases
+    /// they use are added to `frames` (a new registry by default) and their tools to the
+    /// program. Status and Turn are not kept.
+    #[staticmethod]
+    #[pyo3(signature = (source, data=None, config=None, routine=None, frames=None, radians=false))]
+    fn from_krl(
+        py: Python<'_>,
+        source: &str,
+        data: Option<&str>,
+        config: Option<&str>,
+        routine: Option<&str>,
+        frames: Option<Py<Frames>>,
+        radians: bool,
+    ) -> PyResult<Program> {
+        let text = read_source(source, "KRL program")?;
+        let mut files = Vec::new();
+        match data {
+            Some(data) => files.push(read_source(data, "KRL data file")?),
+            None => {
+                let dat = std::path::Path::new(source).with_extension("dat");
+                if source.ends_with(".src") && dat.is_file() {
+                    files.push(std::fs::read_to_string(dat)?);
+                }
+            }
+        }
+        if let Some(config) = config {
+            files.push(read_source(config, "$config.dat")?);
+        }
+        let files: Vec<&str> = files.iter().map(String::as_str).collect();
+        let module = krl::parse(&text, &files, routine).map_err(PyValueError::new_err)?;
+        Program::from_module(py, module, frames, radians)
+    }
+
+    /// The program as a RAPID module with the tooldata and wobjdata it uses, written to
+    /// `path` if given. Targets without a tool are on the tool of `robot`.
+    #[pyo3(signature = (robot=None, path=None))]
+    fn to_rapid(&self, robot: Option<&Robot>, path: Option<&str>) -> PyResult<String> {
+        let text = rapid::write(&self.export_module(robot)?);
+        if let Some(path) = path {
+            std::fs::write(path, &text)?;
+        }
+        Ok(text)
+    }
+
+    /// The program as a KRL .src and .dat declaring the tools and bases it uses, written
+    /// to `path` (the .src) and the .dat next to it if given. Targets without a tool are on
+    /// the tool of `robot`.
+    #[pyo3(signature = (robot=None, path=None))]
+    fn to_krl(&self, robot: Option<&Robot>, path: Option<&str>) -> PyResult<(String, String)> {
+        let (src, dat) = krl::write(&self.export_module(robot)?);
+        if let Some(path) = path {
+            let path = std::path::Path::new(path);
+            std::fs::write(path.with_extension("src"), &src)?;
+            std::fs::write(path.with_extension("dat"), &dat)?;
+        }
+        Ok((src, dat))
+    }
+
+    /// The program as a Fanuc TP program in LS text, written to `path` if given. Frames
+    /// and tools are numbered in the order they are first used; their values have to be set
+    /// on the controller. Targets without a tool are on the tool of `robot`.
+    #[pyo3(signature = (robot=None, path=None))]
+    fn to_ls(&self, robot: Option<&Robot>, path: Option<&str>) -> PyResult<String> {
+        let text = ls::write(&self.export_module(robot)?).map_err(PyValueError::new_err)?;
+        if let Some(path) = path {
+            std::fs::write(path, &text)?;
+        }
+        Ok(text)
+    }
+
+    fn __len__(&self) -> usize {
+        self.waypoints
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Program('{}', {} targets)", self.name, self.__len__())
+    }
+}
+
+/// Text of a file at `source`, or `source` itself if it holds several lines
+fn read_source(source: &str, what: &str) -> PyResult<String> {
+    if std::path::Path::new(source).is_file() {
+        Ok(std::fs::read_to_string(source)?)
+    } else if source.contains('\n') {
+        Ok(source.to_string())
+    } else {
+        Err(PyValueError::new_err(format!("No {} at {}", what, source)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+    use crate::BaseConfig;
+
+    #[test]
+    fn test_program_solves_in_taught_configuration() {
+        let pose = ([0.5, 0.0, 0.4], [0.0, 1.0, 0.0, 0.0]);
+        let joints = [0.0, 0.0, 0.0, 0.0, 90.0, 0.0];
+        let waypoint = |name, pose, joints, configuration| {
+            Waypoint::new(
+                name,
+                pose,
+                joints,
+                WORLD,
+                configuration,
+                None,
+                None,
+                None,
+                false,
+            )
+        };
+        assert!(waypoint("p", Some(pose), Some(joints), None).is_err());
+        assert!(waypoint("p", None, None, None).is_err());
+        assert!(waypoint("p", Some(pose), None, Some((0, 0, 0, 8))).is_err());
+        assert!(Motion::parse("circular").is_err());
+
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let robot = Robot::new(
+            ABB_1660,
+            BaseConfig::new([0.0; 3], identity),
+            ToolConfig::new([0.0, 0.0, 0.1], identity),
+        )
+        .unwrap();
+        let elbow_down = [0.1, 1.2, 1.6, 0.0, -1.0, 0.2];
+        let world = from_isometry(&robot.forward_radians(&elbow_down));
+        let taught = robot.axis_configuration_radians(&elbow_down);
+        Python::initialize();
+        Python::attach(|py| {
+            let frames = Py::new(py, Frames::with([])).unwrap();
+            frames
+                .get()
+                .add("table", ([0.0, 0.0, 0.2], identity), WORLD)
+                .unwrap();
+            let in_table = ([world.0[0], world.0[1], world.0[2] - 0.2], world.1);
+            let program = Program::with("main", frames, Vec::new(), Vec::new(), false);
+            for waypoint in [
+                waypoint("home", None, Some(joints), None),
+                waypoint("p10", Some(in_table), None, Some(taught)),
+                waypoint("p20", Some(in_table), None, Some((0, 0, 0, 0))),
+                waypoint("far", Some(([5.0, 0.0, 0.0], identity)), None, None),
+            ] {
+                program
+                    .push(Waypoint {
+                        frame: "table".to_string(),
+                        ..waypoint.unwrap()
+                    })
+                    .unwrap();
+            }
+            let solved = program.solve_with(&robot, None).unwrap();
+            let issues: Vec<Option<&str>> = solved.iter().map(|solved| solved.issue).collect();
+            assert_eq!(
+                issues,
+                [None, None, Some("configuration"), Some("unreachable")]
+            );
+            // Solving from the nearest solution would pick another branch
+            let reached = solved[1].joints.unwrap();
+            assert!(reached
+                .iter()
+                .zip(elbow_down)
+                .all(|(a, b)| (a - b).abs() < 1e-6));
+
+            let moved = program.transform(py, None, Some(WORLD)).unwrap();
+            let Target::Pose(pose) = &moved.waypoints()[1].target else {
+                panic!("p10 is a pose target");
+            };
+            assert!((pose.translation.vector - nalgebra::Vector3::from(world.0)).norm() < 1e-12);
+        });
+    }
+}
@@ -0,0 +1,164 @@
+use nalgebra::{Isometry3, Vector3};
+
+use crate::pose;
+
+use crate::kinematic_model::KinematicModel;
+
+/// Number of even samples along the line to the shell before refining by bisection
+pub const LINE_SAMPLES: usize = 16;
+
+/// Shift (robot base frame) that moves the wrist center of a flange pose in the robot base
+/// frame to the middle of the shell the arm can reach, along the line from the shoulder
+pub fn shell_shift(model: &KinematicModel, flange: &Isometry3<f64>) -> Vector3<f64> {
+    let reach = model.wrist_reach(flange);
+    let center = reach.center;
+    let outward = Vector3::new(center.x, center.y, 0.0)
+        .try_normalize(1e-9)
+        .unwrap_or_else(Vector3::x);
+    let shoulder = model.a1 * outward + Vector3::new(0.0, 0.0, model.c1);
+    let direction = (center - shoulder).try_normalize(1e-9).unwrap_or(outward);
+    let middle = 0.5 * (reach.min_reach + reach.max_reach);
+    shoulder + middle * direction - center
+}
+
+/// First point from `from` towards `to` that is `reachable`: the first reachable of
+/// `LINE_SAMPLES` even samples, moved back towards `from` by bisection until the step is
+/// below `tolerance` (m). None if no sample is reachable.
+pub fn line_search(
+    reachable: impl Fn(&Vector3<f64>) -> bool,
+    from: &Vector3<f64>,
+    to: &Vector3<f64>,
+    tolerance: f64,
+) -> Option<Vector3<f64>> {
+    let at = |t: f64| from + t * (to - from);
+    if reachable(from) {
+        return Some(*from);
+    }
+    let first = (1..=LINE_SAMPLES)
+        .map(|i| i as f64 / LINE_SAMPLES as f64)
+        .find(|&t| reachable(&at(t)))?;
+    let (mut outside, mut inside) = (first - 1.0 / LINE_SAMPLES as f64, first);
+    let length = (to - from).norm();
+    while (inside - outside) * length > tolerance {
+        let middle = 0.5 * (outside + inside);
+        if reachable(&at(middle)) {
+            inside = middle;
+        } else {
+            outside = middle;
+        }
+    }
+    Some(at(inside))
+}
+
+/// How a pose of a batch came out of the projection onto the workspace
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Reachable,
+    Projected,
+    /// The projection moves the pose by more than the tolerance
+    TooFar,
+    Unreachable,
+    Invalid,
+}
+
+impl Outcome {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Outcome::Reachable => "reachable",
+            Outcome::Projected => "projected",
+            Outcome::TooFar => "too_far",
+            Outcome::Unreachable => "unreachable",
+            Outcome::Invalid => "invalid",
+        }
+    }
+}
+
+/// A pose moved onto the workspace
+pub struct Move {
+    pub to: Isometry3<f64>,
+    /// Translation (m) from the original position
+    pub shift: Vector3<f64>,
+    /// Rotation (radians) from the original orientation
+    pub rotation: f64,
+}
+
+impl Move {
+    pub fn between(from: &Isometry3<f64>, to: &Isometry3<f64>) -> Self {
+        let rotation = match from.rotation == to.rotation {
+            true => 0.0,
+            false => pose::pose_errors(from, to).1,
+        };
+        Move {
+            to: *to,
+            shift: to.translation.vector - from.translation.vector,
+            rotation,
+        }
+    }
+
+    /// Reachable if the pose did not move, projected if it moved by at most `max_move` (m
+    /// per axis) and `max_rotation` (radians), too far otherwise
+    pub fn outcome(&self, max_move: Option<[f64; 3]>, max_rotation: Option<f64>) -> Outcome {
+        let within_move = max_move.is_none_or(|max_move| {
+            self.shift
+                .iter()
+                .zip(max_move)
+                .all(|(shift, max)| shift.abs() <= max)
+        });
+        let within_rotation = max_rotation.is_none_or(|max| self.rotation <= max);
+        if self.shift == Vector3::zeros() && self.rotation == 0.0 {
+            Outcome::Reachable
+        } else if within_move && within_rotation {
+            Outcome::Projected
+        } else {
+            Outcome::TooFar
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+
+    #[test]
+    fn test_line_search_stops_at_the_boundary() {
+        let inside_ball = |point: &Vector3<f64>| point.norm() <= 1.0;
+        let from = Vector3::new(3.0, 0.0, 0.0);
+        let point = line_search(inside_ball, &from, &Vector3::zeros(), 1e-6).unwrap();
+        assert!(point.norm() <= 1.0 && point.norm() > 1.0 - 1e-6);
+        assert_eq!(
+            line_search(inside_ball, &Vector3::x(), &from, 1e-6),
+            Some(Vector3::x())
+        );
+        let beyond = Vector3::new(5.0, 0.0, 0.0);
+        assert!(line_search(inside_ball, &from, &beyond, 1e-6).is_none());
+    }
+
+    #[test]
+    fn test_shell_shift_moves_into_the_shell() {
+        let far = Isometry3::translation(3.0, 1.0, 0.5);
+        let shift = shell_shift(&ABB_1660, &far);
+        let moved = Isometry3::from_parts((far.translation.vector + shift).into(), far.rotation);
+        let reach = ABB_1660.wrist_reach(&moved);
+        assert!(reach.deficit() == 0.0);
+        assert!((reach.reach - 0.5 * (reach.min_reach + reach.max_reach)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_move_outcome() {
+        let from = Isometry3::translation(1.0, 0.0, 0.5);
+        assert_eq!(
+            Move::between(&from, &from).outcome(Some([0.0; 3]), Some(0.0)),
+            Outcome::Reachable
+        );
+        let moved = Move::between(&from, &Isometry3::translation(0.99, 0.0, 0.52));
+        assert!((moved.shift - Vector3::new(-0.01, 0.0, 0.02)).norm() < 1e-12);
+        assert_eq!(moved.outcome(None, None), Outcome::Projected);
+        assert_eq!(moved.outcome(Some([0.03; 3]), None), Outcome::Projected);
+        // 2 cm up is beyond a 1 cm tolerance on z
+        assert_eq!(
+            moved.outcome(Some([0.03, 0.03, 0.01]), None),
+            Outcome::TooFar
+        );
+    }
+}
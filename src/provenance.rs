@@ -0,0 +1,51 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// FNV-1a offset basis and prime (64 bit)
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Stable digest of a sequence of numbers as 16 hex digits, the same on every platform and
+/// build. Negative zero hashes as zero, so equal setups give equal digests.
+pub fn digest(values: impl IntoIterator<Item = f64>) -> String {
+    let hash = values
+        .into_iter()
+        .flat_map(|value| (value + 0.0).to_bits().to_le_bytes())
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        });
+    format!("{:016x}", hash)
+}
+
+/// The kinematic setup a batch was solved with, stamped on its rows so archived joint
+/// tables can be traced back to it
+pub struct Provenance {
+    /// Digest of the kinematic model, link corrections, base and joint limits
+    pub model_hash: String,
+    /// Digest of the tool
+    pub tool_hash: String,
+    /// Solution policy of the robot
+    pub config_policy: &'static str,
+}
+
+impl Provenance {
+    /// Adds the model_hash, tool_hash and config_policy columns to a row
+    pub fn stamp(&self, row: &Bound<'_, PyDict>) -> PyResult<()> {
+        row.set_item("model_hash", &self.model_hash)?;
+        row.set_item("tool_hash", &self.tool_hash)?;
+        row.set_item("config_policy", self.config_policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest_is_stable() {
+        assert_eq!(digest([]), "cbf29ce484222325");
+        assert_eq!(digest([0.0, 1.5]), digest([-0.0, 1.5]));
+        assert_ne!(digest([0.0, 1.5]), digest([1.5, 0.0]));
+        assert_eq!(digest([1.0]).len(), 16);
+    }
+}
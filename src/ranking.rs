@@ -0,0 +1,198 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::f64::consts::PI;
+
+use crate::dict;
+
+/// Weights of the criteria that order_by="weighted" combines into one score per solution,
+/// lowest first. Every criterion is scaled to about 0 at best and 1 at worst.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ranking {
+    /// Wanted configuration index cfx, the configuration of the seed without it
+    pub configuration: Option<u8>,
+    /// Weight of a configuration other than the wanted one, which scores 1
+    pub configuration_weight: f64,
+    /// Weight of the summed joint distance from the seed in half turns
+    pub distance_weight: f64,
+    /// Weight of the shortfall in manipulability against the most dexterous solution
+    pub manipulability_weight: f64,
+    /// Weight of the shortfall of the smallest joint margin to the limits, relative to half
+    /// the joint range, against the middle of the range
+    pub limit_margin_weight: f64,
+}
+
+impl Default for Ranking {
+    fn default() -> Self {
+        Ranking {
+            configuration: None,
+            configuration_weight: 1.0,
+            distance_weight: 1.0,
+            manipulability_weight: 0.0,
+            limit_margin_weight: 0.0,
+        }
+    }
+}
+
+impl Ranking {
+    pub fn check(&self) -> PyResult<()> {
+        let weights = [
+            self.configuration_weight,
+            self.distance_weight,
+            self.manipulability_weight,
+            self.limit_margin_weight,
+        ];
+        if !weights
+            .iter()
+            .all(|weight| weight.is_finite() && *weight >= 0.0)
+        {
+            return Err(PyValueError::new_err(format!(
+                "Ranking weights must be finite and not negative, got {:?}",
+                weights
+            )));
+        }
+        match self.configuration {
+            Some(cfx) if cfx > 7 => Err(PyValueError::new_err(format!(
+                "configuration must be a cfx from 0 to 7, got {}",
+                cfx
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Scores of solutions (radians) in their order. `configuration` gives the
+    /// configuration index cfx of joints, `manipulability` is only evaluated with a weight
+    /// on it.
+    pub fn scores(
+        &self,
+        solutions: &[Joints],
+        seed: &Joints,
+        limits: Option<&[(f64, f64); 6]>,
+        configuration: impl Fn(&Joints) -> u8,
+        manipulability: impl Fn(&Joints) -> f64,
+    ) -> Vec<f64> {
+        let wanted = self.configuration.unwrap_or_else(|| configuration(seed));
+        let dexterity: Vec<f64> = match self.manipulability_weight > 0.0 {
+            true => solutions.iter().map(manipulability).collect(),
+            false => vec![0.0; solutions.len()],
+        };
+        let best = dexterity.iter().copied().fold(0.0, f64::max);
+        solutions
+            .iter()
+            .zip(&dexterity)
+            .map(|(joints, dexterity)| {
+                let mismatch = f64::from(u8::from(configuration(joints) != wanted));
+                let distance = joints
+                    .iter()
+                    .zip(seed)
+                    .map(|(a, b)| (a - b).abs())
+                    .sum::<f64>()
+                    / PI;
+                let manipulability = match best > 0.0 {
+                    true => 1.0 - dexterity / best,
+                    false => 0.0,
+                };
+                let limit_margin = limits.map_or(0.0, |limits| {
+                    let margin = joints
+                        .iter()
+                        .zip(limits)
+                        .map(|(joint, (min, max))| {
+                            let half = (max - min) / 2.0;
+                            match half > 0.0 {
+                                true => (joint - min).min(max - joint) / half,
+                                false => 1.0,
+                            }
+                        })
+                        .fold(1.0, f64::min);
+                    1.0 - margin.clamp(0.0, 1.0)
+                });
+                self.configuration_weight * mismatch
+                    + self.distance_weight * distance
+                    + self.manipulability_weight * manipulability
+                    + self.limit_margin_weight * limit_margin
+            })
+            .collect()
+    }
+
+    pub fn to_dict<'py>(self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("configuration", self.configuration)?;
+        dict.set_item("configuration_weight", self.configuration_weight)?;
+        dict.set_item("distance_weight", self.distance_weight)?;
+        dict.set_item("manipulability_weight", self.manipulability_weight)?;
+        dict.set_item("limit_margin_weight", self.limit_margin_weight)?;
+        Ok(dict)
+    }
+
+    /// Settings from a dict as produced by `to_dict`, missing weights at their defaults
+    pub fn from_dict(data: &Bound<'_, PyDict>) -> PyResult<Self> {
+        dict::check_keys(
+            data,
+            &[
+                "configuration",
+                "configuration_weight",
+                "distance_weight",
+                "manipulability_weight",
+                "limit_margin_weight",
+            ],
+            "ranking",
+        )?;
+        let default = Ranking::default();
+        Ok(Ranking {
+            configuration: dict::optional(data, "configuration")?,
+            configuration_weight: dict::optional(data, "configuration_weight")?
+                .unwrap_or(default.configuration_weight),
+            distance_weight: dict::optional(data, "distance_weight")?
+                .unwrap_or(default.distance_weight),
+            manipulability_weight: dict::optional(data, "manipulability_weight")?
+                .unwrap_or(default.manipulability_weight),
+            limit_margin_weight: dict::optional(data, "limit_margin_weight")?
+                .unwrap_or(default.limit_margin_weight),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::configuration::configuration;
+    use crate::tests::ABB_1660;
+
+    #[test]
+    fn test_ranking_scores() {
+        let seed = [0.0, 0.3, 0.2, 0.0, 0.8, 0.0];
+        let near_limit = [2.9, 0.3, 0.2, 0.0, 0.8, 0.0];
+        let limits = [(-3.0, 3.0); 6];
+        let solutions = [seed, near_limit];
+        let dexterity = |joints: &Joints| if joints[0] > 1.0 { 2.0 } else { 1.0 };
+        let cfx = |joints: &Joints| configuration(&ABB_1660, joints);
+
+        let by_distance =
+            Ranking::default().scores(&solutions, &seed, Some(&limits), cfx, dexterity);
+        assert_eq!(by_distance[0], 0.0);
+        assert!((by_distance[1] - 2.9 / PI).abs() < 1e-12);
+
+        let by_manipulability = Ranking {
+            distance_weight: 0.0,
+            manipulability_weight: 1.0,
+            ..Ranking::default()
+        };
+        let scores = by_manipulability.scores(&solutions, &seed, Some(&limits), cfx, dexterity);
+        assert_eq!(scores, vec![0.5, 0.0]);
+
+        let by_margin = Ranking {
+            distance_weight: 0.0,
+            limit_margin_weight: 1.0,
+            ..Ranking::default()
+        };
+        let scores = by_margin.scores(&solutions, &seed, Some(&limits), cfx, dexterity);
+        assert!(scores[0] < scores[1]);
+        assert!(Ranking {
+            configuration: Some(8),
+            ..Ranking::default()
+        }
+        .check()
+        .is_err());
+    }
+}
@@ -0,0 +1,987 @@
+use nalgebra::{Isometry3, Translation3, UnitQuaternion, Vector3};
+use std::collections::HashMap;
+
+use crate::frames::WORLD;
+use crate::pose::to_isometry;
+use crate::program::{Motion, Target, Waypoint};
+
+/// RAPID positions are in millimeters
+const MM: f64 = 1000.0;
+
+/// ABB axis configuration (cf1, cf4, cf6, cfx)
+type Configuration = (i32, i32, i32, u8);
+
+/// Lines that open or close a block and end without a semicolon
+const BLOCKS: [&str; 22] = [
+    "MODULE",
+    "ENDMODULE",
+    "PROC",
+    "ENDPROC",
+    "FUNC",
+    "ENDFUNC",
+    "TRAP",
+    "ENDTRAP",
+    "IF",
+    "ELSEIF",
+    "ELSE",
+    "ENDIF",
+    "FOR",
+    "ENDFOR",
+    "WHILE",
+    "ENDWHILE",
+    "TEST",
+    "CASE",
+    "DEFAULT",
+    "ENDTEST",
+    "ERROR",
+    "UNDO",
+];
+
+/// A RAPID module read into the pieces of a program: its tools and work objects as poses
+/// (the TCP in the flange frame, the object frame in the world frame) and its moves
+pub struct Module {
+    pub name: String,
+    pub tools: Vec<(String, Isometry3<f64>)>,
+    pub work_objects: Vec<(String, Isometry3<f64>)>,
+    pub waypoints: Vec<Waypoint>,
+}
+
+/// A data value: a number, bool, string, name, aggregate [..] or function call f(..) with
+/// its arguments as text
+#[derive(Debug, PartialEq)]
+enum Value {
+    Number(f64),
+    Bool(bool),
+    Text(String),
+    Name(String),
+    List(Vec<Value>),
+    Call(String, Vec<String>),
+}
+
+impl Value {
+    fn list(&self, n: usize) -> Result<&[Value], String> {
+        match self {
+            Value::List(items) if items.len() >= n => Ok(items),
+            _ => Err(format!(
+                "Expected an aggregate of {} items, got {:?}",
+                n, self
+            )),
+        }
+    }
+
+    fn number(&self) -> Result<f64, String> {
+        match self {
+            Value::Number(number) => Ok(*number),
+            _ => Err(format!("Expected a number, got {:?}", self)),
+        }
+    }
+
+    fn numbers<const N: usize>(&self) -> Result<[f64; N], String> {
+        let items = self.list(N)?;
+        let mut numbers = [0.0; N];
+        for (number, item) in numbers.iter_mut().zip(items) {
+            *number = item.number()?;
+        }
+        Ok(numbers)
+    }
+
+    fn boolean(&self) -> Result<bool, String> {
+        match self {
+            Value::Bool(value) => Ok(*value),
+            _ => Err(format!("Expected TRUE or FALSE, got {:?}", self)),
+        }
+    }
+
+    /// A pose [[x, y, z], [q1, q2, q3, q4]] with the position in millimeters
+    fn pose(&self) -> Result<Isometry3<f64>, String> {
+        let items = self.list(2)?;
+        pose(&items[0], &items[1])
+    }
+}
+
+fn pose(translation: &Value, rotation: &Value) -> Result<Isometry3<f64>, String> {
+    let translation: [f64; 3] = translation.numbers()?;
+    Ok(to_isometry(
+        translation.map(|x| x / MM),
+        rotation.numbers()?,
+    ))
+}
+
+/// Text up to a `!` comment outside strings
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '!' if !quoted => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// Splits at `separator` outside brackets, braces, parentheses and strings
+pub fn split_top(text: &str, separator: char) -> Vec<&str> {
+    let (mut depth, mut quoted, mut start) = (0i32, false, 0);
+    let mut parts = Vec::new();
+    for (i, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '[' | '(' | '{' if !quoted => depth += 1,
+            ']' | ')' | '}' if !quoted => depth -= 1,
+            c if c == separator && depth == 0 && !quoted => {
+                parts.push(text[start..i].trim());
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(text[start..].trim());
+    parts
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn parse_value(text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    if let Some(inner) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        return split_top(inner, ',')
+            .into_iter()
+            .map(parse_value)
+            .collect::<Result<_, _>>()
+            .map(Value::List);
+    }
+    if let Some(inner) = text.strip_prefix('"').and_then(|t| t.strip_suffix('"')) {
+        return Ok(Value::Text(inner.to_string()));
+    }
+    if let Some((name, rest)) = text.split_once('(') {
+        if let (true, Some(args)) = (is_identifier(name.trim()), rest.strip_suffix(')')) {
+            let args = split_top(args, ',').into_iter().map(String::from).collect();
+            return Ok(Value::Call(name.trim().to_string(), args));
+        }
+    }
+    match text.to_ascii_uppercase().as_str() {
+        "TRUE" => return Ok(Value::Bool(true)),
+        "FALSE" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+    if let Ok(number) = text.replace(' ', "").parse::<f64>() {
+        return Ok(Value::Number(number));
+    }
+    match is_identifier(text) {
+        true => Ok(Value::Name(text.to_string())),
+        false => Err(format!(
+            "Cannot read '{}', expressions are not supported",
+            text
+        )),
+    }
+}
+
+/// Statements of a module with the routine they are in, and the module name
+fn statements(text: &str) -> (Option<String>, Vec<(Option<String>, String)>) {
+    let (mut module, mut routine) = (None, None);
+    let mut statements = Vec::new();
+    let mut pending = String::new();
+    for line in text.lines() {
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if pending.is_empty() {
+            let mut words = line.split(|c: char| c.is_whitespace() || c == '(');
+            let mut first = words.next().unwrap_or("").to_ascii_uppercase();
+            if first == "LOCAL" {
+                first = words.next().unwrap_or("").to_ascii_uppercase();
+            }
+            if BLOCKS.contains(&first.as_str()) {
+                let name = words.find(|word| !word.is_empty()).map(String::from);
+                match first.as_str() {
+                    "MODULE" => module = name,
+                    "PROC" | "FUNC" | "TRAP" => routine = name,
+                    "ENDPROC" | "ENDFUNC" | "ENDTRAP" => routine = None,
+                    _ => {}
+                }
+                continue;
+            }
+        }
+        pending.push_str(line);
+        pending.push(' ');
+        // A statement may span lines, it ends at a semicolon outside strings
+        let parts = split_top(&pending, ';');
+        if parts.len() > 1 {
+            for part in &parts[..parts.len() - 1] {
+                if !part.is_empty() {
+                    statements.push((routine.clone(), part.to_string()));
+                }
+            }
+            pending = parts[parts.len() - 1].to_string();
+        }
+    }
+    (module, statements)
+}
+
+/// Data declarations by lowercase name: type and value
+fn declarations(
+    statements: &[(Option<String>, String)],
+) -> Result<HashMap<String, (String, Value)>, String> {
+    let mut data = HashMap::new();
+    for (_, statement) in statements {
+        let mut words = statement.split_whitespace().peekable();
+        if words
+            .peek()
+            .is_some_and(|w| ["LOCAL", "TASK"].contains(&w.to_ascii_uppercase().as_str()))
+        {
+            words.next();
+        }
+        let kind = words.next().unwrap_or("").to_ascii_uppercase();
+        if !["CONST", "PERS", "VAR"].contains(&kind.as_str()) {
+            continue;
+        }
+        let data_type = words.next().unwrap_or("").to_string();
+        let rest: Vec<&str> = words.collect();
+        let Some((name, value)) = rest
+            .join(" ")
+            .split_once(":=")
+            .map(|(n, v)| (n.trim().to_string(), v.to_string()))
+        else {
+            continue;
+        };
+        if name.contains('{') {
+            log::warn!("Array {} is not imported", name);
+            continue;
+        }
+        let value = parse_value(&value).map_err(|e| format!("{}: {}", name, e))?;
+        data.insert(name.to_ascii_lowercase(), (data_type, value));
+    }
+    Ok(data)
+}
+
+struct Reader {
+    data: HashMap<String, (String, Value)>,
+}
+
+impl Reader {
+    fn lookup(&self, name: &str, data_type: &str) -> Result<Option<&Value>, String> {
+        // RAPID names are case-insensitive
+        match self.data.get(&name.to_ascii_lowercase()) {
+            Some((declared, value)) if declared.eq_ignore_ascii_case(data_type) => Ok(Some(value)),
+            Some((declared, _)) => Err(format!(
+                "{} is a {}, expected {}",
+                name, declared, data_type
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Target of a move as a TCP pose in its work object and configuration, or joints.
+    /// `resolving` holds the names whose values are being read, to catch cyclic references.
+    fn target(
+        &self,
+        value: &Value,
+        joint: bool,
+        resolving: &mut Vec<String>,
+    ) -> Result<(Target, Option<Configuration>), String> {
+        match value {
+            Value::Name(name) => {
+                let data_type = if joint { "jointtarget" } else { "robtarget" };
+                let value = self
+                    .lookup(name, data_type)?
+                    .ok_or_else(|| format!("Unknown {} {}", data_type, name))?;
+                let key = name.to_ascii_lowercase();
+                if resolving.contains(&key) {
+                    return Err(format!("Cyclic reference to {} {}", data_type, name));
+                }
+                resolving.push(key);
+                let target = self.target(value, joint, resolving);
+                resolving.pop();
+                target
+            }
+            Value::List(_) if joint => {
+                let joints: [f64; 6] = value.list(1)?[0].numbers()?;
+                Ok((Target::Joints(joints.map(f64::to_radians)), None))
+            }
+            Value::List(items) => {
+                let [cf1, cf4, cf6, cfx] = value.list(3)?[2].numbers()?;
+                let configuration = (cf1 as i32, cf4 as i32, cf6 as i32, cfx as u8);
+                Ok((
+                    Target::Pose(pose(&items[0], &items[1])?),
+                    Some(configuration),
+                ))
+            }
+            Value::Call(function, args) if !joint => {
+                let function = function.to_ascii_lowercase();
+                if function != "offs" && function != "reltool" {
+                    return Err(format!("Cannot read the target function {}", function));
+                }
+                // Optional arguments such as \Rz:=90 follow the last distance
+                let (mut values, mut options) = (Vec::new(), Vec::new());
+                for arg in args {
+                    let parts = split_top(arg, '\\');
+                    values.push(parts[0]);
+                    options.extend(parts[1..].iter().copied());
+                }
+                if values.len() != 4 || (function == "offs" && !options.is_empty()) {
+                    return Err(format!("Cannot read {}({})", function, args.join(", ")));
+                }
+                let (Target::Pose(pose), configuration) =
+                    self.target(&parse_value(values[0])?, false, resolving)?
+                else {
+                    return Err(format!("{} needs a robtarget", function));
+                };
+                let deltas: Vec<f64> = values[1..]
+                    .iter()
+                    .map(|value| parse_value(value)?.number())
+                    .collect::<Result<_, _>>()?;
+                let delta = Translation3::new(deltas[0] / MM, deltas[1] / MM, deltas[2] / MM);
+                if function == "offs" {
+                    // Offs moves along the work object axes
+                    return Ok((Target::Pose(delta * pose), configuration));
+                }
+                // RelTool moves along the tool axes, then turns about the moved tool x, y and z
+                let mut rotation = UnitQuaternion::identity();
+                for option in options {
+                    let (axis, angle) = option
+                        .split_once(":=")
+                        .ok_or_else(|| format!("Unknown RelTool argument {}", option))?;
+                    let axis = match axis.trim().to_ascii_uppercase().as_str() {
+                        "RX" => Vector3::x_axis(),
+                        "RY" => Vector3::y_axis(),
+                        "RZ" => Vector3::z_axis(),
+                        _ => return Err(format!("Unknown RelTool argument {}", option)),
+                    };
+                    let angle = parse_value(angle)?.number()?.to_radians();
+                    rotation *= UnitQuaternion::from_axis_angle(&axis, angle);
+                }
+                Ok((Target::Pose(pose * delta * rotation), configuration))
+            }
+            _ => Err(format!("Cannot read the target {:?}", value)),
+        }
+    }
+
+    /// TCP speed (m/s) of a speeddata name, predefined (v100) or declared
+    fn speed(&self, name: &str) -> Result<Option<f64>, String> {
+        if let Some(value) = self.lookup(name, "speeddata")? {
+            return Ok(Some(value.list(1)?[0].number()? / MM));
+        }
+        let lower = name.to_ascii_lowercase();
+        match lower.strip_prefix('v').map(|digits| digits.parse::<f64>()) {
+            _ if lower == "vmax" => Ok(Some(5.0)),
+            Some(Ok(mm_per_s)) => Ok(Some(mm_per_s / MM)),
+            _ => {
+                log::warn!("Unknown speeddata {}, the speed is left unset", name);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Zone radius (m) of a zonedata name, 0 for fine
+    fn zone(&self, name: &str) -> Result<Option<f64>, String> {
+        if let Some(value) = self.lookup(name, "zonedata")? {
+            let items = value.list(2)?;
+            return Ok(Some(match items[0].boolean()? {
+                true => 0.0,
+                false => items[1].number()? / MM,
+            }));
+        }
+        let lower = name.to_ascii_lowercase();
+        match lower.strip_prefix('z').map(|digits| digits.parse::<f64>()) {
+            _ if lower == "fine" => Ok(Some(0.0)),
+            Some(Ok(mm)) => Ok(Some(mm / MM)),
+            _ => {
+                log::warn!("Unknown zonedata {}, the zone is left unset", name);
+                Ok(None)
+            }
+        }
+    }
+
+    /// TCP of a tooldata in the flange frame
+    fn tool(&self, name: &str) -> Result<Isometry3<f64>, String> {
+        if name.eq_ignore_ascii_case("tool0") {
+            return Ok(Isometry3::identity());
+        }
+        let value = self
+            .lookup(name, "tooldata")?
+            .ok_or_else(|| format!("Unknown tooldata {}", name))?;
+        let items = value.list(2)?;
+        if !items[0].boolean()? {
+            return Err(format!(
+                "Tool {} is stationary (robhold FALSE), which is not supported",
+                name
+            ));
+        }
+        items[1].pose()
+    }
+
+    /// Object frame of a wobjdata in the world frame: the user frame times the object frame
+    fn work_object(&self, name: &str) -> Result<Isometry3<f64>, String> {
+        let value = self
+            .lookup(name, "wobjdata")?
+            .ok_or_else(|| format!("Unknown wobjdata {}", name))?;
+        let items = value.list(5)?;
+        if items[0].boolean()? {
+            return Err(format!(
+                "Work object {} is held by the robot, which is not supported",
+                name
+            ));
+        }
+        if !items[1].boolean()? || !matches!(&items[2], Value::Text(unit) if unit.is_empty()) {
+            log::warn!(
+                "Work object {} moves with a mechanical unit, it is imported as fixed",
+                name
+            );
+        }
+        Ok(items[3].pose()? * items[4].pose()?)
+    }
+}
+
+/// Reads the moves of a RAPID module: MoveJ, MoveL and MoveAbsJ (and their DO, AO, GO and
+/// Sync variants) to named or inline targets, Offs and RelTool of them, in the order they
+/// appear in `routine`, by default main if the module has it, else in every routine.
+/// Control flow is not followed. Positions are converted to meters.
+pub fn parse(text: &str, routine: Option<&str>) -> Result<Module, String> {
+    let (module, statements) = statements(text);
+    let reader = Reader {
+        data: declarations(&statements)?,
+    };
+    let has = |wanted: &str| {
+        statements.iter().any(|(routine, _)| {
+            routine
+                .as_deref()
+                .is_some_and(|r| r.eq_ignore_ascii_case(wanted))
+        })
+    };
+    let routine = match routine {
+        Some(routine) if !has(routine) => {
+            return Err(format!("No routine {} with moves in the module", routine))
+        }
+        Some(routine) => Some(routine.to_string()),
+        None if has("main") => Some("main".to_string()),
+        None => None,
+    };
+    let mut tools: Vec<(String, Isometry3<f64>)> = Vec::new();
+    let mut work_objects: Vec<(String, Isometry3<f64>)> = Vec::new();
+    let mut waypoints = Vec::new();
+    for (in_routine, statement) in &statements {
+        let Some(in_routine) = in_routine else {
+            continue;
+        };
+        if routine
+            .as_deref()
+            .is_some_and(|r| !r.eq_ignore_ascii_case(in_routine))
+        {
+            continue;
+        }
+        let (instruction, arguments) = statement
+            .split_once(|c: char| c.is_whitespace())
+            .unwrap_or((statement.as_str(), ""));
+        let upper = instruction.to_ascii_uppercase();
+        let (motion, joint) = if upper.starts_with("MOVEABSJ") {
+            (Motion::Joint, true)
+        } else if upper.starts_with("MOVEJ") {
+            (Motion::Joint, false)
+        } else if upper.starts_with("MOVEL") {
+            (Motion::Linear, false)
+        } else {
+            if upper.starts_with("MOVE") {
+                log::warn!("{} is not imported", instruction);
+            }
+            continue;
+        };
+        let error = |e: String| format!("{}: {}", statement, e);
+        // Leading switches such as \Conc are arguments of their own
+        let arguments: Vec<Vec<&str>> = split_top(arguments, ',')
+            .into_iter()
+            .filter(|argument| !argument.starts_with('\\'))
+            .map(|argument| split_top(argument, '\\'))
+            .collect();
+        if arguments.len() < 4 {
+            return Err(error("Expected a target, speed, zone and tool".to_string()));
+        }
+        let option = |argument: &[&str], name: &str| {
+            argument[1..].iter().find_map(|option| {
+                let (key, value) = option.split_once(":=")?;
+                key.trim()
+                    .eq_ignore_ascii_case(name)
+                    .then(|| value.trim().to_string())
+            })
+        };
+        let target_text = arguments[0][0];
+        let (target, configuration) = reader
+            .target(
+                &parse_value(target_text).map_err(error)?,
+                joint,
+                &mut Vec::new(),
+            )
+            .map_err(error)?;
+        let name = match parse_value(target_text) {
+            Ok(Value::Name(name)) => name,
+            Ok(Value::List(_)) => "*".to_string(),
+            _ => target_text.split_whitespace().collect(),
+        };
+        let speed = match option(&arguments[1], "V") {
+            Some(v) => Some(parse_value(&v).and_then(|v| v.number()).map_err(error)? / MM),
+            None => reader.speed(arguments[1][0]).map_err(error)?,
+        };
+        let zone = match option(&arguments[2], "Z") {
+            Some(z) => Some(parse_value(&z).and_then(|z| z.number()).map_err(error)? / MM),
+            None => reader.zone(arguments[2][0]).map_err(error)?,
+        };
+        let tool = arguments[3][0].to_string();
+        if !tools.iter().any(|(name, _)| *name == tool) {
+            tools.push((tool.clone(), reader.tool(&tool).map_err(error)?));
+        }
+        let frame = match option(&arguments[3], "WObj") {
+            Some(wobj) if !wobj.eq_ignore_ascii_case("wobj0") => {
+                if !work_objects.iter().any(|(name, _)| *name == wobj) {
+                    work_objects.push((wobj.clone(), reader.work_object(&wobj).map_err(error)?));
+                }
+                wobj
+            }
+            _ => WORLD.to_string(),
+        };
+        waypoints.push(Waypoint {
+            name,
+            target,
+            frame,
+            tool: Some(tool),
+            configuration,
+            motion: Some(motion),
+            speed,
+            zone,
+        });
+    }
+    Ok(Module {
+        name: module.unwrap_or_else(|| "main".to_string()),
+        tools,
+        work_objects,
+        waypoints,
+    })
+}
+
+/// `value` with at most `places` decimals and no trailing zeros
+pub fn decimal(value: f64, places: usize) -> String {
+    let text = format!("{:.*}", places, value);
+    let text = match text.contains('.') {
+        true => text.trim_end_matches('0').trim_end_matches('.'),
+        false => &text,
+    };
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+/// Controller identifiers given to the names of a program: letters, digits and underscores
+/// starting with a letter, at most `length` long and unique ignoring case
+pub struct Identifiers {
+    length: usize,
+    taken: Vec<String>,
+}
+
+impl Identifiers {
+    pub fn new(length: usize, reserved: &[&str]) -> Self {
+        Identifiers {
+            length,
+            taken: reserved
+                .iter()
+                .map(|name| name.to_ascii_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Identifier for `name`, other characters replaced by an underscore, or `fallback` if
+    /// it does not start with a letter
+    pub fn add(&mut self, name: &str, fallback: &str) -> String {
+        let mut cleaned = String::new();
+        for c in name.chars() {
+            match c.is_ascii_alphanumeric() {
+                true => cleaned.push(c),
+                false if !cleaned.ends_with('_') => cleaned.push('_'),
+                false => {}
+            }
+        }
+        let cleaned = cleaned.trim_matches('_');
+        let base = match cleaned.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            true => cleaned,
+            false => fallback,
+        };
+        let mut identifier: String = base.chars().take(self.length).collect();
+        let mut n = 1;
+        while self.taken.contains(&identifier.to_ascii_lowercase()) {
+            n += 1;
+            let suffix = format!("_{}", n);
+            let kept = self.length.saturating_sub(suffix.len());
+            identifier = format!("{}{}", base.chars().take(kept).collect::<String>(), suffix);
+        }
+        self.taken.push(identifier.to_ascii_lowercase());
+        identifier
+    }
+}
+
+/// Identifiers of the targets of `waypoints`, declaring a target once however often it is
+/// visited: each waypoint's identifier and the first waypoint of every identifier
+pub fn target_names(
+    waypoints: &[Waypoint],
+    identifiers: &mut Identifiers,
+    fallback: impl Fn(usize) -> String,
+) -> Vec<(String, bool)> {
+    let mut named: Vec<(usize, String)> = Vec::new();
+    waypoints
+        .iter()
+        .enumerate()
+        .map(|(i, waypoint)| {
+            let same = |(k, _): &&(usize, String)| {
+                let other = &waypoints[*k];
+                other.name == waypoint.name
+                    && other.target == waypoint.target
+                    && other.configuration == waypoint.configuration
+            };
+            if let Some((_, identifier)) = named.iter().find(same) {
+                return (identifier.clone(), false);
+            }
+            let identifier = identifiers.add(&waypoint.name, &fallback(i));
+            named.push((i, identifier.clone()));
+            (identifier, true)
+        })
+        .collect()
+}
+
+/// Position (mm) and orientation of a pose, as in the first items of a robtarget
+fn rapid_pose(pose: &Isometry3<f64>) -> String {
+    let [x, y, z] = [0, 1, 2].map(|i| decimal(pose.translation.vector[i] * MM, 3));
+    let q = pose.rotation;
+    let [qw, qx, qy, qz] = [q.w, q.i, q.j, q.k].map(|value| decimal(value, 8));
+    format!("[{},{},{}],[{},{},{},{}]", x, y, z, qw, qx, qy, qz)
+}
+
+/// External axes of a target on a robot without them
+const NO_EXTERNAL_AXES: &str = "[9E+09,9E+09,9E+09,9E+09,9E+09,9E+09]";
+
+/// Predefined speeddata, TCP speeds in mm/s
+const SPEEDS: [f64; 25] = [
+    5.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 80.0, 100.0, 150.0, 200.0, 300.0, 400.0, 500.0, 600.0,
+    800.0, 1000.0, 1500.0, 2000.0, 2500.0, 3000.0, 4000.0, 5000.0, 6000.0, 7000.0,
+];
+
+/// Predefined zonedata, TCP zone radii in mm
+const ZONES: [f64; 14] = [
+    0.0, 1.0, 5.0, 10.0, 15.0, 20.0, 30.0, 40.0, 50.0, 60.0, 80.0, 100.0, 150.0, 200.0,
+];
+
+/// Name of the speeddata for a TCP speed (m/s), v1000 if unset, and its declaration with
+/// a placeholder for the name if it is not predefined
+fn speed_data(speed: Option<f64>) -> (String, Option<String>) {
+    let mm_per_s = speed.map_or(1000.0, |speed| speed * MM);
+    let name = format!("v{}", decimal(mm_per_s, 1));
+    match SPEEDS.iter().any(|v| (v - mm_per_s).abs() < 0.05) {
+        true => (name, None),
+        // The reorientation and external axis speeds of the predefined speeddata
+        false => (
+            name,
+            Some(format!(
+                "    CONST speeddata {{}}:=[{},500,5000,1000];",
+                decimal(mm_per_s, 1)
+            )),
+        ),
+    }
+}
+
+/// Name of the zonedata for a zone radius (m), fine if unset or 0, and its declaration
+/// with a placeholder for the name if it is not predefined
+fn zone_data(zone: Option<f64>) -> (String, Option<String>) {
+    let mm = zone.unwrap_or(0.0) * MM;
+    if mm < 0.05 {
+        return ("fine".to_string(), None);
+    }
+    let name = format!("z{}", decimal(mm, 1));
+    match ZONES.iter().any(|z| (z - mm).abs() < 0.05) {
+        true => (name, None),
+        // The reorientation and external axis zones in the ratios of the predefined zonedata
+        false => {
+            let [path, angle] = [1.5 * mm, 0.15 * mm].map(|value| decimal(value, 2));
+            (
+                name,
+                Some(format!(
+                    "    CONST zonedata {{}}:=[FALSE,{},{},{},{},{},{}];",
+                    decimal(mm, 1),
+                    path,
+                    path,
+                    angle,
+                    path,
+                    angle
+                )),
+            )
+        }
+    }
+}
+
+/// Writes a RAPID module with the tooldata and wobjdata of `module` (tools as their TCP in
+/// the flange frame, work objects as their user frame in the world frame), a robtarget or
+/// jointtarget per target and the moves in procedure main with the speed and zone of
+/// their target: predefined speeddata and zonedata where one matches, else declared ones,
+/// v1000 and fine where unset. Tools get the load data of tool0, to be set before running
+/// the program.
+pub fn write(module: &Module) -> String {
+    let mut identifiers = Identifiers::new(32, &["tool0", "wobj0", "load0", "main"]);
+    let module_name = identifiers.add(&module.name, "main_module");
+    let mut lines = vec![format!("MODULE {}", module_name)];
+    let mut tools = HashMap::from([("tool0".to_string(), "tool0".to_string())]);
+    for (name, tcp) in &module.tools {
+        if name.eq_ignore_ascii_case("tool0") {
+            continue;
+        }
+        let identifier = identifiers.add(name, "tool");
+        lines.push(format!(
+            "    PERS tooldata {}:=[TRUE,[{}],[0.001,[0,0,0.001],[1,0,0,0],0,0,0]];",
+            identifier,
+            rapid_pose(tcp)
+        ));
+        tools.insert(name.clone(), identifier);
+    }
+    let mut work_objects = HashMap::new();
+    for (name, frame) in &module.work_objects {
+        let identifier = identifiers.add(name, "wobj");
+        lines.push(format!(
+            "    TASK PERS wobjdata {}:=[FALSE,TRUE,\"\",[{}],[[0,0,0],[1,0,0,0]]];",
+            identifier,
+            rapid_pose(frame)
+        ));
+        work_objects.insert(name.clone(), identifier);
+    }
+    let names = target_names(&module.waypoints, &mut identifiers, |i| {
+        format!("p{}", i + 1)
+    });
+    for (waypoint, (identifier, first)) in module.waypoints.iter().zip(&names) {
+        if !first {
+            continue;
+        }
+        lines.push(match &waypoint.target {
+            Target::Pose(pose) => {
+                let (cf1, cf4, cf6, cfx) = waypoint.configuration.unwrap_or((0, 0, 0, 0));
+                format!(
+                    "    CONST robtarget {}:=[{},[{},{},{},{}],{}];",
+                    identifier,
+                    rapid_pose(pose),
+                    cf1,
+                    cf4,
+                    cf6,
+                    cfx,
+                    NO_EXTERNAL_AXES
+                )
+            }
+            Target::Joints(joints) => {
+                let joints: Vec<String> = joints
+                    .iter()
+                    .map(|joint| decimal(joint.to_degrees(), 4))
+                    .collect();
+                format!(
+                    "    CONST jointtarget {}:=[[{}],{}];",
+                    identifier,
+                    joints.join(","),
+                    NO_EXTERNAL_AXES
+                )
+            }
+        });
+    }
+    let mut moves = Vec::new();
+    let mut data = Vec::new();
+    let mut declared: HashMap<String, String> = HashMap::new();
+    for (waypoint, (identifier, _)) in module.waypoints.iter().zip(&names) {
+        let tool = waypoint
+            .tool
+            .as_ref()
+            .and_then(|tool| tools.get(tool))
+            .map_or("tool0", String::as_str);
+        let work_object = match work_objects.get(&waypoint.frame) {
+            Some(wobj) => format!("\\WObj:={}", wobj),
+            None => String::new(),
+        };
+        let mut data_name = |value: Option<f64>, kind: &str| {
+            let (name, declaration) = match kind {
+                "speed" => speed_data(value),
+                _ => zone_data(value),
+            };
+            let Some(declaration) = declaration else {
+                return name;
+            };
+            declared
+                .entry(name.clone())
+                .or_insert_with(|| {
+                    let identifier = identifiers.add(&name, kind);
+                    data.push(declaration.replacen("{}", &identifier, 1));
+                    identifier
+                })
+                .clone()
+        };
+        let speed = data_name(waypoint.speed, "speed");
+        let zone = data_name(waypoint.zone, "zone");
+        moves.push(match (&waypoint.target, waypoint.motion) {
+            (Target::Joints(_), _) => {
+                format!(
+                    "        MoveAbsJ {}\\NoEOffs, {}, {}, {};",
+                    identifier, speed, zone, tool
+                )
+            }
+            (Target::Pose(_), motion) => format!(
+                "        {} {}, {}, {}, {}{};",
+                match motion {
+                    Some(Motion::Linear) => "MoveL",
+                    _ => "MoveJ",
+                },
+                identifier,
+                speed,
+                zone,
+                tool,
+                work_object
+            ),
+        });
+    }
+    lines.extend(data);
+    lines.push(String::new());
+    lines.push("    PROC main()".to_string());
+    lines.extend(moves);
+    lines.push("    ENDPROC".to_string());
+    lines.push("ENDMODULE".to_string());
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODULE: &str = r#"
+MODULE Weld(SYSMODULE)
+    ! Seam on the fixture
+    PERS tooldata torch:=[TRUE,[[0,0,400],[1,0,0,0]],[2,[0,0,100],[1,0,0,0],0,0,0]];
+    TASK PERS wobjdata fixture:=[FALSE,TRUE,"",[[1000,0,500],[1,0,0,0]],[[0,100,0],[1,0,0,0]]];
+    CONST robtarget p10:=[[10,20,30],[0,1,0,0],[0,0,-1,1],[9E+09,9E+09,9E+09,9E+09,9E+09,9E+09]];
+    CONST jointtarget home:=[[0,0,0,0,90,0],[9E+09,9E+09,9E+09,9E+09,9E+09,9E+09]];
+    CONST speeddata weld_speed:=[8,500,5000,1000];
+    PROC main()
+        MoveAbsJ home\NoEOffs, v1000, fine, tool0;
+        MoveJ p10, v500, z10, torch\WObj:=fixture; ! approach
+        MoveL Offs(p10, 0, 0, -30), weld_speed, fine, torch\WObj:=fixture;
+        MoveL [[0,0,0],[0,1,0,0],[0,0,0,0],[9E+09,9E+09,9E+09,9E+09,9E+09,9E+09]],
+            v100\V:=150, z5\Z:=7, torch\WObj:=fixture;
+        MoveC p10, p10, v100, z10, torch;
+    ENDPROC
+    PROC other()
+        MoveJ RelTool(p10, 0, 0, -50\Rz:=90), v100, z10, tool0;
+    ENDPROC
+ENDMODULE
+"#;
+
+    #[test]
+    fn test_parse_rapid_module() {
+        let module = parse(MODULE, None).unwrap();
+        assert_eq!(module.name, "Weld");
+        let names: Vec<&str> = module.waypoints.iter().map(|w| w.name.as_str()).collect();
+        assert_eq!(names, ["home", "p10", "Offs(p10,0,0,-30)", "*"]);
+        let [home, p10, offs, inline] = &module.waypoints[..] else {
+            panic!("four moves");
+        };
+        assert_eq!(
+            home.target,
+            Target::Joints([0.0, 0.0, 0.0, 0.0, 90f64.to_radians(), 0.0])
+        );
+        assert_eq!(home.frame, WORLD);
+        assert_eq!((home.speed, home.zone), (Some(1.0), Some(0.0)));
+        assert_eq!(p10.configuration, Some((0, 0, -1, 1)));
+        assert_eq!((p10.motion, p10.zone), (Some(Motion::Joint), Some(0.01)));
+        assert_eq!(
+            (p10.frame.as_str(), p10.tool.as_deref()),
+            ("fixture", Some("torch"))
+        );
+        let Target::Pose(pose) = &offs.target else {
+            panic!("a pose")
+        };
+        assert!((pose.translation.vector - Vector3::new(0.01, 0.02, 0.0)).norm() < 1e-12);
+        assert_eq!(
+            (offs.motion, offs.speed),
+            (Some(Motion::Linear), Some(0.008))
+        );
+        assert_eq!((inline.speed, inline.zone), (Some(0.15), Some(0.007)));
+
+        assert_eq!(
+            module.tools[0],
+            ("tool0".to_string(), Isometry3::identity())
+        );
+        assert!((module.tools[1].1.translation.vector.z - 0.4).abs() < 1e-12);
+        let (name, fixture) = &module.work_objects[0];
+        assert_eq!(name, "fixture");
+        assert!((fixture.translation.vector - Vector3::new(1.0, 0.1, 0.5)).norm() < 1e-12);
+
+        // Rotating about the tool z axis after moving 50 mm back along it
+        let other = parse(MODULE, Some("other")).unwrap();
+        let Target::Pose(pose) = &other.waypoints[0].target else {
+            panic!("a pose")
+        };
+        // The tool z axis of p10 points along world -z
+        assert!((pose.translation.vector - Vector3::new(0.01, 0.02, 0.08)).norm() < 1e-12);
+        assert!((pose.rotation * Vector3::x() - Vector3::new(0.0, -1.0, 0.0)).norm() < 1e-12);
+        assert!(parse(MODULE, Some("missing")).is_err());
+        assert!(parse("PROC main()\n MoveL p99, v100, z10, tool0;\nENDPROC", None).is_err());
+    }
+
+    #[test]
+    fn test_cyclic_targets_are_an_error() {
+        for declarations in [
+            "CONST robtarget p1:=p1;",
+            "CONST robtarget p1:=p2;\nCONST robtarget p2:=p1;",
+            "CONST robtarget p1:=Offs(p2, 0, 0, 10);\nCONST robtarget p2:=RelTool(p1, 0, 0, 10);",
+        ] {
+            let text = format!(
+                "MODULE Loop\n{}\nPROC main()\n MoveL p1, v100, z10, tool0;\nENDPROC\nENDMODULE",
+                declarations
+            );
+            let Err(error) = parse(&text, None) else {
+                panic!("{} is cyclic", declarations)
+            };
+            assert!(error.contains("Cyclic reference"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn test_write_rapid_reads_back() {
+        let module = parse(MODULE, None).unwrap();
+        let text = write(&module);
+        assert!(text.contains(
+            "    PERS tooldata torch:=[TRUE,[[0,0,400],[1,0,0,0]],[0.001,[0,0,0.001],[1,0,0,0],0,0,0]];"
+        ));
+        assert!(text.contains("        MoveAbsJ home\\NoEOffs, v1000, fine, tool0;"));
+        // Inline and function targets get a declaration of their own
+        assert!(text.contains("CONST robtarget Offs_p10_0_0_30:=[[10,20,0],"));
+        assert!(text.contains("        MoveL p4, v150, z7, torch\\WObj:=fixture;"));
+        assert!(text.contains("    CONST speeddata v8:=[8,500,5000,1000];"));
+        assert!(text.contains("    CONST zonedata z7:=[FALSE,7,10.5,10.5,1.05,10.5,1.05];"));
+        let read = parse(&text, None).unwrap();
+        assert_eq!(read.work_objects, module.work_objects);
+        assert_eq!(read.tools[1], module.tools[1]);
+        for (read, written) in read.waypoints.iter().zip(&module.waypoints) {
+            assert_eq!(
+                (read.frame.as_str(), read.motion),
+                (written.frame.as_str(), written.motion)
+            );
+            assert_eq!((read.speed, read.zone), (written.speed, written.zone));
+            match (&read.target, &written.target) {
+                (Target::Pose(read), Target::Pose(written)) => {
+                    assert!((read.translation.vector - written.translation.vector).norm() < 1e-9)
+                }
+                (read, written) => assert_eq!(read, written),
+            }
+        }
+
+        let mut identifiers = Identifiers::new(8, &["tool0"]);
+        assert_eq!(identifiers.add("Tool0", "tool"), "Tool0_2");
+        assert_eq!(identifiers.add("12 abc", "p1"), "p1");
+        assert_eq!(identifiers.add("long_name_here", "p"), "long_nam");
+        assert_eq!(identifiers.add("long_name_too", "p"), "long_n_2");
+        assert_eq!(decimal(-0.00001, 3), "0");
+        assert_eq!(decimal(1.5, 3), "1.5");
+    }
+}
@@ -0,0 +1,95 @@
+use nalgebra::{Isometry3, Matrix6, UnitQuaternion, Vector3};
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::f64::consts::TAU;
+
+/// Joint step of the numerical Jacobian, in radians
+const JACOBIAN_STEP: f64 = 1e-7;
+
+/// Jacobian of the TCP position (m) and rotation (rad) in the world frame with respect to
+/// the joints (rad), by forward differences of `forward`
+pub fn jacobian(forward: impl Fn(&Joints) -> Isometry3<f64>, joints: &Joints) -> Matrix6<f64> {
+    let pose = forward(joints);
+    let mut jacobian = Matrix6::zeros();
+    for k in 0..6 {
+        let mut stepped = *joints;
+        stepped[k] += JACOBIAN_STEP;
+        let moved = forward(&stepped);
+        let translation = moved.translation.vector - pose.translation.vector;
+        let rotation = (moved.rotation * pose.rotation.inverse()).scaled_axis();
+        for row in 0..3 {
+            jacobian[(row, k)] = translation[row] / JACOBIAN_STEP;
+            jacobian[(row + 3, k)] = rotation[row] / JACOBIAN_STEP;
+        }
+    }
+    jacobian
+}
+
+/// Yoshikawa manipulability sqrt(det(J J^T)), which is |det J| for six joints. Zero at
+/// singularities and larger the more freely the TCP moves in every direction.
+pub fn manipulability(forward: impl Fn(&Joints) -> Isometry3<f64>, joints: &Joints) -> f64 {
+    jacobian(forward, joints).determinant().abs()
+}
+
+/// Orientations of a fan around the z axis of `frame` as (tilt, roll, rotation): the z
+/// axis tilted by `samples` angles from -`max_tilt` to `max_tilt` (rad) about the x axis of
+/// `frame`, each rolled by `samples` angles over a full turn about the tilted axis
+pub fn fan(
+    frame: &UnitQuaternion<f64>,
+    max_tilt: f64,
+    samples: usize,
+) -> Vec<(f64, f64, UnitQuaternion<f64>)> {
+    let tilts: Vec<f64> = if samples > 1 {
+        (0..samples)
+            .map(|i| -max_tilt + 2.0 * max_tilt * i as f64 / (samples - 1) as f64)
+            .collect()
+    } else {
+        vec![0.0]
+    };
+    let rolls: Vec<f64> = (0..samples)
+        .map(|i| -TAU / 2.0 + TAU * i as f64 / samples as f64)
+        .collect();
+    tilts
+        .iter()
+        .flat_map(|&tilt| {
+            rolls.iter().map(move |&roll| {
+                let rotation = frame
+                    * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), tilt)
+                    * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), roll);
+                (tilt, roll, rotation)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+    use rs_opw_kinematics::kinematic_traits::Kinematics;
+
+    #[test]
+    fn test_manipulability_vanishes_at_singularities() {
+        let robot = ABB_1660.to_opw_kinematics();
+        let forward = |joints: &Joints| robot.forward(joints);
+        let regular = manipulability(forward, &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+        // J5 = 0 aligns the axes of J4 and J6
+        let wrist = manipulability(forward, &[0.1, 0.2, 0.3, 0.4, 0.0, 0.6]);
+        assert!(regular > 1e-3);
+        assert!(wrist < 1e-6 * regular);
+    }
+
+    #[test]
+    fn test_fan_tilts_about_reference_x() {
+        let frame = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.5);
+        let fan = fan(&frame, 0.6, 3);
+        assert_eq!(fan.len(), 9);
+        let axis = frame * Vector3::z();
+        for (tilt, roll, rotation) in fan {
+            let z = rotation * Vector3::z();
+            assert!((z.angle(&axis) - tilt.abs()).abs() < 1e-12);
+            assert!((-TAU / 2.0..TAU / 2.0).contains(&roll));
+            // Tilting keeps the tool axis in the plane normal to the reference x axis
+            assert!(z.dot(&(frame * Vector3::x())).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+/// How a continuously solved pose without a solution near the previous joints is
+/// recovered, tried in the order given
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Recovery {
+    /// Retry without the singularity handling of the continuing solver and without
+    /// `max_step`
+    Relaxed,
+    /// Retry from the default seed, the stored postures and the zero position
+    Seeds,
+    /// Interpolate the joints between the solved rows around the gap
+    Interpolate,
+}
+
+impl Recovery {
+    pub const ALL: [Recovery; 3] = [Recovery::Relaxed, Recovery::Seeds, Recovery::Interpolate];
+
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "relaxed" => Ok(Recovery::Relaxed),
+            "seeds" => Ok(Recovery::Seeds),
+            "interpolate" => Ok(Recovery::Interpolate),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown recovery '{}', expected 'relaxed', 'seeds' or 'interpolate'",
+                name
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Recovery::Relaxed => "relaxed",
+            Recovery::Seeds => "seeds",
+            Recovery::Interpolate => "interpolate",
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for Recovery {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        Recovery::parse(&obj.extract::<String>()?)
+    }
+}
+
+/// Fills the gaps of `rows` for which `fill` holds with joints interpolated linearly by
+/// row between the solved rows on either side. Gaps at the start or end stay.
+pub fn interpolate(rows: &mut [Option<(Joints, Option<Recovery>)>], fill: impl Fn(usize) -> bool) {
+    let mut before: Option<usize> = None;
+    for after in 0..rows.len() {
+        let Some((end, _)) = rows[after] else {
+            continue;
+        };
+        if let Some(before) = before.filter(|&before| after > before + 1) {
+            let (start, _) = rows[before].expect("a solved row");
+            let span = (after - before) as f64;
+            let gap = rows.iter_mut().enumerate().take(after).skip(before + 1);
+            for (i, row) in gap.filter(|(i, _)| fill(*i)) {
+                let share = (i - before) as f64 / span;
+                let joints = std::array::from_fn(|k| start[k] + share * (end[k] - start[k]));
+                *row = Some((joints, Some(Recovery::Interpolate)));
+            }
+        }
+        before = Some(after);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate_gaps() {
+        let solved = |j: f64| Some(([j; 6], None));
+        let mut rows = vec![None, solved(0.0), None, None, None, solved(0.4), None];
+        // Row 3 is invalid and stays empty
+        interpolate(&mut rows, |i| i != 3);
+        assert_eq!(rows[0], None);
+        assert_eq!(rows[2], Some(([0.1; 6], Some(Recovery::Interpolate))));
+        assert_eq!(rows[3], None);
+        assert!((rows[4].unwrap().0[0] - 0.3).abs() < 1e-12);
+        assert_eq!(rows[6], None);
+        assert!(Recovery::parse("retry").is_err());
+    }
+}
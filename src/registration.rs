@@ -0,0 +1,221 @@
+use nalgebra::{Isometry3, Matrix3, Rotation3, Translation3, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::columns::PoseTable;
+use crate::pose::{pose_errors, to_isometry};
+use crate::transform_out;
+use crate::validation;
+
+/// A pose in the robot base frame and the same pose measured in the world frame
+pub type Pair = (Isometry3<f64>, Isometry3<f64>);
+
+/// Smallest ratio of the second to the largest singular value of the correlation matrix
+/// for the rotation to be determined
+const RANK_TOLERANCE: f64 = 1e-9;
+
+/// Least-squares base `B` with `B * robot ≈ measured` for pairs of (pose in the robot base
+/// frame, pose measured in the world frame). Positions are fitted in meters, and rotations
+/// by the chordal distance of their matrices scaled by `orientation_weight` (m), so 0 fits
+/// positions only. None if the pairs do not determine the rotation, e.g. fewer than three
+/// positions off a line without orientations.
+pub fn fit_base(pairs: &[Pair], orientation_weight: f64) -> Option<Isometry3<f64>> {
+    if pairs.is_empty() {
+        return None;
+    }
+    let n = pairs.len() as f64;
+    let centroid =
+        |pick: fn(&Pair) -> Vector3<f64>| pairs.iter().map(pick).sum::<Vector3<f64>>() / n;
+    let robot_mean = centroid(|(robot, _)| robot.translation.vector);
+    let measured_mean = centroid(|(_, measured)| measured.translation.vector);
+
+    // Maximizing trace(R^T H) minimizes both residuals at once
+    let weight = orientation_weight * orientation_weight;
+    let correlation: Matrix3<f64> = pairs
+        .iter()
+        .map(|(robot, measured)| {
+            (measured.translation.vector - measured_mean)
+                * (robot.translation.vector - robot_mean).transpose()
+                + weight
+                    * measured.rotation.to_rotation_matrix().matrix()
+                    * robot.rotation.to_rotation_matrix().matrix().transpose()
+        })
+        .sum();
+    let svd = correlation.svd(true, true);
+    let mut singular = svd.singular_values.as_slice().to_vec();
+    singular.sort_by(|a, b| b.total_cmp(a));
+    if !(singular[0] > 0.0 && singular[1] > RANK_TOLERANCE * singular[0]) {
+        return None;
+    }
+    let (u, v_t) = (svd.u?, svd.v_t?);
+    // Flip the axis of the smallest singular value if the fit came out a reflection
+    let sign = (u * v_t).determinant().signum();
+    let smallest = (0..3)
+        .min_by(|&a, &b| svd.singular_values[a].total_cmp(&svd.singular_values[b]))
+        .unwrap_or(2);
+    let mut correction = Matrix3::identity();
+    correction[(smallest, smallest)] = sign;
+    let rotation = Rotation3::from_matrix_unchecked(u * correction * v_t);
+    let translation = measured_mean - rotation * robot_mean;
+    Some(Isometry3::from_parts(
+        Translation3::from(translation),
+        UnitQuaternion::from_rotation_matrix(&rotation),
+    ))
+}
+
+/// Position (m) and rotation (radians) residual of every pair under the base
+pub fn residuals(base: &Isometry3<f64>, pairs: &[Pair]) -> Vec<(f64, f64)> {
+    pairs
+        .iter()
+        .map(|(robot, measured)| pose_errors(&(base * robot), measured))
+        .collect()
+}
+
+/// Root mean square of `values`
+fn rms(values: &[f64]) -> f64 {
+    (values.iter().map(|value| value * value).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+fn max(values: &[f64]) -> f64 {
+    values.iter().copied().fold(0.0, f64::max)
+}
+
+/// Raises unless both sides of a fit have the same number of entries
+fn check_pairs(a: usize, b: usize) -> PyResult<()> {
+    if a != b {
+        return Err(PyValueError::new_err(format!(
+            "Got {} and {} entries, expected pairs",
+            a, b
+        )));
+    }
+    Ok(())
+}
+
+/// Best-fit rigid transform `T` with `T * a ≈ b` for corresponding points (Kabsch), e.g. to
+/// register points measured in a tracker frame to the robot frame. Returns the transform
+/// (as a 4x4 matrix with `as_matrix`) with the distance (m) of every fitted point from its
+/// counterpart as residuals, and their RMS and maximum.
+#[pyfunction]
+#[pyo3(signature = (points_a, points_b, as_matrix=false))]
+pub fn fit_transform<'py>(
+    py: Python<'py>,
+    points_a: Vec<[f64; 3]>,
+    points_b: Vec<[f64; 3]>,
+    as_matrix: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    check_pairs(points_a.len(), points_b.len())?;
+    let pairs = points_a
+        .iter()
+        .zip(&points_b)
+        .map(|(a, b)| {
+            validation::check_finite("points_a", a, a.iter().all(|x| x.is_finite()))?;
+            validation::check_finite("points_b", b, b.iter().all(|x| x.is_finite()))?;
+            Ok((
+                Isometry3::translation(a[0], a[1], a[2]),
+                Isometry3::translation(b[0], b[1], b[2]),
+            ))
+        })
+        .collect::<PyResult<Vec<Pair>>>()?;
+    let transform = fit_base(&pairs, 0.0).ok_or_else(|| {
+        PyValueError::new_err(
+            "The points do not determine the transform, give at least three points off a line",
+        )
+    })?;
+    let distances: Vec<f64> = residuals(&transform, &pairs)
+        .iter()
+        .map(|(position, _)| *position)
+        .collect();
+    let dict = PyDict::new(py);
+    dict.set_item("transform", transform_out(py, &transform, as_matrix)?)?;
+    dict.set_item("rms_error", rms(&distances))?;
+    dict.set_item("max_error", max(&distances))?;
+    dict.set_item("residuals", distances)?;
+    Ok(dict)
+}
+
+/// Best-fit rigid transform `T` with `T * a ≈ b` for corresponding poses, fitting positions
+/// and, weighted by `orientation_weight` (m per radian), orientations. Returns the
+/// transform with the position (m) and rotation (degrees) residual of every pair and their
+/// RMS and maximum.
+#[pyfunction]
+#[pyo3(signature = (poses_a, poses_b, orientation_weight=1.0, as_matrix=false))]
+pub fn fit_pose_transform<'py>(
+    py: Python<'py>,
+    poses_a: PoseTable,
+    poses_b: PoseTable,
+    orientation_weight: f64,
+    as_matrix: bool,
+) -> PyResult<Bound<'py, PyDict>> {
+    check_pairs(poses_a.0.len(), poses_b.0.len())?;
+    if !(orientation_weight.is_finite() && orientation_weight >= 0.0) {
+        return Err(PyValueError::new_err(
+            "orientation_weight must be finite and not negative",
+        ));
+    }
+    let pairs = poses_a
+        .0
+        .iter()
+        .zip(&poses_b.0)
+        .map(|(a, b)| {
+            validation::check_pose(a)?;
+            validation::check_pose(b)?;
+            Ok((to_isometry(a.0, a.1), to_isometry(b.0, b.1)))
+        })
+        .collect::<PyResult<Vec<Pair>>>()?;
+    let transform = fit_base(&pairs, orientation_weight).ok_or_else(|| {
+        PyValueError::new_err(
+            "The poses do not determine the transform, give at least three positions off a \
+             line or a positive orientation_weight",
+        )
+    })?;
+    let (positions, rotations): (Vec<f64>, Vec<f64>) = residuals(&transform, &pairs)
+        .iter()
+        .map(|(position, rotation)| (*position, rotation.to_degrees()))
+        .unzip();
+    let dict = PyDict::new(py);
+    dict.set_item("transform", transform_out(py, &transform, as_matrix)?)?;
+    dict.set_item("rms_position", rms(&positions))?;
+    dict.set_item("max_position", max(&positions))?;
+    dict.set_item("rms_rotation", rms(&rotations))?;
+    dict.set_item("max_rotation", max(&rotations))?;
+    dict.set_item("position_residuals", positions)?;
+    dict.set_item("rotation_residuals", rotations)?;
+    Ok(dict)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_base_recovers_the_base() {
+        let base = Isometry3::from_parts(
+            Translation3::new(1.5, -0.4, 0.7),
+            UnitQuaternion::from_euler_angles(0.1, -0.2, 2.5),
+        );
+        let robot: Vec<Isometry3<f64>> = (0..5)
+            .map(|i| {
+                let i = i as f64;
+                Isometry3::from_parts(
+                    Translation3::new(0.8 + 0.1 * i, 0.3 * i.sin(), 0.5 + 0.05 * i * i),
+                    UnitQuaternion::from_euler_angles(0.3 * i, 0.2, -0.1 * i),
+                )
+            })
+            .collect();
+        let pairs: Vec<_> = robot.iter().map(|pose| (*pose, base * pose)).collect();
+        for weight in [0.0, 1.0] {
+            let fitted = fit_base(&pairs, weight).unwrap();
+            let (position, rotation) = pose_errors(&fitted, &base);
+            assert!(position < 1e-12 && rotation < 1e-12);
+            assert!(residuals(&fitted, &pairs)
+                .iter()
+                .all(|(p, r)| *p < 1e-12 && *r < 1e-9));
+        }
+
+        // One pose fixes the base through its orientation, but not from its position alone
+        assert!(fit_base(&pairs[..1], 1.0).is_some());
+        assert!(fit_base(&pairs[..1], 0.0).is_none());
+        assert!(fit_base(&pairs[..2], 0.0).is_none());
+    }
+}
@@ -0,0 +1,161 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+/// How joints are interpolated between the points of a trajectory
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    /// Straight lines, the velocity jumps at every point
+    Linear,
+    /// Cubic Hermite segments with the tangents of neighbouring points (Catmull-Rom), the
+    /// velocity is continuous
+    Cubic,
+}
+
+impl Interpolation {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "linear" => Ok(Interpolation::Linear),
+            "cubic" => Ok(Interpolation::Cubic),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown interpolation '{}', expected 'linear' or 'cubic'",
+                name
+            ))),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for Interpolation {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        Interpolation::parse(&obj.extract::<String>()?)
+    }
+}
+
+/// Checks that a trajectory has as many finite, strictly increasing times as points
+pub fn check_times(times: &[f64], points: usize) -> PyResult<()> {
+    if times.len() != points {
+        return Err(PyValueError::new_err(format!(
+            "Got {} times for {} joint rows",
+            times.len(),
+            points
+        )));
+    }
+    if times.is_empty() {
+        return Err(PyValueError::new_err("The trajectory has no points"));
+    }
+    if let Some(i) = times.iter().position(|t| !t.is_finite()) {
+        return Err(PyValueError::new_err(format!(
+            "Time of row {} is not finite",
+            i
+        )));
+    }
+    match times.windows(2).position(|pair| pair[1] <= pair[0]) {
+        Some(i) => Err(PyValueError::new_err(format!(
+            "Times must increase, row {} is not after row {}",
+            i + 1,
+            i
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Velocity of every joint at point `i`, from its neighbours
+pub fn tangent(times: &[f64], joints: &[Joints], i: usize) -> Joints {
+    let (before, after) = (i.saturating_sub(1), (i + 1).min(times.len() - 1));
+    let dt = times[after] - times[before];
+    std::array::from_fn(|k| match dt > 0.0 {
+        true => (joints[after][k] - joints[before][k]) / dt,
+        false => 0.0,
+    })
+}
+
+/// Joints at time `t`, held at the ends of the trajectory
+pub fn interpolate(
+    times: &[f64],
+    joints: &[Joints],
+    t: f64,
+    interpolation: Interpolation,
+) -> Joints {
+    let last = times.len() - 1;
+    if t <= times[0] {
+        return joints[0];
+    }
+    if t >= times[last] {
+        return joints[last];
+    }
+    // The segment [times[i], times[i + 1]) containing t
+    let i = times.partition_point(|time| *time <= t) - 1;
+    let h = times[i + 1] - times[i];
+    let s = (t - times[i]) / h;
+    let (a, b) = (&joints[i], &joints[i + 1]);
+    match interpolation {
+        Interpolation::Linear => std::array::from_fn(|k| a[k] + s * (b[k] - a[k])),
+        Interpolation::Cubic => {
+            let (ta, tb) = (tangent(times, joints, i), tangent(times, joints, i + 1));
+            let (s2, s3) = (s * s, s * s * s);
+            let (h00, h10) = (2.0 * s3 - 3.0 * s2 + 1.0, s3 - 2.0 * s2 + s);
+            let (h01, h11) = (-2.0 * s3 + 3.0 * s2, s3 - s2);
+            std::array::from_fn(|k| h00 * a[k] + h10 * h * ta[k] + h01 * b[k] + h11 * h * tb[k])
+        }
+    }
+}
+
+/// Samples a trajectory every `period` from its first time until the first sample at or
+/// after its last, which holds the last point. Returns (time, joints) per sample.
+pub fn resample(
+    times: &[f64],
+    joints: &[Joints],
+    period: f64,
+    interpolation: Interpolation,
+) -> Vec<(f64, Joints)> {
+    let (start, end) = (times[0], times[times.len() - 1]);
+    // Tolerate the rounding of a duration that is a whole number of periods
+    let samples = ((end - start) / period - 1e-9).ceil().max(0.0) as usize + 1;
+    (0..samples)
+        .map(|k| {
+            let t = start + k as f64 * period;
+            (t, interpolate(times, joints, t, interpolation))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample_at_the_controller_period() {
+        let times = [0.0, 0.01, 0.03];
+        let joints = [[0.0; 6], [0.1; 6], [0.1, 0.2, 0.3, 0.4, 0.5, 0.6]];
+        check_times(&times, joints.len()).unwrap();
+        let samples = resample(&times, &joints, 0.004, Interpolation::Linear);
+        // 0.03 s is 7.5 periods, the ninth sample at 0.032 s holds the last point
+        assert_eq!(samples.len(), 9);
+        assert!((samples[1].0 - 0.004).abs() < 1e-12);
+        assert!((samples[1].1[0] - 0.04).abs() < 1e-12);
+        assert_eq!(samples[8].1, joints[2]);
+
+        let cubic = resample(&times, &joints, 0.001, Interpolation::Cubic);
+        for (t, point) in times.iter().zip(&joints) {
+            let sample = cubic
+                .iter()
+                .find(|(time, _)| (time - t).abs() < 1e-9)
+                .unwrap();
+            assert!(sample
+                .1
+                .iter()
+                .zip(point)
+                .all(|(a, b)| (a - b).abs() < 1e-9));
+        }
+        assert_eq!(
+            resample(&[1.0], &joints[..1], 0.004, Interpolation::Cubic).len(),
+            1
+        );
+
+        assert!(check_times(&[0.0, 0.0], 2).is_err());
+        assert!(check_times(&[0.0, 1.0], 3).is_err());
+        assert!(Interpolation::parse("spline").is_err());
+    }
+}
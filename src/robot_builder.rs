@@ -0,0 +1,624 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::calibrated::{CalibratedChain, Corrections};
+use crate::comfort::Comfort;
+use crate::kinematic_model::KinematicModel;
+use crate::pose::to_isometry;
+use crate::postures::{self, Postures};
+use crate::ranking::Ranking;
+use crate::validation;
+use crate::{BaseConfig, Robot, SolutionPolicy, ToolConfig};
+
+const IDENTITY_ROTATION: [f64; 4] = [1.0, 0.0, 0.0, 0.0];
+
+/// Step by step construction of a Robot, validated at `build()`.
+#[pyclass(skip_from_py_object)]
+#[derive(Clone, Default)]
+pub struct RobotBuilder {
+    pub(crate) kinematic_model: Option<KinematicModel>,
+    pub(crate) base_config: Option<BaseConfig>,
+    pub(crate) tool_config: Option<ToolConfig>,
+    pub(crate) joint_limits: Option<[(f64, f64); 6]>,
+    pub(crate) units: Option<String>,
+    pub(crate) solution_policy: Option<String>,
+    pub(crate) corrections: Option<Corrections>,
+    pub(crate) default_seed: Option<[f64; 6]>,
+    pub(crate) singularity_tolerance_deg: Option<f64>,
+    /// Posture cost with the preferred posture in the units of the robot
+    pub(crate) comfort: Option<Comfort>,
+    pub(crate) ranking: Option<Ranking>,
+    /// Named postures in the units of the robot
+    pub(crate) postures: Postures,
+    /// Mastering offsets per joint in the units of the robot
+    pub(crate) mastering_offsets: Option<[f64; 6]>,
+    pub(crate) stationary_tool: Option<ToolConfig>,
+}
+
+fn check_rotation(name: &str, rotation: &[f64; 4]) -> PyResult<()> {
+    let norm = rotation.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm.is_finite() && norm > 0.0 {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "The {} rotation must be a non-zero, finite quaternion, got {:?}",
+            name, rotation
+        )))
+    }
+}
+
+#[pymethods]
+impl RobotBuilder {
+    #[new]
+    fn new() -> Self {
+        RobotBuilder::default()
+    }
+
+    /// Sets the kinematic model (required)
+    fn model(mut slf: PyRefMut<'_, Self>, kinematic_model: KinematicModel) -> PyRefMut<'_, Self> {
+        slf.kinematic_model = Some(kinematic_model);
+        slf
+    }
+
+    /// Sets the base configuration, defaults to the world origin
+    fn base(mut slf: PyRefMut<'_, Self>, base_config: BaseConfig) -> PyRefMut<'_, Self> {
+        slf.base_config = Some(base_config);
+        slf
+    }
+
+    /// Sets the tool configuration, defaults to the bare flange
+    fn tool(mut slf: PyRefMut<'_, Self>, tool_config: ToolConfig) -> PyRefMut<'_, Self> {
+        slf.tool_config = Some(tool_config);
+        slf
+    }
+
+    /// Sets (min, max) limits for each of the six joints, in the units of the robot
+    fn joint_limits(
+        mut slf: PyRefMut<'_, Self>,
+        joint_limits: [[f64; 2]; 6],
+    ) -> PyRefMut<'_, Self> {
+        slf.joint_limits = Some(joint_limits.map(|[min, max]| (min, max)));
+        slf
+    }
+
+    /// Sets the joint units, "degrees" (default) or "radians"
+    fn units(mut slf: PyRefMut<'_, Self>, units: String) -> PyRefMut<'_, Self> {
+        slf.units = Some(units);
+        slf
+    }
+
+    /// Sets which inverse solutions are returned, "all" (default) or "closest"
+    fn solution_policy(mut slf: PyRefMut<'_, Self>, policy: String) -> PyRefMut<'_, Self> {
+        slf.solution_policy = Some(policy);
+        slf
+    }
+
+    /// Sets measured per-link corrections [vx, vy, vz, wx, wy, wz] (m, rad), one per joint,
+    /// applied on top of the nominal model
+    fn corrections(mut slf: PyRefMut<'_, Self>, corrections: Corrections) -> PyRefMut<'_, Self> {
+        slf.corrections = Some(corrections);
+        slf
+    }
+
+    /// Sets the preferred posture, e.g. the typical working posture, that seeds inverse
+    /// kinematics when no current joints are given, in the units of the robot. Without it
+    /// the solver centers on the zero position.
+    fn default_seed(mut slf: PyRefMut<'_, Self>, joints: [f64; 6]) -> PyRefMut<'_, Self> {
+        slf.default_seed = Some(joints);
+        slf
+    }
+
+    /// Sets how close J5 (degrees) may come to a multiple of 180 before the joints count as
+    /// at the wrist singularity, for near_singularity flags, filter_reachable and inverse
+    /// with exclude_singular. Defaults to 0.01, the threshold of the solver.
+    fn singularity_tolerance_deg(
+        mut slf: PyRefMut<'_, Self>,
+        tolerance: f64,
+    ) -> PyRefMut<'_, Self> {
+        slf.singularity_tolerance_deg = Some(tolerance);
+        slf
+    }
+
+    /// Stores a named joint posture such as "home", "mastering" or "service", in the units
+    /// of the robot. Postures can be read back with Robot.posture(), start programs and are
+    /// saved with the robot.
+    fn posture(mut slf: PyRefMut<'_, Self>, name: String, joints: [f64; 6]) -> PyRefMut<'_, Self> {
+        slf.postures.insert(name, joints);
+        slf
+    }
+
+    /// Sets per-joint mastering offsets, in the units of the robot: what the controller
+    /// reports minus the true joint angle, e.g. after a slightly off re-mastering. Joints
+    /// in and out stay as the controller reports them, the offsets are removed before the
+    /// kinematic model and added back to its solutions. Unlike the `offsets` of the
+    /// kinematic model they leave the nominal model untouched.
+    fn mastering_offsets(mut slf: PyRefMut<'_, Self>, offsets: [f64; 6]) -> PyRefMut<'_, Self> {
+        slf.mastering_offsets = Some(offsets);
+        slf
+    }
+
+    /// Sets the pose of a tool fixed in the cell, in the world frame, for a robot that holds
+    /// the part: the tool() is then the part frame on the flange, and poses in and out of
+    /// forward, inverse and the solving methods are where the stationary tool meets the
+    /// part, in the part frame
+    fn stationary_tool(mut slf: PyRefMut<'_, Self>, tool_config: ToolConfig) -> PyRefMut<'_, Self> {
+        slf.stationary_tool = Some(tool_config);
+        slf
+    }
+
+    /// Sets the posture cost of inverse with_comfort and order_by="comfort": the mean
+    /// squared distance from the `preferred` posture (the default seed or the zero position
+    /// without it, in the units of the robot) per joint relative to half its range, the
+    /// squared depth of the worst joint in the last `limit_zone` share of its range before
+    /// a limit, and the squared extension of the arm, each weighted
+    #[pyo3(signature = (
+        preferred=None,
+        posture_weight=1.0,
+        limit_weight=1.0,
+        extension_weight=1.0,
+        limit_zone=0.1,
+    ))]
+    fn comfort(
+        mut slf: PyRefMut<'_, Self>,
+        preferred: Option<[f64; 6]>,
+        posture_weight: f64,
+        limit_weight: f64,
+        extension_weight: f64,
+        limit_zone: f64,
+    ) -> PyRefMut<'_, Self> {
+        slf.comfort = Some(Comfort {
+            preferred,
+            posture_weight,
+            limit_weight,
+            extension_weight,
+            limit_zone,
+        });
+        slf
+    }
+
+    /// Sets the criteria that order_by="weighted" combines into one score per solution,
+    /// lowest first: 1 for a configuration other than `configuration` (a cfx, the seed's
+    /// by default), the summed joint distance from the seed in half turns, the shortfall
+    /// in manipulability against the most dexterous solution as a share of it, and the
+    /// shortfall of the smallest joint margin to the limits, relative to half the joint
+    /// range, each weighted
+    #[pyo3(signature = (
+        configuration=None,
+        configuration_weight=1.0,
+        distance_weight=1.0,
+        manipulability_weight=0.0,
+        limit_margin_weight=0.0,
+    ))]
+    fn ranking(
+        mut slf: PyRefMut<'_, Self>,
+        configuration: Option<u8>,
+        configuration_weight: f64,
+        distance_weight: f64,
+        manipulability_weight: f64,
+        limit_margin_weight: f64,
+    ) -> PyRefMut<'_, Self> {
+        slf.ranking = Some(Ranking {
+            configuration,
+            configuration_weight,
+            distance_weight,
+            manipulability_weight,
+            limit_margin_weight,
+        });
+        slf
+    }
+
+    /// Validates the collected settings and creates the Robot
+    pub(crate) fn build(&self) -> PyResult<Robot> {
+        let kinematic_model = self
+            .kinematic_model
+            .clone()
+            .ok_or_else(|| PyValueError::new_err("A kinematic model is required, call model()"))?;
+        let base_config = self
+            .base_config
+            .clone()
+            .unwrap_or_else(|| BaseConfig::new([0.0; 3], IDENTITY_ROTATION));
+        let tool_config = self
+            .tool_config
+            .clone()
+            .unwrap_or_else(|| ToolConfig::new([0.0; 3], IDENTITY_ROTATION));
+        check_rotation("base", &base_config.rotation)?;
+        check_rotation("tool", &tool_config.rotation)?;
+        if let Some(stationary_tool) = &self.stationary_tool {
+            check_rotation("stationary tool", &stationary_tool.rotation)?;
+            if stationary_tool.translation.iter().any(|x| !x.is_finite()) {
+                return Err(PyValueError::new_err(
+                    "The stationary tool translation must be finite",
+                ));
+            }
+        }
+
+        let radians = match self.units.as_deref() {
+            None | Some("degrees") => false,
+            Some("radians") => true,
+            Some(other) => {
+                return Err(PyValueError::new_err(format!(
+                    "Unknown units '{}', expected 'degrees' or 'radians'",
+                    other
+                )))
+            }
+        };
+
+        let joint_limits = match self.joint_limits {
+            Some(limits) => {
+                for (i, (min, max)) in limits.iter().enumerate() {
+                    if min.is_nan() || max.is_nan() || min > max {
+                        return Err(PyValueError::new_err(format!(
+                            "Joint {} has an invalid limit range ({}, {})",
+                            i + 1,
+                            min,
+                            max
+                        )));
+                    }
+                }
+                if radians {
+                    Some(limits)
+                } else {
+                    Some(limits.map(|(min, max)| (min.to_radians(), max.to_radians())))
+                }
+            }
+            None => None,
+        };
+
+        if let Some(seed) = &self.default_seed {
+            validation::check_joints("default_seed", seed)?;
+        }
+        let default_seed = self.default_seed.map(|seed| {
+            if radians {
+                seed
+            } else {
+                seed.map(f64::to_radians)
+            }
+        });
+
+        if let Some(offsets) = &self.mastering_offsets {
+            validation::check_joints("mastering_offsets", offsets)?;
+        }
+        let mastering = self.mastering_offsets.map_or([0.0; 6], |offsets| {
+            if radians {
+                offsets
+            } else {
+                offsets.map(f64::to_radians)
+            }
+        });
+
+        let postures = self
+            .postures
+            .iter()
+            .map(|(name, joints)| {
+                let joints = if radians {
+                    *joints
+                } else {
+                    joints.map(f64::to_radians)
+                };
+                postures::check(name, &joints, joint_limits.as_ref())?;
+                Ok((name.clone(), joints))
+            })
+            .collect::<PyResult<Postures>>()?;
+
+        let comfort = self.comfort.unwrap_or_default();
+        comfort.check()?;
+        let comfort = Comfort {
+            preferred: comfort.preferred.map(|preferred| {
+                if radians {
+                    preferred
+                } else {
+                    preferred.map(f64::to_radians)
+                }
+            }),
+            ..comfort
+        };
+
+        let ranking = self.ranking.unwrap_or_default();
+        ranking.check()?;
+
+        if let Some(tolerance) = self.singularity_tolerance_deg {
+            validation::check_positive("singularity_tolerance_deg", tolerance)?;
+        }
+
+        let solution_policy = match self.solution_policy.as_deref() {
+            Some(policy) => SolutionPolicy::parse(policy)?,
+            None => SolutionPolicy::All,
+        };
+
+        if let Some(corrections) = &self.corrections {
+            if corrections.iter().flatten().any(|x| !x.is_finite()) {
+                return Err(PyValueError::new_err("Corrections must be finite"));
+            }
+        }
+        let calibration = self.corrections.map(|corrections| {
+            CalibratedChain::new(
+                &kinematic_model,
+                corrections,
+                to_isometry(base_config.translation, base_config.rotation),
+                to_isometry(tool_config.translation, tool_config.rotation),
+            )
+        });
+
+        let mut robot = Robot::new(kinematic_model, base_config, tool_config)?;
+        robot.calibration = calibration;
+        robot.joint_limits = joint_limits;
+        robot.radians = radians;
+        robot.solution_policy = solution_policy;
+        robot.default_seed = default_seed;
+        robot.comfort = comfort;
+        robot.ranking = ranking;
+        robot.postures = postures;
+        robot.mastering = mastering;
+        robot.stationary_tool = self.stationary_tool.clone();
+        if let Some(tolerance) = self.singularity_tolerance_deg {
+            robot.singularity_tolerance = tolerance.to_radians();
+        }
+        Ok(robot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ordering::InverseOptions;
+    use crate::tests::ABB_1660;
+    use crate::SINGULARITY_TOLERANCE;
+
+    const POSE: ([f64; 3], [f64; 4]) = ([0.8, 0.2, 0.9], [0.0, 0.0, 1.0, 0.0]);
+
+    fn builder() -> RobotBuilder {
+        RobotBuilder {
+            kinematic_model: Some(ABB_1660),
+            ..RobotBuilder::default()
+        }
+    }
+
+    #[test]
+    fn test_build_requires_model() {
+        assert!(RobotBuilder::default().build().is_err());
+    }
+
+    #[test]
+    fn test_build_rejects_inverted_limits() {
+        let robot = RobotBuilder {
+            joint_limits: Some([(10.0, -10.0); 6]),
+            ..builder()
+        }
+        .build();
+        assert!(robot.is_err());
+    }
+
+    #[test]
+    fn test_joint_limits_filter_solutions() {
+        let all = builder()
+            .build()
+            .unwrap()
+            .solve(POSE, None, &InverseOptions::default());
+        let limited = RobotBuilder {
+            joint_limits: Some([(-90.0, 90.0); 6]),
+            ..builder()
+        }
+        .build()
+        .unwrap()
+        .solve(POSE, None, &InverseOptions::default());
+        assert!(limited.len() < all.len());
+        assert!(limited
+            .iter()
+            .all(|joints| joints.iter().all(|j| (-90.0..=90.0).contains(j))));
+    }
+
+    #[test]
+    fn test_closest_policy_and_radians() {
+        let all = builder()
+            .build()
+            .unwrap()
+            .solve(POSE, None, &InverseOptions::default());
+        let closest = RobotBuilder {
+            units: Some("radians".to_string()),
+            solution_policy: Some("closest".to_string()),
+            ..builder()
+        }
+        .build()
+        .unwrap()
+        .solve(POSE, None, &InverseOptions::default());
+        assert_eq!(closest.len(), 1);
+        for (radians, degrees) in closest[0].iter().zip(all[0].iter()) {
+            assert!((radians.to_degrees() - degrees).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_default_seed_picks_the_branch() {
+        let seed = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let robot = builder().build().unwrap();
+        let pose = robot.forward(seed, None).unwrap();
+        let options = InverseOptions::default();
+        let seeded = RobotBuilder {
+            default_seed: Some(seed),
+            ..builder()
+        }
+        .build()
+        .unwrap();
+        let first = seeded.solve(pose, None, &options)[0];
+        assert!(first.iter().zip(seed).all(|(a, b)| (a - b).abs() < 1e-6));
+        assert_eq!(seeded.solve(pose, Some(seed), &options)[0], first);
+        assert!(RobotBuilder {
+            default_seed: Some([f64::NAN; 6]),
+            ..builder()
+        }
+        .build()
+        .is_err());
+    }
+
+    #[test]
+    fn test_singularity_tolerance() {
+        let near = [10.0, 20.0, 30.0, 40.0, 2.0, 60.0].map(f64::to_radians);
+        assert!(builder()
+            .build()
+            .unwrap()
+            .is_regular(&near, SINGULARITY_TOLERANCE));
+        let robot = RobotBuilder {
+            singularity_tolerance_deg: Some(5.0),
+            ..builder()
+        }
+        .build()
+        .unwrap();
+        assert!(!robot.is_regular(&near, robot.singularity_tolerance));
+        // J5 near 180 degrees aligns J4 and J6 as well
+        let flipped = [0.0, 0.0, 0.0, 0.0, 177.0, 0.0].map(f64::to_radians);
+        assert!(!robot.is_regular(&flipped, robot.singularity_tolerance));
+        assert!(RobotBuilder {
+            singularity_tolerance_deg: Some(-1.0),
+            ..builder()
+        }
+        .build()
+        .is_err());
+    }
+
+    #[test]
+    fn test_postures() {
+        let home = [0.0, -30.0, 30.0, 0.0, 60.0, 0.0];
+        let robot = RobotBuilder {
+            postures: Postures::from([("home".to_string(), home)]),
+            ..builder()
+        }
+        .build()
+        .unwrap();
+        assert_eq!(robot.postures["home"], home.map(f64::to_radians));
+        assert!(robot.at_posture(home, "home", None, None).unwrap());
+        assert!(!robot.at_posture([0.0; 6], "home", None, None).unwrap());
+        assert!(robot.at_posture([0.0; 6], "service", None, None).is_err());
+        let beyond = RobotBuilder {
+            joint_limits: Some([(-20.0, 20.0); 6]),
+            postures: Postures::from([("home".to_string(), home)]),
+            ..builder()
+        }
+        .build();
+        assert!(beyond.is_err());
+    }
+
+    #[test]
+    fn test_mastering_offsets() {
+        let offsets = [0.5, -0.3, 0.2, 0.0, 0.4, -0.1];
+        let mastered = RobotBuilder {
+            mastering_offsets: Some(offsets),
+            ..builder()
+        }
+        .build()
+        .unwrap();
+        let nominal = builder().build().unwrap();
+
+        // Reported joints reach where the nominal robot is at the true angles
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let true_joints: Vec<f64> = joints.iter().zip(offsets).map(|(j, m)| j - m).collect();
+        let pose = mastered.forward(joints, None).unwrap();
+        let nominal_pose = nominal
+            .forward(true_joints.clone().try_into().unwrap(), None)
+            .unwrap();
+        for (a, b) in pose.0.iter().zip(nominal_pose.0.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        let solution = mastered.solve(pose, Some(joints), &InverseOptions::default())[0];
+        for (a, b) in solution.iter().zip(joints.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+
+        // Configuration flags and the extension come from the true angles, here with J5
+        // reported on the other side of zero
+        let joints = [10.0, 20.0, 30.0, 40.0, 0.2, 60.0];
+        let true_joints = std::array::from_fn(|k| joints[k] - offsets[k]);
+        let (reported, true_joints) = (
+            mastered.joints_in(joints, None),
+            nominal.joints_in(true_joints, None),
+        );
+        assert_eq!(
+            mastered.axis_configuration_radians(&reported),
+            nominal.axis_configuration_radians(&true_joints)
+        );
+        assert_ne!(
+            mastered.axis_configuration_radians(&reported).3,
+            nominal.axis_configuration_radians(&reported).3
+        );
+        let extension = mastered.comfort_score(&reported).extension;
+        assert!((extension - nominal.comfort_score(&true_joints).extension).abs() < 1e-12);
+        assert!(RobotBuilder {
+            mastering_offsets: Some([f64::NAN; 6]),
+            ..builder()
+        }
+        .build()
+        .is_err());
+    }
+
+    #[test]
+    fn test_stationary_tool() {
+        let tool = ToolConfig::new([1.2, 0.3, 0.9], [0.0, 0.0, 1.0, 0.0]);
+        let remote = RobotBuilder {
+            stationary_tool: Some(tool.clone()),
+            ..builder()
+        }
+        .build()
+        .unwrap();
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        // The target is the stationary tool seen from the part frame the robot holds
+        let part = builder().build().unwrap().forward(joints, None).unwrap();
+        let expected =
+            to_isometry(part.0, part.1).inverse() * to_isometry(tool.translation, tool.rotation);
+        let target = remote.forward(joints, None).unwrap();
+        for (a, b) in target.0.iter().zip(expected.translation.vector.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        let solutions = remote.solve(target, Some(joints), &InverseOptions::default());
+        for (a, b) in solutions[0].iter().zip(joints.iter()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+        assert_ne!(
+            remote.fingerprint(true),
+            builder().build().unwrap().fingerprint(true)
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let robot = builder().build().unwrap();
+        let limited = RobotBuilder {
+            joint_limits: Some([(-170.0, 170.0); 6]),
+            ..builder()
+        }
+        .build()
+        .unwrap();
+        assert_eq!(
+            robot.fingerprint(true),
+            builder().build().unwrap().fingerprint(true)
+        );
+        assert_ne!(robot.fingerprint(true), limited.fingerprint(true));
+        assert_eq!(robot.fingerprint(false), limited.fingerprint(false));
+        let mastered = RobotBuilder {
+            mastering_offsets: Some([0.0, 0.1, 0.0, 0.0, 0.0, 0.0]),
+            ..builder()
+        }
+        .build()
+        .unwrap();
+        assert_ne!(robot.fingerprint(true), mastered.fingerprint(true));
+    }
+
+    #[test]
+    fn test_corrections_round_trip() {
+        let mut corrections = [[0.0; 6]; 6];
+        corrections[2] = [0.0005, 0.0, 0.001, 0.0, -0.001, 0.0];
+        let calibrated = RobotBuilder {
+            corrections: Some(corrections),
+            ..builder()
+        }
+        .build()
+        .unwrap();
+        let nominal = builder().build().unwrap();
+
+        let joints = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let pose = calibrated.forward(joints, None).unwrap();
+        assert_ne!(pose, nominal.forward(joints, None).unwrap());
+        let solution = calibrated.solve(pose, Some(joints), &InverseOptions::default())[0];
+        for (a, b) in solution.iter().zip(joints.iter()) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+use crate::resampling;
+
+/// Joint names of the ROS-Industrial robot descriptions
+pub const DEFAULT_JOINT_NAMES: [&str; 6] = [
+    "joint_1", "joint_2", "joint_3", "joint_4", "joint_5", "joint_6",
+];
+
+/// ROS generation, which names the fields of a duration differently
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RosVersion {
+    /// secs and nsecs
+    Ros1,
+    /// sec and nanosec
+    Ros2,
+}
+
+impl RosVersion {
+    pub fn parse(version: u8) -> PyResult<Self> {
+        match version {
+            1 => Ok(RosVersion::Ros1),
+            2 => Ok(RosVersion::Ros2),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown ROS version {}, expected 1 or 2",
+                version
+            ))),
+        }
+    }
+
+    fn duration_fields(&self) -> (&'static str, &'static str) {
+        match self {
+            RosVersion::Ros1 => ("secs", "nsecs"),
+            RosVersion::Ros2 => ("sec", "nanosec"),
+        }
+    }
+}
+
+/// A trajectory_msgs/JointTrajectory: positions (radians) at times from the start (s),
+/// with velocities (rad/s) if given
+pub struct JointTrajectory {
+    pub joint_names: Vec<String>,
+    pub frame_id: String,
+    pub times: Vec<f64>,
+    pub positions: Vec<Joints>,
+    pub velocities: Option<Vec<Joints>>,
+    pub version: RosVersion,
+}
+
+/// Velocities at the points of a trajectory from their neighbours, at rest at both ends
+pub fn velocities(times: &[f64], joints: &[Joints]) -> Vec<Joints> {
+    (0..joints.len())
+        .map(|i| match i == 0 || i + 1 == joints.len() {
+            true => [0.0; 6],
+            false => resampling::tangent(times, joints, i),
+        })
+        .collect()
+}
+
+/// Whole seconds and nanoseconds of a time, as in a ROS duration
+pub fn duration(t: f64) -> (i64, u32) {
+    let nanoseconds = (t * 1e9).round() as i64;
+    (
+        nanoseconds.div_euclid(1_000_000_000),
+        nanoseconds.rem_euclid(1_000_000_000) as u32,
+    )
+}
+
+fn yaml_list(values: &[f64]) -> String {
+    // Debug keeps the decimal point, so that YAML reads whole numbers as floats
+    let values: Vec<String> = values.iter().map(|value| format!("{:?}", value)).collect();
+    format!("[{}]", values.join(", "))
+}
+
+impl JointTrajectory {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let (secs, nsecs) = self.version.duration_fields();
+        let header = PyDict::new(py);
+        header.set_item("frame_id", &self.frame_id)?;
+        let points = self
+            .times
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                let point = PyDict::new(py);
+                point.set_item("positions", self.positions[i])?;
+                let velocities = self.velocities.as_ref().map(|velocities| velocities[i]);
+                point.set_item("velocities", velocities.map_or(vec![], |v| v.to_vec()))?;
+                point.set_item("accelerations", Vec::<f64>::new())?;
+                point.set_item("effort", Vec::<f64>::new())?;
+                let (seconds, nanoseconds) = duration(*t);
+                let time = PyDict::new(py);
+                time.set_item(secs, seconds)?;
+                time.set_item(nsecs, nanoseconds)?;
+                point.set_item("time_from_start", time)?;
+                Ok(point)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let dict = PyDict::new(py);
+        dict.set_item("header", header)?;
+        dict.set_item("joint_names", &self.joint_names)?;
+        dict.set_item("points", points)?;
+        Ok(dict)
+    }
+
+    /// The message as YAML, as `rostopic pub` and `ros2 topic pub` read it
+    pub fn to_yaml(&self) -> String {
+        let (secs, nsecs) = self.version.duration_fields();
+        let mut yaml = format!(
+            "header:\n  frame_id: \"{}\"\njoint_names: [{}]\npoints:\n",
+            self.frame_id,
+            self.joint_names.join(", ")
+        );
+        for (i, t) in self.times.iter().enumerate() {
+            let velocities = self.velocities.as_ref().map(|velocities| velocities[i]);
+            let (seconds, nanoseconds) = duration(*t);
+            yaml.push_str(&format!(
+                "  - positions: {}\n    velocities: {}\n    accelerations: []\n    effort: []\n    time_from_start: {{{}: {}, {}: {}}}\n",
+                yaml_list(&self.positions[i]),
+                yaml_list(velocities.as_ref().map_or(&[], |v| v.as_slice())),
+                secs,
+                seconds,
+                nsecs,
+                nanoseconds
+            ));
+        }
+        yaml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_joint_trajectory_yaml() {
+        assert_eq!(duration(1.5), (1, 500_000_000));
+        assert_eq!(duration(0.004), (0, 4_000_000));
+        assert_eq!(duration(2.9999999999), (3, 0));
+
+        let times = [0.0, 0.5, 1.0];
+        let positions = [[0.0; 6], [0.5; 6], [1.5; 6]];
+        let velocities = velocities(&times, &positions);
+        assert_eq!(velocities[0], [0.0; 6]);
+        assert!((velocities[1][0] - 1.5).abs() < 1e-12);
+        assert_eq!(velocities[2], [0.0; 6]);
+
+        let trajectory = JointTrajectory {
+            joint_names: DEFAULT_JOINT_NAMES.map(String::from).to_vec(),
+            frame_id: "base_link".to_string(),
+            times: times.to_vec(),
+            positions: positions.to_vec(),
+            velocities: None,
+            version: RosVersion::Ros1,
+        };
+        let yaml = trajectory.to_yaml();
+        assert!(yaml.starts_with("header:\n  frame_id: \"base_link\"\njoint_names: [joint_1, "));
+        assert!(
+            yaml.contains("  - positions: [0.5, 0.5, 0.5, 0.5, 0.5, 0.5]\n    velocities: []\n")
+        );
+        assert!(yaml.ends_with("time_from_start: {secs: 1, nsecs: 0}\n"));
+        assert!(RosVersion::parse(3).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+use nalgebra::{Matrix3, Quaternion, Rotation3, Unit, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use crate::batch::ThreadPool;
+use crate::columns::QuaternionTable;
+
+/// Middle angle (radians) this close to a gimbal lock leaves only the sum or difference of
+/// the outer angles determined
+const GIMBAL_LOCK: f64 = 1e-7;
+
+/// Sequence of three elementary rotations, about the fixed axes (extrinsic) or the rotated
+/// axes (intrinsic)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EulerSequence {
+    /// Axis indices (0 for x) in the order the angles are given
+    axes: [usize; 3],
+    intrinsic: bool,
+}
+
+impl EulerSequence {
+    /// Extrinsic rotations about x, y and z, roll, pitch and yaw
+    pub const XYZ: EulerSequence = EulerSequence {
+        axes: [0, 1, 2],
+        intrinsic: false,
+    };
+
+    /// Lowercase for extrinsic ("xyz"), uppercase for intrinsic ("ZYX") as in SciPy, any
+    /// of the 6 Tait-Bryan and 6 proper Euler sequences. "kuka" is ZYX (A, B, C) and
+    /// "fanuc" xyz (W, P, R).
+    pub fn parse(name: &str) -> PyResult<Self> {
+        let name = match name {
+            "kuka" => "ZYX",
+            "fanuc" => "xyz",
+            name => name,
+        };
+        let error = || {
+            PyValueError::new_err(format!(
+                "Unknown Euler sequence '{}', expected three axes such as 'xyz' (extrinsic), 'ZYX' (intrinsic), 'kuka' or 'fanuc'",
+                name
+            ))
+        };
+        let intrinsic = name.chars().all(|c| c.is_ascii_uppercase());
+        if !(intrinsic || name.chars().all(|c| c.is_ascii_lowercase())) {
+            return Err(error());
+        }
+        let axes: Vec<usize> = name
+            .to_ascii_lowercase()
+            .chars()
+            .map(|c| "xyz".find(c).ok_or_else(error))
+            .collect::<PyResult<_>>()?;
+        match axes[..] {
+            [i, j, k] if i != j && j != k => Ok(EulerSequence {
+                axes: [i, j, k],
+                intrinsic,
+            }),
+            _ => Err(error()),
+        }
+    }
+
+    /// Rotation of three angles (radians) in this sequence
+    pub fn to_rotation(self, angles: [f64; 3]) -> UnitQuaternion<f64> {
+        let [a, b, c] = std::array::from_fn(|n| {
+            UnitQuaternion::from_axis_angle(&Unit::new_unchecked(unit(self.axes[n])), angles[n])
+        });
+        if self.intrinsic {
+            a * b * c
+        } else {
+            c * b * a
+        }
+    }
+
+    /// Angles (radians) of a rotation in this sequence, the first and last in [-180, 180)
+    /// degrees and the middle one in [0, 180] for proper Euler and [-90, 90] for Tait-Bryan
+    /// sequences. In gimbal lock the third angle is 0.
+    ///
+    /// The quaternion method of Bernardes and Viollet (2022), which covers every sequence
+    /// with the same steps.
+    pub fn angles(self, rotation: &UnitQuaternion<f64>) -> [f64; 3] {
+        // Intrinsic angles are the extrinsic ones of the reversed sequence, reversed
+        let [i, j, mut k] = match self.intrinsic {
+            true => [self.axes[2], self.axes[1], self.axes[0]],
+            false => self.axes,
+        };
+        let proper = i == k;
+        if proper {
+            k = 3 - i - j;
+        }
+        // Parity of the permutation (i, j, k)
+        let sign = ((i as f64 - j as f64) * (j as f64 - k as f64) * (k as f64 - i as f64)) / 2.0;
+        let w = rotation.w;
+        let axis = |n: usize| rotation.imag()[n];
+        let (a, b, c, d) = if proper {
+            (w, axis(i), axis(j), axis(k) * sign)
+        } else {
+            (
+                w - axis(j),
+                axis(i) + axis(k) * sign,
+                axis(j) + w,
+                axis(k) * sign - axis(i),
+            )
+        };
+        let middle = 2.0 * c.hypot(d).atan2(a.hypot(b));
+        let half_sum = b.atan2(a);
+        let half_difference = d.atan2(c);
+        let (first, mut last) = if middle <= GIMBAL_LOCK {
+            match self.intrinsic {
+                true => (0.0, 2.0 * half_sum),
+                false => (2.0 * half_sum, 0.0),
+            }
+        } else if middle >= PI - GIMBAL_LOCK {
+            match self.intrinsic {
+                true => (0.0, 2.0 * half_difference),
+                false => (-2.0 * half_difference, 0.0),
+            }
+        } else {
+            (half_sum - half_difference, half_sum + half_difference)
+        };
+        let middle = if proper {
+            middle
+        } else {
+            last *= sign;
+            middle - FRAC_PI_2
+        };
+        let angles = [wrap(first), middle, wrap(last)];
+        match self.intrinsic {
+            true => [angles[2], angles[1], angles[0]],
+            false => angles,
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for EulerSequence {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        EulerSequence::parse(&obj.extract::<String>()?)
+    }
+}
+
+fn unit(axis: usize) -> Vector3<f64> {
+    Vector3::ith(axis, 1.0)
+}
+
+/// Angle in [-pi, pi)
+fn wrap(angle: f64) -> f64 {
+    (angle + PI).rem_euclid(2.0 * PI) - PI
+}
+
+/// Unit quaternion of (w, x, y, z), None if it is not finite or zero
+fn quaternion(q: &[f64; 4]) -> Option<UnitQuaternion<f64>> {
+    let quaternion = Quaternion::new(q[0], q[1], q[2], q[3]);
+    let norm = quaternion.norm();
+    (norm.is_finite() && norm > 1e-12).then(|| UnitQuaternion::new_normalize(quaternion))
+}
+
+fn wxyz(rotation: &UnitQuaternion<f64>) -> [f64; 4] {
+    [rotation.w, rotation.i, rotation.j, rotation.k]
+}
+
+/// Converts quaternions (w, x, y, z), given as sequences, an (n, 4) array or a table with
+/// the columns qw, qx, qy, qz, to angles in the Euler `sequence`: lowercase for extrinsic
+/// ("xyz", the default) and uppercase for intrinsic ("ZYX") rotations, or "kuka" (A, B, C)
+/// and "fanuc" (W, P, R). Angles are in degrees unless `radians`. Rows that are not finite
+/// or zero give None.
+#[pyfunction]
+#[pyo3(signature = (quaternions, sequence=EulerSequence::XYZ, radians=false))]
+pub fn quaternions_to_euler(
+    py: Python<'_>,
+    quaternions: QuaternionTable,
+    sequence: EulerSequence,
+    radians: bool,
+) -> Vec<Option<[f64; 3]>> {
+    py.detach(|| {
+        ThreadPool::default().map(&quaternions.0, |q| {
+            let angles = sequence.angles(&quaternion(q)?);
+            Some(match radians {
+                true => angles,
+                false => angles.map(f64::to_degrees),
+            })
+        })
+    })
+}
+
+/// Converts Euler angles, triples in the `sequence` of quaternions_to_euler and in degrees
+/// unless `radians`, to quaternions (w, x, y, z). Rows that are not finite give None.
+#[pyfunction]
+#[pyo3(signature = (angles, sequence=EulerSequence::XYZ, radians=false))]
+pub fn euler_to_quaternions(
+    py: Python<'_>,
+    angles: Vec<[f64; 3]>,
+    sequence: EulerSequence,
+    radians: bool,
+) -> Vec<Option<[f64; 4]>> {
+    py.detach(|| {
+        ThreadPool::default().map(&angles, |angles| {
+            if !angles.iter().all(|angle| angle.is_finite()) {
+                return None;
+            }
+            let angles = match radians {
+                true => *angles,
+                false => angles.map(f64::to_radians),
+            };
+            Some(wxyz(&sequence.to_rotation(angles)))
+        })
+    })
+}
+
+/// Converts quaternions, given as for quaternions_to_euler, to 3x3 rotation matrices as
+/// lists of rows. Rows that are not finite or zero give None.
+#[pyfunction]
+pub fn quaternions_to_matrices(
+    py: Python<'_>,
+    quaternions: QuaternionTable,
+) -> Vec<Option<[[f64; 3]; 3]>> {
+    py.detach(|| {
+        ThreadPool::default().map(&quaternions.0, |q| {
+            let matrix = quaternion(q)?.to_rotation_matrix();
+            Some(std::array::from_fn(|row| {
+                std::array::from_fn(|column| matrix[(row, column)])
+            }))
+        })
+    })
+}
+
+/// Converts 3x3 rotation matrices, as lists of rows or an (n, 3, 3) array, to quaternions
+/// (w, x, y, z). Matrices that are not quite orthonormal, e.g. from rounded values, give
+/// the nearest rotation; those that are not finite give None.
+#[pyfunction]
+pub fn matrices_to_quaternions(
+    py: Python<'_>,
+    matrices: Vec<[[f64; 3]; 3]>,
+) -> Vec<Option<[f64; 4]>> {
+    py.detach(|| {
+        ThreadPool::default().map(&matrices, |rows| {
+            if !rows.iter().flatten().all(|x| x.is_finite()) {
+                return None;
+            }
+            let matrix = Matrix3::from_fn(|row, column| rows[row][column]);
+            let rotation = Rotation3::from_matrix(&matrix);
+            Some(wxyz(&UnitQuaternion::from_rotation_matrix(&rotation)))
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_euler_round_trip() {
+        let names = [
+            "xyz", "xzy", "yxz", "yzx", "zxy", "zyx", "xyx", "xzx", "yxy", "yzy", "zxz", "zyz",
+        ];
+        for name in names
+            .iter()
+            .flat_map(|name| [name.to_string(), name.to_uppercase()])
+        {
+            let sequence = EulerSequence::parse(&name).unwrap();
+            let proper = name.as_bytes()[0] == name.as_bytes()[2];
+            let middle = if proper { 1.1 } else { 0.4 };
+            for (angles, locked) in [
+                ([0.3, middle, -2.0], false),
+                ([0.3, if proper { 0.0 } else { FRAC_PI_2 }, -2.0], true),
+                ([0.3, if proper { PI } else { -FRAC_PI_2 }, -2.0], true),
+            ] {
+                let rotation = sequence.to_rotation(angles);
+                let found = sequence.angles(&rotation);
+                assert!(
+                    sequence.to_rotation(found).angle_to(&rotation) < 1e-6,
+                    "{} {:?} {:?}",
+                    name,
+                    angles,
+                    found
+                );
+                if locked {
+                    assert_eq!(found[2], 0.0, "{}", name);
+                } else {
+                    for (a, b) in angles.iter().zip(found) {
+                        assert!((a - b).abs() < 1e-9, "{} {:?} {:?}", name, angles, found);
+                    }
+                }
+            }
+        }
+        // KUKA A, B, C and Fanuc W, P, R as the KRL and LS exports write them
+        let rotation = UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3);
+        let kuka = EulerSequence::parse("kuka").unwrap().angles(&rotation);
+        let fanuc = EulerSequence::parse("fanuc").unwrap().angles(&rotation);
+        for (a, b) in kuka.iter().zip([0.3, 0.2, 0.1]) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        for (a, b) in fanuc.iter().zip([0.1, 0.2, 0.3]) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert!(EulerSequence::parse("xYz").is_err());
+        assert!(EulerSequence::parse("xxy").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+use crate::configuration::{self, Vendor};
+use crate::kinematic_model::KinematicModel;
+use crate::pose::PyPose;
+use crate::validation;
+
+/// Rows returned to Python, each a flat dictionary
+pub type Rows<'py> = Vec<Bound<'py, PyDict>>;
+
+/// Column names of the pose, in the order of PyPose
+pub const POSE_COLUMNS: [&str; 7] = ["x", "y", "z", "qw", "qx", "qy", "qz"];
+/// Column names of the joints
+pub const JOINT_COLUMNS: [&str; 6] = ["j1", "j2", "j3", "j4", "j5", "j6"];
+
+/// Outcome of solving one pose, as a row of `solve_and_join`
+pub struct SolvedRow {
+    pub pose: PyPose,
+    /// Best solution in the units of the call, None if the pose is unreachable
+    pub joints: Option<[f64; 6]>,
+    pub configuration: Option<u8>,
+    /// Position (m) and rotation (degrees) error of the solution's forward kinematics
+    pub residual: Option<(f64, f64)>,
+}
+
+impl SolvedRow {
+    pub fn status(&self) -> &'static str {
+        if !validation::is_finite_pose(&self.pose) {
+            "invalid"
+        } else if self.joints.is_some() {
+            "ok"
+        } else {
+            "unreachable"
+        }
+    }
+
+    /// A flat dictionary, so that a list of rows converts directly into a DataFrame
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        let (translation, rotation) = self.pose;
+        for (column, value) in POSE_COLUMNS.iter().zip(translation.iter().chain(&rotation)) {
+            dict.set_item(column, value)?;
+        }
+        for (i, column) in JOINT_COLUMNS.iter().enumerate() {
+            dict.set_item(column, self.joints.map(|joints| joints[i]))?;
+        }
+        dict.set_item("status", self.status())?;
+        dict.set_item("configuration", self.configuration)?;
+        dict.set_item("position_residual", self.residual.map(|r| r.0))?;
+        dict.set_item("rotation_residual", self.residual.map(|r| r.1))?;
+        Ok(dict)
+    }
+}
+
+/// A row of `batch_axis_configuration`: the joints as given (`joints`) followed by the
+/// configuration flags computed from the same joints in radians, None if they are not finite
+pub fn configuration_row<'py>(
+    py: Python<'py>,
+    model: &KinematicModel,
+    joints: &[f64; 6],
+    radians: &Joints,
+    vendor: Option<Vendor>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (column, value) in JOINT_COLUMNS.iter().zip(joints.iter()) {
+        dict.set_item(column, value)?;
+    }
+    if !validation::is_finite_joints(radians) {
+        let columns: &[&str] = match vendor {
+            Some(Vendor::Kuka) => &["CF1", "CF4", "CF6", "CFX", "S", "T"],
+            Some(Vendor::Fanuc) => &["CF1", "CF4", "CF6", "CFX", "FANUC"],
+            None => &["CF1", "CF4", "CF6", "CFX"],
+        };
+        for column in columns {
+            dict.set_item(column, py.None())?;
+        }
+        return Ok(dict);
+    }
+    dict.set_item("CF1", configuration::quadrant(radians[0]))?;
+    dict.set_item("CF4", configuration::quadrant(radians[3]))?;
+    dict.set_item("CF6", configuration::quadrant(radians[5]))?;
+    dict.set_item("CFX", configuration::configuration(model, radians))?;
+    match vendor {
+        Some(Vendor::Kuka) => {
+            dict.set_item("S", configuration::kuka_status(model, radians))?;
+            dict.set_item("T", configuration::kuka_turn(radians))?;
+        }
+        Some(Vendor::Fanuc) => {
+            dict.set_item("FANUC", configuration::fanuc_config(model, radians))?;
+        }
+        None => {}
+    }
+    Ok(dict)
+}
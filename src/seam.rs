@@ -0,0 +1,157 @@
+use nalgebra::{Isometry3, Rotation3, Translation3, UnitQuaternion, Vector3};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::axis::Axis;
+use crate::pose::from_isometry;
+use crate::rows::POSE_COLUMNS;
+use crate::validation;
+
+/// Tangent of an ordered polyline at every point: along the chord between its neighbours,
+/// one-sided at the ends. None if consecutive points coincide.
+fn tangents(points: &[Vector3<f64>]) -> Option<Vec<Vector3<f64>>> {
+    let last = points.len() - 1;
+    (0..=last)
+        .map(|i| (points[(i + 1).min(last)] - points[i.saturating_sub(1)]).try_normalize(1e-12))
+        .collect()
+}
+
+/// Tool poses along `points` (m): x along the travel direction, z pointing into the surface
+/// against its normal at the point, then turned by `work_angle` (radians) about the travel
+/// direction and tilted by `travel_angle` forward along it. Err names the first point whose
+/// frame is undetermined.
+fn seam_frames(
+    points: &[Vector3<f64>],
+    normals: &[Vector3<f64>],
+    work_angle: f64,
+    travel_angle: f64,
+) -> Result<Vec<Isometry3<f64>>, String> {
+    let tangents = tangents(points).ok_or("Consecutive seam points coincide")?;
+    let tilt = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), work_angle)
+        * UnitQuaternion::from_axis_angle(&Vector3::y_axis(), travel_angle);
+    points
+        .iter()
+        .zip(&tangents)
+        .zip(normals.iter().cycle())
+        .enumerate()
+        .map(|(i, ((point, tangent), normal))| {
+            let z = (-normal + tangent * normal.dot(tangent))
+                .try_normalize(1e-9)
+                .ok_or_else(|| format!("The seam runs along the normal at point {}", i))?;
+            let rotation = Rotation3::from_basis_unchecked(&[*tangent, z.cross(tangent), z]);
+            Ok(Isometry3::from_parts(
+                Translation3::from(*point),
+                UnitQuaternion::from_rotation_matrix(&rotation) * tilt,
+            ))
+        })
+        .collect()
+}
+
+/// Tool poses following an ordered polyline of points (m), e.g. a scanned weld seam, as
+/// rows for batch_inverse or solve_and_join. The tool axis of every pose (`tool_axis`, the
+/// convention of set_tool_axis by default) points into the surface, against `normal` (the
+/// world z axis by default) or the per-point `normals`, and the next axis (x for a tool
+/// along z) follows the seam. `work_angle` (degrees) turns the tool about the seam and a
+/// positive `travel_angle` (degrees) tilts it forward along the seam, pushing, a negative
+/// one back, dragging. Rows have the pose columns with the index of the point and the
+/// distance (m) along the seam.
+#[pyfunction]
+#[pyo3(signature = (
+    points,
+    normal=[0.0, 0.0, 1.0],
+    normals=None,
+    work_angle=0.0,
+    travel_angle=0.0,
+    tool_axis=None,
+))]
+pub fn seam_poses<'py>(
+    py: Python<'py>,
+    points: Vec<[f64; 3]>,
+    normal: [f64; 3],
+    normals: Option<Vec<[f64; 3]>>,
+    work_angle: f64,
+    travel_angle: f64,
+    tool_axis: Option<Axis>,
+) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    if points.len() < 2 {
+        return Err(PyValueError::new_err(format!(
+            "A seam needs at least 2 points, got {}",
+            points.len()
+        )));
+    }
+    let normals = match normals {
+        Some(normals) if normals.len() != points.len() => {
+            return Err(PyValueError::new_err(format!(
+                "Got {} normals for {} points",
+                normals.len(),
+                points.len()
+            )));
+        }
+        Some(normals) => normals,
+        None => vec![normal],
+    };
+    for (name, values) in [("points", &points), ("normals", &normals)] {
+        let finite = values.iter().flatten().all(|x| x.is_finite());
+        validation::check_finite(name, values, finite)?;
+    }
+    validation::check_finite(
+        "work_angle, travel_angle",
+        &[work_angle, travel_angle],
+        work_angle.is_finite() && travel_angle.is_finite(),
+    )?;
+    let points: Vec<Vector3<f64>> = points.into_iter().map(Vector3::from).collect();
+    let normals: Vec<Vector3<f64>> = normals.into_iter().map(Vector3::from).collect();
+    let frames = seam_frames(
+        &points,
+        &normals,
+        work_angle.to_radians(),
+        travel_angle.to_radians(),
+    )
+    .map_err(PyValueError::new_err)?;
+    let tool_rotation = Axis::tool(tool_axis).tool_rotation();
+    let mut distance = 0.0;
+    frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            if i > 0 {
+                distance += (points[i] - points[i - 1]).norm();
+            }
+            let (translation, rotation) = from_isometry(&(frame * tool_rotation));
+            let row = PyDict::new(py);
+            for (column, value) in POSE_COLUMNS.iter().zip(translation.iter().chain(&rotation)) {
+                row.set_item(column, value)?;
+            }
+            row.set_item("index", i)?;
+            row.set_item("distance", distance)?;
+            Ok(row)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seam_frames() {
+        let points: Vec<Vector3<f64>> = (0..4)
+            .map(|i| Vector3::new(0.1 * i as f64, 0.0, 0.0))
+            .collect();
+        let up = [Vector3::z()];
+        let frames = seam_frames(&points, &up, 0.0, 0.0).unwrap();
+        let axis = |frame: &Isometry3<f64>, axis: Vector3<f64>| frame.rotation * axis;
+        assert!((axis(&frames[1], Vector3::x()) - Vector3::x()).norm() < 1e-12);
+        assert!((axis(&frames[1], Vector3::z()) + Vector3::z()).norm() < 1e-12);
+
+        // Pushing 10 degrees tilts the tool z axis forward along the seam
+        let frames = seam_frames(&points, &up, 0.0, 10f64.to_radians()).unwrap();
+        let z = axis(&frames[0], Vector3::z());
+        assert!((z.x - 10f64.to_radians().sin()).abs() < 1e-12);
+
+        let vertical = [Vector3::zeros(), Vector3::z()];
+        assert!(seam_frames(&vertical, &up, 0.0, 0.0).is_err());
+        assert!(seam_frames(&[Vector3::zeros(); 2], &up, 0.0, 0.0).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::f64::consts::PI;
+
+use crate::ordering;
+
+/// Values of each of J1, J4 and J6 in the automatic spread of seeds
+const SPREAD_LEVELS: usize = 3;
+/// Solutions of different seeds closer than this (radians) on every joint are the same
+const DISTINCT: f64 = 1e-9;
+
+/// Seeds of a multi-seed inverse: joint sets in the units of the robot, or "auto" for a
+/// spread over the joint ranges
+#[derive(Clone, Debug, PartialEq)]
+pub enum Seeds {
+    Given(Vec<[f64; 6]>),
+    Auto,
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for Seeds {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if let Ok(name) = obj.extract::<String>() {
+            return match name.as_str() {
+                "auto" => Ok(Seeds::Auto),
+                _ => Err(PyValueError::new_err(format!(
+                    "Unknown seeds '{}', expected 'auto' or a list of joint sets",
+                    name
+                ))),
+            };
+        }
+        Ok(Seeds::Given(obj.extract()?))
+    }
+}
+
+/// Seeds spread over the ranges (radians) of the joints that turn freely, J1, J4 and J6,
+/// at SPREAD_LEVELS evenly spaced values inside each range. The other joints sit at the
+/// middle of their range.
+pub fn spread(limits: &[(f64, f64); 6]) -> Vec<Joints> {
+    let level = |joint: usize, k: usize| {
+        let (min, max) = limits[joint];
+        min + (max - min) * (k + 1) as f64 / (SPREAD_LEVELS + 1) as f64
+    };
+    let middle: Joints = std::array::from_fn(|joint| level(joint, (SPREAD_LEVELS - 1) / 2));
+    let mut seeds = Vec::with_capacity(SPREAD_LEVELS.pow(3));
+    for j1 in 0..SPREAD_LEVELS {
+        for j4 in 0..SPREAD_LEVELS {
+            for j6 in 0..SPREAD_LEVELS {
+                let mut seed = middle;
+                seed[0] = level(0, j1);
+                seed[3] = level(3, j4);
+                seed[5] = level(5, j6);
+                seeds.push(seed);
+            }
+        }
+    }
+    seeds
+}
+
+/// Ranges used by the spread when the robot has no joint limits
+pub const FULL_TURNS: [(f64, f64); 6] = [(-PI, PI); 6];
+
+/// The union of the solutions of several seeds without repeats. Solutions that differ by
+/// whole turns of a joint are distinct postures of the robot and are kept.
+pub fn merge(solutions: impl IntoIterator<Item = Joints>) -> Vec<Joints> {
+    let mut merged: Vec<Joints> = solutions.into_iter().collect();
+    ordering::dedup(&mut merged, DISTINCT, None);
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spread_stays_inside_limits() {
+        let limits = [
+            (-3.0, 3.0),
+            (-1.0, 2.0),
+            (-2.0, 1.0),
+            (-6.0, 6.0),
+            (-2.0, 2.0),
+            (-6.0, 6.0),
+        ];
+        let seeds = spread(&limits);
+        assert_eq!(seeds.len(), 27);
+        assert!(seeds.iter().all(|seed| seed
+            .iter()
+            .zip(&limits)
+            .all(|(joint, (min, max))| min < joint && joint < max)));
+        assert!(seeds.iter().any(|seed| seed[3] == -3.0 && seed[5] == 3.0));
+        assert!(seeds.iter().all(|seed| seed[1] == 0.5));
+
+        let merged = merge([[0.0; 6], [1e-12; 6], [0.0, 0.0, 0.0, 2.0 * PI, 0.0, 0.0]]);
+        assert_eq!(merged.len(), 2);
+    }
+}
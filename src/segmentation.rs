@@ -0,0 +1,85 @@
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+/// A run of rows of a trajectory that can be written as one sequence of linear moves
+pub struct Segment<T> {
+    pub start: usize,
+    /// Last row, inclusive
+    pub end: usize,
+    pub configuration: T,
+    /// The configuration differs from the previous segment
+    pub configuration_change: bool,
+    /// Joints (0-based) that jump by the flip threshold or more from the previous row
+    pub flipped: Vec<usize>,
+    /// Summed absolute change of every joint within the segment
+    pub travel: Joints,
+    /// Largest change of any joint between two rows of the segment
+    pub max_step: f64,
+}
+
+/// Splits a trajectory wherever the configuration changes or a joint jumps by
+/// `flip_threshold` or more between rows, as a ±360° flip of a wrapped joint does
+pub fn split<T: PartialEq + Copy>(
+    joints: &[Joints],
+    configurations: &[T],
+    flip_threshold: f64,
+) -> Vec<Segment<T>> {
+    let mut segments: Vec<Segment<T>> = Vec::new();
+    for (row, (joint_set, configuration)) in joints.iter().zip(configurations).enumerate() {
+        let steps: Joints = match row.checked_sub(1) {
+            Some(previous) => std::array::from_fn(|k| (joint_set[k] - joints[previous][k]).abs()),
+            None => [0.0; 6],
+        };
+        let flipped: Vec<usize> = (0..6).filter(|&k| steps[k] >= flip_threshold).collect();
+        match segments.last_mut() {
+            Some(segment) if segment.configuration == *configuration && flipped.is_empty() => {
+                segment.end = row;
+                for (travel, step) in segment.travel.iter_mut().zip(steps) {
+                    *travel += step;
+                }
+                segment.max_step = steps.iter().copied().fold(segment.max_step, f64::max);
+            }
+            last => {
+                let configuration_change =
+                    last.is_some_and(|segment| segment.configuration != *configuration);
+                segments.push(Segment {
+                    start: row,
+                    end: row,
+                    configuration: *configuration,
+                    configuration_change,
+                    flipped,
+                    travel: [0.0; 6],
+                    max_step: 0.0,
+                })
+            }
+        }
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_at_configuration_changes_and_flips() {
+        let joints = [
+            [0.0, 0.0, 0.0, 0.0, 0.5, 3.0],
+            [0.1, 0.0, 0.0, 0.0, 0.5, 3.1],
+            // J6 wraps from +178° to -178°
+            [0.2, 0.0, 0.0, 0.0, 0.5, -3.1],
+            [0.3, 0.0, 0.0, 0.0, -0.5, -3.0],
+            [0.4, 0.0, 0.0, 0.0, -0.5, -2.9],
+        ];
+        let configurations = [0, 0, 0, 1, 1];
+        let segments = split(&joints, &configurations, std::f64::consts::PI);
+        let bounds: Vec<(usize, usize)> = segments.iter().map(|s| (s.start, s.end)).collect();
+        assert_eq!(bounds, [(0, 1), (2, 2), (3, 4)]);
+        assert!(segments[0].flipped.is_empty() && !segments[0].configuration_change);
+        assert_eq!(segments[1].flipped, [5]);
+        assert!(!segments[1].configuration_change);
+        assert!(segments[2].configuration_change && segments[2].flipped.is_empty());
+        assert!((segments[0].travel[0] - 0.1).abs() < 1e-12);
+        assert!((segments[2].max_step - 0.1).abs() < 1e-12);
+        assert!(split::<u8>(&[], &[], 1.0).is_empty());
+    }
+}
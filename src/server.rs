@@ -0,0 +1,367 @@
+use pyo3::exceptions::{PyOSError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::ordering::InverseOptions;
+use crate::validation;
+use crate::Robot;
+
+/// How often idle connections and the accept loop check for a stop request
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long an answer may wait for a client that does not read, before its connection is
+/// closed
+pub const WRITE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Longest request line in bytes, longer lines are refused and close the connection
+pub const MAX_LINE: usize = 4096;
+
+fn numbers<const N: usize>(fields: &[&str]) -> Result<[f64; N], String> {
+    if fields.len() != N {
+        return Err(format!("expected {} numbers, got {}", N, fields.len()));
+    }
+    let mut values = [0.0; N];
+    for (value, field) in values.iter_mut().zip(fields) {
+        *value = field
+            .parse()
+            .map_err(|_| format!("'{}' is not a number", field))?;
+    }
+    Ok(values)
+}
+
+fn joined(values: &[f64]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Solves `x y z qw qx qy qz [j1 .. j6]`, answering the best or all solutions
+fn inverse(robot: &Robot, arguments: &[&str], all: bool) -> Result<String, String> {
+    let (pose, seed) = match arguments.len() {
+        7 => (numbers::<7>(arguments)?, None),
+        13 => (
+            numbers::<7>(&arguments[..7])?,
+            Some(numbers::<6>(&arguments[7..])?),
+        ),
+        n => return Err(format!("expected 7 or 13 numbers, got {}", n)),
+    };
+    let pose = (
+        [pose[0], pose[1], pose[2]],
+        [pose[3], pose[4], pose[5], pose[6]],
+    );
+    if !validation::is_finite_pose(&pose) || !seed.as_ref().is_none_or(validation::is_finite_joints)
+    {
+        return Err("NaN or infinite values".to_string());
+    }
+    let solutions = robot.solve(pose, seed, &InverseOptions::default());
+    if all {
+        let all: Vec<String> = solutions.iter().map(|joints| joined(joints)).collect();
+        Ok(format!(" {} {}", solutions.len(), all.join(";"))
+            .trim_end()
+            .to_string())
+    } else {
+        let best = solutions.first().ok_or("unreachable")?;
+        Ok(format!(" {}", joined(best)))
+    }
+}
+
+/// Answers one request line of the text protocol:
+///
+/// * `PING` -> `OK`
+/// * `FK j1 .. j6` -> `OK x y z qw qx qy qz`
+/// * `IK x y z qw qx qy qz [j1 .. j6]` -> `OK j1 .. j6`, the best solution near the
+///   optional seed
+/// * `IKALL x y z qw qx qy qz [j1 .. j6]` -> `OK n` followed by every solution as
+///   `j1 .. j6`, separated by `;`
+///
+/// Joints are in the units of the robot. Failures answer `ERR <message>`.
+pub fn respond(robot: &Robot, line: &str) -> String {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let Some((command, arguments)) = fields.split_first() else {
+        return "ERR empty request".to_string();
+    };
+    let command = command.to_ascii_uppercase();
+    let result = match command.as_str() {
+        "PING" => Ok(String::new()),
+        "FK" => numbers::<6>(arguments).and_then(|joints| {
+            let (translation, rotation) = robot
+                .forward(joints, None)
+                .map_err(|_| "NaN or infinite joints".to_string())?;
+            Ok(format!(" {} {}", joined(&translation), joined(&rotation)))
+        }),
+        "IK" => inverse(robot, arguments, false),
+        "IKALL" => inverse(robot, arguments, true),
+        _ => Err(format!("unknown command '{}'", command)),
+    };
+    match result {
+        Ok(answer) => format!("OK{}", answer),
+        Err(message) => format!("ERR {}", message),
+    }
+}
+
+fn serve_connection(robot: &Robot, stream: TcpStream, stop: &AtomicBool) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(POLL_INTERVAL))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    while !stop.load(Ordering::Relaxed) {
+        let limit = (MAX_LINE + 1 - line.len()) as u64;
+        match reader.by_ref().take(limit).read_line(&mut line) {
+            Ok(0) => return Ok(()),
+            Ok(_) if line.len() > MAX_LINE && !line.ends_with('\n') => {
+                writeln!(writer, "ERR request longer than {} bytes", MAX_LINE)?;
+                return Ok(());
+            }
+            Ok(_) => {
+                writeln!(writer, "{}", respond(robot, line.trim()))?;
+                line.clear();
+            }
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Counts a served connection until it closes
+struct Connection(Arc<AtomicUsize>);
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Serves forward and inverse kinematics of a Robot over TCP with a line based text
+/// protocol, so clients in other languages (C#, LabVIEW, PLC gateways) use the exact same
+/// kinematics. Every connection is served on its own thread, without holding the GIL, up to
+/// `max_connections` at a time.
+///
+/// Requests and answers are single lines of space separated values:
+/// `FK j1 .. j6` answers `OK x y z qw qx qy qz`, `IK x y z qw qx qy qz [seed j1 .. j6]`
+/// answers `OK j1 .. j6` and `IKALL` the same arguments with `OK n` followed by all
+/// solutions separated by `;`. `PING` answers `OK`, failures `ERR <message>`.
+#[pyclass(frozen)]
+pub struct KinematicsServer {
+    address: SocketAddr,
+    stop: Arc<AtomicBool>,
+    accept_thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[pymethods]
+impl KinematicsServer {
+    /// Starts serving `robot` on `host`:`port`, port 0 picks a free port. Connections beyond
+    /// `max_connections` are answered `ERR too many connections` and closed.
+    #[new]
+    #[pyo3(signature = (robot, host="127.0.0.1", port=0, max_connections=64))]
+    fn new(robot: Py<Robot>, host: &str, port: u16, max_connections: usize) -> PyResult<Self> {
+        if max_connections == 0 {
+            return Err(PyValueError::new_err("max_connections must be positive"));
+        }
+        let robot = Arc::new(robot);
+        let listener = TcpListener::bind((host, port)).map_err(|e| {
+            PyOSError::new_err(format!("Cannot listen on {}:{}: {}", host, port, e))
+        })?;
+        let address = listener.local_addr()?;
+        listener.set_nonblocking(true)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let accept_stop = stop.clone();
+        let open = Arc::new(AtomicUsize::new(0));
+        let accept_thread = std::thread::spawn(move || {
+            while !accept_stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((mut stream, peer)) => {
+                        if open.load(Ordering::Relaxed) >= max_connections {
+                            log::warn!("Refused a connection from {}: too many connections", peer);
+                            let _ = writeln!(stream, "ERR too many connections");
+                            continue;
+                        }
+                        open.fetch_add(1, Ordering::Relaxed);
+                        let connection = Connection(open.clone());
+                        let robot = robot.clone();
+                        let stop = accept_stop.clone();
+                        std::thread::spawn(move || {
+                            let _connection = connection;
+                            let served = stream
+                                .set_nonblocking(false)
+                                .and_then(|_| serve_connection(robot.get(), stream, &stop));
+                            if let Err(e) = served {
+                                log::warn!("Connection from {} failed: {}", peer, e);
+                            }
+                        });
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                        std::thread::sleep(POLL_INTERVAL)
+                    }
+                    Err(e) => log::warn!("Accepting a connection failed: {}", e),
+                }
+            }
+        });
+        log::info!("Serving kinematics on {}", address);
+        Ok(KinematicsServer {
+            address,
+            stop,
+            accept_thread: Mutex::new(Some(accept_thread)),
+        })
+    }
+
+    /// (host, port) the server listens on
+    #[getter]
+    fn address(&self) -> (String, u16) {
+        (self.address.ip().to_string(), self.address.port())
+    }
+
+    #[getter]
+    fn running(&self) -> bool {
+        !self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Stops accepting connections and closes the open ones within a tenth of a second, or
+    /// the write timeout of a second for a client that does not read its answers
+    fn stop(&self, py: Python<'_>) -> PyResult<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        let handle = self
+            .accept_thread
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(handle) = handle {
+            py.detach(|| handle.join())
+                .map_err(|_| PyRuntimeError::new_err("The server thread panicked"))?;
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        self.stop(py)?;
+        Ok(false)
+    }
+}
+
+impl Drop for KinematicsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::abb_1660;
+
+    /// Sends `request` and reads the answer line
+    fn ask(reader: &mut BufReader<TcpStream>, request: &str) -> String {
+        write!(reader.get_mut(), "{}", request).unwrap();
+        let mut answer = String::new();
+        reader.read_line(&mut answer).unwrap();
+        answer.trim_end().to_string()
+    }
+
+    #[test]
+    fn test_respond() {
+        let robot = abb_1660();
+        assert_eq!(respond(&robot, "ping"), "OK");
+        let pose = respond(&robot, "FK 10 20 30 40 50 60");
+        let values: Vec<f64> = pose[3..].split(' ').map(|x| x.parse().unwrap()).collect();
+        assert_eq!(values.len(), 7);
+
+        let seeded = respond(&robot, &format!("IK {} 10 20 30 40 50 60", &pose[3..]));
+        let joints: Vec<f64> = seeded[3..].split(' ').map(|x| x.parse().unwrap()).collect();
+        for (joint, expected) in joints.iter().zip([10.0, 20.0, 30.0, 40.0, 50.0, 60.0]) {
+            assert!((joint - expected).abs() < 1e-6);
+        }
+        assert!(respond(&robot, &format!("IKALL {}", &pose[3..])).starts_with("OK 8 "));
+        assert_eq!(respond(&robot, "IK 9 9 9 1 0 0 0"), "ERR unreachable");
+        assert!(respond(&robot, "FK 1 2 x 4 5 6").starts_with("ERR"));
+        assert!(respond(&robot, "MOVE").starts_with("ERR unknown command"));
+    }
+
+    #[test]
+    fn test_long_lines_close_the_connection() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let server = std::thread::spawn(move || {
+            serve_connection(&abb_1660(), stream, &AtomicBool::new(false)).unwrap()
+        });
+        let mut reader = BufReader::new(client);
+        let padded = format!("PING{}\n", " ".repeat(MAX_LINE - 5));
+        assert_eq!(ask(&mut reader, &padded), "OK");
+        let long = "1".repeat(MAX_LINE + 1);
+        assert_eq!(
+            ask(&mut reader, &long),
+            format!("ERR request longer than {} bytes", MAX_LINE)
+        );
+        server.join().unwrap();
+        let mut rest = String::new();
+        assert_eq!(reader.read_line(&mut rest).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_connections_are_capped() {
+        Python::initialize();
+        let server = Python::attach(|py| {
+            let robot = Py::new(py, abb_1660()).unwrap();
+            KinematicsServer::new(robot, "127.0.0.1", 0, 1).unwrap()
+        });
+        let connect = || BufReader::new(TcpStream::connect(server.address).unwrap());
+        let mut first = connect();
+        assert_eq!(ask(&mut first, "PING\n"), "OK");
+        assert_eq!(ask(&mut connect(), ""), "ERR too many connections");
+        drop(first);
+        // The slot is free once the first connection is closed
+        let mut answer = String::new();
+        for _ in 0..50 {
+            answer = ask(&mut connect(), "PING\n");
+            if answer == "OK" {
+                break;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        assert_eq!(answer, "OK");
+        Python::attach(|py| server.stop(py)).unwrap();
+        assert!(Python::attach(|py| KinematicsServer::new(
+            Py::new(py, abb_1660()).unwrap(),
+            "127.0.0.1",
+            0,
+            0
+        ))
+        .is_err());
+    }
+
+    #[test]
+    fn test_clients_that_do_not_read_are_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        let server = std::thread::spawn(move || {
+            serve_connection(&abb_1660(), stream, &AtomicBool::new(false))
+        });
+        // Requests with long answers until the server closes the connection
+        let request = format!("{}\n", "X".repeat(MAX_LINE - 1));
+        while client.write_all(request.as_bytes()).is_ok() {}
+        let error = server.join().unwrap().unwrap_err();
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::WouldBlock | ErrorKind::TimedOut
+        ));
+    }
+}
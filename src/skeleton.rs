@@ -0,0 +1,113 @@
+use nalgebra::{Isometry3, Point3, Rotation3, Vector3};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rs_opw_kinematics::kinematic_traits::Joints;
+
+use crate::kinematic_model::KinematicModel;
+
+/// Names of the skeleton points, from the base to the TCP
+pub const POINT_NAMES: [&str; 7] = [
+    "base", "shoulder", "elbow", "forearm", "wrist", "flange", "tcp",
+];
+
+/// Shoulder (J2 axis), elbow (J3 axis), forearm (end of the a2 offset) and wrist center of
+/// the kinematic `joints` (decoupled, in radians), in the robot base frame
+pub fn arm_points(model: &KinematicModel, joints: &Joints) -> [Point3<f64>; 4] {
+    let q: Vec<f64> = (0..3)
+        .map(|i| joints[i] * model.sign_corrections[i] as f64 - model.offsets[i])
+        .collect();
+    let q23 = q[1] + q[2];
+    let column = Rotation3::from_axis_angle(&Vector3::z_axis(), q[0]);
+    // Points in the plane of the arm, before turning about J1
+    let shoulder = Vector3::new(model.a1, 0.0, 0.0);
+    let elbow = shoulder + model.c2 * Vector3::new(q[1].sin(), 0.0, q[1].cos());
+    let forearm = elbow + Vector3::new(model.a2 * q23.cos(), model.b, -model.a2 * q23.sin());
+    let wrist = forearm + model.c3 * Vector3::new(q23.sin(), 0.0, q23.cos());
+    [shoulder, elbow, forearm, wrist]
+        .map(|point| Point3::from(column * point + Vector3::new(0.0, 0.0, model.c1)))
+}
+
+/// Points along the kinematic chain of a posture, in world coordinates
+pub struct Skeleton {
+    pub points: [Point3<f64>; 7],
+}
+
+impl Skeleton {
+    /// Skeleton of the kinematic `joints` (decoupled, in radians). The flange and TCP are
+    /// taken from `tcp`, the forward kinematics of the robot, so they match it exactly.
+    pub fn new(
+        model: &KinematicModel,
+        joints: &Joints,
+        base: &Isometry3<f64>,
+        tcp: &Isometry3<f64>,
+        tool: &Isometry3<f64>,
+    ) -> Self {
+        let [shoulder, elbow, forearm, wrist] = arm_points(model, joints).map(|p| base * p);
+        let flange = tcp * tool.inverse();
+        Skeleton {
+            points: [
+                base * Point3::origin(),
+                shoulder,
+                elbow,
+                forearm,
+                wrist,
+                flange * Point3::origin(),
+                tcp * Point3::origin(),
+            ],
+        }
+    }
+
+    /// Names, points and the line segments between consecutive points as [x, y, z] lists,
+    /// with a cylinder (start, end, radius) per segment when a link radius is given
+    pub fn to_dict<'py>(
+        &self,
+        py: Python<'py>,
+        link_radius: Option<f64>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let points: Vec<[f64; 3]> = self.points.iter().map(|p| [p.x, p.y, p.z]).collect();
+        let segments: Vec<[[f64; 3]; 2]> = points.windows(2).map(|w| [w[0], w[1]]).collect();
+        let dict = PyDict::new(py);
+        dict.set_item("names", POINT_NAMES)?;
+        dict.set_item("points", &points)?;
+        if let Some(radius) = link_radius {
+            let cylinders: Vec<([f64; 3], [f64; 3], f64)> = segments
+                .iter()
+                .map(|[start, end]| (*start, *end, radius))
+                .collect();
+            dict.set_item("cylinders", cylinders)?;
+        }
+        dict.set_item("segments", segments)?;
+        Ok(dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::ABB_1660;
+    use nalgebra::{Translation3, UnitQuaternion};
+    use rs_opw_kinematics::kinematic_traits::Kinematics;
+
+    #[test]
+    fn test_skeleton_follows_the_chain() {
+        let base = Isometry3::from_parts(
+            Translation3::new(1.0, 2.0, 0.5),
+            UnitQuaternion::from_euler_angles(0.0, 0.0, 0.3),
+        );
+        let tool =
+            Isometry3::from_parts(Translation3::new(0.0, 0.0, 0.1), UnitQuaternion::identity());
+        let joints = [0.2, -0.4, 0.6, 0.8, -1.0, 1.2];
+        let flange = ABB_1660.to_opw_kinematics().forward(&joints);
+        let tcp = base * flange * tool;
+        let skeleton = Skeleton::new(&ABB_1660, &joints, &base, &tcp, &tool);
+        let [origin, shoulder, elbow, _, wrist, flange_point, tcp_point] = skeleton.points;
+
+        assert!((origin - Point3::new(1.0, 2.0, 0.5)).norm() < 1e-12);
+        assert!(((shoulder - origin).norm() - ABB_1660.a1.hypot(ABB_1660.c1)).abs() < 1e-12);
+        assert!(((elbow - shoulder).norm() - ABB_1660.c2).abs() < 1e-12);
+        // The wrist center lies c4 behind the flange along its z axis
+        let approach = (base * flange).rotation * Vector3::z();
+        assert!((flange_point - ABB_1660.c4 * approach - wrist).norm() < 1e-9);
+        assert!(((tcp_point - flange_point).norm() - 0.1).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,226 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::f64::consts::PI;
+
+/// Halvings of the blend between a smoothed point and the original when searching the
+/// most smoothing within the tolerance
+const BLEND_STEPS: usize = 16;
+
+/// How a joint trajectory is smoothed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Smoothing {
+    /// Centered mean over a window of points
+    MovingAverage,
+    /// Discrete smoothing spline (Whittaker-Henderson), penalizing second differences
+    Spline,
+}
+
+impl Smoothing {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "moving_average" => Ok(Smoothing::MovingAverage),
+            "spline" => Ok(Smoothing::Spline),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown smoothing '{}', expected 'moving_average' or 'spline'",
+                name
+            ))),
+        }
+    }
+
+    /// Smoothed joints, the first and last point unchanged
+    pub fn apply(self, joints: &[Joints], window: usize) -> Vec<Joints> {
+        match self {
+            Smoothing::MovingAverage => moving_average(joints, window),
+            Smoothing::Spline => spline(joints, window),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for Smoothing {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        Smoothing::parse(&obj.extract::<String>()?)
+    }
+}
+
+/// Checks that a smoothing window is an odd number of at least 3 points
+pub fn check_window(window: usize) -> PyResult<()> {
+    if window >= 3 && window % 2 == 1 {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "window must be an odd number of at least 3 points, got {}",
+            window
+        )))
+    }
+}
+
+/// Centered mean over `window` points, narrowed symmetrically near the ends so that the
+/// first and last point stay
+fn moving_average(joints: &[Joints], window: usize) -> Vec<Joints> {
+    let last = joints.len().saturating_sub(1);
+    (0..joints.len())
+        .map(|i| {
+            let half = (window / 2).min(i).min(last - i);
+            let points = &joints[i - half..=i + half];
+            std::array::from_fn(|k| {
+                points.iter().map(|point| point[k]).sum::<f64>() / points.len() as f64
+            })
+        })
+        .collect()
+}
+
+/// Minimizes the squared distance from the points plus lambda times the squared second
+/// differences, with lambda chosen to halve the amplitude of a wave of `window` points.
+/// The ends are weighted so heavily that they stay.
+fn spline(joints: &[Joints], window: usize) -> Vec<Joints> {
+    let n = joints.len();
+    if n < 3 {
+        return joints.to_vec();
+    }
+    let lambda = (2.0 * (PI / window as f64).sin()).powi(-4);
+    // The symmetric band (diagonal and two off-diagonals) of W + lambda D'D
+    let mut band = vec![[0.0; 3]; n];
+    for row in 0..n - 2 {
+        let coefficients = [1.0, -2.0, 1.0];
+        for (j, a) in coefficients.iter().enumerate() {
+            for (k, b) in coefficients.iter().enumerate().skip(j) {
+                band[row + j][k - j] += lambda * a * b;
+            }
+        }
+    }
+    let mut weights = vec![1.0; n];
+    weights[0] = 1e9;
+    weights[n - 1] = 1e9;
+    for (entry, weight) in band.iter_mut().zip(&weights) {
+        entry[0] += weight;
+    }
+    // LDL' factors of the band, l[i] = (L[i + 1][i], L[i + 2][i])
+    let mut d = vec![0.0_f64; n];
+    let mut l = vec![[0.0_f64; 2]; n];
+    for i in 0..n {
+        let mut diagonal = band[i][0];
+        if i >= 1 {
+            diagonal -= l[i - 1][0].powi(2) * d[i - 1];
+        }
+        if i >= 2 {
+            diagonal -= l[i - 2][1].powi(2) * d[i - 2];
+        }
+        d[i] = diagonal;
+        let mut below = band[i][1];
+        if i >= 1 {
+            below -= l[i - 1][1] * l[i - 1][0] * d[i - 1];
+        }
+        l[i] = [below / d[i], band[i][2] / d[i]];
+    }
+    let mut smoothed: Vec<Joints> = joints
+        .iter()
+        .zip(&weights)
+        .map(|(point, weight)| point.map(|x| weight * x))
+        .collect();
+    for i in 0..n {
+        if i >= 1 {
+            let previous = smoothed[i - 1];
+            subtract(&mut smoothed[i], l[i - 1][0], &previous);
+        }
+        if i >= 2 {
+            let previous = smoothed[i - 2];
+            subtract(&mut smoothed[i], l[i - 2][1], &previous);
+        }
+    }
+    for (point, d) in smoothed.iter_mut().zip(&d) {
+        *point = point.map(|x| x / d);
+    }
+    for i in (0..n).rev() {
+        if i + 1 < n {
+            let next = smoothed[i + 1];
+            subtract(&mut smoothed[i], l[i][0], &next);
+        }
+        if i + 2 < n {
+            let next = smoothed[i + 2];
+            subtract(&mut smoothed[i], l[i][1], &next);
+        }
+    }
+    smoothed[0] = joints[0];
+    smoothed[n - 1] = joints[n - 1];
+    smoothed
+}
+
+fn subtract(point: &mut Joints, factor: f64, other: &Joints) {
+    for (x, y) in point.iter_mut().zip(other) {
+        *x -= factor * y;
+    }
+}
+
+/// Moves each smoothed point back toward its original as far as needed for `accept`, which
+/// is given the original and the candidate. Returns every point with the share of its
+/// smoothing kept, 0 where only the original is accepted.
+pub fn constrain(
+    joints: &[Joints],
+    smoothed: &[Joints],
+    accept: impl Fn(&Joints, &Joints) -> bool,
+) -> Vec<(Joints, f64)> {
+    let blend = |original: &Joints, target: &Joints, share: f64| -> Joints {
+        std::array::from_fn(|k| original[k] + share * (target[k] - original[k]))
+    };
+    joints
+        .iter()
+        .zip(smoothed)
+        .map(|(original, target)| {
+            if accept(original, target) {
+                return (*target, 1.0);
+            }
+            let (mut low, mut high) = (0.0, 1.0);
+            for _ in 0..BLEND_STEPS {
+                let share = (low + high) / 2.0;
+                match accept(original, &blend(original, target, share)) {
+                    true => low = share,
+                    false => high = share,
+                }
+            }
+            (blend(original, target, low), low)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smoothing_removes_jitter() {
+        // A ramp with alternating jitter on J1
+        let joints: Vec<Joints> = (0..40)
+            .map(|i| {
+                let jitter = if i % 2 == 0 { 0.01 } else { -0.01 };
+                let mut point = [0.02 * i as f64; 6];
+                point[0] += jitter;
+                point
+            })
+            .collect();
+        let roughness = |points: &[Joints]| {
+            points
+                .windows(3)
+                .map(|w| (w[0][0] - 2.0 * w[1][0] + w[2][0]).abs())
+                .fold(0.0, f64::max)
+        };
+        for method in [Smoothing::MovingAverage, Smoothing::Spline] {
+            let smoothed = method.apply(&joints, 5);
+            assert_eq!(smoothed[0], joints[0]);
+            assert_eq!(smoothed[39], joints[39]);
+            assert!(roughness(&smoothed[2..38]) < 0.25 * roughness(&joints));
+            // A straight ramp stays where it is
+            assert!((smoothed[20][1] - joints[20][1]).abs() < 1e-9);
+        }
+
+        // The mean of three moves J1 by 0.04 / 3, so within 0.005 keeps 3/8 of the smoothing
+        let smoothed = Smoothing::MovingAverage.apply(&joints, 3);
+        let constrained = constrain(&joints, &smoothed, |a, b| (a[0] - b[0]).abs() <= 0.005);
+        let (point, share) = constrained[10];
+        assert!((point[0] - joints[10][0]).abs() <= 0.005);
+        assert!((share - 0.375).abs() < 1e-3);
+        assert!(check_window(4).is_err() && check_window(1).is_err());
+    }
+}
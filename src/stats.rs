@@ -0,0 +1,108 @@
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+
+/// Opt-in call counters and solver timing, updated atomically so a shared Robot can record
+/// from any thread.
+#[derive(Default)]
+pub struct Stats {
+    enabled: AtomicBool,
+    forward_calls: AtomicU64,
+    forward_nanos: AtomicU64,
+    inverse_calls: AtomicU64,
+    inverse_nanos: AtomicU64,
+    inverse_failures: AtomicU64,
+    solutions: AtomicU64,
+}
+
+fn elapsed_nanos(start: Instant) -> u64 {
+    u64::try_from(start.elapsed().as_nanos()).unwrap_or(u64::MAX)
+}
+
+impl Stats {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Starts timing a call, returns None when stats are disabled
+    pub fn start(&self) -> Option<Instant> {
+        self.enabled.load(Ordering::Relaxed).then(Instant::now)
+    }
+
+    pub fn record_forward(&self, start: Option<Instant>) {
+        if let Some(start) = start {
+            self.forward_nanos
+                .fetch_add(elapsed_nanos(start), Ordering::Relaxed);
+            self.forward_calls.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_inverse(&self, start: Option<Instant>, solutions: usize) {
+        if let Some(start) = start {
+            self.inverse_nanos
+                .fetch_add(elapsed_nanos(start), Ordering::Relaxed);
+            self.inverse_calls.fetch_add(1, Ordering::Relaxed);
+            self.solutions
+                .fetch_add(solutions as u64, Ordering::Relaxed);
+            if solutions == 0 {
+                self.inverse_failures.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    pub fn reset(&self) {
+        for counter in [
+            &self.forward_calls,
+            &self.forward_nanos,
+            &self.inverse_calls,
+            &self.inverse_nanos,
+            &self.inverse_failures,
+            &self.solutions,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let seconds = |nanos: &AtomicU64| nanos.load(Ordering::Relaxed) as f64 * 1e-9;
+        let dict = PyDict::new(py);
+        dict.set_item("enabled", self.enabled.load(Ordering::Relaxed))?;
+        dict.set_item("forward_calls", self.forward_calls.load(Ordering::Relaxed))?;
+        dict.set_item("forward_time", seconds(&self.forward_nanos))?;
+        dict.set_item("inverse_calls", self.inverse_calls.load(Ordering::Relaxed))?;
+        dict.set_item("inverse_time", seconds(&self.inverse_nanos))?;
+        dict.set_item(
+            "inverse_failures",
+            self.inverse_failures.load(Ordering::Relaxed),
+        )?;
+        dict.set_item("solutions", self.solutions.load(Ordering::Relaxed))?;
+        Ok(dict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_stats_record_nothing() {
+        let stats = Stats::default();
+        stats.record_inverse(stats.start(), 0);
+        assert_eq!(stats.inverse_calls.load(Ordering::Relaxed), 0);
+
+        stats.set_enabled(true);
+        stats.record_inverse(stats.start(), 0);
+        stats.record_inverse(stats.start(), 8);
+        assert_eq!(stats.inverse_calls.load(Ordering::Relaxed), 2);
+        assert_eq!(stats.inverse_failures.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.solutions.load(Ordering::Relaxed), 8);
+
+        stats.reset();
+        assert_eq!(stats.solutions.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,324 @@
+use pyo3::exceptions::{PyOSError, PyRuntimeError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::ordering::InverseOptions;
+use crate::pose::PyPose;
+use crate::server::POLL_INTERVAL;
+use crate::validation;
+use crate::Robot;
+
+/// Size of a binary target: x, y, z, qw, qx, qy, qz as little-endian f64
+const BINARY_TARGET: usize = 7 * 8;
+
+/// Encoding of a datagram, answers use the encoding of the target they answer
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Encoding {
+    /// Space separated numbers
+    Text,
+    /// Little-endian f64 values
+    Binary,
+}
+
+/// The seven numbers of a text target, None unless `datagram` is one
+fn parse_text(datagram: &[u8]) -> Option<[f64; 7]> {
+    std::str::from_utf8(datagram)
+        .ok()?
+        .split_whitespace()
+        .map(str::parse)
+        .collect::<Result<Vec<f64>, _>>()
+        .ok()?
+        .try_into()
+        .ok()
+}
+
+/// Reads a target as text, or as binary when it is not seven numbers in text and has the
+/// size of a binary target, so a text target of 56 bytes stays text
+fn parse_target(datagram: &[u8]) -> Option<(PyPose, Encoding)> {
+    let (values, encoding) = match parse_text(datagram) {
+        Some(values) => (values, Encoding::Text),
+        None if datagram.len() == BINARY_TARGET => {
+            let mut values = [0.0; 7];
+            for (value, bytes) in values.iter_mut().zip(datagram.chunks_exact(8)) {
+                *value = f64::from_le_bytes(bytes.try_into().expect("chunks of 8 bytes"));
+            }
+            (values, Encoding::Binary)
+        }
+        None => return None,
+    };
+    let [x, y, z, qw, qx, qy, qz] = values;
+    let pose = ([x, y, z], [qw, qx, qy, qz]);
+    validation::is_finite_pose(&pose).then_some((pose, encoding))
+}
+
+fn encode_joints(joints: &[f64; 6], encoding: Encoding) -> Vec<u8> {
+    match encoding {
+        Encoding::Binary => joints
+            .iter()
+            .flat_map(|joint| joint.to_le_bytes())
+            .collect(),
+        Encoding::Text => joints
+            .iter()
+            .map(|joint| joint.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+            .into_bytes(),
+    }
+}
+
+/// Joint state of the stream and its counters
+#[derive(Default)]
+struct BridgeState {
+    joints: Option<[f64; 6]>,
+    received: u64,
+    sent: u64,
+    unreachable: u64,
+    invalid: u64,
+    latency_total: Duration,
+    latency_max: Duration,
+    latency_last: Duration,
+}
+
+impl BridgeState {
+    /// Solves one target datagram near the previous joints, returning the joint target to
+    /// send. Unreachable and malformed targets get no answer, the previous joints stay.
+    fn step(&mut self, robot: &Robot, datagram: &[u8]) -> Option<Vec<u8>> {
+        self.received += 1;
+        let Some((pose, encoding)) = parse_target(datagram) else {
+            self.invalid += 1;
+            return None;
+        };
+        let solutions = robot.solve(pose, self.joints, &InverseOptions::default());
+        let Some(joints) = solutions.first() else {
+            self.unreachable += 1;
+            return None;
+        };
+        self.joints = Some(*joints);
+        Some(encode_joints(joints, encoding))
+    }
+
+    fn record_latency(&mut self, latency: Duration) {
+        self.sent += 1;
+        self.latency_total += latency;
+        self.latency_max = self.latency_max.max(latency);
+        self.latency_last = latency;
+    }
+}
+
+/// Streams Cartesian targets to joint targets over UDP, e.g. as the guidance component
+/// between a vision system and a controller running ABB EGM or another external guidance
+/// interface. Every target datagram, x y z qw qx qy qz as text or else as seven
+/// little-endian f64, is solved with the previous joint target as seed so the stream follows one
+/// continuous branch, and answered with j1..j6 in the same encoding and the units of the
+/// robot, to the sender or to `reply_to`. Unreachable and malformed targets are counted
+/// and not answered.
+#[pyclass(frozen)]
+pub struct StreamingBridge {
+    address: SocketAddr,
+    stop: Arc<AtomicBool>,
+    state: Arc<Mutex<BridgeState>>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl StreamingBridge {
+    fn lock(&self) -> MutexGuard<'_, BridgeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[pymethods]
+impl StreamingBridge {
+    /// Listens for targets on `host`:`port` (0 picks a free port), starting from
+    /// `current_joints`
+    #[new]
+    #[pyo3(signature = (robot, host="127.0.0.1", port=0, reply_to=None, current_joints=None))]
+    fn new(
+        robot: Py<Robot>,
+        host: &str,
+        port: u16,
+        reply_to: Option<(String, u16)>,
+        current_joints: Option<[f64; 6]>,
+    ) -> PyResult<Self> {
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        let socket = UdpSocket::bind((host, port)).map_err(|e| {
+            PyOSError::new_err(format!("Cannot listen on {}:{}: {}", host, port, e))
+        })?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+        let address = socket.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let state = Arc::new(Mutex::new(BridgeState {
+            joints: current_joints,
+            ..BridgeState::default()
+        }));
+        let (thread_stop, thread_state) = (stop.clone(), state.clone());
+        let thread = std::thread::spawn(move || {
+            let mut buffer = [0u8; 1024];
+            while !thread_stop.load(Ordering::Relaxed) {
+                let (size, sender) = match socket.recv_from(&mut buffer) {
+                    Ok(received) => received,
+                    Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                        continue
+                    }
+                    Err(e) => {
+                        // Such as a reset reported for an earlier answer, wait before
+                        // receiving again rather than spinning on a persistent error
+                        log::warn!("Receiving a target failed: {}", e);
+                        std::thread::sleep(POLL_INTERVAL);
+                        continue;
+                    }
+                };
+                let received = Instant::now();
+                let mut state = thread_state.lock().unwrap_or_else(|e| e.into_inner());
+                let Some(answer) = state.step(robot.get(), &buffer[..size]) else {
+                    continue;
+                };
+                let sent = match &reply_to {
+                    Some((host, port)) => socket.send_to(&answer, (host.as_str(), *port)),
+                    None => socket.send_to(&answer, sender),
+                };
+                match sent {
+                    Ok(_) => state.record_latency(received.elapsed()),
+                    Err(e) => log::warn!("Sending a joint target failed: {}", e),
+                }
+            }
+        });
+        log::info!("Streaming targets on {}", address);
+        Ok(StreamingBridge {
+            address,
+            stop,
+            state,
+            thread: Mutex::new(Some(thread)),
+        })
+    }
+
+    /// (host, port) the bridge listens on
+    #[getter]
+    fn address(&self) -> (String, u16) {
+        (self.address.ip().to_string(), self.address.port())
+    }
+
+    #[getter]
+    fn running(&self) -> bool {
+        !self.stop.load(Ordering::Relaxed)
+    }
+
+    /// The last joint target sent, or the starting joints
+    #[getter]
+    fn joints(&self) -> Option<[f64; 6]> {
+        self.lock().joints
+    }
+
+    /// Restarts the stream from `current_joints`, keeping the counters
+    #[pyo3(signature = (current_joints=None))]
+    fn reset(&self, current_joints: Option<[f64; 6]>) -> PyResult<()> {
+        if let Some(joints) = &current_joints {
+            validation::check_joints("current_joints", joints)?;
+        }
+        self.lock().joints = current_joints;
+        Ok(())
+    }
+
+    /// Counts of received, sent, unreachable and invalid targets, and the mean, maximum and
+    /// last latency from receiving a target to sending its joints, in seconds
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let state = self.lock();
+        let dict = PyDict::new(py);
+        dict.set_item("received", state.received)?;
+        dict.set_item("sent", state.sent)?;
+        dict.set_item("unreachable", state.unreachable)?;
+        dict.set_item("invalid", state.invalid)?;
+        let mean = if state.sent > 0 {
+            state.latency_total.as_secs_f64() / state.sent as f64
+        } else {
+            0.0
+        };
+        dict.set_item("latency_mean", mean)?;
+        dict.set_item("latency_max", state.latency_max.as_secs_f64())?;
+        dict.set_item("latency_last", state.latency_last.as_secs_f64())?;
+        Ok(dict)
+    }
+
+    /// Stops listening for targets
+    fn stop(&self, py: Python<'_>) -> PyResult<()> {
+        self.stop.store(true, Ordering::Relaxed);
+        let handle = self.thread.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(handle) = handle {
+            py.detach(|| handle.join())
+                .map_err(|_| PyRuntimeError::new_err("The streaming thread panicked"))?;
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python<'_>,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        self.stop(py)?;
+        Ok(false)
+    }
+}
+
+impl Drop for StreamingBridge {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::abb_1660;
+
+    #[test]
+    fn test_stream_follows_previous_joints() {
+        let robot = abb_1660();
+        let start = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let mut state = BridgeState {
+            joints: Some(start),
+            ..BridgeState::default()
+        };
+        let (translation, rotation) = robot.forward(start, None).unwrap();
+        let binary: Vec<u8> = translation
+            .iter()
+            .chain(rotation.iter())
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let answer = state.step(&robot, &binary).unwrap();
+        assert_eq!(answer.len(), 48);
+        let joints = state.joints.unwrap();
+        assert!(joints.iter().zip(start).all(|(a, b)| (a - b).abs() < 1e-6));
+
+        let text = state.step(&robot, b"0.8 0.1 0.9 0 0 1 0").unwrap();
+        assert_eq!(std::str::from_utf8(&text).unwrap().split(' ').count(), 6);
+        assert!(state.step(&robot, b"9 9 9 1 0 0 0").is_none());
+        assert!(state.step(&robot, b"1 2 3").is_none());
+        assert_eq!(
+            (state.received, state.unreachable, state.invalid),
+            (4, 1, 1)
+        );
+
+        // A text target the size of a binary one is read as text
+        let padded = b"0.812345 0.100000 0.900000 0.000000 0.000 1.0000 0.00000";
+        assert_eq!(padded.len(), BINARY_TARGET);
+        let (pose, encoding) = parse_target(padded).unwrap();
+        assert_eq!(encoding, Encoding::Text);
+        assert_eq!(pose, ([0.812345, 0.1, 0.9], [0.0, 0.0, 1.0, 0.0]));
+        let text = state.step(&robot, padded).unwrap();
+        assert_eq!(std::str::from_utf8(&text).unwrap().split(' ').count(), 6);
+    }
+}
@@ -0,0 +1,175 @@
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use rs_opw_kinematics::kinematic_traits::Joints;
+use std::time::Instant;
+
+/// What a batch summary records of one row
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Row {
+    /// NaN or infinite input
+    Invalid,
+    /// No solution within the joint limits; `outside_limits` if the pose has solutions
+    /// beyond them
+    Unreachable { outside_limits: bool },
+    /// Solved to `joints` (radians)
+    Solved {
+        joints: Joints,
+        singular: bool,
+        within_limits: bool,
+    },
+}
+
+/// Aggregate statistics of a batch, collected row by row in the order of the result so
+/// callers need not scan large result tables again. Every batch method returns it as the
+/// same dict:
+///
+/// * rows: rows of the batch, including dropped invalid rows
+/// * solved: rows with a solution
+/// * unreachable: poses without a solution within the joint limits, never counted by
+///   forward kinematics
+/// * limit_violating: poses reachable only beyond the joint limits, or joints beyond them
+/// * invalid: rows with NaN or infinite values
+/// * singular: solved rows near a wrist singularity
+/// * max_joint_jump: largest change of any joint between consecutive solved rows in the
+///   joint units, and max_joint_jump_row, the row it ends at (both None with fewer than
+///   two solved rows)
+/// * runtime: seconds since the batch started
+pub struct Summary {
+    start: Instant,
+    /// Invalid rows dropped from the result
+    skipped: usize,
+    rows: usize,
+    solved: usize,
+    unreachable: usize,
+    limit_violating: usize,
+    invalid: usize,
+    singular: usize,
+    previous: Option<Joints>,
+    /// Largest change of any joint between consecutive solved rows (radians) and the row
+    /// it happens at
+    max_joint_jump: Option<(f64, usize)>,
+}
+
+impl Summary {
+    /// Summary of a batch started at `start`, of which `skipped` invalid rows were dropped
+    /// from the result
+    pub fn new(start: Instant, skipped: usize) -> Self {
+        Summary {
+            start,
+            skipped,
+            rows: 0,
+            solved: 0,
+            unreachable: 0,
+            limit_violating: 0,
+            invalid: 0,
+            singular: 0,
+            previous: None,
+            max_joint_jump: None,
+        }
+    }
+
+    /// Records the next row of the result
+    pub fn record(&mut self, row: Row) {
+        let index = self.rows;
+        self.rows += 1;
+        match row {
+            Row::Invalid => self.invalid += 1,
+            Row::Unreachable { outside_limits } => {
+                self.unreachable += 1;
+                self.limit_violating += outside_limits as usize;
+            }
+            Row::Solved {
+                joints,
+                singular,
+                within_limits,
+            } => {
+                self.solved += 1;
+                self.singular += singular as usize;
+                self.limit_violating += !within_limits as usize;
+                if let Some(previous) = self.previous {
+                    let jump = joints
+                        .iter()
+                        .zip(previous)
+                        .map(|(joint, previous)| (joint - previous).abs())
+                        .fold(0.0, f64::max);
+                    if self.max_joint_jump.is_none_or(|(max, _)| jump > max) {
+                        self.max_joint_jump = Some((jump, index));
+                    }
+                }
+                self.previous = Some(joints);
+            }
+        }
+    }
+
+    /// The summary as the dict described on `Summary`, with `joint_out` applied to
+    /// max_joint_jump
+    pub fn to_dict<'py>(
+        &self,
+        py: Python<'py>,
+        joint_out: impl Fn(f64) -> f64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("rows", self.rows + self.skipped)?;
+        dict.set_item("solved", self.solved)?;
+        dict.set_item("unreachable", self.unreachable)?;
+        dict.set_item("limit_violating", self.limit_violating)?;
+        dict.set_item("invalid", self.invalid + self.skipped)?;
+        dict.set_item("singular", self.singular)?;
+        dict.set_item(
+            "max_joint_jump",
+            self.max_joint_jump.map(|(jump, _)| joint_out(jump)),
+        )?;
+        dict.set_item(
+            "max_joint_jump_row",
+            self.max_joint_jump.map(|(_, row)| row),
+        )?;
+        dict.set_item("runtime", self.start.elapsed().as_secs_f64())?;
+        Ok(dict)
+    }
+}
+
+/// The result of a batch method, as (rows, summary) if a summary was collected
+pub fn with_summary<'py>(
+    py: Python<'py>,
+    rows: Vec<Bound<'py, PyAny>>,
+    summary: Option<Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let rows = rows.into_pyobject(py)?.into_any();
+    match summary {
+        Some(summary) => Ok(PyTuple::new(py, [rows, summary.into_any()])?.into_any()),
+        None => Ok(rows),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_counts_rows_and_jumps() {
+        let mut summary = Summary::new(Instant::now(), 2);
+        let solved = |j1: f64, singular, within_limits| Row::Solved {
+            joints: [j1, 0.0, 0.0, 0.0, 0.5, 0.0],
+            singular,
+            within_limits,
+        };
+        summary.record(solved(0.0, false, true));
+        summary.record(Row::Unreachable {
+            outside_limits: true,
+        });
+        summary.record(Row::Invalid);
+        // The jump is taken over the rows in between
+        summary.record(solved(0.3, true, false));
+        summary.record(solved(0.2, false, true));
+        assert_eq!(summary.rows + summary.skipped, 7);
+        assert_eq!((summary.solved, summary.unreachable), (3, 1));
+        assert_eq!(
+            (summary.invalid + summary.skipped, summary.singular),
+            (3, 1)
+        );
+        assert_eq!(summary.limit_violating, 2);
+        let (jump, row) = summary.max_joint_jump.unwrap();
+        assert!((jump - 0.3).abs() < 1e-12);
+        assert_eq!(row, 3);
+    }
+}
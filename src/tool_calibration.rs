@@ -0,0 +1,66 @@
+use nalgebra::{Isometry3, Point3, Vector3};
+
+/// Direction in the flange frame from the TCP `tcp` (flange frame) to a fixed world `point`,
+/// averaged over flange poses where the point lies on the same tool axis. Returns the unit
+/// direction and the largest angle (rad) of a single pose from it, or None if the point
+/// coincides with the TCP in a pose or the directions cancel out.
+pub fn axis_direction(
+    flanges: &[Isometry3<f64>],
+    point: &Point3<f64>,
+    tcp: &Vector3<f64>,
+) -> Option<(Vector3<f64>, f64)> {
+    let directions = flanges
+        .iter()
+        .map(|flange| (flange.inverse() * point - Point3::from(*tcp)).try_normalize(1e-9))
+        .collect::<Option<Vec<Vector3<f64>>>>()?;
+    let mean = directions
+        .iter()
+        .sum::<Vector3<f64>>()
+        .try_normalize(1e-9)?;
+    let spread = directions
+        .iter()
+        .map(|direction| direction.angle(&mean))
+        .fold(0.0, f64::max);
+    Some((mean, spread))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Translation3, UnitQuaternion};
+
+    #[test]
+    fn test_axis_direction_finds_the_tool_axes() {
+        let tool = Isometry3::from_parts(
+            Translation3::new(0.02, -0.01, 0.15),
+            UnitQuaternion::from_euler_angles(0.4, -0.3, 1.1),
+        );
+        let point = Point3::new(1.0, 0.5, 0.3);
+        // Flanges that put the point 0.1 m along the tool axis, with turns about it
+        let flanges = |axis: Vector3<f64>| -> Vec<Isometry3<f64>> {
+            (0..3)
+                .map(|i| {
+                    let turn = UnitQuaternion::from_axis_angle(
+                        &nalgebra::Unit::new_normalize(axis),
+                        0.7 * i as f64,
+                    );
+                    let tcp = Isometry3::from_parts(
+                        Translation3::from(point.coords - 0.1 * (turn * axis)),
+                        turn,
+                    );
+                    tcp * tool.inverse()
+                })
+                .collect()
+        };
+        let tcp = tool.translation.vector;
+        for axis in [Vector3::z(), Vector3::x()] {
+            let (direction, spread) = axis_direction(&flanges(axis), &point, &tcp).unwrap();
+            assert!((direction - tool.rotation * axis).norm() < 1e-12);
+            assert!(spread < 1e-9);
+        }
+        let at_tcp = [
+            Isometry3::from_parts(Translation3::from(point.coords), tool.rotation) * tool.inverse(),
+        ];
+        assert!(axis_direction(&at_tcp, &point, &tcp).is_none());
+    }
+}
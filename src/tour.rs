@@ -0,0 +1,122 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Improvement below which a 2-opt move is not taken, so rounding cannot make it cycle
+const MIN_GAIN: f64 = 1e-12;
+
+/// How the visiting order of independent targets is found
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TourMethod {
+    /// Always the nearest target not yet visited
+    Greedy,
+    /// The greedy order improved by reversing stretches of it while that shortens it
+    TwoOpt,
+}
+
+impl TourMethod {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "greedy" => Ok(TourMethod::Greedy),
+            "two_opt" => Ok(TourMethod::TwoOpt),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown ordering method '{}', expected 'greedy' or 'two_opt'",
+                name
+            ))),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for TourMethod {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        TourMethod::parse(&obj.extract::<String>()?)
+    }
+}
+
+/// Order of visiting nodes 1..n of the symmetric `distances`, starting from node 0 and
+/// returning to it when `closed`. The order lists the nodes as 0..n-1, node 1 being 0.
+pub fn order(distances: &[Vec<f64>], method: TourMethod, closed: bool) -> Vec<usize> {
+    let n = distances.len().saturating_sub(1);
+    // Greedy nearest neighbour from the start
+    let mut tour = vec![0];
+    let mut visited = vec![false; n + 1];
+    visited[0] = true;
+    for _ in 0..n {
+        let last = tour[tour.len() - 1];
+        let next = (1..=n)
+            .filter(|&node| !visited[node])
+            .min_by(|&a, &b| distances[last][a].total_cmp(&distances[last][b]))
+            .expect("an unvisited node");
+        visited[next] = true;
+        tour.push(next);
+    }
+    if method == TourMethod::TwoOpt {
+        two_opt(distances, &mut tour, closed);
+    }
+    tour[1..].iter().map(|node| node - 1).collect()
+}
+
+/// Reverses stretches tour[i..=j] (never the start) while that shortens the tour
+fn two_opt(distances: &[Vec<f64>], tour: &mut [usize], closed: bool) {
+    let n = tour.len();
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 1..n {
+            for j in i + 1..n {
+                let (a, b, c) = (tour[i - 1], tour[i], tour[j]);
+                // The node after the stretch, the start again on a closed tour
+                let after = match (j + 1 < n, closed) {
+                    (true, _) => Some(tour[j + 1]),
+                    (false, true) => Some(tour[0]),
+                    (false, false) => None,
+                };
+                let before = distances[a][b] + after.map_or(0.0, |d| distances[c][d]);
+                let reversed = distances[a][c] + after.map_or(0.0, |d| distances[b][d]);
+                if reversed < before - MIN_GAIN {
+                    tour[i..=j].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_opt_untangles_the_greedy_order() {
+        // Start at the origin, the greedy order heads up to (0, 2) first and leaves the far
+        // target at (3, -2) for last
+        let points: [(f64, f64); 5] = [
+            (0.0, 0.0),
+            (0.0, 2.0),
+            (3.0, -2.0),
+            (-2.0, 2.0),
+            (-2.0, 3.0),
+        ];
+        let distances: Vec<Vec<f64>> = points
+            .iter()
+            .map(|a| {
+                points
+                    .iter()
+                    .map(|b| (a.0 - b.0).hypot(a.1 - b.1))
+                    .collect()
+            })
+            .collect();
+        let length = |order: &[usize]| {
+            let mut nodes = vec![0];
+            nodes.extend(order.iter().map(|i| i + 1));
+            nodes.windows(2).map(|w| distances[w[0]][w[1]]).sum::<f64>()
+        };
+        let greedy = order(&distances, TourMethod::Greedy, false);
+        assert_eq!(greedy, vec![0, 2, 3, 1]);
+        let improved = order(&distances, TourMethod::TwoOpt, false);
+        assert_eq!(improved, vec![2, 3, 0, 1]);
+        assert!(length(&improved) < length(&greedy) - 1.0);
+        assert!(order(&distances[..1], TourMethod::TwoOpt, true).is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+use nalgebra::{Isometry3, Matrix3, Translation3, UnitQuaternion, Vector3};
+use pyo3::prelude::*;
+
+use crate::pose::{from_isometry, to_isometry, PyPose};
+
+/// Exponential coordinates of a pose as [vx, vy, vz, wx, wy, wz].
+pub type Twist = [f64; 6];
+
+/// Below this rotation angle the series expansions are used, the closed forms lose all
+/// precision to cancellation in 1 - cos
+const SMALL_ANGLE: f64 = 1e-4;
+
+fn skew(w: &Vector3<f64>) -> Matrix3<f64> {
+    Matrix3::new(0.0, -w.z, w.y, w.z, 0.0, -w.x, -w.y, w.x, 0.0)
+}
+
+/// The left Jacobian V of SO(3), mapping v to the translation of exp([v, w]).
+fn left_jacobian(w: &Vector3<f64>) -> Matrix3<f64> {
+    let theta = w.norm();
+    let w_hat = skew(w);
+    let (a, b) = if theta < SMALL_ANGLE {
+        (
+            0.5 - theta * theta / 24.0,
+            1.0 / 6.0 - theta * theta / 120.0,
+        )
+    } else {
+        (
+            (1.0 - theta.cos()) / (theta * theta),
+            (theta - theta.sin()) / (theta * theta * theta),
+        )
+    };
+    Matrix3::identity() + w_hat * a + w_hat * w_hat * b
+}
+
+fn left_jacobian_inverse(w: &Vector3<f64>) -> Matrix3<f64> {
+    let theta = w.norm();
+    let w_hat = skew(w);
+    let c = if theta < SMALL_ANGLE {
+        1.0 / 12.0 + theta * theta / 720.0
+    } else {
+        (1.0 - theta * theta.sin() / (2.0 * (1.0 - theta.cos()))) / (theta * theta)
+    };
+    Matrix3::identity() - w_hat * 0.5 + w_hat * w_hat * c
+}
+
+/// se(3) logarithm of a pose.
+pub fn log(pose: &Isometry3<f64>) -> Twist {
+    let w = pose.rotation.scaled_axis();
+    let v = left_jacobian_inverse(&w) * pose.translation.vector;
+    [v.x, v.y, v.z, w.x, w.y, w.z]
+}
+
+/// se(3) exponential of a twist.
+pub fn exp(twist: &Twist) -> Isometry3<f64> {
+    let v = Vector3::new(twist[0], twist[1], twist[2]);
+    let w = Vector3::new(twist[3], twist[4], twist[5]);
+    Isometry3::from_parts(
+        Translation3::from(left_jacobian(&w) * v),
+        UnitQuaternion::from_scaled_axis(w),
+    )
+}
+
+/// Screw-motion interpolation between two poses, `t` = 0 gives `start` and 1 gives `end`.
+pub fn interpolate(start: &Isometry3<f64>, end: &Isometry3<f64>, t: f64) -> Isometry3<f64> {
+    let delta = log(&(start.inverse() * end)).map(|x| x * t);
+    start * exp(&delta)
+}
+
+/// Converts a pose ([x, y, z], [w, x, y, z]) to its twist [vx, vy, vz, wx, wy, wz]
+#[pyfunction]
+pub fn pose_to_twist(pose: PyPose) -> Twist {
+    log(&to_isometry(pose.0, pose.1))
+}
+
+/// Converts a twist [vx, vy, vz, wx, wy, wz] to a pose ([x, y, z], [w, x, y, z])
+#[pyfunction]
+pub fn twist_to_pose(twist: Twist) -> PyPose {
+    from_isometry(&exp(&twist))
+}
+
+/// Interpolates along the screw motion from `start` to `end` at fraction `t`
+#[pyfunction]
+pub fn interpolate_twist(start: PyPose, end: PyPose, t: f64) -> PyPose {
+    let start = to_isometry(start.0, start.1);
+    let end = to_isometry(end.0, end.1);
+    from_isometry(&interpolate(&start, &end, t))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_pose_close(a: &Isometry3<f64>, b: &Isometry3<f64>) {
+        assert!((a.translation.vector - b.translation.vector).norm() < 1e-12);
+        assert!(a.rotation.angle_to(&b.rotation) < 1e-12);
+    }
+
+    #[test]
+    fn test_log_exp_round_trip() {
+        let pose = Isometry3::from_parts(
+            Translation3::new(0.4, -0.2, 1.3),
+            UnitQuaternion::from_euler_angles(0.1, 2.0, -0.5),
+        );
+        assert_pose_close(&exp(&log(&pose)), &pose);
+    }
+
+    #[test]
+    fn test_tiny_rotation_is_finite() {
+        let pose = Isometry3::from_parts(
+            Translation3::new(0.4, -0.2, 1.3),
+            UnitQuaternion::from_euler_angles(1e-8, 0.0, 0.0),
+        );
+        let twist = log(&pose);
+        assert!(twist.iter().all(|x| x.is_finite()));
+        assert_pose_close(&exp(&twist), &pose);
+    }
+
+    #[test]
+    fn test_pure_translation() {
+        let pose = Isometry3::translation(1.0, 2.0, 3.0);
+        assert_eq!(log(&pose), [1.0, 2.0, 3.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_interpolate_end_points() {
+        let start =
+            Isometry3::from_parts(Translation3::new(0.5, 0.0, 0.3), UnitQuaternion::identity());
+        let end = Isometry3::from_parts(
+            Translation3::new(0.2, 0.4, 0.9),
+            UnitQuaternion::from_euler_angles(0.0, 1.0, 0.5),
+        );
+        assert_pose_close(&interpolate(&start, &end, 0.0), &start);
+        assert_pose_close(&interpolate(&start, &end, 1.0), &end);
+    }
+}
@@ -0,0 +1,118 @@
+/// Travel and duty of one joint over a trace, in the units of its values
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AxisUsage {
+    /// Summed absolute motion
+    pub travel: f64,
+    /// Changes of direction after moving at least the deadband
+    pub reversals: usize,
+    /// Largest change between consecutive samples
+    pub max_step: f64,
+    /// Largest change per second, with times
+    pub max_speed: Option<f64>,
+    pub min: f64,
+    pub max: f64,
+    /// Seconds within the margin of a limit with times, else the number of samples
+    pub near_limit: Option<f64>,
+    /// Share of the trace within the margin of a limit
+    pub near_limit_fraction: Option<f64>,
+}
+
+/// Usage of one joint from its `values`, sampled at `times` (s) if given. Near a limit is
+/// within `margin` of either end of `limits`; each sample counts for half the intervals
+/// to its neighbours.
+pub fn axis_usage(
+    values: &[f64],
+    times: Option<&[f64]>,
+    limits: Option<(f64, f64)>,
+    margin: f64,
+    deadband: f64,
+) -> AxisUsage {
+    let mut travel = 0.0;
+    let mut max_step: f64 = 0.0;
+    let mut max_speed: Option<f64> = times.map(|_| 0.0);
+    for i in 1..values.len() {
+        let step = (values[i] - values[i - 1]).abs();
+        travel += step;
+        max_step = max_step.max(step);
+        if let (Some(times), Some(speed)) = (times, max_speed.as_mut()) {
+            *speed = speed.max(step / (times[i] - times[i - 1]));
+        }
+    }
+    let last = values.len().saturating_sub(1);
+    let weight = |i: usize| match times {
+        Some(times) => (times[(i + 1).min(last)] - times[i.saturating_sub(1)]) / 2.0,
+        None => 1.0,
+    };
+    let near_limit = limits.map(|(min, max)| {
+        (0..values.len())
+            .filter(|&i| values[i] - min <= margin || max - values[i] <= margin)
+            .map(weight)
+            .sum::<f64>()
+    });
+    let total = match times {
+        Some(times) => times[last] - times[0],
+        None => values.len() as f64,
+    };
+    AxisUsage {
+        travel,
+        reversals: reversals(values, deadband),
+        max_step,
+        max_speed,
+        min: values.iter().copied().fold(f64::INFINITY, f64::min),
+        max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        near_limit,
+        near_limit_fraction: near_limit.map(|near| match total > 0.0 {
+            true => near / total,
+            false => 0.0,
+        }),
+    }
+}
+
+/// Changes of direction, each counted once the joint has moved back `deadband` from the
+/// furthest point in the previous direction, so jitter below it does not count
+fn reversals(values: &[f64], deadband: f64) -> usize {
+    let Some(&first) = values.first() else {
+        return 0;
+    };
+    let (mut direction, mut extreme, mut count) = (0.0, first, 0);
+    for &value in &values[1..] {
+        let moved = value - extreme;
+        if direction == 0.0 {
+            if moved.abs() >= deadband && moved != 0.0 {
+                direction = moved.signum();
+                extreme = value;
+            }
+        } else if moved * direction > 0.0 {
+            extreme = value;
+        } else if -moved * direction >= deadband && moved != 0.0 {
+            count += 1;
+            direction = -direction;
+            extreme = value;
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_axis_usage() {
+        // Up to 10, down to -10 with jitter below the deadband, back to 0
+        let values = [0.0, 5.0, 10.0, 9.95, 10.0, 0.0, -10.0, -5.0, 0.0];
+        let times = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let usage = axis_usage(&values, Some(&times), Some((-10.0, 10.0)), 1.0, 0.1);
+        assert_eq!(usage.reversals, 2);
+        assert!((usage.travel - 40.1).abs() < 1e-12);
+        assert_eq!((usage.max_step, usage.max_speed), (10.0, Some(10.0)));
+        assert_eq!((usage.min, usage.max), (-10.0, 10.0));
+        // Samples 2 to 4 at the upper limit and 6 at the lower one, a second each
+        assert_eq!(usage.near_limit, Some(4.0));
+        assert_eq!(usage.near_limit_fraction, Some(0.5));
+
+        let counted = axis_usage(&values, None, None, 1.0, 0.01);
+        assert_eq!(counted.reversals, 4);
+        assert_eq!((counted.max_speed, counted.near_limit), (None, None));
+    }
+}
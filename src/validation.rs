@@ -0,0 +1,121 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::pose::PyPose;
+
+/// What batch methods do with input rows that contain NaN or infinite values
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InvalidPolicy {
+    /// Keep the row, with None as its result
+    Null,
+    /// Raise a ValueError naming the first invalid row
+    Raise,
+    /// Drop the row from the result
+    Skip,
+}
+
+impl InvalidPolicy {
+    pub fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "null" => Ok(InvalidPolicy::Null),
+            "raise" => Ok(InvalidPolicy::Raise),
+            "skip" => Ok(InvalidPolicy::Skip),
+            _ => Err(PyValueError::new_err(format!(
+                "Unknown invalid input policy '{}', expected 'null', 'raise' or 'skip'",
+                name
+            ))),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for InvalidPolicy {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        InvalidPolicy::parse(&obj.extract::<String>()?)
+    }
+}
+
+pub fn is_finite_pose(pose: &PyPose) -> bool {
+    pose.0.iter().chain(&pose.1).all(|x| x.is_finite())
+}
+
+pub fn is_finite_joints(joints: &[f64; 6]) -> bool {
+    joints.iter().all(|x| x.is_finite())
+}
+
+/// Raises for values with NaN or infinite entries, `name` is the argument in the message
+pub fn check_finite<T: std::fmt::Debug>(name: &str, value: &T, finite: bool) -> PyResult<()> {
+    if finite {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "NaN or infinite value in {}: {:?}",
+            name, value
+        )))
+    }
+}
+
+pub fn check_pose(pose: &PyPose) -> PyResult<()> {
+    check_finite("pose", pose, is_finite_pose(pose))
+}
+
+pub fn check_joints(name: &str, joints: &[f64; 6]) -> PyResult<()> {
+    check_finite(name, joints, is_finite_joints(joints))
+}
+
+/// Raises unless `value` is positive and finite, `name` is the argument in the message
+pub fn check_positive(name: &str, value: f64) -> PyResult<()> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        Err(PyValueError::new_err(format!(
+            "{} must be positive and finite, got {}",
+            name, value
+        )))
+    }
+}
+
+/// Applies `policy` to the rows of a batch: Raise fails at the first invalid row, Skip drops
+/// invalid rows and Null keeps them for the caller to answer with None
+pub fn screen<T: std::fmt::Debug>(
+    mut rows: Vec<T>,
+    is_valid: impl Fn(&T) -> bool,
+    policy: InvalidPolicy,
+) -> PyResult<Vec<T>> {
+    match policy {
+        InvalidPolicy::Null => {}
+        InvalidPolicy::Skip => rows.retain(is_valid),
+        InvalidPolicy::Raise => {
+            if let Some((i, row)) = rows.iter().enumerate().find(|(_, row)| !is_valid(row)) {
+                return Err(PyValueError::new_err(format!(
+                    "Row {} contains NaN or infinite values: {:?}",
+                    i, row
+                )));
+            }
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen() {
+        let nan = [f64::NAN; 6];
+        let rows = vec![
+            [0.0; 6],
+            nan,
+            [1.0; 6],
+            [0.0, 0.0, f64::INFINITY, 0.0, 0.0, 0.0],
+        ];
+        let kept = screen(rows.clone(), is_finite_joints, InvalidPolicy::Skip).unwrap();
+        assert_eq!(kept, vec![[0.0; 6], [1.0; 6]]);
+        let nulls = screen(rows.clone(), is_finite_joints, InvalidPolicy::Null).unwrap();
+        assert_eq!(nulls.len(), 4);
+        assert!(screen(rows, is_finite_joints, InvalidPolicy::Raise).is_err());
+        assert!(screen(vec![[0.0; 6]], is_finite_joints, InvalidPolicy::Raise).is_ok());
+    }
+}
@@ -0,0 +1,66 @@
+use nalgebra::{Isometry3, Vector3};
+
+/// A wrench as [fx, fy, fz, tx, ty, tz], forces in N and torques in Nm.
+pub type Wrench = [f64; 6];
+
+fn split(wrench: &Wrench) -> (Vector3<f64>, Vector3<f64>) {
+    (
+        Vector3::new(wrench[0], wrench[1], wrench[2]),
+        Vector3::new(wrench[3], wrench[4], wrench[5]),
+    )
+}
+
+fn join(force: Vector3<f64>, torque: Vector3<f64>) -> Wrench {
+    [force.x, force.y, force.z, torque.x, torque.y, torque.z]
+}
+
+/// Re-expresses a wrench measured in the flange frame in the TCP frame.
+///
+/// `tool` is the flange -> TCP transform. The torque is shifted to act about the TCP.
+pub fn flange_to_tcp(tool: &Isometry3<f64>, wrench: &Wrench) -> Wrench {
+    let (force, torque) = split(wrench);
+    let lever = tool.translation.vector;
+    let rotation_inv = tool.rotation.inverse();
+    join(
+        rotation_inv * force,
+        rotation_inv * (torque - lever.cross(&force)),
+    )
+}
+
+/// Re-expresses a wrench given in the TCP frame in the flange frame.
+///
+/// `tool` is the flange -> TCP transform. The torque is shifted to act about the flange.
+pub fn tcp_to_flange(tool: &Isometry3<f64>, wrench: &Wrench) -> Wrench {
+    let (force, torque) = split(wrench);
+    let lever = tool.translation.vector;
+    let force = tool.rotation * force;
+    join(force, tool.rotation * torque + lever.cross(&force))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra::{Translation3, UnitQuaternion};
+
+    #[test]
+    fn test_pure_offset_adds_lever_torque() {
+        let tool =
+            Isometry3::from_parts(Translation3::new(0.0, 0.0, 0.1), UnitQuaternion::identity());
+        // 10 N along x at the flange, the TCP sits 0.1 m further along z
+        let wrench = flange_to_tcp(&tool, &[10.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(wrench, [10.0, 0.0, 0.0, 0.0, -1.0, 0.0]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let tool = Isometry3::from_parts(
+            Translation3::new(0.02, -0.05, 0.2),
+            UnitQuaternion::from_euler_angles(0.3, -0.7, 1.1),
+        );
+        let wrench = [1.0, -2.0, 3.0, 0.4, 0.5, -0.6];
+        let back = tcp_to_flange(&tool, &flange_to_tcp(&tool, &wrench));
+        for (a, b) in wrench.iter().zip(back.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+}